@@ -0,0 +1,367 @@
+//!
+//! # Basic RBAC policy
+//!
+//! Maps roles in scopes of X509 identity to actions allowed on object types.
+//! Actions can be restricted to instances by name or name prefix.
+//! Shared by SC admin API and SPU data plane.
+//!
+
+use std::fs::read;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::convert::TryFrom;
+
+use tracing::debug;
+use serde::{Serialize, Deserialize};
+
+use fluvio_controlplane_metadata::extended::ObjectType;
+
+use crate::{AuthError, TypeAction, InstanceAction};
+use crate::x509::X509Identity;
+
+type Role = String;
+
+#[derive(Debug, Clone, PartialEq, Hash, Eq, Deserialize, Serialize)]
+pub enum Action {
+    Create,
+    Read,
+    Update,
+    Delete,
+    /// produce records to topic
+    Write,
+    All,
+}
+
+impl From<TypeAction> for Action {
+    fn from(action: TypeAction) -> Self {
+        match action {
+            TypeAction::Create => Action::Create,
+            TypeAction::Read => Action::Read,
+        }
+    }
+}
+
+impl From<InstanceAction> for Action {
+    fn from(action: InstanceAction) -> Self {
+        match action {
+            InstanceAction::Create => Action::Create,
+            InstanceAction::Delete => Action::Delete,
+            InstanceAction::Update => Action::Update,
+            InstanceAction::Read => Action::Read,
+            InstanceAction::Write => Action::Write,
+        }
+    }
+}
+
+impl Action {
+    fn grants(&self, action: &Action) -> bool {
+        self == action || self == &Action::All
+    }
+}
+
+/// Permission of role on object type
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Permission {
+    /// action allowed on all instances
+    Action(Action),
+    /// actions allowed on matching instances only
+    Instances(InstanceRule),
+}
+
+impl From<Action> for Permission {
+    fn from(action: Action) -> Self {
+        Self::Action(action)
+    }
+}
+
+impl From<InstanceRule> for Permission {
+    fn from(rule: InstanceRule) -> Self {
+        Self::Instances(rule)
+    }
+}
+
+impl Permission {
+    /// check if action is permitted.
+    /// type level check (without instance) is permitted if action is allowed on any instance
+    fn allows(&self, action: &Action, instance: Option<&str>) -> bool {
+        match self {
+            Self::Action(permitted) => permitted.grants(action),
+            Self::Instances(rule) => {
+                rule.actions
+                    .iter()
+                    .any(|permitted| permitted.grants(action))
+                    && instance.map(|name| rule.matches(name)).unwrap_or(true)
+            }
+        }
+    }
+}
+
+/// Actions on instances with matching names.
+/// Name ending with `*` matches all instances with that prefix, ex: `team-a.*`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct InstanceRule {
+    pub actions: Vec<Action>,
+    pub instances: Vec<String>,
+}
+
+impl InstanceRule {
+    pub fn new(actions: Vec<Action>, instances: Vec<String>) -> Self {
+        Self { actions, instances }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        self.instances
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => name.starts_with(prefix),
+                None => pattern == name,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct BasicRbacPolicy(pub HashMap<Role, HashMap<ObjectType, Vec<Permission>>>);
+
+impl From<HashMap<Role, HashMap<ObjectType, Vec<Permission>>>> for BasicRbacPolicy {
+    fn from(map: HashMap<Role, HashMap<ObjectType, Vec<Permission>>>) -> Self {
+        Self(map)
+    }
+}
+
+impl TryFrom<PathBuf> for BasicRbacPolicy {
+    type Error = std::io::Error;
+    fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
+        debug!("reading basic policy: {:#?}", path);
+        let file = read(path)?;
+        let policy: BasicRbacPolicy = serde_json::from_slice(&file)?;
+        Ok(policy)
+    }
+}
+
+impl BasicRbacPolicy {
+    pub async fn evaluate(
+        &self,
+        action: Action,
+        object_type: ObjectType,
+        instance: Option<&str>,
+        identity: &X509Identity,
+    ) -> Result<bool, AuthError> {
+        // For each scope provided in the identity,
+        // check if there is a match;
+        let is_allowed = identity.scopes().iter().any(|scope| {
+            self.0
+                .get(scope)
+                .map(|objects| {
+                    objects
+                        .get(&object_type)
+                        .map(|permissions| {
+                            permissions
+                                .iter()
+                                .any(|permission| permission.allows(&action, instance))
+                        })
+                        .unwrap_or(false)
+                })
+                .unwrap_or(false)
+        });
+
+        Ok(is_allowed)
+    }
+}
+
+impl Default for BasicRbacPolicy {
+    // default only allows the `Root` role to have full permissions;
+    fn default() -> Self {
+        let mut root_policy: HashMap<ObjectType, Vec<Permission>> = HashMap::new();
+
+        root_policy.insert(ObjectType::Spu, vec![Action::All.into()]);
+        root_policy.insert(ObjectType::CustomSpu, vec![Action::All.into()]);
+        root_policy.insert(ObjectType::SpuGroup, vec![Action::All.into()]);
+        root_policy.insert(ObjectType::Topic, vec![Action::All.into()]);
+        root_policy.insert(ObjectType::Partition, vec![Action::All.into()]);
+        root_policy.insert(ObjectType::TableFormat, vec![Action::All.into()]);
+        root_policy.insert(ObjectType::Quota, vec![Action::All.into()]);
+
+        let mut policy = HashMap::new();
+
+        policy.insert(String::from("Root"), root_policy);
+
+        Self(policy)
+    }
+}
+#[cfg(test)]
+mod test {
+
+    use std::fs::File;
+    use std::path::PathBuf;
+    use std::convert::TryFrom;
+    use std::collections::HashMap;
+
+    use fluvio_controlplane_metadata::extended::ObjectType;
+
+    use crate::x509::X509Identity;
+
+    use super::*;
+
+    #[test]
+    fn test_policy_serialization() {
+        let mut policy = BasicRbacPolicy::default();
+
+        let mut default_role = HashMap::new();
+
+        default_role.insert(ObjectType::Topic, vec![Action::All.into()]);
+        default_role.insert(ObjectType::Partition, vec![Action::All.into()]);
+        default_role.insert(ObjectType::SpuGroup, vec![Action::Read.into()]);
+        default_role.insert(ObjectType::CustomSpu, vec![Action::Read.into()]);
+        default_role.insert(ObjectType::Spu, vec![Action::Read.into()]);
+
+        policy.0.insert(String::from("Default"), default_role);
+
+        let tmp_file_path = PathBuf::from("/tmp/policy.json");
+        let tmp = File::create(tmp_file_path.clone()).expect("failed to create policy file");
+        serde_json::to_writer(&tmp, &policy).expect("failed to serialize policy to json file");
+
+        let recovered_policy =
+            BasicRbacPolicy::try_from(tmp_file_path).expect("failed to parse policy from file");
+
+        assert_eq!(
+            policy, recovered_policy,
+            "serialized and deserialized policies from file should match"
+        )
+    }
+
+    #[fluvio_future::test]
+    async fn test_policy_enforcement_simple() {
+        let mut policy = BasicRbacPolicy::default();
+        let identity = X509Identity::new("User".to_owned(), vec!["Default".to_owned()]);
+
+        let mut role1 = HashMap::new();
+        role1.insert(
+            ObjectType::Topic,
+            vec![Action::Delete.into(), Action::Read.into()],
+        );
+
+        policy.0.insert(String::from("Default"), role1);
+
+        assert!(!policy
+            .evaluate(Action::Create, ObjectType::CustomSpu, None, &identity)
+            .await
+            .expect("eval"));
+        assert!(!policy
+            .evaluate(Action::Create, ObjectType::Topic, None, &identity)
+            .await
+            .expect("eval"));
+        assert!(policy
+            .evaluate(Action::Read, ObjectType::Topic, None, &identity)
+            .await
+            .expect("eval"));
+        assert!(policy
+            .evaluate(Action::Delete, ObjectType::Topic, Some("test"), &identity)
+            .await
+            .expect("eval"));
+        assert!(!policy
+            .evaluate(Action::Write, ObjectType::Topic, Some("test"), &identity)
+            .await
+            .expect("eval"));
+    }
+
+    #[fluvio_future::test]
+    async fn test_policy_enforcement_instances() {
+        let mut policy = BasicRbacPolicy::default();
+        let identity = X509Identity::new("User".to_owned(), vec!["TeamA".to_owned()]);
+
+        let mut role = HashMap::new();
+        role.insert(
+            ObjectType::Topic,
+            vec![
+                Action::Read.into(),
+                InstanceRule::new(
+                    vec![Action::Create, Action::Delete, Action::Write],
+                    vec!["team-a.*".to_owned(), "shared".to_owned()],
+                )
+                .into(),
+            ],
+        );
+        policy.0.insert(String::from("TeamA"), role);
+
+        // type level check passes if any instance is allowed
+        assert!(policy
+            .evaluate(Action::Create, ObjectType::Topic, None, &identity)
+            .await
+            .expect("eval"));
+        assert!(policy
+            .evaluate(
+                Action::Create,
+                ObjectType::Topic,
+                Some("team-a.orders"),
+                &identity
+            )
+            .await
+            .expect("eval"));
+        assert!(policy
+            .evaluate(Action::Write, ObjectType::Topic, Some("shared"), &identity)
+            .await
+            .expect("eval"));
+        assert!(!policy
+            .evaluate(Action::Write, ObjectType::Topic, Some("shared2"), &identity)
+            .await
+            .expect("eval"));
+        assert!(!policy
+            .evaluate(
+                Action::Delete,
+                ObjectType::Topic,
+                Some("team-b.orders"),
+                &identity
+            )
+            .await
+            .expect("eval"));
+        // plain action applies to all instances
+        assert!(policy
+            .evaluate(
+                Action::Read,
+                ObjectType::Topic,
+                Some("team-b.orders"),
+                &identity
+            )
+            .await
+            .expect("eval"));
+        assert!(!policy
+            .evaluate(
+                Action::Update,
+                ObjectType::Topic,
+                Some("team-a.orders"),
+                &identity
+            )
+            .await
+            .expect("eval"));
+    }
+
+    #[test]
+    fn test_policy_mixed_format() {
+        let policy: BasicRbacPolicy = serde_json::from_str(
+            r#"{
+                "TeamA": {
+                    "Topic": ["Read", { "actions": ["All"], "instances": ["team-a.*"] }],
+                    "Spu": ["Read"]
+                }
+            }"#,
+        )
+        .expect("parse");
+
+        let team_a = policy.0.get("TeamA").expect("role");
+        assert_eq!(
+            team_a.get(&ObjectType::Topic).expect("topic"),
+            &vec![
+                Permission::Action(Action::Read),
+                Permission::Instances(InstanceRule::new(
+                    vec![Action::All],
+                    vec!["team-a.*".to_owned()]
+                ))
+            ]
+        );
+        assert_eq!(
+            team_a.get(&ObjectType::Spu).expect("spu"),
+            &vec![Permission::Action(Action::Read)]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 mod policy;
 mod error;
 
+pub mod basic;
 pub mod x509;
 
 pub use policy::*;
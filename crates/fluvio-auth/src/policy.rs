@@ -14,8 +14,15 @@ pub enum TypeAction {
     Read,
 }
 
+#[derive(Debug, Clone, PartialEq, Hash, Eq)]
 pub enum InstanceAction {
+    Create,
     Delete,
+    Update,
+    /// consume records of instance
+    Read,
+    /// produce records to instance
+    Write,
 }
 
 #[async_trait]
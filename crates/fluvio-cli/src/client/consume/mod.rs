@@ -13,7 +13,7 @@ pub use cmd::ConsumeOpt;
 
 mod cmd {
 
-    use std::time::{UNIX_EPOCH, Duration};
+    use std::time::{UNIX_EPOCH, Duration, SystemTime};
     use std::{io::Error as IoError, path::PathBuf};
     use std::io::{self, ErrorKind, Stdout};
     use std::collections::BTreeMap;
@@ -34,13 +34,19 @@ mod cmd {
     };
     use handlebars::{self, Handlebars};
     use anyhow::Result;
+    use humantime::{parse_duration, parse_rfc3339_weak, format_duration};
 
     use fluvio_types::PartitionId;
     use fluvio_spu_schema::server::smartmodule::SmartModuleContextData;
+    use fluvio_smartmodule::dataplane::smartmodule::SmartModuleStepTrace;
     use fluvio_protocol::record::NO_TIMESTAMP;
     use fluvio::metadata::tableformat::TableFormatSpec;
     use fluvio_future::io::StreamExt;
-    use fluvio::{ConsumerConfig, Fluvio, MultiplePartitionConsumer, Offset, FluvioError};
+    use fluvio::{
+        ConsumerConfig, Fluvio, MultiplePartitionConsumer, Offset, FluvioError,
+        SmartModuleChainTrace, SmartModuleDebugConfig, SmartModuleErrorPolicy,
+    };
+    use fluvio_future::task::spawn;
     use fluvio::consumer::{PartitionSelectionStrategy, Record};
     use fluvio_spu_schema::Isolation;
 
@@ -132,6 +138,16 @@ mod cmd {
         #[arg(long, value_name = "integer", conflicts_with_all = &["beginning", "head", "tail"])]
         pub start: Option<u32>,
 
+        /// Consume records produced within the duration before now
+        /// Ex: '2h', '30m', '1day'
+        #[arg(long, value_name = "duration", value_parser = parse_duration, conflicts_with_all = &["beginning", "head", "start", "tail", "from_time"])]
+        pub since: Option<Duration>,
+
+        /// Consume records produced at or after the time, in RFC3339 format
+        /// Ex: '2023-10-05T14:30:00Z'
+        #[arg(long, value_name = "time", value_parser = parse_rfc3339_weak, conflicts_with_all = &["beginning", "head", "start", "tail", "since"])]
+        pub from_time: Option<SystemTime>,
+
         /// Consume records until end offset (inclusive)
         #[arg(long, value_name = "integer")]
         pub end: Option<u32>,
@@ -182,6 +198,39 @@ mod cmd {
         #[arg(long, requires = "aggregate_group", alias = "a-init")]
         pub aggregate_initial: Option<String>,
 
+        /// (Optional) Id under which SPU checkpoints aggregate accumulators.
+        /// Consuming again with the same id resumes from the checkpointed accumulators
+        #[arg(long, requires = "aggregate_group")]
+        pub aggregate_state_id: Option<String>,
+
+        /// (Optional) Topic to join records with, for join SmartModules.
+        /// Partition of the topic must be led by the same SPU as consumed partition
+        #[arg(
+            long,
+            requires = "smartmodule_group",
+            conflicts_with = "aggregate_initial"
+        )]
+        pub join_topic: Option<String>,
+
+        /// (Optional) Size of time windows, for window SmartModules.
+        /// Ex: '10s', '5m'
+        #[arg(
+            long,
+            value_name = "duration",
+            value_parser = parse_duration,
+            requires = "smartmodule_group",
+            conflicts_with_all = &["aggregate_initial", "join_topic"]
+        )]
+        pub window_size: Option<Duration>,
+
+        /// (Optional) Interval between starts of hopping windows, defaults to window size
+        #[arg(long, value_name = "duration", value_parser = parse_duration, requires = "window_size")]
+        pub window_slide: Option<Duration>,
+
+        /// (Optional) How long a window accepts late records after its end
+        #[arg(long, value_name = "duration", value_parser = parse_duration, requires = "window_size")]
+        pub window_lateness: Option<Duration>,
+
         /// (Optional) Extra input parameters passed to the smartmodule.
         /// They should be passed using key=value format
         /// Eg. fluvio consume topic-name --smartmodule my_filter -e foo=bar -e key=value -e one=1
@@ -196,6 +245,20 @@ mod cmd {
         )]
         pub params: Option<Vec<(String, String)>>,
 
+        /// (Optional) What happens to records the smartmodule fails to process:
+        /// `fail` (default), `skip` or `dead-letter:<topic>`
+        #[arg(long, value_name = "policy", requires = "smartmodule_group")]
+        pub on_error: Option<SmartModuleErrorPolicy>,
+
+        /// (Optional) Run SmartModules in debug mode, printing input and output of each
+        /// SmartModule of the chain and messages logged by SmartModules to stderr
+        #[arg(long)]
+        pub transform_debug: bool,
+
+        /// (Optional) Trace only records with offset divisible by this number
+        #[arg(long, value_name = "n", requires = "transform_debug")]
+        pub transform_debug_sample: Option<u32>,
+
         /// (Optional) Path to a file with transformation specification.
         #[arg(long, conflicts_with = "smartmodule_group")]
         pub transforms_file: Option<PathBuf>,
@@ -291,6 +354,15 @@ mod cmd {
                 SmartModuleContextData::Aggregate {
                     accumulator: agg_initial.clone().into_bytes(),
                 }
+            } else if let Some(join_topic) = &self.join_topic {
+                SmartModuleContextData::Join(join_topic.clone())
+            } else if let Some(window_size) = self.window_size {
+                SmartModuleContextData::Window {
+                    size_ms: window_size.as_millis() as u64,
+                    slide_ms: self.window_slide.unwrap_or(window_size).as_millis() as u64,
+                    allowed_lateness_ms: self.window_lateness.unwrap_or_default().as_millis()
+                        as u64,
+                }
             } else {
                 SmartModuleContextData::None
             }
@@ -320,12 +392,14 @@ mod cmd {
                     smart_module_name,
                     self.smart_module_ctx(),
                     initial_param,
+                    self.on_error.clone().unwrap_or_default(),
                 )]
             } else if let Some(path) = &self.smartmodule_path {
                 vec![create_smartmodule_from_path(
                     path,
                     self.smart_module_ctx(),
                     initial_param,
+                    self.on_error.clone().unwrap_or_default(),
                 )?]
             } else if !self.transform.is_empty() {
                 let config =
@@ -369,6 +443,23 @@ mod cmd {
                 builder.isolation(isolation);
             }
 
+            if let Some(state_id) = &self.aggregate_state_id {
+                builder.smartmodule_state_id(state_id);
+            }
+
+            if self.transform_debug {
+                let debug = SmartModuleDebugConfig {
+                    sample_every: self.transform_debug_sample.unwrap_or(1),
+                };
+                let (sender, receiver) = async_channel::unbounded();
+                builder.smartmodule_debug(debug, sender);
+                spawn(async move {
+                    while let Ok(trace) = receiver.recv().await {
+                        print_smartmodule_trace(&trace);
+                    }
+                });
+            }
+
             let consume_config = builder.build()?;
             debug!("consume config: {:#?}", consume_config);
 
@@ -670,6 +761,13 @@ mod cmd {
                 format!(" starting at offset {offset}")
             } else if let Some(offset) = self.tail {
                 format!(" starting {offset} from the end of log")
+            } else if let Some(since) = self.since {
+                format!(" produced in the last {}", format_duration(since))
+            } else if let Some(time) = self.from_time {
+                format!(
+                    " produced since {}",
+                    humantime::format_rfc3339_seconds(time)
+                )
             } else {
                 "".to_string()
             };
@@ -719,6 +817,10 @@ mod cmd {
                 Offset::absolute(offset as i64).unwrap()
             } else if let Some(offset) = self.tail {
                 Offset::from_end(offset)
+            } else if let Some(since) = self.since {
+                Offset::from_timestamp(SystemTime::now() - since)
+            } else if let Some(time) = self.from_time {
+                Offset::from_timestamp(time)
             } else {
                 Offset::end()
             };
@@ -746,11 +848,48 @@ mod cmd {
             ConsumeOutputType::dynamic
         }
     }
+
+    /// Print traces of SmartModule chain running in debug mode to stderr
+    fn print_smartmodule_trace(trace: &SmartModuleChainTrace) {
+        for step in &trace.records {
+            eprintln!("{}", format_step_trace(step));
+        }
+        for log in &trace.logs {
+            eprintln!("[log] step {}: {}", log.step, log.message);
+        }
+    }
+
+    fn format_step_trace(step: &SmartModuleStepTrace) -> String {
+        let output = if let Some(error) = &step.error {
+            format!("error: {error}")
+        } else if step.output.is_empty() {
+            "dropped".to_string()
+        } else {
+            step.output
+                .iter()
+                .map(|record| record.value.as_utf8_lossy_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        format!(
+            "[trace] offset {} step {} ({}): {} -> {}",
+            step.offset,
+            step.step,
+            step.smartmodule,
+            step.input.value.as_utf8_lossy_string(),
+            output
+        )
+    }
+
     #[cfg(test)]
     mod tests {
+        use std::time::{Duration, UNIX_EPOCH};
+
         use fluvio::Offset;
+        use fluvio_protocol::record::Record;
+        use fluvio_smartmodule::dataplane::smartmodule::SmartModuleStepTrace;
 
-        use super::ConsumeOpt;
+        use super::{ConsumeOpt, format_step_trace};
 
         fn get_opt() -> ConsumeOpt {
             ConsumeOpt {
@@ -765,6 +904,8 @@ mod cmd {
                 start: Default::default(),
                 head: Default::default(),
                 tail: Default::default(),
+                since: Default::default(),
+                from_time: Default::default(),
                 end: Default::default(),
                 max_bytes: Default::default(),
                 suppress_unknown: Default::default(),
@@ -772,7 +913,15 @@ mod cmd {
                 smartmodule: Default::default(),
                 smartmodule_path: Default::default(),
                 aggregate_initial: Default::default(),
+                aggregate_state_id: Default::default(),
+                join_topic: Default::default(),
+                window_size: Default::default(),
+                window_slide: Default::default(),
+                window_lateness: Default::default(),
                 params: Default::default(),
+                on_error: Default::default(),
+                transform_debug: Default::default(),
+                transform_debug_sample: Default::default(),
                 isolation: Default::default(),
                 beginning: Default::default(),
                 transforms_file: Default::default(),
@@ -831,6 +980,22 @@ mod cmd {
             "Consuming records from 'TOPIC_NAME' starting 1 from the end of log until offset 2 (inclusive)",
         );
 
+            // --since
+            let mut opt = get_opt();
+            opt.since = Some(Duration::from_secs(2 * 60 * 60));
+            assert_eq!(
+                opt.format_status_string(),
+                "Consuming records from 'TOPIC_NAME' produced in the last 2h",
+            );
+
+            // --from-time
+            let mut opt = get_opt();
+            opt.from_time = Some(UNIX_EPOCH + Duration::from_secs(1_500_000_000));
+            assert_eq!(
+                opt.format_status_string(),
+                "Consuming records from 'TOPIC_NAME' produced since 2017-07-14T02:40:00Z",
+            );
+
             // base case
             let mut opt = get_opt();
             assert_eq!(
@@ -874,6 +1039,41 @@ mod cmd {
             opt.start = Some(1);
             let offset = opt.calculate_offset().unwrap();
             assert_eq!(offset, Offset::absolute(1).unwrap());
+
+            // --from-time
+            let mut opt = get_opt();
+            let time = UNIX_EPOCH + Duration::from_secs(1_500_000_000);
+            opt.from_time = Some(time);
+            let offset = opt.calculate_offset().unwrap();
+            assert_eq!(offset, Offset::from_timestamp(time));
+        }
+
+        #[test]
+        fn test_format_step_trace() {
+            let mut step = SmartModuleStepTrace {
+                step: 1,
+                smartmodule: "map".to_string(),
+                offset: 5,
+                input: Record::new("apple"),
+                output: vec![Record::new("APPLE")],
+                error: None,
+            };
+            assert_eq!(
+                format_step_trace(&step),
+                "[trace] offset 5 step 1 (map): apple -> APPLE"
+            );
+
+            step.output.clear();
+            assert_eq!(
+                format_step_trace(&step),
+                "[trace] offset 5 step 1 (map): apple -> dropped"
+            );
+
+            step.error = Some("not a number".to_string());
+            assert_eq!(
+                format_step_trace(&step),
+                "[trace] offset 5 step 1 (map): apple -> error: not a number"
+            );
         }
     }
 }
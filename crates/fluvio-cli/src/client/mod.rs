@@ -4,6 +4,7 @@ mod hub;
 mod produce;
 mod partition;
 mod tableformat;
+mod quota;
 mod smartmodule;
 mod smartmodule_invocation;
 
@@ -51,6 +52,7 @@ mod cmd {
     use super::topic::TopicCmd;
     use super::partition::PartitionCmd;
     use super::tableformat::TableFormatCmd;
+    use super::quota::QuotaCmd;
     use super::hub::HubCmd;
 
     #[async_trait]
@@ -131,6 +133,13 @@ mod cmd {
         #[command(subcommand, name = "table-format", visible_alias = "tf")]
         TableFormat(TableFormatCmd),
 
+        /// Create and manage client quotas
+        ///
+        /// Quotas limit produce and consume rates of clients on SPUs.
+        /// Clients exceeding a quota are throttled.
+        #[command(subcommand, name = "quota")]
+        Quota(QuotaCmd),
+
         /// Work with the SmartModule Hub
         #[command(subcommand, name = "hub")]
         Hub(HubCmd),
@@ -162,6 +171,9 @@ mod cmd {
                 Self::TableFormat(tableformat) => {
                     tableformat.process(out, target).await?;
                 }
+                Self::Quota(quota) => {
+                    quota.process(out, target).await?;
+                }
                 Self::Hub(hub) => {
                     hub.process(out, target).await?;
                 }
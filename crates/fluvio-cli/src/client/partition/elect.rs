@@ -0,0 +1,50 @@
+//!
+//! # Elect Preferred Leader
+//!
+//! CLI tree to move leaders of partitions back to their preferred replica
+//!
+
+use clap::Parser;
+use anyhow::Result;
+
+use fluvio::Fluvio;
+use fluvio_protocol::record::ReplicaKey;
+
+use crate::error::CliError;
+
+#[derive(Debug, Parser)]
+pub struct ElectLeaderOpt {
+    /// Partitions in the form <topic>-<partition>, all partitions if omitted
+    #[arg(value_name = "partition")]
+    partitions: Vec<ReplicaKey>,
+}
+
+impl ElectLeaderOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        let admin = fluvio.admin().await;
+        let results = admin.elect_preferred_leaders(self.partitions).await?;
+
+        let mut err_happened = false;
+        for result in results {
+            let replica = &result.replica;
+            if result.error_code.is_error() {
+                err_happened = true;
+                println!(
+                    "partition \"{replica}\" leader election failed with: {}",
+                    result.error_code
+                );
+            } else {
+                println!("partition \"{replica}\" leader is spu {}", result.leader);
+            }
+        }
+
+        if err_happened {
+            Err(CliError::CollectedError(
+                "Failed electing preferred leader(s). Check previous errors.".to_string(),
+            )
+            .into())
+        } else {
+            Ok(())
+        }
+    }
+}
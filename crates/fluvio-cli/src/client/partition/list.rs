@@ -132,12 +132,22 @@ mod display {
                         _ => bytesize::ByteSize::b(status.size as u64).to_string(),
                     };
 
+                    // out of sync replica was elected, records might be lost
+                    let resolution = match &status.unclean_election {
+                        Some(election) => format!(
+                            "{:?} (unclean election, lost records: {})",
+                            status.resolution,
+                            election.lost_records()
+                        ),
+                        None => format!("{:?}", status.resolution),
+                    };
+
                     Row::from([
                         Cell::new(topic),
                         Cell::new(partition),
                         Cell::new(spec.leader.to_string()),
                         Cell::new(format!("{:?}", spec.followers())),
-                        Cell::new(format!("{:?}", status.resolution)),
+                        Cell::new(resolution),
                         Cell::new(printable_size),
                         Cell::new(status.leader.hw.to_string()),
                         Cell::new(status.leader.leo.to_string()),
@@ -1,4 +1,6 @@
 mod list;
+mod reassign;
+mod elect;
 
 pub use cmd::PartitionCmd;
 
@@ -18,6 +20,8 @@ mod cmd {
     use crate::common::FluvioExtensionMetadata;
 
     use super::list::ListPartitionOpt;
+    use super::reassign::ReassignPartitionOpt;
+    use super::elect::ElectLeaderOpt;
 
     #[derive(Debug, Parser)]
     #[command(name = "partition", about = "Partition operations")]
@@ -28,6 +32,20 @@ mod cmd {
             help_template = crate::common::COMMAND_TEMPLATE,
         )]
         List(ListPartitionOpt),
+
+        /// Move replicas of Partitions to other SPUs
+        #[command(
+            name = "reassign",
+            help_template = crate::common::COMMAND_TEMPLATE,
+        )]
+        Reassign(ReassignPartitionOpt),
+
+        /// Move leaders of Partitions back to their preferred replica
+        #[command(
+            name = "elect-leader",
+            help_template = crate::common::COMMAND_TEMPLATE,
+        )]
+        ElectLeader(ElectLeaderOpt),
     }
 
     #[async_trait]
@@ -41,6 +59,12 @@ mod cmd {
                 Self::List(list) => {
                     list.process(out, fluvio).await?;
                 }
+                Self::Reassign(reassign) => {
+                    reassign.process(fluvio).await?;
+                }
+                Self::ElectLeader(elect) => {
+                    elect.process(fluvio).await?;
+                }
             }
 
             Ok(())
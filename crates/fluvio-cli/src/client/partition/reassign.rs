@@ -0,0 +1,106 @@
+//!
+//! # Reassign Partitions
+//!
+//! CLI tree to move replicas of partitions to other SPUs
+//!
+
+use clap::Parser;
+use anyhow::{anyhow, Result};
+
+use fluvio::Fluvio;
+use fluvio::metadata::partition::{PartitionAssignment, ReassignPartitionsRequest};
+use fluvio_protocol::record::ReplicaKey;
+use fluvio_types::SpuId;
+
+use crate::error::CliError;
+
+#[derive(Debug, Parser)]
+pub struct ReassignPartitionOpt {
+    /// Replicas of partition in the form <topic>-<partition>=<spu>,<spu>,...
+    /// First replica is the preferred leader
+    #[arg(short, long = "assignment", value_name = "assignment", value_parser = parse_assignment)]
+    assignments: Vec<PartitionAssignment>,
+
+    /// Balance replicas of all partitions of the topic across online SPUs
+    #[arg(short, long = "balance", value_name = "topic")]
+    balance_topics: Vec<String>,
+
+    /// Validate assignments without moving any replica
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl ReassignPartitionOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        if self.assignments.is_empty() && self.balance_topics.is_empty() {
+            return Err(CliError::InvalidArg(
+                "at least one --assignment or --balance is required".to_owned(),
+            )
+            .into());
+        }
+
+        let admin = fluvio.admin().await;
+        let results = admin
+            .reassign_partitions(ReassignPartitionsRequest {
+                assignments: self.assignments,
+                balance_topics: self.balance_topics,
+                dry_run: self.dry_run,
+            })
+            .await?;
+
+        let mut err_happened = false;
+        for result in results {
+            let replica = &result.assignment.replica;
+            let replicas = &result.assignment.replicas;
+            if result.error_code.is_error() {
+                err_happened = true;
+                println!(
+                    "partition \"{replica}\" reassignment failed with: {}",
+                    result.error_code
+                );
+            } else if self.dry_run {
+                println!("partition \"{replica}\" can be reassigned to {replicas:?}");
+            } else {
+                println!("partition \"{replica}\" reassigning to {replicas:?}");
+            }
+        }
+
+        if err_happened {
+            Err(CliError::CollectedError(
+                "Failed reassigning partition(s). Check previous errors.".to_string(),
+            )
+            .into())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn parse_assignment(value: &str) -> Result<PartitionAssignment> {
+    let (partition, replicas) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow!("expected <topic>-<partition>=<spu>,<spu>,..."))?;
+    let replica: ReplicaKey = partition.parse()?;
+    let replicas = replicas
+        .split(',')
+        .map(|spu| spu.trim().parse::<SpuId>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| anyhow!("invalid spu id: {err}"))?;
+    Ok(PartitionAssignment::new(replica, replicas))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_assignment() {
+        let assignment = parse_assignment("my-topic-1=3,1,2").expect("parse");
+        assert_eq!(assignment.replica, ReplicaKey::new("my-topic", 1u32));
+        assert_eq!(assignment.replicas, vec![3, 1, 2]);
+
+        assert!(parse_assignment("my-topic-1").is_err());
+        assert!(parse_assignment("my-topic=1,2").is_err());
+        assert!(parse_assignment("my-topic-1=1,a").is_err());
+    }
+}
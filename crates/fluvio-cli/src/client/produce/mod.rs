@@ -25,6 +25,7 @@ mod cmd {
     use fluvio::{
         Compression, Fluvio, FluvioError, TopicProducer, TopicProducerConfigBuilder, RecordKey,
         ProduceOutput, DeliverySemantic, SmartModuleContextData, Isolation, SmartModuleInvocation,
+        SmartModuleErrorPolicy,
     };
     use fluvio_extension_common::Terminal;
     use fluvio_types::print_cli_ok;
@@ -159,6 +160,11 @@ mod cmd {
         )]
         pub params: Option<Vec<(String, String)>>,
 
+        /// (Optional) What happens to records the smartmodule fails to process:
+        /// `fail` (default), `skip` or `dead-letter:<topic>`
+        #[arg(long, value_name = "policy", requires = "smartmodule_group")]
+        pub on_error: Option<SmartModuleErrorPolicy>,
+
         #[cfg(feature = "producer-file-io")]
         /// (Optional) Path to a file with transformation specification.
         #[arg(long, conflicts_with = "smartmodule_group")]
@@ -613,6 +619,7 @@ mod cmd {
                     smart_module_name,
                     self.smart_module_ctx(),
                     initial_param,
+                    self.on_error.clone().unwrap_or_default(),
                 )]);
             }
 
@@ -622,6 +629,7 @@ mod cmd {
                     path,
                     self.smart_module_ctx(),
                     initial_param,
+                    self.on_error.clone().unwrap_or_default(),
                 )?]);
             }
 
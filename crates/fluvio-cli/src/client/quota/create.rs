@@ -0,0 +1,64 @@
+//!
+//! # Create a client Quota
+//!
+//! CLI tree to generate Create a Quota spec
+//!
+
+use clap::Parser;
+use tracing::debug;
+use anyhow::Result;
+
+use fluvio::Fluvio;
+use fluvio::metadata::quota::QuotaSpec;
+
+// -----------------------------------
+// CLI Options
+// -----------------------------------
+
+#[derive(Debug, Parser, Default)]
+pub struct CreateQuotaOpt {
+    /// The name of the quota to create
+    #[arg(value_name = "name")]
+    pub name: String,
+
+    /// X509 principal of clients limited by quota. Applies to all clients if not set
+    #[arg(long)]
+    pub principal: Option<String>,
+
+    /// Topic limited by quota. Applies to all topics if not set
+    #[arg(long)]
+    pub topic: Option<String>,
+
+    /// Max bytes per second produced by a client
+    #[arg(long)]
+    pub producer_byte_rate: Option<u64>,
+
+    /// Max bytes per second consumed by a client
+    #[arg(long)]
+    pub consumer_byte_rate: Option<u64>,
+
+    /// Max produce and fetch requests per second of a client
+    #[arg(long)]
+    pub request_rate: Option<u64>,
+}
+
+impl CreateQuotaOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        let quota_spec = QuotaSpec {
+            principal: self.principal,
+            topic: self.topic,
+            producer_byte_rate: self.producer_byte_rate,
+            consumer_byte_rate: self.consumer_byte_rate,
+            request_rate: self.request_rate,
+        };
+        quota_spec.validate()?;
+
+        debug!("creating quota: {} spec: {:#?}", &self.name, quota_spec);
+
+        let admin = fluvio.admin().await;
+        admin.create(self.name.clone(), false, quota_spec).await?;
+        println!("quota \"{}\" created", &self.name);
+
+        Ok(())
+    }
+}
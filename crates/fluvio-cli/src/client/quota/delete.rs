@@ -0,0 +1,28 @@
+//!
+//! # Delete Quota spec
+//!
+//! CLI tree to generate Delete Quota spec
+//!
+use clap::Parser;
+use anyhow::Result;
+
+use fluvio::Fluvio;
+use fluvio::metadata::quota::QuotaSpec;
+
+// -----------------------------------
+// CLI Options
+// -----------------------------------
+
+#[derive(Debug, Parser)]
+pub struct DeleteQuotaOpt {
+    /// The name of the quota to delete
+    name: String,
+}
+
+impl DeleteQuotaOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        let admin = fluvio.admin().await;
+        admin.delete::<QuotaSpec>(&self.name).await?;
+        Ok(())
+    }
+}
@@ -4,13 +4,14 @@ use std::io::Read;
 
 use fluvio::{
     SmartModuleInvocation, SmartModuleInvocationWasm, SmartModuleKind, SmartModuleContextData,
-    SmartModuleExtraParams,
+    SmartModuleErrorPolicy, SmartModuleExtraParams, SmartModuleBudget,
 };
+use fluvio::metadata::topic::Transform;
 use fluvio_smartengine::transformation::TransformationConfig;
 
 use flate2::bufread::GzEncoder;
 use flate2::Compression;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use tracing::debug;
 
 /// create smartmodule from predefined name
@@ -18,11 +19,14 @@ pub(crate) fn create_smartmodule(
     name: &str,
     ctx: SmartModuleContextData,
     params: BTreeMap<String, String>,
+    error_policy: SmartModuleErrorPolicy,
 ) -> SmartModuleInvocation {
     SmartModuleInvocation {
         wasm: SmartModuleInvocationWasm::Predefined(name.to_string()),
         kind: SmartModuleKind::Generic(ctx),
         params: params.into(),
+        error_policy,
+        budget: Default::default(),
     }
 }
 
@@ -31,6 +35,7 @@ pub(crate) fn create_smartmodule_from_path(
     path: &Path,
     ctx: SmartModuleContextData,
     params: BTreeMap<String, String>,
+    error_policy: SmartModuleErrorPolicy,
 ) -> Result<SmartModuleInvocation> {
     let raw_buffer = std::fs::read(path)?;
     debug!(len = raw_buffer.len(), "read wasm bytes");
@@ -42,6 +47,8 @@ pub(crate) fn create_smartmodule_from_path(
         wasm: SmartModuleInvocationWasm::AdHoc(buffer),
         kind: SmartModuleKind::Generic(ctx),
         params: params.into(),
+        error_policy,
+        budget: Default::default(),
     })
 }
 
@@ -62,6 +69,37 @@ pub(crate) fn create_smartmodule_list(
                     .collect::<std::collections::BTreeMap<String, String>>(),
                 t.lookback.map(Into::into),
             ),
+            error_policy: t.on_error.map(Into::into).unwrap_or_default(),
+            budget: SmartModuleBudget {
+                fuel_limit: t.fuel_limit,
+                call_timeout: t.call_timeout,
+            },
         })
         .collect())
 }
+
+/// create topic transforms from a list of transformations
+pub(crate) fn create_topic_transforms(config: TransformationConfig) -> Result<Vec<Transform>> {
+    config
+        .transforms
+        .into_iter()
+        .map(|t| {
+            if t.lookback.is_some() {
+                return Err(anyhow!(
+                    "`lookback` of `{}` is not supported in topic transforms",
+                    t.uses
+                ));
+            }
+            if t.on_error.is_some() {
+                return Err(anyhow!(
+                    "`on_error` of `{}` is not supported in topic transforms",
+                    t.uses
+                ));
+            }
+            Ok(Transform {
+                uses: t.uses,
+                with: t.with.into_iter().map(|(k, v)| (k, v.into())).collect(),
+            })
+        })
+        .collect()
+}
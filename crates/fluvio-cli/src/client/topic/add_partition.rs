@@ -0,0 +1,42 @@
+//!
+//! # Add Partitions to Topic
+//!
+//! CLI tree to add partitions to existing topic
+//!
+
+use tracing::debug;
+use clap::Parser;
+use anyhow::Result;
+
+use fluvio::Fluvio;
+use fluvio::metadata::topic::{TopicSpec, UpdateTopicAction, AddPartitionConfig};
+use fluvio_types::PartitionCount;
+
+#[derive(Debug, Parser)]
+pub struct AddPartitionOpt {
+    /// Name of the topic
+    #[arg(value_name = "name")]
+    topic: String,
+
+    /// Number of partitions to add
+    #[arg(short, long, default_value = "1", value_parser = clap::value_parser!(u32).range(1..))]
+    count: PartitionCount,
+}
+
+impl AddPartitionOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        debug!(topic = %self.topic, count = self.count, "adding partitions");
+        let admin = fluvio.admin().await;
+        admin
+            .update::<TopicSpec>(
+                self.topic.clone(),
+                UpdateTopicAction::AddPartition(AddPartitionConfig { count: self.count }),
+            )
+            .await?;
+        println!(
+            "added {} partition(s) to topic \"{}\"",
+            self.count, self.topic
+        );
+        Ok(())
+    }
+}
@@ -14,17 +14,21 @@ use anyhow::Result;
 use fluvio_types::PartitionCount;
 use fluvio_types::ReplicationFactor;
 use fluvio::metadata::topic::CleanupPolicy;
+use fluvio::metadata::topic::CompactPolicy;
 use fluvio::metadata::topic::ReplicaSpec;
 use fluvio::metadata::topic::SegmentBasedPolicy;
 use fluvio::metadata::topic::TopicStorageConfig;
 use fluvio::metadata::topic::CompressionAlgorithm;
+use fluvio::metadata::topic::LeaderElectionPolicy;
 
 use fluvio_controlplane_metadata::topic::config::TopicConfig;
 use fluvio_sc_schema::shared::validate_resource_name;
+use fluvio_smartengine::transformation::TransformationConfig;
 
 use fluvio::Fluvio;
 use fluvio::metadata::topic::TopicSpec;
 use crate::CliError;
+use crate::client::smartmodule_invocation::create_topic_transforms;
 
 #[derive(Debug, Parser)]
 pub struct CreateTopicOpt {
@@ -136,7 +140,13 @@ impl CreateTopicOpt {
         };
 
         let mut topic_spec: TopicSpec = replica_spec.into();
-        if let Some(retention) = self.setting.retention_time {
+        if self.setting.compact {
+            let mut policy = CompactPolicy::default();
+            if let Some(delete_retention) = self.setting.delete_retention_time {
+                policy.delete_retention_secs = delete_retention.as_secs() as u32;
+            }
+            topic_spec.set_cleanup_policy(CleanupPolicy::Compact(policy));
+        } else if let Some(retention) = self.setting.retention_time {
             topic_spec.set_cleanup_policy(CleanupPolicy::Segment(SegmentBasedPolicy {
                 time_in_seconds: retention.as_secs() as u32,
             }));
@@ -160,6 +170,21 @@ impl CreateTopicOpt {
             topic_spec.set_storage(storage);
         }
 
+        if let Some(transforms_file) = &self.setting.transforms_file {
+            let config = TransformationConfig::from_file(transforms_file).map_err(|err| {
+                CliError::InvalidArg(format!(
+                    "unable to process `transforms_file` argument: {err}"
+                ))
+            })?;
+            topic_spec.set_transforms(create_topic_transforms(config)?);
+        }
+
+        topic_spec.set_min_in_sync_replicas(self.setting.min_in_sync_replicas);
+
+        if let Some(leader_election) = self.setting.leader_election {
+            topic_spec.set_leader_election(leader_election);
+        }
+
         Ok((self.topic.unwrap_or_default(), topic_spec))
     }
 }
@@ -181,6 +206,19 @@ pub struct TopicConfigOpt {
     #[arg(long, value_name = "time",value_parser=parse_duration)]
     retention_time: Option<Duration>,
 
+    /// Compact topic, keeping only the latest record for each key instead of
+    /// removing old segments after retention time.
+    /// Tombstone record, a record with `tombstone` header, deletes the key.
+    /// Only closed segments are compacted, so older records of keys are
+    /// kept until their segment reaches segment size.
+    #[arg(long, conflicts_with = "retention_time")]
+    compact: bool,
+
+    /// How long records deleting a key are kept in compacted topic (round to seconds)
+    /// Ex: '1h', '2d 10s', '1 day' (default)
+    #[arg(long, value_name = "time", value_parser=parse_duration, requires = "compact")]
+    delete_retention_time: Option<Duration>,
+
     /// Segment size (by default measured in bytes)
     /// Ex: `2048`, '2 Ki', '10 MiB', `1 GB`
     #[arg(long, value_name = "bytes")]
@@ -194,6 +232,24 @@ pub struct TopicConfigOpt {
     /// Ex: `2048`, '2 Ki', '10 MiB', `1 GB`
     #[arg(long, value_name = "bytes")]
     max_partition_size: Option<bytesize::ByteSize>,
+
+    /// Path to a file with transformation specification.
+    /// SPU applies the transformations to every record written to topic,
+    /// after SmartModules of producer.
+    #[arg(long, value_name = "PATH")]
+    transforms_file: Option<PathBuf>,
+
+    /// Min number of in-sync replicas, including leader, to accept writes.
+    /// Writes are rejected while fewer replicas are in sync.
+    /// SPU default is used if not set
+    #[arg(long, value_name = "integer")]
+    min_in_sync_replicas: Option<u16>,
+
+    /// Leader election when none of in-sync replicas is online.
+    /// 'consistency' (default) keeps partition offline until in-sync replica is back,
+    /// 'availability' elects out of sync replica and loses records it has not replicated
+    #[arg(long, value_name = "policy")]
+    leader_election: Option<LeaderElectionPolicy>,
 }
 
 /// module to load partitions maps from file
@@ -146,6 +146,18 @@ mod display {
                 }
             }
 
+            if let Some(min_in_sync_replicas) = spec.get_min_in_sync_replicas() {
+                key_values.push((
+                    "Min In-Sync Replicas".to_owned(),
+                    Some(min_in_sync_replicas.to_string()),
+                ));
+            }
+
+            key_values.push((
+                "Leader Election".to_owned(),
+                Some(spec.get_leader_election().to_string()),
+            ));
+
             if let Some(dedup) = spec.get_deduplication() {
                 key_values.push((
                     "Deduplication Filter".to_owned(),
@@ -164,6 +176,15 @@ mod display {
                 ));
             };
 
+            if !spec.get_transforms().is_empty() {
+                let transforms: Vec<&str> = spec
+                    .get_transforms()
+                    .iter()
+                    .map(|transform| transform.uses.as_str())
+                    .collect();
+                key_values.push(("Transforms".to_owned(), Some(transforms.join(", "))));
+            }
+
             key_values.push((
                 "Status".to_owned(),
                 Some(status.resolution.resolution_label().to_string()),
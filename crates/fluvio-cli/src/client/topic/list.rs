@@ -48,7 +48,7 @@ mod display {
     use serde::Serialize;
 
     use fluvio::metadata::objects::Metadata;
-    use fluvio::metadata::topic::TopicSpec;
+    use fluvio::metadata::topic::{CleanupPolicy, TopicSpec};
 
     use crate::common::output::{OutputType, TableOutputHandler, Terminal, OutputError};
     use crate::common::t_println;
@@ -110,9 +110,17 @@ mod display {
                         Cell::new(topic.type_label()),
                         Cell::new(topic.partitions_display()).set_alignment(CellAlignment::Left),
                         Cell::new(topic.replication_factor_display()),
-                        Cell::new(format_duration(Duration::from_secs(
-                            topic.retention_secs() as u64
-                        ))),
+                        Cell::new(
+                            if topic
+                                .get_clean_policy()
+                                .is_some_and(CleanupPolicy::is_compact)
+                            {
+                                "compact".to_owned()
+                            } else {
+                                format_duration(Duration::from_secs(topic.retention_secs() as u64))
+                                    .to_string()
+                            },
+                        ),
                         Cell::new(topic.get_compression_type()),
                         Cell::new(
                             topic
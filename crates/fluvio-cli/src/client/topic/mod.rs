@@ -1,7 +1,9 @@
+mod add_partition;
 mod create;
 mod delete;
 mod describe;
 mod list;
+mod set_transforms;
 
 pub use cmd::TopicCmd;
 
@@ -21,10 +23,12 @@ mod cmd {
     use crate::common::output::Terminal;
     use crate::common::FluvioExtensionMetadata;
 
+    use super::add_partition::AddPartitionOpt;
     use super::create::CreateTopicOpt;
     use super::delete::DeleteTopicOpt;
     use super::describe::DescribeTopicsOpt;
     use super::list::ListTopicsOpt;
+    use super::set_transforms::SetTransformsOpt;
 
     #[derive(Debug, Parser)]
     #[command(name = "topic", about = "Topic operations")]
@@ -56,6 +60,20 @@ mod cmd {
             help_template = COMMAND_TEMPLATE,
         )]
         List(ListTopicsOpt),
+
+        /// Add partitions to a Topic
+        #[command(
+            name = "add-partition",
+            help_template = COMMAND_TEMPLATE,
+        )]
+        AddPartition(AddPartitionOpt),
+
+        /// Set SmartModules applied to records written to a Topic
+        #[command(
+            name = "set-transforms",
+            help_template = COMMAND_TEMPLATE,
+        )]
+        SetTransforms(SetTransformsOpt),
     }
 
     #[async_trait]
@@ -78,6 +96,12 @@ mod cmd {
                 Self::List(list) => {
                     list.process(out, fluvio).await?;
                 }
+                Self::AddPartition(add_partition) => {
+                    add_partition.process(fluvio).await?;
+                }
+                Self::SetTransforms(set_transforms) => {
+                    set_transforms.process(fluvio).await?;
+                }
             }
 
             Ok(())
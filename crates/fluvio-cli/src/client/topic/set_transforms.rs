@@ -0,0 +1,64 @@
+//!
+//! # Set Transforms of Topic
+//!
+//! CLI tree to replace SmartModule chain applied to records written to existing topic
+//!
+
+use std::path::PathBuf;
+
+use tracing::debug;
+use clap::Parser;
+use anyhow::Result;
+
+use fluvio::Fluvio;
+use fluvio::metadata::topic::{TopicSpec, UpdateTopicAction, SetTransformsConfig};
+use fluvio_smartengine::transformation::TransformationConfig;
+
+use crate::CliError;
+use crate::client::smartmodule_invocation::create_topic_transforms;
+
+#[derive(Debug, Parser)]
+pub struct SetTransformsOpt {
+    /// Name of the topic
+    #[arg(value_name = "name")]
+    topic: String,
+
+    /// Path to a file with transformation specification
+    #[arg(long, value_name = "PATH", required_unless_present = "clear")]
+    transforms_file: Option<PathBuf>,
+
+    /// Remove transforms from topic
+    #[arg(long, conflicts_with = "transforms_file")]
+    clear: bool,
+}
+
+impl SetTransformsOpt {
+    pub async fn process(self, fluvio: &Fluvio) -> Result<()> {
+        let transforms = match &self.transforms_file {
+            Some(transforms_file) => {
+                let config = TransformationConfig::from_file(transforms_file).map_err(|err| {
+                    CliError::InvalidArg(format!(
+                        "unable to process `transforms_file` argument: {err}"
+                    ))
+                })?;
+                create_topic_transforms(config)?
+            }
+            None => vec![],
+        };
+        debug!(topic = %self.topic, ?transforms, "setting transforms");
+        let count = transforms.len();
+        let admin = fluvio.admin().await;
+        admin
+            .update::<TopicSpec>(
+                self.topic.clone(),
+                UpdateTopicAction::SetTransforms(SetTransformsConfig { transforms }),
+            )
+            .await?;
+        if count == 0 {
+            println!("removed transforms from topic \"{}\"", self.topic);
+        } else {
+            println!("set {count} transform(s) on topic \"{}\"", self.topic);
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,6 @@
-use fluvio::{FluvioConfig, SmartModuleInvocation, SmartModuleKind, SmartModuleExtraParams};
+use fluvio::{
+    FluvioConfig, SmartModuleInvocation, SmartModuleKind, SmartModuleExtraParams, SmartModuleBudget,
+};
 use crate::{config::ConnectorConfig, Result};
 use fluvio_smartengine::transformation::TransformationConfig;
 
@@ -47,6 +49,11 @@ pub fn smartmodule_vec_from_config(config: &ConnectorConfig) -> Option<Vec<Smart
                         .collect::<std::collections::BTreeMap<String, String>>(),
                     s.lookback.map(Into::into),
                 ),
+                error_policy: s.on_error.clone().map(Into::into).unwrap_or_default(),
+                budget: SmartModuleBudget {
+                    fuel_limit: s.fuel_limit,
+                    call_timeout: s.call_timeout,
+                },
             })
             .collect(),
     )
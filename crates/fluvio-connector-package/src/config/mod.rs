@@ -608,6 +608,9 @@ mod tests {
                         ),
                         ("param".to_string(), "param_value".into()),
                     ]),
+                    on_error: None,
+                    fuel_limit: None,
+                    call_timeout: None,
                 }
                 .into(),
             ),
@@ -639,11 +642,15 @@ mod tests {
                         max_size: Some(bytesize::ByteSize(1000)),
                         replication: Some(2),
                         ignore_rack_assignment: Some(true),
+                        min_in_sync_replicas: None,
+                        leader_election: None,
                         maps: None,
                     },
                     retention: RetentionConfig {
                         time: Some(Duration::from_secs(120)),
                         segment_size: Some(bytesize::ByteSize(2000)),
+                        compact: None,
+                        delete_retention_time: None,
                     },
                     compression: CompressionConfig {
                         type_: CompressionAlgorithm::Lz4,
@@ -661,6 +668,7 @@ mod tests {
                             },
                         },
                     }),
+                    transforms: vec![],
                 },
                 version: "0.1.0".to_string(),
                 producer: Some(ProducerParameters {
@@ -687,6 +695,9 @@ mod tests {
                         ),
                         ("param".to_string(), "param_value".into()),
                     ]),
+                    on_error: None,
+                    fuel_limit: None,
+                    call_timeout: None,
                 }
                 .into(),
             ),
@@ -4,6 +4,8 @@ pub mod partition;
 pub mod spg;
 pub mod smartmodule;
 pub mod tableformat;
+pub mod quota;
+pub mod transaction;
 pub mod message;
 
 pub use fluvio_stream_model::core;
@@ -31,6 +33,7 @@ pub mod extended {
         SmartModule,
         TableFormat,
         DerivedStream,
+        Quota,
     }
 
     pub trait SpecExt: Spec {
@@ -7,7 +7,10 @@
 use fluvio_types::SpuId;
 use fluvio_protocol::{Encoder, Decoder};
 
-use crate::topic::{CleanupPolicy, TopicStorageConfig, TopicSpec, CompressionAlgorithm, Deduplication};
+use crate::topic::{
+    CleanupPolicy, TopicStorageConfig, TopicSpec, CompressionAlgorithm, Deduplication, Transform,
+    LeaderElectionPolicy,
+};
 
 /// Spec for Partition
 /// Each partition has replicas spread among SPU
@@ -30,6 +33,18 @@ pub struct PartitionSpec {
     pub compression_type: CompressionAlgorithm,
     #[fluvio(min_version = 12)]
     pub deduplication: Option<Deduplication>,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    #[fluvio(min_version = 13)]
+    pub reassignment: Option<PartitionReassignment>,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    #[fluvio(min_version = 14)]
+    pub transforms: Vec<Transform>,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    #[fluvio(min_version = 15)]
+    pub min_in_sync_replicas: Option<u16>,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    #[fluvio(min_version = 16)]
+    pub leader_election: LeaderElectionPolicy,
 }
 
 impl PartitionSpec {
@@ -52,6 +67,10 @@ impl PartitionSpec {
             storage: topic.get_storage().cloned(),
             compression_type: topic.get_compression_type().clone(),
             deduplication: topic.get_deduplication().cloned(),
+            reassignment: None,
+            transforms: topic.get_transforms().to_vec(),
+            min_in_sync_replicas: topic.get_min_in_sync_replicas(),
+            leader_election: topic.get_leader_election(),
         }
     }
 
@@ -78,6 +97,43 @@ impl From<Vec<SpuId>> for PartitionSpec {
     }
 }
 
+/// Replicas of partition being moved to new SPUs.
+/// While partition is reassigned, `replicas` of the spec contains both current and target replicas.
+#[derive(Decoder, Encoder, Default, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct PartitionReassignment {
+    /// replicas after reassignment, first replica is preferred leader
+    pub target_replicas: Vec<SpuId>,
+    /// new replicas which have not caught up with leader yet
+    pub adding_replicas: Vec<SpuId>,
+}
+
+impl PartitionReassignment {
+    /// start reassignment from current replicas
+    pub fn new(current_replicas: &[SpuId], target_replicas: Vec<SpuId>) -> Self {
+        let adding_replicas = target_replicas
+            .iter()
+            .filter(|spu| !current_replicas.contains(spu))
+            .copied()
+            .collect();
+        Self {
+            target_replicas,
+            adding_replicas,
+        }
+    }
+
+    /// current and target replicas
+    pub fn all_replicas(&self, current_replicas: &[SpuId]) -> Vec<SpuId> {
+        let mut replicas = current_replicas.to_vec();
+        replicas.extend(self.adding_replicas.iter().copied());
+        replicas
+    }
+}
+
 /// Setting applied to a replica
 #[derive(Decoder, Encoder, Debug, Eq, PartialEq, Clone, Default)]
 pub struct PartitionConfig {
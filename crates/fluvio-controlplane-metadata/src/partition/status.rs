@@ -38,6 +38,13 @@ pub struct PartitionStatus {
     #[fluvio(min_version = 5)]
     pub size: i64,
     pub is_being_deleted: bool,
+    /// last election which promoted replica that was not in sync
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[fluvio(min_version = 16)]
+    pub unclean_election: Option<UncleanElection>,
 }
 
 impl Default for PartitionStatus {
@@ -49,6 +56,7 @@ impl Default for PartitionStatus {
             lsr: Default::default(),
             replicas: Default::default(),
             is_being_deleted: Default::default(),
+            unclean_election: Default::default(),
         }
     }
 }
@@ -158,6 +166,42 @@ pub enum PartitionResolution {
     ElectionLeaderFound, // New leader has been selected
 }
 
+/// Leader election of partition which promoted replica that was not in sync.
+/// Records written to previous leader after `leo` of new leader might be lost.
+#[derive(Decoder, Encoder, Default, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct UncleanElection {
+    pub previous_leader: SpuId,
+    /// last log end offset reported by previous leader
+    pub previous_leo: Offset,
+    pub leader: SpuId,
+    /// log end offset of new leader when it was elected
+    pub leo: Offset,
+}
+
+impl UncleanElection {
+    /// number of records which might be lost
+    pub fn lost_records(&self) -> i64 {
+        (self.previous_leo - self.leo).max(0)
+    }
+}
+
+impl fmt::Display for UncleanElection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "leader: {} -> {} lost records: {}",
+            self.previous_leader,
+            self.leader,
+            self.lost_records()
+        )
+    }
+}
+
 #[derive(Decoder, Encoder, Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(
     feature = "use_serde",
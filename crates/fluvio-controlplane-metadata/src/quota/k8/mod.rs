@@ -0,0 +1,38 @@
+//!
+//! # Quota
+//!
+//! Interface to the Quota metadata in K8 key value store
+//!
+
+use super::QuotaStatus;
+use super::QuotaSpec;
+use crate::k8_types::Status as K8Status;
+use crate::k8_types::{Crd, Spec, DefaultHeader};
+
+/// implement k8 status for quota status because they are same
+impl K8Status for QuotaStatus {}
+
+use crd::QUOTA_SPEC_API;
+mod crd {
+
+    use crate::k8_types::{Crd, CrdNames, GROUP, V1};
+
+    pub const QUOTA_SPEC_API: Crd = Crd {
+        group: GROUP,
+        version: V1,
+        names: CrdNames {
+            kind: "Quota",
+            plural: "quotas",
+            singular: "quota",
+        },
+    };
+}
+
+impl Spec for QuotaSpec {
+    type Status = QuotaStatus;
+    type Header = DefaultHeader;
+
+    fn metadata() -> &'static Crd {
+        &QUOTA_SPEC_API
+    }
+}
@@ -0,0 +1,115 @@
+#![allow(clippy::assign_op_pattern)]
+
+use fluvio_protocol::{Encoder, Decoder};
+
+/// Rate limits of clients producing to and consuming from SPUs.
+/// Limits apply to each client identity separately.
+#[derive(Encoder, Decoder, Default, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct QuotaSpec {
+    /// X509 principal of clients, quota applies to all clients if not set
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub principal: Option<String>,
+    /// quota applies to all topics if not set
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub topic: Option<String>,
+    /// max bytes per second produced by a client
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub producer_byte_rate: Option<u64>,
+    /// max bytes per second consumed by a client
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub consumer_byte_rate: Option<u64>,
+    /// max produce and fetch requests per second of a client
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub request_rate: Option<u64>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidQuota {
+    #[error(
+        "quota must limit at least one of producer byte rate, consumer byte rate or request rate"
+    )]
+    NoLimit,
+    #[error("quota rates must be greater than zero")]
+    ZeroRate,
+}
+
+impl QuotaSpec {
+    /// check if quota applies to client with `principal` accessing `topic`
+    pub fn is_match(&self, principal: Option<&str>, topic: &str) -> bool {
+        let principal_match = match &self.principal {
+            Some(quota_principal) => principal == Some(quota_principal.as_str()),
+            None => true,
+        };
+        let topic_match = match &self.topic {
+            Some(quota_topic) => quota_topic == topic,
+            None => true,
+        };
+        principal_match && topic_match
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidQuota> {
+        let rates = [
+            self.producer_byte_rate,
+            self.consumer_byte_rate,
+            self.request_rate,
+        ];
+        if rates.iter().all(Option::is_none) {
+            return Err(InvalidQuota::NoLimit);
+        }
+        if rates.contains(&Some(0)) {
+            return Err(InvalidQuota::ZeroRate);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::QuotaSpec;
+
+    #[test]
+    fn test_quota_match() {
+        let all = QuotaSpec::default();
+        assert!(all.is_match(None, "topic1"));
+        assert!(all.is_match(Some("alice"), "topic1"));
+
+        let alice = QuotaSpec {
+            principal: Some("alice".to_owned()),
+            ..Default::default()
+        };
+        assert!(alice.is_match(Some("alice"), "topic1"));
+        assert!(!alice.is_match(Some("bob"), "topic1"));
+        assert!(!alice.is_match(None, "topic1"));
+
+        let alice_topic = QuotaSpec {
+            principal: Some("alice".to_owned()),
+            topic: Some("topic1".to_owned()),
+            ..Default::default()
+        };
+        assert!(alice_topic.is_match(Some("alice"), "topic1"));
+        assert!(!alice_topic.is_match(Some("alice"), "topic2"));
+    }
+
+    #[test]
+    fn test_quota_validate() {
+        assert!(QuotaSpec::default().validate().is_err());
+        assert!(QuotaSpec {
+            producer_byte_rate: Some(0),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+        assert!(QuotaSpec {
+            producer_byte_rate: Some(1024),
+            request_rate: Some(10),
+            ..Default::default()
+        }
+        .validate()
+        .is_ok());
+    }
+}
@@ -0,0 +1,44 @@
+#![allow(clippy::assign_op_pattern)]
+
+use std::fmt;
+
+use fluvio_protocol::{Encoder, Decoder};
+
+#[derive(Encoder, Decoder, Default, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct QuotaStatus {
+    /// Status resolution
+    pub resolution: QuotaStatusResolution,
+
+    /// Reason for Status resolution (if applies)
+    pub reason: Option<String>,
+}
+
+impl fmt::Display for QuotaStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.resolution)
+    }
+}
+
+#[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Encoder, Decoder, Default, Debug, Clone, Eq, PartialEq)]
+pub enum QuotaStatusResolution {
+    #[default]
+    #[fluvio(tag = 0)]
+    Init,
+    #[fluvio(tag = 1)]
+    Invalid,
+}
+
+impl fmt::Display for QuotaStatusResolution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Init => write!(f, "Init"),
+            Self::Invalid => write!(f, "Invalid"),
+        }
+    }
+}
@@ -4,10 +4,14 @@ use derive_builder::Builder;
 use fluvio_types::{ReplicationFactor, TopicName, PartitionCount, IgnoreRackAssignment};
 
 use crate::topic::{
-    ReplicaSpec, TopicReplicaParam, SegmentBasedPolicy, CleanupPolicy, TopicStorageConfig,
+    ReplicaSpec, TopicReplicaParam, SegmentBasedPolicy, CompactPolicy, CleanupPolicy,
+    TopicStorageConfig,
 };
 
-use super::{TopicSpec, PartitionMap, CompressionAlgorithm, deduplication::Deduplication};
+use super::{
+    TopicSpec, PartitionMap, CompressionAlgorithm, LeaderElectionPolicy,
+    deduplication::{Deduplication, Transform},
+};
 
 const DEFAULT_PARTITION_COUNT: PartitionCount = 1;
 const DEFAULT_REPLICATION_FACTOR: ReplicationFactor = 1;
@@ -45,6 +49,13 @@ pub struct TopicConfig {
         serde(default, skip_serializing_if = "Option::is_none")
     )]
     pub deduplication: Option<Deduplication>,
+
+    #[builder(default)]
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub transforms: Vec<Transform>,
 }
 
 #[derive(Debug, Default, Builder, Clone, PartialEq, Eq)]
@@ -76,6 +87,12 @@ pub struct PartitionConfig {
     #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "Option::is_none"))]
     pub ignore_rack_assignment: Option<IgnoreRackAssignment>,
 
+    #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub min_in_sync_replicas: Option<u16>,
+
+    #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub leader_election: Option<LeaderElectionPolicy>,
+
     #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "Option::is_none"))]
     pub maps: Option<Vec<PartitionMap>>,
 }
@@ -89,12 +106,31 @@ pub struct PartitionConfig {
 pub struct RetentionConfig {
     #[cfg_attr(
         feature = "use_serde",
-        serde(skip_serializing_if = "Option::is_none", with = "humantime_serde")
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "humantime_serde"
+        )
     )]
     pub time: Option<Duration>,
 
     #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "Option::is_none"))]
     pub segment_size: Option<bytesize::ByteSize>,
+
+    /// keep only latest record per key instead of removing old segments
+    #[cfg_attr(feature = "use_serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub compact: Option<bool>,
+
+    /// how long tombstones are kept in compacted topic
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "humantime_serde"
+        )
+    )]
+    pub delete_retention_time: Option<Duration>,
 }
 
 #[derive(Debug, Default, Builder, Clone, PartialEq, Eq)]
@@ -130,6 +166,8 @@ impl Default for PartitionConfig {
             count: Some(DEFAULT_PARTITION_COUNT),
             replication: Some(DEFAULT_REPLICATION_FACTOR),
             ignore_rack_assignment: Some(DEFAULT_IGNORE_RACK_ASSIGMENT),
+            min_in_sync_replicas: Default::default(),
+            leader_election: Default::default(),
             max_size: Default::default(),
             maps: Default::default(),
         }
@@ -156,7 +194,13 @@ impl From<TopicConfig> for TopicSpec {
             }),
         };
         let mut topic_spec: TopicSpec = replica_spec.into();
-        if let Some(retention_time) = config.retention.time {
+        if config.retention.compact.unwrap_or_default() {
+            let mut policy = CompactPolicy::default();
+            if let Some(delete_retention_time) = config.retention.delete_retention_time {
+                policy.delete_retention_secs = delete_retention_time.as_secs() as u32;
+            }
+            topic_spec.set_cleanup_policy(CleanupPolicy::Compact(policy));
+        } else if let Some(retention_time) = config.retention.time {
             topic_spec.set_cleanup_policy(CleanupPolicy::Segment(SegmentBasedPolicy {
                 time_in_seconds: retention_time.as_secs() as u32,
             }));
@@ -164,6 +208,9 @@ impl From<TopicConfig> for TopicSpec {
 
         topic_spec.set_compression_type(config.compression.type_);
         topic_spec.set_deduplication(config.deduplication);
+        topic_spec.set_transforms(config.transforms);
+        topic_spec.set_min_in_sync_replicas(config.partition.min_in_sync_replicas);
+        topic_spec.set_leader_election(config.partition.leader_election.unwrap_or_default());
 
         if segment_size.is_some() || max_partition_size.is_some() {
             topic_spec.set_storage(TopicStorageConfig {
@@ -314,6 +361,104 @@ compression:
         assert_eq!(spec, test_spec);
     }
 
+    #[cfg(feature = "use_serde")]
+    #[test]
+    fn test_compact_topic_config_to_spec() {
+        //given
+        let input = r#"meta:
+  name: test_topic
+retention:
+  compact: true
+  delete-retention-time: 1h
+"#;
+
+        //when
+        use std::str::FromStr;
+
+        let spec: TopicSpec = TopicConfig::from_str(input).expect("deserialized").into();
+
+        //then
+        assert_eq!(
+            spec.get_clean_policy(),
+            Some(&CleanupPolicy::Compact(CompactPolicy {
+                delete_retention_secs: 3600
+            }))
+        );
+    }
+
+    #[cfg(feature = "use_serde")]
+    #[test]
+    fn test_transforms_topic_config_to_spec() {
+        //given
+        let input = r#"meta:
+  name: test_topic
+transforms:
+- uses: infinyon/jolt@0.1.0
+  with:
+    spec: '[{"operation":"remove","spec":{"password":""}}]'
+- uses: infinyon/regex-filter@0.1.0
+"#;
+
+        //when
+        use std::str::FromStr;
+
+        let spec: TopicSpec = TopicConfig::from_str(input).expect("deserialized").into();
+
+        //then
+        let transforms = spec.get_transforms();
+        assert_eq!(transforms.len(), 2);
+        assert_eq!(transforms[0].uses, "infinyon/jolt@0.1.0");
+        assert_eq!(
+            transforms[0].with.get("spec").map(String::as_str),
+            Some(r#"[{"operation":"remove","spec":{"password":""}}]"#)
+        );
+        assert_eq!(transforms[1].uses, "infinyon/regex-filter@0.1.0");
+        assert!(transforms[1].with.is_empty());
+    }
+
+    #[cfg(feature = "use_serde")]
+    #[test]
+    fn test_min_in_sync_replicas_topic_config_to_spec() {
+        //given
+        let input = r#"meta:
+  name: test_topic
+partition:
+  replication: 3
+  min-in-sync-replicas: 2
+"#;
+
+        //when
+        use std::str::FromStr;
+
+        let spec: TopicSpec = TopicConfig::from_str(input).expect("deserialized").into();
+
+        //then
+        assert_eq!(spec.get_min_in_sync_replicas(), Some(2));
+    }
+
+    #[cfg(feature = "use_serde")]
+    #[test]
+    fn test_leader_election_topic_config_to_spec() {
+        //given
+        let input = r#"meta:
+  name: test_topic
+partition:
+  replication: 3
+  leader-election: availability
+"#;
+
+        //when
+        use std::str::FromStr;
+
+        let spec: TopicSpec = TopicConfig::from_str(input).expect("deserialized").into();
+
+        //then
+        assert_eq!(
+            spec.get_leader_election(),
+            LeaderElectionPolicy::Availability
+        );
+    }
+
     fn test_config() -> TopicConfig {
         TopicConfig {
             version: "0.1.1".to_string(),
@@ -325,6 +470,8 @@ compression:
                 max_size: Some(bytesize::ByteSize(1000)),
                 replication: Some(2),
                 ignore_rack_assignment: Some(true),
+                min_in_sync_replicas: None,
+                leader_election: None,
                 maps: Some(vec![PartitionMap {
                     id: 1,
                     replicas: vec![1, 2],
@@ -333,11 +480,14 @@ compression:
             retention: RetentionConfig {
                 time: Some(Duration::from_secs(120)),
                 segment_size: Some(bytesize::ByteSize(2000)),
+                compact: None,
+                delete_retention_time: None,
             },
             compression: CompressionConfig {
                 type_: CompressionAlgorithm::Lz4,
             },
             deduplication: Some(test_deduplication()),
+            transforms: vec![],
         }
     }
 
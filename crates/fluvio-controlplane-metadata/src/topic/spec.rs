@@ -1,16 +1,18 @@
+use std::io::Error as IoError;
 use std::ops::Deref;
 
 use anyhow::{anyhow, Result};
+use bytes::BufMut;
 
 use fluvio_types::defaults::{
-    STORAGE_RETENTION_SECONDS, SPU_LOG_LOG_SEGMENT_MAX_BYTE_MIN, STORAGE_RETENTION_SECONDS_MIN,
-    SPU_PARTITION_MAX_BYTES_MIN, SPU_LOG_SEGMENT_MAX_BYTES,
+    STORAGE_RETENTION_SECONDS, STORAGE_DELETE_RETENTION_SECONDS, SPU_LOG_LOG_SEGMENT_MAX_BYTE_MIN,
+    STORAGE_RETENTION_SECONDS_MIN, SPU_PARTITION_MAX_BYTES_MIN, SPU_LOG_SEGMENT_MAX_BYTES,
 };
 use fluvio_types::SpuId;
 use fluvio_types::{PartitionId, PartitionCount, ReplicationFactor, IgnoreRackAssignment};
-use fluvio_protocol::{Encoder, Decoder};
+use fluvio_protocol::{Encoder, Decoder, Version};
 
-use super::deduplication::Deduplication;
+use super::deduplication::{Deduplication, Transform};
 
 #[derive(Debug, Clone, PartialEq, Default, Encoder, Decoder)]
 #[cfg_attr(
@@ -30,6 +32,21 @@ pub struct TopicSpec {
     #[cfg_attr(feature = "use_serde", serde(default))]
     #[fluvio(min_version = 12)]
     deduplication: Option<Deduplication>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    #[fluvio(min_version = 14)]
+    transforms: Vec<Transform>,
+    #[cfg_attr(
+        feature = "use_serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    #[fluvio(min_version = 15)]
+    min_in_sync_replicas: Option<u16>,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    #[fluvio(min_version = 16)]
+    leader_election: LeaderElectionPolicy,
 }
 
 impl From<ReplicaSpec> for TopicSpec {
@@ -73,6 +90,28 @@ impl TopicSpec {
         &self.replicas
     }
 
+    /// Add partitions to computed topic and return new partition count.
+    /// Partitions of assigned topic are defined by its partition map.
+    pub fn add_partitions(&mut self, count: PartitionCount) -> Result<PartitionCount> {
+        if count == 0 {
+            return Err(anyhow!(
+                "number of partitions to add must be greater than 0"
+            ));
+        }
+        match &mut self.replicas {
+            ReplicaSpec::Computed(param) => {
+                param.partitions = param
+                    .partitions
+                    .checked_add(count)
+                    .ok_or_else(|| anyhow!("too many partitions"))?;
+                Ok(param.partitions)
+            }
+            ReplicaSpec::Assigned(_) => Err(anyhow!(
+                "partitions can only be added to topic with computed replicas"
+            )),
+        }
+    }
+
     pub fn set_cleanup_policy(&mut self, policy: CleanupPolicy) {
         self.cleanup_policy = Some(policy);
     }
@@ -109,6 +148,34 @@ impl TopicSpec {
         self.deduplication = deduplication;
     }
 
+    /// SmartModule chain applied by partition leader to every record written to topic
+    pub fn get_transforms(&self) -> &[Transform] {
+        &self.transforms
+    }
+
+    pub fn set_transforms(&mut self, transforms: Vec<Transform>) {
+        self.transforms = transforms;
+    }
+
+    /// min number of in-sync replicas partition leader requires to accept writes,
+    /// SPU default is used if not set
+    pub fn get_min_in_sync_replicas(&self) -> Option<u16> {
+        self.min_in_sync_replicas
+    }
+
+    pub fn set_min_in_sync_replicas(&mut self, min_in_sync_replicas: Option<u16>) {
+        self.min_in_sync_replicas = min_in_sync_replicas;
+    }
+
+    /// how leader is elected when none of in-sync replicas is online
+    pub fn get_leader_election(&self) -> LeaderElectionPolicy {
+        self.leader_election
+    }
+
+    pub fn set_leader_election(&mut self, leader_election: LeaderElectionPolicy) {
+        self.leader_election = leader_election;
+    }
+
     /// get retention secs that can be displayed
     pub fn retention_secs(&self) -> u32 {
         self.get_clean_policy()
@@ -151,6 +218,19 @@ impl TopicSpec {
             }
         }
 
+        if let Some(min_in_sync_replicas) = self.min_in_sync_replicas {
+            if min_in_sync_replicas == 0 {
+                return Some("min_in_sync_replicas must be at least 1".to_string());
+            }
+            if let Some(replication_factor) = self.replication_factor() {
+                if min_in_sync_replicas as ReplicationFactor > replication_factor {
+                    return Some(format!(
+                        "min_in_sync_replicas {min_in_sync_replicas} is greater than replication factor {replication_factor}"
+                    ));
+                }
+            }
+        }
+
         None
     }
 }
@@ -527,12 +607,18 @@ pub struct PartitionMap {
     pub replicas: Vec<SpuId>,
 }
 
-#[derive(Decoder, Encoder, Debug, Clone, Eq, PartialEq)]
+/// first version which can decode compact cleanup policy
+pub const COMPACT_POLICY_VERSION: Version = 13;
+
+#[derive(Decoder, Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "use_serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CleanupPolicy {
     #[cfg_attr(feature = "use_serde", serde(rename = "segment"))]
     #[fluvio(tag = 0)]
     Segment(SegmentBasedPolicy),
+    #[cfg_attr(feature = "use_serde", serde(rename = "compact"))]
+    #[fluvio(tag = 1)]
+    Compact(CompactPolicy),
 }
 
 impl Default for CleanupPolicy {
@@ -545,7 +631,51 @@ impl CleanupPolicy {
     pub fn retention_secs(&self) -> u32 {
         match self {
             CleanupPolicy::Segment(policy) => policy.retention_secs(),
+            CleanupPolicy::Compact(policy) => policy.retention_secs(),
+        }
+    }
+
+    pub fn is_compact(&self) -> bool {
+        matches!(self, CleanupPolicy::Compact(_))
+    }
+}
+
+// custom encoding to handle prev version
+impl Encoder for CleanupPolicy {
+    fn write_size(&self, version: Version) -> usize {
+        let size = match self {
+            CleanupPolicy::Segment(policy) => policy.write_size(version),
+            CleanupPolicy::Compact(_) if version < COMPACT_POLICY_VERSION => {
+                SegmentBasedPolicy::default().write_size(version)
+            }
+            CleanupPolicy::Compact(policy) => policy.write_size(version),
+        };
+        0u8.write_size(version) + size
+    }
+
+    fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), IoError>
+    where
+        T: BufMut,
+    {
+        match self {
+            CleanupPolicy::Segment(policy) => {
+                0u8.encode(dest, version)?;
+                policy.encode(dest, version)?;
+            }
+            CleanupPolicy::Compact(_) if version < COMPACT_POLICY_VERSION => {
+                // older versions don't know compaction, segments of compacted topic never expire
+                0u8.encode(dest, version)?;
+                SegmentBasedPolicy {
+                    time_in_seconds: u32::MAX,
+                }
+                .encode(dest, version)?;
+            }
+            CleanupPolicy::Compact(policy) => {
+                1u8.encode(dest, version)?;
+                policy.encode(dest, version)?;
+            }
         }
+        Ok(())
     }
 }
 
@@ -565,6 +695,36 @@ impl SegmentBasedPolicy {
     }
 }
 
+/// Keep only latest record for each key.
+/// Tombstone record deletes the key and is removed after `delete_retention_secs`.
+/// Record values can't be null, so tombstones are marked by `tombstone` record header.
+/// Only closed segments are compacted, records of the active segment are all kept
+/// until it is closed when reaching segment size.
+#[derive(Decoder, Encoder, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct CompactPolicy {
+    pub delete_retention_secs: u32,
+}
+
+impl Default for CompactPolicy {
+    fn default() -> Self {
+        Self {
+            delete_retention_secs: STORAGE_DELETE_RETENTION_SECONDS,
+        }
+    }
+}
+
+impl CompactPolicy {
+    /// time tombstones are kept before being removed
+    pub fn retention_secs(&self) -> u32 {
+        self.delete_retention_secs
+    }
+}
+
 #[derive(Decoder, Encoder, Default, Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(
     feature = "use_serde",
@@ -626,6 +786,49 @@ impl std::fmt::Display for CompressionAlgorithm {
     }
 }
 
+/// Leader election of partition when none of in-sync replicas is online
+#[derive(Decoder, Default, Encoder, Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum LeaderElectionPolicy {
+    /// partition stays offline until one of in-sync replicas is back
+    #[default]
+    #[fluvio(tag = 0)]
+    Consistency,
+    /// online replica with highest log end offset is elected,
+    /// records not replicated to it are lost
+    #[fluvio(tag = 1)]
+    Availability,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid leader election policy, expected consistency or availability")]
+pub struct InvalidLeaderElectionPolicy;
+
+impl std::str::FromStr for LeaderElectionPolicy {
+    type Err = InvalidLeaderElectionPolicy;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "consistency" => Ok(LeaderElectionPolicy::Consistency),
+            "availability" => Ok(LeaderElectionPolicy::Availability),
+            _ => Err(InvalidLeaderElectionPolicy),
+        }
+    }
+}
+
+impl std::fmt::Display for LeaderElectionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        match self {
+            Self::Consistency => write!(f, "consistency"),
+            Self::Availability => write!(f, "availability"),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -645,6 +848,18 @@ mod test {
         assert!(t2.is_computed());
     }
 
+    #[test]
+    fn test_add_partitions() {
+        let mut computed = TopicSpec::new_computed(2, 1, None);
+        assert_eq!(computed.add_partitions(3).expect("added"), 5);
+        assert_eq!(computed.partitions(), 5);
+        assert!(computed.add_partitions(0).is_err());
+
+        let mut assigned = TopicSpec::new_assigned(vec![(0, vec![0])]);
+        assert!(assigned.add_partitions(1).is_err());
+        assert_eq!(assigned.partitions(), 1);
+    }
+
     #[test]
     fn test_valid_computed_replica_params() {
         // 0 is not a valid partition
@@ -846,6 +1061,159 @@ mod test {
         assert!(topic_spec_decoded.deduplication.is_none());
     }
 
+    #[test]
+    fn test_topic_with_transforms_prev_version_compatibility() {
+        //given
+        let prev_version = 13;
+        let mut topic_spec: TopicSpec = ReplicaSpec::Computed((2, 3, true).into()).into();
+        topic_spec.set_transforms(vec![Transform {
+            uses: "sanitize".to_string(),
+            ..Default::default()
+        }]);
+
+        //when
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, prev_version).expect("encoded");
+        let mut topic_spec_decoded = TopicSpec::default();
+        topic_spec_decoded
+            .decode(&mut Cursor::new(&dest), prev_version)
+            .expect("decoded");
+
+        //then
+        assert!(topic_spec_decoded.transforms.is_empty());
+
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, 14).expect("encoded");
+        let mut topic_spec_decoded = TopicSpec::default();
+        topic_spec_decoded
+            .decode(&mut Cursor::new(&dest), 14)
+            .expect("decoded");
+        assert_eq!(topic_spec_decoded, topic_spec);
+    }
+
+    #[test]
+    fn test_validate_min_in_sync_replicas() {
+        let mut topic_spec: TopicSpec = ReplicaSpec::Computed((2, 3, true).into()).into();
+        topic_spec.set_min_in_sync_replicas(Some(3));
+        assert!(topic_spec.validate_config().is_none());
+
+        topic_spec.set_min_in_sync_replicas(Some(4));
+        assert!(topic_spec.validate_config().is_some());
+
+        topic_spec.set_min_in_sync_replicas(Some(0));
+        assert!(topic_spec.validate_config().is_some());
+    }
+
+    #[test]
+    fn test_topic_with_min_in_sync_replicas_prev_version_compatibility() {
+        //given
+        let prev_version = 14;
+        let mut topic_spec: TopicSpec = ReplicaSpec::Computed((2, 3, true).into()).into();
+        topic_spec.set_min_in_sync_replicas(Some(2));
+
+        //when
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, prev_version).expect("encoded");
+        let mut topic_spec_decoded = TopicSpec::default();
+        topic_spec_decoded
+            .decode(&mut Cursor::new(&dest), prev_version)
+            .expect("decoded");
+
+        //then
+        assert!(topic_spec_decoded.min_in_sync_replicas.is_none());
+
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, 15).expect("encoded");
+        let mut topic_spec_decoded = TopicSpec::default();
+        topic_spec_decoded
+            .decode(&mut Cursor::new(&dest), 15)
+            .expect("decoded");
+        assert_eq!(topic_spec_decoded, topic_spec);
+    }
+
+    #[test]
+    fn test_topic_with_leader_election_prev_version_compatibility() {
+        //given
+        let prev_version = 15;
+        let mut topic_spec: TopicSpec = ReplicaSpec::Computed((2, 3, true).into()).into();
+        topic_spec.set_leader_election(LeaderElectionPolicy::Availability);
+
+        //when
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, prev_version).expect("encoded");
+        let mut topic_spec_decoded = TopicSpec::default();
+        topic_spec_decoded
+            .decode(&mut Cursor::new(&dest), prev_version)
+            .expect("decoded");
+
+        //then
+        assert_eq!(
+            topic_spec_decoded.get_leader_election(),
+            LeaderElectionPolicy::Consistency
+        );
+
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, 16).expect("encoded");
+        let mut topic_spec_decoded = TopicSpec::default();
+        topic_spec_decoded
+            .decode(&mut Cursor::new(&dest), 16)
+            .expect("decoded");
+        assert_eq!(topic_spec_decoded, topic_spec);
+    }
+
+    #[test]
+    fn test_compact_policy_encoding_for_prev_version() {
+        //given
+        let prev_version = COMPACT_POLICY_VERSION - 1;
+        let mut topic_spec: TopicSpec = ReplicaSpec::Computed((2, 3, true).into()).into();
+        topic_spec.set_cleanup_policy(CleanupPolicy::Compact(CompactPolicy::default()));
+
+        //when
+        let mut dest = vec![];
+        topic_spec.encode(&mut dest, prev_version).expect("encoded");
+        let mut topic_spec_decoded = TopicSpec::default();
+        topic_spec_decoded
+            .decode(&mut Cursor::new(&dest), prev_version)
+            .expect("decoded");
+
+        //then
+        assert_eq!(dest.len(), topic_spec.write_size(prev_version));
+        assert_eq!(
+            topic_spec_decoded.get_clean_policy(),
+            Some(&CleanupPolicy::Segment(SegmentBasedPolicy {
+                time_in_seconds: u32::MAX
+            }))
+        );
+
+        let mut dest = vec![];
+        topic_spec
+            .encode(&mut dest, COMPACT_POLICY_VERSION)
+            .expect("encoded");
+        let mut topic_spec_decoded = TopicSpec::default();
+        topic_spec_decoded
+            .decode(&mut Cursor::new(&dest), COMPACT_POLICY_VERSION)
+            .expect("decoded");
+        assert_eq!(dest.len(), topic_spec.write_size(COMPACT_POLICY_VERSION));
+        assert_eq!(topic_spec_decoded, topic_spec);
+    }
+
+    #[test]
+    fn test_leader_election_policy_from_str() {
+        assert_eq!(
+            "availability"
+                .parse::<LeaderElectionPolicy>()
+                .expect("parse"),
+            LeaderElectionPolicy::Availability
+        );
+        assert_eq!(
+            "Consistency"
+                .parse::<LeaderElectionPolicy>()
+                .expect("parse"),
+            LeaderElectionPolicy::Consistency
+        );
+        assert!("fast".parse::<LeaderElectionPolicy>().is_err());
+    }
+
     #[test]
     fn test_partition_map_str() {
         // Test multiple
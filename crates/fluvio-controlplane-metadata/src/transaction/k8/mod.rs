@@ -0,0 +1,38 @@
+//!
+//! # Transaction
+//!
+//! Interface to the Transaction metadata in K8 key value store
+//!
+
+use super::TransactionStatus;
+use super::TransactionSpec;
+use crate::k8_types::Status as K8Status;
+use crate::k8_types::{Crd, Spec, DefaultHeader};
+
+/// implement k8 status for transaction status because they are same
+impl K8Status for TransactionStatus {}
+
+use crd::TRANSACTION_SPEC_API;
+mod crd {
+
+    use crate::k8_types::{Crd, CrdNames, GROUP, V1};
+
+    pub const TRANSACTION_SPEC_API: Crd = Crd {
+        group: GROUP,
+        version: V1,
+        names: CrdNames {
+            kind: "Transaction",
+            plural: "transactions",
+            singular: "transaction",
+        },
+    };
+}
+
+impl Spec for TransactionSpec {
+    type Status = TransactionStatus;
+    type Header = DefaultHeader;
+
+    fn metadata() -> &'static Crd {
+        &TRANSACTION_SPEC_API
+    }
+}
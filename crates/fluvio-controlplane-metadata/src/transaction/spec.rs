@@ -0,0 +1,84 @@
+#![allow(clippy::assign_op_pattern)]
+
+use fluvio_protocol::{Encoder, Decoder};
+
+/// State of transactional producer kept by SC as transaction coordinator,
+/// so open and ending transactions are ended after SC restart.
+/// Key is transactional id of the producer.
+#[derive(Encoder, Decoder, Default, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TransactionSpec {
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub state: TransactionState,
+    /// partitions written by open transaction
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub partitions: Vec<TransactionPartition>,
+    /// consumer offsets committed with open transaction
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub offsets: Vec<TransactionOffset>,
+}
+
+#[derive(Encoder, Decoder, Default, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub enum TransactionState {
+    #[default]
+    #[fluvio(tag = 0)]
+    Empty,
+    #[fluvio(tag = 1)]
+    Ongoing,
+    /// control batches are being written
+    #[fluvio(tag = 2)]
+    Ending(TransactionEnd),
+}
+
+/// Ending transaction, control batches are written with producer id and epoch
+/// of the producer which wrote the transaction
+#[derive(Encoder, Decoder, Default, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TransactionEnd {
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub commit: bool,
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub partitions: Vec<TransactionPartition>,
+    /// offsets to commit, empty when transaction is aborted
+    #[cfg_attr(feature = "use_serde", serde(default))]
+    pub offsets: Vec<TransactionOffset>,
+}
+
+#[derive(Encoder, Decoder, Default, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TransactionPartition {
+    pub topic: String,
+    pub partition: u32,
+}
+
+#[derive(Encoder, Decoder, Default, Debug, Eq, PartialEq, Clone)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TransactionOffset {
+    pub consumer_group: String,
+    pub topic: String,
+    pub partition: u32,
+    pub offset: i64,
+}
@@ -0,0 +1,22 @@
+//!
+//! # Transaction Status
+//!
+//! Transaction state is kept in spec, status is not used.
+//!
+use std::fmt;
+
+use fluvio_protocol::{Encoder, Decoder};
+
+#[derive(Default, Decoder, Encoder, Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "use_serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct TransactionStatus;
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "TransactionStatus")
+    }
+}
@@ -1,5 +1,7 @@
 pub mod sc_api;
 pub mod spu_api;
+pub mod txn_api;
+pub mod offset_api;
 pub mod replica;
 pub mod message;
 pub mod requests;
@@ -2,6 +2,7 @@ mod replica_msg;
 
 pub use self::replica_msg::{ReplicaMsgs, ReplicaMsg};
 pub use self::smartmodule_msg::{SmartModuleMsgs, SmartModuleMsg};
+pub use self::quota_msg::{QuotaMsgs, QuotaMsg};
 
 pub use spu_msg::*;
 pub use smartmodule_msg::*;
@@ -20,3 +21,12 @@ mod smartmodule_msg {
     pub type SmartModuleMsg = Message<SmartModule>;
     pub type SmartModuleMsgs = Messages<SmartModule>;
 }
+
+mod quota_msg {
+    use fluvio_controlplane_metadata::message::{Message, Messages};
+
+    use crate::spu_api::update_quota::Quota;
+
+    pub type QuotaMsg = Message<Quota>;
+    pub type QuotaMsgs = Messages<Quota>;
+}
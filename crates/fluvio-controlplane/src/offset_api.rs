@@ -0,0 +1,63 @@
+//!
+//! # Consumer Offset API
+//!
+//! Requests of SC to private endpoint of SPU leaders for offsets committed by consumer groups.
+//! SC resolves leaders of all partitions of the topic, so offsets of the whole group
+//! can be fetched or reset with one request to SC.
+//!
+
+use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_protocol::api::Request;
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::Offset;
+use fluvio_protocol::{Decoder, Encoder};
+
+/// Keys of consumer offset requests in SPU peer api, following transaction requests
+#[repr(u16)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum OffsetApiKey {
+    FetchGroupOffsets = 3,
+    ResetGroupOffsets = 4,
+}
+
+/// Fetch offsets committed by consumer group for partitions led by the SPU
+#[derive(Decoder, Encoder, Default, Debug, Clone)]
+pub struct FetchGroupOffsetsRequest {
+    pub consumer_group: String,
+    pub replicas: Vec<ReplicaKey>,
+}
+
+impl Request for FetchGroupOffsetsRequest {
+    const API_KEY: u16 = OffsetApiKey::FetchGroupOffsets as u16;
+    type Response = FetchGroupOffsetsResponse;
+}
+
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct FetchGroupOffsetsResponse {
+    pub partitions: Vec<GroupOffsetResult>,
+}
+
+/// Remove offsets committed by consumer group for partitions led by the SPU
+#[derive(Decoder, Encoder, Default, Debug, Clone)]
+pub struct ResetGroupOffsetsRequest {
+    pub consumer_group: String,
+    pub replicas: Vec<ReplicaKey>,
+}
+
+impl Request for ResetGroupOffsetsRequest {
+    const API_KEY: u16 = OffsetApiKey::ResetGroupOffsets as u16;
+    type Response = ResetGroupOffsetsResponse;
+}
+
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct ResetGroupOffsetsResponse {
+    pub partitions: Vec<GroupOffsetResult>,
+}
+
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct GroupOffsetResult {
+    pub replica: ReplicaKey,
+    pub error_code: ErrorCode,
+    /// committed offset, None if the group has no offset or it was reset
+    pub offset: Option<Offset>,
+}
@@ -3,16 +3,19 @@
 use std::fmt;
 
 use fluvio_controlplane_metadata::{
-    topic::{CleanupPolicy, TopicStorageConfig, CompressionAlgorithm, Deduplication},
+    topic::{CleanupPolicy, TopicStorageConfig, CompressionAlgorithm, Deduplication, Transform},
     core::MetadataItem,
     store::MetadataStoreObject,
     partition::PartitionSpec,
 };
-use fluvio_protocol::{Encoder, Decoder, record::ReplicaKey};
+use fluvio_protocol::{Encoder, Decoder, Version, record::ReplicaKey};
 use fluvio_types::SpuId;
 
 use crate::PartitionMetadata;
 
+/// replicas added by partition reassignment are encoded since this version
+pub const REPLICA_REASSIGNMENT_VERSION: Version = 14;
+
 /// Metadata about Replica send from SC
 #[derive(Decoder, Encoder, Debug, Eq, PartialEq, Clone, Default)]
 pub struct Replica {
@@ -24,6 +27,13 @@ pub struct Replica {
     pub storage: Option<TopicStorageConfig>,
     pub compression_type: CompressionAlgorithm,
     pub deduplication: Option<Deduplication>,
+    /// replicas added by reassignment which are not in sync yet
+    #[fluvio(min_version = REPLICA_REASSIGNMENT_VERSION)]
+    pub adding_replicas: Vec<SpuId>,
+    /// SmartModules applied by leader to every produced record
+    pub transforms: Vec<Transform>,
+    /// min number of in-sync replicas to accept writes, SPU default if not set
+    pub min_in_sync_replicas: Option<u16>,
 }
 
 impl Replica {
@@ -67,6 +77,12 @@ where
             storage: spec.storage,
             compression_type: spec.compression_type,
             deduplication: spec.deduplication,
+            adding_replicas: spec
+                .reassignment
+                .map(|reassignment| reassignment.adding_replicas)
+                .unwrap_or_default(),
+            transforms: spec.transforms,
+            min_in_sync_replicas: spec.min_in_sync_replicas,
         }
     }
 }
@@ -12,6 +12,8 @@ use fluvio_protocol::Decoder;
 use super::update_spu::UpdateSpuRequest;
 use super::update_replica::UpdateReplicaRequest;
 use super::update_smartmodule::UpdateSmartModuleRequest;
+use super::update_quota::UpdateQuotaRequest;
+use super::update_policy::UpdatePolicyRequest;
 
 #[repr(u16)]
 #[derive(Eq, PartialEq, Debug, Encoder, Decoder, Clone, Copy)]
@@ -21,6 +23,8 @@ pub enum InternalSpuApi {
     UpdateReplica = 1002,
     UpdateSmartModule = 1003,
     // UpdateDerivedStream = 1004,
+    UpdateQuota = 1005,
+    UpdatePolicy = 1006,
 }
 
 impl Default for InternalSpuApi {
@@ -37,6 +41,10 @@ pub enum InternalSpuRequest {
     UpdateReplicaRequest(RequestMessage<UpdateReplicaRequest>),
     #[fluvio(tag = 2)]
     UpdateSmartModuleRequest(RequestMessage<UpdateSmartModuleRequest>),
+    #[fluvio(tag = 3)]
+    UpdateQuotaRequest(RequestMessage<UpdateQuotaRequest>),
+    #[fluvio(tag = 4)]
+    UpdatePolicyRequest(RequestMessage<UpdatePolicyRequest>),
 }
 
 // Added to satisfy Encoder/Decoder traits
@@ -67,6 +75,8 @@ impl ApiMessage for InternalSpuRequest {
             InternalSpuApi::UpdateSmartModule => {
                 api_decode!(Self, UpdateSmartModuleRequest, src, header)
             }
+            InternalSpuApi::UpdateQuota => api_decode!(Self, UpdateQuotaRequest, src, header),
+            InternalSpuApi::UpdatePolicy => api_decode!(Self, UpdatePolicyRequest, src, header),
         }
     }
 }
@@ -2,3 +2,5 @@ pub mod api;
 pub mod update_replica;
 pub mod update_smartmodule;
 pub mod update_spu;
+pub mod update_quota;
+pub mod update_policy;
@@ -0,0 +1,27 @@
+use fluvio_protocol::Decoder;
+use fluvio_protocol::Encoder;
+use fluvio_protocol::api::Request;
+
+use super::api::InternalSpuApi;
+
+/// Authorization policy of clients, sent to SPU when it connects to SC
+#[derive(Decoder, Encoder, Debug, Default, Clone, Eq, PartialEq)]
+pub struct UpdatePolicyRequest {
+    /// JSON policy document, SPU allows all clients if SC has no policy
+    pub policy: Option<String>,
+}
+
+impl UpdatePolicyRequest {
+    pub fn new(policy: Option<String>) -> Self {
+        Self { policy }
+    }
+}
+
+impl Request for UpdatePolicyRequest {
+    const API_KEY: u16 = InternalSpuApi::UpdatePolicy as u16;
+    type Response = UpdatePolicyResponse;
+    const DEFAULT_API_VERSION: i16 = 10; // align with pubic api to get version encoding
+}
+
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct UpdatePolicyResponse {}
@@ -0,0 +1,47 @@
+use std::fmt;
+
+use fluvio_controlplane_metadata::core::MetadataItem;
+use fluvio_controlplane_metadata::quota::QuotaSpec;
+use fluvio_controlplane_metadata::store::MetadataStoreObject;
+use fluvio_protocol::Decoder;
+use fluvio_protocol::Encoder;
+use fluvio_protocol::api::Request;
+
+use crate::requests::ControlPlaneRequest;
+
+use super::api::InternalSpuApi;
+
+pub type UpdateQuotaRequest = ControlPlaneRequest<Quota>;
+
+impl Request for UpdateQuotaRequest {
+    const API_KEY: u16 = InternalSpuApi::UpdateQuota as u16;
+    type Response = UpdateQuotaResponse;
+    const DEFAULT_API_VERSION: i16 = 10; // align with pubic api to get version encoding
+}
+
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct UpdateQuotaResponse {}
+
+/// Quota object that can be used to transport from SC to SPU
+#[derive(Debug, Default, Clone, Eq, PartialEq, Encoder, Decoder)]
+pub struct Quota {
+    pub name: String,
+    pub spec: QuotaSpec,
+}
+
+impl fmt::Display for Quota {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Quota({})", self.name)
+    }
+}
+
+impl<C> From<MetadataStoreObject<QuotaSpec, C>> for Quota
+where
+    C: MetadataItem,
+{
+    fn from(mso: MetadataStoreObject<QuotaSpec, C>) -> Self {
+        let name = mso.key_owned();
+        let spec = mso.spec;
+        Self { name, spec }
+    }
+}
@@ -2,7 +2,7 @@ use fluvio_protocol::api::Request;
 use fluvio_protocol::Decoder;
 use fluvio_protocol::Encoder;
 
-use crate::replica::Replica;
+use crate::replica::{Replica, REPLICA_REASSIGNMENT_VERSION};
 use crate::requests::ControlPlaneRequest;
 
 use super::api::InternalSpuApi;
@@ -12,6 +12,7 @@ pub type UpdateReplicaRequest = ControlPlaneRequest<Replica>;
 impl Request for UpdateReplicaRequest {
     const API_KEY: u16 = InternalSpuApi::UpdateReplica as u16;
     type Response = UpdateReplicaResponse;
+    const DEFAULT_API_VERSION: i16 = REPLICA_REASSIGNMENT_VERSION; // cleanup policy and reassignment are encoded by version
 }
 
 #[derive(Decoder, Encoder, Default, Debug)]
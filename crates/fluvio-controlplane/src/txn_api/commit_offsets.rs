@@ -0,0 +1,30 @@
+use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_protocol::api::Request;
+use fluvio_protocol::record::Offset;
+use fluvio_protocol::{Decoder, Encoder};
+
+use super::TxnApiKey;
+use super::write_markers::TxnPartitionResult;
+
+/// Commit consumer offsets of committed transaction to partitions led by the SPU
+#[derive(Decoder, Encoder, Default, Debug, Clone)]
+pub struct CommitTxnOffsetsRequest {
+    pub offsets: Vec<TxnOffset>,
+}
+
+impl Request for CommitTxnOffsetsRequest {
+    const API_KEY: u16 = TxnApiKey::CommitTxnOffsets as u16;
+    type Response = CommitTxnOffsetsResponse;
+}
+
+#[derive(Decoder, Encoder, Default, Debug, Clone, PartialEq, Eq)]
+pub struct TxnOffset {
+    pub consumer_group: String,
+    pub replica: ReplicaKey,
+    pub offset: Offset,
+}
+
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct CommitTxnOffsetsResponse {
+    pub partitions: Vec<TxnPartitionResult>,
+}
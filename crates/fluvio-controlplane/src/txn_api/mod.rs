@@ -0,0 +1,18 @@
+//!
+//! # Transaction API
+//!
+//! Requests of SC transaction coordinator to private endpoint of SPU leaders.
+//! Control batches and offsets of transactions are written by SC only,
+//! public endpoint of SPU rejects control batches sent by clients.
+//!
+
+pub mod write_markers;
+pub mod commit_offsets;
+
+/// Keys of transaction requests in SPU peer api, following fetch stream of followers
+#[repr(u16)]
+#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+pub enum TxnApiKey {
+    WriteTxnMarkers = 1,
+    CommitTxnOffsets = 2,
+}
@@ -0,0 +1,35 @@
+use std::time::Duration;
+
+use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_protocol::api::Request;
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::{Decoder, Encoder};
+
+use super::TxnApiKey;
+
+/// Write control batch ending transaction of the producer to partitions led by the SPU
+#[derive(Decoder, Encoder, Default, Debug, Clone)]
+pub struct WriteTxnMarkersRequest {
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub commit: bool,
+    pub partitions: Vec<ReplicaKey>,
+    /// time for followers to replicate control batches
+    pub timeout: Duration,
+}
+
+impl Request for WriteTxnMarkersRequest {
+    const API_KEY: u16 = TxnApiKey::WriteTxnMarkers as u16;
+    type Response = WriteTxnMarkersResponse;
+}
+
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct WriteTxnMarkersResponse {
+    pub partitions: Vec<TxnPartitionResult>,
+}
+
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct TxnPartitionResult {
+    pub replica: ReplicaKey,
+    pub error_code: ErrorCode,
+}
@@ -108,6 +108,25 @@ pub enum ErrorCode {
     #[error("the fetch session was not found")]
     FetchSessionNotFoud,
 
+    // Partition reassignment errors
+    #[fluvio(tag = 3100)]
+    #[error("the partition was not found")]
+    PartitionNotFound,
+    #[fluvio(tag = 3101)]
+    #[error("invalid partition reassignment: {0}")]
+    PartitionReassignmentInvalid(String),
+    #[fluvio(tag = 3102)]
+    #[error("the partition is being reassigned")]
+    PartitionReassignmentInProgress,
+    #[fluvio(tag = 3103)]
+    #[error("the preferred leader is not online or not in sync")]
+    PreferredLeaderNotAvailable,
+
+    // Replication errors
+    #[fluvio(tag = 3200)]
+    #[error("not enough in-sync replicas: {in_sync} in sync, at least {min} required")]
+    NotEnoughInSyncReplicas { in_sync: u16, min: u16 },
+
     // Legacy SmartModule errors
     #[cfg(feature = "smartmodule")]
     #[deprecated(since = "0.9.13")]
@@ -158,6 +177,18 @@ pub enum ErrorCode {
         "SmartModule memory limit exceeded: requested {requested} bytes, max allowed {max} bytes"
     )]
     SmartModuleMemoryLimitExceeded { requested: u64, max: u64 },
+    #[fluvio(tag = 6009)]
+    #[error("SmartModule fuel limit exceeded: call exhausted {limit} fuel")]
+    SmartModuleFuelExhausted { limit: u64 },
+    #[fluvio(tag = 6010)]
+    #[error("SmartModule call timed out after {timeout_ms} ms")]
+    SmartModuleTimeout { timeout_ms: u64 },
+    #[fluvio(tag = 6011)]
+    #[error("SmartModule join error: {0}")]
+    SmartModuleJoinError(String),
+    #[fluvio(tag = 6012)]
+    #[error("SmartModule dead-letter error: {0}")]
+    SmartModuleDeadLetterError(String),
 
     // TableFormat Errors
     #[fluvio(tag = 7000)]
@@ -195,6 +226,56 @@ pub enum ErrorCode {
     #[fluvio(tag = 10001)]
     #[error("Deduplication SmartModule name is invalid: {0}")]
     DeduplicationSmartModuleNameInvalid(String),
+
+    // Consumer groups
+    #[fluvio(tag = 11000)]
+    #[error("the consumer group name is invalid: {0}")]
+    ConsumerGroupInvalidName(String),
+    #[fluvio(tag = 11001)]
+    #[error("the consumer group member is unknown: {0}")]
+    ConsumerGroupUnknownMember(String),
+    #[fluvio(tag = 11002)]
+    #[error("the consumer group is consuming a different topic: {0}")]
+    ConsumerGroupTopicMismatch(String),
+    #[fluvio(tag = 11003)]
+    #[error("the consumer group has live members: {0}")]
+    ConsumerGroupNotEmpty(String),
+
+    // Idempotent producer
+    #[fluvio(tag = 12000)]
+    #[error("the producer sequence number is out of order")]
+    OutOfOrderSequenceNumber,
+    #[fluvio(tag = 12001)]
+    #[error("the producer epoch is older than the current epoch of the producer")]
+    InvalidProducerEpoch,
+    #[fluvio(tag = 12002)]
+    #[error("the producer epoch is exhausted, producer must be recreated")]
+    ProducerEpochExhausted,
+    #[fluvio(tag = 12003)]
+    #[error("the batch is already written, but its offset is no longer tracked")]
+    DuplicateSequenceNumber,
+
+    // Transactions
+    #[fluvio(tag = 12100)]
+    #[error("the transaction is in invalid state: {0}")]
+    InvalidTransactionState(String),
+    #[fluvio(tag = 12101)]
+    #[error("failed to write transaction markers: {0}")]
+    TransactionMarkersFailed(String),
+
+    // Quota Errors
+    #[fluvio(tag = 13000)]
+    #[error("a quota error occurred")]
+    QuotaError,
+    #[fluvio(tag = 13001)]
+    #[error("the quota was not found")]
+    QuotaNotFound,
+    #[fluvio(tag = 13002)]
+    #[error("the quota already exists")]
+    QuotaAlreadyExists,
+    #[fluvio(tag = 13003)]
+    #[error("the quota is invalid: {0}")]
+    QuotaInvalid(String),
 }
 
 impl ErrorCode {
@@ -289,6 +370,13 @@ mod test {
 
         // Stream Fetch error
         assert_tag!(ErrorCode::FetchSessionNotFoud, 3002, 0);
+
+        // Replication errors
+        assert_tag!(
+            ErrorCode::NotEnoughInSyncReplicas { in_sync: 1, min: 2 },
+            3200,
+            0
+        );
     }
 
     #[test]
@@ -20,6 +20,9 @@ use super::Offset;
 use super::Size;
 
 const ATTR_SCHEMA_PRESENT: i16 = 0x10;
+const ATTR_TRANSACTIONAL: i16 = 0x20;
+const ATTR_CONTROL: i16 = 0x40;
+const ATTR_RECORD_HEADERS: i16 = 0x80;
 const ATTR_COMPRESSION_CODEC_MASK: i16 = 0x07;
 pub const NO_TIMESTAMP: i64 = -1;
 
@@ -212,7 +215,10 @@ impl TryFrom<Batch<RawRecords>> for Batch {
 #[cfg(feature = "compress")]
 impl TryFrom<Batch> for Batch<RawRecords> {
     type Error = CompressionError;
-    fn try_from(f: Batch) -> Result<Self, Self::Error> {
+    fn try_from(mut f: Batch) -> Result<Self, Self::Error> {
+        if f.records.iter().any(|record| !record.headers.is_empty()) {
+            f.header.set_record_headers();
+        }
         let mut buf = Vec::new();
         f.records.encode(&mut buf, 0)?;
 
@@ -291,6 +297,23 @@ impl Batch {
         self.header.last_offset_delta = self.records().len() as i32 - 1;
     }
 
+    /// remove headers of all records, for peers which don't support record headers.
+    /// return true if any record had headers
+    pub fn clear_record_headers(&mut self) -> bool {
+        let mut cleared = false;
+        for record in self.records.iter_mut() {
+            if !record.headers.is_empty() {
+                record.headers.clear();
+                cleared = true;
+            }
+        }
+        if cleared {
+            self.batch_len = self.calc_batch_len();
+        }
+        self.header.clear_record_headers();
+        cleared
+    }
+
     pub fn into_consumer_records_iter(
         self,
         partition: PartitionId,
@@ -298,15 +321,13 @@ impl Batch {
         let base_offset = self.base_offset;
         let first_timestamp = self.header.first_timestamp;
 
-        self.records
-            .into_iter()
-            .enumerate()
-            .map(move |(relative, record)| ConsumerRecord {
-                partition,
-                offset: base_offset + relative as Offset,
-                timestamp_base: first_timestamp,
-                record,
-            })
+        // use offset delta of record since compacted batches may have gaps
+        self.records.into_iter().map(move |record| ConsumerRecord {
+            partition,
+            offset: base_offset + record.offset_delta(),
+            timestamp_base: first_timestamp,
+            record,
+        })
     }
 }
 
@@ -478,6 +499,38 @@ impl BatchHeader {
     pub fn set_schema_id(&mut self) {
         self.attributes |= ATTR_SCHEMA_PRESENT;
     }
+
+    /// batch is written by transactional producer
+    pub fn is_transactional(&self) -> bool {
+        self.attributes & ATTR_TRANSACTIONAL != 0
+    }
+
+    pub fn set_transactional(&mut self) {
+        self.attributes |= ATTR_TRANSACTIONAL;
+    }
+
+    /// batch contains control record which ends transaction
+    pub fn is_control(&self) -> bool {
+        self.attributes & ATTR_CONTROL != 0
+    }
+
+    pub fn set_control(&mut self) {
+        self.attributes |= ATTR_CONTROL;
+    }
+
+    /// records of batch may have headers, batches without the flag are
+    /// sent as is to peers which don't support record headers
+    pub fn has_record_headers(&self) -> bool {
+        self.attributes & ATTR_RECORD_HEADERS != 0
+    }
+
+    pub fn set_record_headers(&mut self) {
+        self.attributes |= ATTR_RECORD_HEADERS;
+    }
+
+    pub fn clear_record_headers(&mut self) {
+        self.attributes &= !ATTR_RECORD_HEADERS;
+    }
 }
 impl Default for BatchHeader {
     fn default() -> Self {
@@ -816,6 +869,42 @@ mod test {
         assert_eq!(batch_created.last_offset_delta(), 2);
     }
 
+    #[test]
+    fn test_clear_record_headers() {
+        let mut record = Record::new("value");
+        record.add_header(("trace-id", "abc"));
+        let mut batch = Batch::from(vec![record, Record::new("other")]);
+        let batch_len = batch.batch_len;
+
+        assert!(batch.clear_record_headers());
+        assert!(batch
+            .records
+            .iter()
+            .all(|record| record.headers().is_empty()));
+        assert!(batch.batch_len < batch_len);
+        assert!(batch.validate_decoding());
+        assert!(!batch.clear_record_headers());
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn test_record_headers_attribute() {
+        let raw_batch = Batch::<RawRecords>::try_from(Batch::from(vec![Record::new("value")]))
+            .expect("raw batch");
+        assert!(!raw_batch.header.has_record_headers());
+
+        let mut record = Record::new("value");
+        record.add_header(("trace-id", "abc"));
+        let raw_batch =
+            Batch::<RawRecords>::try_from(Batch::from(vec![record])).expect("raw batch");
+        assert!(raw_batch.header.has_record_headers());
+
+        let mut batch = Batch::try_from(raw_batch).expect("memory batch");
+        assert!(batch.header.has_record_headers());
+        batch.clear_record_headers();
+        assert!(!batch.header.has_record_headers());
+    }
+
     #[test]
     fn test_into_consumer_records_iter() {
         let mut batch = Batch::from(vec![
@@ -0,0 +1,102 @@
+//!
+//! # Control Records
+//!
+//! Transaction coordinator writes control batch to every partition touched by the transaction
+//! when the transaction is committed or aborted. Control batch contains single record,
+//! its key holds version and type of the control record.
+//!
+
+use super::{Batch, Record, RecordData};
+
+const CONTROL_RECORD_VERSION: i16 = 0;
+const CONTROL_KEY_SIZE: usize = 4;
+
+/// Marker which ends the transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ControlRecordType {
+    Abort,
+    Commit,
+}
+
+impl ControlRecordType {
+    fn to_key(self) -> Vec<u8> {
+        let control_type: i16 = match self {
+            Self::Abort => 0,
+            Self::Commit => 1,
+        };
+        let mut key = Vec::with_capacity(CONTROL_KEY_SIZE);
+        key.extend_from_slice(&CONTROL_RECORD_VERSION.to_be_bytes());
+        key.extend_from_slice(&control_type.to_be_bytes());
+        key
+    }
+
+    /// decode type from key of control record
+    pub fn from_key(key: &[u8]) -> Option<Self> {
+        if key.len() != CONTROL_KEY_SIZE {
+            return None;
+        }
+        match i16::from_be_bytes([key[2], key[3]]) {
+            0 => Some(Self::Abort),
+            1 => Some(Self::Commit),
+            _ => None,
+        }
+    }
+}
+
+impl Batch {
+    /// control batch ending transaction of the producer
+    pub fn control(producer_id: i64, producer_epoch: i16, control_type: ControlRecordType) -> Self {
+        let mut batch = Batch::default();
+        batch.add_record(Record::new_key_value(
+            control_type.to_key(),
+            RecordData::from(Vec::<u8>::new()),
+        ));
+        let header = batch.get_mut_header();
+        header.producer_id = producer_id;
+        header.producer_epoch = producer_epoch;
+        header.set_transactional();
+        header.set_control();
+        batch
+    }
+
+    /// type of control record if this is control batch
+    pub fn control_type(&self) -> Option<ControlRecordType> {
+        if !self.get_header().is_control() {
+            return None;
+        }
+        self.records()
+            .first()
+            .and_then(|record| record.key())
+            .and_then(|key| ControlRecordType::from_key(key.as_ref()))
+    }
+}
+
+impl<R> Batch<R> {
+    /// batch is hidden from consumers, it only marks end of transaction
+    pub fn is_control(&self) -> bool {
+        self.get_header().is_control()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_control_batch() {
+        let batch = Batch::control(10, 2, ControlRecordType::Commit);
+        assert!(batch.is_control());
+        assert!(batch.get_header().is_transactional());
+        assert_eq!(batch.get_header().producer_id, 10);
+        assert_eq!(batch.get_header().producer_epoch, 2);
+        assert_eq!(batch.control_type(), Some(ControlRecordType::Commit));
+
+        let abort = Batch::control(10, 2, ControlRecordType::Abort);
+        assert_eq!(abort.control_type(), Some(ControlRecordType::Abort));
+
+        let mut data = Batch::default();
+        data.add_record(Record::new("value"));
+        assert!(!data.is_control());
+        assert_eq!(data.control_type(), None);
+    }
+}
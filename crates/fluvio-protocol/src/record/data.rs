@@ -409,12 +409,96 @@ impl RecordHeader {
     }
 }
 
+/// Header marking record as tombstone, it deletes the record key from compacted topics.
+/// Record value can't be null in the record encoding and empty value is a valid payload,
+/// so tombstones are marked by this header instead of null value.
+pub const TOMBSTONE_HEADER: &str = "tombstone";
+
+/// Key/value metadata attached to a [`Record`], such as trace ids or content types.
+///
+/// Headers are kept apart from the record key and value, so they can be inspected
+/// without decoding the payload.
+///
+/// # Examples
+///
+/// ```
+/// # use fluvio_protocol::record::{Header, Record};
+/// let mut record = Record::new("payload");
+/// record.add_header(Header::new("content-type", "application/json"));
+/// assert_eq!(record.header("content-type").map(|value| value.as_ref()), Some("application/json".as_bytes()));
+/// ```
+#[derive(Clone, Default, Debug, Eq, PartialEq, Hash)]
+pub struct Header {
+    pub key: String,
+    pub value: RecordData,
+}
+
+impl Header {
+    pub fn new(key: impl Into<String>, value: impl Into<RecordData>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+impl<K, V> From<(K, V)> for Header
+where
+    K: Into<String>,
+    V: Into<RecordData>,
+{
+    fn from((key, value): (K, V)) -> Self {
+        Self::new(key, value)
+    }
+}
+
+impl Encoder for Header {
+    fn write_size(&self, version: Version) -> usize {
+        let key_len = self.key.len() as i64;
+        key_len.var_write_size() + self.key.len() + self.value.write_size(version)
+    }
+
+    fn encode<T>(&self, dest: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: BufMut,
+    {
+        let key_len = self.key.len() as i64;
+        key_len.encode_varint(dest)?;
+        dest.put_slice(self.key.as_bytes());
+        self.value.encode(dest, version)
+    }
+}
+
+impl Decoder for Header {
+    fn decode<T>(&mut self, src: &mut T, version: Version) -> Result<(), Error>
+    where
+        T: Buf,
+    {
+        let mut key_len: i64 = 0;
+        key_len.decode_varint(src)?;
+        if key_len < 0 || src.remaining() < key_len as usize {
+            return Err(Error::new(
+                ErrorKind::UnexpectedEof,
+                "not enough bytes for header key",
+            ));
+        }
+        let mut key = vec![0; key_len as usize];
+        src.copy_to_slice(&mut key);
+        self.key = String::from_utf8(key).map_err(|err| {
+            Error::new(ErrorKind::InvalidData, format!("invalid header key: {err}"))
+        })?;
+        self.value.decode(src, version)
+    }
+}
+
 #[derive(Default, Clone)]
 pub struct Record<B = RecordData> {
     pub preamble: RecordHeader,
     pub key: Option<B>,
     pub value: B,
-    pub headers: i64,
+    /// Headers are encoded as a count followed by the entries.
+    /// Records without headers are encoded the same way as before headers were supported.
+    pub headers: Vec<Header>,
 }
 
 impl<B: Default> Record<B> {
@@ -452,6 +536,29 @@ impl<B: Default> Record<B> {
     pub fn into_key(self) -> Option<B> {
         self.key
     }
+
+    /// Returns the headers of this record
+    pub fn headers(&self) -> &[Header] {
+        &self.headers
+    }
+
+    /// Returns the value of the first header with the given key
+    pub fn header(&self, key: &str) -> Option<&RecordData> {
+        self.headers
+            .iter()
+            .find(|header| header.key == key)
+            .map(|header| &header.value)
+    }
+
+    /// Appends a header, keys don't have to be unique
+    pub fn add_header(&mut self, header: impl Into<Header>) {
+        self.headers.push(header.into());
+    }
+
+    /// Returns true if this record deletes its key from compacted topics
+    pub fn is_tombstone(&self) -> bool {
+        self.header(TOMBSTONE_HEADER).is_some()
+    }
 }
 
 impl Record {
@@ -471,6 +578,13 @@ impl Record {
         }
     }
 
+    /// Creates tombstone record which deletes `key` from compacted topics
+    pub fn tombstone(key: impl Into<RecordKey>) -> Self {
+        let mut record = Self::new_key_value(key, Vec::new());
+        record.add_header(Header::new(TOMBSTONE_HEADER, Vec::new()));
+        record
+    }
+
     pub fn timestamp_delta(&self) -> Timestamp {
         self.preamble.timestamp_delta
     }
@@ -509,7 +623,12 @@ where
         let inner_size = self.preamble.write_size(version)
             + self.key.write_size(version)
             + self.value.write_size(version)
-            + self.headers.var_write_size();
+            + (self.headers.len() as i64).var_write_size()
+            + self
+                .headers
+                .iter()
+                .map(|header| header.write_size(version))
+                .sum::<usize>();
         let len: i64 = inner_size as i64;
         len.var_write_size() + inner_size
     }
@@ -522,7 +641,10 @@ where
         self.preamble.encode(&mut out, version)?;
         self.key.encode(&mut out, version)?;
         self.value.encode(&mut out, version)?;
-        self.headers.encode_varint(&mut out)?;
+        (self.headers.len() as i64).encode_varint(&mut out)?;
+        for header in &self.headers {
+            header.encode(&mut out, version)?;
+        }
         let len: i64 = out.len() as i64;
         trace!("record encode as {} bytes", len);
         len.encode_varint(dest)?;
@@ -555,7 +677,14 @@ where
         trace!("offset delta: {}", self.preamble.offset_delta);
         self.key.decode(src, version)?;
         self.value.decode(src, version)?;
-        self.headers.decode_varint(src)?;
+        let mut header_count: i64 = 0;
+        header_count.decode_varint(src)?;
+        self.headers.clear();
+        for _ in 0..header_count {
+            let mut header = Header::default();
+            header.decode(src, version)?;
+            self.headers.push(header);
+        }
 
         Ok(())
     }
@@ -615,6 +744,21 @@ impl ConsumerRecord {
         self.inner().value().as_ref()
     }
 
+    /// Returns the headers of this Record
+    pub fn headers(&self) -> &[Header] {
+        self.inner().headers()
+    }
+
+    /// Returns the value of the first header with the given key
+    pub fn header(&self, key: &str) -> Option<&RecordData> {
+        self.inner().header(key)
+    }
+
+    /// Returns true if this Record deletes its key from compacted topics
+    pub fn is_tombstone(&self) -> bool {
+        self.inner().is_tombstone()
+    }
+
     /// Return the timestamp of the Record
     pub fn timestamp(&self) -> Timestamp {
         if self.timestamp_base <= 0 {
@@ -764,6 +908,37 @@ mod test {
         assert!(decoded.key.is_none());
     }
 
+    #[test]
+    fn test_record_headers_encoding() {
+        let mut record = Record::new_key_value("key", "value");
+        record.add_header(("trace-id", "abc"));
+        record.add_header(Header::new("content-type", "text/plain"));
+
+        let mut encoded = Vec::new();
+        record.encode(&mut encoded, 0).unwrap();
+        assert_eq!(encoded.len(), record.write_size(0));
+
+        let decoded = Record::<RecordData>::decode_from(&mut Cursor::new(encoded), 0).unwrap();
+        assert_eq!(decoded.headers(), record.headers());
+        assert_eq!(
+            decoded.header("trace-id").map(|value| value.as_ref()),
+            Some("abc".as_bytes())
+        );
+        assert!(decoded.header("unknown").is_none());
+        assert_eq!(decoded.value.as_ref(), record.value.as_ref());
+    }
+
+    #[test]
+    fn test_tombstone_record() {
+        let record = Record::tombstone("key");
+        assert!(record.is_tombstone());
+        assert_eq!(record.key().map(|key| key.as_ref()), Some("key".as_bytes()));
+        assert!(record.value().as_ref().is_empty());
+
+        // empty value alone doesn't delete the key
+        assert!(!Record::new_key_value("key", "").is_tombstone());
+    }
+
     #[test]
     fn test_consumer_record_no_timestamp() {
         let record = ConsumerRecord {
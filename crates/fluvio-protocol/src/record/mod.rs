@@ -5,8 +5,10 @@ mod data;
 pub use self::data::*;
 
 mod batch;
+mod control;
 mod replica;
 pub use batch::*;
+pub use control::*;
 pub use replica::*;
 
 pub type Offset = i64;
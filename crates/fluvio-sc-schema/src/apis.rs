@@ -22,6 +22,23 @@ pub enum AdminPublicApiKey {
     Delete = 1002,
     List = 1003,
     Watch = 1004,
+    Update = 1005,
+
+    // Consumer groups
+    ConsumerGroupHeartbeat = 1101,
+    LeaveConsumerGroup = 1102,
+    FetchConsumerGroupOffsets = 1103,
+    ResetConsumerGroupOffsets = 1104,
+
+    // Idempotent and transactional producer
+    InitProducerId = 1201,
+    AddPartitionsToTxn = 1202,
+    AddOffsetsToTxn = 1203,
+    EndTxn = 1204,
+
+    // Partition reassignment
+    ReassignPartitions = 1301,
+    ElectPreferredLeader = 1302,
 }
 
 impl Default for AdminPublicApiKey {
@@ -0,0 +1,178 @@
+//!
+//! # Consumer Group Membership
+//!
+//! Consumers of the same group send heartbeats to the SC. SC tracks live members
+//! and assigns partitions of the topic across them.
+//!
+//! Offsets committed by the group are stored by partition leaders. SC fetches and resets
+//! them for all partitions of the topic at once.
+//!
+
+use std::fmt;
+
+use fluvio_protocol::api::Request;
+use fluvio_protocol::{Encoder, Decoder};
+use fluvio_protocol::record::Offset;
+use fluvio_types::PartitionId;
+
+use crate::errors::ErrorCode;
+use crate::AdminPublicApiKey;
+
+/// Default time after which member without heartbeat is removed from the group
+pub const DEFAULT_SESSION_TIMEOUT_MS: u32 = 10000;
+
+/// How partitions of the topic are distributed across members of the group
+#[derive(Encoder, Decoder, Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum AssignmentStrategy {
+    /// contiguous range of partitions per member
+    #[default]
+    #[fluvio(tag = 0)]
+    Range,
+    /// partitions are dealt to members one by one
+    #[fluvio(tag = 1)]
+    RoundRobin,
+}
+
+impl fmt::Display for AssignmentStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Range => write!(f, "range"),
+            Self::RoundRobin => write!(f, "round-robin"),
+        }
+    }
+}
+
+/// Join consumer group or keep membership alive.
+/// Member joins the group by sending empty `member_id`, SC assigns new id in the response.
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct ConsumerGroupHeartbeatRequest {
+    pub group: String,
+    pub topic: String,
+    pub member_id: String,
+    /// last generation whose partitions member is consuming.
+    /// partitions revoked from the member are assigned to other members once it has caught up
+    pub generation: u32,
+    pub strategy: AssignmentStrategy,
+    pub session_timeout_ms: u32,
+}
+
+impl Request for ConsumerGroupHeartbeatRequest {
+    const API_KEY: u16 = AdminPublicApiKey::ConsumerGroupHeartbeat as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = ConsumerGroupHeartbeatResponse;
+}
+
+impl ConsumerGroupHeartbeatRequest {
+    pub fn new(group: impl Into<String>, topic: impl Into<String>) -> Self {
+        Self {
+            group: group.into(),
+            topic: topic.into(),
+            session_timeout_ms: DEFAULT_SESSION_TIMEOUT_MS,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct ConsumerGroupHeartbeatResponse {
+    pub error_code: ErrorCode,
+    pub member_id: String,
+    /// incremented every time partitions are reassigned
+    pub generation: u32,
+    /// partitions assigned to the member
+    pub partitions: Vec<PartitionId>,
+}
+
+/// Leave consumer group, partitions are reassigned to remaining members
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct LeaveConsumerGroupRequest {
+    pub group: String,
+    pub member_id: String,
+}
+
+impl Request for LeaveConsumerGroupRequest {
+    const API_KEY: u16 = AdminPublicApiKey::LeaveConsumerGroup as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = LeaveConsumerGroupResponse;
+}
+
+impl LeaveConsumerGroupRequest {
+    pub fn new(group: impl Into<String>, member_id: impl Into<String>) -> Self {
+        Self {
+            group: group.into(),
+            member_id: member_id.into(),
+        }
+    }
+}
+
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct LeaveConsumerGroupResponse {
+    pub error_code: ErrorCode,
+}
+
+/// Fetch offsets committed by consumer group for all partitions of the topic
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct FetchConsumerGroupOffsetsRequest {
+    pub group: String,
+    pub topic: String,
+}
+
+impl Request for FetchConsumerGroupOffsetsRequest {
+    const API_KEY: u16 = AdminPublicApiKey::FetchConsumerGroupOffsets as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = FetchConsumerGroupOffsetsResponse;
+}
+
+impl FetchConsumerGroupOffsetsRequest {
+    pub fn new(group: impl Into<String>, topic: impl Into<String>) -> Self {
+        Self {
+            group: group.into(),
+            topic: topic.into(),
+        }
+    }
+}
+
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct FetchConsumerGroupOffsetsResponse {
+    pub error_code: ErrorCode,
+    pub partitions: Vec<ConsumerGroupOffset>,
+}
+
+/// Remove offsets committed by consumer group for all partitions of the topic.
+/// Group must not have live members, consumers of the group start from their own
+/// starting offset afterwards.
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct ResetConsumerGroupOffsetsRequest {
+    pub group: String,
+    pub topic: String,
+}
+
+impl Request for ResetConsumerGroupOffsetsRequest {
+    const API_KEY: u16 = AdminPublicApiKey::ResetConsumerGroupOffsets as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = ResetConsumerGroupOffsetsResponse;
+}
+
+impl ResetConsumerGroupOffsetsRequest {
+    pub fn new(group: impl Into<String>, topic: impl Into<String>) -> Self {
+        Self {
+            group: group.into(),
+            topic: topic.into(),
+        }
+    }
+}
+
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct ResetConsumerGroupOffsetsResponse {
+    pub error_code: ErrorCode,
+    pub partitions: Vec<ConsumerGroupOffset>,
+}
+
+/// Offset of consumer group for a partition, or error of its leader
+#[derive(Encoder, Decoder, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConsumerGroupOffset {
+    pub partition: PartitionId,
+    pub error_code: ErrorCode,
+    /// committed offset, None if the group has no offset or it was reset
+    pub offset: Option<Offset>,
+}
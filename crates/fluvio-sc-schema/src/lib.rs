@@ -8,6 +8,10 @@ pub mod versions;
 pub mod objects;
 pub mod shared;
 pub mod tableformat;
+pub mod quota;
+pub mod consumer_group;
+pub mod producer;
+pub mod transaction;
 
 pub mod edge;
 
@@ -69,6 +73,12 @@ mod error {
                 ApiError::Code(ErrorCode::TableFormatNotFound, _) => {
                     write!(f, "TableFormat not found")
                 }
+                ApiError::Code(ErrorCode::QuotaAlreadyExists, _) => {
+                    write!(f, "Quota already exists")
+                }
+                ApiError::Code(ErrorCode::QuotaNotFound, _) => {
+                    write!(f, "Quota not found")
+                }
                 ApiError::Code(_, Some(msg)) => {
                     write!(f, "{msg}")
                 }
@@ -123,6 +133,12 @@ mod admin {
         type DeleteKey: Encoder + Decoder + Debug + Default;
     }
 
+    /// Existing object which can be changed by actions it defines
+    pub trait UpdatableAdminSpec: Spec + Encoder + Decoder {
+        type UpdateKey: Encoder + Decoder + Debug + Default;
+        type UpdateAction: Encoder + Decoder + Debug + Default + Clone;
+    }
+
     /// try to encode type object into dynamic type which can be downcast later
     pub trait TryEncodableFrom<T>: Sized + Encoder + Decoder {
         fn try_encode_from(value: T, version: Version) -> Result<Self>;
@@ -426,6 +426,7 @@ mod create {
     use crate::smartmodule::SmartModuleSpec;
     use crate::tableformat::TableFormatSpec;
     use crate::spg::SpuGroupSpec;
+    use crate::quota::QuotaSpec;
 
     #[derive(Debug, Default, Encoder, Decoder)]
     pub struct ClassicObjectApiCreateRequest {
@@ -653,4 +654,6 @@ mod create {
             }
         }
     }
+    // quotas are created only by dynamic object requests
+    impl ClassicCreatableAdminSpec for QuotaSpec {}
 }
@@ -1,5 +1,6 @@
 mod create;
 mod delete;
+mod update;
 mod list;
 mod watch;
 mod metadata;
@@ -9,11 +10,12 @@ pub mod classic;
 
 pub use create::*;
 pub use delete::*;
+pub use update::*;
 pub use list::*;
 pub use watch::*;
 pub use metadata::*;
 
-pub(crate) const COMMON_VERSION: i16 = 12; // from now, we use a single version for all objects
+pub(crate) const COMMON_VERSION: i16 = 16; // from now, we use a single version for all objects
 pub(crate) const DYN_OBJ: i16 = 11; // version indicate dynamic object
 
 #[cfg(test)]
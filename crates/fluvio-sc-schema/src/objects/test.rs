@@ -12,10 +12,11 @@ use crate::objects::{
     ClassicObjectApiListRequest, DYN_OBJ,
 };
 
-use crate::topic::TopicSpec;
+use crate::topic::{TopicSpec, UpdateTopicAction, AddPartitionConfig};
 use crate::customspu::CustomSpuSpec;
 
 use super::{ListRequest, ObjectApiListRequest, WatchResponse, ObjectApiWatchResponse, COMMON_VERSION};
+use super::{UpdateRequest, ObjectApiUpdateRequest};
 
 #[test]
 fn test_encoding_compatibility() {
@@ -59,6 +60,35 @@ fn test_req_encoding_decoding() {
     assert!(downcast.is_some());
 }
 
+#[test]
+fn test_update_req_encoding_decoding() {
+    let raw_req: UpdateRequest<TopicSpec> = UpdateRequest::new(
+        "test".to_owned(),
+        UpdateTopicAction::AddPartition(AddPartitionConfig { count: 2 }),
+    );
+
+    let test_request =
+        ObjectApiUpdateRequest::try_encode_from(raw_req, COMMON_VERSION).expect("encoded");
+    let mut dest = vec![];
+    test_request
+        .encode(&mut dest, COMMON_VERSION)
+        .expect("encoding");
+
+    let recovered_request =
+        ObjectApiUpdateRequest::decode_from(&mut Cursor::new(dest), COMMON_VERSION)
+            .expect("decode");
+
+    let downcast = recovered_request
+        .downcast()
+        .expect("downcast")
+        .expect("topic") as UpdateRequest<TopicSpec>;
+    assert_eq!(downcast.key(), "test");
+    assert_eq!(
+        downcast.action(),
+        &UpdateTopicAction::AddPartition(AddPartitionConfig { count: 2 })
+    );
+}
+
 #[test]
 fn test_req_old_to_new() {
     let raw_req: ListRequest<TopicSpec> = ListRequest::new(vec![], false);
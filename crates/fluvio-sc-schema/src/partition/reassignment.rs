@@ -0,0 +1,82 @@
+//!
+//! # Partition Reassignment
+//!
+//! Move replicas of existing partitions to other SPUs and move leaders back to preferred replicas.
+//! SC adds new replicas first, switches leader once they are in sync, then drops old replicas.
+//!
+
+use fluvio_protocol::api::Request;
+use fluvio_protocol::record::ReplicaKey;
+use fluvio_protocol::{Encoder, Decoder};
+use fluvio_types::SpuId;
+
+use crate::errors::ErrorCode;
+use crate::AdminPublicApiKey;
+
+/// Replicas of partition, first replica is preferred leader
+#[derive(Encoder, Decoder, Debug, Default, Clone, PartialEq, Eq)]
+pub struct PartitionAssignment {
+    pub replica: ReplicaKey,
+    pub replicas: Vec<SpuId>,
+}
+
+impl PartitionAssignment {
+    pub fn new(replica: impl Into<ReplicaKey>, replicas: Vec<SpuId>) -> Self {
+        Self {
+            replica: replica.into(),
+            replicas,
+        }
+    }
+}
+
+/// Reassign replicas of partitions.
+/// Assignments of partitions of `balance_topics` are generated by SC from online SPUs.
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct ReassignPartitionsRequest {
+    pub assignments: Vec<PartitionAssignment>,
+    pub balance_topics: Vec<String>,
+    /// validate and return assignments without starting reassignment
+    pub dry_run: bool,
+}
+
+impl Request for ReassignPartitionsRequest {
+    const API_KEY: u16 = AdminPublicApiKey::ReassignPartitions as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = ReassignPartitionsResponse;
+}
+
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct ReassignPartitionsResponse {
+    pub results: Vec<ReassignmentResult>,
+}
+
+#[derive(Encoder, Decoder, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReassignmentResult {
+    pub assignment: PartitionAssignment,
+    pub error_code: ErrorCode,
+}
+
+/// Move leaders of partitions to their preferred replica, which is first replica of partition.
+/// All partitions are elected if `partitions` is empty.
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct ElectPreferredLeaderRequest {
+    pub partitions: Vec<ReplicaKey>,
+}
+
+impl Request for ElectPreferredLeaderRequest {
+    const API_KEY: u16 = AdminPublicApiKey::ElectPreferredLeader as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = ElectPreferredLeaderResponse;
+}
+
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct ElectPreferredLeaderResponse {
+    pub results: Vec<ElectionResult>,
+}
+
+#[derive(Encoder, Decoder, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ElectionResult {
+    pub replica: ReplicaKey,
+    pub leader: SpuId,
+    pub error_code: ErrorCode,
+}
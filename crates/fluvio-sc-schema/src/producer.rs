@@ -0,0 +1,46 @@
+//!
+//! # Producer Id
+//!
+//! Idempotent producers obtain a producer id from the SC. SPU leaders use the id
+//! together with the epoch and sequence numbers of batches to detect duplicates.
+//!
+
+use fluvio_protocol::api::Request;
+use fluvio_protocol::{Encoder, Decoder};
+
+use crate::errors::ErrorCode;
+use crate::AdminPublicApiKey;
+
+/// Producer id of batches which are not sent by an idempotent producer
+pub const NO_PRODUCER_ID: i64 = -1;
+
+/// Allocate new producer id.
+/// Transactional producer gets the same id for the same `transactional_id` with incremented epoch,
+/// so previous instances of the producer are fenced and their open transaction is aborted.
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct InitProducerIdRequest {
+    pub transactional_id: Option<String>,
+}
+
+impl Request for InitProducerIdRequest {
+    const API_KEY: u16 = AdminPublicApiKey::InitProducerId as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = InitProducerIdResponse;
+}
+
+#[derive(Encoder, Decoder, Debug)]
+pub struct InitProducerIdResponse {
+    pub error_code: ErrorCode,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+}
+
+impl Default for InitProducerIdResponse {
+    fn default() -> Self {
+        Self {
+            error_code: ErrorCode::None,
+            producer_id: NO_PRODUCER_ID,
+            producer_epoch: 0,
+        }
+    }
+}
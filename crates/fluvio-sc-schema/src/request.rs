@@ -17,8 +17,16 @@ use fluvio_protocol::core::{Decoder};
 use fluvio_protocol::link::versions::ApiVersionsRequest;
 
 use crate::AdminPublicApiKey;
+use crate::consumer_group::{
+    ConsumerGroupHeartbeatRequest, FetchConsumerGroupOffsetsRequest, LeaveConsumerGroupRequest,
+    ResetConsumerGroupOffsetsRequest,
+};
+use crate::producer::InitProducerIdRequest;
+use crate::transaction::{AddOffsetsToTxnRequest, AddPartitionsToTxnRequest, EndTxnRequest};
+use crate::partition::{ElectPreferredLeaderRequest, ReassignPartitionsRequest};
 use crate::objects::{
-    ObjectApiCreateRequest, ObjectApiDeleteRequest, ObjectApiListRequest, ObjectApiWatchRequest,
+    ObjectApiCreateRequest, ObjectApiDeleteRequest, ObjectApiListRequest, ObjectApiUpdateRequest,
+    ObjectApiWatchRequest,
 };
 
 /// Non generic AdminRequest, This is typically used Decoding
@@ -29,6 +37,17 @@ pub enum AdminPublicDecodedRequest {
     DeleteRequest(RequestMessage<ObjectApiDeleteRequest>),
     ListRequest(RequestMessage<ObjectApiListRequest>),
     WatchRequest(RequestMessage<ObjectApiWatchRequest>),
+    UpdateRequest(RequestMessage<ObjectApiUpdateRequest>),
+    ConsumerGroupHeartbeatRequest(RequestMessage<ConsumerGroupHeartbeatRequest>),
+    LeaveConsumerGroupRequest(RequestMessage<LeaveConsumerGroupRequest>),
+    FetchConsumerGroupOffsetsRequest(RequestMessage<FetchConsumerGroupOffsetsRequest>),
+    ResetConsumerGroupOffsetsRequest(RequestMessage<ResetConsumerGroupOffsetsRequest>),
+    InitProducerIdRequest(RequestMessage<InitProducerIdRequest>),
+    AddPartitionsToTxnRequest(RequestMessage<AddPartitionsToTxnRequest>),
+    AddOffsetsToTxnRequest(RequestMessage<AddOffsetsToTxnRequest>),
+    EndTxnRequest(RequestMessage<EndTxnRequest>),
+    ReassignPartitionsRequest(RequestMessage<ReassignPartitionsRequest>),
+    ElectPreferredLeaderRequest(RequestMessage<ElectPreferredLeaderRequest>),
 }
 
 impl Default for AdminPublicDecodedRequest {
@@ -81,6 +100,40 @@ impl ApiMessage for AdminPublicDecodedRequest {
                 header,
                 ObjectApiWatchRequest::decode_from(src, version)?,
             ))),
+
+            AdminPublicApiKey::Update => Ok(Self::UpdateRequest(RequestMessage::new(
+                header,
+                ObjectApiUpdateRequest::decode_from(src, version)?,
+            ))),
+
+            AdminPublicApiKey::ConsumerGroupHeartbeat => {
+                api_decode!(Self, ConsumerGroupHeartbeatRequest, src, header)
+            }
+            AdminPublicApiKey::LeaveConsumerGroup => {
+                api_decode!(Self, LeaveConsumerGroupRequest, src, header)
+            }
+            AdminPublicApiKey::FetchConsumerGroupOffsets => {
+                api_decode!(Self, FetchConsumerGroupOffsetsRequest, src, header)
+            }
+            AdminPublicApiKey::ResetConsumerGroupOffsets => {
+                api_decode!(Self, ResetConsumerGroupOffsetsRequest, src, header)
+            }
+            AdminPublicApiKey::InitProducerId => {
+                api_decode!(Self, InitProducerIdRequest, src, header)
+            }
+            AdminPublicApiKey::AddPartitionsToTxn => {
+                api_decode!(Self, AddPartitionsToTxnRequest, src, header)
+            }
+            AdminPublicApiKey::AddOffsetsToTxn => {
+                api_decode!(Self, AddOffsetsToTxnRequest, src, header)
+            }
+            AdminPublicApiKey::EndTxn => api_decode!(Self, EndTxnRequest, src, header),
+            AdminPublicApiKey::ReassignPartitions => {
+                api_decode!(Self, ReassignPartitionsRequest, src, header)
+            }
+            AdminPublicApiKey::ElectPreferredLeader => {
+                api_decode!(Self, ElectPreferredLeaderRequest, src, header)
+            }
         }
     }
 }
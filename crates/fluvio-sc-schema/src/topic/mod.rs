@@ -1,4 +1,5 @@
 pub use fluvio_controlplane_metadata::topic::*;
+pub use update::*;
 
 pub mod validate {
     use crate::shared::validate_resource_name;
@@ -11,13 +12,53 @@ pub mod validate {
     }
 }
 
+mod update {
+
+    use fluvio_protocol::{Encoder, Decoder};
+    use fluvio_types::PartitionCount;
+
+    use super::Transform;
+
+    /// Change to be applied to existing topic
+    #[derive(Debug, Clone, PartialEq, Eq, Encoder, Decoder)]
+    pub enum UpdateTopicAction {
+        /// Add partitions to computed topic.
+        /// New partitions are scheduled by SC, existing partitions are not moved.
+        #[fluvio(tag = 0)]
+        AddPartition(AddPartitionConfig),
+        /// Replace SmartModule chain applied to records written to topic.
+        /// Empty chain removes transforms from topic.
+        #[fluvio(tag = 1)]
+        SetTransforms(SetTransformsConfig),
+    }
+
+    impl Default for UpdateTopicAction {
+        fn default() -> Self {
+            Self::AddPartition(AddPartitionConfig::default())
+        }
+    }
+
+    #[derive(Debug, Default, Clone, PartialEq, Eq, Encoder, Decoder)]
+    pub struct AddPartitionConfig {
+        /// number of partitions to add
+        pub count: PartitionCount,
+    }
+
+    #[derive(Debug, Default, Clone, PartialEq, Eq, Encoder, Decoder)]
+    pub struct SetTransformsConfig {
+        /// SmartModules applied in order by partition leader
+        pub transforms: Vec<Transform>,
+    }
+}
+
 mod convert {
 
     use crate::CreatableAdminSpec;
     use crate::DeletableAdminSpec;
+    use crate::UpdatableAdminSpec;
     use crate::AdminSpec;
 
-    use super::TopicSpec;
+    use super::{TopicSpec, UpdateTopicAction};
 
     impl AdminSpec for TopicSpec {}
 
@@ -26,4 +67,9 @@ mod convert {
     impl DeletableAdminSpec for TopicSpec {
         type DeleteKey = String;
     }
+
+    impl UpdatableAdminSpec for TopicSpec {
+        type UpdateKey = String;
+        type UpdateAction = UpdateTopicAction;
+    }
 }
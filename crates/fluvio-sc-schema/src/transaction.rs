@@ -0,0 +1,83 @@
+//!
+//! # Transactions
+//!
+//! Transactional producer registers partitions it writes to and consumer offsets it wants to
+//! commit with the transaction. When the transaction ends, SC writes commit or abort control
+//! batches to all registered partitions.
+//!
+
+use fluvio_protocol::api::Request;
+use fluvio_protocol::record::{Offset, ReplicaKey};
+use fluvio_protocol::{Encoder, Decoder};
+
+use crate::errors::ErrorCode;
+use crate::AdminPublicApiKey;
+
+/// Add partitions to the open transaction, starting it if it's not open yet
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct AddPartitionsToTxnRequest {
+    pub transactional_id: String,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub partitions: Vec<ReplicaKey>,
+}
+
+impl Request for AddPartitionsToTxnRequest {
+    const API_KEY: u16 = AdminPublicApiKey::AddPartitionsToTxn as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = AddPartitionsToTxnResponse;
+}
+
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct AddPartitionsToTxnResponse {
+    pub error_code: ErrorCode,
+}
+
+/// Consumer offset committed together with the transaction
+#[derive(Encoder, Decoder, Debug, Default, Clone, PartialEq, Eq)]
+pub struct TxnConsumerOffset {
+    pub replica: ReplicaKey,
+    pub offset: Offset,
+}
+
+/// Add consumer offsets to the open transaction,
+/// offsets are committed for the consumer group only if the transaction is committed
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct AddOffsetsToTxnRequest {
+    pub transactional_id: String,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub consumer_group: String,
+    pub offsets: Vec<TxnConsumerOffset>,
+}
+
+impl Request for AddOffsetsToTxnRequest {
+    const API_KEY: u16 = AdminPublicApiKey::AddOffsetsToTxn as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = AddOffsetsToTxnResponse;
+}
+
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct AddOffsetsToTxnResponse {
+    pub error_code: ErrorCode,
+}
+
+/// Commit or abort the open transaction
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct EndTxnRequest {
+    pub transactional_id: String,
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub commit: bool,
+}
+
+impl Request for EndTxnRequest {
+    const API_KEY: u16 = AdminPublicApiKey::EndTxn as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = EndTxnResponse;
+}
+
+#[derive(Encoder, Decoder, Debug, Default)]
+pub struct EndTxnResponse {
+    pub error_code: ErrorCode,
+}
@@ -135,7 +135,10 @@ impl ScOpt {
 
         let policy = match self.auth_policy {
             // Lookup a policy from a path
-            Some(p) => Some(BasicRbacPolicy::try_from(p)?),
+            Some(p) => {
+                config.auth_policy_path = Some(p.clone());
+                Some(BasicRbacPolicy::try_from(p)?)
+            }
             // Use root-only default policy if no policy path is found;
             None => None,
         };
@@ -28,6 +28,8 @@ pub struct ScConfig {
     pub private_endpoint: String,
     pub namespace: String,
     pub x509_auth_scopes: Option<PathBuf>,
+    /// policy file, reloaded when changed
+    pub auth_policy_path: Option<PathBuf>,
     pub white_list: HashSet<String>,
 }
 
@@ -39,6 +41,7 @@ impl ::std::default::Default for ScConfig {
             private_endpoint: format!("0.0.0.0:{SC_PRIVATE_PORT}"),
             namespace: DEFAULT_NAMESPACE.to_owned(),
             x509_auth_scopes: None,
+            auth_policy_path: None,
             white_list: HashSet::new(),
         }
     }
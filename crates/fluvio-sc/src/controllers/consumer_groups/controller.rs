@@ -0,0 +1,41 @@
+//!
+//! # Consumer Group Controller
+//!
+//! Removes members of consumer groups which stopped sending heartbeats,
+//! so groups whose all members are gone are dropped and partitions of expired members
+//! are reassigned even if no other member sends a heartbeat soon.
+
+use std::time::{Duration, Instant};
+
+use fluvio_future::timer::sleep;
+use fluvio_stream_model::core::MetadataItem;
+use tracing::{info, instrument};
+
+use fluvio_future::task::spawn;
+
+use crate::core::SharedContext;
+
+/// how often members are checked for expiration
+const EXPIRATION_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct ConsumerGroupController<C: MetadataItem> {
+    ctx: SharedContext<C>,
+}
+
+impl<C: MetadataItem + 'static> ConsumerGroupController<C> {
+    pub fn start(ctx: SharedContext<C>) {
+        let controller = Self { ctx };
+
+        info!("starting consumer group controller");
+        spawn(controller.dispatch_loop());
+    }
+
+    #[instrument(skip(self), name = "ConsumerGroupControllerLoop")]
+    async fn dispatch_loop(self) {
+        info!("started");
+        loop {
+            sleep(EXPIRATION_CHECK_INTERVAL).await;
+            self.ctx.consumer_groups().expire(Instant::now()).await;
+        }
+    }
+}
@@ -2,3 +2,5 @@ pub(crate) mod partitions;
 pub(crate) mod spus;
 pub(crate) mod topics;
 pub(crate) mod scheduler;
+pub(crate) mod transactions;
+pub(crate) mod consumer_groups;
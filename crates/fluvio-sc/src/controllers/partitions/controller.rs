@@ -67,11 +67,15 @@ where
         let mut partition_listener = self.partitions.change_listener();
         let _ = partition_listener.wait_for_initial_sync().await;
 
+        let mut reassignment_listener = self.partitions.change_listener();
+        let _ = reassignment_listener.wait_for_initial_sync().await;
+
         debug!("finish initializing listeners");
 
         loop {
             self.sync_spu_changes(&mut spu_status_listener).await;
             self.sync_partition_changes(&mut partition_listener).await;
+            self.sync_reassignments(&mut reassignment_listener).await;
 
             trace!("waiting for events");
 
@@ -83,6 +87,9 @@ where
                 _ = partition_listener.listen() => {
                     debug!("detected partition changes");
                 }
+                _ = reassignment_listener.listen() => {
+                    debug!("detected partition spec or status changes");
+                }
 
             }
         }
@@ -115,6 +122,29 @@ where
         }
     }
 
+    /// move partition reassignments forward as replicas catch up
+    #[instrument(skip(self, listener))]
+    async fn sync_reassignments(&mut self, listener: &mut ChangeListener<PartitionSpec, C>) {
+        if !listener.has_change() {
+            trace!("no partitions change");
+            return;
+        }
+
+        let changes = listener.sync_changes().await;
+        if changes.is_empty() {
+            debug!("no partition changes");
+            return;
+        }
+
+        let (updates, _) = changes.parts();
+        let actions = self.reducer.process_reassignments(updates).await;
+
+        debug!("generated reassignment actions: {}", actions.len());
+        for action in actions.into_iter() {
+            self.partitions.send_action(action).await;
+        }
+    }
+
     /// sync spu states to partition
     /// check to make sure
     async fn sync_spu_changes(&mut self, listener: &mut ChangeListener<SpuSpec, C>) {
@@ -3,18 +3,21 @@
 //!
 //! Partition metadata information on cached in the local Controller.
 //!
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use fluvio_controlplane::PartitionMetadata;
-use tracing::{debug, info, instrument};
+use tracing::{debug, info, warn, instrument};
 
 use fluvio_controlplane_metadata::store::k8::K8MetaItem;
 use fluvio_controlplane_metadata::core::MetadataItem;
+use fluvio_types::SpuId;
 
 use crate::stores::partition::{
     PartitionSpec, PartitionResolution, PartitionLocalStore, SimplePolicy, PartitonStatusExtension,
-    ElectionPolicy,
+    ElectionPolicy, PartitionStatus, PartitionReassignment, UncleanElection,
 };
+use crate::stores::topic::LeaderElectionPolicy;
 use crate::stores::actions::WSAction;
 use crate::stores::spu::{SpuLocalStorePolicy, SpuLocalStore, SpuMetadata};
 
@@ -102,6 +105,31 @@ impl<C: MetadataItem> PartitionReducer<C> {
             .collect()
     }
 
+    /// move reassignment of partitions to next step
+    #[instrument(skip(self, updates))]
+    pub async fn process_reassignments(
+        &self,
+        updates: Vec<PartitionMetadata<C>>,
+    ) -> Vec<PartitionWSAction<C>> {
+        let online = self.spu_store.online_status().await;
+        let policy = SimplePolicy::new();
+
+        updates
+            .into_iter()
+            .filter_map(|partition| {
+                let spec =
+                    next_reassignment_spec(&partition.spec, &partition.status, &online, &policy)?;
+                info!(
+                    partition = %partition.key(),
+                    leader = spec.leader,
+                    replicas = ?spec.replicas,
+                    "reassignment step",
+                );
+                Some(PartitionWSAction::UpdateSpec((partition.key, spec)))
+            })
+            .collect()
+    }
+
     ///
     /// based on spu change, update election
     ///
@@ -174,6 +202,27 @@ impl<C: MetadataItem> PartitionReducer<C> {
                     );
 
                 // change the
+                } else if let Some((spec, status)) =
+                    unclean_election(&partition_kv.spec, &partition_kv.status, &spu_status)
+                {
+                    warn!(
+                        partition = %partition_kv.key(),
+                        old_leader = offline_leader_spu_id,
+                        new_leader = spec.leader,
+                        lost_records = status
+                            .unclean_election
+                            .as_ref()
+                            .map(|election| election.lost_records()),
+                        "no in-sync replica is online, electing out of sync replica",
+                    );
+                    actions.push(PartitionWSAction::UpdateSpec((
+                        partition_kv.key_owned(),
+                        spec,
+                    )));
+                    actions.push(PartitionWSAction::UpdateStatus((
+                        partition_kv.key_owned(),
+                        status,
+                    )));
                 } else {
                     // check partition is already offline
                     if partition_kv.status.is_online() {
@@ -210,6 +259,8 @@ impl<C: MetadataItem> PartitionReducer<C> {
         debug!(spu = %online_spu.key(),"performing election check spu online");
         let online_leader_spu_id = online_spu.spec.id;
 
+        let spu_status = self.spu_store.online_status().await;
+
         let policy = SimplePolicy::new();
         // go thru each partitions which are not online and try to promote given online spu
 
@@ -218,6 +269,7 @@ impl<C: MetadataItem> PartitionReducer<C> {
             if partition_kv.status.is_offline() {
                 if partition_kv.spec.leader != online_leader_spu_id {
                     // switch leader if online leader is different
+                    let mut elected = false;
                     for replica_status in partition_kv.status.replica_iter() {
                         if replica_status.spu == online_leader_spu_id
                             && policy
@@ -235,6 +287,29 @@ impl<C: MetadataItem> PartitionReducer<C> {
                                 online_spu = online_leader_spu_id,
                                 "changing to new leader",
                             );
+                            elected = true;
+                        }
+                    }
+                    // replica which is not in sync is elected only while leader is offline
+                    if !elected && !spu_status.contains(&partition_kv.spec.leader) {
+                        if let Some((spec, status)) = unclean_election(
+                            &partition_kv.spec,
+                            &partition_kv.status,
+                            &HashSet::from([online_leader_spu_id]),
+                        ) {
+                            warn!(
+                                partition = %partition_kv.key(),
+                                online_spu = online_leader_spu_id,
+                                "no in-sync replica is online, electing out of sync replica",
+                            );
+                            actions.push(PartitionWSAction::UpdateSpec((
+                                partition_kv.key_owned(),
+                                spec,
+                            )));
+                            actions.push(PartitionWSAction::UpdateStatus((
+                                partition_kv.key_owned(),
+                                status,
+                            )));
                         }
                     }
                 } else {
@@ -258,6 +333,89 @@ impl<C: MetadataItem> PartitionReducer<C> {
     }
 }
 
+/// Spec and status of partition after electing replica which is not in sync,
+/// None if partition prefers consistency or no replica is online.
+/// Online replica with highest log end offset is elected,
+/// other replicas discard records they have beyond its log end offset.
+fn unclean_election(
+    spec: &PartitionSpec,
+    status: &PartitionStatus,
+    online: &HashSet<SpuId>,
+) -> Option<(PartitionSpec, PartitionStatus)> {
+    if spec.leader_election != LeaderElectionPolicy::Availability {
+        return None;
+    }
+    let adding_replicas = spec
+        .reassignment
+        .as_ref()
+        .map(|reassignment| reassignment.adding_replicas.as_slice())
+        .unwrap_or_default();
+    let candidate = status
+        .replica_iter()
+        .filter(|replica| {
+            replica.spu != spec.leader
+                && replica.leo >= 0
+                && spec.has_spu(&replica.spu)
+                && !adding_replicas.contains(&replica.spu)
+                && online.contains(&replica.spu)
+        })
+        .max_by_key(|replica| replica.leo)?;
+
+    let mut next_spec = spec.clone();
+    next_spec.leader = candidate.spu;
+
+    let mut next_status = status.clone();
+    next_status.resolution = PartitionResolution::ElectionLeaderFound;
+    next_status.unclean_election = Some(UncleanElection {
+        previous_leader: spec.leader,
+        previous_leo: status.leader.leo,
+        leader: candidate.spu,
+        leo: candidate.leo,
+    });
+    Some((next_spec, next_status))
+}
+
+/// Next spec of partition being reassigned, None if partition has to wait.
+/// Once added replicas are in sync, leader is switched to preferred replica.
+/// When preferred replica is serving as leader, old replicas are dropped.
+fn next_reassignment_spec<P: ElectionPolicy>(
+    spec: &PartitionSpec,
+    status: &PartitionStatus,
+    online: &HashSet<SpuId>,
+    policy: &P,
+) -> Option<PartitionSpec> {
+    let reassignment = spec.reassignment.as_ref()?;
+    // wait until leader reports status and added replicas have caught up
+    if status.leader.spu != spec.leader
+        || !reassignment
+            .adding_replicas
+            .iter()
+            .all(|spu| online.contains(spu) && status.is_in_sync(*spu, policy))
+    {
+        return None;
+    }
+
+    let preferred = *reassignment.target_replicas.first()?;
+    let mut next = spec.clone();
+    if spec.leader != preferred
+        && online.contains(&preferred)
+        && status.is_in_sync(preferred, policy)
+    {
+        next.leader = preferred;
+        next.reassignment = Some(PartitionReassignment {
+            target_replicas: reassignment.target_replicas.clone(),
+            adding_replicas: vec![],
+        });
+    } else if reassignment.target_replicas.contains(&spec.leader) {
+        next.replicas = reassignment.target_replicas.clone();
+        next.reassignment = None;
+    } else {
+        // preferred replica is not available and current leader is dropped
+        return None;
+    }
+    Some(next)
+}
+
 // -----------------------------------
 //  Unit Tests
 //      >> utils::init_logger();
@@ -267,6 +425,119 @@ impl<C: MetadataItem> PartitionReducer<C> {
 #[cfg(test)]
 pub mod test {
 
+    use std::collections::HashSet;
+
+    use crate::stores::partition::{
+        PartitionSpec, PartitionStatus, PartitionReassignment, ReplicaStatus, SimplePolicy,
+        PartitionResolution,
+    };
+    use crate::stores::topic::LeaderElectionPolicy;
+
+    use super::{next_reassignment_spec, unclean_election};
+
+    fn reassigned(leader: i32, current: Vec<i32>, target: Vec<i32>) -> PartitionSpec {
+        let reassignment = PartitionReassignment::new(&current, target);
+        let mut spec = PartitionSpec::new(leader, reassignment.all_replicas(&current));
+        spec.reassignment = Some(reassignment);
+        spec
+    }
+
+    #[test]
+    fn test_reassignment_steps() {
+        let online: HashSet<i32> = [0, 1, 2, 3].into();
+        let policy = SimplePolicy::new();
+
+        // move [0, 1] to [2, 3]
+        let spec = reassigned(0, vec![0, 1], vec![2, 3]);
+        assert_eq!(spec.replicas, vec![0, 1, 2, 3]);
+
+        // new replicas have not caught up
+        let status = PartitionStatus::new(
+            (0, 100, 100),
+            vec![
+                (1, 100, 100).into(),
+                (2, 100, 100).into(),
+                (3, 0, 10).into(),
+            ],
+        );
+        assert!(next_reassignment_spec(&spec, &status, &online, &policy).is_none());
+
+        // switch leader to preferred replica
+        let status = PartitionStatus::new(
+            (0, 100, 100),
+            vec![
+                (1, 100, 100).into(),
+                (2, 100, 100).into(),
+                (3, 100, 100).into(),
+            ],
+        );
+        let spec = next_reassignment_spec(&spec, &status, &online, &policy).expect("leader switch");
+        assert_eq!(spec.leader, 2);
+        assert_eq!(spec.replicas, vec![0, 1, 2, 3]);
+        assert!(spec
+            .reassignment
+            .as_ref()
+            .expect("reassignment")
+            .adding_replicas
+            .is_empty());
+
+        // wait for new leader
+        assert!(next_reassignment_spec(&spec, &status, &online, &policy).is_none());
+
+        // drop old replicas
+        let status = PartitionStatus::new(
+            (2, 100, 100),
+            vec![
+                ReplicaStatus::new(3, 100, 100),
+                ReplicaStatus::new(0, 100, 100),
+            ],
+        );
+        let spec = next_reassignment_spec(&spec, &status, &online, &policy).expect("complete");
+        assert_eq!(spec.leader, 2);
+        assert_eq!(spec.replicas, vec![2, 3]);
+        assert!(spec.reassignment.is_none());
+        assert!(next_reassignment_spec(&spec, &status, &online, &policy).is_none());
+    }
+
+    #[test]
+    fn test_reassignment_offline_replica() {
+        let online: HashSet<i32> = [0, 1].into();
+        let policy = SimplePolicy::new();
+
+        let spec = reassigned(0, vec![0, 1], vec![0, 2]);
+        let status =
+            PartitionStatus::new((0, 10, 10), vec![(1, 10, 10).into(), (2, 10, 10).into()]);
+        assert!(next_reassignment_spec(&spec, &status, &online, &policy).is_none());
+    }
+
+    #[test]
+    fn test_unclean_election() {
+        let mut spec = PartitionSpec::new(0, vec![0, 1, 2]);
+        let status =
+            PartitionStatus::new((0, 100, 120), vec![(1, 80, 90).into(), (2, 80, 100).into()]);
+
+        // consistency is default
+        assert!(unclean_election(&spec, &status, &[1, 2].into()).is_none());
+
+        spec.leader_election = LeaderElectionPolicy::Availability;
+        let (next_spec, next_status) =
+            unclean_election(&spec, &status, &[1, 2].into()).expect("elected");
+        assert_eq!(next_spec.leader, 2);
+        assert_eq!(
+            next_status.resolution,
+            PartitionResolution::ElectionLeaderFound
+        );
+        let election = next_status.unclean_election.expect("unclean election");
+        assert_eq!(election.previous_leader, 0);
+        assert_eq!(election.leader, 2);
+        assert_eq!(election.lost_records(), 20);
+
+        // only online replicas are elected
+        let (next_spec, _) = unclean_election(&spec, &status, &[1].into()).expect("elected");
+        assert_eq!(next_spec.leader, 1);
+        assert!(unclean_election(&spec, &status, &[0].into()).is_none());
+    }
+
     /*
     #[fluvio_future::test]
     async fn test_process_partition_actions_without_partitions()  {
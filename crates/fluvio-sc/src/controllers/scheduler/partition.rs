@@ -4,7 +4,7 @@ use tracing::{instrument, debug, trace};
 
 use fluvio_controlplane_metadata::topic::{TopicReplicaParam, PartitionMaps};
 use fluvio_stream_model::core::MetadataItem;
-use fluvio_types::{PartitionId, SpuId, ReplicaMap, ReplicationFactor};
+use fluvio_types::{PartitionCount, PartitionId, SpuId, ReplicaMap, ReplicationFactor};
 
 use crate::stores::{
     spu::{SpuLocalStore, SpuLocalStorePolicy},
@@ -18,13 +18,6 @@ use crate::stores::{
 #[derive(Default, Debug, PartialEq)]
 pub(crate) struct ReplicaPartitionMap(ReplicaMap);
 
-impl ReplicaPartitionMap {
-    // check if there are resource are scheduled
-    pub fn scheduled(&self) -> bool {
-        !self.is_empty()
-    }
-}
-
 impl From<ReplicaMap> for ReplicaPartitionMap {
     fn from(replica_map: ReplicaMap) -> Self {
         Self(replica_map)
@@ -55,6 +48,21 @@ impl From<ReplicaPartitionMap> for ReplicaMap {
     }
 }
 
+/// replicas of topic which can't be placed on online spus
+#[derive(Debug, PartialEq, Eq, thiserror::Error)]
+pub(crate) enum ScheduleError {
+    #[error("replication factor {replication_factor} is greater than {online} online spus")]
+    InsufficientSpus {
+        replication_factor: ReplicationFactor,
+        online: ReplicationFactor,
+    },
+    #[error("no suitable spu for replica {replica} of partition {partition}")]
+    NoSuitableSpu {
+        partition: PartitionId,
+        replica: ReplicationFactor,
+    },
+}
+
 impl Deref for ReplicaPartitionMap {
     type Target = BTreeMap<PartitionId, Vec<SpuId>>;
 
@@ -100,24 +108,114 @@ where
     pub async fn generate_replica_map_for_topic(
         &'a mut self,
         param: &TopicReplicaParam,
-    ) -> ReplicaPartitionMap {
-        let spu_count = self.spus.count().await as ReplicationFactor;
-        if spu_count < param.replication_factor {
-            debug!(
-                param.replication_factor,
-                spu_count, "insufficient spu count"
-            );
-            ReplicaPartitionMap::default()
-        } else {
+    ) -> Result<ReplicaPartitionMap, ScheduleError> {
+        let online = self.spus.online_spu_count().await as ReplicationFactor;
+        if online < param.replication_factor {
+            debug!(param.replication_factor, online, "insufficient spu count");
+            Err(ScheduleError::InsufficientSpus {
+                replication_factor: param.replication_factor,
+                online,
+            })
+        } else if param.ignore_rack_assignment || self.spus.spus_in_rack_count().await == 0 {
             self.generate_partitions_without_rack(param).await
+        } else {
+            self.generate_partitions_with_rack(param).await
         }
     }
 
+    /// Generate replica map for partitions added to a topic which has `existing` partitions.
+    /// Existing partitions are already counted in scheduling groups, so they are not moved.
+    #[instrument(level = "debug")]
+    pub async fn generate_replica_map_for_new_partitions(
+        &'a mut self,
+        param: &TopicReplicaParam,
+        existing: PartitionCount,
+    ) -> Result<ReplicaPartitionMap, ScheduleError> {
+        let new_param = TopicReplicaParam {
+            partitions: param.partitions.saturating_sub(existing),
+            ..param.clone()
+        };
+        let replica_map = self.generate_replica_map_for_topic(&new_param).await?;
+        Ok(replica_map
+            .0
+            .into_iter()
+            .map(|(partition, replicas)| (partition + existing, replicas))
+            .collect::<ReplicaMap>()
+            .into())
+    }
+
+    /// Generate partitions with replicas of each partition in distinct racks
+    /// and leaders balanced across racks.
+    /// Spus without rack are considered to be in their own rack.
+    /// If there are fewer racks than replicas, racks with fewest replicas of the partition are reused.
+    pub(crate) async fn generate_partitions_with_rack(
+        &mut self,
+        param: &TopicReplicaParam,
+    ) -> Result<ReplicaPartitionMap, ScheduleError> {
+        let racks = self.online_racks().await;
+
+        trace!(?racks, "online racks");
+        let mut partition_map = BTreeMap::new();
+        for p_idx in 0..param.partitions {
+            let mut reserved_spus: Vec<SpuId> = vec![];
+            for r_idx in 0..param.replication_factor {
+                if let Some(spu) = self.scheduling_groups.find_suitable_spu_in_racks(
+                    &racks,
+                    &reserved_spus,
+                    if r_idx == 0 {
+                        SpuWeightSelection::Leader
+                    } else {
+                        SpuWeightSelection::Follower
+                    },
+                ) {
+                    trace!(spu, "found spu");
+                    reserved_spus.push(spu);
+                    if r_idx == 0 {
+                        self.scheduling_groups.increase_leaders(spu);
+                    } else {
+                        self.scheduling_groups.increase_followers(spu);
+                    }
+                } else {
+                    trace!("no suitable spu found");
+                    return Err(ScheduleError::NoSuitableSpu {
+                        partition: p_idx as PartitionId,
+                        replica: r_idx,
+                    });
+                }
+            }
+            partition_map.insert(p_idx as PartitionId, reserved_spus);
+        }
+
+        Ok(partition_map.into())
+    }
+
+    /// online spus grouped by rack, ordered by rack name
+    async fn online_racks(&self) -> Vec<Vec<SpuId>> {
+        let mut racks: BTreeMap<String, Vec<SpuId>> = BTreeMap::new();
+        let mut no_rack = vec![];
+        for spu in self.spus.online_spus().await {
+            match spu.spec.rack {
+                Some(rack) => racks.entry(rack).or_default().push(spu.spec.id),
+                None => no_rack.push(vec![spu.spec.id]),
+            }
+        }
+        no_rack.sort_unstable();
+
+        racks
+            .into_values()
+            .map(|mut spus| {
+                spus.sort_unstable();
+                spus
+            })
+            .chain(no_rack)
+            .collect()
+    }
+
     /// Generate partitions without taking rack assignments into consideration
     pub(crate) async fn generate_partitions_without_rack(
         &mut self,
         param: &TopicReplicaParam,
-    ) -> ReplicaPartitionMap {
+    ) -> Result<ReplicaPartitionMap, ScheduleError> {
         let mut online_spus = self.spus.online_spu_ids().await;
         online_spus.sort_unstable();
 
@@ -145,13 +243,16 @@ where
                     }
                 } else {
                     trace!("no suitable spu found");
-                    return BTreeMap::new().into();
+                    return Err(ScheduleError::NoSuitableSpu {
+                        partition: p_idx as PartitionId,
+                        replica: r_idx,
+                    });
                 }
             }
             partition_map.insert(p_idx as PartitionId, reserved_spus);
         }
 
-        partition_map.into()
+        Ok(partition_map.into())
     }
 }
 
@@ -184,7 +285,7 @@ pub mod replica_map_test {
         let expected: ReplicaPartitionMap = vec![(0, vec![0]), (1, vec![1])].into();
         assert_eq!(
             scheduler.generate_partitions_without_rack(&param).await,
-            expected
+            Ok(expected)
         );
     }
 
@@ -204,7 +305,7 @@ pub mod replica_map_test {
             vec![(0, vec![0]), (1, vec![1]), (2, vec![0]), (3, vec![1])].into();
         assert_eq!(
             scheduler.generate_partitions_without_rack(&param).await,
-            expected
+            Ok(expected)
         );
     }
 
@@ -220,10 +321,13 @@ pub mod replica_map_test {
         };
         let mut scheduler = PartitionScheduler::init(&spus, &partitions).await;
         // not enough spus for replication
-        assert!(scheduler
-            .generate_partitions_without_rack(&param)
-            .await
-            .is_empty());
+        assert_eq!(
+            scheduler.generate_partitions_without_rack(&param).await,
+            Err(ScheduleError::NoSuitableSpu {
+                partition: 0,
+                replica: 2
+            })
+        );
     }
 
     #[fluvio_future::test]
@@ -250,7 +354,7 @@ pub mod replica_map_test {
             vec![(0, vec![0]), (1, vec![1]), (2, vec![2]), (3, vec![4])].into();
         assert_eq!(
             scheduler.generate_partitions_without_rack(&param).await,
-            expected
+            Ok(expected)
         );
     }
 
@@ -293,6 +397,106 @@ pub mod replica_map_test {
         let actual = scheduler.generate_partitions_without_rack(&param).await;
         //println!("after group: {:#?}", scheduler.scheduling_groups());
 
-        assert_eq!(actual, expect);
+        assert_eq!(actual, Ok(expect));
+    }
+
+    #[fluvio_future::test]
+    async fn generate_replica_map_for_new_partitions() {
+        let spus = DefaultSpuStore::quick(vec![(0, true, None), (1, true, None)]);
+        let partitions =
+            DefaultPartitionStore::bulk_load(vec![(("t1", 0), vec![0]), (("t1", 1), vec![1])]);
+
+        let param = TopicReplicaParam {
+            partitions: 4,
+            replication_factor: 1,
+            ignore_rack_assignment: false,
+        };
+        let mut scheduler = PartitionScheduler::init(&spus, &partitions).await;
+        // existing partitions are kept, new partitions are numbered after them
+        let expected: ReplicaPartitionMap = vec![(2, vec![0]), (3, vec![1])].into();
+        assert_eq!(
+            scheduler
+                .generate_replica_map_for_new_partitions(&param, 2)
+                .await,
+            Ok(expected)
+        );
+    }
+
+    #[fluvio_future::test]
+    async fn generate_replica_map_with_rack() {
+        let spus = DefaultSpuStore::quick(vec![
+            (0, true, Some("r1".to_owned())),
+            (1, true, Some("r1".to_owned())),
+            (2, true, Some("r2".to_owned())),
+            (3, true, Some("r2".to_owned())),
+            (4, true, Some("r3".to_owned())),
+            (5, true, Some("r3".to_owned())),
+        ]);
+        let partitions = PartitionAdminStore::new_shared();
+
+        let param = TopicReplicaParam {
+            partitions: 3,
+            replication_factor: 3,
+            ignore_rack_assignment: false,
+        };
+        let mut scheduler = PartitionScheduler::init(&spus, &partitions).await;
+        // each replica is in different rack, one leader per rack
+        let expected: ReplicaPartitionMap =
+            vec![(0, vec![0, 2, 4]), (1, vec![2, 0, 5]), (2, vec![4, 1, 3])].into();
+        assert_eq!(
+            scheduler.generate_replica_map_for_topic(&param).await,
+            Ok(expected)
+        );
+    }
+
+    #[fluvio_future::test]
+    async fn generate_replica_map_with_fewer_racks() {
+        let spus = DefaultSpuStore::quick(vec![
+            (0, true, Some("r1".to_owned())),
+            (1, true, Some("r1".to_owned())),
+            (2, true, Some("r2".to_owned())),
+            (3, false, Some("r2".to_owned())),
+        ]);
+        let partitions = PartitionAdminStore::new_shared();
+
+        let param = TopicReplicaParam {
+            partitions: 2,
+            replication_factor: 3,
+            ignore_rack_assignment: false,
+        };
+        let mut scheduler = PartitionScheduler::init(&spus, &partitions).await;
+        // third replica reuses rack
+        let expected: ReplicaPartitionMap = vec![(0, vec![0, 2, 1]), (1, vec![2, 0, 1])].into();
+        assert_eq!(
+            scheduler.generate_replica_map_for_topic(&param).await,
+            Ok(expected)
+        );
+    }
+
+    #[fluvio_future::test]
+    async fn generate_replica_map_with_offline_spus() {
+        let spus = DefaultSpuStore::quick(vec![
+            (0, true, None),
+            (1, true, None),
+            (2, false, Some("r1".to_owned())),
+            (3, false, Some("r2".to_owned())),
+        ]);
+        let partitions = PartitionAdminStore::new_shared();
+        assert_eq!(spus.spus_in_rack_count().await, 0);
+
+        let param = TopicReplicaParam {
+            partitions: 1,
+            replication_factor: 3,
+            ignore_rack_assignment: false,
+        };
+        let mut scheduler = PartitionScheduler::init(&spus, &partitions).await;
+        // offline spus can't host replicas
+        assert_eq!(
+            scheduler.generate_replica_map_for_topic(&param).await,
+            Err(ScheduleError::InsufficientSpus {
+                replication_factor: 3,
+                online: 2
+            })
+        );
     }
 }
@@ -7,9 +7,10 @@ use fluvio_controlplane_metadata::topic::ReplicaSpec;
 use fluvio_controlplane_metadata::topic::TopicReplicaParam;
 use fluvio_controlplane_metadata::topic::TopicResolution;
 
-use fluvio_types::ReplicaMap;
+use fluvio_types::{PartitionCount, ReplicaMap};
 use fluvio_controlplane_metadata::topic::PartitionMaps;
 use fluvio_controlplane_metadata::topic::TopicStatus;
+use fluvio_controlplane_metadata::topic::PENDING_REASON;
 use fluvio_stream_model::core::MetadataItem;
 
 use crate::controllers::scheduler::PartitionScheduler;
@@ -164,21 +165,25 @@ impl<C: MetadataItem> TopicNextState<C> {
                     validate_computed_topic_parameters(param)
                 }
                 TopicResolution::Pending | TopicResolution::InsufficientResources => {
-                    let replica_map = scheduler.generate_replica_map_for_topic(param).await;
-                    if replica_map.scheduled() {
-                        debug!(
-                            topic = %topic.key(),
-                            "generated replica map for mirror topic"
-                        );
-                        TopicNextState {
-                            resolution: TopicResolution::Provisioned,
-                            replica_map,
-                            ..Default::default()
+                    match scheduler.generate_replica_map_for_topic(param).await {
+                        Ok(replica_map) => {
+                            debug!(
+                                topic = %topic.key(),
+                                "generated replica map for mirror topic"
+                            );
+                            TopicNextState {
+                                resolution: TopicResolution::Provisioned,
+                                replica_map,
+                                ..Default::default()
+                            }
                         }
-                    } else {
-                        TopicNextState {
-                            resolution: TopicResolution::InsufficientResources,
-                            ..Default::default()
+                        Err(err) => {
+                            debug!(topic = %topic.key(), %err, "insufficient resources");
+                            TopicNextState {
+                                resolution: TopicResolution::InsufficientResources,
+                                reason: err.to_string(),
+                                ..Default::default()
+                            }
                         }
                     }
                 }
@@ -190,9 +195,33 @@ impl<C: MetadataItem> TopicNextState<C> {
                     );
                     let mut next_state = TopicNextState::same_next_state(topic);
                     if next_state.resolution == TopicResolution::Provisioned {
-                        debug!("creating new partitions");
-                        next_state.partitions =
-                            topic.create_new_partitions(scheduler.partitions()).await;
+                        let scheduled = topic.status.replica_map.len() as PartitionCount;
+                        if scheduled < param.partitions {
+                            // partitions were added to topic, schedule only new ones
+                            match scheduler
+                                .generate_replica_map_for_new_partitions(param, scheduled)
+                                .await
+                            {
+                                Ok(new_replica_map) => {
+                                    debug!(
+                                        topic = %topic.key(),
+                                        partitions = param.partitions,
+                                        "generated replica map for new partitions"
+                                    );
+                                    let mut replica_map = topic.status.replica_map.clone();
+                                    replica_map.extend(ReplicaMap::from(new_replica_map));
+                                    next_state.replica_map = replica_map.into();
+                                }
+                                Err(err) => {
+                                    debug!(topic = %topic.key(), %err, "new partitions pending");
+                                    next_state.reason = format!("{PENDING_REASON}: {err}");
+                                }
+                            }
+                        } else {
+                            debug!("creating new partitions");
+                            next_state.partitions =
+                                topic.create_new_partitions(scheduler.partitions()).await;
+                        }
                     }
                     next_state
                 }
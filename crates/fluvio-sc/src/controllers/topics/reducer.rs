@@ -160,9 +160,25 @@ impl<C: MetadataItem> TopicReducer<C> {
                 .push(WSAction::<PartitionSpec, C>::Apply(partition_kv));
         }
 
+        // carry transforms of topic to existing partitions, so leaders apply changed chain
+        if updated_topic.status.resolution == TopicResolution::Provisioned {
+            let transforms = updated_topic.spec.get_transforms();
+            for partition in topic.childrens(self.partition_store()).await {
+                if partition.spec.transforms != transforms {
+                    debug!(partition = %partition.key(), "updating partition transforms");
+                    let mut spec = partition.spec.clone();
+                    spec.transforms = transforms.to_vec();
+                    actions
+                        .partitions
+                        .push(PartitionWSAction::UpdateSpec((partition.key_owned(), spec)));
+                }
+            }
+        }
+
         // apply changes to topics
         if updated_topic.status.resolution != topic.status.resolution
             || updated_topic.status.reason != topic.status.reason
+            || updated_topic.status.replica_map != topic.status.replica_map
         {
             debug!(
                 topic = %topic.key(),
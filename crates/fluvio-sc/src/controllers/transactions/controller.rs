@@ -0,0 +1,77 @@
+//!
+//! # Transaction Controller
+//!
+//! Loads transactions persisted before SC restart and aborts transactions of producers
+//! which stopped sending requests, so open transactions don't hold back last stable offset
+//! of their partitions.
+
+use std::time::{Duration, Instant};
+
+use fluvio_future::timer::sleep;
+use fluvio_stream_model::core::MetadataItem;
+use tracing::{debug, info, error, instrument};
+
+use fluvio_future::task::spawn;
+
+use crate::core::SharedContext;
+use crate::services::{end_transaction, persist_transaction};
+
+/// how often transactions are checked for expiration
+const EXPIRATION_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct TransactionController<C: MetadataItem> {
+    ctx: SharedContext<C>,
+}
+
+impl<C: MetadataItem + 'static> TransactionController<C> {
+    /// transactions are loaded before returning, so requests are not served from empty state
+    pub async fn start(ctx: SharedContext<C>) {
+        let controller = Self { ctx };
+
+        info!("starting transaction controller");
+        controller.load().await;
+        spawn(controller.dispatch_loop());
+    }
+
+    async fn load(&self) {
+        let mut listener = self.ctx.transaction_specs().change_listener();
+        let specs = listener.wait_for_initial_sync().await;
+        info!(count = specs.len(), "loading transactions");
+        self.ctx
+            .transactions()
+            .load(specs.into_iter().map(|spec| (spec.key, spec.spec)))
+            .await;
+    }
+
+    #[instrument(skip(self), name = "TransactionControllerLoop")]
+    async fn dispatch_loop(self) {
+        info!("started");
+        loop {
+            sleep(EXPIRATION_CHECK_INTERVAL).await;
+            self.end_expired().await;
+        }
+    }
+
+    /// abort idle transactions and retry ending transactions which failed to complete
+    async fn end_expired(&self) {
+        let expired = self
+            .ctx
+            .transactions()
+            .expire(Instant::now(), self.ctx.producer_ids())
+            .await;
+        for (transactional_id, end) in expired {
+            debug!(
+                transactional_id,
+                commit = end.commit,
+                "ending expired transaction"
+            );
+            let result = match persist_transaction(&self.ctx, &transactional_id).await {
+                Ok(()) => end_transaction(&self.ctx, &transactional_id, &end).await,
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                error!(transactional_id, %err, "failed to end expired transaction");
+            }
+        }
+    }
+}
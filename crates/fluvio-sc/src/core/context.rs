@@ -14,6 +14,10 @@ use crate::stores::topic::*;
 use crate::stores::spg::*;
 use crate::stores::smartmodule::*;
 use crate::stores::tableformat::*;
+use crate::stores::quota::*;
+use crate::stores::consumer_group::*;
+use crate::stores::producer_id::*;
+use crate::stores::transaction::*;
 use crate::stores::*;
 
 pub type SharedContext<C> = Arc<Context<C>>;
@@ -29,7 +33,12 @@ pub struct Context<C: MetadataItem> {
     spgs: StoreContext<SpuGroupSpec, C>,
     smartmodules: StoreContext<SmartModuleSpec, C>,
     tableformats: StoreContext<TableFormatSpec, C>,
+    quotas: StoreContext<QuotaSpec, C>,
+    transaction_specs: StoreContext<TransactionSpec, C>,
     health: SharedHealthCheck,
+    consumer_groups: SharedConsumerGroups,
+    producer_ids: SharedProducerIds,
+    transactions: SharedTransactions,
     config: ScConfig,
 }
 
@@ -51,7 +60,12 @@ impl<C: MetadataItem> Context<C> {
             spgs: StoreContext::new(),
             smartmodules: StoreContext::new(),
             tableformats: StoreContext::new(),
+            quotas: StoreContext::new(),
+            transaction_specs: StoreContext::new(),
             health: HealthCheck::shared(),
+            consumer_groups: ConsumerGroups::shared(),
+            producer_ids: ProducerIds::shared(),
+            transactions: Transactions::shared(),
             config,
         }
     }
@@ -83,11 +97,35 @@ impl<C: MetadataItem> Context<C> {
         &self.tableformats
     }
 
+    pub fn quotas(&self) -> &StoreContext<QuotaSpec, C> {
+        &self.quotas
+    }
+
+    /// persisted state of transactions
+    pub fn transaction_specs(&self) -> &StoreContext<TransactionSpec, C> {
+        &self.transaction_specs
+    }
+
     /// spu health channel
     pub fn health(&self) -> &SharedHealthCheck {
         &self.health
     }
 
+    /// membership of consumer groups
+    pub fn consumer_groups(&self) -> &SharedConsumerGroups {
+        &self.consumer_groups
+    }
+
+    /// allocator of idempotent producer ids
+    pub fn producer_ids(&self) -> &SharedProducerIds {
+        &self.producer_ids
+    }
+
+    /// transactions of transactional producers
+    pub fn transactions(&self) -> &SharedTransactions {
+        &self.transactions
+    }
+
     /// reference to config
     pub fn config(&self) -> &ScConfig {
         &self.config
@@ -14,10 +14,12 @@ use crate::core::SharedContext;
 use crate::controllers::partitions::PartitionController;
 use crate::controllers::spus::SpuController;
 use crate::controllers::topics::controller::TopicController;
+use crate::controllers::transactions::TransactionController;
+use crate::controllers::consumer_groups::ConsumerGroupController;
 use crate::config::ScConfig;
 use crate::services::start_internal_server;
 use crate::dispatcher::dispatcher::MetadataDispatcher;
-use crate::services::auth::basic::BasicRbacPolicy;
+use crate::services::auth::basic::{BasicRbacPolicy, SharedRbacPolicy};
 
 pub async fn start_main_loop<C, M>(
     sc_config_policy: (ScConfig, Option<BasicRbacPolicy>),
@@ -34,6 +36,8 @@ where
     use crate::stores::spg::SpuGroupSpec;
     use crate::stores::tableformat::TableFormatSpec;
     use crate::stores::smartmodule::SmartModuleSpec;
+    use crate::stores::quota::QuotaSpec;
+    use crate::stores::transaction::TransactionSpec;
 
     let (sc_config, auth_policy) = sc_config_policy;
 
@@ -76,6 +80,18 @@ where
         ctx.smartmodules().clone(),
     );
 
+    MetadataDispatcher::<QuotaSpec, C, M>::start(
+        namespace.clone(),
+        metadata_client.clone(),
+        ctx.quotas().clone(),
+    );
+
+    MetadataDispatcher::<TransactionSpec, C, M>::start(
+        namespace.clone(),
+        metadata_client.clone(),
+        ctx.transaction_specs().clone(),
+    );
+
     start_main_loop_services(ctx, auth_policy).await
 }
 
@@ -90,6 +106,11 @@ where
 {
     let config = ctx.config();
 
+    let auth_policy = auth_policy.map(SharedRbacPolicy::new);
+    if let (Some(policy), Some(path)) = (&auth_policy, &config.auth_policy_path) {
+        policy.watch(path.clone());
+    }
+
     whitelist!(config, "spu", SpuController::start(ctx.clone()));
     whitelist!(config, "topic", TopicController::start(ctx.clone()));
     whitelist!(
@@ -97,8 +118,22 @@ where
         "partition",
         PartitionController::start(ctx.partitions().clone(), ctx.spus().clone())
     );
+    whitelist!(
+        config,
+        "transaction",
+        TransactionController::start(ctx.clone()).await
+    );
+    whitelist!(
+        config,
+        "consumer_group",
+        ConsumerGroupController::start(ctx.clone())
+    );
 
-    whitelist!(config, "internal", start_internal_server(ctx.clone()));
+    whitelist!(
+        config,
+        "internal",
+        start_internal_server(ctx.clone(), auth_policy.clone())
+    );
     whitelist!(
         config,
         "public",
@@ -115,9 +150,9 @@ where
 
         use fluvio_controlplane_metadata::core::MetadataItem;
         use crate::services::auth::{AuthGlobalContext, RootAuthorization, ReadOnlyAuthorization};
-        use crate::services::auth::basic::{BasicAuthorization, BasicRbacPolicy};
+        use crate::services::auth::basic::{BasicAuthorization, SharedRbacPolicy};
 
-        pub fn start<C>(ctx: SharedContext<C>, auth_policy_option: Option<BasicRbacPolicy>)
+        pub fn start<C>(ctx: SharedContext<C>, auth_policy_option: Option<SharedRbacPolicy>)
         where
             C: MetadataItem + 'static,
             C::UId: Send + Sync,
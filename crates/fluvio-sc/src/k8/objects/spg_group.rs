@@ -254,6 +254,15 @@ mod k8_convert {
             size.clone(),
         ];
 
+        if let Some(min_in_sync_replicas) = spu_template
+            .replication
+            .as_ref()
+            .and_then(|replication| replication.in_sync_replica_min)
+        {
+            args.push("--min-in-sync-replicas".to_owned());
+            args.push(min_in_sync_replicas.to_string());
+        }
+
         if let Some(tls) = tls_config {
             args.push("--tls".to_owned());
             if tls.enable_client_cert {
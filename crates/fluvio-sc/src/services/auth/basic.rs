@@ -1,23 +1,92 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::path::PathBuf;
+use std::convert::TryFrom;
+use std::time::Duration;
 
-use tracing::instrument;
+use tracing::{info, error, instrument};
 use async_trait::async_trait;
-pub use policy::BasicRbacPolicy;
+pub use fluvio_auth::basic::BasicRbacPolicy;
 
 use fluvio_auth::{AuthContext, Authorization, TypeAction, InstanceAction, AuthError};
 use fluvio_controlplane_metadata::extended::ObjectType;
 use fluvio_auth::x509::X509Identity;
+use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
+use fluvio_types::event::offsets::{OffsetPublisher, OffsetChangeListener};
 
+const POLICY_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Policy which can be replaced while SC is running.
+/// Replacements are published so that SPUs can be updated.
 #[derive(Debug, Clone)]
-pub struct BasicAuthorization {
-    policy: Arc<BasicRbacPolicy>,
+pub struct SharedRbacPolicy {
+    policy: Arc<RwLock<Arc<BasicRbacPolicy>>>,
+    version: Arc<OffsetPublisher>,
 }
 
-impl BasicAuthorization {
+impl SharedRbacPolicy {
     pub fn new(policy: BasicRbacPolicy) -> Self {
         Self {
-            policy: Arc::new(policy),
+            policy: Arc::new(RwLock::new(Arc::new(policy))),
+            version: OffsetPublisher::shared(0),
+        }
+    }
+
+    pub fn current(&self) -> Arc<BasicRbacPolicy> {
+        self.policy
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    pub fn change_listener(&self) -> OffsetChangeListener {
+        self.version.change_listener()
+    }
+
+    /// replace policy, return true if it was different from current one
+    pub fn update(&self, policy: BasicRbacPolicy) -> bool {
+        {
+            let mut current = self.policy.write().unwrap_or_else(|err| err.into_inner());
+            if **current == policy {
+                return false;
+            }
+            *current = Arc::new(policy);
         }
+        self.version.update_increment();
+        true
+    }
+
+    /// periodically reload policy from file
+    pub fn watch(&self, path: PathBuf) {
+        spawn(reload_loop(self.clone(), path));
+    }
+}
+
+async fn reload_loop(policy: SharedRbacPolicy, path: PathBuf) {
+    info!(?path, "watching auth policy");
+    loop {
+        sleep(POLICY_RELOAD_INTERVAL).await;
+        match BasicRbacPolicy::try_from(path.clone()) {
+            Ok(new_policy) => {
+                if policy.update(new_policy) {
+                    info!(?path, "auth policy reloaded");
+                }
+            }
+            Err(err) => {
+                error!(%err, ?path, "failed to reload auth policy, keeping current");
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BasicAuthorization {
+    policy: SharedRbacPolicy,
+}
+
+impl BasicAuthorization {
+    pub fn new(policy: SharedRbacPolicy) -> Self {
+        Self { policy }
     }
 }
 
@@ -46,7 +115,7 @@ impl Authorization for BasicAuthorization {
 #[derive(Debug)]
 pub struct BasicAuthContext {
     identity: X509Identity,
-    policy: Arc<BasicRbacPolicy>,
+    policy: SharedRbacPolicy,
 }
 
 #[async_trait]
@@ -57,201 +126,67 @@ impl AuthContext for BasicAuthContext {
         action: TypeAction,
     ) -> Result<bool, AuthError> {
         self.policy
+            .current()
             .evaluate(action.into(), ty, None, &self.identity)
             .await
     }
 
-    /// check if specific instance of spec can be deleted
+    /// check if action is allowed on specific instance of spec
     async fn allow_instance_action(
         &self,
-        _ty: ObjectType,
-        _action: InstanceAction,
-        _key: &str,
+        ty: ObjectType,
+        action: InstanceAction,
+        key: &str,
     ) -> Result<bool, AuthError> {
-        Ok(true)
-    }
-}
-
-/// basic policy module
-/// does impl substitution
-mod policy {
-
-    use std::fs::read;
-    use std::collections::HashMap;
-    use std::path::PathBuf;
-    use std::convert::TryFrom;
-
-    use tracing::debug;
-    use serde::{Serialize, Deserialize};
-
-    use fluvio_auth::{AuthError, TypeAction, InstanceAction};
-    use fluvio_auth::x509::X509Identity;
-
-    use super::ObjectType;
-
-    type Role = String;
-
-    #[derive(Debug, Clone, PartialEq, Hash, Eq, Deserialize, Serialize)]
-    pub enum Action {
-        Create,
-        Read,
-        Update,
-        Delete,
-        All,
-    }
-
-    impl From<TypeAction> for Action {
-        fn from(action: TypeAction) -> Self {
-            match action {
-                TypeAction::Create => Action::Create,
-                TypeAction::Read => Action::Read,
-            }
-        }
-    }
-
-    impl From<InstanceAction> for Action {
-        fn from(action: InstanceAction) -> Self {
-            match action {
-                InstanceAction::Delete => Action::Delete,
-            }
-        }
-    }
-
-    #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
-    pub struct BasicRbacPolicy(pub HashMap<Role, HashMap<ObjectType, Vec<Action>>>);
-
-    impl From<HashMap<Role, HashMap<ObjectType, Vec<Action>>>> for BasicRbacPolicy {
-        fn from(map: HashMap<Role, HashMap<ObjectType, Vec<Action>>>) -> Self {
-            Self(map)
-        }
-    }
-
-    impl TryFrom<PathBuf> for BasicRbacPolicy {
-        type Error = std::io::Error;
-        fn try_from(path: PathBuf) -> Result<Self, Self::Error> {
-            debug!("reading basic policy: {:#?}", path);
-            let file = read(path)?;
-            let policy: BasicRbacPolicy = serde_json::from_slice(&file)?;
-            Ok(policy)
-        }
-    }
-
-    impl BasicRbacPolicy {
-        pub async fn evaluate(
-            &self,
-            action: Action,
-            object_type: ObjectType,
-            _instance: Option<&str>,
-            identity: &X509Identity,
-        ) -> Result<bool, AuthError> {
-            //   let (action,object,_instance) = request;
-            // For each scope provided in the identity,
-            // check if there is a match;
-            let is_allowed = identity.scopes().iter().any(|scope| {
-                self.0
-                    .get(scope)
-                    .map(|objects| {
-                        objects
-                            .get(&object_type)
-                            .map(|actions| {
-                                actions.iter().any(|permission| {
-                                    permission == &action || permission == &Action::All
-                                })
-                            })
-                            .unwrap_or(false)
-                    })
-                    .unwrap_or(false)
-            });
-
-            Ok(is_allowed)
-        }
-    }
-
-    impl Default for BasicRbacPolicy {
-        // default only allows the `Root` role to have full permissions;
-        fn default() -> Self {
-            let mut root_policy: HashMap<ObjectType, Vec<Action>> = HashMap::new();
-
-            root_policy.insert(ObjectType::Spu, vec![Action::All]);
-            root_policy.insert(ObjectType::CustomSpu, vec![Action::All]);
-            root_policy.insert(ObjectType::SpuGroup, vec![Action::All]);
-            root_policy.insert(ObjectType::Topic, vec![Action::All]);
-            root_policy.insert(ObjectType::Partition, vec![Action::All]);
-            root_policy.insert(ObjectType::TableFormat, vec![Action::All]);
-
-            let mut policy = HashMap::new();
-
-            policy.insert(String::from("Root"), root_policy);
-
-            Self(policy)
-        }
+        self.policy
+            .current()
+            .evaluate(action.into(), ty, Some(key), &self.identity)
+            .await
     }
 }
 
 #[cfg(test)]
 mod test {
-
-    use std::fs::File;
-    use std::path::PathBuf;
-    use std::convert::TryFrom;
     use std::collections::HashMap;
 
+    use fluvio_auth::{AuthContext, InstanceAction};
+    use fluvio_auth::basic::{Action, InstanceRule};
     use fluvio_auth::x509::X509Identity;
+    use fluvio_controlplane_metadata::extended::ObjectType;
 
-    use super::policy::*;
-    use super::ObjectType;
-
-    #[test]
-    fn test_policy_serialization() {
-        let mut policy = BasicRbacPolicy::default();
-
-        let mut default_role = HashMap::new();
-
-        default_role.insert(ObjectType::Topic, vec![Action::All]);
-        default_role.insert(ObjectType::Partition, vec![Action::All]);
-        default_role.insert(ObjectType::SpuGroup, vec![Action::Read]);
-        default_role.insert(ObjectType::CustomSpu, vec![Action::Read]);
-        default_role.insert(ObjectType::Spu, vec![Action::Read]);
-
-        policy.0.insert(String::from("Default"), default_role);
-
-        let tmp_file_path = PathBuf::from("/tmp/policy.json");
-        let tmp = File::create(tmp_file_path.clone()).expect("failed to create policy file");
-        serde_json::to_writer(&tmp, &policy).expect("failed to serialize policy to json file");
-
-        let recovered_policy =
-            BasicRbacPolicy::try_from(tmp_file_path).expect("failed to parse policy from file");
-
-        assert_eq!(
-            policy, recovered_policy,
-            "serialized and deserialized policies from file should match"
-        )
-    }
+    use super::{BasicAuthContext, BasicRbacPolicy, SharedRbacPolicy};
 
     #[fluvio_future::test]
-    async fn test_policy_enforcement_simple() {
-        let mut policy = BasicRbacPolicy::default();
-        let identity = X509Identity::new("User".to_owned(), vec!["Default".to_owned()]);
-
-        let mut role1 = HashMap::new();
-        role1.insert(ObjectType::Topic, vec![Action::Delete, Action::Read]);
-
-        policy.0.insert(String::from("Default"), role1);
-
-        assert!(!policy
-            .evaluate(Action::Create, ObjectType::CustomSpu, None, &identity)
+    async fn test_instance_actions_follow_reloaded_policy() {
+        let shared = SharedRbacPolicy::new(BasicRbacPolicy::default());
+        let mut listener = shared.change_listener();
+        let ctx = BasicAuthContext {
+            identity: X509Identity::new("alice".to_owned(), vec!["TeamA".to_owned()]),
+            policy: shared.clone(),
+        };
+
+        assert!(!ctx
+            .allow_instance_action(ObjectType::Topic, InstanceAction::Delete, "team-a.orders")
             .await
             .expect("eval"));
-        assert!(!policy
-            .evaluate(Action::Create, ObjectType::Topic, None, &identity)
-            .await
-            .expect("eval"));
-        assert!(policy
-            .evaluate(Action::Read, ObjectType::Topic, None, &identity)
+        assert!(!shared.update(BasicRbacPolicy::default()));
+
+        let mut team_a = HashMap::new();
+        team_a.insert(
+            ObjectType::Topic,
+            vec![InstanceRule::new(vec![Action::All], vec!["team-a.*".to_owned()]).into()],
+        );
+        let mut policy = BasicRbacPolicy::default();
+        policy.0.insert("TeamA".to_owned(), team_a);
+        assert!(shared.update(policy));
+        assert_eq!(listener.listen().await, 1);
+
+        assert!(ctx
+            .allow_instance_action(ObjectType::Topic, InstanceAction::Delete, "team-a.orders")
             .await
             .expect("eval"));
-        assert!(policy
-            .evaluate(Action::Delete, ObjectType::Topic, Some("test"), &identity)
+        assert!(!ctx
+            .allow_instance_action(ObjectType::Topic, InstanceAction::Delete, "team-b.orders")
             .await
             .expect("eval"));
     }
@@ -5,4 +5,5 @@ mod private_api;
 pub mod auth;
 
 pub use public_api::start_public_server;
+pub(crate) use public_api::{end_transaction, persist_transaction};
 pub use private_api::start_internal_server;
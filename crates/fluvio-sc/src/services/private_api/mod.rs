@@ -8,20 +8,21 @@ use private_server::ScInternalService;
 use fluvio_service::FluvioApiServer;
 
 use crate::core::SharedContext;
+use crate::services::auth::basic::SharedRbacPolicy;
 
 // start server
 #[instrument(
     name = "sc_private_server"
-    skip(ctx),
+    skip(ctx, auth_policy),
     fields(address = &*ctx.config().private_endpoint)
 )]
-pub fn start_internal_server<C>(ctx: SharedContext<C>)
+pub fn start_internal_server<C>(ctx: SharedContext<C>, auth_policy: Option<SharedRbacPolicy>)
 where
     C: MetadataItem + 'static,
 {
     info!("starting internal services");
 
     let addr = ctx.config().private_endpoint.clone();
-    let server = FluvioApiServer::new(addr, ctx, ScInternalService::new());
+    let server = FluvioApiServer::new(addr, ctx, ScInternalService::new(auth_policy));
     server.run();
 }
@@ -4,6 +4,7 @@ use std::io::Error as IoError;
 use std::io::ErrorKind;
 use std::time::Duration;
 
+use fluvio_controlplane::message::QuotaMsg;
 use fluvio_controlplane::message::ReplicaMsg;
 use fluvio_controlplane::message::SmartModuleMsg;
 use fluvio_controlplane::message::SpuMsg;
@@ -13,6 +14,8 @@ use fluvio_controlplane::sc_api::api::InternalScRequest;
 use fluvio_controlplane::sc_api::register_spu::RegisterSpuResponse;
 use fluvio_controlplane::sc_api::remove::ReplicaRemovedRequest;
 use fluvio_controlplane::sc_api::update_lrs::UpdateLrsRequest;
+use fluvio_controlplane::spu_api::update_quota::UpdateQuotaRequest;
+use fluvio_controlplane::spu_api::update_policy::UpdatePolicyRequest;
 use fluvio_controlplane::spu_api::update_replica::UpdateReplicaRequest;
 use fluvio_controlplane::spu_api::update_smartmodule::UpdateSmartModuleRequest;
 use fluvio_controlplane::spu_api::update_spu::UpdateSpuRequest;
@@ -28,12 +31,15 @@ use anyhow::Result;
 use fluvio_future::timer::sleep;
 use fluvio_service::ConnectInfo;
 use fluvio_controlplane_metadata::smartmodule::SmartModuleSpec;
+use fluvio_controlplane_metadata::quota::QuotaSpec;
 use fluvio_types::SpuId;
+use fluvio_types::event::offsets::OffsetChangeListener;
 use fluvio_protocol::api::RequestMessage;
 use fluvio_service::{FluvioService, wait_for_request};
 use fluvio_socket::{FluvioSocket, SocketError, FluvioSink};
 
 use crate::core::SharedContext;
+use crate::services::auth::basic::SharedRbacPolicy;
 use crate::stores::partition::PartitonStatusExtension;
 use crate::stores::partition::{PartitionSpec, PartitionStatus, PartitionResolution};
 use crate::stores::spu::SpuLocalStorePolicy;
@@ -44,12 +50,17 @@ const HEALTH_DURATION: u64 = 90;
 
 #[derive(Debug)]
 pub struct ScInternalService<C> {
+    /// policy of SPU public API, SPUs authorize clients only if set
+    auth_policy: Option<SharedRbacPolicy>,
     data: PhantomData<C>,
 }
 
 impl<C> ScInternalService<C> {
-    pub fn new() -> Self {
-        Self { data: PhantomData }
+    pub fn new(auth_policy: Option<SharedRbacPolicy>) -> Self {
+        Self {
+            auth_policy,
+            data: PhantomData,
+        }
     }
 }
 
@@ -103,7 +114,22 @@ where
 
         health_check.update(spu_id, true).await;
 
-        if let Err(err) = dispatch_loop(context, spu_id, api_stream, sink).await {
+        let policy_listener = self
+            .auth_policy
+            .as_ref()
+            .map(SharedRbacPolicy::change_listener);
+        send_auth_policy(self.auth_policy.as_ref(), &mut sink, spu_id).await?;
+
+        if let Err(err) = dispatch_loop(
+            context,
+            spu_id,
+            api_stream,
+            sink,
+            self.auth_policy.as_ref(),
+            policy_listener,
+        )
+        .await
+        {
             error!("error with SPU <{}>, error: {}", spu_id, err);
         }
 
@@ -116,12 +142,17 @@ where
 }
 
 // perform internal dispatch
-#[instrument(name = "ScInternalService", skip(context, api_stream))]
+#[instrument(
+    name = "ScInternalService",
+    skip(context, api_stream, auth_policy, policy_listener)
+)]
 async fn dispatch_loop<C>(
     context: SharedContext<C>,
     spu_id: SpuId,
     mut api_stream: impl Stream<Item = Result<InternalScRequest, SocketError>> + Unpin,
     mut sink: FluvioSink,
+    auth_policy: Option<&SharedRbacPolicy>,
+    mut policy_listener: Option<OffsetChangeListener>,
 ) -> Result<(), SocketError>
 where
     C: MetadataItem,
@@ -129,6 +160,7 @@ where
     let mut spu_spec_listener = context.spus().change_listener();
     let mut partition_spec_listener = context.partitions().change_listener();
     let mut sm_spec_listener = context.smartmodules().change_listener();
+    let mut quota_spec_listener = context.quotas().change_listener();
 
     // send initial changes
 
@@ -140,6 +172,7 @@ where
 
         send_spu_spec_changes(&mut spu_spec_listener, &mut sink, spu_id).await?;
         send_smartmodule_changes(&mut sm_spec_listener, &mut sink, spu_id).await?;
+        send_quota_changes(&mut quota_spec_listener, &mut sink, spu_id).await?;
         send_replica_spec_changes(&mut partition_spec_listener, &mut sink, spu_id).await?;
 
         trace!(spu_id, "waiting for SPU channel");
@@ -194,6 +227,15 @@ where
 
             }
 
+            _ = quota_spec_listener.listen() => {
+                debug!("quota lister changed");
+            }
+
+            _ = policy_changed(&mut policy_listener) => {
+                debug!("auth policy changed");
+                send_auth_policy(auth_policy, &mut sink, spu_id).await?;
+            }
+
         }
     }
 
@@ -450,3 +492,90 @@ async fn send_smartmodule_changes<C: MetadataItem>(
     sink.send_request(&message).await?;
     Ok(())
 }
+
+/// wait until auth policy is replaced, never completes if there is no policy
+async fn policy_changed(listener: &mut Option<OffsetChangeListener>) {
+    match listener {
+        Some(listener) => {
+            listener.listen().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// send authorization policy of clients to spu
+#[instrument(skip(policy, sink))]
+async fn send_auth_policy(
+    policy: Option<&SharedRbacPolicy>,
+    sink: &mut FluvioSink,
+    spu_id: SpuId,
+) -> Result<(), SocketError> {
+    let policy = policy
+        .map(|policy| serde_json::to_string(policy.current().as_ref()))
+        .transpose()
+        .map_err(|err| IoError::new(ErrorKind::InvalidData, err))?;
+    debug!(has_policy = policy.is_some(), "sending auth policy to spu");
+
+    let mut message = RequestMessage::new_request(UpdatePolicyRequest::new(policy));
+    message.get_mut_header().set_client_id("sc");
+
+    sink.send_request(&message).await?;
+    Ok(())
+}
+
+#[instrument(level = "trace", skip(sink))]
+async fn send_quota_changes<C: MetadataItem>(
+    listener: &mut ChangeListener<QuotaSpec, C>,
+    sink: &mut FluvioSink,
+    spu_id: SpuId,
+) -> Result<(), SocketError> {
+    use crate::stores::ChangeFlag;
+
+    if !listener.has_change() {
+        trace!("changes is empty, skipping");
+        return Ok(());
+    }
+
+    let changes = listener
+        .sync_changes_with_filter(&ChangeFlag {
+            spec: true,
+            status: false,
+            meta: true,
+        })
+        .await;
+    if changes.is_empty() {
+        trace!("spec changes is empty, skipping");
+        return Ok(());
+    }
+
+    let epoch = changes.epoch;
+
+    let is_sync_all = changes.is_sync_all();
+    let (updates, deletes) = changes.parts();
+
+    let request = if is_sync_all {
+        UpdateQuotaRequest::with_all(
+            epoch,
+            updates.into_iter().map(|quota| quota.into()).collect(),
+        )
+    } else {
+        let mut changes: Vec<QuotaMsg> = updates
+            .into_iter()
+            .map(|quota| Message::update(quota.into()))
+            .collect();
+        let mut deletes = deletes
+            .into_iter()
+            .map(|quota| Message::delete(quota.into()))
+            .collect();
+        changes.append(&mut deletes);
+        UpdateQuotaRequest::with_changes(epoch, changes)
+    };
+
+    debug!(?request, "sending quotas to spu");
+
+    let mut message = RequestMessage::new_request(request);
+    message.get_mut_header().set_client_id("sc");
+
+    sink.send_request(&message).await?;
+    Ok(())
+}
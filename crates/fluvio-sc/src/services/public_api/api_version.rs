@@ -8,9 +8,17 @@ use fluvio_protocol::link::versions::{
     ApiVersionKey, ApiVersionsRequest, ApiVersionsResponse, PlatformVersion,
 };
 use fluvio_sc_schema::objects::{
-    ObjectApiCreateRequest, ObjectApiDeleteRequest, ObjectApiListRequest, ObjectApiWatchRequest,
+    ObjectApiCreateRequest, ObjectApiDeleteRequest, ObjectApiListRequest, ObjectApiUpdateRequest,
+    ObjectApiWatchRequest,
 };
 use fluvio_sc_schema::AdminPublicApiKey;
+use fluvio_sc_schema::consumer_group::{
+    ConsumerGroupHeartbeatRequest, FetchConsumerGroupOffsetsRequest, LeaveConsumerGroupRequest,
+    ResetConsumerGroupOffsetsRequest,
+};
+use fluvio_sc_schema::producer::InitProducerIdRequest;
+use fluvio_sc_schema::transaction::{AddOffsetsToTxnRequest, AddPartitionsToTxnRequest, EndTxnRequest};
+use fluvio_sc_schema::partition::{ElectPreferredLeaderRequest, ReassignPartitionsRequest};
 
 // Fluvi Client version 0.14.0 corresponds to Platform version 10.0.0
 
@@ -52,6 +60,70 @@ pub async fn handle_api_versions_request(
         ObjectApiWatchRequest::MAX_API_VERSION,
     ));
 
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::Update,
+        ObjectApiUpdateRequest::MIN_API_VERSION,
+        ObjectApiUpdateRequest::MAX_API_VERSION,
+    ));
+
+    // consumer group versions
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::ConsumerGroupHeartbeat,
+        ConsumerGroupHeartbeatRequest::DEFAULT_API_VERSION,
+        ConsumerGroupHeartbeatRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::LeaveConsumerGroup,
+        LeaveConsumerGroupRequest::DEFAULT_API_VERSION,
+        LeaveConsumerGroupRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::FetchConsumerGroupOffsets,
+        FetchConsumerGroupOffsetsRequest::DEFAULT_API_VERSION,
+        FetchConsumerGroupOffsetsRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::ResetConsumerGroupOffsets,
+        ResetConsumerGroupOffsetsRequest::DEFAULT_API_VERSION,
+        ResetConsumerGroupOffsetsRequest::DEFAULT_API_VERSION,
+    ));
+
+    // idempotent producer versions
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::InitProducerId,
+        InitProducerIdRequest::DEFAULT_API_VERSION,
+        InitProducerIdRequest::DEFAULT_API_VERSION,
+    ));
+
+    // transaction versions
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::AddPartitionsToTxn,
+        AddPartitionsToTxnRequest::DEFAULT_API_VERSION,
+        AddPartitionsToTxnRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::AddOffsetsToTxn,
+        AddOffsetsToTxnRequest::DEFAULT_API_VERSION,
+        AddOffsetsToTxnRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::EndTxn,
+        EndTxnRequest::DEFAULT_API_VERSION,
+        EndTxnRequest::DEFAULT_API_VERSION,
+    ));
+
+    // partition reassignment versions
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::ReassignPartitions,
+        ReassignPartitionsRequest::DEFAULT_API_VERSION,
+        ReassignPartitionsRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        AdminPublicApiKey::ElectPreferredLeader,
+        ElectPreferredLeaderRequest::DEFAULT_API_VERSION,
+        ElectPreferredLeaderRequest::DEFAULT_API_VERSION,
+    ));
+
     trace!("flv api versions response: {:#?}", response);
 
     Ok(request.new_response(response))
@@ -0,0 +1,330 @@
+//!
+//! # Consumer Group Request
+//!
+//! Heartbeat and leave requests from members of consumer groups,
+//! and requests for offsets committed by consumer groups.
+//! Offsets are stored by partition leaders, SC forwards requests to private endpoint of leaders.
+//!
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use tracing::{debug, trace, instrument};
+use anyhow::{anyhow, Result};
+
+use fluvio_protocol::api::{Request, RequestMessage, ResponseMessage};
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::ReplicaKey;
+use fluvio_sc_schema::consumer_group::{
+    ConsumerGroupHeartbeatRequest, ConsumerGroupHeartbeatResponse, ConsumerGroupOffset,
+    FetchConsumerGroupOffsetsRequest, FetchConsumerGroupOffsetsResponse, LeaveConsumerGroupRequest,
+    LeaveConsumerGroupResponse, ResetConsumerGroupOffsetsRequest,
+    ResetConsumerGroupOffsetsResponse,
+};
+use fluvio_sc_schema::topic::TopicSpec;
+use fluvio_socket::FluvioSocket;
+use fluvio_controlplane::offset_api::{
+    FetchGroupOffsetsRequest, GroupOffsetResult, ResetGroupOffsetsRequest,
+};
+use fluvio_types::{PartitionCount, SpuId};
+use fluvio_auth::{AuthContext, InstanceAction};
+use fluvio_stream_model::core::MetadataItem;
+use fluvio_controlplane_metadata::extended::SpecExt;
+
+use crate::core::Context;
+use crate::services::auth::AuthServiceContext;
+use crate::stores::spu::SpuLocalStorePolicy;
+
+/// Handler for consumer group heartbeat request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_consumer_group_heartbeat_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<ConsumerGroupHeartbeatRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<ConsumerGroupHeartbeatResponse>> {
+    let req = request.request();
+    debug!(group = %req.group, topic = %req.topic, member_id = %req.member_id, "consumer group heartbeat");
+
+    if !allow_topic_action(auth_ctx, InstanceAction::Read, &req.topic).await? {
+        trace!("authorization failed");
+        return Ok(request.new_response(ConsumerGroupHeartbeatResponse {
+            error_code: ErrorCode::PermissionDenied,
+            ..Default::default()
+        }));
+    }
+
+    if req.group.trim().is_empty() {
+        return Ok(request.new_response(ConsumerGroupHeartbeatResponse {
+            error_code: ErrorCode::ConsumerGroupInvalidName(req.group.clone()),
+            ..Default::default()
+        }));
+    }
+
+    let Some(topic) = auth_ctx.global_ctx.topics().store().value(&req.topic).await else {
+        return Ok(request.new_response(ConsumerGroupHeartbeatResponse {
+            error_code: ErrorCode::TopicNotFound,
+            ..Default::default()
+        }));
+    };
+
+    let response = match auth_ctx
+        .global_ctx
+        .consumer_groups()
+        .heartbeat(
+            &req.group,
+            &req.topic,
+            &req.member_id,
+            req.generation,
+            req.strategy,
+            Duration::from_millis(req.session_timeout_ms as u64),
+            topic.spec.partitions(),
+        )
+        .await
+    {
+        Ok(assignment) => ConsumerGroupHeartbeatResponse {
+            error_code: ErrorCode::None,
+            member_id: assignment.member_id,
+            generation: assignment.generation,
+            partitions: assignment.partitions,
+        },
+        Err(error_code) => ConsumerGroupHeartbeatResponse {
+            error_code,
+            ..Default::default()
+        },
+    };
+
+    trace!("consumer group heartbeat response {:#?}", response);
+
+    Ok(request.new_response(response))
+}
+
+/// Handler for leave consumer group request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_leave_consumer_group_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<LeaveConsumerGroupRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<LeaveConsumerGroupResponse>> {
+    let req = request.request();
+    debug!(group = %req.group, member_id = %req.member_id, "leave consumer group");
+
+    // member of unknown group is rejected by leave
+    let topic = auth_ctx
+        .global_ctx
+        .consumer_groups()
+        .topic(&req.group)
+        .await;
+    let allowed = match &topic {
+        Some(topic) => allow_topic_action(auth_ctx, InstanceAction::Read, topic).await?,
+        None => true,
+    };
+
+    let error_code = if !allowed {
+        trace!("authorization failed");
+        ErrorCode::PermissionDenied
+    } else if let Err(error_code) = auth_ctx
+        .global_ctx
+        .consumer_groups()
+        .leave(&req.group, &req.member_id)
+        .await
+    {
+        error_code
+    } else {
+        ErrorCode::None
+    };
+
+    Ok(request.new_response(LeaveConsumerGroupResponse { error_code }))
+}
+
+/// Handler for fetch consumer group offsets request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_fetch_consumer_group_offsets_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<FetchConsumerGroupOffsetsRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<FetchConsumerGroupOffsetsResponse>> {
+    let req = request.request();
+    debug!(group = %req.group, topic = %req.topic, "fetch consumer group offsets");
+
+    let response =
+        match group_partitions(auth_ctx, InstanceAction::Read, &req.group, &req.topic).await? {
+            Ok(partition_count) => FetchConsumerGroupOffsetsResponse {
+                error_code: ErrorCode::None,
+                partitions: send_to_leaders(
+                    &auth_ctx.global_ctx,
+                    &req.topic,
+                    partition_count,
+                    |replicas| FetchGroupOffsetsRequest {
+                        consumer_group: req.group.clone(),
+                        replicas,
+                    },
+                    |response| response.partitions,
+                )
+                .await,
+            },
+            Err(error_code) => FetchConsumerGroupOffsetsResponse {
+                error_code,
+                ..Default::default()
+            },
+        };
+
+    trace!("fetch consumer group offsets response {:#?}", response);
+
+    Ok(request.new_response(response))
+}
+
+/// Handler for reset consumer group offsets request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_reset_consumer_group_offsets_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<ResetConsumerGroupOffsetsRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<ResetConsumerGroupOffsetsResponse>> {
+    let req = request.request();
+    debug!(group = %req.group, topic = %req.topic, "reset consumer group offsets");
+
+    let partition_count =
+        match group_partitions(auth_ctx, InstanceAction::Write, &req.group, &req.topic).await? {
+            Ok(partition_count) => partition_count,
+            Err(error_code) => {
+                return Ok(request.new_response(ResetConsumerGroupOffsetsResponse {
+                    error_code,
+                    ..Default::default()
+                }))
+            }
+        };
+
+    // live members would commit offsets again
+    if auth_ctx
+        .global_ctx
+        .consumer_groups()
+        .topic(&req.group)
+        .await
+        .is_some()
+    {
+        return Ok(request.new_response(ResetConsumerGroupOffsetsResponse {
+            error_code: ErrorCode::ConsumerGroupNotEmpty(req.group.clone()),
+            ..Default::default()
+        }));
+    }
+
+    let partitions = send_to_leaders(
+        &auth_ctx.global_ctx,
+        &req.topic,
+        partition_count,
+        |replicas| ResetGroupOffsetsRequest {
+            consumer_group: req.group.clone(),
+            replicas,
+        },
+        |response| response.partitions,
+    )
+    .await;
+
+    Ok(request.new_response(ResetConsumerGroupOffsetsResponse {
+        error_code: ErrorCode::None,
+        partitions,
+    }))
+}
+
+/// check offset request of consumer group and return partition count of its topic
+async fn group_partitions<AC: AuthContext, C: MetadataItem>(
+    auth_ctx: &AuthServiceContext<AC, C>,
+    action: InstanceAction,
+    group: &str,
+    topic: &str,
+) -> Result<Result<PartitionCount, ErrorCode>> {
+    if !allow_topic_action(auth_ctx, action, topic).await? {
+        trace!("authorization failed");
+        return Ok(Err(ErrorCode::PermissionDenied));
+    }
+    if group.trim().is_empty() {
+        return Ok(Err(ErrorCode::ConsumerGroupInvalidName(group.to_owned())));
+    }
+    match auth_ctx.global_ctx.topics().store().value(topic).await {
+        Some(topic) => Ok(Ok(topic.spec.partitions())),
+        None => Ok(Err(ErrorCode::TopicNotFound)),
+    }
+}
+
+/// send request for partitions of the topic to their leaders,
+/// partitions whose leader can't be reached get the error
+async fn send_to_leaders<C, R>(
+    ctx: &Context<C>,
+    topic: &str,
+    partition_count: PartitionCount,
+    request: impl Fn(Vec<ReplicaKey>) -> R,
+    results: impl Fn(R::Response) -> Vec<GroupOffsetResult>,
+) -> Vec<ConsumerGroupOffset>
+where
+    C: MetadataItem,
+    R: Request,
+{
+    let mut offsets = vec![];
+    let mut leaders: BTreeMap<SpuId, Vec<ReplicaKey>> = BTreeMap::new();
+    for partition in 0..partition_count {
+        let replica = ReplicaKey::new(topic, partition);
+        match ctx.partitions().store().value(&replica).await {
+            Some(metadata) => leaders
+                .entry(metadata.spec.leader)
+                .or_default()
+                .push(replica),
+            None => offsets.push(ConsumerGroupOffset {
+                partition,
+                error_code: ErrorCode::PartitionNotFound,
+                offset: None,
+            }),
+        }
+    }
+
+    for (leader, replicas) in leaders {
+        let partitions: Vec<_> = replicas.iter().map(|replica| replica.partition).collect();
+        let response = match connect_to_spu_private(ctx, leader).await {
+            Ok(mut socket) => socket
+                .send(&RequestMessage::new_request(request(replicas)))
+                .await
+                .map_err(|err| ErrorCode::Other(format!("spu {leader}: {err}"))),
+            Err(error_code) => Err(error_code),
+        };
+        match response {
+            Ok(response) => offsets.extend(results(response.response).into_iter().map(|result| {
+                ConsumerGroupOffset {
+                    partition: result.replica.partition,
+                    error_code: result.error_code,
+                    offset: result.offset,
+                }
+            })),
+            Err(error_code) => {
+                offsets.extend(partitions.into_iter().map(|partition| ConsumerGroupOffset {
+                    partition,
+                    error_code: error_code.clone(),
+                    offset: None,
+                }))
+            }
+        }
+    }
+    offsets.sort_by_key(|offset| offset.partition);
+    offsets
+}
+
+/// connect to private endpoint of the SPU, offsets of any group are served only there
+async fn connect_to_spu_private<C: MetadataItem>(
+    ctx: &Context<C>,
+    spu_id: SpuId,
+) -> Result<FluvioSocket, ErrorCode> {
+    let Some(spu) = ctx.spus().store().get_by_id(spu_id).await else {
+        return Err(ErrorCode::SpuNotFound);
+    };
+    FluvioSocket::connect(&spu.spec.private_endpoint.to_string())
+        .await
+        .map_err(|err| ErrorCode::Other(format!("spu {spu_id}: {err}")))
+}
+
+/// members of consumer group must be allowed to read its topic,
+/// resetting offsets of the group requires write like commit does
+async fn allow_topic_action<AC: AuthContext, C: MetadataItem>(
+    auth_ctx: &AuthServiceContext<AC, C>,
+    action: InstanceAction,
+    topic: &str,
+) -> Result<bool> {
+    auth_ctx
+        .auth
+        .allow_instance_action(TopicSpec::OBJECT_TYPE, action, topic)
+        .await
+        .map_err(|_| anyhow!("authorization io error"))
+}
@@ -7,6 +7,7 @@ use fluvio_controlplane_metadata::smartmodule::SmartModuleSpec;
 use fluvio_controlplane_metadata::spg::SpuGroupSpec;
 use fluvio_controlplane_metadata::spu::CustomSpuSpec;
 use fluvio_controlplane_metadata::tableformat::TableFormatSpec;
+use fluvio_controlplane_metadata::quota::QuotaSpec;
 use fluvio_controlplane_metadata::topic::TopicSpec;
 use fluvio_protocol::api::{RequestMessage, ResponseMessage};
 use fluvio_sc_schema::{Status, TryEncodableFrom};
@@ -35,6 +36,8 @@ pub async fn handle_create_request<AC: AuthContext, C: MetadataItem>(
         super::smartmodule::handle_create_smartmodule_request(create, auth_context).await?
     } else if let Some(create) = req.downcast()? as Option<CreateRequest<TableFormatSpec>> {
         super::tableformat::handle_create_tableformat_request(create, auth_context).await?
+    } else if let Some(create) = req.downcast()? as Option<CreateRequest<QuotaSpec>> {
+        super::quota::handle_create_quota_request(create, auth_context).await?
     } else {
         error!("unknown create request: {:#?}", req);
         Status::new(
@@ -61,7 +64,7 @@ mod create_handler {
     use fluvio_sc_schema::{AdminSpec, Status};
     use fluvio_sc_schema::objects::CommonCreateRequest;
     use fluvio_controlplane_metadata::extended::SpecExt;
-    use fluvio_auth::{AuthContext, TypeAction};
+    use fluvio_auth::{AuthContext, TypeAction, InstanceAction};
 
     use crate::services::auth::AuthServiceContext;
 
@@ -82,11 +85,20 @@ mod create_handler {
 
         info!(%name, ty = %S::LABEL,"creating");
 
-        if let Ok(authorized) = auth_ctx
+        let authorized = match auth_ctx
             .auth
             .allow_type_action(S::OBJECT_TYPE, TypeAction::Create)
             .await
         {
+            Ok(true) => {
+                auth_ctx
+                    .auth
+                    .allow_instance_action(S::OBJECT_TYPE, InstanceAction::Create, &name)
+                    .await
+            }
+            other => other,
+        };
+        if let Ok(authorized) = authorized {
             if !authorized {
                 trace!("authorization failed");
                 return Ok(Status::new(
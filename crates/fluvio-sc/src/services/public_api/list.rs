@@ -5,6 +5,7 @@ use fluvio_controlplane_metadata::{
     partition::PartitionSpec,
     smartmodule::SmartModuleSpec,
     tableformat::TableFormatSpec,
+    quota::QuotaSpec,
 };
 use fluvio_stream_model::core::MetadataItem;
 use tracing::{debug, instrument};
@@ -74,6 +75,12 @@ pub async fn handle_list_request<AC: AuthContext, C: MetadataItem>(
             .await?,
             header.api_version(),
         )?
+    } else if let Some(req) = req.downcast()? as Option<ListRequest<QuotaSpec>> {
+        ObjectApiListResponse::try_encode_from(
+            fetch::handle_fetch_request(req.name_filters, auth_ctx, auth_ctx.global_ctx.quotas())
+                .await?,
+            header.api_version(),
+        )?
     } else {
         return Err(anyhow::anyhow!("unsupported list request: {:#?}", req));
     };
@@ -95,7 +102,7 @@ mod fetch {
     use tracing::{debug, trace, instrument};
 
     use fluvio_sc_schema::objects::{ListResponse, Metadata, ListFilters};
-    use fluvio_auth::{AuthContext, TypeAction};
+    use fluvio_auth::{AuthContext, TypeAction, InstanceAction};
     use fluvio_controlplane_metadata::store::MetadataStoreObject;
     use fluvio_controlplane_metadata::extended::SpecExt;
     use fluvio_controlplane_metadata::store::KeyFilter;
@@ -132,17 +139,22 @@ mod fetch {
         }
 
         let reader = object_ctx.store().read().await;
-        let objects: Vec<Metadata<S>> = reader
-            .values()
-            .filter_map(|value| {
-                if filters.filter(value.key().as_ref()) {
-                    let list_obj: Metadata<S> = AdminSpec::convert_from(value);
-                    Some(list_obj)
-                } else {
-                    None
-                }
-            })
-            .collect();
+        let mut objects: Vec<Metadata<S>> = vec![];
+        for value in reader.values() {
+            let key = value.key().as_ref();
+            if !filters.filter(key) {
+                continue;
+            }
+            // skip instances which are not readable by policy
+            let readable = auth_ctx
+                .auth
+                .allow_instance_action(S::OBJECT_TYPE, InstanceAction::Read, key)
+                .await
+                .map_err(|_| Error::new(ErrorKind::Interrupted, "authorization io error"))?;
+            if readable {
+                objects.push(AdminSpec::convert_from(value));
+            }
+        }
 
         debug!(fetch_items = objects.len(),);
         trace!("fetch {:#?}", objects);
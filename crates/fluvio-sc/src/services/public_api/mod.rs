@@ -7,12 +7,18 @@ mod partition;
 mod api_version;
 mod create;
 mod delete;
+mod update;
 mod list;
 mod watch;
 mod tableformat;
+mod quota;
 mod derivedstream;
+mod consumer_group;
+mod producer;
+mod transaction;
 
 pub use server::start_public_server;
+pub(crate) use transaction::{end_transaction, persist_transaction};
 
 mod server {
 
@@ -35,7 +41,7 @@ mod server {
         C::UId: Send + Sync,
 
         AuthGlobalContext<A, C>: Clone + Debug,
-        <A as Authorization>::Context: Send + Sync,
+        <A as Authorization>::Context: Send + Sync + 'static,
     {
         let addr = ctx.global_ctx.config().public_endpoint.clone();
         debug!("starting public api service");
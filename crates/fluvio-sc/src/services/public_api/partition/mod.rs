@@ -1,3 +1,5 @@
+pub(crate) mod reassignment;
+
 use std::io::{Error, ErrorKind};
 
 use fluvio_stream_model::core::MetadataItem;
@@ -7,7 +9,7 @@ use anyhow::Result;
 use fluvio_sc_schema::objects::{ListResponse, Metadata, ListFilters};
 use fluvio_sc_schema::partition::PartitionSpec;
 use fluvio_controlplane_metadata::extended::SpecExt;
-use fluvio_auth::{AuthContext, TypeAction};
+use fluvio_auth::{AuthContext, TypeAction, InstanceAction};
 
 use crate::services::auth::AuthServiceContext;
 
@@ -31,15 +33,23 @@ pub async fn handle_fetch_request<AC: AuthContext, C: MetadataItem>(
         return Err(Error::new(ErrorKind::Interrupted, "authorization io error").into());
     }
 
-    let partitions: Vec<Metadata<PartitionSpec>> = auth_ctx
-        .global_ctx
-        .partitions()
-        .store()
-        .read()
-        .await
-        .values()
-        .map(|value| value.inner().clone().into())
-        .collect();
+    let reader = auth_ctx.global_ctx.partitions().store().read().await;
+    let mut partitions: Vec<Metadata<PartitionSpec>> = vec![];
+    for value in reader.values() {
+        // skip partitions which are not readable by policy
+        let readable = auth_ctx
+            .auth
+            .allow_instance_action(
+                PartitionSpec::OBJECT_TYPE,
+                InstanceAction::Read,
+                &value.key().to_string(),
+            )
+            .await
+            .map_err(|_| Error::new(ErrorKind::Interrupted, "authorization io error"))?;
+        if readable {
+            partitions.push(value.inner().clone().into());
+        }
+    }
 
     debug!("flv fetch partitions resp: {} items", partitions.len());
     trace!("flv fetch partitions resp {:#?}", partitions);
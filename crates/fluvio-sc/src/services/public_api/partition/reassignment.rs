@@ -0,0 +1,259 @@
+//!
+//! # Partition Reassignment Requests
+//!
+//! Handlers only update partition spec. Partition controller moves reassignment forward
+//! as new replicas catch up with leader.
+//!
+
+use std::collections::HashSet;
+
+use tracing::{debug, info, trace, instrument};
+use anyhow::{anyhow, Result};
+
+use fluvio_protocol::api::{RequestMessage, ResponseMessage};
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::ReplicaKey;
+use fluvio_sc_schema::partition::{
+    ElectPreferredLeaderRequest, ElectPreferredLeaderResponse, ElectionResult, PartitionAssignment,
+    PartitionReassignment, PartitionSpec, ReassignPartitionsRequest, ReassignPartitionsResponse,
+    ReassignmentResult,
+};
+use fluvio_controlplane_metadata::topic::TopicReplicaParam;
+use fluvio_controlplane_metadata::extended::SpecExt;
+use fluvio_auth::{AuthContext, InstanceAction};
+use fluvio_stream_model::core::MetadataItem;
+use fluvio_types::{PartitionId, ReplicationFactor, SpuId};
+
+use crate::core::Context;
+use crate::controllers::scheduler::PartitionScheduler;
+use crate::services::auth::AuthServiceContext;
+use crate::stores::partition::{PartitionLocalStorePolicy, PartitonStatusExtension, SimplePolicy};
+use crate::stores::spu::SpuLocalStorePolicy;
+
+/// Handler for reassign partitions request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_reassign_partitions_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<ReassignPartitionsRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<ReassignPartitionsResponse>> {
+    let req = request.request();
+    debug!(
+        assignments = req.assignments.len(),
+        balance_topics = ?req.balance_topics,
+        dry_run = req.dry_run,
+        "reassign partitions"
+    );
+
+    let ctx = &auth_ctx.global_ctx;
+
+    let mut results = vec![];
+    let mut assignments = req.assignments.clone();
+    for topic in &req.balance_topics {
+        match balanced_assignments(ctx, topic).await {
+            Ok(generated) => assignments.extend(generated),
+            Err(error_code) => results.push(ReassignmentResult {
+                assignment: PartitionAssignment::new((topic.clone(), 0), vec![]),
+                error_code,
+            }),
+        }
+    }
+
+    for assignment in assignments {
+        let error_code = if !allow_partition_update(auth_ctx, &assignment.replica).await? {
+            trace!("authorization failed");
+            ErrorCode::PermissionDenied
+        } else if let Err(error_code) = reassign_partition(ctx, &assignment, req.dry_run).await {
+            error_code
+        } else {
+            ErrorCode::None
+        };
+        results.push(ReassignmentResult {
+            assignment,
+            error_code,
+        });
+    }
+
+    Ok(request.new_response(ReassignPartitionsResponse { results }))
+}
+
+/// Handler for elect preferred leader request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_elect_preferred_leader_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<ElectPreferredLeaderRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<ElectPreferredLeaderResponse>> {
+    let ctx = &auth_ctx.global_ctx;
+
+    let mut partitions = request.request().partitions.clone();
+    // all partitions are only those client is allowed to update
+    let all_partitions = partitions.is_empty();
+    if all_partitions {
+        partitions = ctx.partitions().store().clone_keys().await;
+    }
+    debug!(partitions = partitions.len(), "elect preferred leader");
+
+    let mut results = vec![];
+    for replica in partitions {
+        let allowed = allow_partition_update(auth_ctx, &replica).await?;
+        if !allowed && all_partitions {
+            continue;
+        }
+        let (leader, error_code) = if !allowed {
+            trace!("authorization failed");
+            (-1, ErrorCode::PermissionDenied)
+        } else {
+            match elect_preferred_leader(ctx, &replica).await {
+                Ok(leader) => (leader, ErrorCode::None),
+                Err(error_code) => (-1, error_code),
+            }
+        };
+        results.push(ElectionResult {
+            replica,
+            leader,
+            error_code,
+        });
+    }
+
+    Ok(request.new_response(ElectPreferredLeaderResponse { results }))
+}
+
+/// validate assignment and start reassignment of the partition
+async fn reassign_partition<C: MetadataItem>(
+    ctx: &Context<C>,
+    assignment: &PartitionAssignment,
+    dry_run: bool,
+) -> Result<(), ErrorCode> {
+    let Some(partition) = ctx.partitions().store().value(&assignment.replica).await else {
+        return Err(ErrorCode::PartitionNotFound);
+    };
+    let spec = &partition.spec;
+    if spec.reassignment.is_some() {
+        return Err(ErrorCode::PartitionReassignmentInProgress);
+    }
+
+    let target = &assignment.replicas;
+    if target.is_empty() {
+        return Err(invalid("replicas are empty"));
+    }
+    if target.iter().collect::<HashSet<_>>().len() != target.len() {
+        return Err(invalid("replicas must be on different spus"));
+    }
+    let spu_ids = ctx.spus().store().spu_ids().await;
+    if let Some(spu) = target.iter().find(|spu| !spu_ids.contains(spu)) {
+        return Err(invalid(&format!("spu {spu} is not found")));
+    }
+
+    if dry_run || *target == spec.replicas {
+        return Ok(());
+    }
+
+    let reassignment = PartitionReassignment::new(&spec.replicas, target.clone());
+    let mut new_spec = spec.clone();
+    new_spec.replicas = reassignment.all_replicas(&spec.replicas);
+    new_spec.reassignment = Some(reassignment);
+    update_spec(ctx, assignment.replica.clone(), new_spec).await?;
+    info!(replica = %assignment.replica, replicas = ?target, "partition reassignment started");
+    Ok(())
+}
+
+/// generate assignment of topic's partitions balanced across online spus
+async fn balanced_assignments<C: MetadataItem>(
+    ctx: &Context<C>,
+    topic: &str,
+) -> Result<Vec<PartitionAssignment>, ErrorCode> {
+    let Some(topic_md) = ctx.topics().store().value(topic).await else {
+        return Err(ErrorCode::TopicNotFound);
+    };
+    let partitions = ctx.partitions().store().topic_partitions(topic).await;
+    let Some(first) = partitions.first() else {
+        return Err(ErrorCode::PartitionNotFound);
+    };
+    let replication_factor = first
+        .spec
+        .reassignment
+        .as_ref()
+        .map_or(first.spec.replicas.len(), |reassignment| {
+            reassignment.target_replicas.len()
+        }) as ReplicationFactor;
+
+    let param = TopicReplicaParam::new(
+        partitions.len() as PartitionId,
+        replication_factor,
+        topic_md.spec.replicas().ignore_rack_assignment(),
+    );
+    let mut scheduler =
+        PartitionScheduler::init(ctx.spus().store(), ctx.partitions().store()).await;
+    let replica_map = scheduler
+        .generate_replica_map_for_topic(&param)
+        .await
+        .map_err(|err| invalid(&err.to_string()))?;
+
+    Ok(replica_map
+        .iter()
+        .map(|(partition, replicas)| {
+            PartitionAssignment::new(ReplicaKey::new(topic, *partition), replicas.clone())
+        })
+        .collect())
+}
+
+/// move leader to first replica if it is in sync
+async fn elect_preferred_leader<C: MetadataItem>(
+    ctx: &Context<C>,
+    replica: &ReplicaKey,
+) -> Result<SpuId, ErrorCode> {
+    let Some(partition) = ctx.partitions().store().value(replica).await else {
+        return Err(ErrorCode::PartitionNotFound);
+    };
+    if partition.spec.reassignment.is_some() {
+        return Err(ErrorCode::PartitionReassignmentInProgress);
+    }
+    let Some(preferred) = partition.spec.replicas.first().copied() else {
+        return Err(ErrorCode::PreferredLeaderNotAvailable);
+    };
+    if partition.spec.leader == preferred {
+        return Ok(preferred);
+    }
+
+    let online = ctx.spus().store().online_status().await;
+    if !online.contains(&preferred) || !partition.status.is_in_sync(preferred, &SimplePolicy::new())
+    {
+        return Err(ErrorCode::PreferredLeaderNotAvailable);
+    }
+
+    let mut new_spec = partition.spec.clone();
+    new_spec.leader = preferred;
+    update_spec(ctx, replica.clone(), new_spec).await?;
+    info!(%replica, leader = preferred, "preferred leader elected");
+    Ok(preferred)
+}
+
+async fn update_spec<C: MetadataItem>(
+    ctx: &Context<C>,
+    replica: ReplicaKey,
+    spec: PartitionSpec,
+) -> Result<(), ErrorCode> {
+    ctx.partitions()
+        .create_spec(replica, spec)
+        .await
+        .map(|_| ())
+        .map_err(|err| ErrorCode::Other(err.to_string()))
+}
+
+fn invalid(message: &str) -> ErrorCode {
+    ErrorCode::PartitionReassignmentInvalid(message.to_owned())
+}
+
+async fn allow_partition_update<AC: AuthContext, C: MetadataItem>(
+    auth_ctx: &AuthServiceContext<AC, C>,
+    replica: &ReplicaKey,
+) -> Result<bool> {
+    auth_ctx
+        .auth
+        .allow_instance_action(
+            PartitionSpec::OBJECT_TYPE,
+            InstanceAction::Update,
+            &replica.to_string(),
+        )
+        .await
+        .map_err(|_| anyhow!("authorization io error"))
+}
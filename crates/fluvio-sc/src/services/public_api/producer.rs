@@ -0,0 +1,102 @@
+//!
+//! # Init Producer Id Request
+//!
+//! Allocates producer id for idempotent producers.
+//! Transactional producers get the same producer id with incremented epoch,
+//! open transaction of previous instance is aborted first.
+//!
+
+use tracing::{debug, trace, instrument};
+use anyhow::{anyhow, Result};
+
+use fluvio_protocol::api::{RequestMessage, ResponseMessage};
+use fluvio_protocol::link::ErrorCode;
+use fluvio_sc_schema::producer::{InitProducerIdRequest, InitProducerIdResponse};
+use fluvio_sc_schema::shared::validate_resource_name;
+use fluvio_sc_schema::topic::TopicSpec;
+use fluvio_auth::{AuthContext, TypeAction};
+use fluvio_stream_model::core::MetadataItem;
+use fluvio_controlplane_metadata::extended::SpecExt;
+
+use crate::services::auth::AuthServiceContext;
+
+use super::transaction::{allow_transaction, end_transaction, persist_transaction};
+
+/// Handler for init producer id request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_init_producer_id_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<InitProducerIdRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<InitProducerIdResponse>> {
+    let allowed = auth_ctx
+        .auth
+        .allow_type_action(TopicSpec::OBJECT_TYPE, TypeAction::Read)
+        .await
+        .map_err(|_| anyhow!("authorization io error"))?;
+    if !allowed {
+        trace!("authorization failed");
+        return Ok(request.new_response(InitProducerIdResponse {
+            error_code: ErrorCode::PermissionDenied,
+            ..Default::default()
+        }));
+    }
+
+    let ctx = &auth_ctx.global_ctx;
+    let Some(transactional_id) = request.request().transactional_id.clone() else {
+        let producer_id = ctx.producer_ids().next_id();
+        debug!(producer_id, "allocated producer id");
+
+        return Ok(request.new_response(InitProducerIdResponse {
+            error_code: ErrorCode::None,
+            producer_id,
+            producer_epoch: 0,
+        }));
+    };
+
+    // transactional id is the key of persisted transaction state
+    if let Err(err) = validate_resource_name(&transactional_id) {
+        return Ok(request.new_response(InitProducerIdResponse {
+            error_code: ErrorCode::InvalidTransactionState(format!(
+                "invalid transactional id: '{transactional_id}'. {err}"
+            )),
+            ..Default::default()
+        }));
+    }
+
+    // new instance aborts open transaction of previous one
+    if !allow_transaction(auth_ctx, &transactional_id).await? {
+        trace!("authorization failed");
+        return Ok(request.new_response(InitProducerIdResponse {
+            error_code: ErrorCode::PermissionDenied,
+            ..Default::default()
+        }));
+    }
+
+    let (producer_id, producer_epoch, pending) = ctx
+        .transactions()
+        .init_producer(&transactional_id, ctx.producer_ids())
+        .await;
+    // previous instances stay fenced after SC restart
+    if let Err(error_code) = persist_transaction(ctx, &transactional_id).await {
+        return Ok(request.new_response(InitProducerIdResponse {
+            error_code,
+            ..Default::default()
+        }));
+    }
+    if let Some(pending) = pending {
+        debug!(%transactional_id, commit = pending.commit, "ending transaction of previous instance");
+        if let Err(error_code) = end_transaction(ctx, &transactional_id, &pending).await {
+            return Ok(request.new_response(InitProducerIdResponse {
+                error_code,
+                ..Default::default()
+            }));
+        }
+    }
+    debug!(%transactional_id, producer_id, producer_epoch, "initialized transactional producer");
+
+    Ok(request.new_response(InitProducerIdResponse {
+        error_code: ErrorCode::None,
+        producer_id,
+        producer_epoch,
+    }))
+}
@@ -47,7 +47,7 @@ where
     A: Authorization + Sync + Send,
     C::UId: Send + Sync,
     C: MetadataItem + 'static,
-    <A as Authorization>::Context: Send + Sync,
+    <A as Authorization>::Context: Send + Sync + 'static,
 {
     type Context = AuthGlobalContext<A, C>;
     type Request = AdminPublicDecodedRequest;
@@ -103,6 +103,12 @@ where
                 shared_sink,
                 "delete  handler"
             ),
+            AdminPublicDecodedRequest::UpdateRequest(request) => call_service!(
+                request,
+                super::update::handle_update_request(request, &service_context),
+                shared_sink,
+                "update handler"
+            ),
 
             AdminPublicDecodedRequest::ListRequest(request) => call_service!(
                 request,
@@ -110,6 +116,72 @@ where
                 shared_sink,
                 "list handler"
             ),
+            AdminPublicDecodedRequest::ConsumerGroupHeartbeatRequest(request) => call_service!(
+                request,
+                super::consumer_group::handle_consumer_group_heartbeat_request(request, &service_context),
+                shared_sink,
+                "consumer group heartbeat handler"
+            ),
+            AdminPublicDecodedRequest::LeaveConsumerGroupRequest(request) => call_service!(
+                request,
+                super::consumer_group::handle_leave_consumer_group_request(request, &service_context),
+                shared_sink,
+                "leave consumer group handler"
+            ),
+            AdminPublicDecodedRequest::FetchConsumerGroupOffsetsRequest(request) => call_service!(
+                request,
+                super::consumer_group::handle_fetch_consumer_group_offsets_request(request, &service_context),
+                shared_sink,
+                "fetch consumer group offsets handler"
+            ),
+            AdminPublicDecodedRequest::ResetConsumerGroupOffsetsRequest(request) => call_service!(
+                request,
+                super::consumer_group::handle_reset_consumer_group_offsets_request(request, &service_context),
+                shared_sink,
+                "reset consumer group offsets handler"
+            ),
+            AdminPublicDecodedRequest::InitProducerIdRequest(request) => call_service!(
+                request,
+                super::producer::handle_init_producer_id_request(request, &service_context),
+                shared_sink,
+                "init producer id handler"
+            ),
+            AdminPublicDecodedRequest::AddPartitionsToTxnRequest(request) => call_service!(
+                request,
+                super::transaction::handle_add_partitions_to_txn_request(request, &service_context),
+                shared_sink,
+                "add partitions to transaction handler"
+            ),
+            AdminPublicDecodedRequest::AddOffsetsToTxnRequest(request) => call_service!(
+                request,
+                super::transaction::handle_add_offsets_to_txn_request(request, &service_context),
+                shared_sink,
+                "add offsets to transaction handler"
+            ),
+            AdminPublicDecodedRequest::EndTxnRequest(request) => call_service!(
+                request,
+                super::transaction::handle_end_txn_request(request, &service_context),
+                shared_sink,
+                "end transaction handler"
+            ),
+            AdminPublicDecodedRequest::ReassignPartitionsRequest(request) => call_service!(
+                request,
+                super::partition::reassignment::handle_reassign_partitions_request(
+                    request,
+                    &service_context
+                ),
+                shared_sink,
+                "reassign partitions handler"
+            ),
+            AdminPublicDecodedRequest::ElectPreferredLeaderRequest(request) => call_service!(
+                request,
+                super::partition::reassignment::handle_elect_preferred_leader_request(
+                    request,
+                    &service_context
+                ),
+                shared_sink,
+                "elect preferred leader handler"
+            ),
             AdminPublicDecodedRequest::WatchRequest(request) =>
 
                 super::watch::handle_watch_request(
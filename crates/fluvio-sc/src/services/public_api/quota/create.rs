@@ -0,0 +1,92 @@
+//!
+//! # Create Quota Request
+//!
+//! Converts Quota API request into KV request and sends to KV store for processing.
+//!
+
+use fluvio_stream_model::core::MetadataItem;
+use tracing::{debug, info, trace, instrument};
+use anyhow::{anyhow, Result};
+
+use fluvio_protocol::link::ErrorCode;
+use fluvio_sc_schema::Status;
+use fluvio_sc_schema::objects::CreateRequest;
+use fluvio_sc_schema::quota::QuotaSpec;
+use fluvio_controlplane_metadata::extended::SpecExt;
+use fluvio_auth::{AuthContext, TypeAction};
+
+use crate::core::Context;
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for quota request
+#[instrument(skip(req, auth_ctx))]
+pub async fn handle_create_quota_request<AC: AuthContext, C: MetadataItem>(
+    req: CreateRequest<QuotaSpec>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<Status> {
+    let (create, spec) = req.parts();
+    let name = create.name;
+
+    info!(%name, "creating quota");
+
+    if let Ok(authorized) = auth_ctx
+        .auth
+        .allow_type_action(QuotaSpec::OBJECT_TYPE, TypeAction::Create)
+        .await
+    {
+        if !authorized {
+            trace!("authorization failed");
+            return Ok(Status::new(
+                name.clone(),
+                ErrorCode::PermissionDenied,
+                Some(String::from("permission denied")),
+            ));
+        }
+    } else {
+        return Err(anyhow!("authorization io error"));
+    }
+
+    if auth_ctx
+        .global_ctx
+        .quotas()
+        .store()
+        .contains_key(&name)
+        .await
+    {
+        debug!("quota already exists");
+        return Ok(Status::new(
+            name.to_string(),
+            ErrorCode::QuotaAlreadyExists,
+            Some(format!("quota '{name}' already defined")),
+        ));
+    }
+
+    if let Err(err) = spec.validate() {
+        return Ok(Status::new(
+            name,
+            ErrorCode::QuotaInvalid(err.to_string()),
+            Some(err.to_string()),
+        ));
+    }
+
+    let status = process_quota_request(&auth_ctx.global_ctx, name, spec).await;
+    trace!("create quota response {:#?}", status);
+
+    Ok(status)
+}
+
+/// Process quota, converts quota spec to K8 and sends to KV store
+#[instrument(skip(ctx, name, quota_spec))]
+async fn process_quota_request<C: MetadataItem>(
+    ctx: &Context<C>,
+    name: String,
+    quota_spec: QuotaSpec,
+) -> Status {
+    if let Err(err) = ctx.quotas().create_spec(name.clone(), quota_spec).await {
+        let error = Some(err.to_string());
+        Status::new(name, ErrorCode::QuotaError, error)
+    } else {
+        info!(%name, "quota created");
+        Status::new_ok(name.clone())
+    }
+}
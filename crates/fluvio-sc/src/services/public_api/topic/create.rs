@@ -13,12 +13,12 @@ use tracing::{info, debug, trace, instrument};
 use anyhow::{anyhow, Result};
 
 use fluvio_protocol::link::ErrorCode;
-use fluvio_controlplane_metadata::topic::ReplicaSpec;
+use fluvio_controlplane_metadata::topic::{ReplicaSpec, Transform};
 use fluvio_sc_schema::objects::CreateRequest;
 use fluvio_sc_schema::shared::validate_resource_name;
 use fluvio_sc_schema::Status;
 use fluvio_sc_schema::topic::TopicSpec;
-use fluvio_auth::{AuthContext, TypeAction};
+use fluvio_auth::{AuthContext, TypeAction, InstanceAction};
 use fluvio_controlplane_metadata::extended::SpecExt;
 use fluvio_controlplane_metadata::smartmodule::SmartModulePackageKey;
 use fluvio_stream_model::core::MetadataItem;
@@ -41,11 +41,21 @@ pub(crate) async fn handle_create_topics_request<AC: AuthContext, C: MetadataIte
 
     info!( topic = %name,"creating topic");
 
-    if let Ok(authorized) = auth_ctx
+    // instance rules of policy can restrict names of created objects
+    let authorized = match auth_ctx
         .auth
         .allow_type_action(TopicSpec::OBJECT_TYPE, TypeAction::Create)
         .await
     {
+        Ok(true) => {
+            auth_ctx
+                .auth
+                .allow_instance_action(TopicSpec::OBJECT_TYPE, InstanceAction::Create, &name)
+                .await
+        }
+        other => other,
+    };
+    if let Ok(authorized) = authorized {
         if !authorized {
             trace!("authorization failed");
             return Ok(Status::new(
@@ -72,6 +82,39 @@ pub(crate) async fn handle_create_topics_request<AC: AuthContext, C: MetadataIte
     Ok(status)
 }
 
+/// Ensure SmartModules of topic transforms are loaded into the cluster
+pub(crate) async fn validate_transforms<C: MetadataItem>(
+    transforms: &[Transform],
+    metadata: &Context<C>,
+) -> Option<Status> {
+    for transform in transforms {
+        let sm_name = transform.uses.as_str();
+        let sm_fqdn = match SmartModulePackageKey::from_qualified_name(sm_name) {
+            Ok(fqdn) => fqdn.store_id(),
+            Err(err) => {
+                return Some(Status::new(
+                    sm_name.to_string(),
+                    ErrorCode::SmartModuleInvalid {
+                        error: err.to_string(),
+                        name: Some(sm_name.to_string()),
+                    },
+                    Some(err.to_string()),
+                ))
+            }
+        };
+        if !metadata.smartmodules().store().contains_key(&sm_fqdn).await {
+            let error_code = ErrorCode::SmartModuleNotFound {
+                name: sm_name.to_string(),
+            };
+            let reason = format!(
+                "{error_code}\nHint: try `fluvio hub download {sm_name}` and repeat this operation"
+            );
+            return Some(Status::new(sm_name.to_string(), error_code, Some(reason)));
+        }
+    }
+    None
+}
+
 /// Validate topic, takes advantage of the validation routines inside topic action workflow
 async fn validate_topic_request<C: MetadataItem>(
     name: &str,
@@ -134,6 +177,10 @@ async fn validate_topic_request<C: MetadataItem>(
         }
     }
 
+    if let Some(status) = validate_transforms(topic_spec.get_transforms(), metadata).await {
+        return status;
+    }
+
     match topic_spec.replicas() {
         ReplicaSpec::Computed(param) => {
             let next_state = validate_computed_topic_parameters::<C>(param);
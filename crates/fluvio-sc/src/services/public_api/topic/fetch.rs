@@ -5,7 +5,7 @@ use anyhow::{anyhow, Result};
 use fluvio_controlplane_metadata::store::KeyFilter;
 use fluvio_sc_schema::objects::{ListResponse, Metadata, ListFilters};
 use fluvio_sc_schema::topic::TopicSpec;
-use fluvio_auth::{AuthContext, TypeAction};
+use fluvio_auth::{AuthContext, TypeAction, InstanceAction};
 use fluvio_controlplane_metadata::extended::SpecExt;
 
 use crate::services::auth::AuthServiceContext;
@@ -30,21 +30,22 @@ pub async fn handle_fetch_topics_request<AC: AuthContext, C: MetadataItem>(
         return Err(anyhow!("authorization error"));
     }
 
-    let topics: Vec<Metadata<TopicSpec>> = auth_ctx
-        .global_ctx
-        .topics()
-        .store()
-        .read()
-        .await
-        .values()
-        .filter_map(|value| {
-            if filters.filter(value.key()) {
-                Some(value.inner().clone().into())
-            } else {
-                None
-            }
-        })
-        .collect();
+    let reader = auth_ctx.global_ctx.topics().store().read().await;
+    let mut topics: Vec<Metadata<TopicSpec>> = vec![];
+    for value in reader.values() {
+        if !filters.filter(value.key()) {
+            continue;
+        }
+        // skip topics which are not readable by policy
+        let readable = auth_ctx
+            .auth
+            .allow_instance_action(TopicSpec::OBJECT_TYPE, InstanceAction::Read, value.key())
+            .await
+            .map_err(|_| anyhow!("authorization error"))?;
+        if readable {
+            topics.push(value.inner().clone().into());
+        }
+    }
 
     debug!("flv fetch topics resp: {} items", topics.len());
     trace!("flv fetch topics resp {:#?}", topics);
@@ -0,0 +1,100 @@
+//!
+//! # Update Topic Request
+//!
+//! Update topic request handler. Only spec of topic is changed here,
+//! new partitions are scheduled by topic controller.
+//!
+
+use tracing::{info, trace, instrument};
+use anyhow::{anyhow, Result};
+
+use fluvio_protocol::link::ErrorCode;
+use fluvio_sc_schema::Status;
+use fluvio_sc_schema::topic::{TopicSpec, UpdateTopicAction, TopicResolution};
+use fluvio_auth::{AuthContext, InstanceAction};
+use fluvio_controlplane_metadata::extended::SpecExt;
+use fluvio_stream_model::core::MetadataItem;
+
+use crate::services::auth::AuthServiceContext;
+
+use super::create::validate_transforms;
+
+/// Handler for update topic request
+#[instrument(skip(topic_name, action, auth_ctx))]
+pub(crate) async fn handle_update_topic<AC: AuthContext, C: MetadataItem>(
+    topic_name: String,
+    action: UpdateTopicAction,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<Status> {
+    info!(%topic_name, ?action, "updating topic");
+
+    if let Ok(authorized) = auth_ctx
+        .auth
+        .allow_instance_action(TopicSpec::OBJECT_TYPE, InstanceAction::Update, &topic_name)
+        .await
+    {
+        if !authorized {
+            trace!("authorization failed");
+            return Ok(Status::new(
+                topic_name.clone(),
+                ErrorCode::PermissionDenied,
+                Some(String::from("permission denied")),
+            ));
+        }
+    } else {
+        return Err(anyhow!("authorization io error"));
+    }
+
+    let topics = auth_ctx.global_ctx.topics();
+    let Some(topic) = topics.store().value(&topic_name).await else {
+        return Ok(Status::new(
+            topic_name,
+            ErrorCode::TopicNotFound,
+            Some("not found".to_owned()),
+        ));
+    };
+    if topic.status.resolution != TopicResolution::Provisioned {
+        return Ok(Status::new(
+            topic_name,
+            ErrorCode::TopicPendingInitialization,
+            Some(format!(
+                "topic is not provisioned: {}",
+                topic.status.resolution.resolution_label()
+            )),
+        ));
+    }
+
+    let mut spec = topic.spec.clone();
+    match action {
+        UpdateTopicAction::AddPartition(config) => match spec.add_partitions(config.count) {
+            Ok(partitions) => info!(%topic_name, partitions, "adding partitions"),
+            Err(err) => {
+                return Ok(Status::new(
+                    topic_name,
+                    ErrorCode::TopicInvalidConfiguration,
+                    Some(err.to_string()),
+                ))
+            }
+        },
+        UpdateTopicAction::SetTransforms(config) => {
+            if let Some(status) =
+                validate_transforms(&config.transforms, &auth_ctx.global_ctx).await
+            {
+                return Ok(status);
+            }
+            info!(%topic_name, transforms = config.transforms.len(), "setting transforms");
+            spec.set_transforms(config.transforms);
+        }
+    }
+
+    let status = if let Err(err) = topics.create_spec(topic_name.clone(), spec).await {
+        Status::new(topic_name, ErrorCode::TopicError, Some(err.to_string()))
+    } else {
+        info!(%topic_name, "topic updated");
+        Status::new_ok(topic_name)
+    };
+
+    trace!("update topic response {:#?}", status);
+
+    Ok(status)
+}
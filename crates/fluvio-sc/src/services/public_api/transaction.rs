@@ -0,0 +1,358 @@
+//!
+//! # Transaction Requests
+//!
+//! SC is the transaction coordinator. It tracks partitions and consumer offsets of open
+//! transactions and writes control batches to partition leaders when transaction ends.
+//! State of transaction is persisted before request is answered.
+//! Control batches are sent to private endpoint of SPUs, clients can't write them.
+//!
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use tracing::{debug, trace, instrument};
+use anyhow::{anyhow, Result};
+
+use fluvio_protocol::api::{RequestMessage, ResponseMessage};
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::ReplicaKey;
+use fluvio_sc_schema::transaction::{
+    AddOffsetsToTxnRequest, AddOffsetsToTxnResponse, AddPartitionsToTxnRequest,
+    AddPartitionsToTxnResponse, EndTxnRequest, EndTxnResponse,
+};
+use fluvio_sc_schema::topic::TopicSpec;
+use fluvio_socket::FluvioSocket;
+use fluvio_controlplane::txn_api::commit_offsets::{CommitTxnOffsetsRequest, TxnOffset};
+use fluvio_controlplane::txn_api::write_markers::WriteTxnMarkersRequest;
+use fluvio_types::SpuId;
+use fluvio_auth::{AuthContext, InstanceAction};
+use fluvio_stream_model::core::MetadataItem;
+use fluvio_controlplane_metadata::extended::SpecExt;
+
+use crate::core::Context;
+use crate::services::auth::AuthServiceContext;
+use crate::stores::spu::SpuLocalStorePolicy;
+use crate::stores::transaction::TxnEnd;
+
+/// time for leaders to replicate control batches
+const MARKER_WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Handler for add partitions to transaction request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_add_partitions_to_txn_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<AddPartitionsToTxnRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<AddPartitionsToTxnResponse>> {
+    let req = request.request();
+    debug!(transactional_id = %req.transactional_id, partitions = ?req.partitions, "add partitions to transaction");
+
+    let topics = req.partitions.iter().map(|replica| replica.topic.as_str());
+    let error_code = if !allow_topics_action(auth_ctx, InstanceAction::Write, topics).await? {
+        ErrorCode::PermissionDenied
+    } else if let Err(error_code) = auth_ctx
+        .global_ctx
+        .transactions()
+        .add_partitions(
+            &req.transactional_id,
+            req.producer_id,
+            req.producer_epoch,
+            req.partitions.clone(),
+        )
+        .await
+    {
+        error_code
+    } else if let Err(error_code) =
+        persist_transaction(&auth_ctx.global_ctx, &req.transactional_id).await
+    {
+        error_code
+    } else {
+        ErrorCode::None
+    };
+
+    Ok(request.new_response(AddPartitionsToTxnResponse { error_code }))
+}
+
+/// Handler for add consumer offsets to transaction request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_add_offsets_to_txn_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<AddOffsetsToTxnRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<AddOffsetsToTxnResponse>> {
+    let req = request.request();
+    debug!(transactional_id = %req.transactional_id, group = %req.consumer_group, "add offsets to transaction");
+
+    let offsets = req
+        .offsets
+        .iter()
+        .map(|offset| (offset.replica.clone(), offset.offset))
+        .collect();
+    let topics = req
+        .offsets
+        .iter()
+        .map(|offset| offset.replica.topic.as_str());
+    // offsets are committed for records consumed by the producer
+    let error_code = if !allow_topics_action(auth_ctx, InstanceAction::Read, topics).await? {
+        ErrorCode::PermissionDenied
+    } else if let Err(error_code) = auth_ctx
+        .global_ctx
+        .transactions()
+        .add_offsets(
+            &req.transactional_id,
+            req.producer_id,
+            req.producer_epoch,
+            &req.consumer_group,
+            offsets,
+        )
+        .await
+    {
+        error_code
+    } else if let Err(error_code) =
+        persist_transaction(&auth_ctx.global_ctx, &req.transactional_id).await
+    {
+        error_code
+    } else {
+        ErrorCode::None
+    };
+
+    Ok(request.new_response(AddOffsetsToTxnResponse { error_code }))
+}
+
+/// Handler for end transaction request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_end_txn_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<EndTxnRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<EndTxnResponse>> {
+    let req = request.request();
+    debug!(transactional_id = %req.transactional_id, commit = req.commit, "end transaction");
+
+    // markers and offsets are written on behalf of the producer
+    let transactions = auth_ctx.global_ctx.transactions();
+    if !allow_transaction(auth_ctx, &req.transactional_id).await? {
+        trace!("authorization failed");
+        return Ok(request.new_response(EndTxnResponse {
+            error_code: ErrorCode::PermissionDenied,
+        }));
+    }
+
+    let ctx = &auth_ctx.global_ctx;
+    let result = async {
+        let end = transactions
+            .prepare_end(
+                &req.transactional_id,
+                req.producer_id,
+                req.producer_epoch,
+                req.commit,
+            )
+            .await?;
+        // decision must survive SC restart before any control batch is written
+        persist_transaction(ctx, &req.transactional_id).await?;
+        end_transaction(ctx, &req.transactional_id, &end).await
+    }
+    .await;
+    let error_code = match result {
+        Ok(()) => ErrorCode::None,
+        Err(error_code) => error_code,
+    };
+
+    Ok(request.new_response(EndTxnResponse { error_code }))
+}
+
+/// Write control batches and commit consumer offsets of ending transaction,
+/// then mark transaction as complete. If any write fails, transaction stays ending
+/// and it has to be ended again with the same decision.
+pub(crate) async fn end_transaction<C: MetadataItem>(
+    ctx: &Context<C>,
+    transactional_id: &str,
+    end: &TxnEnd,
+) -> Result<(), ErrorCode> {
+    write_markers(ctx, end).await?;
+    commit_offsets(ctx, end).await?;
+    ctx.transactions().complete(transactional_id, end).await;
+    persist_transaction(ctx, transactional_id).await?;
+    debug!(
+        transactional_id,
+        commit = end.commit,
+        "transaction completed"
+    );
+    Ok(())
+}
+
+/// write current state of transaction to metadata, unchanged state is not written
+pub(crate) async fn persist_transaction<C: MetadataItem>(
+    ctx: &Context<C>,
+    transactional_id: &str,
+) -> Result<(), ErrorCode> {
+    let transactions = ctx.transactions();
+    let _guard = transactions.persist_lock().await;
+    let Some(spec) = transactions.spec(transactional_id).await else {
+        return Ok(());
+    };
+    let store = ctx.transaction_specs();
+    if let Some(current) = store.store().value(transactional_id).await {
+        if current.spec == spec {
+            return Ok(());
+        }
+    }
+    trace!(transactional_id, state = ?spec.state, "persisting transaction");
+    store
+        .create_spec(transactional_id.to_owned(), spec)
+        .await
+        .map_err(|err| {
+            ErrorCode::Other(format!(
+                "persisting transaction {transactional_id} failed: {err}"
+            ))
+        })?;
+    Ok(())
+}
+
+async fn write_markers<C: MetadataItem>(ctx: &Context<C>, end: &TxnEnd) -> Result<(), ErrorCode> {
+    for (leader, partitions) in group_by_leader(ctx, end.partitions.iter()).await? {
+        let request = WriteTxnMarkersRequest {
+            producer_id: end.producer_id,
+            producer_epoch: end.producer_epoch,
+            commit: end.commit,
+            partitions,
+            timeout: MARKER_WRITE_TIMEOUT,
+        };
+        let mut socket = connect_to_spu_private(ctx, leader).await?;
+        let response = socket
+            .send(&RequestMessage::new_request(request))
+            .await
+            .map_err(|err| marker_error(format!("spu {leader}: {err}")))?
+            .response;
+        if let Some(failed) = response
+            .partitions
+            .iter()
+            .find(|partition| partition.error_code.is_error())
+        {
+            return Err(marker_error(format!(
+                "{}: {}",
+                failed.replica, failed.error_code
+            )));
+        }
+    }
+    Ok(())
+}
+
+async fn commit_offsets<C: MetadataItem>(ctx: &Context<C>, end: &TxnEnd) -> Result<(), ErrorCode> {
+    let mut leaders: BTreeMap<SpuId, Vec<TxnOffset>> = BTreeMap::new();
+    for (group, replica, offset) in &end.offsets {
+        let Some(partition) = ctx.partitions().store().value(replica).await else {
+            return Err(marker_error(format!("partition {replica} not found")));
+        };
+        leaders
+            .entry(partition.spec.leader)
+            .or_default()
+            .push(TxnOffset {
+                consumer_group: group.clone(),
+                replica: replica.clone(),
+                offset: *offset,
+            });
+    }
+    for (leader, offsets) in leaders {
+        let mut socket = connect_to_spu_private(ctx, leader).await?;
+        let response = socket
+            .send(&RequestMessage::new_request(CommitTxnOffsetsRequest {
+                offsets,
+            }))
+            .await
+            .map_err(|err| marker_error(format!("spu {leader}: {err}")))?
+            .response;
+        if let Some(failed) = response
+            .partitions
+            .iter()
+            .find(|partition| partition.error_code.is_error())
+        {
+            return Err(marker_error(format!(
+                "offset commit for {}: {}",
+                failed.replica, failed.error_code
+            )));
+        }
+    }
+    Ok(())
+}
+
+async fn group_by_leader<'a, C: MetadataItem>(
+    ctx: &Context<C>,
+    replicas: impl Iterator<Item = &'a ReplicaKey>,
+) -> Result<BTreeMap<SpuId, Vec<ReplicaKey>>, ErrorCode> {
+    let mut leaders: BTreeMap<SpuId, Vec<ReplicaKey>> = BTreeMap::new();
+    for replica in replicas {
+        let Some(partition) = ctx.partitions().store().value(replica).await else {
+            return Err(marker_error(format!("partition {replica} not found")));
+        };
+        leaders
+            .entry(partition.spec.leader)
+            .or_default()
+            .push(replica.clone());
+    }
+    Ok(leaders)
+}
+
+/// connect to private endpoint of the SPU, transaction requests are accepted only there
+async fn connect_to_spu_private<C: MetadataItem>(
+    ctx: &Context<C>,
+    spu_id: SpuId,
+) -> Result<FluvioSocket, ErrorCode> {
+    let Some(spu) = ctx.spus().store().get_by_id(spu_id).await else {
+        return Err(ErrorCode::SpuNotFound);
+    };
+    FluvioSocket::connect(&spu.spec.private_endpoint.to_string())
+        .await
+        .map_err(|err| marker_error(format!("spu {spu_id}: {err}")))
+}
+
+fn marker_error(message: String) -> ErrorCode {
+    ErrorCode::TransactionMarkersFailed(message)
+}
+
+/// transactional producers must be allowed the action on every topic
+pub(crate) async fn allow_topics_action<'a, AC: AuthContext, C: MetadataItem>(
+    auth_ctx: &AuthServiceContext<AC, C>,
+    action: InstanceAction,
+    topics: impl Iterator<Item = &'a str>,
+) -> Result<bool> {
+    for topic in topics {
+        let allowed = auth_ctx
+            .auth
+            .allow_instance_action(TopicSpec::OBJECT_TYPE, action.clone(), topic)
+            .await
+            .map_err(|_| anyhow!("authorization io error"))?;
+        if !allowed {
+            debug!(
+                topic,
+                ?action,
+                "transactional producer is not allowed action on topic"
+            );
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// transactional producer must be allowed to write to partitions of open transaction
+/// and to read topics of its consumer offsets
+pub(crate) async fn allow_transaction<AC: AuthContext, C: MetadataItem>(
+    auth_ctx: &AuthServiceContext<AC, C>,
+    transactional_id: &str,
+) -> Result<bool> {
+    let transactions = auth_ctx.global_ctx.transactions();
+    let topics = transactions.topics(transactional_id).await;
+    if !allow_topics_action(
+        auth_ctx,
+        InstanceAction::Write,
+        topics.iter().map(String::as_str),
+    )
+    .await?
+    {
+        return Ok(false);
+    }
+    let offset_topics = transactions.offset_topics(transactional_id).await;
+    allow_topics_action(
+        auth_ctx,
+        InstanceAction::Read,
+        offset_topics.iter().map(String::as_str),
+    )
+    .await
+}
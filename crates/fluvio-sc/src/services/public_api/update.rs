@@ -0,0 +1,45 @@
+//!
+//! # Update Request
+//!
+//! Update request handler. Dispatch update action to handler of the object type.
+//!
+
+use fluvio_protocol::link::ErrorCode;
+use fluvio_stream_model::core::MetadataItem;
+use tracing::{instrument, trace, debug, error};
+use anyhow::Result;
+
+use fluvio_controlplane_metadata::topic::TopicSpec;
+use fluvio_protocol::api::{RequestMessage, ResponseMessage};
+use fluvio_sc_schema::{Status, TryEncodableFrom};
+use fluvio_sc_schema::objects::{ObjectApiUpdateRequest, UpdateRequest};
+use fluvio_auth::AuthContext;
+
+use crate::services::auth::AuthServiceContext;
+
+/// Handler for update request
+#[instrument(skip(request, auth_ctx))]
+pub async fn handle_update_request<AC: AuthContext, C: MetadataItem>(
+    request: RequestMessage<ObjectApiUpdateRequest>,
+    auth_ctx: &AuthServiceContext<AC, C>,
+) -> Result<ResponseMessage<Status>> {
+    let (header, update_req) = request.get_header_request();
+
+    debug!(?update_req, "update request");
+
+    let status = if let Some(req) = update_req.downcast()? as Option<UpdateRequest<TopicSpec>> {
+        let (name, action) = req.into_parts();
+        super::topic::handle_update_topic(name, action, auth_ctx).await?
+    } else {
+        error!("unknown update request: {:#?}", update_req);
+        Status::new(
+            "update error".to_owned(),
+            ErrorCode::Other("unknown admin object type".to_owned()),
+            None,
+        )
+    };
+
+    trace!("flv update resp {:#?}", status);
+
+    Ok(ResponseMessage::from_header(&header, status))
+}
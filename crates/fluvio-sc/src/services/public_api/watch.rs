@@ -5,6 +5,7 @@ use fluvio_stream_model::store::ChangeListener;
 use tracing::{debug, trace, error, instrument};
 use anyhow::{anyhow, Result};
 
+use fluvio_auth::{AuthContext, TypeAction, InstanceAction};
 use fluvio_sc_schema::{AdminSpec, TryEncodableFrom};
 use fluvio_types::event::StickyEvent;
 use fluvio_socket::ExclusiveFlvSink;
@@ -20,70 +21,93 @@ use fluvio_controlplane_metadata::spu::SpuSpec;
 use fluvio_controlplane_metadata::topic::TopicSpec;
 use fluvio_controlplane_metadata::smartmodule::SmartModuleSpec;
 use fluvio_controlplane_metadata::tableformat::TableFormatSpec;
+use fluvio_controlplane_metadata::quota::QuotaSpec;
+use fluvio_controlplane_metadata::extended::SpecExt;
+use fluvio_controlplane_metadata::store::MetadataStoreObject;
 
 use crate::services::auth::AuthServiceContext;
 use crate::stores::StoreContext;
 use fluvio_controlplane_metadata::spg::SpuGroupSpec;
 
-/// handle watch request by spawning watch controller for each store
+/// handle watch request by spawning watch controller for each store,
+/// only objects readable by client are sent
 #[instrument(skip(request, auth_ctx, sink, end_event))]
-pub fn handle_watch_request<AC, C: MetadataItem + 'static>(
+pub fn handle_watch_request<AC, C>(
     request: RequestMessage<ObjectApiWatchRequest>,
-    auth_ctx: &AuthServiceContext<AC, C>,
+    auth_ctx: &Arc<AuthServiceContext<AC, C>>,
     sink: ExclusiveFlvSink,
     end_event: Arc<StickyEvent>,
-) -> Result<()> {
+) -> Result<()>
+where
+    AC: AuthContext + Send + Sync + 'static,
+    C: MetadataItem + 'static,
+{
     let (header, req) = request.get_header_request();
     debug!("handling watch header: {:#?}, request: {:#?}", header, req);
 
     if (req.downcast()? as Option<WatchRequest<TopicSpec>>).is_some() {
-        WatchController::<TopicSpec, C>::update(
+        WatchController::<TopicSpec, AC, C>::update(
             sink,
             end_event,
+            auth_ctx.clone(),
             auth_ctx.global_ctx.topics().clone(),
             header,
             false,
         )
     } else if (req.downcast()? as Option<WatchRequest<SpuSpec>>).is_some() {
-        WatchController::<SpuSpec, C>::update(
+        WatchController::<SpuSpec, AC, C>::update(
             sink,
             end_event,
+            auth_ctx.clone(),
             auth_ctx.global_ctx.spus().clone(),
             header,
             false,
         )
     } else if (req.downcast()? as Option<WatchRequest<SpuGroupSpec>>).is_some() {
-        WatchController::<SpuGroupSpec, C>::update(
+        WatchController::<SpuGroupSpec, AC, C>::update(
             sink,
             end_event,
+            auth_ctx.clone(),
             auth_ctx.global_ctx.spgs().clone(),
             header,
             false,
         )
     } else if (req.downcast()? as Option<WatchRequest<PartitionSpec>>).is_some() {
-        WatchController::<PartitionSpec, C>::update(
+        WatchController::<PartitionSpec, AC, C>::update(
             sink,
             end_event,
+            auth_ctx.clone(),
             auth_ctx.global_ctx.partitions().clone(),
             header,
             false,
         )
     } else if let Some(req) = req.downcast()? as Option<WatchRequest<SmartModuleSpec>> {
-        WatchController::<SmartModuleSpec, C>::update(
+        WatchController::<SmartModuleSpec, AC, C>::update(
             sink,
             end_event,
+            auth_ctx.clone(),
             auth_ctx.global_ctx.smartmodules().clone(),
             header,
             req.summary,
         )
     } else if (req.downcast()? as Option<WatchRequest<TableFormatSpec>>).is_some() {
-        WatchController::<TableFormatSpec, C>::update(
+        WatchController::<TableFormatSpec, AC, C>::update(
             sink,
             end_event,
+            auth_ctx.clone(),
             auth_ctx.global_ctx.tableformats().clone(),
             header,
             false,
         )
+    } else if (req.downcast()? as Option<WatchRequest<QuotaSpec>>).is_some() {
+        WatchController::<QuotaSpec, AC, C>::update(
+            sink,
+            end_event,
+            auth_ctx.clone(),
+            auth_ctx.global_ctx.quotas().clone(),
+            header,
+            false,
+        )
     } else {
         debug!("Invalid Watch Req {:?}", req);
         return Err(anyhow!("Not Valid Watch Request",));
@@ -93,18 +117,20 @@ pub fn handle_watch_request<AC, C: MetadataItem + 'static>(
 }
 
 /// Watch controller for each object.  Note that return type may or not be the same as the object hence two separate spec
-struct WatchController<S: AdminSpec, C: MetadataItem> {
+struct WatchController<S: AdminSpec, AC, C: MetadataItem> {
     response_sink: ExclusiveFlvSink,
+    auth_ctx: Arc<AuthServiceContext<AC, C>>,
     store: StoreContext<S, C>,
     header: RequestHeader,
     summary: bool,
     end_event: Arc<StickyEvent>,
 }
 
-impl<S, C> WatchController<S, C>
+impl<S, AC, C> WatchController<S, AC, C>
 where
+    AC: AuthContext + Send + Sync + 'static,
     C: MetadataItem + 'static,
-    S: AdminSpec + 'static,
+    S: AdminSpec + SpecExt + 'static,
     S: Encoder + Decoder + Send + Sync,
     S::Status: Encoder + Decoder + Send + Sync,
     S::IndexKey: ToString + Send + Sync,
@@ -113,6 +139,7 @@ where
     fn update(
         response_sink: ExclusiveFlvSink,
         end_event: Arc<StickyEvent>,
+        auth_ctx: Arc<AuthServiceContext<AC, C>>,
         store: StoreContext<S, C>,
         header: RequestHeader,
         summary: bool,
@@ -121,6 +148,7 @@ where
 
         let controller = Self {
             response_sink,
+            auth_ctx,
             store,
             header,
             end_event,
@@ -187,6 +215,7 @@ where
 
         let updates = if changes.is_sync_all() {
             let (updates, _) = changes.parts();
+            let updates = self.readable(updates).await;
             MetadataUpdate::with_all(
                 epoch,
                 updates
@@ -197,6 +226,8 @@ where
             )
         } else {
             let (updates, deletes) = changes.parts();
+            let updates = self.readable(updates).await;
+            let deletes = self.readable(deletes).await;
             let mut changes: Vec<Message<Metadata<S>>> = updates
                 .into_iter()
                 .map(|u| u.into())
@@ -241,4 +272,44 @@ where
 
         true
     }
+
+    /// objects which client is allowed to read, none if client can't read this type
+    async fn readable(
+        &self,
+        objects: Vec<MetadataStoreObject<S, C>>,
+    ) -> Vec<MetadataStoreObject<S, C>> {
+        let auth = &self.auth_ctx.auth;
+        match auth
+            .allow_type_action(S::OBJECT_TYPE, TypeAction::Read)
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                debug!(ty = %S::LABEL, "watch authorization failed");
+                return vec![];
+            }
+            Err(err) => {
+                error!(ty = %S::LABEL, %err, "watch authorization error");
+                return vec![];
+            }
+        }
+        let mut readable = Vec::with_capacity(objects.len());
+        for object in objects {
+            match auth
+                .allow_instance_action(
+                    S::OBJECT_TYPE,
+                    InstanceAction::Read,
+                    &object.key().to_string(),
+                )
+                .await
+            {
+                Ok(true) => readable.push(object),
+                Ok(false) => {}
+                Err(err) => {
+                    error!(ty = %S::LABEL, %err, "watch authorization error");
+                }
+            }
+        }
+        readable
+    }
 }
@@ -0,0 +1,635 @@
+//!
+//! # Consumer Groups
+//!
+//! Membership of consumer groups and assignment of partitions to members.
+//! Membership is kept in memory only, members rejoin if SC is restarted.
+//!
+//! Partition moved to another member by rebalance is not handed to the new member
+//! until the previous owner confirms, with the generation sent in heartbeat,
+//! that it has stopped consuming it, or until the previous owner leaves the group.
+//!
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_lock::RwLock;
+use rand::{Rng, thread_rng};
+use rand::distributions::Alphanumeric;
+use tracing::{debug, info};
+
+use fluvio_sc_schema::consumer_group::AssignmentStrategy;
+use fluvio_sc_schema::errors::ErrorCode;
+use fluvio_types::{PartitionCount, PartitionId};
+
+const MEMBER_ID_LEN: usize = 12;
+
+pub type SharedConsumerGroups = Arc<ConsumerGroups>;
+
+/// Partitions assigned to a member for current generation of the group
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct MemberAssignment {
+    pub member_id: String,
+    pub generation: u32,
+    pub partitions: Vec<PartitionId>,
+}
+
+#[derive(Debug)]
+struct Member {
+    last_heartbeat: Instant,
+    session_timeout: Duration,
+}
+
+impl Member {
+    fn is_expired(&self, now: Instant) -> bool {
+        now.duration_since(self.last_heartbeat) > self.session_timeout
+    }
+}
+
+#[derive(Debug)]
+struct GroupState {
+    topic: String,
+    strategy: AssignmentStrategy,
+    generation: u32,
+    partition_count: PartitionCount,
+    /// sorted by id so assignment is stable for the same set of members
+    members: BTreeMap<String, Member>,
+    assignment: HashMap<String, Vec<PartitionId>>,
+    /// member currently consuming partition
+    owners: HashMap<PartitionId, String>,
+}
+
+impl GroupState {
+    fn new(topic: &str, strategy: AssignmentStrategy) -> Self {
+        Self {
+            topic: topic.to_owned(),
+            strategy,
+            generation: 0,
+            partition_count: 0,
+            members: BTreeMap::new(),
+            assignment: HashMap::new(),
+            owners: HashMap::new(),
+        }
+    }
+
+    /// remove members which missed heartbeats, return true if any was removed
+    fn expire_members(&mut self, now: Instant) -> bool {
+        let before = self.members.len();
+        self.members.retain(|member_id, member| {
+            let expired = member.is_expired(now);
+            if expired {
+                info!(member_id, topic = %self.topic, "consumer group member expired");
+            }
+            !expired
+        });
+        before != self.members.len()
+    }
+
+    fn rebalance(&mut self) {
+        let members: Vec<&String> = self.members.keys().collect();
+        let assigned = match self.strategy {
+            AssignmentStrategy::Range => assign_range(self.partition_count, members.len()),
+            AssignmentStrategy::RoundRobin => {
+                assign_round_robin(self.partition_count, members.len())
+            }
+        };
+        self.assignment = members
+            .into_iter()
+            .cloned()
+            .zip(assigned)
+            .collect::<HashMap<_, _>>();
+        // partitions of removed members are released right away
+        let members = &self.members;
+        self.owners.retain(|partition, owner| {
+            members.contains_key(owner) && *partition < self.partition_count
+        });
+        self.generation += 1;
+        debug!(
+            topic = %self.topic,
+            generation = self.generation,
+            assignment = ?self.assignment,
+            "consumer group rebalanced"
+        );
+    }
+
+    /// member has applied assignment of `generation`, so it no longer consumes partitions
+    /// which are not assigned to it in that generation
+    fn release_revoked(&mut self, member_id: &str, generation: u32) {
+        if generation != self.generation {
+            return;
+        }
+        let assigned = self.assignment.get(member_id);
+        self.owners.retain(|partition, owner| {
+            owner != member_id || assigned.is_some_and(|assigned| assigned.contains(partition))
+        });
+    }
+
+    /// partitions assigned to member, except partitions still owned by another member
+    fn member_assignment(&mut self, member_id: &str) -> MemberAssignment {
+        let mut partitions = self.assignment.get(member_id).cloned().unwrap_or_default();
+        partitions.retain(
+            |partition| !matches!(self.owners.get(partition), Some(owner) if owner != member_id),
+        );
+        for partition in &partitions {
+            self.owners.insert(*partition, member_id.to_owned());
+        }
+        MemberAssignment {
+            member_id: member_id.to_owned(),
+            generation: self.generation,
+            partitions,
+        }
+    }
+}
+
+/// Tracks members of consumer groups through heartbeats
+#[derive(Debug, Default)]
+pub struct ConsumerGroups {
+    groups: RwLock<HashMap<String, GroupState>>,
+}
+
+impl ConsumerGroups {
+    pub fn shared() -> SharedConsumerGroups {
+        Arc::new(Self::default())
+    }
+
+    /// Register heartbeat of a member, empty `member_id` joins the group as a new member.
+    /// Partitions are reassigned if members or partition count of the topic have changed.
+    /// `applied_generation` is the last generation whose assignment member has applied.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn heartbeat(
+        &self,
+        group: &str,
+        topic: &str,
+        member_id: &str,
+        applied_generation: u32,
+        strategy: AssignmentStrategy,
+        session_timeout: Duration,
+        partition_count: PartitionCount,
+    ) -> Result<MemberAssignment, ErrorCode> {
+        self.heartbeat_at(
+            Instant::now(),
+            group,
+            topic,
+            member_id,
+            applied_generation,
+            strategy,
+            session_timeout,
+            partition_count,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn heartbeat_at(
+        &self,
+        now: Instant,
+        group: &str,
+        topic: &str,
+        member_id: &str,
+        applied_generation: u32,
+        strategy: AssignmentStrategy,
+        session_timeout: Duration,
+        partition_count: PartitionCount,
+    ) -> Result<MemberAssignment, ErrorCode> {
+        let mut groups = self.groups.write().await;
+        let state = groups
+            .entry(group.to_owned())
+            .or_insert_with(|| GroupState::new(topic, strategy));
+
+        let mut changed = state.expire_members(now);
+
+        if state.members.is_empty() {
+            // group without members can move to another topic or strategy
+            state.topic = topic.to_owned();
+            state.strategy = strategy;
+        } else if state.topic != topic {
+            return Err(ErrorCode::ConsumerGroupTopicMismatch(state.topic.clone()));
+        }
+
+        let member_id = if member_id.is_empty() {
+            let member_id = generate_member_id();
+            info!(group, %member_id, topic, "member joined consumer group");
+            state.members.insert(
+                member_id.clone(),
+                Member {
+                    last_heartbeat: now,
+                    session_timeout,
+                },
+            );
+            changed = true;
+            member_id
+        } else if let Some(member) = state.members.get_mut(member_id) {
+            member.last_heartbeat = now;
+            member.session_timeout = session_timeout;
+            state.release_revoked(member_id, applied_generation);
+            member_id.to_owned()
+        } else {
+            return Err(ErrorCode::ConsumerGroupUnknownMember(member_id.to_owned()));
+        };
+
+        if state.partition_count != partition_count {
+            state.partition_count = partition_count;
+            changed = true;
+        }
+
+        if changed {
+            state.rebalance();
+        }
+
+        Ok(state.member_assignment(&member_id))
+    }
+
+    /// topic consumed by the group
+    pub async fn topic(&self, group: &str) -> Option<String> {
+        let groups = self.groups.read().await;
+        groups.get(group).map(|state| state.topic.clone())
+    }
+
+    /// Remove members which missed heartbeats from all groups, groups without members are removed.
+    /// Partitions of expired members are reassigned to remaining members.
+    pub async fn expire(&self, now: Instant) {
+        let mut groups = self.groups.write().await;
+        groups.retain(|group, state| {
+            if !state.expire_members(now) {
+                return true;
+            }
+            if state.members.is_empty() {
+                info!(group, "consumer group without members removed");
+                return false;
+            }
+            state.rebalance();
+            true
+        });
+    }
+
+    /// Remove member from the group, partitions are reassigned to remaining members
+    pub async fn leave(&self, group: &str, member_id: &str) -> Result<(), ErrorCode> {
+        let mut groups = self.groups.write().await;
+        let Some(state) = groups.get_mut(group) else {
+            return Err(ErrorCode::ConsumerGroupUnknownMember(member_id.to_owned()));
+        };
+
+        if state.members.remove(member_id).is_none() {
+            return Err(ErrorCode::ConsumerGroupUnknownMember(member_id.to_owned()));
+        }
+        info!(group, member_id, "member left consumer group");
+
+        if state.members.is_empty() {
+            groups.remove(group);
+        } else {
+            state.rebalance();
+        }
+        Ok(())
+    }
+}
+
+fn generate_member_id() -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(MEMBER_ID_LEN)
+        .map(char::from)
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// assign contiguous range of partitions to each member,
+/// first members get one extra partition if partitions can't be evenly divided
+fn assign_range(partition_count: PartitionCount, members: usize) -> Vec<Vec<PartitionId>> {
+    if members == 0 {
+        return vec![];
+    }
+    let members = members as PartitionCount;
+    let per_member = partition_count / members;
+    let extra = partition_count % members;
+
+    let mut start = 0;
+    (0..members)
+        .map(|index| {
+            let count = per_member + u32::from(index < extra);
+            let range = (start..start + count).collect();
+            start += count;
+            range
+        })
+        .collect()
+}
+
+/// assign partitions to members one by one
+fn assign_round_robin(partition_count: PartitionCount, members: usize) -> Vec<Vec<PartitionId>> {
+    let mut assignment = vec![vec![]; members];
+    if members == 0 {
+        return assignment;
+    }
+    for partition in 0..partition_count {
+        assignment[partition as usize % members].push(partition);
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn test_assign_range() {
+        assert_eq!(assign_range(5, 2), vec![vec![0, 1, 2], vec![3, 4]]);
+        assert_eq!(assign_range(4, 2), vec![vec![0, 1], vec![2, 3]]);
+        assert_eq!(assign_range(1, 3), vec![vec![0], vec![], vec![]]);
+        assert!(assign_range(3, 0).is_empty());
+    }
+
+    #[test]
+    fn test_assign_round_robin() {
+        assert_eq!(assign_round_robin(5, 2), vec![vec![0, 2, 4], vec![1, 3]]);
+        assert_eq!(assign_round_robin(1, 3), vec![vec![0], vec![], vec![]]);
+        assert!(assign_round_robin(3, 0).is_empty());
+    }
+
+    #[fluvio_future::test]
+    async fn test_group_join_leave_and_expire() {
+        let groups = ConsumerGroups::default();
+        let start = Instant::now();
+
+        let first = groups
+            .heartbeat_at(
+                start,
+                "g1",
+                "topic",
+                "",
+                0,
+                AssignmentStrategy::Range,
+                TIMEOUT,
+                4,
+            )
+            .await
+            .expect("join");
+        assert_eq!(first.generation, 1);
+        assert_eq!(first.partitions, vec![0, 1, 2, 3]);
+
+        // partitions are still consumed by first member
+        let second = groups
+            .heartbeat_at(
+                start,
+                "g1",
+                "topic",
+                "",
+                0,
+                AssignmentStrategy::Range,
+                TIMEOUT,
+                4,
+            )
+            .await
+            .expect("join");
+        assert_eq!(second.generation, 2);
+        assert!(second.partitions.is_empty());
+
+        let middle = start + Duration::from_secs(6);
+        let first = groups
+            .heartbeat_at(
+                middle,
+                "g1",
+                "topic",
+                &first.member_id,
+                1,
+                AssignmentStrategy::Range,
+                TIMEOUT,
+                4,
+            )
+            .await
+            .expect("heartbeat");
+        assert_eq!(first.generation, 2);
+        assert_eq!(first.partitions.len(), 2);
+
+        // first member has stopped consuming revoked partitions
+        groups
+            .heartbeat_at(
+                middle,
+                "g1",
+                "topic",
+                &first.member_id,
+                2,
+                AssignmentStrategy::Range,
+                TIMEOUT,
+                4,
+            )
+            .await
+            .expect("heartbeat");
+        let second = groups
+            .heartbeat_at(
+                start,
+                "g1",
+                "topic",
+                &second.member_id,
+                2,
+                AssignmentStrategy::Range,
+                TIMEOUT,
+                4,
+            )
+            .await
+            .expect("heartbeat");
+        assert_eq!(second.generation, 2);
+        assert_eq!(second.partitions.len(), 2);
+        assert!(second
+            .partitions
+            .iter()
+            .all(|partition| !first.partitions.contains(partition)));
+
+        // other topic is rejected while group has members
+        assert!(matches!(
+            groups
+                .heartbeat_at(
+                    start,
+                    "g1",
+                    "other",
+                    "",
+                    0,
+                    AssignmentStrategy::Range,
+                    TIMEOUT,
+                    4
+                )
+                .await,
+            Err(ErrorCode::ConsumerGroupTopicMismatch(_))
+        ));
+
+        // second member stops sending heartbeats
+        let later = start + TIMEOUT + Duration::from_secs(1);
+        let first = groups
+            .heartbeat_at(
+                later,
+                "g1",
+                "topic",
+                &first.member_id,
+                2,
+                AssignmentStrategy::Range,
+                TIMEOUT,
+                4,
+            )
+            .await
+            .expect("heartbeat");
+        assert_eq!(first.generation, 3);
+        assert_eq!(first.partitions, vec![0, 1, 2, 3]);
+
+        assert!(matches!(
+            groups
+                .heartbeat_at(
+                    later,
+                    "g1",
+                    "topic",
+                    &second.member_id,
+                    2,
+                    AssignmentStrategy::Range,
+                    TIMEOUT,
+                    4
+                )
+                .await,
+            Err(ErrorCode::ConsumerGroupUnknownMember(_))
+        ));
+
+        groups.leave("g1", &first.member_id).await.expect("leave");
+        assert!(groups.leave("g1", &first.member_id).await.is_err());
+    }
+
+    #[fluvio_future::test]
+    async fn test_expire_groups() {
+        let groups = ConsumerGroups::default();
+        let start = Instant::now();
+
+        for group in ["g1", "g2"] {
+            groups
+                .heartbeat_at(
+                    start,
+                    group,
+                    "topic",
+                    "",
+                    0,
+                    AssignmentStrategy::Range,
+                    TIMEOUT,
+                    2,
+                )
+                .await
+                .expect("join");
+        }
+        let later = start + Duration::from_secs(6);
+        let member = groups
+            .heartbeat_at(
+                later,
+                "g2",
+                "topic",
+                "",
+                0,
+                AssignmentStrategy::Range,
+                TIMEOUT,
+                2,
+            )
+            .await
+            .expect("join");
+        assert_eq!(member.generation, 2);
+
+        groups.expire(start + Duration::from_secs(11)).await;
+
+        // group without live members is removed
+        assert_eq!(groups.topic("g1").await, None);
+        // partitions of expired member are reassigned
+        let member = groups
+            .heartbeat_at(
+                start + Duration::from_secs(12),
+                "g2",
+                "topic",
+                &member.member_id,
+                2,
+                AssignmentStrategy::Range,
+                TIMEOUT,
+                2,
+            )
+            .await
+            .expect("heartbeat");
+        assert_eq!(member.generation, 3);
+        assert_eq!(member.partitions, vec![0, 1]);
+    }
+
+    #[fluvio_future::test]
+    async fn test_partition_not_assigned_until_revoked() {
+        let groups = ConsumerGroups::default();
+        let now = Instant::now();
+
+        let first = groups
+            .heartbeat_at(
+                now,
+                "g1",
+                "topic",
+                "",
+                0,
+                AssignmentStrategy::RoundRobin,
+                TIMEOUT,
+                2,
+            )
+            .await
+            .expect("join");
+        assert_eq!(first.partitions, vec![0, 1]);
+
+        let second = groups
+            .heartbeat_at(
+                now,
+                "g1",
+                "topic",
+                "",
+                0,
+                AssignmentStrategy::RoundRobin,
+                TIMEOUT,
+                2,
+            )
+            .await
+            .expect("join");
+        assert!(second.partitions.is_empty());
+
+        // first member has not applied generation 2 yet
+        let first = groups
+            .heartbeat_at(
+                now,
+                "g1",
+                "topic",
+                &first.member_id,
+                1,
+                AssignmentStrategy::RoundRobin,
+                TIMEOUT,
+                2,
+            )
+            .await
+            .expect("heartbeat");
+        assert_eq!(first.partitions.len(), 1);
+        let second = groups
+            .heartbeat_at(
+                now,
+                "g1",
+                "topic",
+                &second.member_id,
+                2,
+                AssignmentStrategy::RoundRobin,
+                TIMEOUT,
+                2,
+            )
+            .await
+            .expect("heartbeat");
+        assert!(second.partitions.is_empty());
+
+        // revoked partition is released when first member leaves
+        groups.leave("g1", &first.member_id).await.expect("leave");
+        let second = groups
+            .heartbeat_at(
+                now,
+                "g1",
+                "topic",
+                &second.member_id,
+                2,
+                AssignmentStrategy::RoundRobin,
+                TIMEOUT,
+                2,
+            )
+            .await
+            .expect("heartbeat");
+        assert_eq!(second.generation, 3);
+        assert_eq!(second.partitions, vec![0, 1]);
+    }
+}
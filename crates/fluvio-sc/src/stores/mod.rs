@@ -4,6 +4,10 @@ pub mod partition;
 pub mod spg;
 pub mod smartmodule;
 pub mod tableformat;
+pub mod quota;
+pub mod consumer_group;
+pub mod producer_id;
+pub mod transaction;
 
 pub use crate::dispatcher::store::*;
 
@@ -105,8 +105,8 @@ impl ReplicaSchedulingGroups {
     /// this is done by scanning all spu and find the one with least weight
     pub(crate) fn find_suitable_spu(
         &self,
-        spu_list: &Vec<SpuId>,
-        anti_affinity: &Vec<SpuId>,
+        spu_list: &[SpuId],
+        anti_affinity: &[SpuId],
         weight: SpuWeightSelection,
     ) -> Option<SpuId> {
         trace!(?spu_list, ?anti_affinity, "find_suitable_spu");
@@ -118,14 +118,7 @@ impl ReplicaSchedulingGroups {
             if anti_affinity.contains(spu_id) {
                 continue;
             }
-            let candidate_weight = if let Some(group) = self.get(spu_id) {
-                match weight {
-                    SpuWeightSelection::Leader => group.leader_weight(),
-                    SpuWeightSelection::Follower => group.follower_weight(),
-                }
-            } else {
-                0
-            };
+            let candidate_weight = self.spu_weight(spu_id, weight);
 
             if candidate_weight < min_weight {
                 min_weight = candidate_weight;
@@ -136,6 +129,55 @@ impl ReplicaSchedulingGroups {
         current_spu
     }
 
+    /// find suitable spu across racks, each rack is list of its spus.
+    /// rack with fewest spus in anti-affinity is preferred, then the one with the least loaded spu
+    pub(crate) fn find_suitable_spu_in_racks(
+        &self,
+        racks: &[Vec<SpuId>],
+        anti_affinity: &[SpuId],
+        weight: SpuWeightSelection,
+    ) -> Option<SpuId> {
+        trace!(?racks, ?anti_affinity, "find_suitable_spu_in_racks");
+
+        let rack = racks
+            .iter()
+            .filter(|rack| rack.iter().any(|spu| !anti_affinity.contains(spu)))
+            .min_by_key(|rack| {
+                let reserved = rack
+                    .iter()
+                    .filter(|spu| anti_affinity.contains(spu))
+                    .count();
+                (reserved, self.rack_weight(rack, anti_affinity, weight))
+            })?;
+
+        self.find_suitable_spu(rack, anti_affinity, weight)
+    }
+
+    /// weight of the least loaded spu in the rack which is not in anti-affinity,
+    /// so racks with more spus are not penalized for their total load
+    fn rack_weight(
+        &self,
+        rack: &[SpuId],
+        anti_affinity: &[SpuId],
+        weight: SpuWeightSelection,
+    ) -> u16 {
+        rack.iter()
+            .filter(|spu| !anti_affinity.contains(spu))
+            .map(|spu| self.spu_weight(spu, weight))
+            .min()
+            .unwrap_or(u16::MAX)
+    }
+
+    fn spu_weight(&self, spu: &SpuId, weight: SpuWeightSelection) -> u16 {
+        match self.get(spu) {
+            Some(group) => match weight {
+                SpuWeightSelection::Leader => group.leader_weight(),
+                SpuWeightSelection::Follower => group.follower_weight(),
+            },
+            None => 0,
+        }
+    }
+
     pub(crate) fn increase_leaders(&mut self, spu: SpuId) {
         if let Some(groups) = self.get_mut(&spu) {
             groups.leaders += 1;
@@ -180,6 +222,7 @@ impl ReplicaSchedulingGroups {
 }
 
 // used for selecting weight
+#[derive(Debug, Clone, Copy)]
 pub(crate) enum SpuWeightSelection {
     Leader,
     Follower,
@@ -350,6 +393,11 @@ pub(crate) trait PartitonStatusExtension: Sized {
     where
         P: ElectionPolicy;
 
+    /// replica of spu has caught up with leader
+    fn is_in_sync<P>(&self, spu: SpuId, policy: &P) -> bool
+    where
+        P: ElectionPolicy;
+
     fn merge(&mut self, other: Self);
 
     fn update_lrs(&mut self);
@@ -384,6 +432,22 @@ impl PartitonStatusExtension for PartitionStatus {
         candidate_spu
     }
 
+    fn is_in_sync<P>(&self, spu: SpuId, policy: &P) -> bool
+    where
+        P: ElectionPolicy,
+    {
+        if self.leader.spu == spu {
+            return true;
+        }
+        self.replicas.iter().any(|status| {
+            status.spu == spu
+                && status.leo >= 0
+                && policy
+                    .potential_leader_score(status, &self.leader)
+                    .is_suitable()
+        })
+    }
+
     /// merge status from spu
     /// ignore changes from spu = -1 or offsets = -1
     fn merge(&mut self, other: Self) {
@@ -717,4 +781,55 @@ mod test2 {
             Some(2)
         ); // anti-affinity
     }
+
+    #[fluvio_future::test]
+    async fn test_spu_scheduling_racks() {
+        let partitions = DefaultPartitionStore::bulk_load(vec![
+            (("t1", 0), vec![0, 2]),
+            (("t1", 1), vec![1, 3]),
+        ]);
+        let groups = partitions.group_by_spu().await;
+        let racks = vec![vec![0, 1], vec![2, 3], vec![4]];
+
+        // rack without leaders
+        assert_eq!(
+            groups.find_suitable_spu_in_racks(&racks, &[], SpuWeightSelection::Leader),
+            Some(2)
+        );
+        // replicas go to other racks
+        assert_eq!(
+            groups.find_suitable_spu_in_racks(&racks, &[2], SpuWeightSelection::Follower),
+            Some(0)
+        );
+        assert_eq!(
+            groups.find_suitable_spu_in_racks(&racks, &[2, 0], SpuWeightSelection::Follower),
+            Some(4)
+        );
+        // fewer racks than replicas, rack is reused
+        assert_eq!(
+            groups.find_suitable_spu_in_racks(&racks, &[2, 0, 4], SpuWeightSelection::Follower),
+            Some(1)
+        );
+    }
+
+    #[fluvio_future::test]
+    async fn test_spu_scheduling_racks_of_different_size() {
+        let partitions = DefaultPartitionStore::bulk_load(vec![
+            (("t1", 0), vec![0]),
+            (("t1", 1), vec![1]),
+            (("t1", 2), vec![2]),
+            (("t1", 3), vec![3]),
+            (("t1", 4), vec![4]),
+            (("t1", 5), vec![4]),
+            (("t1", 6), vec![4]),
+        ]);
+        let groups = partitions.group_by_spu().await;
+        let racks = vec![vec![0, 1, 2, 3], vec![4]];
+
+        // large rack has more leaders in total, but its spus are less loaded
+        assert_eq!(
+            groups.find_suitable_spu_in_racks(&racks, &[], SpuWeightSelection::Leader),
+            Some(0)
+        );
+    }
 }
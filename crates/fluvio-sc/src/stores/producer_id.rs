@@ -0,0 +1,39 @@
+//!
+//! # Producer Ids
+//!
+//! Allocation of ids for idempotent producers.
+//! Ids are seeded from current time so ids allocated after SC restart don't collide
+//! with ids of producers which are still running.
+//!
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub type SharedProducerIds = Arc<ProducerIds>;
+
+#[derive(Debug)]
+pub struct ProducerIds {
+    next_id: AtomicI64,
+}
+
+impl ProducerIds {
+    pub fn shared() -> SharedProducerIds {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_micros() as i64)
+            .unwrap_or_default();
+        Arc::new(Self::new(seed))
+    }
+
+    pub(crate) fn new(seed: i64) -> Self {
+        Self {
+            next_id: AtomicI64::new(seed),
+        }
+    }
+
+    /// allocate new unique producer id
+    pub fn next_id(&self) -> i64 {
+        self.next_id.fetch_add(1, Ordering::SeqCst)
+    }
+}
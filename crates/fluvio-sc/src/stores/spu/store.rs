@@ -227,12 +227,12 @@ where
         table
     }
 
-    /// number of spus in rack count
+    /// number of online spus in rack
     async fn spus_in_rack_count(&self) -> u32 {
         self.read()
             .await
             .values()
-            .filter(|spu| spu.spec.rack.is_some())
+            .filter(|spu| spu.status.is_online() && spu.spec.rack.is_some())
             .count() as u32
     }
 
@@ -0,0 +1,632 @@
+//!
+//! # Transactions
+//!
+//! State of transactions of transactional producers.
+//! State is written to [`TransactionSpec`] metadata before requests are answered and loaded
+//! when SC starts, so transactions open or ending during SC restart are still ended by SC.
+//! Transactions idle longer than [`TRANSACTION_TIMEOUT`] are aborted and their producer is fenced.
+//!
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_lock::{Mutex, MutexGuard, RwLock};
+use tracing::debug;
+
+use fluvio_protocol::record::{Offset, ReplicaKey};
+use fluvio_sc_schema::errors::ErrorCode;
+
+pub use fluvio_controlplane_metadata::transaction::*;
+
+use super::producer_id::ProducerIds;
+
+pub type SharedTransactions = Arc<Transactions>;
+
+/// transaction without any request of its producer for this long is aborted
+pub const TRANSACTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TxnState {
+    Empty,
+    Ongoing,
+    /// control batches are being written, they are kept until all of them are written,
+    /// so producer id and decision don't change when producer is fenced meanwhile
+    Ending(TxnEnd),
+}
+
+#[derive(Debug, Clone)]
+struct TxnMetadata {
+    producer_id: i64,
+    producer_epoch: i16,
+    state: TxnState,
+    partitions: BTreeSet<ReplicaKey>,
+    /// offsets committed with the transaction by consumer group and replica
+    offsets: BTreeMap<(String, ReplicaKey), Offset>,
+    last_update: Instant,
+}
+
+impl TxnMetadata {
+    fn new(producer_id: i64) -> Self {
+        Self {
+            producer_id,
+            producer_epoch: 0,
+            state: TxnState::Empty,
+            partitions: BTreeSet::new(),
+            offsets: BTreeMap::new(),
+            last_update: Instant::now(),
+        }
+    }
+
+    fn validate(&self, producer_id: i64, producer_epoch: i16) -> Result<(), ErrorCode> {
+        if self.producer_id != producer_id || self.producer_epoch != producer_epoch {
+            return Err(ErrorCode::InvalidProducerEpoch);
+        }
+        Ok(())
+    }
+
+    /// start transaction if it's not open
+    fn begin(&mut self) -> Result<(), ErrorCode> {
+        match self.state {
+            TxnState::Ending(_) => Err(ErrorCode::InvalidTransactionState(
+                "transaction is ending".to_owned(),
+            )),
+            _ => {
+                self.state = TxnState::Ongoing;
+                Ok(())
+            }
+        }
+    }
+
+    /// fence previous instances of the producer, id is changed when epoch is exhausted
+    fn bump_epoch(&mut self, producer_ids: &ProducerIds) {
+        match self.producer_epoch.checked_add(1) {
+            Some(epoch) => self.producer_epoch = epoch,
+            None => {
+                self.producer_id = producer_ids.next_id();
+                self.producer_epoch = 0;
+            }
+        }
+    }
+
+    /// abort open transaction, offsets are not committed
+    fn abort(&mut self) -> TxnEnd {
+        self.offsets.clear();
+        self.end(false)
+    }
+
+    fn end(&mut self, commit: bool) -> TxnEnd {
+        let end = self.to_end(commit);
+        self.state = TxnState::Ending(end.clone());
+        end
+    }
+
+    fn to_end(&self, commit: bool) -> TxnEnd {
+        TxnEnd {
+            producer_id: self.producer_id,
+            producer_epoch: self.producer_epoch,
+            commit,
+            partitions: self.partitions.iter().cloned().collect(),
+            offsets: if commit {
+                self.offsets
+                    .iter()
+                    .map(|((group, replica), offset)| (group.clone(), replica.clone(), *offset))
+                    .collect()
+            } else {
+                vec![]
+            },
+        }
+    }
+}
+
+/// Transaction to be ended by writing control batches to its partitions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxnEnd {
+    pub producer_id: i64,
+    pub producer_epoch: i16,
+    pub commit: bool,
+    pub partitions: Vec<ReplicaKey>,
+    /// consumer group, replica and offset to commit
+    pub offsets: Vec<(String, ReplicaKey, Offset)>,
+}
+
+#[derive(Debug, Default)]
+pub struct Transactions {
+    txns: RwLock<HashMap<String, TxnMetadata>>,
+    persist: Mutex<()>,
+}
+
+impl Transactions {
+    pub fn shared() -> SharedTransactions {
+        Arc::new(Self::default())
+    }
+
+    /// load transactions written to metadata before SC restart
+    pub async fn load(&self, specs: impl IntoIterator<Item = (String, TransactionSpec)>) {
+        let mut txns = self.txns.write().await;
+        for (transactional_id, spec) in specs {
+            debug!(transactional_id, state = ?spec.state, "loaded transaction");
+            txns.insert(transactional_id, spec.into());
+        }
+    }
+
+    /// current state of transaction to be written to metadata
+    pub async fn spec(&self, transactional_id: &str) -> Option<TransactionSpec> {
+        let txns = self.txns.read().await;
+        txns.get(transactional_id).map(TransactionSpec::from)
+    }
+
+    /// Held while state is written to metadata, so state read earlier doesn't overwrite newer state
+    pub async fn persist_lock(&self) -> MutexGuard<'_, ()> {
+        self.persist.lock().await
+    }
+
+    /// Register new instance of transactional producer, returns its producer id and epoch.
+    /// Epoch is incremented, so previous instances are fenced. If previous instance left open
+    /// transaction, it's returned to be aborted before new instance can start.
+    pub async fn init_producer(
+        &self,
+        transactional_id: &str,
+        producer_ids: &ProducerIds,
+    ) -> (i64, i16, Option<TxnEnd>) {
+        let mut txns = self.txns.write().await;
+        let txn = txns
+            .entry(transactional_id.to_owned())
+            .or_insert_with(|| TxnMetadata::new(producer_ids.next_id()));
+        // control batches of pending transaction use producer id of the previous instance
+        let pending = match txn.state {
+            TxnState::Empty => None,
+            TxnState::Ongoing => Some(txn.abort()),
+            TxnState::Ending(ref end) => Some(end.clone()),
+        };
+        txn.bump_epoch(producer_ids);
+        txn.last_update = Instant::now();
+        debug!(
+            transactional_id,
+            producer_id = txn.producer_id,
+            producer_epoch = txn.producer_epoch,
+            "transactional producer initialized"
+        );
+        (txn.producer_id, txn.producer_epoch, pending)
+    }
+
+    /// add partitions written by the transaction
+    pub async fn add_partitions(
+        &self,
+        transactional_id: &str,
+        producer_id: i64,
+        producer_epoch: i16,
+        partitions: Vec<ReplicaKey>,
+    ) -> Result<(), ErrorCode> {
+        let mut txns = self.txns.write().await;
+        let txn = find_txn(&mut txns, transactional_id)?;
+        txn.validate(producer_id, producer_epoch)?;
+        txn.begin()?;
+        txn.partitions.extend(partitions);
+        txn.last_update = Instant::now();
+        Ok(())
+    }
+
+    /// add consumer offsets to be committed with the transaction
+    pub async fn add_offsets(
+        &self,
+        transactional_id: &str,
+        producer_id: i64,
+        producer_epoch: i16,
+        consumer_group: &str,
+        offsets: Vec<(ReplicaKey, Offset)>,
+    ) -> Result<(), ErrorCode> {
+        let mut txns = self.txns.write().await;
+        let txn = find_txn(&mut txns, transactional_id)?;
+        txn.validate(producer_id, producer_epoch)?;
+        txn.begin()?;
+        for (replica, offset) in offsets {
+            txn.offsets
+                .insert((consumer_group.to_owned(), replica), offset);
+        }
+        txn.last_update = Instant::now();
+        Ok(())
+    }
+
+    /// Start ending transaction. Returned partitions must get control batches
+    /// before transaction is completed. Ending transaction which failed to complete
+    /// must use the same decision.
+    pub async fn prepare_end(
+        &self,
+        transactional_id: &str,
+        producer_id: i64,
+        producer_epoch: i16,
+        commit: bool,
+    ) -> Result<TxnEnd, ErrorCode> {
+        let mut txns = self.txns.write().await;
+        let txn = find_txn(&mut txns, transactional_id)?;
+        txn.validate(producer_id, producer_epoch)?;
+        txn.last_update = Instant::now();
+        match txn.state {
+            TxnState::Ending(ref end) if end.commit != commit => {
+                Err(ErrorCode::InvalidTransactionState(format!(
+                    "transaction is being {}",
+                    if end.commit { "committed" } else { "aborted" }
+                )))
+            }
+            TxnState::Ending(ref end) => Ok(end.clone()),
+            _ => Ok(txn.end(commit)),
+        }
+    }
+
+    /// Abort transactions idle longer than [`TRANSACTION_TIMEOUT`], their producers are fenced.
+    /// Ending transactions which failed to complete are returned again with the same decision.
+    pub async fn expire(&self, now: Instant, producer_ids: &ProducerIds) -> Vec<(String, TxnEnd)> {
+        let mut txns = self.txns.write().await;
+        let mut expired = vec![];
+        for (transactional_id, txn) in txns.iter_mut() {
+            if now.saturating_duration_since(txn.last_update) < TRANSACTION_TIMEOUT {
+                continue;
+            }
+            let end = match txn.state {
+                TxnState::Empty => continue,
+                TxnState::Ongoing => {
+                    let end = txn.abort();
+                    txn.bump_epoch(producer_ids);
+                    debug!(
+                        transactional_id,
+                        producer_id = txn.producer_id,
+                        producer_epoch = txn.producer_epoch,
+                        "idle transaction is aborted"
+                    );
+                    end
+                }
+                TxnState::Ending(ref end) => end.clone(),
+            };
+            txn.last_update = now;
+            expired.push((transactional_id.clone(), end));
+        }
+        expired
+    }
+
+    /// topics of partitions written by the transaction
+    pub async fn topics(&self, transactional_id: &str) -> BTreeSet<String> {
+        let txns = self.txns.read().await;
+        let Some(txn) = txns.get(transactional_id) else {
+            return BTreeSet::new();
+        };
+        txn.partitions
+            .iter()
+            .map(|replica| replica.topic.clone())
+            .collect()
+    }
+
+    /// topics of consumer offsets committed by the transaction
+    pub async fn offset_topics(&self, transactional_id: &str) -> BTreeSet<String> {
+        let txns = self.txns.read().await;
+        let Some(txn) = txns.get(transactional_id) else {
+            return BTreeSet::new();
+        };
+        txn.offsets
+            .keys()
+            .map(|(_, replica)| replica.topic.clone())
+            .collect()
+    }
+
+    /// all control batches of ending transaction are written
+    pub async fn complete(&self, transactional_id: &str, end: &TxnEnd) {
+        let mut txns = self.txns.write().await;
+        if let Some(txn) = txns.get_mut(transactional_id) {
+            if matches!(&txn.state, TxnState::Ending(pending) if pending == end) {
+                txn.state = TxnState::Empty;
+                txn.partitions.clear();
+                txn.offsets.clear();
+            }
+        }
+    }
+}
+
+impl From<&TxnMetadata> for TransactionSpec {
+    fn from(txn: &TxnMetadata) -> Self {
+        Self {
+            producer_id: txn.producer_id,
+            producer_epoch: txn.producer_epoch,
+            state: match &txn.state {
+                TxnState::Empty => TransactionState::Empty,
+                TxnState::Ongoing => TransactionState::Ongoing,
+                TxnState::Ending(end) => TransactionState::Ending(end.into()),
+            },
+            partitions: txn.partitions.iter().map(to_partition).collect(),
+            offsets: txn
+                .offsets
+                .iter()
+                .map(|((group, replica), offset)| to_offset(group, replica, *offset))
+                .collect(),
+        }
+    }
+}
+
+impl From<TransactionSpec> for TxnMetadata {
+    fn from(spec: TransactionSpec) -> Self {
+        Self {
+            producer_id: spec.producer_id,
+            producer_epoch: spec.producer_epoch,
+            state: match spec.state {
+                TransactionState::Empty => TxnState::Empty,
+                TransactionState::Ongoing => TxnState::Ongoing,
+                TransactionState::Ending(end) => TxnState::Ending(end.into()),
+            },
+            partitions: spec.partitions.into_iter().map(from_partition).collect(),
+            offsets: spec
+                .offsets
+                .into_iter()
+                .map(|offset| {
+                    let (group, replica, offset) = from_offset(offset);
+                    ((group, replica), offset)
+                })
+                .collect(),
+            last_update: Instant::now(),
+        }
+    }
+}
+
+impl From<&TxnEnd> for TransactionEnd {
+    fn from(end: &TxnEnd) -> Self {
+        Self {
+            producer_id: end.producer_id,
+            producer_epoch: end.producer_epoch,
+            commit: end.commit,
+            partitions: end.partitions.iter().map(to_partition).collect(),
+            offsets: end
+                .offsets
+                .iter()
+                .map(|(group, replica, offset)| to_offset(group, replica, *offset))
+                .collect(),
+        }
+    }
+}
+
+impl From<TransactionEnd> for TxnEnd {
+    fn from(end: TransactionEnd) -> Self {
+        Self {
+            producer_id: end.producer_id,
+            producer_epoch: end.producer_epoch,
+            commit: end.commit,
+            partitions: end.partitions.into_iter().map(from_partition).collect(),
+            offsets: end.offsets.into_iter().map(from_offset).collect(),
+        }
+    }
+}
+
+fn to_partition(replica: &ReplicaKey) -> TransactionPartition {
+    TransactionPartition {
+        topic: replica.topic.clone(),
+        partition: replica.partition,
+    }
+}
+
+fn from_partition(partition: TransactionPartition) -> ReplicaKey {
+    ReplicaKey::new(partition.topic, partition.partition)
+}
+
+fn to_offset(group: &str, replica: &ReplicaKey, offset: Offset) -> TransactionOffset {
+    TransactionOffset {
+        consumer_group: group.to_owned(),
+        topic: replica.topic.clone(),
+        partition: replica.partition,
+        offset,
+    }
+}
+
+fn from_offset(offset: TransactionOffset) -> (String, ReplicaKey, Offset) {
+    (
+        offset.consumer_group,
+        ReplicaKey::new(offset.topic, offset.partition),
+        offset.offset,
+    )
+}
+
+fn find_txn<'a>(
+    txns: &'a mut HashMap<String, TxnMetadata>,
+    transactional_id: &str,
+) -> Result<&'a mut TxnMetadata, ErrorCode> {
+    txns.get_mut(transactional_id).ok_or_else(|| {
+        ErrorCode::InvalidTransactionState(format!("unknown transactional id: {transactional_id}"))
+    })
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::*;
+
+    fn replica(partition: u32) -> ReplicaKey {
+        ReplicaKey::new("topic", partition)
+    }
+
+    #[fluvio_future::test]
+    async fn test_commit_transaction() {
+        let transactions = Transactions::default();
+        let producer_ids = ProducerIds::new(100);
+
+        let (producer_id, epoch, pending) = transactions.init_producer("tx", &producer_ids).await;
+        assert_eq!(producer_id, 100);
+        assert_eq!(epoch, 1);
+        assert!(pending.is_none());
+
+        transactions
+            .add_partitions("tx", producer_id, epoch, vec![replica(0), replica(1)])
+            .await
+            .expect("add partitions");
+        transactions
+            .add_offsets("tx", producer_id, epoch, "group", vec![(replica(0), 5)])
+            .await
+            .expect("add offsets");
+
+        let end = transactions
+            .prepare_end("tx", producer_id, epoch, true)
+            .await
+            .expect("end");
+        assert!(end.commit);
+        assert_eq!(end.partitions, vec![replica(0), replica(1)]);
+        assert_eq!(end.offsets, vec![("group".to_owned(), replica(0), 5)]);
+
+        // retry must keep the decision
+        assert!(matches!(
+            transactions
+                .prepare_end("tx", producer_id, epoch, false)
+                .await,
+            Err(ErrorCode::InvalidTransactionState(_))
+        ));
+
+        assert_eq!(
+            transactions.topics("tx").await,
+            BTreeSet::from(["topic".to_owned()])
+        );
+        assert_eq!(
+            transactions.offset_topics("tx").await,
+            BTreeSet::from(["topic".to_owned()])
+        );
+        transactions.complete("tx", &end).await;
+        assert!(transactions.topics("tx").await.is_empty());
+        assert!(transactions.offset_topics("tx").await.is_empty());
+        let end = transactions
+            .prepare_end("tx", producer_id, epoch, true)
+            .await
+            .expect("end");
+        assert!(end.partitions.is_empty());
+    }
+
+    #[fluvio_future::test]
+    async fn test_new_instance_fences_and_aborts() {
+        let transactions = Transactions::default();
+        let producer_ids = ProducerIds::new(100);
+
+        let (producer_id, epoch, _) = transactions.init_producer("tx", &producer_ids).await;
+        transactions
+            .add_partitions("tx", producer_id, epoch, vec![replica(0)])
+            .await
+            .expect("add partitions");
+        transactions
+            .add_offsets("tx", producer_id, epoch, "group", vec![(replica(0), 5)])
+            .await
+            .expect("add offsets");
+
+        let (new_producer_id, new_epoch, pending) =
+            transactions.init_producer("tx", &producer_ids).await;
+        assert_eq!(new_producer_id, producer_id);
+        assert_eq!(new_epoch, epoch + 1);
+        let pending = pending.expect("open transaction is aborted");
+        assert!(!pending.commit);
+        assert_eq!(pending.producer_epoch, epoch);
+        assert_eq!(pending.partitions, vec![replica(0)]);
+        assert!(pending.offsets.is_empty());
+
+        // old instance is fenced
+        assert_eq!(
+            transactions
+                .add_partitions("tx", producer_id, epoch, vec![replica(1)])
+                .await,
+            Err(ErrorCode::InvalidProducerEpoch)
+        );
+        // new instance can't start until abort is completed
+        assert!(matches!(
+            transactions
+                .add_partitions("tx", new_producer_id, new_epoch, vec![replica(1)])
+                .await,
+            Err(ErrorCode::InvalidTransactionState(_))
+        ));
+        transactions.complete("tx", &pending).await;
+        transactions
+            .add_partitions("tx", new_producer_id, new_epoch, vec![replica(1)])
+            .await
+            .expect("add partitions");
+    }
+
+    #[fluvio_future::test]
+    async fn test_expire_idle_transaction() {
+        let transactions = Transactions::default();
+        let producer_ids = ProducerIds::new(100);
+
+        let (producer_id, epoch, _) = transactions.init_producer("tx", &producer_ids).await;
+        transactions
+            .add_partitions("tx", producer_id, epoch, vec![replica(0)])
+            .await
+            .expect("add partitions");
+        transactions
+            .add_offsets("tx", producer_id, epoch, "group", vec![(replica(0), 5)])
+            .await
+            .expect("add offsets");
+
+        assert!(transactions
+            .expire(Instant::now(), &producer_ids)
+            .await
+            .is_empty());
+
+        let later = Instant::now() + TRANSACTION_TIMEOUT;
+        let expired = transactions.expire(later, &producer_ids).await;
+        assert_eq!(expired.len(), 1);
+        let (transactional_id, end) = &expired[0];
+        assert_eq!(transactional_id, "tx");
+        assert!(!end.commit);
+        assert_eq!(end.producer_epoch, epoch);
+        assert_eq!(end.partitions, vec![replica(0)]);
+        assert!(end.offsets.is_empty());
+
+        // producer is fenced
+        assert_eq!(
+            transactions
+                .prepare_end("tx", producer_id, epoch, true)
+                .await,
+            Err(ErrorCode::InvalidProducerEpoch)
+        );
+
+        // abort is retried until it's completed
+        assert!(transactions.expire(later, &producer_ids).await.is_empty());
+        let expired = transactions
+            .expire(later + TRANSACTION_TIMEOUT, &producer_ids)
+            .await;
+        assert_eq!(expired, vec![("tx".to_owned(), end.clone())]);
+
+        transactions.complete("tx", end).await;
+        assert!(transactions
+            .expire(later + TRANSACTION_TIMEOUT * 2, &producer_ids)
+            .await
+            .is_empty());
+    }
+
+    #[fluvio_future::test]
+    async fn test_load_transactions() {
+        let transactions = Transactions::default();
+        let producer_ids = ProducerIds::new(100);
+
+        let (producer_id, epoch, _) = transactions.init_producer("tx", &producer_ids).await;
+        transactions
+            .add_partitions("tx", producer_id, epoch, vec![replica(0), replica(1)])
+            .await
+            .expect("add partitions");
+        transactions
+            .add_offsets("tx", producer_id, epoch, "group", vec![(replica(0), 5)])
+            .await
+            .expect("add offsets");
+        let end = transactions
+            .prepare_end("tx", producer_id, epoch, true)
+            .await
+            .expect("end");
+        let spec = transactions.spec("tx").await.expect("spec");
+        assert!(matches!(spec.state, TransactionState::Ending(_)));
+        assert!(transactions.spec("other").await.is_none());
+
+        // restarted SC ends transaction with the same decision
+        let restarted = Transactions::default();
+        restarted.load([("tx".to_owned(), spec.clone())]).await;
+        assert_eq!(restarted.spec("tx").await, Some(spec));
+        assert_eq!(
+            restarted
+                .prepare_end("tx", producer_id, epoch, true)
+                .await
+                .expect("end"),
+            end
+        );
+        restarted.complete("tx", &end).await;
+        assert_eq!(
+            restarted.spec("tx").await.map(|spec| spec.state),
+            Some(TransactionState::Empty)
+        );
+    }
+}
@@ -27,6 +27,13 @@ impl fmt::Debug for ConnectInfo {
     }
 }
 
+impl ConnectInfo {
+    /// address of connected client
+    pub fn peer(&self) -> &str {
+        &self.peer
+    }
+}
+
 /// Trait for responding to kf service
 /// Request -> Response is type specific
 /// Each response is responsible for sending back to socket
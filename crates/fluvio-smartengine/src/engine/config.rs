@@ -4,7 +4,11 @@ use derive_builder::Builder;
 
 use fluvio_protocol::Version;
 use fluvio_smartmodule::SMARTMODULE_TIMESTAMPS_VERSION;
-use fluvio_smartmodule::dataplane::smartmodule::SmartModuleExtraParams;
+use fluvio_smartmodule::dataplane::smartmodule::{SmartModuleErrorPolicy, SmartModuleExtraParams};
+
+use super::join::SmartModuleJoinTable;
+use super::key_value::SmartModuleKeyValueState;
+use super::window::SmartModuleWindowConfig;
 
 pub const DEFAULT_SMARTENGINE_VERSION: Version = SMARTMODULE_TIMESTAMPS_VERSION;
 
@@ -14,12 +18,22 @@ pub const DEFAULT_SMARTENGINE_VERSION: Version = SMARTMODULE_TIMESTAMPS_VERSION;
 pub enum SmartModuleInitialData {
     None,
     Aggregate { accumulator: Vec<u8> },
+    Join { table: SmartModuleJoinTable },
+    Window { config: SmartModuleWindowConfig },
 }
 
 impl SmartModuleInitialData {
     pub fn with_aggregate(accumulator: Vec<u8>) -> Self {
         Self::Aggregate { accumulator }
     }
+
+    pub fn with_join(table: SmartModuleJoinTable) -> Self {
+        Self::Join { table }
+    }
+
+    pub fn with_window(config: SmartModuleWindowConfig) -> Self {
+        Self::Window { config }
+    }
 }
 
 impl Default for SmartModuleInitialData {
@@ -40,6 +54,21 @@ pub struct SmartModuleConfig {
     pub(crate) version: Option<i16>,
     #[builder(default)]
     pub(crate) lookback: Option<Lookback>,
+    /// fuel allowed for each call, overrides limit of chain
+    #[builder(default, setter(strip_option))]
+    pub(crate) fuel_limit: Option<u64>,
+    /// time allowed for each call, overrides timeout of chain
+    #[builder(default, setter(strip_option))]
+    pub(crate) call_timeout: Option<Duration>,
+    /// key-value state available to SmartModule thru host functions
+    #[builder(default)]
+    pub(crate) key_value_state: SmartModuleKeyValueState,
+    /// what chain does with records SmartModule failed to process
+    #[builder(default)]
+    pub(crate) error_policy: SmartModuleErrorPolicy,
+    /// name of SmartModule, compiled SmartModule is cached by name and hash of its binary
+    #[builder(default, setter(into, strip_option))]
+    pub(crate) name: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -86,6 +115,11 @@ impl From<crate::transformation::TransformationStep> for SmartModuleConfig {
                 .into(),
             version: None,
             lookback: step.lookback.map(|l| l.into()),
+            fuel_limit: step.fuel_limit,
+            call_timeout: step.call_timeout,
+            key_value_state: Default::default(),
+            error_policy: step.on_error.map(Into::into).unwrap_or_default(),
+            name: None,
         }
     }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 #[derive(thiserror::Error, Debug)]
 pub enum EngineError {
     #[error("No valid smartmodule found")]
@@ -10,4 +12,8 @@ pub enum EngineError {
         requested: usize,
         max: usize,
     },
+    #[error("SmartModule call exhausted fuel limit {limit}")]
+    FuelExhausted { limit: u64 },
+    #[error("SmartModule call exceeded timeout {timeout:?}")]
+    CallTimeout { timeout: Duration },
 }
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// 64 MB
+pub const DEFAULT_JOIN_TABLE_MAX_SIZE: usize = 64 * 1024 * 1024;
+
+/// Latest value of each key of the joined topic.
+/// It is filled by the owner of the chain and read by Join SmartModule.
+#[derive(Debug, Clone, Default)]
+pub struct SmartModuleJoinTable(Arc<RwLock<Arc<RwLock<JoinTableInner>>>>);
+
+#[derive(Debug)]
+struct JoinTableInner {
+    entries: HashMap<Vec<u8>, Vec<u8>>,
+    // sum of key and value lengths
+    size: usize,
+    max_size: usize,
+}
+
+impl Default for JoinTableInner {
+    fn default() -> Self {
+        Self {
+            entries: HashMap::new(),
+            size: 0,
+            max_size: DEFAULT_JOIN_TABLE_MAX_SIZE,
+        }
+    }
+}
+
+impl SmartModuleJoinTable {
+    /// max total size of keys and values stored in the table
+    pub fn set_max_size(&self, max_size: usize) {
+        self.inner().write().unwrap().max_size = max_size;
+    }
+
+    /// make this table and its clones read entries of other table,
+    /// so chains joining the same partition share one table
+    pub fn share(&self, other: &SmartModuleJoinTable) {
+        let inner = other.inner();
+        *self.0.write().unwrap() = inner;
+    }
+
+    /// store latest value of the key, empty value is stored as any other value.
+    /// returns false if max size would be exceeded
+    pub fn upsert(&self, key: &[u8], value: &[u8]) -> bool {
+        let inner = self.inner();
+        let mut table = inner.write().unwrap();
+        let replaced = table
+            .entries
+            .get(key)
+            .map(|old| key.len() + old.len())
+            .unwrap_or_default();
+        let size = table.size - replaced + key.len() + value.len();
+        if size > table.max_size {
+            return false;
+        }
+        table.entries.insert(key.to_vec(), value.to_vec());
+        table.size = size;
+        true
+    }
+
+    /// remove the key, for tombstone records of joined topic
+    pub fn remove(&self, key: &[u8]) {
+        let inner = self.inner();
+        let mut table = inner.write().unwrap();
+        if let Some(old) = table.entries.remove(key) {
+            table.size -= key.len() + old.len();
+        }
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.inner().read().unwrap().entries.get(key).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner().read().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn inner(&self) -> Arc<RwLock<JoinTableInner>> {
+        self.0.read().unwrap().clone()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::SmartModuleJoinTable;
+
+    #[test]
+    fn test_join_table_upsert() {
+        let table = SmartModuleJoinTable::default();
+        let shared = table.clone();
+
+        assert!(table.upsert(b"a", b"1"));
+        assert!(table.upsert(b"b", b"2"));
+        assert!(table.upsert(b"a", b"3"));
+
+        assert_eq!(shared.len(), 2);
+        assert_eq!(shared.get(b"a"), Some(b"3".to_vec()));
+        assert_eq!(shared.get(b"b"), Some(b"2".to_vec()));
+        assert_eq!(shared.get(b"c"), None);
+
+        assert!(table.upsert(b"a", b""));
+        assert_eq!(shared.get(b"a"), Some(vec![]));
+        assert_eq!(shared.len(), 2);
+
+        table.remove(b"a");
+        assert_eq!(shared.get(b"a"), None);
+        assert_eq!(shared.len(), 1);
+    }
+
+    #[test]
+    fn test_join_table_max_size() {
+        let table = SmartModuleJoinTable::default();
+        table.set_max_size(8);
+
+        assert!(table.upsert(b"a", b"123"));
+        assert!(table.upsert(b"b", b"123"));
+        assert!(!table.upsert(b"c", b"1"));
+
+        // removing key frees its size
+        table.remove(b"a");
+        assert!(table.upsert(b"c", b"1"));
+        assert_eq!(table.len(), 2);
+    }
+
+    #[test]
+    fn test_join_table_share() {
+        let chain_table = SmartModuleJoinTable::default();
+        let chain_clone = chain_table.clone();
+        let partition_table = SmartModuleJoinTable::default();
+        assert!(partition_table.upsert(b"a", b"1"));
+
+        chain_table.share(&partition_table);
+
+        assert_eq!(chain_clone.get(b"a"), Some(b"1".to_vec()));
+        assert!(partition_table.upsert(b"b", b"2"));
+        assert_eq!(chain_clone.len(), 2);
+    }
+}
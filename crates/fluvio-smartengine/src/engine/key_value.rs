@@ -0,0 +1,163 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+/// 64 MB
+pub const DEFAULT_KEY_VALUE_STATE_MAX_SIZE: usize = 64 * 1024 * 1024;
+
+/// Key-value state of SmartModule, read and written by SmartModule thru host functions.
+/// It is loaded and persisted by the owner of the chain.
+#[derive(Debug, Clone, Default)]
+pub struct SmartModuleKeyValueState(Arc<Mutex<KeyValueInner>>);
+
+#[derive(Debug)]
+struct KeyValueInner {
+    entries: BTreeMap<Vec<u8>, Vec<u8>>,
+    // sum of key and value lengths
+    size: usize,
+    max_size: usize,
+    // keys put or deleted since last persisted
+    changed: BTreeSet<Vec<u8>>,
+}
+
+impl Default for KeyValueInner {
+    fn default() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            size: 0,
+            max_size: DEFAULT_KEY_VALUE_STATE_MAX_SIZE,
+            changed: BTreeSet::new(),
+        }
+    }
+}
+
+impl SmartModuleKeyValueState {
+    /// max total size of keys and values SmartModule can store
+    pub fn set_max_size(&self, max_size: usize) {
+        self.0.lock().unwrap().max_size = max_size;
+    }
+
+    /// replace entries with persisted ones
+    pub fn load(&self, entries: BTreeMap<Vec<u8>, Vec<u8>>) {
+        let mut inner = self.0.lock().unwrap();
+        inner.size = entries.iter().map(|(k, v)| k.len() + v.len()).sum();
+        inner.entries = entries;
+        inner.changed.clear();
+    }
+
+    /// keys changed since last call with their values, `None` if key was deleted
+    pub fn take_changes(&self) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+        let mut inner = self.0.lock().unwrap();
+        let changed = std::mem::take(&mut inner.changed);
+        changed
+            .into_iter()
+            .map(|key| {
+                let value = inner.entries.get(&key).cloned();
+                (key, value)
+            })
+            .collect()
+    }
+
+    pub fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.0.lock().unwrap().entries.get(key).cloned()
+    }
+
+    /// store value under the key, returns false if max size would be exceeded
+    pub fn put(&self, key: &[u8], value: &[u8]) -> bool {
+        let mut inner = self.0.lock().unwrap();
+        let replaced = inner
+            .entries
+            .get(key)
+            .map(|old| key.len() + old.len())
+            .unwrap_or_default();
+        let size = inner.size - replaced + key.len() + value.len();
+        if size > inner.max_size {
+            return false;
+        }
+        inner.entries.insert(key.to_vec(), value.to_vec());
+        inner.size = size;
+        inner.changed.insert(key.to_vec());
+        true
+    }
+
+    pub fn delete(&self, key: &[u8]) {
+        let mut inner = self.0.lock().unwrap();
+        if let Some(old) = inner.entries.remove(key) {
+            inner.size -= key.len() + old.len();
+            inner.changed.insert(key.to_vec());
+        }
+    }
+
+    /// entries with keys starting with prefix, ordered by key
+    pub fn scan(&self, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+        self.0
+            .lock()
+            .unwrap()
+            .entries
+            .range(prefix.to_vec()..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.lock().unwrap().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::collections::BTreeMap;
+
+    use super::SmartModuleKeyValueState;
+
+    #[test]
+    fn test_key_value_state() {
+        let state = SmartModuleKeyValueState::default();
+        let shared = state.clone();
+        state.load(BTreeMap::from([(b"a/1".to_vec(), b"1".to_vec())]));
+        assert!(state.take_changes().is_empty());
+
+        assert!(state.put(b"a/2", b"2"));
+        assert!(state.put(b"b/1", b"3"));
+        assert_eq!(shared.get(b"a/2"), Some(b"2".to_vec()));
+        assert_eq!(
+            shared.scan(b"a/"),
+            vec![
+                (b"a/1".to_vec(), b"1".to_vec()),
+                (b"a/2".to_vec(), b"2".to_vec())
+            ]
+        );
+
+        state.delete(b"a/1");
+        assert_eq!(shared.len(), 2);
+        assert_eq!(
+            shared.take_changes(),
+            vec![
+                (b"a/1".to_vec(), None),
+                (b"a/2".to_vec(), Some(b"2".to_vec())),
+                (b"b/1".to_vec(), Some(b"3".to_vec()))
+            ]
+        );
+        assert!(shared.take_changes().is_empty());
+    }
+
+    #[test]
+    fn test_key_value_state_max_size() {
+        let state = SmartModuleKeyValueState::default();
+        state.set_max_size(8);
+
+        assert!(state.put(b"a", b"123"));
+        assert!(state.put(b"b", b"123"));
+        assert!(!state.put(b"c", b"1"));
+
+        // replacing value frees its size
+        assert!(state.put(b"a", b"1"));
+        assert!(state.put(b"c", b"1"));
+        assert_eq!(state.len(), 3);
+    }
+}
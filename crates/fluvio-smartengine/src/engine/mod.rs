@@ -2,7 +2,10 @@
 
 mod config;
 mod error;
+mod join;
+mod key_value;
 mod wasmtime;
+mod window;
 
 #[cfg(test)]
 mod fixture;
@@ -10,6 +13,9 @@ mod fixture;
 pub mod metrics;
 
 pub use error::EngineError;
+pub use join::{SmartModuleJoinTable, DEFAULT_JOIN_TABLE_MAX_SIZE};
+pub use key_value::{SmartModuleKeyValueState, DEFAULT_KEY_VALUE_STATE_MAX_SIZE};
+pub use window::SmartModuleWindowConfig;
 pub use config::{
     SmartModuleConfig, SmartModuleConfigBuilder, SmartModuleConfigBuilderError,
     SmartModuleInitialData, Lookback, DEFAULT_SMARTENGINE_VERSION,
@@ -20,4 +26,6 @@ pub type WasmSlice = (i32, i32, u32);
 /// SmartEngine Version
 pub type Version = i16;
 
-pub use self::wasmtime::{SmartEngine, SmartModuleChainBuilder, SmartModuleChainInstance};
+pub use self::wasmtime::{
+    SmartEngine, SmartModuleChainBuilder, SmartModuleChainInstance, SmartModuleDeadLetter,
+};
@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use tracing::{debug, warn};
+use wasmtime::Engine;
+use wasmtime::Module;
+use wasmtime::component::Component;
+
+use crate::engine::error::EngineError;
+
+use super::component::is_component;
+
+const COMPILED_EXTENSION: &str = "cwasm";
+
+/// SmartModule compiled for the engine
+#[derive(Clone)]
+pub(crate) enum CompiledSmartModule {
+    Module(Module),
+    Component(Component),
+}
+
+impl CompiledSmartModule {
+    fn compile(engine: &Engine, bytes: &[u8]) -> Result<Self> {
+        if is_component(bytes) {
+            let component = Component::new(engine, bytes).map_err(EngineError::Instantiate)?;
+            Ok(Self::Component(component))
+        } else {
+            Ok(Self::Module(Module::new(engine, bytes)?))
+        }
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>> {
+        match self {
+            Self::Module(module) => module.serialize(),
+            Self::Component(component) => component.serialize(),
+        }
+    }
+
+    /// Safety: file must be written by `serialize` of the same engine configuration.
+    /// Wasmtime rejects artifacts of other versions and configurations.
+    unsafe fn deserialize_file(engine: &Engine, path: &Path, component: bool) -> Result<Self> {
+        if component {
+            Ok(Self::Component(Component::deserialize_file(engine, path)?))
+        } else {
+            Ok(Self::Module(Module::deserialize_file(engine, path)?))
+        }
+    }
+}
+
+struct CachedSmartModule {
+    hash: String,
+    compiled: CompiledSmartModule,
+}
+
+/// Compiled SmartModules keyed by SmartModule name and hash of their binary.
+/// Compiled SmartModules are kept in memory and, if directory is set,
+/// serialized to disk so they are not compiled again after restart.
+#[derive(Clone, Default)]
+pub(crate) struct SmartModuleCache {
+    modules: Arc<RwLock<HashMap<String, CachedSmartModule>>>,
+    dir: Option<PathBuf>,
+}
+
+impl SmartModuleCache {
+    pub(crate) fn new(dir: Option<PathBuf>) -> Self {
+        Self {
+            modules: Default::default(),
+            dir,
+        }
+    }
+
+    /// compiled SmartModule from cache, SmartModules without name are always compiled
+    pub(crate) fn get_or_compile(
+        &self,
+        engine: &Engine,
+        name: Option<&str>,
+        bytes: &[u8],
+    ) -> Result<CompiledSmartModule> {
+        let Some(name) = name else {
+            return CompiledSmartModule::compile(engine, bytes);
+        };
+        let hash = hex::encode(Sha256::digest(bytes));
+        if let Some(cached) = self.read_modules().get(name) {
+            if cached.hash == hash {
+                debug!(name, "compiled SmartModule found in memory");
+                return Ok(cached.compiled.clone());
+            }
+        }
+
+        let compiled = match self.load(engine, name, &hash, is_component(bytes)) {
+            Some(compiled) => compiled,
+            None => {
+                let compiled = CompiledSmartModule::compile(engine, bytes)?;
+                self.store(name, &hash, &compiled);
+                compiled
+            }
+        };
+        self.write_modules().insert(
+            name.to_owned(),
+            CachedSmartModule {
+                hash,
+                compiled: compiled.clone(),
+            },
+        );
+        Ok(compiled)
+    }
+
+    /// remove compiled SmartModule, it is compiled again by the next chain using it
+    pub(crate) fn invalidate(&self, name: &str) {
+        self.write_modules().remove(name);
+        if let Some(dir) = self.module_dir(name) {
+            if dir.exists() {
+                if let Err(err) = fs::remove_dir_all(&dir) {
+                    warn!(name, ?dir, "unable to remove compiled SmartModule: {err}");
+                }
+            }
+        }
+        debug!(name, "compiled SmartModule invalidated");
+    }
+
+    fn load(
+        &self,
+        engine: &Engine,
+        name: &str,
+        hash: &str,
+        component: bool,
+    ) -> Option<CompiledSmartModule> {
+        let path = self.module_path(name, hash)?;
+        if !path.exists() {
+            return None;
+        }
+        // SAFETY: files in cache directory are written only by `store`
+        match unsafe { CompiledSmartModule::deserialize_file(engine, &path, component) } {
+            Ok(compiled) => {
+                debug!(name, ?path, "compiled SmartModule loaded from disk");
+                Some(compiled)
+            }
+            Err(err) => {
+                warn!(name, ?path, "unable to load compiled SmartModule: {err}");
+                let _ = fs::remove_file(&path);
+                None
+            }
+        }
+    }
+
+    fn store(&self, name: &str, hash: &str, compiled: &CompiledSmartModule) {
+        let (Some(dir), Some(path)) = (self.module_dir(name), self.module_path(name, hash)) else {
+            return;
+        };
+        let result = compiled.serialize().and_then(|bytes| {
+            // only the latest binary of SmartModule is kept
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+            fs::create_dir_all(&dir)?;
+            // written under temporary name, so partially written file is never loaded
+            let tmp_path = path.with_extension("tmp");
+            fs::write(&tmp_path, bytes)?;
+            fs::rename(&tmp_path, &path)?;
+            Ok(())
+        });
+        match result {
+            Ok(()) => debug!(name, ?path, "compiled SmartModule stored on disk"),
+            Err(err) => warn!(name, ?path, "unable to store compiled SmartModule: {err}"),
+        }
+    }
+
+    fn module_dir(&self, name: &str) -> Option<PathBuf> {
+        let dir_name: String = name
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        self.dir.as_ref().map(|dir| dir.join(dir_name))
+    }
+
+    fn module_path(&self, name: &str, hash: &str) -> Option<PathBuf> {
+        self.module_dir(name)
+            .map(|dir| dir.join(hash).with_extension(COMPILED_EXTENSION))
+    }
+
+    fn read_modules(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, CachedSmartModule>> {
+        self.modules.read().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn write_modules(&self) -> std::sync::RwLockWriteGuard<'_, HashMap<String, CachedSmartModule>> {
+        self.modules.write().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use crate::SmartEngine;
+    use crate::engine::fixture::read_wasm_module;
+
+    use super::{CompiledSmartModule, SmartModuleCache};
+
+    const SM_FILTER: &str = "fluvio_smartmodule_filter";
+    const SM_MAP: &str = "fluvio_smartmodule_map";
+
+    fn cached_files(dir: &std::path::Path) -> usize {
+        std::fs::read_dir(dir)
+            .map(|entries| entries.count())
+            .unwrap_or_default()
+    }
+
+    #[ignore]
+    #[test]
+    fn test_cache_compiled_smartmodule() {
+        let dir = std::env::temp_dir().join("test_cache_compiled_smartmodule");
+        let _ = std::fs::remove_dir_all(&dir);
+        let engine = SmartEngine::new();
+        let cache = SmartModuleCache::new(Some(dir.clone()));
+        let filter = read_wasm_module(SM_FILTER);
+
+        let compiled = cache
+            .get_or_compile(&engine.engine, Some("filter"), &filter)
+            .expect("compiled");
+        assert!(matches!(compiled, CompiledSmartModule::Module(_)));
+        assert_eq!(cached_files(&dir.join("filter")), 1);
+
+        // new cache with the same directory loads module from disk
+        let cache = SmartModuleCache::new(Some(dir.clone()));
+        cache
+            .get_or_compile(&engine.engine, Some("filter"), &filter)
+            .expect("loaded");
+        assert_eq!(cached_files(&dir.join("filter")), 1);
+
+        // other binary with the same name replaces compiled module
+        let map = read_wasm_module(SM_MAP);
+        cache
+            .get_or_compile(&engine.engine, Some("filter"), &map)
+            .expect("compiled");
+        assert_eq!(cached_files(&dir.join("filter")), 1);
+
+        cache.invalidate("filter");
+        assert!(!dir.join("filter").exists());
+        assert!(cache.read_modules().is_empty());
+    }
+
+    #[test]
+    fn test_module_path() {
+        let cache = SmartModuleCache::new(Some(PathBuf::from("/tmp/cache")));
+        assert_eq!(
+            cache.module_path("infinyon/jolt@0.1.0", "abc"),
+            Some(PathBuf::from("/tmp/cache/infinyon_jolt_0.1.0/abc.cwasm"))
+        );
+        assert_eq!(
+            SmartModuleCache::default().module_path("filter", "abc"),
+            None
+        );
+    }
+}
@@ -0,0 +1,308 @@
+use std::fmt::Debug;
+
+use anyhow::{anyhow, Result};
+use tracing::{debug, instrument};
+use wasmtime::Engine;
+use wasmtime::component::{Component, Linker};
+
+use fluvio_protocol::record::{Header, Record, RecordData};
+use fluvio_smartmodule::dataplane::smartmodule::{
+    SmartModuleInitRuntimeError, SmartModuleInput, SmartModuleKind, SmartModuleOutput,
+    SmartModuleTransformRuntimeError,
+};
+
+use crate::engine::config::{SmartModuleConfig, SmartModuleInitialData};
+use crate::engine::error::EngineError;
+
+use super::instance::{SmartModuleInstanceContext, SmartModuleTransform};
+use super::state::{Context, WasmState};
+
+wasmtime::component::bindgen!({
+    path: "wit",
+    world: "smartmodule",
+});
+
+use exports::fluvio::smartmodule::transform::{Header as ComponentHeader, SmartmoduleRecord};
+
+pub(crate) const COMPONENT_FN_NAME: &str = "component";
+
+// layer field of the binary header, 0 for core modules and 1 for components
+const COMPONENT_LAYER: [u8; 2] = [0x01, 0x00];
+
+/// whether binary is WebAssembly component rather than core module
+pub(crate) fn is_component(bytes: &[u8]) -> bool {
+    bytes.len() >= 8 && bytes[0..4] == *b"\0asm" && bytes[6..8] == COMPONENT_LAYER
+}
+
+/// components only implement generic transform of `smartmodule` world,
+/// so data of other SmartModule kinds and look back can't be applied
+fn check_config(config: &SmartModuleConfig) -> Result<(), EngineError> {
+    let unsupported = match config.initial_data {
+        SmartModuleInitialData::None if config.lookback.is_none() => return Ok(()),
+        SmartModuleInitialData::None => "look back",
+        SmartModuleInitialData::Aggregate { .. } => "aggregate",
+        SmartModuleInitialData::Join { .. } => "join",
+        SmartModuleInitialData::Window { .. } => "window",
+    };
+    Err(EngineError::Instantiate(anyhow!(
+        "{unsupported} is not supported by SmartModule component"
+    )))
+}
+
+/// delta of record field from base of the batch, fails if it doesn't fit
+fn delta(field: &str, value: i64, base: i64) -> Result<i64> {
+    value.checked_sub(base).ok_or_else(|| {
+        anyhow!("{field} {value} of component output is out of range of base {base}")
+    })
+}
+
+/// SmartModule built as component implementing `smartmodule` world of `wit/smartmodule.wit`
+pub(crate) struct SmartModuleComponent {
+    bindings: Smartmodule,
+}
+
+impl Debug for SmartModuleComponent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ComponentFn")
+    }
+}
+
+impl SmartModuleComponent {
+    /// instantiate component and call its init with SmartModule params
+    #[instrument(skip(state, engine, component, config))]
+    pub(crate) fn instantiate(
+        state: &mut WasmState,
+        engine: &Engine,
+        component: &Component,
+        config: &SmartModuleConfig,
+    ) -> Result<Self> {
+        debug!("creating WasmComponentInstance");
+        check_config(config)?;
+        let mut linker = Linker::<Context>::new(engine);
+        Smartmodule::add_root_to_linker(&mut linker, |ctx: &mut Context| ctx)?;
+        let (bindings, _) =
+            Smartmodule::instantiate(&mut *state, component, &linker).map_err(|e| match e
+                .downcast::<EngineError>(
+            ) {
+                Ok(e) => e,
+                Err(e) => EngineError::Instantiate(e),
+            })?;
+
+        let params: Vec<(String, String)> = config
+            .params
+            .inner()
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        bindings
+            .fluvio_smartmodule_transform()
+            .call_init(&mut *state, &params)?
+            .map_err(|hint| SmartModuleInitRuntimeError { hint })?;
+
+        Ok(Self { bindings })
+    }
+}
+
+impl SmartmoduleImports for Context {
+    fn log(&mut self, message: String) -> Result<()> {
+        debug!(message, "smartmodule log");
+        self.push_log(message);
+        Ok(())
+    }
+}
+
+impl SmartModuleTransform for SmartModuleComponent {
+    #[instrument(skip(self,ctx,store),fields(offset = input.base_offset()))]
+    fn process(
+        &mut self,
+        input: SmartModuleInput,
+        ctx: &mut SmartModuleInstanceContext,
+        store: &mut WasmState,
+    ) -> Result<SmartModuleOutput> {
+        let base_offset = input.base_offset();
+        let base_timestamp = input.base_timestamp();
+        let input_records = input.try_into_smartmodule_records(ctx.version())?;
+        let records: Vec<SmartmoduleRecord> = input_records
+            .iter()
+            .map(|record| SmartmoduleRecord {
+                key: record.key().map(|key| key.as_ref().to_vec()),
+                value: record.value().as_ref().to_vec(),
+                offset: record.offset(),
+                timestamp: record.timestamp(),
+                headers: record
+                    .headers()
+                    .iter()
+                    .map(|header| ComponentHeader {
+                        key: header.key.clone(),
+                        value: header.value.as_ref().to_vec(),
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        let output = self
+            .bindings
+            .fluvio_smartmodule_transform()
+            .call_process(&mut *store, &records)?;
+        debug!(records_out = output.records.len(), "component output");
+
+        let successes = output
+            .records
+            .into_iter()
+            .map(|record| -> Result<Record> {
+                let mut out = Record {
+                    key: record.key.map(RecordData::from),
+                    value: RecordData::from(record.value),
+                    headers: record
+                        .headers
+                        .into_iter()
+                        .map(|header| Header::new(header.key, header.value))
+                        .collect(),
+                    ..Default::default()
+                };
+                out.preamble
+                    .set_offset_delta(delta("offset", record.offset, base_offset)?);
+                out.preamble.set_timestamp_delta(delta(
+                    "timestamp",
+                    record.timestamp,
+                    base_timestamp,
+                )?);
+                Ok(out)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let error = output.error.map(|error| {
+            let record = input_records
+                .into_iter()
+                .find(|record| record.offset() == error.offset)
+                .map(|record| record.into_inner())
+                .unwrap_or_default();
+            SmartModuleTransformRuntimeError {
+                hint: error.hint,
+                offset: error.offset,
+                kind: SmartModuleKind::Generic,
+                record_key: record.key,
+                record_value: record.value,
+            }
+        });
+
+        Ok(SmartModuleOutput::with_error(successes, error))
+    }
+
+    fn name(&self) -> &str {
+        COMPONENT_FN_NAME
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use fluvio_protocol::record::{Header, Record};
+    use fluvio_smartmodule::SMARTMODULE_HEADERS_VERSION;
+    use fluvio_smartmodule::dataplane::smartmodule::SmartModuleInput;
+
+    use crate::engine::{
+        Lookback, SmartEngine, SmartModuleChainBuilder, SmartModuleConfig, SmartModuleInitialData,
+        metrics::SmartModuleChainMetrics,
+    };
+    use crate::engine::fixture::read_wasm_module;
+
+    use super::{check_config, delta, is_component, COMPONENT_FN_NAME};
+
+    const SM_COMPONENT_MAP: &str = "fluvio_smartmodule_component_map_component";
+
+    #[test]
+    fn test_is_component() {
+        // header of core module
+        assert!(!is_component(b"\0asm\x01\x00\x00\x00"));
+        // header of component
+        assert!(is_component(b"\0asm\x0d\x00\x01\x00"));
+        assert!(!is_component(b"\0asm"));
+        assert!(!is_component(b"\x7fELF\x0d\x00\x01\x00"));
+    }
+
+    #[test]
+    fn test_check_component_config() {
+        assert!(check_config(
+            &SmartModuleConfig::builder()
+                .param("key", "value")
+                .build()
+                .unwrap()
+        )
+        .is_ok());
+        assert!(check_config(
+            &SmartModuleConfig::builder()
+                .initial_data(SmartModuleInitialData::with_aggregate(vec![]))
+                .build()
+                .unwrap()
+        )
+        .is_err());
+        assert!(check_config(
+            &SmartModuleConfig::builder()
+                .lookback(Some(Lookback::Last(1)))
+                .build()
+                .unwrap()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_component_output_delta() {
+        assert_eq!(delta("offset", 15, 10).expect("delta"), 5);
+        assert_eq!(delta("timestamp", 0, 10).expect("delta"), -10);
+        assert!(delta("timestamp", i64::MIN, 1).is_err());
+        assert!(delta("offset", i64::MAX, -1).is_err());
+    }
+
+    #[ignore]
+    #[test]
+    fn test_component_map_keeps_headers() {
+        let engine = SmartEngine::new();
+        let mut chain_builder = SmartModuleChainBuilder::default();
+
+        chain_builder.add_smart_module(
+            SmartModuleConfig::builder()
+                .version(SMARTMODULE_HEADERS_VERSION)
+                .build()
+                .unwrap(),
+            read_wasm_module(SM_COMPONENT_MAP),
+        );
+
+        let mut chain = chain_builder
+            .initialize(&engine)
+            .expect("failed to build chain");
+
+        assert_eq!(
+            chain.instances().first().expect("first").transform().name(),
+            COMPONENT_FN_NAME
+        );
+
+        let metrics = SmartModuleChainMetrics::default();
+        let mut apple = Record::new_key_value("fruit", "apple");
+        apple.add_header(Header::new("color", "red"));
+        let input = vec![apple, Record::new("banana")];
+        let output = chain
+            .process(
+                SmartModuleInput::try_from_records(input, SMARTMODULE_HEADERS_VERSION)
+                    .expect("input"),
+                &metrics,
+            )
+            .expect("process");
+
+        assert!(output.error.is_none());
+        assert_eq!(output.successes.len(), 2);
+        assert_eq!(output.successes[0].value.as_ref(), b"APPLE");
+        assert_eq!(
+            output.successes[0].key.as_ref().map(|key| key.as_ref()),
+            Some(b"fruit".as_ref())
+        );
+        assert_eq!(
+            output.successes[0]
+                .header("color")
+                .map(|value| value.as_ref()),
+            Some(b"red".as_ref())
+        );
+        assert_eq!(output.successes[1].value.as_ref(), b"BANANA");
+        assert!(output.successes[1].headers().is_empty());
+    }
+}
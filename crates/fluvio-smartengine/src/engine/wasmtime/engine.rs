@@ -1,41 +1,86 @@
 use std::fmt::{self, Debug};
 use std::future::Future;
+use std::io::{Cursor, Error as IoError, ErrorKind};
+use std::path::PathBuf;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
 
 use anyhow::Result;
-use fluvio_smartmodule::Record;
+use fluvio_smartmodule::{Record, SMARTMODULE_HEADERS_VERSION};
 use tracing::debug;
-use wasmtime::{Engine, Module};
+use wasmtime::Engine;
 
-use fluvio_smartmodule::dataplane::smartmodule::{SmartModuleInput, SmartModuleOutput};
+use fluvio_smartmodule::dataplane::smartmodule::{
+    SmartModuleChainTrace, SmartModuleDebugConfig, SmartModuleErrorPolicy, SmartModuleInput,
+    SmartModuleLog, SmartModuleOutput, SmartModuleStepTrace, SmartModuleTransformRuntimeError,
+};
+use fluvio_protocol::{Decoder, DecoderVarInt, Encoder, Version};
+use fluvio_protocol::record::{Offset, RecordHeader};
 
 use crate::SmartModuleConfig;
 use crate::engine::config::{Lookback, DEFAULT_SMARTENGINE_VERSION};
 
+use super::cache::{CompiledSmartModule, SmartModuleCache};
+use super::component::SmartModuleComponent;
+use super::epoch::EpochTicker;
 use super::init::SmartModuleInit;
 use super::instance::{SmartModuleInstance, SmartModuleInstanceContext};
 
-use super::limiter::StoreResourceLimiter;
+use super::limiter::{CallBudget, StoreResourceLimiter};
 use super::look_back::SmartModuleLookBack;
 use super::metrics::SmartModuleChainMetrics;
 use super::state::WasmState;
-use super::transforms::create_transform;
+use super::transforms::{create_transform, SmartModuleAggregate};
 
 // 1 GB
 const DEFAULT_STORE_MEMORY_LIMIT: usize = 1_000_000_000;
 
+/// max number of record traces collected between calls to `take_trace`
+const MAX_TRACE_RECORDS: usize = 1_000;
+// 1 MB
+const DEFAULT_TRACE_MAX_BYTES: usize = 1_000_000;
+
 #[derive(Clone)]
-pub struct SmartEngine(Engine);
+pub struct SmartEngine {
+    engine: Engine,
+    // started by first chain with call timeout
+    epoch_ticker: Arc<OnceLock<EpochTicker>>,
+    cache: SmartModuleCache,
+}
 
 #[allow(clippy::new_without_default)]
 impl SmartEngine {
     pub fn new() -> Self {
         let mut config = wasmtime::Config::default();
         config.consume_fuel(true);
-        Self(Engine::new(&config).expect("Config is static"))
+        config.epoch_interruption(true);
+        config.wasm_component_model(true);
+        Self {
+            engine: Engine::new(&config).expect("Config is static"),
+            epoch_ticker: Default::default(),
+            cache: Default::default(),
+        }
+    }
+
+    /// Keep compiled SmartModules in directory, so they are not compiled again after restart.
+    /// Without directory, compiled SmartModules are kept only in memory.
+    pub fn with_module_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache = SmartModuleCache::new(Some(dir.into()));
+        self
+    }
+
+    /// Remove compiled SmartModule from cache, it is compiled again by the next chain using it
+    pub fn invalidate_module(&self, name: &str) {
+        self.cache.invalidate(name);
     }
 
     pub(crate) fn new_state(&self, store_limiter: StoreResourceLimiter) -> WasmState {
-        WasmState::new(&self.0, store_limiter)
+        WasmState::new(&self.engine, store_limiter)
+    }
+
+    fn ensure_epoch_ticker(&self) {
+        self.epoch_ticker
+            .get_or_init(|| EpochTicker::start(self.engine.clone()));
     }
 }
 
@@ -49,6 +94,8 @@ impl Debug for SmartEngine {
 pub struct SmartModuleChainBuilder {
     smart_modules: Vec<(SmartModuleConfig, Vec<u8>)>,
     store_limiter: StoreResourceLimiter,
+    fuel_limit: Option<u64>,
+    call_timeout: Option<Duration>,
 }
 
 impl SmartModuleChainBuilder {
@@ -61,32 +108,91 @@ impl SmartModuleChainBuilder {
         self.store_limiter.set_memory_size(max_memory_bytes);
     }
 
+    /// Max fuel each SmartModule call can consume, unless overridden by SmartModule config
+    pub fn set_fuel_limit(&mut self, fuel: u64) {
+        self.fuel_limit = Some(fuel);
+    }
+
+    /// Max time each SmartModule call can run, unless overridden by SmartModule config
+    pub fn set_call_timeout(&mut self, timeout: Duration) {
+        self.call_timeout = Some(timeout);
+    }
+
     /// stop adding smartmodule and return SmartModuleChain that can be executed
     pub fn initialize(self, engine: &SmartEngine) -> Result<SmartModuleChainInstance> {
         let mut instances = Vec::with_capacity(self.smart_modules.len());
         let mut state = engine.new_state(self.store_limiter);
         for (config, bytes) in self.smart_modules {
-            let module = Module::new(&engine.0, bytes)?;
             let version = config.version();
+            let budget = CallBudget::new(
+                config.fuel_limit.or(self.fuel_limit),
+                config.call_timeout.or(self.call_timeout),
+            );
+            if budget.timeout.is_some() {
+                engine.ensure_epoch_ticker();
+            }
+            // instantiation and init share budget of the call
+            state.start_call(budget);
+            let module =
+                match engine
+                    .cache
+                    .get_or_compile(&engine.engine, config.name.as_deref(), &bytes)?
+                {
+                    CompiledSmartModule::Module(module) => module,
+                    CompiledSmartModule::Component(component) => {
+                        let component = SmartModuleComponent::instantiate(
+                            &mut state,
+                            &engine.engine,
+                            &component,
+                            &config,
+                        )
+                        .map_err(|err| state.budget_error(err))?;
+                        let ctx = SmartModuleInstanceContext::component(config.params, version);
+                        instances.push(SmartModuleInstance::new(
+                            ctx,
+                            None,
+                            None,
+                            Box::new(component),
+                            version,
+                            budget,
+                            config.error_policy,
+                        ));
+                        continue;
+                    }
+                };
             let ctx = SmartModuleInstanceContext::instantiate(
                 &mut state,
                 module,
                 config.params,
                 version,
                 config.lookback,
+                config.key_value_state,
             )?;
             let init = SmartModuleInit::try_instantiate(&ctx, &mut state)?;
             let look_back = SmartModuleLookBack::try_instantiate(&ctx, &mut state)?;
             let transform = create_transform(&ctx, config.initial_data, &mut state)?;
-            let mut instance = SmartModuleInstance::new(ctx, init, look_back, transform, version);
+            let mut instance = SmartModuleInstance::new(
+                ctx,
+                init,
+                look_back,
+                transform,
+                version,
+                budget,
+                config.error_policy,
+            );
 
-            instance.call_init(&mut state)?;
+            instance
+                .call_init(&mut state)
+                .map_err(|err| state.budget_error(err))?;
             instances.push(instance);
         }
 
         Ok(SmartModuleChainInstance {
             store: state,
             instances,
+            dead_letters: Vec::new(),
+            debug: None,
+            trace: BoundedTrace::default(),
         })
     }
 }
@@ -98,6 +204,8 @@ impl Default for SmartModuleChainBuilder {
         Self {
             smart_modules: Default::default(),
             store_limiter,
+            fuel_limit: None,
+            call_timeout: None,
         }
     }
 }
@@ -110,10 +218,77 @@ impl<T: Into<Vec<u8>>> From<(SmartModuleConfig, T)> for SmartModuleChainBuilder
     }
 }
 
+/// Record SmartModule failed to process, to be written to dead-letter topic
+#[derive(Debug)]
+pub struct SmartModuleDeadLetter {
+    pub topic: String,
+    pub error: SmartModuleTransformRuntimeError,
+}
+
 /// SmartModule Chain Instance that can be executed
 pub struct SmartModuleChainInstance {
     store: WasmState,
     instances: Vec<SmartModuleInstance>,
+    dead_letters: Vec<SmartModuleDeadLetter>,
+    debug: Option<SmartModuleDebugConfig>,
+    trace: BoundedTrace,
+}
+
+/// Traces collected in debug mode, limited in number of records and encoded size.
+/// Once the limit is reached, nothing more is traced until the trace is taken.
+struct BoundedTrace {
+    trace: SmartModuleChainTrace,
+    size: usize,
+    max_size: usize,
+    full: bool,
+}
+
+impl Default for BoundedTrace {
+    fn default() -> Self {
+        Self {
+            trace: SmartModuleChainTrace::default(),
+            size: 0,
+            max_size: DEFAULT_TRACE_MAX_BYTES,
+            full: false,
+        }
+    }
+}
+
+impl BoundedTrace {
+    fn is_full(&self) -> bool {
+        self.full
+    }
+
+    /// add entry if it fits, otherwise trace is full
+    fn reserve(&mut self, size: usize) -> bool {
+        if self.full || self.size + size > self.max_size {
+            self.full = true;
+            return false;
+        }
+        self.size += size;
+        true
+    }
+
+    fn push_record(&mut self, record: SmartModuleStepTrace) {
+        if self.trace.records.len() >= MAX_TRACE_RECORDS {
+            self.full = true;
+        }
+        if self.reserve(record.write_size(0)) {
+            self.trace.records.push(record);
+        }
+    }
+
+    fn push_log(&mut self, log: SmartModuleLog) {
+        if self.reserve(log.write_size(0)) {
+            self.trace.logs.push(log);
+        }
+    }
+
+    fn take(&mut self) -> SmartModuleChainTrace {
+        self.size = 0;
+        self.full = false;
+        std::mem::take(&mut self.trace)
+    }
 }
 
 impl Debug for SmartModuleChainInstance {
@@ -128,6 +303,48 @@ impl SmartModuleChainInstance {
         &self.instances
     }
 
+    /// current accumulators of aggregate SmartModules in the chain order
+    pub fn accumulators(&self) -> Vec<Vec<u8>> {
+        self.instances
+            .iter()
+            .filter_map(|instance| {
+                instance
+                    .transform()
+                    .as_any()
+                    .downcast_ref::<SmartModuleAggregate>()
+            })
+            .map(|aggregate| aggregate.accumulator().to_vec())
+            .collect()
+    }
+
+    /// records failed by SmartModules with dead-letter policy since last call
+    pub fn take_dead_letters(&mut self) -> Vec<SmartModuleDeadLetter> {
+        std::mem::take(&mut self.dead_letters)
+    }
+
+    /// Enable debug mode, in which chain traces input and output of each SmartModule
+    /// for sampled records and keeps messages logged by SmartModules.
+    pub fn set_debug(&mut self, debug: Option<SmartModuleDebugConfig>) {
+        self.debug = debug;
+        self.store.collect_logs(debug.is_some());
+    }
+
+    /// Limit encoded size of traces collected between calls to `take_trace`,
+    /// records processed after the limit is reached are not traced.
+    pub fn set_trace_max_bytes(&mut self, max_bytes: usize) {
+        self.trace.max_size = max_bytes;
+    }
+
+    /// encoded size of traces collected since last call to `take_trace`
+    pub fn trace_size(&self) -> usize {
+        self.trace.size
+    }
+
+    /// traces collected in debug mode since last call
+    pub fn take_trace(&mut self) -> SmartModuleChainTrace {
+        self.trace.take()
+    }
+
     /// A single record is processed thru all smartmodules in the chain.
     /// The output of one smartmodule is the input of the next smartmodule.
     /// A single record may result in multiple records.
@@ -147,14 +364,26 @@ impl SmartModuleChainInstance {
         if let Some((last, instances)) = self.instances.split_last_mut() {
             let mut next_input = input;
 
-            for instance in instances {
+            for (step, instance) in instances.iter_mut().enumerate() {
+                let sampled = sample_input(self.sampling(), &next_input, instance.version())?;
                 // pass raw inputs to transform instance
                 // each raw input may result in multiple records
-                self.store.top_up_fuel();
-                let output = instance.process(next_input, &mut self.store)?;
-                let fuel_used = self.store.get_used_fuel();
-                debug!(fuel_used, "fuel used");
-                metric.add_fuel_used(fuel_used);
+                let output = process_instance(
+                    instance,
+                    &mut self.store,
+                    next_input,
+                    metric,
+                    &mut self.dead_letters,
+                )?;
+                trace_step(
+                    &mut self.trace,
+                    &mut self.store,
+                    step,
+                    instance,
+                    sampled,
+                    &output,
+                    base_offset,
+                );
 
                 if output.error.is_some() {
                     // encountered error, we stop processing and return partial output
@@ -167,11 +396,23 @@ impl SmartModuleChainInstance {
                 }
             }
 
-            self.store.top_up_fuel();
-            let output = last.process(next_input, &mut self.store)?;
-            let fuel_used = self.store.get_used_fuel();
-            debug!(fuel_used, "fuel used");
-            metric.add_fuel_used(fuel_used);
+            let sampled = sample_input(self.sampling(), &next_input, last.version())?;
+            let output = process_instance(
+                last,
+                &mut self.store,
+                next_input,
+                metric,
+                &mut self.dead_letters,
+            )?;
+            trace_step(
+                &mut self.trace,
+                &mut self.store,
+                instances.len(),
+                last,
+                sampled,
+                &output,
+                base_offset,
+            );
             let records_out = output.successes.len();
             metric.add_records_out(records_out as u64);
             debug!(records_out, "sm records out");
@@ -184,6 +425,11 @@ impl SmartModuleChainInstance {
         }
     }
 
+    /// debug config if records are still traced
+    fn sampling(&self) -> Option<SmartModuleDebugConfig> {
+        self.debug.filter(|_| !self.trace.is_full())
+    }
+
     pub async fn look_back<F, R>(
         &mut self,
         read_fn: F,
@@ -203,9 +449,11 @@ impl SmartModuleChainInstance {
                     SmartModuleInput::try_from_records(records, instance.version())?;
 
                 metrics.add_bytes_in(input.raw_bytes().len() as u64);
-                self.store.top_up_fuel();
+                self.store.start_call(instance.budget());
 
-                let result = instance.call_look_back(input, &mut self.store);
+                let result = instance
+                    .call_look_back(input, &mut self.store)
+                    .map_err(|err| self.store.budget_error(err));
                 let fuel_used = self.store.get_used_fuel();
 
                 debug!(fuel_used, "fuel used");
@@ -218,6 +466,164 @@ impl SmartModuleChainInstance {
     }
 }
 
+/// Records of the input traced in debug mode, with their offsets.
+/// Only sampled records are decoded, others are skipped after reading their offset.
+fn sample_input(
+    debug: Option<SmartModuleDebugConfig>,
+    input: &SmartModuleInput,
+    version: Version,
+) -> Result<Option<Vec<(Offset, Record)>>> {
+    let Some(debug) = debug else {
+        return Ok(None);
+    };
+    let base_offset = input.base_offset();
+    let mut src = Cursor::new(input.raw_bytes());
+    let mut count: i32 = 0;
+    count.decode(&mut src, version)?;
+    let mut sampled = vec![];
+    for _ in 0..count {
+        let start = src.position();
+        let mut len: i64 = 0;
+        len.decode_varint(&mut src)?;
+        let end = u64::try_from(len)
+            .ok()
+            .and_then(|len| src.position().checked_add(len))
+            .filter(|end| *end <= input.raw_bytes().len() as u64)
+            .ok_or_else(|| IoError::new(ErrorKind::UnexpectedEof, "not enough for record"))?;
+        let mut preamble = RecordHeader::default();
+        preamble.decode(&mut src, version)?;
+        let offset = base_offset + preamble.offset_delta();
+        if debug.is_sampled(offset) {
+            src.set_position(start);
+            let mut record = Record::default();
+            record.decode(&mut src, version)?;
+            sampled.push((offset, record));
+        }
+        src.set_position(end);
+    }
+    Ok(Some(sampled))
+}
+
+/// Add sampled records and logs of SmartModule at `step` to the trace
+fn trace_step(
+    trace: &mut BoundedTrace,
+    store: &mut WasmState,
+    step: usize,
+    instance: &SmartModuleInstance,
+    sampled: Option<Vec<(Offset, Record)>>,
+    output: &SmartModuleOutput,
+    base_offset: Offset,
+) {
+    let Some(sampled) = sampled else {
+        return;
+    };
+    let step = step as u32;
+    let smartmodule = instance.transform().name();
+    for (offset, input) in sampled {
+        let output_records = output
+            .successes
+            .iter()
+            .filter(|record| base_offset + record.offset_delta() == offset)
+            .cloned()
+            .collect();
+        let error = output
+            .error
+            .as_ref()
+            .filter(|error| error.offset == offset)
+            .map(|error| error.hint.clone());
+        trace.push_record(SmartModuleStepTrace {
+            step,
+            smartmodule: smartmodule.to_owned(),
+            offset,
+            input,
+            output: output_records,
+            error,
+        });
+    }
+    for message in store.take_logs() {
+        trace.push_log(SmartModuleLog { step, message });
+    }
+}
+
+/// Process input by instance, applying its error policy.
+/// Unless policy is to fail, record failed by SmartModule is dropped
+/// and the instance is called again with records after it.
+fn process_instance(
+    instance: &mut SmartModuleInstance,
+    store: &mut WasmState,
+    input: SmartModuleInput,
+    metric: &SmartModuleChainMetrics,
+    dead_letters: &mut Vec<SmartModuleDeadLetter>,
+) -> Result<SmartModuleOutput> {
+    let base_offset = input.base_offset();
+    let base_timestamp = input.base_timestamp();
+    let mut successes = vec![];
+    let mut next_input = input;
+    // records passed to and from SmartModule carry headers only if its version supports them
+    let headers_supported = instance.version() >= SMARTMODULE_HEADERS_VERSION;
+    next_input.clear_record_headers(instance.version())?;
+    loop {
+        // kept to resume after failed record
+        let pending = match instance.error_policy() {
+            SmartModuleErrorPolicy::Fail => None,
+            _ => Some(next_input.clone()),
+        };
+
+        store.start_call(instance.budget());
+        let mut output = instance
+            .process(next_input, store)
+            .map_err(|err| store.budget_error(err))?;
+        let fuel_used = store.get_used_fuel();
+        debug!(fuel_used, "fuel used");
+        metric.add_fuel_used(fuel_used);
+        if !headers_supported {
+            output
+                .successes
+                .iter_mut()
+                .for_each(|record| record.headers.clear());
+        }
+
+        successes.extend(output.successes);
+        let Some(error) = output.error else {
+            return Ok(SmartModuleOutput::new(successes));
+        };
+        let Some(pending) = pending else {
+            return Ok(SmartModuleOutput::with_error(successes, Some(error)));
+        };
+
+        let pending = pending.try_into_smartmodule_records(instance.version())?;
+        let pending_count = pending.len();
+        let remaining: Vec<Record> = pending
+            .into_iter()
+            .filter(|record| record.offset() > error.offset)
+            .map(|record| record.into_inner())
+            .collect();
+        if remaining.len() == pending_count {
+            // failed record is not part of the input, there is nothing to drop
+            return Ok(SmartModuleOutput::with_error(successes, Some(error)));
+        }
+
+        debug!(
+            offset = error.offset,
+            policy = %instance.error_policy(),
+            "dropping record failed by SmartModule"
+        );
+        if let SmartModuleErrorPolicy::DeadLetter(topic) = instance.error_policy() {
+            dead_letters.push(SmartModuleDeadLetter {
+                topic: topic.clone(),
+                error,
+            });
+        }
+        if remaining.is_empty() {
+            return Ok(SmartModuleOutput::new(successes));
+        }
+
+        next_input = SmartModuleInput::try_from_records(remaining, instance.version())?;
+        next_input.set_base_offset(base_offset);
+        next_input.set_base_timestamp(base_timestamp);
+    }
+}
+
 #[cfg(test)]
 mod test {
 
@@ -237,9 +643,12 @@ mod test {
 #[cfg(test)]
 mod chaining_test {
 
+    use fluvio_protocol::Encoder;
     use fluvio_protocol::record::Record;
     use fluvio_protocol::link::smartmodule::SmartModuleLookbackRuntimeError;
-    use fluvio_smartmodule::{dataplane::smartmodule::SmartModuleInput};
+    use fluvio_smartmodule::dataplane::smartmodule::{
+        SmartModuleDebugConfig, SmartModuleErrorPolicy, SmartModuleInput,
+    };
 
     use crate::engine::error::EngineError;
     use crate::engine::config::{Lookback, DEFAULT_SMARTENGINE_VERSION};
@@ -582,6 +991,77 @@ mod chaining_test {
         ))
     }
 
+    const SM_FILTER: &str = "fluvio_smartmodule_filter";
+
+    #[ignore]
+    #[test]
+    fn test_process_fuel_exhausted() {
+        //given
+        let engine = SmartEngine::new();
+        let mut chain_builder = SmartModuleChainBuilder::default();
+        let metrics = SmartModuleChainMetrics::default();
+        let fuel_limit = 10;
+
+        chain_builder.add_smart_module(
+            SmartModuleConfig::builder().build().unwrap(),
+            read_wasm_module(SM_FILTER),
+        );
+        chain_builder.set_fuel_limit(fuel_limit);
+
+        let mut chain = chain_builder
+            .initialize(&engine)
+            .expect("failed to build chain");
+
+        // when
+        let input: Vec<Record> = (0..1000).map(|_| Record::new("apple")).collect();
+        let res = chain.process(
+            SmartModuleInput::try_from_records(input, DEFAULT_SMARTENGINE_VERSION).expect("input"),
+            &metrics,
+        );
+
+        // then
+        let err = res
+            .unwrap_err()
+            .downcast::<EngineError>()
+            .expect("EngineError expected");
+        assert!(matches!(err, EngineError::FuelExhausted { limit } if limit == fuel_limit));
+    }
+
+    #[ignore]
+    #[test]
+    fn test_fuel_limit_overridden_by_config() {
+        //given
+        let engine = SmartEngine::new();
+        let mut chain_builder = SmartModuleChainBuilder::default();
+        let metrics = SmartModuleChainMetrics::default();
+
+        chain_builder.add_smart_module(
+            SmartModuleConfig::builder()
+                .fuel_limit(1_000_000)
+                .build()
+                .unwrap(),
+            read_wasm_module(SM_FILTER),
+        );
+        chain_builder.set_fuel_limit(10);
+
+        let mut chain = chain_builder
+            .initialize(&engine)
+            .expect("failed to build chain");
+
+        // when
+        let input = vec![Record::new("apple"), Record::new("fruit")];
+        let output = chain
+            .process(
+                SmartModuleInput::try_from_records(input, DEFAULT_SMARTENGINE_VERSION)
+                    .expect("input"),
+                &metrics,
+            )
+            .expect("process");
+
+        // then
+        assert_eq!(output.successes.len(), 1);
+    }
+
     #[ignore]
     #[test]
     fn test_process_unsufficient_memory() {
@@ -624,4 +1104,149 @@ mod chaining_test {
             if max == max_memory
         ))
     }
+
+    const SM_FILTER_ODD: &str = "fluvio_wasm_filter_odd";
+
+    #[ignore]
+    #[test]
+    fn test_chain_error_policy() {
+        //given
+        let engine = SmartEngine::new();
+        let metrics = SmartModuleChainMetrics::default();
+        let input = || {
+            let records = vec![
+                Record::new("2"),
+                Record::new("two"),
+                Record::new("4"),
+                Record::new("four"),
+                Record::new("6"),
+            ];
+            SmartModuleInput::try_from_records(records, DEFAULT_SMARTENGINE_VERSION).expect("input")
+        };
+        let chain = |error_policy| {
+            let mut chain_builder = SmartModuleChainBuilder::default();
+            chain_builder.add_smart_module(
+                SmartModuleConfig::builder()
+                    .error_policy(error_policy)
+                    .build()
+                    .unwrap(),
+                read_wasm_module(SM_FILTER_ODD),
+            );
+            chain_builder
+                .initialize(&engine)
+                .expect("failed to build chain")
+        };
+
+        //when
+        let output = chain(SmartModuleErrorPolicy::Fail)
+            .process(input(), &metrics)
+            .expect("process");
+
+        //then
+        assert_eq!(output.successes.len(), 1);
+        assert_eq!(output.error.expect("error").offset, 1);
+
+        //when
+        let mut skip_chain = chain(SmartModuleErrorPolicy::Skip);
+        let output = skip_chain.process(input(), &metrics).expect("process");
+
+        //then
+        assert_eq!(output.successes.len(), 3);
+        assert!(output.error.is_none());
+        assert!(skip_chain.take_dead_letters().is_empty());
+
+        //when
+        let mut dead_letter_chain = chain(SmartModuleErrorPolicy::DeadLetter("failed".to_string()));
+        let output = dead_letter_chain
+            .process(input(), &metrics)
+            .expect("process");
+
+        //then
+        assert_eq!(output.successes.len(), 3);
+        assert_eq!(output.successes[2].value.as_ref(), b"6");
+        let dead_letters = dead_letter_chain.take_dead_letters();
+        assert_eq!(dead_letters.len(), 2);
+        assert_eq!(dead_letters[0].topic, "failed");
+        assert_eq!(dead_letters[0].error.record_value.as_ref(), b"two");
+        assert_eq!(dead_letters[1].error.offset, 3);
+    }
+
+    #[ignore]
+    #[test]
+    fn test_chain_debug_trace() {
+        //given
+        let engine = SmartEngine::new();
+        let mut chain_builder = SmartModuleChainBuilder::default();
+        let metrics = SmartModuleChainMetrics::default();
+        chain_builder.add_smart_module(
+            SmartModuleConfig::builder()
+                .param("key", "a")
+                .build()
+                .unwrap(),
+            read_wasm_module(SM_FILTER_INIT),
+        );
+        chain_builder.add_smart_module(
+            SmartModuleConfig::builder().build().unwrap(),
+            read_wasm_module(SM_MAP),
+        );
+        let mut chain = chain_builder
+            .initialize(&engine)
+            .expect("failed to build chain");
+        let input = || {
+            let records = vec![
+                Record::new("apple"),
+                Record::new("fruit"),
+                Record::new("banana"),
+            ];
+            SmartModuleInput::try_from_records(records, DEFAULT_SMARTENGINE_VERSION).expect("input")
+        };
+
+        //when
+        chain.process(input(), &metrics).expect("process");
+
+        //then
+        assert!(chain.take_trace().is_empty());
+
+        //when
+        chain.set_debug(Some(SmartModuleDebugConfig::default()));
+        let output = chain.process(input(), &metrics).expect("process");
+
+        //then
+        assert_eq!(output.successes.len(), 2);
+        let trace = chain.take_trace();
+        assert_eq!(trace.records.len(), 5);
+        let filtered_out = &trace.records[1];
+        assert_eq!(filtered_out.step, 0);
+        assert_eq!(filtered_out.smartmodule, "filter");
+        assert_eq!(filtered_out.offset, 1);
+        assert_eq!(filtered_out.input.value.as_ref(), b"fruit");
+        assert!(filtered_out.output.is_empty());
+        let mapped = &trace.records[4];
+        assert_eq!(mapped.step, 1);
+        assert_eq!(mapped.smartmodule, "map");
+        assert_eq!(mapped.offset, 2);
+        assert_eq!(mapped.output[0].value.as_ref(), b"BANANA");
+        assert!(chain.take_trace().is_empty());
+
+        //when
+        chain.set_debug(Some(SmartModuleDebugConfig { sample_every: 2 }));
+        chain.process(input(), &metrics).expect("process");
+
+        //then
+        let trace = chain.take_trace();
+        assert_eq!(trace.records.len(), 4);
+        assert!(trace.records.iter().all(|record| record.offset != 1));
+
+        //when
+        chain.set_debug(Some(SmartModuleDebugConfig::default()));
+        let trace_size = trace.records[0].write_size(0);
+        chain.set_trace_max_bytes(trace_size);
+        chain.process(input(), &metrics).expect("process");
+
+        //then
+        assert_eq!(chain.trace_size(), trace_size);
+        let trace = chain.take_trace();
+        assert_eq!(trace.records.len(), 1);
+        assert_eq!(chain.trace_size(), 0);
+    }
 }
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use tracing::debug;
+use wasmtime::Engine;
+
+/// Interval between epoch increments, call timeouts are rounded up to it
+pub(crate) const EPOCH_TICK: Duration = Duration::from_millis(10);
+
+// deadline far enough to never be reached, wasmtime adds it to the current epoch
+pub(crate) const NO_DEADLINE: u64 = u64::MAX / 2;
+
+/// number of epoch ticks to wait before interrupting call
+pub(crate) fn timeout_ticks(timeout: Duration) -> u64 {
+    let tick = EPOCH_TICK.as_nanos();
+    let ticks = (timeout.as_nanos() + tick - 1) / tick;
+    (ticks as u64).max(1)
+}
+
+/// Background thread that increments engine epoch, it is stopped when dropped
+#[derive(Debug)]
+pub(crate) struct EpochTicker {
+    stop: Arc<AtomicBool>,
+}
+
+impl EpochTicker {
+    pub(crate) fn start(engine: Engine) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = stop.clone();
+        std::thread::Builder::new()
+            .name("smartengine-epoch".to_string())
+            .spawn(move || {
+                debug!("epoch ticker started");
+                while !stopped.load(Ordering::Relaxed) {
+                    std::thread::sleep(EPOCH_TICK);
+                    engine.increment_epoch();
+                }
+                debug!("epoch ticker stopped");
+            })
+            .expect("failed to spawn epoch ticker");
+        Self { stop }
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::timeout_ticks;
+
+    #[test]
+    fn test_timeout_ticks() {
+        assert_eq!(timeout_ticks(Duration::ZERO), 1);
+        assert_eq!(timeout_ticks(Duration::from_millis(1)), 1);
+        assert_eq!(timeout_ticks(Duration::from_millis(10)), 1);
+        assert_eq!(timeout_ticks(Duration::from_millis(11)), 2);
+        assert_eq!(timeout_ticks(Duration::from_secs(1)), 100);
+    }
+}
@@ -3,19 +3,22 @@ use std::sync::{Arc, Mutex};
 use std::fmt::{self, Debug};
 
 use tracing::debug;
-use anyhow::{Error, Result};
+use anyhow::{anyhow, Error, Result};
 use wasmtime::{Memory, Module, Caller, Extern, Instance, Func, AsContextMut, AsContext};
 
 use fluvio_protocol::{Encoder, Decoder, Version};
 
 use fluvio_smartmodule::dataplane::smartmodule::{
-    SmartModuleExtraParams, SmartModuleInput, SmartModuleOutput, SmartModuleInitInput,
+    SmartModuleErrorPolicy, SmartModuleExtraParams, SmartModuleInput, SmartModuleOutput,
+    SmartModuleInitInput,
 };
 
 use crate::engine::config::Lookback;
+use crate::engine::SmartModuleKeyValueState;
 
 use super::error::EngineError;
 use super::init::SmartModuleInit;
+use super::limiter::CallBudget;
 use super::look_back::SmartModuleLookBack;
 use super::{WasmSlice, memory};
 use super::state::WasmState;
@@ -26,10 +29,11 @@ pub(crate) struct SmartModuleInstance {
     look_back: Option<SmartModuleLookBack>,
     transform: Box<dyn DowncastableTransform>,
     version: Version,
+    budget: CallBudget,
+    error_policy: SmartModuleErrorPolicy,
 }
 
 impl SmartModuleInstance {
-    #[cfg(test)]
     #[allow(clippy::borrowed_box)]
     pub(crate) fn transform(&self) -> &Box<dyn DowncastableTransform> {
         &self.transform
@@ -46,6 +50,8 @@ impl SmartModuleInstance {
         look_back: Option<SmartModuleLookBack>,
         transform: Box<dyn DowncastableTransform>,
         version: Version,
+        budget: CallBudget,
+        error_policy: SmartModuleErrorPolicy,
     ) -> Self {
         Self {
             ctx,
@@ -53,6 +59,8 @@ impl SmartModuleInstance {
             look_back,
             transform,
             version,
+            budget,
+            error_policy,
         }
     }
 
@@ -98,10 +106,21 @@ impl SmartModuleInstance {
     pub fn version(&self) -> Version {
         self.version
     }
+
+    /// Fuel and time allowed for each call
+    pub(crate) fn budget(&self) -> CallBudget {
+        self.budget
+    }
+
+    /// What chain does with records this SmartModule failed to process
+    pub(crate) fn error_policy(&self) -> &SmartModuleErrorPolicy {
+        &self.error_policy
+    }
 }
 
 pub(crate) struct SmartModuleInstanceContext {
-    instance: Instance,
+    // core module instance, components are called thru their bindings
+    instance: Option<Instance>,
     records_cb: Arc<RecordsCallBack>,
     params: SmartModuleExtraParams,
     version: Version,
@@ -116,13 +135,14 @@ impl Debug for SmartModuleInstanceContext {
 
 impl SmartModuleInstanceContext {
     /// instantiate new module instance that contain context
-    #[tracing::instrument(skip(state, module, params))]
+    #[tracing::instrument(skip(state, module, params, key_value_state))]
     pub(crate) fn instantiate(
         state: &mut WasmState,
         module: Module,
         params: SmartModuleExtraParams,
         version: Version,
         lookback: Option<Lookback>,
+        key_value_state: SmartModuleKeyValueState,
     ) -> Result<Self, EngineError> {
         debug!("creating WasmModuleInstance");
         let cb = Arc::new(RecordsCallBack::new());
@@ -142,13 +162,13 @@ impl SmartModuleInstanceContext {
 
         debug!("instantiating WASMtime");
         let instance = state
-            .instantiate(&module, copy_records_fn)
+            .instantiate(&module, copy_records_fn, key_value_state)
             .map_err(|e| match e.downcast::<EngineError>() {
                 Ok(e) => e,
                 Err(e) => EngineError::Instantiate(e),
             })?;
         Ok(Self {
-            instance,
+            instance: Some(instance),
             records_cb,
             params,
             version,
@@ -156,9 +176,24 @@ impl SmartModuleInstanceContext {
         })
     }
 
+    /// context of component, which exports no core functions and doesn't look back
+    pub(crate) fn component(params: SmartModuleExtraParams, version: Version) -> Self {
+        Self {
+            instance: None,
+            records_cb: Arc::new(RecordsCallBack::new()),
+            params,
+            version,
+            lookback: None,
+        }
+    }
+
+    pub(crate) fn version(&self) -> Version {
+        self.version
+    }
+
     /// get wasm function from instance
     pub(crate) fn get_wasm_func(&self, store: &mut impl AsContextMut, name: &str) -> Option<Func> {
-        self.instance.get_func(store, name)
+        self.instance.as_ref()?.get_func(store, name)
     }
 
     pub(crate) fn write_input<E: Encoder>(
@@ -166,6 +201,10 @@ impl SmartModuleInstanceContext {
         input: &E,
         store: &mut impl AsContextMut,
     ) -> Result<WasmSlice> {
+        let instance = self
+            .instance
+            .as_ref()
+            .ok_or_else(|| anyhow!("component has no core module memory"))?;
         self.records_cb.clear();
         let mut input_data = Vec::new();
         input.encode(&mut input_data, self.version)?;
@@ -174,7 +213,7 @@ impl SmartModuleInstanceContext {
             version = self.version,
             "input encoded"
         );
-        let array_ptr = memory::copy_memory_to_instance(store, &self.instance, &input_data)?;
+        let array_ptr = memory::copy_memory_to_instance(store, instance, &input_data)?;
         let length = input_data.len();
         Ok((array_ptr as i32, length as i32, self.version as u32))
     }
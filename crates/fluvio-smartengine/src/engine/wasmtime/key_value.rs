@@ -0,0 +1,189 @@
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use tracing::trace;
+use wasmtime::{Caller, Extern, Linker, Memory};
+
+use fluvio_protocol::Encoder;
+use fluvio_smartmodule::state::{
+    StateEntry, STATE_ENCODING_VERSION, STATE_KEY_NOT_FOUND, STATE_SIZE_LIMIT_EXCEEDED,
+};
+
+use crate::engine::SmartModuleKeyValueState;
+
+use super::state::Context;
+
+// module of imports declared by `extern "C"` blocks
+const STATE_IMPORT_MODULE: &str = "env";
+
+fn memory(caller: &mut Caller<'_, Context>) -> Result<Memory> {
+    match caller.get_export("memory") {
+        Some(Extern::Memory(mem)) => Ok(mem),
+        _ => Err(anyhow!("failed to find host memory")),
+    }
+}
+
+pub(super) fn read_bytes(caller: &mut Caller<'_, Context>, ptr: i32, len: i32) -> Result<Vec<u8>> {
+    let memory = memory(caller)?;
+    let mut bytes = vec![0u8; len as u32 as usize];
+    memory.read(&*caller, ptr as u32 as usize, &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Define host functions of key-value state imported by SmartModule.
+/// Values are returned in two steps: `state_get` and `state_scan` return length of the result,
+/// then SmartModule allocates buffer and `state_read` copies the result into it.
+pub(crate) fn add_to_linker(
+    linker: &mut Linker<Context>,
+    state: SmartModuleKeyValueState,
+) -> Result<()> {
+    let result: Arc<Mutex<Vec<u8>>> = Default::default();
+
+    let (get_state, get_result) = (state.clone(), result.clone());
+    linker.func_wrap(
+        STATE_IMPORT_MODULE,
+        "state_get",
+        move |mut caller: Caller<'_, Context>, key_ptr: i32, key_len: i32| {
+            let key = read_bytes(&mut caller, key_ptr, key_len)?;
+            match get_state.get(&key) {
+                Some(value) => {
+                    let len = value.len() as i32;
+                    *get_result.lock().unwrap() = value;
+                    Ok(len)
+                }
+                None => Ok(STATE_KEY_NOT_FOUND),
+            }
+        },
+    )?;
+
+    let put_state = state.clone();
+    linker.func_wrap(
+        STATE_IMPORT_MODULE,
+        "state_put",
+        move |mut caller: Caller<'_, Context>,
+              key_ptr: i32,
+              key_len: i32,
+              value_ptr: i32,
+              value_len: i32| {
+            let key = read_bytes(&mut caller, key_ptr, key_len)?;
+            let value = read_bytes(&mut caller, value_ptr, value_len)?;
+            if put_state.put(&key, &value) {
+                Ok(0)
+            } else {
+                trace!(key_len, value_len, "state size limit exceeded");
+                Ok(STATE_SIZE_LIMIT_EXCEEDED)
+            }
+        },
+    )?;
+
+    let delete_state = state.clone();
+    linker.func_wrap(
+        STATE_IMPORT_MODULE,
+        "state_delete",
+        move |mut caller: Caller<'_, Context>, key_ptr: i32, key_len: i32| {
+            let key = read_bytes(&mut caller, key_ptr, key_len)?;
+            delete_state.delete(&key);
+            Ok(())
+        },
+    )?;
+
+    let (scan_state, scan_result) = (state, result.clone());
+    linker.func_wrap(
+        STATE_IMPORT_MODULE,
+        "state_scan",
+        move |mut caller: Caller<'_, Context>, prefix_ptr: i32, prefix_len: i32| {
+            let prefix = read_bytes(&mut caller, prefix_ptr, prefix_len)?;
+            let entries: Vec<StateEntry> = scan_state
+                .scan(&prefix)
+                .into_iter()
+                .map(|(key, value)| StateEntry { key, value })
+                .collect();
+            let mut bytes = vec![];
+            entries.encode(&mut bytes, STATE_ENCODING_VERSION)?;
+            let len = bytes.len() as i32;
+            *scan_result.lock().unwrap() = bytes;
+            Ok(len)
+        },
+    )?;
+
+    linker.func_wrap(
+        STATE_IMPORT_MODULE,
+        "state_read",
+        move |mut caller: Caller<'_, Context>, ptr: i32, len: i32| {
+            let bytes = std::mem::take(&mut *result.lock().unwrap());
+            if bytes.len() != len as u32 as usize {
+                anyhow::bail!(
+                    "state result has {} bytes, SmartModule expected {len}",
+                    bytes.len()
+                );
+            }
+            let memory = memory(&mut caller)?;
+            memory.write(&mut caller, ptr as u32 as usize, &bytes)?;
+            Ok(0)
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::collections::BTreeMap;
+
+    use fluvio_protocol::record::Record;
+    use fluvio_smartmodule::dataplane::smartmodule::SmartModuleInput;
+
+    use crate::engine::{
+        SmartEngine, SmartModuleChainBuilder, SmartModuleConfig, SmartModuleKeyValueState,
+        metrics::SmartModuleChainMetrics,
+    };
+    use crate::engine::config::DEFAULT_SMARTENGINE_VERSION;
+    use crate::engine::fixture::read_wasm_module;
+
+    const SM_FILTER_STATE: &str = "fluvio_smartmodule_filter_state";
+
+    #[ignore]
+    #[test]
+    fn test_filter_with_key_value_state() {
+        let engine = SmartEngine::new();
+        let mut chain_builder = SmartModuleChainBuilder::default();
+        let state = SmartModuleKeyValueState::default();
+        state.load(BTreeMap::from([(b"a".to_vec(), b"0".to_vec())]));
+
+        chain_builder.add_smart_module(
+            SmartModuleConfig::builder()
+                .key_value_state(state.clone())
+                .build()
+                .unwrap(),
+            read_wasm_module(SM_FILTER_STATE),
+        );
+
+        let mut chain = chain_builder
+            .initialize(&engine)
+            .expect("failed to build chain");
+
+        let metrics = SmartModuleChainMetrics::default();
+        let input = vec![
+            Record::new_key_value("a", "1"),
+            Record::new_key_value("b", "2"),
+            Record::new_key_value("b", "3"),
+        ];
+        let output = chain
+            .process(
+                SmartModuleInput::try_from_records(input, DEFAULT_SMARTENGINE_VERSION)
+                    .expect("input"),
+                &metrics,
+            )
+            .expect("process");
+
+        // key "a" was seen before the chain started
+        assert_eq!(output.successes.len(), 1);
+        assert_eq!(output.successes[0].value.as_ref(), b"2");
+        assert_eq!(state.get(b"b"), Some(b"1".to_vec()));
+        assert_eq!(
+            state.take_changes(),
+            vec![(b"b".to_vec(), Some(b"1".to_vec()))]
+        );
+    }
+}
@@ -1,7 +1,36 @@
+use std::time::Duration;
+
 use wasmtime::ResourceLimiter;
 
 use crate::engine::error::EngineError;
 
+// DO NOT INCREASE THIS VALUE HIGHER THAN i64::MAX / 2.
+// WASMTIME keeps fuel as i64 and has some strange behavior with `add_fuel` if trying to top fuel
+// up to a values close to i64:MAX
+pub(crate) const DEFAULT_FUEL: u64 = i64::MAX as u64 / 2;
+
+/// Fuel and time allowed for a single call into SmartModule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CallBudget {
+    pub fuel: u64,
+    pub timeout: Option<Duration>,
+}
+
+impl CallBudget {
+    pub(crate) fn new(fuel: Option<u64>, timeout: Option<Duration>) -> Self {
+        Self {
+            fuel: fuel.unwrap_or(DEFAULT_FUEL).min(DEFAULT_FUEL),
+            timeout,
+        }
+    }
+}
+
+impl Default for CallBudget {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct StoreResourceLimiter {
     pub memory_size: Option<usize>,
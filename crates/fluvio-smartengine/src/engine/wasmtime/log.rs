@@ -0,0 +1,26 @@
+use anyhow::Result;
+use tracing::debug;
+use wasmtime::{Caller, Linker};
+
+use super::key_value::read_bytes;
+use super::state::Context;
+
+// module of imports declared by `extern "C"` blocks
+const LOG_IMPORT_MODULE: &str = "env";
+
+/// Define host function SmartModule logs messages with.
+/// Messages are kept by the store only in debug mode of the chain.
+pub(crate) fn add_to_linker(linker: &mut Linker<Context>) -> Result<()> {
+    linker.func_wrap(
+        LOG_IMPORT_MODULE,
+        "log",
+        |mut caller: Caller<'_, Context>, ptr: i32, len: i32| {
+            let bytes = read_bytes(&mut caller, ptr, len)?;
+            let message = String::from_utf8_lossy(&bytes).into_owned();
+            debug!(message, "smartmodule log");
+            caller.data_mut().push_log(message);
+            Ok(())
+        },
+    )?;
+    Ok(())
+}
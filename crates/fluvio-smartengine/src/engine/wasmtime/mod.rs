@@ -6,6 +6,13 @@ pub(crate) mod engine;
 pub(crate) mod instance;
 pub(crate) mod look_back;
 pub(crate) mod limiter;
-pub use engine::{SmartEngine, SmartModuleChainBuilder, SmartModuleChainInstance};
+pub(crate) mod epoch;
+pub(crate) mod key_value;
+pub(crate) mod log;
+pub(crate) mod component;
+pub(crate) mod cache;
+pub use engine::{
+    SmartEngine, SmartModuleChainBuilder, SmartModuleChainInstance, SmartModuleDeadLetter,
+};
 
 use super::*;
@@ -1,23 +1,28 @@
-use std::cmp::max;
-
 use anyhow::Error;
 use wasmtime::{
     AsContext, AsContextMut, Engine, Instance, IntoFunc, Module, Store, StoreContext,
-    StoreContextMut,
+    StoreContextMut, Trap,
 };
 
-use super::limiter::StoreResourceLimiter;
+use crate::engine::error::EngineError;
+use crate::engine::SmartModuleKeyValueState;
 
-// DO NOT INCREASE THIS VALUE HIGHER THAN i64::MAX / 2.
-// WASMTIME keeps fuel as i64 and has some strange behavior with `add_fuel` if trying to top fuel
-// up to a values close to i64:MAX
-const DEFAULT_FUEL: u64 = i64::MAX as u64 / 2;
+use super::epoch::{timeout_ticks, NO_DEADLINE};
+use super::key_value;
+use super::log;
+use super::limiter::{CallBudget, StoreResourceLimiter};
 
 #[derive(Debug)]
 pub struct WasmState(Store<Context>);
 
+// messages kept until they are taken, the rest is dropped
+const MAX_PENDING_LOGS: usize = 1024;
+
 pub struct Context {
     limiter: StoreResourceLimiter,
+    budget: CallBudget,
+    // collected only in debug mode
+    logs: Option<Vec<String>>,
     #[cfg(feature = "wasi")]
     wasi_ctx: wasmtime_wasi::WasiCtx,
 }
@@ -37,43 +42,80 @@ impl AsContextMut for WasmState {
 }
 
 impl WasmState {
-    // If current fuel is less than DEFAULT_FUEL, tops up fuel to DEFAULT_FUEL
+    // Sets fuel and deadline for the next call into SmartModule
+    pub(crate) fn start_call(&mut self, budget: CallBudget) {
+        self.0.data_mut().budget = budget;
+        self.top_up_fuel();
+        let deadline = budget.timeout.map(timeout_ticks).unwrap_or(NO_DEADLINE);
+        self.0.set_epoch_deadline(deadline);
+    }
+
+    // Sets current fuel to fuel of call budget
     pub fn top_up_fuel(&mut self) {
+        let fuel = self.0.data().budget.fuel;
         if let Ok(current_fuel) = self.0.consume_fuel(0) {
-            let amount_to_add = max(DEFAULT_FUEL - current_fuel, 0);
-            let _ = self.0.add_fuel(amount_to_add);
+            if current_fuel < fuel {
+                let _ = self.0.add_fuel(fuel - current_fuel);
+            } else {
+                let _ = self.0.consume_fuel(current_fuel - fuel);
+            }
         }
     }
 
     // Get amount of fuel used since last top up
     pub fn get_used_fuel(&mut self) -> u64 {
         if let Ok(current_fuel) = self.0.consume_fuel(0) {
-            max(DEFAULT_FUEL - current_fuel, 0)
+            self.0.data().budget.fuel.saturating_sub(current_fuel)
         } else {
             0
         }
     }
+
+    // Keeps messages logged by SmartModules until they are taken
+    pub(crate) fn collect_logs(&mut self, enabled: bool) {
+        self.0.data_mut().logs = enabled.then(Vec::new);
+    }
+
+    // Messages logged since last call
+    pub(crate) fn take_logs(&mut self) -> Vec<String> {
+        self.0
+            .data_mut()
+            .logs
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    // Converts trap caused by exhausted call budget into EngineError
+    pub(crate) fn budget_error(&self, err: Error) -> Error {
+        let budget = self.0.data().budget;
+        match err.downcast_ref::<Trap>() {
+            Some(Trap::OutOfFuel) => EngineError::FuelExhausted { limit: budget.fuel }.into(),
+            Some(Trap::Interrupt) => EngineError::CallTimeout {
+                timeout: budget.timeout.unwrap_or_default(),
+            }
+            .into(),
+            _ => err,
+        }
+    }
 }
 
 #[cfg(not(feature = "wasi"))]
 impl WasmState {
     pub(crate) fn new(engine: &Engine, limiter: StoreResourceLimiter) -> Self {
-        let mut s = Self(Store::new(engine, Context { limiter }));
+        let budget = CallBudget::default();
+        let mut s = Self(Store::new(
+            engine,
+            Context {
+                limiter,
+                budget,
+                logs: None,
+            },
+        ));
         s.0.limiter(|inner| &mut inner.limiter);
-        s.top_up_fuel();
+        s.start_call(budget);
         s
     }
-
-    pub(crate) fn instantiate<Params, Args>(
-        &mut self,
-        module: &Module,
-        host_fn: impl IntoFunc<<Self as AsContext>::Data, Params, Args>,
-    ) -> Result<Instance, Error> {
-        use wasmtime::Func;
-
-        let func = Func::wrap(&mut *self, host_fn);
-        Instance::new(self, module, &[func.into()])
-    }
 }
 
 #[cfg(feature = "wasi")]
@@ -83,18 +125,31 @@ impl WasmState {
             .inherit_stderr()
             .inherit_stdout()
             .build();
-        let mut s = Self(Store::new(engine, Context { limiter, wasi_ctx }));
+        let budget = CallBudget::default();
+        let mut s = Self(Store::new(
+            engine,
+            Context {
+                limiter,
+                budget,
+                logs: None,
+                wasi_ctx,
+            },
+        ));
         s.0.limiter(|inner| &mut inner.limiter);
-        s.top_up_fuel();
+        s.start_call(budget);
         s
     }
+}
 
+impl WasmState {
     pub(crate) fn instantiate<Params, Args>(
         &mut self,
         module: &Module,
         host_fn: impl IntoFunc<<Self as AsContext>::Data, Params, Args>,
+        key_value_state: SmartModuleKeyValueState,
     ) -> Result<Instance, Error> {
         let mut linker = wasmtime::Linker::new(module.engine());
+        #[cfg(feature = "wasi")]
         wasmtime_wasi::add_to_linker(&mut linker, |c: &mut Context| &mut c.wasi_ctx)?;
         let copy_records_fn_import = module
             .imports()
@@ -105,14 +160,79 @@ impl WasmState {
             copy_records_fn_import.name(),
             host_fn,
         )?;
+        key_value::add_to_linker(&mut linker, key_value_state)?;
+        log::add_to_linker(&mut linker)?;
         linker.instantiate(self, module)
     }
 }
 
+impl Context {
+    pub(crate) fn push_log(&mut self, message: String) {
+        if let Some(logs) = &mut self.logs {
+            if logs.len() < MAX_PENDING_LOGS {
+                logs.push(message);
+            }
+        }
+    }
+}
+
 impl std::fmt::Debug for Context {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Context")
             .field("limiter", &self.limiter)
+            .field("budget", &self.budget)
             .finish()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use wasmtime::Trap;
+
+    use crate::engine::error::EngineError;
+    use crate::engine::SmartEngine;
+    use crate::engine::wasmtime::limiter::{CallBudget, StoreResourceLimiter};
+
+    #[test]
+    fn test_call_budget_fuel() {
+        let engine = SmartEngine::new();
+        let mut state = engine.new_state(StoreResourceLimiter::default());
+
+        state.start_call(CallBudget::new(Some(1_000), None));
+        assert_eq!(state.get_used_fuel(), 0);
+
+        // lowering budget must take away fuel left from previous call
+        state.start_call(CallBudget::new(Some(10), None));
+        assert_eq!(state.get_used_fuel(), 0);
+        state.0.consume_fuel(4).expect("consume");
+        assert_eq!(state.get_used_fuel(), 4);
+    }
+
+    #[test]
+    fn test_budget_error() {
+        let engine = SmartEngine::new();
+        let mut state = engine.new_state(StoreResourceLimiter::default());
+        state.start_call(CallBudget::new(Some(10), Some(Duration::from_millis(50))));
+
+        let err = state
+            .budget_error(Trap::OutOfFuel.into())
+            .downcast::<EngineError>()
+            .expect("EngineError expected");
+        assert!(matches!(err, EngineError::FuelExhausted { limit: 10 }));
+
+        let err = state
+            .budget_error(Trap::Interrupt.into())
+            .downcast::<EngineError>()
+            .expect("EngineError expected");
+        assert!(
+            matches!(err, EngineError::CallTimeout { timeout } if timeout == Duration::from_millis(50))
+        );
+
+        assert!(state
+            .budget_error(Trap::StackOverflow.into())
+            .downcast::<EngineError>()
+            .is_err());
+    }
+}
@@ -31,8 +31,7 @@ impl Debug for SmartModuleAggregate {
 }
 
 impl SmartModuleAggregate {
-    #[cfg(test)]
-    fn accumulator(&self) -> &[u8] {
+    pub(crate) fn accumulator(&self) -> &[u8] {
         &self.accumulator
     }
 
@@ -44,7 +43,9 @@ impl SmartModuleAggregate {
         // get initial -data
         let accumulator = match initial_data {
             SmartModuleInitialData::Aggregate { accumulator } => accumulator,
-            SmartModuleInitialData::None => {
+            SmartModuleInitialData::None
+            | SmartModuleInitialData::Join { .. }
+            | SmartModuleInitialData::Window { .. } => {
                 // if no initial data, then we initialize as default
                 vec![]
             }
@@ -207,6 +208,7 @@ mod test {
             .expect("aggregate");
 
         assert_eq!(aggregate.accumulator(), b"ab");
+        assert_eq!(chain.accumulators(), vec![b"ab".to_vec()]);
 
         let input = vec![Record::new("c")];
         let output = chain
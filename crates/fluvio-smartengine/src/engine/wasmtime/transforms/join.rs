@@ -0,0 +1,185 @@
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::io::Cursor;
+
+use tracing::{debug, instrument};
+use anyhow::Result;
+use wasmtime::{AsContextMut, TypedFunc};
+
+use fluvio_protocol::Decoder;
+use fluvio_protocol::record::{Record, RecordData};
+use fluvio_smartmodule::dataplane::smartmodule::{
+    SmartModuleInput, SmartModuleOutput, SmartModuleJoinInput, SmartModuleTransformErrorStatus,
+};
+use crate::engine::{SmartModuleInitialData, SmartModuleJoinTable};
+use crate::engine::wasmtime::{
+    instance::{SmartModuleInstanceContext, SmartModuleTransform},
+    state::WasmState,
+};
+
+pub(crate) const JOIN_FN_NAME: &str = "join";
+
+type WasmJoinFn = TypedFunc<(i32, i32, u32), i32>;
+
+pub(crate) struct SmartModuleJoin {
+    join_fn: WasmJoinFn,
+    table: SmartModuleJoinTable,
+}
+
+impl Debug for SmartModuleJoin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "JoinFn")
+    }
+}
+
+impl SmartModuleJoin {
+    pub fn try_instantiate(
+        ctx: &SmartModuleInstanceContext,
+        initial_data: &SmartModuleInitialData,
+        store: &mut impl AsContextMut,
+    ) -> Result<Option<Self>> {
+        // without table, every record is joined with no value
+        let table = match initial_data {
+            SmartModuleInitialData::Join { table } => table.clone(),
+            _ => SmartModuleJoinTable::default(),
+        };
+
+        match ctx.get_wasm_func(&mut *store, JOIN_FN_NAME) {
+            Some(func) => {
+                // check type signature
+                func.typed(&mut *store)
+                    .or_else(|_| func.typed(store))
+                    .map(|join_fn| Some(Self { join_fn, table }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// look up value of the key of each input record
+    fn right_values(
+        &self,
+        input: &SmartModuleInput,
+        version: i16,
+    ) -> Result<Vec<Option<RecordData>>> {
+        let records: Vec<Record> =
+            Decoder::decode_from(&mut Cursor::new(input.raw_bytes()), version)?;
+        Ok(records
+            .iter()
+            .map(|record| {
+                record
+                    .key()
+                    .and_then(|key| self.table.get(key.as_ref()))
+                    .map(RecordData::from)
+            })
+            .collect())
+    }
+}
+
+impl SmartModuleTransform for SmartModuleJoin {
+    #[instrument(skip(self,ctx,store),fields(offset = input.base_offset()))]
+    fn process(
+        &mut self,
+        input: SmartModuleInput,
+        ctx: &mut SmartModuleInstanceContext,
+        store: &mut WasmState,
+    ) -> Result<SmartModuleOutput> {
+        let right_values = self.right_values(&input, ctx.version())?;
+        debug!(
+            records = right_values.len(),
+            table = self.table.len(),
+            "start join"
+        );
+        let input = SmartModuleJoinInput {
+            base: input,
+            right_values,
+        };
+        let slice = ctx.write_input(&input, &mut *store)?;
+        let join_output = self.join_fn.call(&mut *store, slice)?;
+
+        debug!(join_output);
+        if join_output < 0 {
+            let internal_error = SmartModuleTransformErrorStatus::try_from(join_output)
+                .unwrap_or(SmartModuleTransformErrorStatus::UnknownError);
+            return Err(internal_error.into());
+        }
+
+        let output: SmartModuleOutput = ctx.read_output(store)?;
+        Ok(output)
+    }
+
+    fn name(&self) -> &str {
+        JOIN_FN_NAME
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use fluvio_protocol::record::Record;
+    use fluvio_smartmodule::dataplane::smartmodule::SmartModuleInput;
+
+    use crate::engine::{
+        SmartEngine, SmartModuleChainBuilder, SmartModuleConfig, SmartModuleInitialData,
+        SmartModuleJoinTable, metrics::SmartModuleChainMetrics,
+    };
+    use crate::engine::fixture::read_wasm_module;
+    use crate::engine::config::DEFAULT_SMARTENGINE_VERSION;
+
+    use super::JOIN_FN_NAME;
+
+    const SM_JOIN: &str = "fluvio_smartmodule_join";
+
+    #[ignore]
+    #[test]
+    fn test_join() {
+        let engine = SmartEngine::new();
+        let mut chain_builder = SmartModuleChainBuilder::default();
+        let table = SmartModuleJoinTable::default();
+        table.upsert(b"apple", b"red");
+
+        chain_builder.add_smart_module(
+            SmartModuleConfig::builder()
+                .initial_data(SmartModuleInitialData::with_join(table.clone()))
+                .build()
+                .unwrap(),
+            read_wasm_module(SM_JOIN),
+        );
+
+        let mut chain = chain_builder
+            .initialize(&engine)
+            .expect("failed to build chain");
+
+        assert_eq!(
+            chain.instances().first().expect("first").transform().name(),
+            JOIN_FN_NAME
+        );
+
+        let metrics = SmartModuleChainMetrics::default();
+        let input = vec![
+            Record::new_key_value("apple", "fruit"),
+            Record::new_key_value("kiwi", "fruit"),
+        ];
+        let output = chain
+            .process(
+                SmartModuleInput::try_from_records(input, DEFAULT_SMARTENGINE_VERSION)
+                    .expect("input"),
+                &metrics,
+            )
+            .expect("process");
+        assert_eq!(output.successes.len(), 2);
+        assert_eq!(output.successes[0].value.as_ref(), b"fruit:red");
+        assert_eq!(output.successes[1].value.as_ref(), b"fruit");
+
+        // table updates are visible to next process call
+        table.upsert(b"kiwi", b"green");
+        let input = vec![Record::new_key_value("kiwi", "fruit")];
+        let output = chain
+            .process(
+                SmartModuleInput::try_from_records(input, DEFAULT_SMARTENGINE_VERSION)
+                    .expect("input"),
+                &metrics,
+            )
+            .expect("process");
+        assert_eq!(output.successes[0].value.as_ref(), b"fruit:green");
+    }
+}
@@ -3,7 +3,10 @@ mod map;
 mod array_map;
 mod filter_map;
 mod aggregate;
+mod join;
+mod window;
 pub(crate) use instance::create_transform;
+pub(crate) use aggregate::SmartModuleAggregate;
 mod simple_transform;
 
 mod instance {
@@ -19,6 +22,8 @@ mod instance {
             SimpleTansform, FILTER_FN_NAME, MAP_FN_NAME, FILTER_MAP_FN_NAME, ARRAY_MAP_FN_NAME,
         },
         aggregate::SmartModuleAggregate,
+        join::SmartModuleJoin,
+        window::SmartModuleWindow,
     };
 
     pub(crate) fn create_transform(
@@ -42,6 +47,14 @@ mod instance {
             .map(|transform| Box::new(transform) as Box<dyn DowncastableTransform>)
         {
             Ok(tr)
+        } else if let Some(tr) = SmartModuleJoin::try_instantiate(ctx, &initial_data, store)?
+            .map(|transform| Box::new(transform) as Box<dyn DowncastableTransform>)
+        {
+            Ok(tr)
+        } else if let Some(tr) = SmartModuleWindow::try_instantiate(ctx, &initial_data, store)?
+            .map(|transform| Box::new(transform) as Box<dyn DowncastableTransform>)
+        {
+            Ok(tr)
         } else if let Some(tr) = SmartModuleAggregate::try_instantiate(ctx, initial_data, store)?
             .map(|transform| Box::new(transform) as Box<dyn DowncastableTransform>)
         {
@@ -0,0 +1,275 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+
+use tracing::{debug, instrument};
+use anyhow::{anyhow, Result};
+use wasmtime::{AsContextMut, TypedFunc};
+
+use fluvio_protocol::record::{Record, RecordData};
+use fluvio_smartmodule::dataplane::smartmodule::{
+    SmartModuleInput, SmartModuleOutput, SmartModuleWindowInput, SmartModuleWindowOutput,
+    SmartModuleTransformErrorStatus,
+};
+use crate::engine::SmartModuleInitialData;
+use crate::engine::error::EngineError;
+use crate::engine::window::{WindowKey, WindowStore};
+use crate::engine::wasmtime::{
+    instance::{SmartModuleInstanceContext, SmartModuleTransform},
+    state::WasmState,
+};
+
+pub(crate) const WINDOW_FN_NAME: &str = "window";
+
+type WasmWindowFn = TypedFunc<(i32, i32, u32), i32>;
+
+pub(crate) struct SmartModuleWindow {
+    window_fn: WasmWindowFn,
+    store: WindowStore,
+}
+
+impl Debug for SmartModuleWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "WindowFn")
+    }
+}
+
+impl SmartModuleWindow {
+    pub fn try_instantiate(
+        ctx: &SmartModuleInstanceContext,
+        initial_data: &SmartModuleInitialData,
+        store: &mut impl AsContextMut,
+    ) -> Result<Option<Self>> {
+        let Some(func) = ctx.get_wasm_func(&mut *store, WINDOW_FN_NAME) else {
+            return Ok(None);
+        };
+
+        let SmartModuleInitialData::Window { config } = initial_data else {
+            return Err(EngineError::Instantiate(anyhow!(
+                "window SmartModule requires window configuration"
+            ))
+            .into());
+        };
+        let window_store =
+            WindowStore::new(config).map_err(|err| EngineError::Instantiate(anyhow!(err)))?;
+
+        // check type signature
+        func.typed(&mut *store)
+            .or_else(|_| func.typed(store))
+            .map(|window_fn| {
+                Some(Self {
+                    window_fn,
+                    store: window_store,
+                })
+            })
+    }
+}
+
+impl SmartModuleTransform for SmartModuleWindow {
+    #[instrument(skip(self,ctx,store),fields(offset = input.base_offset()))]
+    fn process(
+        &mut self,
+        input: SmartModuleInput,
+        ctx: &mut SmartModuleInstanceContext,
+        store: &mut WasmState,
+    ) -> Result<SmartModuleOutput> {
+        let version = ctx.version();
+        let base_offset = input.base_offset();
+        let base_timestamp = input.base_timestamp();
+        let records = input.try_into_smartmodule_records(version)?;
+
+        // assign each record to its open windows
+        let mut windows: Vec<WindowKey> = vec![];
+        let mut window_index: HashMap<WindowKey, u32> = HashMap::new();
+        let mut groups = vec![];
+        let mut window_records = vec![];
+        let mut max_timestamp = None;
+        let mut last_offset_delta = 0;
+        for record in records {
+            let timestamp = record.timestamp();
+            max_timestamp = max_timestamp.max(Some(timestamp));
+            last_offset_delta = record.preamble.offset_delta();
+
+            let key = record.key().map(|key| key.as_ref().to_vec());
+            for start in self.store.assign(timestamp) {
+                let window = WindowKey {
+                    start,
+                    key: key.clone(),
+                };
+                let index = *window_index.entry(window.clone()).or_insert_with(|| {
+                    windows.push(window);
+                    (windows.len() - 1) as u32
+                });
+                groups.push(index);
+                window_records.push(record.clone().into_inner());
+            }
+        }
+        debug!(
+            records = window_records.len(),
+            windows = windows.len(),
+            "start window"
+        );
+
+        if !window_records.is_empty() {
+            let mut base = SmartModuleInput::try_from_records(window_records, version)?;
+            base.set_base_offset(base_offset);
+            base.set_base_timestamp(base_timestamp);
+            let input = SmartModuleWindowInput {
+                base,
+                groups,
+                accumulators: windows
+                    .iter()
+                    .map(|window| self.store.get(window))
+                    .collect(),
+            };
+            let slice = ctx.write_input(&input, &mut *store)?;
+            let window_output = self.window_fn.call(&mut *store, slice)?;
+
+            debug!(window_output);
+            if window_output < 0 {
+                let internal_error = SmartModuleTransformErrorStatus::try_from(window_output)
+                    .unwrap_or(SmartModuleTransformErrorStatus::UnknownError);
+                return Err(internal_error.into());
+            }
+
+            let output: SmartModuleWindowOutput = ctx.read_output(store)?;
+            if output.error.is_some() {
+                // windows are not updated, so failed batch can be retried
+                return Ok(SmartModuleOutput::with_error(vec![], output.error));
+            }
+            if output.accumulators.len() != windows.len() {
+                return Err(SmartModuleTransformErrorStatus::UndefinedWindow.into());
+            }
+            for (window, accumulator) in windows.into_iter().zip(output.accumulators) {
+                self.store.update(window, accumulator);
+            }
+        }
+
+        let Some(max_timestamp) = max_timestamp else {
+            return Ok(SmartModuleOutput::default());
+        };
+
+        // emit closed windows, stamped with window end
+        let successes = self
+            .store
+            .advance(max_timestamp)
+            .into_iter()
+            .map(|(window, accumulator)| {
+                let mut record = Record {
+                    key: window.key.map(RecordData::from),
+                    value: RecordData::from(accumulator),
+                    ..Default::default()
+                };
+                record.preamble.set_timestamp_delta(
+                    self.store.end(window.start).saturating_sub(base_timestamp),
+                );
+                record.preamble.set_offset_delta(last_offset_delta);
+                record
+            })
+            .collect::<Vec<_>>();
+        debug!(closed = successes.len(), "windows closed");
+
+        Ok(SmartModuleOutput::new(successes))
+    }
+
+    fn name(&self) -> &str {
+        WINDOW_FN_NAME
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::Duration;
+
+    use fluvio_protocol::record::Record;
+    use fluvio_smartmodule::dataplane::smartmodule::SmartModuleInput;
+
+    use crate::engine::{
+        SmartEngine, SmartModuleChainBuilder, SmartModuleConfig, SmartModuleInitialData,
+        SmartModuleWindowConfig, metrics::SmartModuleChainMetrics,
+    };
+    use crate::engine::fixture::read_wasm_module;
+    use crate::engine::config::DEFAULT_SMARTENGINE_VERSION;
+
+    use super::WINDOW_FN_NAME;
+
+    const SM_WINDOW: &str = "fluvio_smartmodule_window_sum";
+
+    fn record(key: &str, value: &str, timestamp: i64) -> Record {
+        let mut record = Record::new_key_value(key, value);
+        record.preamble.set_timestamp_delta(timestamp);
+        record
+    }
+
+    #[ignore]
+    #[test]
+    fn test_tumbling_window_sum() {
+        let engine = SmartEngine::new();
+        let mut chain_builder = SmartModuleChainBuilder::default();
+
+        chain_builder.add_smart_module(
+            SmartModuleConfig::builder()
+                .initial_data(SmartModuleInitialData::with_window(
+                    SmartModuleWindowConfig::tumbling(Duration::from_millis(10)),
+                ))
+                .build()
+                .unwrap(),
+            read_wasm_module(SM_WINDOW),
+        );
+
+        let mut chain = chain_builder
+            .initialize(&engine)
+            .expect("failed to build chain");
+
+        assert_eq!(
+            chain.instances().first().expect("first").transform().name(),
+            WINDOW_FN_NAME
+        );
+
+        let metrics = SmartModuleChainMetrics::default();
+        let input = vec![
+            record("a", "1", 1),
+            record("b", "2", 2),
+            record("a", "3", 9),
+        ];
+        let output = chain
+            .process(
+                SmartModuleInput::try_from_records(input, DEFAULT_SMARTENGINE_VERSION)
+                    .expect("input"),
+                &metrics,
+            )
+            .expect("process");
+        assert!(output.successes.is_empty());
+
+        // record of next window closes the first one
+        let input = vec![record("a", "5", 12)];
+        let output = chain
+            .process(
+                SmartModuleInput::try_from_records(input, DEFAULT_SMARTENGINE_VERSION)
+                    .expect("input"),
+                &metrics,
+            )
+            .expect("process");
+        assert_eq!(output.successes.len(), 2);
+        assert_eq!(output.successes[0].key.as_ref().unwrap().as_ref(), b"a");
+        assert_eq!(output.successes[0].value.as_ref(), b"4");
+        assert_eq!(output.successes[0].timestamp_delta(), 10);
+        assert_eq!(output.successes[1].key.as_ref().unwrap().as_ref(), b"b");
+        assert_eq!(output.successes[1].value.as_ref(), b"2");
+    }
+
+    #[ignore]
+    #[test]
+    fn test_window_requires_config() {
+        let engine = SmartEngine::new();
+        let mut chain_builder = SmartModuleChainBuilder::default();
+
+        chain_builder.add_smart_module(
+            SmartModuleConfig::builder().build().unwrap(),
+            read_wasm_module(SM_WINDOW),
+        );
+
+        assert!(chain_builder.initialize(&engine).is_err());
+    }
+}
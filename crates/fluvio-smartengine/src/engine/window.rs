@@ -0,0 +1,265 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use fluvio_protocol::types::Timestamp;
+
+/// Time windows of Window SmartModule.
+/// Windows are aligned to multiples of `slide` since epoch and are based on record timestamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SmartModuleWindowConfig {
+    pub size: Duration,
+    pub slide: Duration,
+    /// how long window stays open after its end for records arriving out of order
+    pub allowed_lateness: Duration,
+}
+
+impl SmartModuleWindowConfig {
+    /// windows which don't overlap
+    pub fn tumbling(size: Duration) -> Self {
+        Self::hopping(size, size)
+    }
+
+    /// windows of `size` starting every `slide`
+    pub fn hopping(size: Duration, slide: Duration) -> Self {
+        Self {
+            size,
+            slide,
+            allowed_lateness: Duration::ZERO,
+        }
+    }
+
+    pub fn with_allowed_lateness(mut self, allowed_lateness: Duration) -> Self {
+        self.allowed_lateness = allowed_lateness;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct WindowKey {
+    pub start: Timestamp,
+    pub key: Option<Vec<u8>>,
+}
+
+/// Accumulators of open windows.
+/// Window is closed when the latest record timestamp passes window end and allowed lateness.
+/// Windows are kept only in memory of the chain instance, unlike aggregate accumulators they are not
+/// checkpointed, so windows open when the chain is dropped are lost.
+#[derive(Debug)]
+pub(crate) struct WindowStore {
+    size: Timestamp,
+    slide: Timestamp,
+    allowed_lateness: Timestamp,
+    windows: BTreeMap<WindowKey, Vec<u8>>,
+    watermark: Timestamp,
+}
+
+impl WindowStore {
+    pub fn new(config: &SmartModuleWindowConfig) -> Result<Self, String> {
+        let size = millis("size", config.size)?;
+        let slide = millis("slide", config.slide)?;
+        let allowed_lateness = millis("allowed lateness", config.allowed_lateness)?;
+        if size == 0 || slide == 0 {
+            return Err("window size and slide must be at least one millisecond".to_owned());
+        }
+        if slide > size {
+            return Err(format!(
+                "window slide {:?} must not be greater than window size {:?}",
+                config.slide, config.size
+            ));
+        }
+        if size.checked_add(allowed_lateness).is_none() {
+            return Err(format!(
+                "window size {:?} with allowed lateness {:?} is too large",
+                config.size, config.allowed_lateness
+            ));
+        }
+        Ok(Self {
+            size,
+            slide,
+            allowed_lateness,
+            windows: BTreeMap::new(),
+            watermark: Timestamp::MIN,
+        })
+    }
+
+    pub fn end(&self, start: Timestamp) -> Timestamp {
+        start.saturating_add(self.size)
+    }
+
+    fn is_closed(&self, start: Timestamp) -> bool {
+        self.end(start).saturating_add(self.allowed_lateness) <= self.watermark
+    }
+
+    /// starts of open windows which contain timestamp
+    pub fn assign(&self, timestamp: Timestamp) -> Vec<Timestamp> {
+        let Some(last_start) = timestamp.checked_sub(timestamp.rem_euclid(self.slide)) else {
+            return vec![];
+        };
+        let mut starts: Vec<Timestamp> =
+            std::iter::successors(Some(last_start), |start| start.checked_sub(self.slide))
+                .take_while(|start| {
+                    timestamp
+                        .checked_sub(*start)
+                        .is_some_and(|offset| offset < self.size)
+                })
+                .filter(|start| !self.is_closed(*start))
+                .collect();
+        starts.reverse();
+        starts
+    }
+
+    /// current accumulator of window, empty for new window
+    pub fn get(&self, window: &WindowKey) -> Vec<u8> {
+        self.windows.get(window).cloned().unwrap_or_default()
+    }
+
+    pub fn update(&mut self, window: WindowKey, accumulator: Vec<u8>) {
+        self.windows.insert(window, accumulator);
+    }
+
+    /// move watermark to timestamp and remove closed windows
+    pub fn advance(&mut self, timestamp: Timestamp) -> Vec<(WindowKey, Vec<u8>)> {
+        self.watermark = self.watermark.max(timestamp);
+        let open = self.windows.split_off(&WindowKey {
+            start: self.first_open_start(),
+            key: None,
+        });
+        std::mem::replace(&mut self.windows, open)
+            .into_iter()
+            .collect()
+    }
+
+    /// earliest window start which is not closed
+    fn first_open_start(&self) -> Timestamp {
+        let min_end = self.watermark.saturating_sub(self.allowed_lateness);
+        min_end.saturating_sub(self.size).saturating_add(1)
+    }
+}
+
+/// duration in milliseconds as timestamp
+fn millis(name: &str, duration: Duration) -> Result<Timestamp, String> {
+    Timestamp::try_from(duration.as_millis())
+        .map_err(|_| format!("window {name} {duration:?} is too large"))
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::Duration;
+
+    use super::{SmartModuleWindowConfig, WindowKey, WindowStore};
+
+    fn window(start: i64, key: &str) -> WindowKey {
+        WindowKey {
+            start,
+            key: Some(key.as_bytes().to_vec()),
+        }
+    }
+
+    #[test]
+    fn test_window_config_validate() {
+        assert!(
+            WindowStore::new(&SmartModuleWindowConfig::tumbling(Duration::from_secs(1))).is_ok()
+        );
+        assert!(WindowStore::new(&SmartModuleWindowConfig::hopping(
+            Duration::from_secs(1),
+            Duration::from_secs(2)
+        ))
+        .is_err());
+        assert!(WindowStore::new(&SmartModuleWindowConfig::tumbling(Duration::ZERO)).is_err());
+        assert!(WindowStore::new(&SmartModuleWindowConfig::hopping(
+            Duration::from_secs(1),
+            Duration::ZERO
+        ))
+        .is_err());
+        // below millisecond resolution of timestamps
+        assert!(
+            WindowStore::new(&SmartModuleWindowConfig::tumbling(Duration::from_micros(
+                500
+            )))
+            .is_err()
+        );
+        assert!(WindowStore::new(&SmartModuleWindowConfig::tumbling(Duration::MAX)).is_err());
+        assert!(WindowStore::new(
+            &SmartModuleWindowConfig::tumbling(Duration::from_millis(i64::MAX as u64))
+                .with_allowed_lateness(Duration::from_millis(1))
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_windows_at_timestamp_bounds() {
+        let mut store = WindowStore::new(&SmartModuleWindowConfig::hopping(
+            Duration::from_millis(10),
+            Duration::from_millis(5),
+        ))
+        .expect("window store");
+
+        assert_eq!(store.assign(i64::MIN + 3), vec![i64::MIN + 3]);
+        // start of the window is not representable
+        assert!(store.assign(i64::MIN + 1).is_empty());
+        assert_eq!(store.assign(i64::MAX), vec![i64::MAX - 7, i64::MAX - 2]);
+        assert_eq!(store.end(i64::MAX - 2), i64::MAX);
+
+        store.update(window(i64::MAX - 7, "a"), b"1".to_vec());
+        assert!(store.advance(i64::MAX).is_empty());
+    }
+
+    #[test]
+    fn test_tumbling_windows() {
+        let mut store = WindowStore::new(&SmartModuleWindowConfig::tumbling(
+            Duration::from_millis(10),
+        ))
+        .expect("window store");
+
+        assert_eq!(store.assign(0), vec![0]);
+        assert_eq!(store.assign(9), vec![0]);
+        assert_eq!(store.assign(10), vec![10]);
+
+        store.update(window(0, "a"), b"1".to_vec());
+        store.update(window(0, "b"), b"2".to_vec());
+        assert!(store.advance(9).is_empty());
+
+        let closed = store.advance(10);
+        assert_eq!(
+            closed,
+            vec![
+                (window(0, "a"), b"1".to_vec()),
+                (window(0, "b"), b"2".to_vec())
+            ]
+        );
+
+        // late record of closed window is not assigned
+        assert!(store.assign(5).is_empty());
+    }
+
+    #[test]
+    fn test_hopping_windows() {
+        let store = WindowStore::new(&SmartModuleWindowConfig::hopping(
+            Duration::from_millis(10),
+            Duration::from_millis(5),
+        ))
+        .expect("window store");
+
+        assert_eq!(store.assign(7), vec![0, 5]);
+        assert_eq!(store.assign(10), vec![5, 10]);
+        assert_eq!(store.assign(-1), vec![-10, -5]);
+    }
+
+    #[test]
+    fn test_allowed_lateness() {
+        let mut store = WindowStore::new(
+            &SmartModuleWindowConfig::tumbling(Duration::from_millis(10))
+                .with_allowed_lateness(Duration::from_millis(5)),
+        )
+        .expect("window store");
+
+        store.update(window(0, "a"), b"1".to_vec());
+        assert!(store.advance(12).is_empty());
+        assert_eq!(store.assign(8), vec![0]);
+
+        let closed = store.advance(15);
+        assert_eq!(closed, vec![(window(0, "a"), b"1".to_vec())]);
+        assert!(store.assign(8).is_empty());
+    }
+}
@@ -54,6 +54,18 @@ pub struct TransformationStep {
     pub lookback: Option<Lookback>,
     #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
     pub with: BTreeMap<String, JsonString>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_error: Option<ErrorPolicy>,
+    /// fuel allowed for each call of the step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel_limit: Option<u64>,
+    /// time allowed for each call of the step
+    #[serde(
+        default,
+        with = "humantime_serde",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub call_timeout: Option<Duration>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
@@ -65,6 +77,16 @@ pub struct Lookback {
     pub age: Option<Duration>,
 }
 
+/// What happens to a record the step failed to process
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorPolicy {
+    Fail,
+    Skip,
+    /// topic the record and the error are written to
+    DeadLetter(String),
+}
+
 impl Display for TransformationStep {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{self:?}")
@@ -94,6 +116,16 @@ impl From<Lookback> for fluvio_smartmodule::dataplane::smartmodule::Lookback {
     }
 }
 
+impl From<ErrorPolicy> for fluvio_smartmodule::dataplane::smartmodule::SmartModuleErrorPolicy {
+    fn from(value: ErrorPolicy) -> Self {
+        match value {
+            ErrorPolicy::Fail => Self::Fail,
+            ErrorPolicy::Skip => Self::Skip,
+            ErrorPolicy::DeadLetter(topic) => Self::DeadLetter(topic),
+        }
+    }
+}
+
 #[derive(Default, Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct JsonString(String);
 
@@ -202,7 +234,10 @@ mod tests {
                         with: BTreeMap::from([(
                             "spec".to_string(),
                             JsonString("[{\"operation\":\"shift\",\"spec\":{\"payload\":{\"device\":\"device\"}}},{\"operation\":\"default\",\"spec\":{\"device\":{\"type\":\"mobile\"}}}]".to_string())
-                        )])
+                        )]),
+                        on_error: None,
+                        fuel_limit: None,
+                        call_timeout: None,
                     },
                     TransformationStep {
                         uses: "infinyon/jolt@0.1.0".to_string(),
@@ -210,7 +245,10 @@ mod tests {
                         with: BTreeMap::from([(
                             "spec".to_string(),
                             JsonString("[{\"operation\":\"shift\",\"spec\":{\"payload\":{\"device\":\"device\"}}},{\"operation\":\"default\",\"spec\":{\"device\":{\"type\":\"mobile\"}}}]".to_string())
-                        )])
+                        )]),
+                        on_error: None,
+                        fuel_limit: None,
+                        call_timeout: None,
                     },
                     TransformationStep {
                         uses: "infinyon/json-sql@0.1.0".to_string(),
@@ -218,7 +256,10 @@ mod tests {
                         with: BTreeMap::from([(
                             "mapping".to_string(),
                             JsonString("{\"map-columns\":{\"device_id\":{\"json-key\":\"device.device_id\",\"value\":{\"default\":\"0\",\"required\":true,\"type\":\"int\"}},\"record\":{\"json-key\":\"$\",\"value\":{\"required\":true,\"type\":\"jsonb\"}}},\"table\":\"topic_message_demo\"}".to_string())
-                        )])
+                        )]),
+                        on_error: None,
+                        fuel_limit: None,
+                        call_timeout: None,
                     }
                 ]
             }
@@ -267,4 +308,44 @@ mod tests {
             )])
         );
     }
+
+    #[test]
+    fn test_from_vec_with_error_policy() {
+        //given
+        let vec = vec![
+            r#"{"uses":"infinyon/jolt@0.1.0","on_error":"skip"}"#,
+            r#"{"uses":"infinyon/json-sql@0.1.0","on_error":{"dead_letter":"failed-records"}}"#,
+        ];
+
+        //when
+        let config = TransformationConfig::try_from(vec).expect("transformation config");
+
+        //then
+        assert_eq!(config.transforms[0].on_error, Some(ErrorPolicy::Skip));
+        assert_eq!(
+            config.transforms[1].on_error,
+            Some(ErrorPolicy::DeadLetter("failed-records".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_from_vec_with_budget() {
+        //given
+        let vec = vec![
+            r#"{"uses":"infinyon/jolt@0.1.0","fuel_limit":1000000,"call_timeout":"500ms"}"#,
+            r#"{"uses":"infinyon/json-sql@0.1.0"}"#,
+        ];
+
+        //when
+        let config = TransformationConfig::try_from(vec).expect("transformation config");
+
+        //then
+        assert_eq!(config.transforms[0].fuel_limit, Some(1_000_000));
+        assert_eq!(
+            config.transforms[0].call_timeout,
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(config.transforms[1].fuel_limit, None);
+        assert_eq!(config.transforms[1].call_timeout, None);
+    }
 }
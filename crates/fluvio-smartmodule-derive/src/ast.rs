@@ -31,6 +31,8 @@ pub enum SmartModuleKind {
     Map,
     ArrayMap,
     FilterMap,
+    Join,
+    Window,
 }
 
 impl ToString for SmartModuleKind {
@@ -43,6 +45,8 @@ impl ToString for SmartModuleKind {
             SmartModuleKind::Map => "map",
             SmartModuleKind::ArrayMap => "array_map",
             SmartModuleKind::FilterMap => "filter_map",
+            SmartModuleKind::Join => "join",
+            SmartModuleKind::Window => "window",
         };
 
         string.to_string()
@@ -62,6 +66,8 @@ impl SmartModuleKind {
             "map" => Some(Self::Map),
             "array_map" => Some(Self::ArrayMap),
             "filter_map" => Some(Self::FilterMap),
+            "join" => Some(Self::Join),
+            "window" => Some(Self::Window),
             "init" => Some(Self::Init),
             "look_back" => Some(Self::LookBack),
             _ => None,
@@ -0,0 +1,110 @@
+use quote::quote;
+use proc_macro2::TokenStream;
+
+use crate::ast::{SmartModuleFn, RecordKind};
+
+pub fn generate_join_smartmodule(sm_func: &SmartModuleFn) -> TokenStream {
+    let user_code = &sm_func.func;
+    let records_code = match sm_func.record_kind {
+        RecordKind::LegacyRecord => quote! {
+            let records: Vec<Record> = match smartmodule_input.base.try_into_records(version) {
+                Ok(records) => records,
+                Err(_) => {
+                    return SmartModuleTransformErrorStatus::DecodingRecords as i32;
+                }
+            };
+        },
+        RecordKind::SmartModuleRecord => quote! {
+            let records: Vec<SmartModuleRecord> = match smartmodule_input.base.try_into_smartmodule_records(version) {
+                Ok(records) => records,
+                Err(_) => {
+                    return SmartModuleTransformErrorStatus::DecodingRecords as i32;
+                }
+            };
+        },
+    };
+
+    let user_fn = &sm_func.name;
+    let function_call = quote!(
+        super:: #user_fn(&record, right_value.as_ref())
+    );
+
+    quote! {
+        #[allow(dead_code)]
+        #user_code
+
+        #[cfg(target_arch = "wasm32")]
+        mod __system {
+            #[no_mangle]
+            #[allow(clippy::missing_safety_doc)]
+            pub unsafe fn join(ptr: *mut u8, len: usize, version: i16) -> i32 {
+                use fluvio_smartmodule::dataplane::smartmodule::{
+                    SmartModuleJoinInput, SmartModuleTransformErrorStatus,
+                    SmartModuleTransformRuntimeError, SmartModuleKind, SmartModuleOutput
+                };
+                use fluvio_smartmodule::SmartModuleRecord;
+                use fluvio_smartmodule::dataplane::core::{Encoder, Decoder};
+                use fluvio_smartmodule::dataplane::record::{Record, RecordData};
+
+                extern "C" {
+                    fn copy_records(putr: i32, len: i32);
+                }
+
+                let input_data = Vec::from_raw_parts(ptr, len, len);
+                let mut smartmodule_input = SmartModuleJoinInput::default();
+                if let Err(_err) = Decoder::decode(&mut smartmodule_input, &mut std::io::Cursor::new(input_data), version) {
+                    return SmartModuleTransformErrorStatus::DecodingBaseInput as i32;
+                }
+
+                let base_offset = smartmodule_input.base.base_offset();
+                let right_values = smartmodule_input.right_values;
+
+                #records_code
+
+                if right_values.len() != records.len() {
+                    return SmartModuleTransformErrorStatus::UndefinedRightRecord as i32;
+                }
+
+                // PROCESSING
+                let mut output = SmartModuleOutput {
+                    successes: Vec::with_capacity(records.len()),
+                    error: None,
+                };
+
+                for (mut record, right_value) in records.into_iter().zip(right_values.into_iter()) {
+                    let result = #function_call;
+
+                    match result {
+                        Ok((maybe_key, value)) => {
+                            record.key = maybe_key;
+                            record.value = value;
+                            output.successes.push(record.into());
+                        }
+                        Err(err) => {
+                            let error = SmartModuleTransformRuntimeError::new(
+                                &record.into(),
+                                base_offset,
+                                SmartModuleKind::Join,
+                                err,
+                            );
+                            output.error = Some(error);
+                            break;
+                        }
+                    }
+                }
+
+                // ENCODING
+                let mut out = vec![];
+                if let Err(_) = Encoder::encode(&mut output, &mut out, version) {
+                    return SmartModuleTransformErrorStatus::EncodingOutput as i32;
+                }
+
+                let out_len = out.len();
+                let ptr = out.as_mut_ptr();
+                std::mem::forget(out);
+                copy_records(ptr as i32, out_len as i32);
+                output.successes.len() as i32
+            }
+        }
+    }
+}
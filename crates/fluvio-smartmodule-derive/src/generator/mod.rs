@@ -3,6 +3,8 @@ mod map;
 mod array_map;
 mod filter_map;
 mod aggregate;
+mod join;
+mod window;
 mod init;
 mod transform;
 mod look_back;
@@ -22,6 +24,8 @@ pub fn generate_smartmodule(config: &SmartModuleConfig, func: &SmartModuleFn) ->
         SmartModuleKind::FilterMap => self::filter_map::generate_filter_map_smartmodule(func),
         SmartModuleKind::Aggregate => self::aggregate::generate_aggregate_smartmodule(func),
         SmartModuleKind::ArrayMap => self::array_map::generate_array_map_smartmodule(func),
+        SmartModuleKind::Join => self::join::generate_join_smartmodule(func),
+        SmartModuleKind::Window => self::window::generate_window_smartmodule(func),
         SmartModuleKind::Init => self::init::generate_init_smartmodule(func),
         SmartModuleKind::LookBack => self::look_back::generate_look_back_smartmodule(func),
     }
@@ -44,7 +48,9 @@ pub fn generate_records_code(sm_func: &SmartModuleFn, kind: &SmartModuleKind) ->
         | SmartModuleKind::FilterMap
         | SmartModuleKind::Map
         | SmartModuleKind::Filter
-        | SmartModuleKind::Aggregate => quote! {
+        | SmartModuleKind::Aggregate
+        | SmartModuleKind::Join
+        | SmartModuleKind::Window => quote! {
             use fluvio_smartmodule::dataplane::smartmodule::SmartModuleTransformErrorStatus;
 
             return SmartModuleTransformErrorStatus::DecodingBaseInput as i32;
@@ -28,6 +28,8 @@ pub enum SmartModuleTransformErrorStatus {
     ParsingExtraParams = -44,
     #[error("undefined right record in Join SmartModule")]
     UndefinedRightRecord = -55,
+    #[error("undefined window accumulator in Window SmartModule")]
+    UndefinedWindow = -66,
 }
 
 #[repr(i32)]
@@ -3,7 +3,7 @@ use std::time::Duration;
 use std::{collections::BTreeMap, fmt::Display};
 use std::fmt;
 use fluvio_protocol::{Decoder, Encoder, Version};
-use fluvio_protocol::record::{Offset, Record};
+use fluvio_protocol::record::{Offset, Record, RecordData};
 use fluvio_protocol::types::Timestamp;
 
 use crate::SmartModuleRecord;
@@ -13,6 +13,10 @@ use crate::SmartModuleRecord;
 /// This version is used for encoding and decoding [`SmartModuleInput`]
 pub const SMARTMODULE_TIMESTAMPS_VERSION: Version = 22;
 
+/// SmartModule Version with support for record headers.
+/// Headers are removed from records passed to SmartModules of older versions.
+pub const SMARTMODULE_HEADERS_VERSION: Version = 23;
+
 #[derive(Debug, Default, Clone, Encoder, Decoder)]
 pub struct SmartModuleExtraParams {
     inner: BTreeMap<String, String>,
@@ -77,6 +81,68 @@ impl Lookback {
     }
 }
 
+/// What happens to a record SmartModule failed to process
+#[derive(Debug, Clone, PartialEq, Eq, Encoder, Decoder, Default)]
+pub enum SmartModuleErrorPolicy {
+    /// stop processing and return the error
+    #[default]
+    #[fluvio(tag = 0)]
+    Fail,
+    /// drop the record and continue with the next one
+    #[fluvio(tag = 1)]
+    Skip,
+    /// write the record and the error to the topic, then continue with the next record
+    #[fluvio(tag = 2)]
+    DeadLetter(String),
+}
+
+impl Display for SmartModuleErrorPolicy {
+    fn fmt(&self, out: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Fail => out.write_str("fail"),
+            Self::Skip => out.write_str("skip"),
+            Self::DeadLetter(topic) => write!(out, "dead-letter:{topic}"),
+        }
+    }
+}
+
+impl std::str::FromStr for SmartModuleErrorPolicy {
+    type Err = String;
+
+    /// parse `fail`, `skip` or `dead-letter:<topic>`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "fail" => Ok(Self::Fail),
+            "skip" => Ok(Self::Skip),
+            _ => match value.strip_prefix("dead-letter:") {
+                Some(topic) if !topic.is_empty() => Ok(Self::DeadLetter(topic.to_owned())),
+                _ => Err(format!(
+                    "invalid error policy `{value}`, expected `fail`, `skip` or `dead-letter:<topic>`"
+                )),
+            },
+        }
+    }
+}
+
+/// Records traced when SmartModule chain runs in debug mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encoder, Decoder)]
+pub struct SmartModuleDebugConfig {
+    /// trace records with offset divisible by this number, 1 traces every record
+    pub sample_every: u32,
+}
+
+impl Default for SmartModuleDebugConfig {
+    fn default() -> Self {
+        Self { sample_every: 1 }
+    }
+}
+
+impl SmartModuleDebugConfig {
+    pub fn is_sampled(&self, offset: Offset) -> bool {
+        offset % i64::from(self.sample_every.max(1)) == 0
+    }
+}
+
 /// A single SmartModule input record
 #[derive(Debug, Default, Clone, Encoder, Decoder)]
 pub struct SmartModuleInput {
@@ -167,14 +233,34 @@ impl SmartModuleInput {
         Ok(records)
     }
 
+    /// Removes headers from the encoded records if SmartModule `version` doesn't support them
+    pub fn clear_record_headers(&mut self, version: Version) -> Result<(), std::io::Error> {
+        if version >= SMARTMODULE_HEADERS_VERSION {
+            return Ok(());
+        }
+        let mut records: Vec<Record> =
+            Decoder::decode_from(&mut Cursor::new(&self.raw_bytes), version)?;
+        if records.iter().all(|record| record.headers.is_empty()) {
+            return Ok(());
+        }
+        records.iter_mut().for_each(|record| record.headers.clear());
+        self.raw_bytes.clear();
+        records.encode(&mut self.raw_bytes, version)?;
+        Ok(())
+    }
+
     /// Attempts to map the [`Record`] vector and build a `SmartModuleInput`
     /// instance from it.
+    /// Record headers are only kept if `version` supports them.
     pub fn try_from_records(
-        records: Vec<Record>,
+        mut records: Vec<Record>,
         version: Version,
     ) -> Result<Self, std::io::Error> {
         let mut raw_bytes = Vec::new();
 
+        if version < SMARTMODULE_HEADERS_VERSION {
+            records.iter_mut().for_each(|record| record.headers.clear());
+        }
         records.encode(&mut raw_bytes, version)?;
 
         Ok(SmartModuleInput {
@@ -206,6 +292,29 @@ pub struct SmartModuleAggregateInput {
     pub accumulator: Vec<u8>,
 }
 
+/// A type to pass input to a Join SmartModule WASM module
+#[derive(Debug, Default, Clone, Encoder, Decoder)]
+pub struct SmartModuleJoinInput {
+    /// The base input required by all SmartModules
+    pub base: SmartModuleInput,
+    /// Latest value of the joined topic for the key of each input record, in the same order
+    pub right_values: Vec<Option<RecordData>>,
+}
+
+/// A type to pass input to a Window SmartModule WASM module.
+///
+/// Each record is folded into accumulator of its window and key.
+/// Record belonging to several hopping windows is repeated for each of them.
+#[derive(Debug, Default, Clone, Encoder, Decoder)]
+pub struct SmartModuleWindowInput {
+    /// The base input required by all SmartModules
+    pub base: SmartModuleInput,
+    /// Index into `accumulators` for each input record, in the same order
+    pub groups: Vec<u32>,
+    /// Current accumulators of windows touched by input records
+    pub accumulators: Vec<Vec<u8>>,
+}
+
 /// Input to SmartModule Init
 #[derive(Debug, Default, Clone, Encoder, Decoder)]
 pub struct SmartModuleInitInput {
@@ -216,6 +325,35 @@ pub struct SmartModuleInitInput {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_error_policy_from_str() {
+        assert_eq!("fail".parse(), Ok(SmartModuleErrorPolicy::Fail));
+        assert_eq!("skip".parse(), Ok(SmartModuleErrorPolicy::Skip));
+        let policy: SmartModuleErrorPolicy = "dead-letter:failed".parse().expect("policy");
+        assert_eq!(
+            policy,
+            SmartModuleErrorPolicy::DeadLetter("failed".to_string())
+        );
+        assert_eq!(policy.to_string(), "dead-letter:failed");
+        assert!("dead-letter:".parse::<SmartModuleErrorPolicy>().is_err());
+        assert!("retry".parse::<SmartModuleErrorPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_debug_config_sampling() {
+        let all = SmartModuleDebugConfig::default();
+        assert!(all.is_sampled(0));
+        assert!(all.is_sampled(7));
+
+        let every_third = SmartModuleDebugConfig { sample_every: 3 };
+        assert!(every_third.is_sampled(0));
+        assert!(!every_third.is_sampled(4));
+        assert!(every_third.is_sampled(6));
+
+        // zero is treated as 1, tracing every record
+        assert!(SmartModuleDebugConfig { sample_every: 0 }.is_sampled(5));
+    }
+
     #[test]
     fn test_record_to_sm_input_and_back() {
         //given
@@ -242,6 +380,38 @@ mod tests {
         assert_eq!(records_decoded[2].value.as_ref(), b"banana");
     }
 
+    #[test]
+    fn test_record_headers_removed_for_older_versions() {
+        let mut record = Record::new("apple");
+        record.add_header(("color", "red"));
+
+        let sm_input =
+            SmartModuleInput::try_from_records(vec![record.clone()], SMARTMODULE_HEADERS_VERSION)
+                .expect("records to input conversion failed");
+        let mut older = sm_input.clone();
+        let records = sm_input
+            .try_into_smartmodule_records(SMARTMODULE_HEADERS_VERSION)
+            .expect("input to records conversion failed");
+        assert_eq!(records[0].headers().len(), 1);
+
+        older
+            .clear_record_headers(SMARTMODULE_TIMESTAMPS_VERSION)
+            .expect("clear headers");
+        let records = older
+            .try_into_smartmodule_records(SMARTMODULE_TIMESTAMPS_VERSION)
+            .expect("input to records conversion failed");
+        assert!(records[0].headers().is_empty());
+
+        let sm_input =
+            SmartModuleInput::try_from_records(vec![record], SMARTMODULE_TIMESTAMPS_VERSION)
+                .expect("records to input conversion failed");
+        let records = sm_input
+            .try_into_smartmodule_records(SMARTMODULE_TIMESTAMPS_VERSION)
+            .expect("input to records conversion failed");
+        assert!(records[0].headers().is_empty());
+        assert_eq!(records[0].value().as_ref(), b"apple");
+    }
+
     #[test]
     fn sets_the_provided_value_as_timestamp() {
         let mut sm_input = SmartModuleInput::new(vec![0, 1, 2, 3], 0, 0);
@@ -254,4 +424,40 @@ mod tests {
         assert_eq!(sm_input.base_timestamp, 1234);
         assert_eq!(sm_input.base_timestamp(), 1234);
     }
+
+    #[test]
+    fn test_join_input_encode_and_decode() {
+        use fluvio_protocol::{Encoder, Decoder};
+
+        //given
+        #[allow(deprecated)]
+        let base = SmartModuleInput::try_from_records(
+            vec![Record::new_key_value("apple", "fruit")],
+            SMARTMODULE_TIMESTAMPS_VERSION,
+        )
+        .expect("records to input conversion failed");
+        let join_input = SmartModuleJoinInput {
+            base,
+            right_values: vec![Some(RecordData::from("red")), None],
+        };
+
+        //when
+        let mut bytes = vec![];
+        join_input
+            .encode(&mut bytes, SMARTMODULE_TIMESTAMPS_VERSION)
+            .expect("encode");
+        let decoded = SmartModuleJoinInput::decode_from(
+            &mut std::io::Cursor::new(bytes),
+            SMARTMODULE_TIMESTAMPS_VERSION,
+        )
+        .expect("decode");
+
+        //then
+        assert_eq!(decoded.right_values.len(), 2);
+        assert_eq!(
+            decoded.right_values[0].as_ref().map(|v| v.as_ref()),
+            Some(b"red".as_ref())
+        );
+        assert!(decoded.right_values[1].is_none());
+    }
 }
@@ -3,6 +3,9 @@
 mod input;
 mod output;
 mod error;
+mod log;
+
+pub mod state;
 
 use std::ops::{Deref, DerefMut};
 
@@ -10,6 +13,8 @@ use fluvio_protocol::types::Timestamp;
 
 pub use fluvio_smartmodule_derive::{smartmodule, SmartOpt};
 
+pub use crate::log::log;
+
 pub const ENCODING_ERROR: i32 = -1;
 
 pub use eyre::Error;
@@ -21,9 +26,9 @@ pub type Result<T> = eyre::Result<T>;
 #[cfg(feature = "smartmodule")]
 pub mod memory;
 
-pub use fluvio_protocol::record::{Offset, Record, RecordData};
+pub use fluvio_protocol::record::{Header, Offset, Record, RecordData};
 
-pub use crate::input::SMARTMODULE_TIMESTAMPS_VERSION;
+pub use crate::input::{SMARTMODULE_TIMESTAMPS_VERSION, SMARTMODULE_HEADERS_VERSION};
 
 /// remap to old data plane
 pub mod dataplane {
@@ -81,6 +86,15 @@ impl SmartModuleRecord {
     pub fn value(&self) -> &RecordData {
         self.inner_record.value()
     }
+
+    pub fn headers(&self) -> &[Header] {
+        self.inner_record.headers()
+    }
+
+    /// value of the first header with the given key
+    pub fn header(&self, key: &str) -> Option<&RecordData> {
+        self.inner_record.header(key)
+    }
 }
 
 impl Deref for SmartModuleRecord {
@@ -0,0 +1,28 @@
+//! Messages logged by SmartModule thru the host.
+//!
+//! Host collects messages only when the chain runs in debug mode, otherwise they are dropped.
+//! Outside of WASM, messages are printed to stderr, so they show up in native unit tests.
+
+/// log message, it is attributed to this SmartModule in the trace of the chain
+pub fn log(message: impl AsRef<str>) {
+    imp::log(message.as_ref())
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    extern "C" {
+        #[link_name = "log"]
+        fn host_log(ptr: i32, len: i32);
+    }
+
+    pub(super) fn log(message: &str) {
+        unsafe { host_log(message.as_ptr() as i32, message.len() as i32) }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    pub(super) fn log(message: &str) {
+        eprintln!("{message}");
+    }
+}
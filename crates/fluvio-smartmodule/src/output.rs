@@ -1,6 +1,6 @@
 use fluvio_protocol::{
     Encoder, Decoder,
-    record::Record,
+    record::{Offset, Record},
     link::smartmodule::{
         SmartModuleTransformRuntimeError, SmartModuleInitRuntimeError,
         SmartModuleLookbackRuntimeError,
@@ -47,6 +47,15 @@ impl SmartModuleAggregateOutput {
     }
 }
 
+/// A type used to return updated accumulators and/or an error from a Window SmartModule
+#[derive(Debug, Default, Encoder, Decoder)]
+pub struct SmartModuleWindowOutput {
+    /// Accumulators in the same order as in the input
+    pub accumulators: Vec<Vec<u8>>,
+    /// Any runtime error if one was encountered
+    pub error: Option<SmartModuleTransformRuntimeError>,
+}
+
 /// A type used to return processed records and/or an error from a SmartModule
 #[derive(Debug, Default, Encoder, Decoder)]
 pub struct SmartModuleInitOutput {
@@ -60,3 +69,42 @@ pub struct SmartModuleLookbackOutput {
     /// Any runtime error if one was encountered
     pub error: SmartModuleLookbackRuntimeError,
 }
+
+/// Record as processed by a single SmartModule of the chain running in debug mode.
+/// Output records are matched to the input by offset.
+#[derive(Debug, Default, Clone, Encoder, Decoder)]
+pub struct SmartModuleStepTrace {
+    /// position of SmartModule in the chain
+    pub step: u32,
+    /// kind of SmartModule, e.g. `filter`
+    pub smartmodule: String,
+    /// offset of the traced record
+    pub offset: Offset,
+    /// record passed to SmartModule
+    pub input: Record,
+    /// records returned by SmartModule, empty if it dropped the record
+    pub output: Vec<Record>,
+    /// hint of the error if SmartModule failed to process the record
+    pub error: Option<String>,
+}
+
+/// Message logged by SmartModule of the chain running in debug mode
+#[derive(Debug, Default, Clone, Encoder, Decoder)]
+pub struct SmartModuleLog {
+    /// position of SmartModule in the chain
+    pub step: u32,
+    pub message: String,
+}
+
+/// Traces collected by SmartModule chain running in debug mode
+#[derive(Debug, Default, Clone, Encoder, Decoder)]
+pub struct SmartModuleChainTrace {
+    pub records: Vec<SmartModuleStepTrace>,
+    pub logs: Vec<SmartModuleLog>,
+}
+
+impl SmartModuleChainTrace {
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty() && self.logs.is_empty()
+    }
+}
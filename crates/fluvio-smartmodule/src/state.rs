@@ -0,0 +1,178 @@
+//! Key-value state kept by the host for each SmartModule and partition.
+//!
+//! State survives restarts of the consumer and of the SPU when SmartModule is referenced by name.
+//! Outside of WASM, state is kept in memory of the current thread, so SmartModule
+//! logic can be unit tested natively.
+
+use fluvio_protocol::{Encoder, Decoder};
+
+use crate::Result;
+
+/// Version used to encode state entries passed between host and SmartModule
+pub const STATE_ENCODING_VERSION: i16 = 0;
+
+/// `state_put` status when the state would exceed the size limit of the host
+pub const STATE_SIZE_LIMIT_EXCEEDED: i32 = -1;
+
+/// `state_get` status when the key is not present
+pub const STATE_KEY_NOT_FOUND: i32 = -1;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encoder, Decoder)]
+pub struct StateEntry {
+    pub key: Vec<u8>,
+    pub value: Vec<u8>,
+}
+
+/// value stored under the key
+pub fn get(key: impl AsRef<[u8]>) -> Option<Vec<u8>> {
+    imp::get(key.as_ref())
+}
+
+/// store value under the key, fails if the state would exceed the size limit of the host
+pub fn put(key: impl AsRef<[u8]>, value: impl AsRef<[u8]>) -> Result<()> {
+    imp::put(key.as_ref(), value.as_ref())
+}
+
+/// remove the key, does nothing if the key is not present
+pub fn delete(key: impl AsRef<[u8]>) {
+    imp::delete(key.as_ref())
+}
+
+/// all entries with keys starting with prefix, ordered by key
+pub fn scan(prefix: impl AsRef<[u8]>) -> Result<Vec<StateEntry>> {
+    imp::scan(prefix.as_ref())
+}
+
+#[cfg(target_arch = "wasm32")]
+mod imp {
+    use fluvio_protocol::Decoder;
+
+    use crate::{Result, eyre};
+
+    use super::{StateEntry, STATE_ENCODING_VERSION, STATE_SIZE_LIMIT_EXCEEDED};
+
+    extern "C" {
+        fn state_get(key_ptr: i32, key_len: i32) -> i32;
+        fn state_put(key_ptr: i32, key_len: i32, value_ptr: i32, value_len: i32) -> i32;
+        fn state_delete(key_ptr: i32, key_len: i32);
+        fn state_scan(prefix_ptr: i32, prefix_len: i32) -> i32;
+        fn state_read(ptr: i32, len: i32) -> i32;
+    }
+
+    /// copy result of the last host call into SmartModule memory
+    fn read_result(len: i32) -> Vec<u8> {
+        let mut buf = vec![0u8; len as usize];
+        unsafe { state_read(buf.as_mut_ptr() as i32, len) };
+        buf
+    }
+
+    pub(super) fn get(key: &[u8]) -> Option<Vec<u8>> {
+        let len = unsafe { state_get(key.as_ptr() as i32, key.len() as i32) };
+        if len < 0 {
+            None
+        } else {
+            Some(read_result(len))
+        }
+    }
+
+    pub(super) fn put(key: &[u8], value: &[u8]) -> Result<()> {
+        let status = unsafe {
+            state_put(
+                key.as_ptr() as i32,
+                key.len() as i32,
+                value.as_ptr() as i32,
+                value.len() as i32,
+            )
+        };
+        if status == STATE_SIZE_LIMIT_EXCEEDED {
+            Err(eyre!("SmartModule state size limit exceeded"))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(super) fn delete(key: &[u8]) {
+        unsafe { state_delete(key.as_ptr() as i32, key.len() as i32) }
+    }
+
+    pub(super) fn scan(prefix: &[u8]) -> Result<Vec<StateEntry>> {
+        let len = unsafe { state_scan(prefix.as_ptr() as i32, prefix.len() as i32) };
+        let bytes = read_result(len);
+        let entries = Vec::decode_from(&mut bytes.as_slice(), STATE_ENCODING_VERSION)?;
+        Ok(entries)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod imp {
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    use crate::Result;
+
+    use super::StateEntry;
+
+    thread_local! {
+        static STATE: RefCell<BTreeMap<Vec<u8>, Vec<u8>>> = const { RefCell::new(BTreeMap::new()) };
+    }
+
+    pub(super) fn get(key: &[u8]) -> Option<Vec<u8>> {
+        STATE.with(|state| state.borrow().get(key).cloned())
+    }
+
+    pub(super) fn put(key: &[u8], value: &[u8]) -> Result<()> {
+        STATE.with(|state| state.borrow_mut().insert(key.to_vec(), value.to_vec()));
+        Ok(())
+    }
+
+    pub(super) fn delete(key: &[u8]) {
+        STATE.with(|state| state.borrow_mut().remove(key));
+    }
+
+    pub(super) fn scan(prefix: &[u8]) -> Result<Vec<StateEntry>> {
+        Ok(STATE.with(|state| {
+            state
+                .borrow()
+                .range(prefix.to_vec()..)
+                .take_while(|(key, _)| key.starts_with(prefix))
+                .map(|(key, value)| StateEntry {
+                    key: key.clone(),
+                    value: value.clone(),
+                })
+                .collect()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::{get, put, delete, scan, StateEntry};
+
+    #[test]
+    fn test_native_state() {
+        put("user/1", "a").expect("put");
+        put("user/2", "b").expect("put");
+        put("visit/1", "c").expect("put");
+
+        assert_eq!(get("user/1"), Some(b"a".to_vec()));
+        assert_eq!(get("user/3"), None);
+        assert_eq!(
+            scan("user/").expect("scan"),
+            vec![
+                StateEntry {
+                    key: b"user/1".to_vec(),
+                    value: b"a".to_vec()
+                },
+                StateEntry {
+                    key: b"user/2".to_vec(),
+                    value: b"b".to_vec()
+                }
+            ]
+        );
+
+        delete("user/1");
+        assert_eq!(get("user/1"), None);
+        assert_eq!(scan("user/").expect("scan").len(), 1);
+    }
+}
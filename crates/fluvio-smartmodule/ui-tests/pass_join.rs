@@ -0,0 +1,11 @@
+use fluvio_smartmodule::{smartmodule, SmartModuleRecord, RecordData, Result};
+
+#[smartmodule(join)]
+pub fn my_join(
+    _record: &SmartModuleRecord,
+    _right: Option<&RecordData>,
+) -> Result<(Option<RecordData>, RecordData)> {
+    unimplemented!()
+}
+
+fn main() {}
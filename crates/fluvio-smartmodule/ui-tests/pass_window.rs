@@ -0,0 +1,8 @@
+use fluvio_smartmodule::{smartmodule, SmartModuleRecord, RecordData, Result};
+
+#[smartmodule(window)]
+pub fn my_window(_accumulator: RecordData, _record: &SmartModuleRecord) -> Result<RecordData> {
+    unimplemented!()
+}
+
+fn main() {}
@@ -1,3 +1,4 @@
+use std::collections::{HashSet, VecDeque};
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
@@ -7,6 +8,7 @@ use fluvio_protocol::derive::FluvioDefault;
 use fluvio_protocol::record::RecordSet;
 use fluvio_protocol::link::ErrorCode;
 use fluvio_protocol::record::Offset;
+use fluvio_protocol::record::Batch;
 
 pub type DefaultFetchResponse = FetchResponse<RecordSet>;
 
@@ -91,12 +93,50 @@ impl<R: BatchRecords> FetchablePartitionResponse<RecordSet<R>> {
     }
 }
 
-#[derive(Encoder, Decoder, FluvioDefault, Debug)]
+#[derive(Encoder, Decoder, FluvioDefault, Debug, Clone, PartialEq, Eq)]
 pub struct AbortedTransaction {
     pub producer_id: i64,
     pub first_offset: i64,
 }
 
+/// Hides batches of aborted transactions and control batches from `ReadCommitted` consumers.
+/// Batches must be passed in offset order. Batches of a producer are aborted from
+/// the first offset of its aborted transaction until its control batch.
+#[derive(Debug, Default)]
+pub struct AbortedBatchFilter {
+    /// aborted transactions not reached yet, ordered by first offset
+    pending: VecDeque<AbortedTransaction>,
+    /// producers with aborted transaction in progress
+    active: HashSet<i64>,
+}
+
+impl AbortedBatchFilter {
+    pub fn new(mut aborted: Vec<AbortedTransaction>) -> Self {
+        aborted.sort_by_key(|txn| txn.first_offset);
+        Self {
+            pending: aborted.into(),
+            active: HashSet::new(),
+        }
+    }
+
+    /// true if batch is not visible to consumer
+    pub fn skip<R>(&mut self, batch: &Batch<R>) -> bool {
+        while let Some(txn) = self.pending.front() {
+            if txn.first_offset > batch.base_offset {
+                break;
+            }
+            self.active.insert(txn.producer_id);
+            self.pending.pop_front();
+        }
+        let header = batch.get_header();
+        if batch.is_control() {
+            self.active.remove(&header.producer_id);
+            return true;
+        }
+        header.is_transactional() && self.active.contains(&header.producer_id)
+    }
+}
+
 // -----------------------------------
 // Implementation
 // -----------------------------------
@@ -191,3 +231,48 @@ mod file {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use fluvio_protocol::record::{ControlRecordType, Record};
+
+    use super::*;
+
+    fn batch(base_offset: Offset, producer_id: i64, transactional: bool) -> Batch {
+        let mut batch = Batch::default();
+        batch.add_record(Record::new("value"));
+        batch.base_offset = base_offset;
+        let header = batch.get_mut_header();
+        header.producer_id = producer_id;
+        if transactional {
+            header.set_transactional();
+        }
+        batch
+    }
+
+    fn control(base_offset: Offset, producer_id: i64, control_type: ControlRecordType) -> Batch {
+        let mut batch = Batch::control(producer_id, 0, control_type);
+        batch.base_offset = base_offset;
+        batch
+    }
+
+    #[test]
+    fn test_aborted_batch_filter() {
+        let mut filter = AbortedBatchFilter::new(vec![AbortedTransaction {
+            producer_id: 1,
+            first_offset: 1,
+        }]);
+
+        // batch before aborted transaction
+        assert!(!filter.skip(&batch(0, 1, true)));
+        // aborted transaction interleaved with committed one
+        assert!(filter.skip(&batch(1, 1, true)));
+        assert!(!filter.skip(&batch(2, 2, true)));
+        assert!(!filter.skip(&batch(3, -1, false)));
+        assert!(filter.skip(&control(4, 1, ControlRecordType::Abort)));
+        assert!(filter.skip(&control(5, 2, ControlRecordType::Commit)));
+        // next transaction of the producer is visible
+        assert!(!filter.skip(&batch(6, 1, true)));
+    }
+}
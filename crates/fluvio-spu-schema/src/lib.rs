@@ -17,4 +17,8 @@ pub use fluvio_protocol::link::versions::{ApiVersions, ApiVersionsRequest, ApiVe
 pub use isolation::*;
 
 /// Default API version for all API
-pub const COMMON_VERSION: i16 = 22;
+pub const COMMON_VERSION: i16 = 29;
+
+/// API version from which records may carry headers.
+/// Older peers don't understand header entries in the record format.
+pub const RECORD_HEADERS_API_VERSION: i16 = 23;
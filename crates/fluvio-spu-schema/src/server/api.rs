@@ -22,6 +22,9 @@ use super::SpuServerApiKey;
 use super::fetch_offset::FetchOffsetsRequest;
 use super::stream_fetch::FileStreamFetchRequest;
 use super::update_offset::UpdateOffsetsRequest;
+use super::consumer_offset::{
+    CommitConsumerOffsetRequest, FetchConsumerOffsetRequest, ResetConsumerOffsetRequest,
+};
 
 #[allow(clippy::large_enum_variant)]
 /// Request to Spu Server
@@ -42,6 +45,12 @@ pub enum SpuServerRequest {
     FileStreamFetchRequest(RequestMessage<FileStreamFetchRequest>),
     #[fluvio(tag = 5)]
     UpdateOffsetsRequest(RequestMessage<UpdateOffsetsRequest>),
+    #[fluvio(tag = 6)]
+    CommitConsumerOffsetRequest(RequestMessage<CommitConsumerOffsetRequest>),
+    #[fluvio(tag = 7)]
+    FetchConsumerOffsetRequest(RequestMessage<FetchConsumerOffsetRequest>),
+    #[fluvio(tag = 8)]
+    ResetConsumerOffsetRequest(RequestMessage<ResetConsumerOffsetRequest>),
 }
 
 impl fmt::Display for SpuServerRequest {
@@ -53,6 +62,9 @@ impl fmt::Display for SpuServerRequest {
             Self::FetchOffsetsRequest(_) => write!(f, "FetchOffsetsRequest"),
             Self::FileStreamFetchRequest(_) => write!(f, "FileStreamFetchRequest"),
             Self::UpdateOffsetsRequest(_) => write!(f, "UpdateOffsetsRequest"),
+            Self::CommitConsumerOffsetRequest(_) => write!(f, "CommitConsumerOffsetRequest"),
+            Self::FetchConsumerOffsetRequest(_) => write!(f, "FetchConsumerOffsetRequest"),
+            Self::ResetConsumerOffsetRequest(_) => write!(f, "ResetConsumerOffsetRequest"),
         }
     }
 }
@@ -85,6 +97,15 @@ impl ApiMessage for SpuServerRequest {
             SpuServerApiKey::FetchOffsets => api_decode!(Self, FetchOffsetsRequest, src, header),
             SpuServerApiKey::StreamFetch => api_decode!(Self, FileStreamFetchRequest, src, header),
             SpuServerApiKey::UpdateOffsets => api_decode!(Self, UpdateOffsetsRequest, src, header),
+            SpuServerApiKey::CommitConsumerOffset => {
+                api_decode!(Self, CommitConsumerOffsetRequest, src, header)
+            }
+            SpuServerApiKey::FetchConsumerOffset => {
+                api_decode!(Self, FetchConsumerOffsetRequest, src, header)
+            }
+            SpuServerApiKey::ResetConsumerOffset => {
+                api_decode!(Self, ResetConsumerOffsetRequest, src, header)
+            }
         }
     }
 }
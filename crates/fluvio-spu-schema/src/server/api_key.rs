@@ -20,6 +20,9 @@ pub enum SpuServerApiKey {
     FetchOffsets = 1002,
     StreamFetch = 1003,
     UpdateOffsets = 1005,
+    CommitConsumerOffset = 1006,
+    FetchConsumerOffset = 1007,
+    ResetConsumerOffset = 1008,
 }
 
 impl Default for SpuServerApiKey {
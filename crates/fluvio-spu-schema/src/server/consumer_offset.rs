@@ -0,0 +1,123 @@
+//!
+//! # Consumer Offsets
+//!
+//! APIs to commit, fetch and reset offsets committed by consumer groups.
+//! Offsets are stored by the leader of the replica.
+//!
+
+use fluvio_protocol::api::Request;
+use fluvio_protocol::{Encoder, Decoder};
+use fluvio_protocol::record::{Offset, ReplicaKey};
+
+use crate::errors::ErrorCode;
+use super::SpuServerApiKey;
+
+/// Commit offset of consumer group for a replica.
+/// Offset is the next offset to be consumed by the group.
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct CommitConsumerOffsetRequest {
+    pub consumer_group: String,
+    pub replica: ReplicaKey,
+    pub offset: Offset,
+}
+
+impl Request for CommitConsumerOffsetRequest {
+    const API_KEY: u16 = SpuServerApiKey::CommitConsumerOffset as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = CommitConsumerOffsetResponse;
+}
+
+impl CommitConsumerOffsetRequest {
+    pub fn new(consumer_group: impl Into<String>, replica: ReplicaKey, offset: Offset) -> Self {
+        Self {
+            consumer_group: consumer_group.into(),
+            replica,
+            offset,
+        }
+    }
+}
+
+#[derive(Encoder, Decoder, Default, Debug)]
+pub struct CommitConsumerOffsetResponse {
+    pub error_code: ErrorCode,
+}
+
+/// Fetch offset committed by consumer group for a replica
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct FetchConsumerOffsetRequest {
+    pub consumer_group: String,
+    pub replica: ReplicaKey,
+}
+
+impl Request for FetchConsumerOffsetRequest {
+    const API_KEY: u16 = SpuServerApiKey::FetchConsumerOffset as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = FetchConsumerOffsetResponse;
+}
+
+impl FetchConsumerOffsetRequest {
+    pub fn new(consumer_group: impl Into<String>, replica: ReplicaKey) -> Self {
+        Self {
+            consumer_group: consumer_group.into(),
+            replica,
+        }
+    }
+}
+
+#[derive(Encoder, Decoder, Default, Debug)]
+pub struct FetchConsumerOffsetResponse {
+    pub error_code: ErrorCode,
+    /// committed offset, None if consumer group has not committed any offset
+    pub offset: Option<Offset>,
+}
+
+/// Remove offset committed by consumer group for a replica.
+/// Consumers of the group start from their own starting offset afterwards.
+#[derive(Decoder, Encoder, Default, Debug)]
+pub struct ResetConsumerOffsetRequest {
+    pub consumer_group: String,
+    pub replica: ReplicaKey,
+}
+
+impl Request for ResetConsumerOffsetRequest {
+    const API_KEY: u16 = SpuServerApiKey::ResetConsumerOffset as u16;
+    const DEFAULT_API_VERSION: i16 = 0;
+    type Response = ResetConsumerOffsetResponse;
+}
+
+impl ResetConsumerOffsetRequest {
+    pub fn new(consumer_group: impl Into<String>, replica: ReplicaKey) -> Self {
+        Self {
+            consumer_group: consumer_group.into(),
+            replica,
+        }
+    }
+}
+
+#[derive(Encoder, Decoder, Default, Debug)]
+pub struct ResetConsumerOffsetResponse {
+    pub error_code: ErrorCode,
+}
+
+#[cfg(test)]
+mod tests {
+
+    use fluvio_protocol::{Encoder, Decoder};
+
+    use super::*;
+
+    #[test]
+    fn test_encode_decode_fetch_consumer_offset_response() {
+        let response = FetchConsumerOffsetResponse {
+            error_code: ErrorCode::None,
+            offset: Some(42),
+        };
+        let mut bytes = Vec::new();
+        response.encode(&mut bytes, 0).expect("encode");
+
+        let decoded =
+            FetchConsumerOffsetResponse::decode_from(&mut bytes.as_slice(), 0).expect("decode");
+        assert_eq!(decoded.error_code, ErrorCode::None);
+        assert_eq!(decoded.offset, Some(42));
+    }
+}
@@ -2,19 +2,24 @@
 //! # Fetch Topic Offsets
 //!
 //! API that allows CLI to fetch topic offsets.
+//! Offset of records produced at or after a timestamp can be resolved as well.
 use std::fmt;
 
 use fluvio_protocol::api::Request;
 use fluvio_protocol::{Encoder, Decoder};
 use fluvio_protocol::record::PartitionOffset;
 use fluvio_protocol::record::ReplicaKey;
+use fluvio_protocol::record::Offset;
 
-use fluvio_types::PartitionId;
+use fluvio_types::{PartitionId, Timestamp};
 
 use crate::COMMON_VERSION;
 use crate::errors::ErrorCode;
 use super::SpuServerApiKey;
 
+/// API version from which offset can be resolved by timestamp
+pub const FETCH_OFFSET_TIMESTAMP_API_VERSION: i16 = 24;
+
 // -----------------------------------
 // FlvFetchOffsetsRequest
 // -----------------------------------
@@ -40,6 +45,21 @@ impl FetchOffsetsRequest {
                 name: topic,
                 partitions: vec![FetchOffsetPartition {
                     partition_index: partition,
+                    timestamp: None,
+                }],
+            }],
+        }
+    }
+
+    /// create request with a single topic and partition,
+    /// resolving first offset of records with timestamp at or after `timestamp`
+    pub fn new_with_timestamp(topic: String, partition: u32, timestamp: Timestamp) -> Self {
+        Self {
+            topics: vec![FetchOffsetTopic {
+                name: topic,
+                partitions: vec![FetchOffsetPartition {
+                    partition_index: partition,
+                    timestamp: Some(timestamp),
                 }],
             }],
         }
@@ -59,6 +79,10 @@ pub struct FetchOffsetTopic {
 pub struct FetchOffsetPartition {
     /// The partition index.
     pub partition_index: PartitionId,
+
+    /// Timestamp in milliseconds to resolve offset for
+    #[fluvio(min_version = 24)]
+    pub timestamp: Option<Timestamp>,
 }
 
 // -----------------------------------
@@ -109,6 +133,11 @@ pub struct FetchOffsetPartitionResponse {
 
     /// Last readable offset
     pub last_stable_offset: i64,
+
+    /// First offset of records with timestamp at or after requested timestamp.
+    /// Log end offset if all records are older.
+    #[fluvio(min_version = 24)]
+    pub timestamp_offset: Option<Offset>,
 }
 
 impl fmt::Display for FetchOffsetPartitionResponse {
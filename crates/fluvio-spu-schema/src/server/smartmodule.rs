@@ -3,6 +3,7 @@
 use std::io::Read;
 use std::io;
 use std::fmt::{Debug, self};
+use std::time::Duration;
 
 use flate2::{
     Compression,
@@ -10,7 +11,7 @@ use flate2::{
 };
 
 use fluvio_protocol::{Encoder, Decoder};
-use fluvio_smartmodule::dataplane::smartmodule::SmartModuleExtraParams;
+use fluvio_smartmodule::dataplane::smartmodule::{SmartModuleErrorPolicy, SmartModuleExtraParams};
 
 /// The request payload when using a Consumer SmartModule.
 ///
@@ -36,6 +37,18 @@ pub struct SmartModuleInvocation {
     pub wasm: SmartModuleInvocationWasm,
     pub kind: SmartModuleKind,
     pub params: SmartModuleExtraParams,
+    #[fluvio(min_version = 26)]
+    pub error_policy: SmartModuleErrorPolicy,
+    #[fluvio(min_version = 29)]
+    pub budget: SmartModuleBudget,
+}
+
+/// Fuel and time allowed for each call of the SmartModule.
+/// Unset limits fall back to limits of the SPU, which also caps the requested ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encoder, Decoder)]
+pub struct SmartModuleBudget {
+    pub fuel_limit: Option<u64>,
+    pub call_timeout: Option<Duration>,
 }
 
 #[derive(Clone, Encoder, Decoder)]
@@ -143,6 +156,13 @@ pub enum SmartModuleContextData {
         topic: String,
         derivedstream: String,
     },
+    /// time windows of Window SmartModule, in milliseconds
+    #[fluvio(tag = 4)]
+    Window {
+        size_ms: u64,
+        slide_ms: u64,
+        allowed_lateness_ms: u64,
+    },
 }
 
 /// Different possible representations of WASM modules.
@@ -189,6 +209,8 @@ fn unzip(compressed: &[u8]) -> io::Result<Vec<u8>> {
 #[cfg(test)]
 mod tests {
 
+    use crate::server::stream_fetch::{SMARTMODULE_BUDGET_API, SMARTMODULE_ERROR_POLICY_API};
+
     use super::*;
 
     #[test]
@@ -210,6 +232,72 @@ mod tests {
         assert!(matches!(value, SmartModuleKind::Map));
     }
 
+    #[test]
+    fn test_encode_decode_error_policy() {
+        let value = SmartModuleInvocation {
+            error_policy: SmartModuleErrorPolicy::DeadLetter("dlq".to_string()),
+            ..Default::default()
+        };
+
+        let mut dest = Vec::new();
+        value
+            .encode(&mut dest, SMARTMODULE_ERROR_POLICY_API)
+            .expect("should encode");
+        let decoded = SmartModuleInvocation::decode_from(
+            &mut io::Cursor::new(&dest),
+            SMARTMODULE_ERROR_POLICY_API,
+        )
+        .expect("decode");
+        assert_eq!(
+            decoded.error_policy,
+            SmartModuleErrorPolicy::DeadLetter("dlq".to_string())
+        );
+
+        // older versions always fail
+        let mut dest = Vec::new();
+        value
+            .encode(&mut dest, SMARTMODULE_ERROR_POLICY_API - 1)
+            .expect("should encode");
+        let decoded = SmartModuleInvocation::decode_from(
+            &mut io::Cursor::new(&dest),
+            SMARTMODULE_ERROR_POLICY_API - 1,
+        )
+        .expect("decode");
+        assert_eq!(decoded.error_policy, SmartModuleErrorPolicy::Fail);
+    }
+
+    #[test]
+    fn test_encode_decode_budget() {
+        let value = SmartModuleInvocation {
+            budget: SmartModuleBudget {
+                fuel_limit: Some(1_000),
+                call_timeout: Some(Duration::from_millis(500)),
+            },
+            ..Default::default()
+        };
+
+        let mut dest = Vec::new();
+        value
+            .encode(&mut dest, SMARTMODULE_BUDGET_API)
+            .expect("should encode");
+        let decoded =
+            SmartModuleInvocation::decode_from(&mut io::Cursor::new(&dest), SMARTMODULE_BUDGET_API)
+                .expect("decode");
+        assert_eq!(decoded.budget, value.budget);
+
+        // older versions use limits of SPU
+        let mut dest = Vec::new();
+        value
+            .encode(&mut dest, SMARTMODULE_BUDGET_API - 1)
+            .expect("should encode");
+        let decoded = SmartModuleInvocation::decode_from(
+            &mut io::Cursor::new(&dest),
+            SMARTMODULE_BUDGET_API - 1,
+        )
+        .expect("decode");
+        assert_eq!(decoded.budget, SmartModuleBudget::default());
+    }
+
     #[test]
     fn test_gzip_smartmoduleinvocationwasm() {
         let bytes = vec![0xde, 0xad, 0xbe, 0xef];
@@ -13,7 +13,9 @@ use fluvio_protocol::record::RawRecords;
 use fluvio_protocol::{Encoder, Decoder};
 use fluvio_protocol::api::Request;
 use fluvio_protocol::record::RecordSet;
-use fluvio_smartmodule::dataplane::smartmodule::SmartModuleExtraParams;
+use fluvio_smartmodule::dataplane::smartmodule::{
+    SmartModuleChainTrace, SmartModuleDebugConfig, SmartModuleExtraParams,
+};
 use fluvio_types::{PartitionId, defaults::FLUVIO_CLIENT_MAX_FETCH_BYTES};
 
 use crate::COMMON_VERSION;
@@ -52,6 +54,21 @@ pub const SMARTMODULE_LOOKBACK_AGE: i16 = 21;
 
 pub const SMARTMODULE_TIMESTAMP: i16 = 22;
 
+// version for durable aggregate SmartModule state
+pub const SMARTMODULE_STATE_API: i16 = 25;
+
+// version for error policy of SmartModule invocations
+pub const SMARTMODULE_ERROR_POLICY_API: i16 = 26;
+
+// version for debug mode of SmartModule chain
+pub const SMARTMODULE_DEBUG_API: i16 = 27;
+
+// version for quota throttle time in stream responses
+pub const THROTTLE_API: i16 = 28;
+
+// version for fuel and time budget of SmartModule invocations
+pub const SMARTMODULE_BUDGET_API: i16 = 29;
+
 /// Fetch records continuously
 /// Output will be send back as stream
 #[allow(deprecated)]
@@ -85,6 +102,15 @@ pub struct StreamFetchRequest<R> {
     #[builder(default)]
     #[fluvio(min_version = 18)]
     pub smartmodules: Vec<SmartModuleInvocation>,
+    /// Id of checkpointed state of aggregate SmartModules.
+    /// If state exists, stream resumes from its accumulators and offset
+    #[builder(default)]
+    #[fluvio(min_version = 25)]
+    pub smartmodule_state_id: Option<String>,
+    /// If set, SmartModule chain runs in debug mode and responses carry its traces
+    #[builder(default)]
+    #[fluvio(min_version = 27)]
+    pub smartmodule_debug: Option<SmartModuleDebugConfig>,
     #[builder(setter(skip))]
     data: PhantomData<R>,
 }
@@ -119,6 +145,13 @@ pub struct StreamFetchResponse<R> {
     pub topic: String,
     pub stream_id: u32,
     pub partition: FetchablePartitionResponse<R>,
+    /// traces of SmartModule chain running in debug mode
+    #[fluvio(min_version = 27)]
+    pub smartmodule_trace: Option<SmartModuleChainTrace>,
+    /// The duration in milliseconds for which the stream was throttled due to a quota violation,
+    /// or zero if the stream did not violate any quota.
+    #[fluvio(min_version = 28)]
+    pub throttle_time_ms: i32,
 }
 
 #[cfg(feature = "file")]
@@ -153,6 +186,12 @@ mod file {
             self.topic.encode(src, version)?;
             self.stream_id.encode(src, version)?;
             self.partition.file_encode(src, data, version)?;
+            if version >= SMARTMODULE_DEBUG_API {
+                self.smartmodule_trace.encode(src, version)?;
+            }
+            if version >= THROTTLE_API {
+                self.throttle_time_ms.encode(src, version)?;
+            }
             Ok(())
         }
     }
@@ -207,6 +246,7 @@ mod tests {
                     wasm: SmartModuleInvocationWasm::AdHoc(vec![0xde, 0xad, 0xbe, 0xef]),
                     kind: SmartModuleKind::Filter,
                     params,
+                    ..Default::default()
                 }),
             ],
             ..Default::default()
@@ -218,7 +258,7 @@ mod tests {
             0x00, 0x03, 0x6f, 0x6e, 0x65, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00,
             0x00, 0x00, 0x04, 0xde, 0xad, 0xbe, 0xef, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         ];
         assert_eq!(dest, expected);
     }
@@ -236,6 +276,7 @@ mod tests {
                     wasm: SmartModuleInvocationWasm::AdHoc(vec![0xde, 0xad, 0xbe, 0xef]),
                     kind: SmartModuleKind::Filter,
                     params,
+                    ..Default::default()
                 }),
             ],
             ..Default::default()
@@ -247,7 +288,7 @@ mod tests {
             0x00, 0x03, 0x6f, 0x6e, 0x65, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00,
             0x00, 0x00, 0x04, 0xde, 0xad, 0xbe, 0xef, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
         ];
         assert_eq!(dest, expected);
     }
@@ -285,7 +326,7 @@ mod tests {
             0x00, 0x03, 0x6f, 0x6e, 0x65, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00,
             0x00, 0x00, 0x04, 0xde, 0xad, 0xbe, 0xef, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
         ];
         let mut value = DefaultStreamFetchRequest::default();
         value
@@ -316,7 +357,7 @@ mod tests {
             0x00, 0x03, 0x6f, 0x6e, 0x65, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00,
             0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x00,
             0x00, 0x00, 0x04, 0xde, 0xad, 0xbe, 0xef, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
-            0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
         ];
         let mut value = DefaultStreamFetchRequest::default();
         value
@@ -341,6 +382,122 @@ mod tests {
         assert!(matches!(sm.kind, SmartModuleKind::Filter));
     }
 
+    #[test]
+    fn test_encode_decode_stream_fetch_request_state_id() {
+        let value = DefaultStreamFetchRequest {
+            topic: "one".to_string(),
+            smartmodule_state_id: Some("sum".to_string()),
+            ..Default::default()
+        };
+
+        let mut dest = Vec::new();
+        value
+            .encode(&mut dest, SMARTMODULE_STATE_API)
+            .expect("should encode");
+        let mut decoded = DefaultStreamFetchRequest::default();
+        decoded
+            .decode(&mut std::io::Cursor::new(&dest), SMARTMODULE_STATE_API)
+            .expect("should decode");
+        assert_eq!(decoded.smartmodule_state_id.as_deref(), Some("sum"));
+
+        // older versions don't carry state id
+        let mut dest = Vec::new();
+        value
+            .encode(&mut dest, SMARTMODULE_STATE_API - 1)
+            .expect("should encode");
+        let mut decoded = DefaultStreamFetchRequest::default();
+        decoded
+            .decode(&mut std::io::Cursor::new(&dest), SMARTMODULE_STATE_API - 1)
+            .expect("should decode");
+        assert!(decoded.smartmodule_state_id.is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_stream_fetch_smartmodule_debug() {
+        use fluvio_smartmodule::dataplane::smartmodule::SmartModuleLog;
+
+        let request = DefaultStreamFetchRequest {
+            topic: "one".to_string(),
+            smartmodule_debug: Some(SmartModuleDebugConfig { sample_every: 10 }),
+            ..Default::default()
+        };
+        let mut dest = Vec::new();
+        request
+            .encode(&mut dest, SMARTMODULE_DEBUG_API)
+            .expect("should encode");
+        let mut decoded = DefaultStreamFetchRequest::default();
+        decoded
+            .decode(&mut std::io::Cursor::new(&dest), SMARTMODULE_DEBUG_API)
+            .expect("should decode");
+        assert_eq!(
+            decoded.smartmodule_debug,
+            Some(SmartModuleDebugConfig { sample_every: 10 })
+        );
+
+        let response = DefaultStreamFetchResponse {
+            topic: "one".to_string(),
+            smartmodule_trace: Some(SmartModuleChainTrace {
+                logs: vec![SmartModuleLog {
+                    step: 1,
+                    message: "hello".to_string(),
+                }],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let mut dest = Vec::new();
+        response
+            .encode(&mut dest, SMARTMODULE_DEBUG_API)
+            .expect("should encode");
+        let mut decoded = DefaultStreamFetchResponse::default();
+        decoded
+            .decode(&mut std::io::Cursor::new(&dest), SMARTMODULE_DEBUG_API)
+            .expect("should decode");
+        let trace = decoded.smartmodule_trace.expect("trace");
+        assert_eq!(trace.logs[0].step, 1);
+        assert_eq!(trace.logs[0].message, "hello");
+
+        // older versions don't carry traces
+        let mut dest = Vec::new();
+        response
+            .encode(&mut dest, SMARTMODULE_DEBUG_API - 1)
+            .expect("should encode");
+        let mut decoded = DefaultStreamFetchResponse::default();
+        decoded
+            .decode(&mut std::io::Cursor::new(&dest), SMARTMODULE_DEBUG_API - 1)
+            .expect("should decode");
+        assert!(decoded.smartmodule_trace.is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_stream_fetch_throttle() {
+        let response = DefaultStreamFetchResponse {
+            topic: "one".to_string(),
+            throttle_time_ms: 100,
+            ..Default::default()
+        };
+        let mut dest = Vec::new();
+        response
+            .encode(&mut dest, THROTTLE_API)
+            .expect("should encode");
+        let mut decoded = DefaultStreamFetchResponse::default();
+        decoded
+            .decode(&mut std::io::Cursor::new(&dest), THROTTLE_API)
+            .expect("should decode");
+        assert_eq!(decoded.throttle_time_ms, 100);
+
+        // older versions are never throttled
+        let mut dest = Vec::new();
+        response
+            .encode(&mut dest, THROTTLE_API - 1)
+            .expect("should encode");
+        let mut decoded = DefaultStreamFetchResponse::default();
+        decoded
+            .decode(&mut std::io::Cursor::new(&dest), THROTTLE_API - 1)
+            .expect("should decode");
+        assert_eq!(decoded.throttle_time_ms, 0);
+    }
+
     #[test]
     fn test_zip_unzip_works() {
         const ORIG_LEN: usize = 1024;
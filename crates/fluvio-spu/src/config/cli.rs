@@ -7,11 +7,14 @@
 use std::io::Error as IoError;
 use std::process;
 use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
 
 use tracing::debug;
 use tracing::info;
 use clap::Parser;
 
+use fluvio::config::{TlsPaths, TlsPolicy};
 use fluvio_types::print_cli_err;
 use fluvio_types::SpuId;
 use fluvio_future::openssl::TlsAcceptor;
@@ -59,6 +62,10 @@ pub struct SpuOpt {
     )]
     pub peer_max_bytes: u32,
 
+    /// Min number of in-sync replicas to accept writes, unless set by topic
+    #[arg(long, value_name = "integer", env = "FLV_MIN_IN_SYNC_REPLICAS")]
+    pub min_in_sync_replicas: Option<u16>,
+
     #[arg(
         long,
         value_name = "integer",
@@ -66,23 +73,59 @@ pub struct SpuOpt {
     )]
     pub smart_engine_max_memory: Option<usize>,
 
+    /// Max fuel each SmartModule call can consume
+    #[arg(long, value_name = "integer", env = "FLV_SMART_ENGINE_FUEL_LIMIT")]
+    pub smart_engine_fuel_limit: Option<u64>,
+
+    /// Max time in milliseconds each SmartModule call can run
+    #[arg(long, value_name = "integer", env = "FLV_SMART_ENGINE_CALL_TIMEOUT_MS")]
+    pub smart_engine_call_timeout_ms: Option<u64>,
+
+    /// Max size in bytes of key-value state of each SmartModule
+    #[arg(
+        long,
+        value_name = "integer",
+        env = "FLV_SMART_ENGINE_KEY_VALUE_MAX_BYTES"
+    )]
+    pub smart_engine_key_value_max_bytes: Option<usize>,
+
+    /// Max size in bytes of table of each partition joined by SmartModules
+    #[arg(
+        long,
+        value_name = "integer",
+        env = "FLV_SMART_ENGINE_JOIN_TABLE_MAX_BYTES"
+    )]
+    pub smart_engine_join_table_max_bytes: Option<usize>,
+
+    /// Directory of compiled SmartModules, defaults to directory under log base directory
+    #[arg(long, value_name = "path", env = "FLV_SMART_ENGINE_MODULE_CACHE_DIR")]
+    pub smart_engine_module_cache_dir: Option<PathBuf>,
+
     #[clap(flatten)]
     tls: TlsConfig,
+
+    /// Identify public clients by X509 certificate, scopes of principals are read from path
+    #[arg(
+        long = "authorization-scopes",
+        value_name = "authorization scopes path",
+        env
+    )]
+    x509_auth_scopes: Option<PathBuf>,
 }
 
 impl SpuOpt {
     /// Validate SPU (Streaming Processing Unit) cli inputs and generate SpuConfig
     fn get_spu_config(self) -> Result<(SpuConfig, Option<(TlsAcceptor, String)>), IoError> {
         let tls_acceptor = self.try_build_tls_acceptor()?;
-        let (spu_config, tls_addr_opt) = self.as_spu_config()?;
+        let client_tls = self.client_tls_policy()?;
+        let (mut spu_config, tls_addr_opt) = self.as_spu_config()?;
+        spu_config.client_tls = client_tls;
         let tls_config = tls_acceptor.map(|it| (it, tls_addr_opt.unwrap()));
         Ok((spu_config, tls_config))
     }
 
     #[allow(clippy::wrong_self_convention)]
     fn as_spu_config(self) -> Result<(SpuConfig, Option<String>), IoError> {
-        use std::path::PathBuf;
-
         let mut config = SpuConfig {
             id: match self.id {
                 Some(id) => id,
@@ -145,6 +188,11 @@ impl SpuOpt {
 
         config.peer_max_bytes = self.peer_max_bytes;
 
+        if let Some(min_in_sync_replicas) = self.min_in_sync_replicas {
+            info!("overriding min in-sync replicas: {}", min_in_sync_replicas);
+            config.replication.min_in_sync_replicas = min_in_sync_replicas;
+        }
+
         if let Some(smart_engine_max_memory) = self.smart_engine_max_memory {
             info!(
                 "overriding smart engine max memory: {}",
@@ -153,6 +201,45 @@ impl SpuOpt {
             config.smart_engine.store_max_memory = smart_engine_max_memory;
         }
 
+        if let Some(fuel_limit) = self.smart_engine_fuel_limit {
+            info!("overriding smart engine fuel limit: {}", fuel_limit);
+            config.smart_engine.fuel_limit = Some(fuel_limit);
+        }
+
+        if let Some(call_timeout_ms) = self.smart_engine_call_timeout_ms {
+            info!(
+                "overriding smart engine call timeout: {} ms",
+                call_timeout_ms
+            );
+            config.smart_engine.call_timeout = Some(Duration::from_millis(call_timeout_ms));
+        }
+
+        if let Some(key_value_max_bytes) = self.smart_engine_key_value_max_bytes {
+            info!(
+                "overriding smart engine key-value max bytes: {}",
+                key_value_max_bytes
+            );
+            config.smart_engine.key_value_max_bytes = key_value_max_bytes;
+        }
+
+        if let Some(join_table_max_bytes) = self.smart_engine_join_table_max_bytes {
+            info!(
+                "overriding smart engine join table max bytes: {}",
+                join_table_max_bytes
+            );
+            config.smart_engine.join_table_max_bytes = join_table_max_bytes;
+        }
+
+        if let Some(module_cache_dir) = self.smart_engine_module_cache_dir {
+            info!(
+                "overriding smart engine module cache dir: {}",
+                module_cache_dir.display()
+            );
+            config.smart_engine.module_cache_dir = Some(module_cache_dir);
+        }
+
+        config.x509_auth_scopes = self.x509_auth_scopes;
+
         Ok((config, tls_port))
     }
 
@@ -189,6 +276,40 @@ impl SpuOpt {
         Ok(Some(builder.build()))
     }
 
+    /// TLS of connections to public endpoints of other SPUs,
+    /// client certificate identifies this SPU to them
+    fn client_tls_policy(&self) -> Result<TlsPolicy, IoError> {
+        let tls_config = &self.tls;
+        if !tls_config.tls {
+            return Ok(TlsPolicy::Disabled);
+        }
+
+        match (&tls_config.client_cert, &tls_config.client_key) {
+            (Some(cert), Some(key)) => {
+                let ca_cert = tls_config
+                    .ca_cert
+                    .as_ref()
+                    .ok_or_else(|| IoError::new(ErrorKind::NotFound, "missing ca cert"))?;
+                let domain = tls_config
+                    .domain
+                    .as_ref()
+                    .ok_or_else(|| IoError::new(ErrorKind::NotFound, "missing tls domain"))?;
+                Ok(TlsPaths {
+                    domain: domain.clone(),
+                    key: key.into(),
+                    cert: cert.into(),
+                    ca_cert: ca_cert.into(),
+                }
+                .into())
+            }
+            (None, None) => Ok(TlsPolicy::Anonymous),
+            _ => Err(IoError::new(
+                ErrorKind::InvalidInput,
+                "client cert and key must be specified together",
+            )),
+        }
+    }
+
     pub fn process_spu_cli_or_exit(self) -> (SpuConfig, Option<(TlsAcceptor, String)>) {
         match self.get_spu_config() {
             Err(err) => {
@@ -271,4 +392,14 @@ struct TlsConfig {
     #[arg(long)]
     /// TLS: address of non tls public service, required
     pub bind_non_tls_public: Option<String>,
+
+    /// TLS: path to client certificate identifying this SPU to other SPUs
+    #[arg(long)]
+    pub client_cert: Option<String>,
+    /// TLS: path to client private key, required with client cert
+    #[arg(long)]
+    pub client_key: Option<String>,
+    /// TLS: domain of public endpoints of other SPUs, required with client cert
+    #[arg(long)]
+    pub domain: Option<String>,
 }
@@ -3,4 +3,4 @@ mod spu_config;
 
 pub use self::cli::SpuOpt;
 
-pub use self::spu_config::{SpuConfig, Log, ReplicationConfig};
+pub use self::spu_config::{SpuConfig, Log, ReplicationConfig, SmartEngineConfig};
@@ -10,6 +10,9 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use fluvio::config::TlsPolicy;
 
 // defaults values
 use fluvio_types::defaults::SPU_PUBLIC_PORT;
@@ -22,10 +25,13 @@ use fluvio_types::defaults::SPU_LOG_INDEX_MAX_INTERVAL_BYTES;
 use fluvio_types::defaults::SPU_LOG_SEGMENT_MAX_BYTES;
 use fluvio_types::defaults::SPU_RETRY_SC_TIMEOUT_MS;
 use fluvio_types::defaults::SPU_SMARTENGINE_STORE_MAX_BYTES;
+use fluvio_types::defaults::SPU_SMARTENGINE_KEY_VALUE_MAX_BYTES;
+use fluvio_types::defaults::SPU_SMARTENGINE_JOIN_TABLE_MAX_BYTES;
 
 // environment variables
 
 use fluvio_types::defaults::SPU_MIN_IN_SYNC_REPLICAS;
+use fluvio_types::defaults::SPU_FOLLOWER_SYNC_GRACE_PERIOD_MS;
 use fluvio_types::defaults::FLV_LOG_BASE_DIR;
 use fluvio_types::defaults::FLV_LOG_SIZE;
 use fluvio_types::SpuId;
@@ -37,12 +43,15 @@ use fluvio_types::defaults::{
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct ReplicationConfig {
     pub min_in_sync_replicas: u16,
+    /// time after leader starts during which followers which have not reported their offsets are in sync
+    pub follower_sync_grace_period: Duration,
 }
 
 impl Default for ReplicationConfig {
     fn default() -> Self {
         Self {
             min_in_sync_replicas: SPU_MIN_IN_SYNC_REPLICAS,
+            follower_sync_grace_period: Duration::from_millis(SPU_FOLLOWER_SYNC_GRACE_PERIOD_MS),
         }
     }
 }
@@ -79,12 +88,27 @@ impl Default for Log {
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub struct SmartEngineConfig {
     pub store_max_memory: usize,
+    /// fuel allowed for each SmartModule call, unbounded if not set
+    pub fuel_limit: Option<u64>,
+    /// time allowed for each SmartModule call, unbounded if not set
+    pub call_timeout: Option<Duration>,
+    /// max size of key-value state of each SmartModule
+    pub key_value_max_bytes: usize,
+    /// max size of table of each joined partition
+    pub join_table_max_bytes: usize,
+    /// directory of compiled SmartModules, under log base directory if not set
+    pub module_cache_dir: Option<PathBuf>,
 }
 
 impl Default for SmartEngineConfig {
     fn default() -> Self {
         Self {
             store_max_memory: SPU_SMARTENGINE_STORE_MAX_BYTES,
+            fuel_limit: None,
+            call_timeout: None,
+            key_value_max_bytes: SPU_SMARTENGINE_KEY_VALUE_MAX_BYTES,
+            join_table_max_bytes: SPU_SMARTENGINE_JOIN_TABLE_MAX_BYTES,
+            module_cache_dir: None,
         }
     }
 }
@@ -111,6 +135,13 @@ pub struct SpuConfig {
     pub peer_max_bytes: u32,
 
     pub smart_engine: SmartEngineConfig,
+
+    /// scopes of client principals, if set public clients are identified by X509 certificate
+    pub x509_auth_scopes: Option<PathBuf>,
+
+    /// TLS and identity of this SPU when connecting to public endpoints of other SPUs,
+    /// i.e. to partitions joined by SmartModules or written as dead-letter topics
+    pub client_tls: TlsPolicy,
 }
 
 impl Default for SpuConfig {
@@ -126,6 +157,8 @@ impl Default for SpuConfig {
             log: Log::default(),
             peer_max_bytes: fluvio_storage::FileReplica::PREFER_MAX_LEN,
             smart_engine: SmartEngineConfig::default(),
+            x509_auth_scopes: None,
+            client_tls: TlsPolicy::Disabled,
         }
     }
 }
@@ -161,6 +194,18 @@ impl SpuConfig {
     pub fn storage(&self) -> &Log {
         &self.log
     }
+
+    /// directory where compiled SmartModules are kept across restarts
+    pub fn smartmodule_cache_dir(&self) -> PathBuf {
+        self.smart_engine
+            .module_cache_dir
+            .clone()
+            .unwrap_or_else(|| {
+                self.log
+                    .base_dir
+                    .join(format!("spu-smartmodule-cache-{}", self.id))
+            })
+    }
 }
 
 impl From<&SpuConfig> for ReplicaConfig {
@@ -10,6 +10,8 @@ use fluvio_controlplane::sc_api::update_lrs::UpdateLrsRequest;
 use fluvio_controlplane::spu_api::api::{InternalSpuRequest, InternalSpuApi};
 use fluvio_controlplane::spu_api::update_replica::UpdateReplicaRequest;
 use fluvio_controlplane::spu_api::update_smartmodule::UpdateSmartModuleRequest;
+use fluvio_controlplane::spu_api::update_quota::UpdateQuotaRequest;
+use fluvio_controlplane::spu_api::update_policy::UpdatePolicyRequest;
 use fluvio_controlplane::spu_api::update_spu::UpdateSpuRequest;
 use flv_util::print_cli_err;
 use fluvio_future::task::spawn;
@@ -18,7 +20,7 @@ use fluvio_protocol::api::RequestMessage;
 use fluvio_socket::{FluvioSocket, FluvioSink};
 use fluvio_storage::FileReplica;
 
-use crate::core::SharedGlobalContext;
+use crate::core::{SharedGlobalContext, SpecChange};
 
 use super::message_sink::SharedStatusUpdate;
 
@@ -29,6 +31,7 @@ struct DispatcherCounter {
     pub spu_changes: u64,     // spu changes received from sc
     pub reconnect: u64,       // number of reconnect to sc
     pub smartmodule: u64,     // number of sm updates from sc
+    pub quota: u64,           // number of quota updates from sc
 }
 
 /// Controller for handling connection to SC
@@ -153,6 +156,13 @@ impl ScDispatcher<FileReplica> {
                                 break;
                             }
                         },
+                        Some(Ok(InternalSpuRequest::UpdateQuotaRequest(request))) => {
+                            self.counter.quota += 1;
+                            self.handle_update_quota_request(request);
+                        },
+                        Some(Ok(InternalSpuRequest::UpdatePolicyRequest(request))) => {
+                            self.handle_update_policy_request(request);
+                        },
                         Some(Err(err)) => {
                             error!(%err, "Api error");
                             break;
@@ -356,6 +366,78 @@ impl ScDispatcher<FileReplica> {
 
         debug!(actions = actions.count(), "finished SmartModule update");
 
+        // compiled SmartModules of changed or deleted SmartModules are stale
+        let sm_engine = self.ctx.smartengine_owned();
+        for action in actions.into_iter() {
+            match action {
+                SpecChange::Mod(smartmodule, _) | SpecChange::Delete(smartmodule) => {
+                    sm_engine.invalidate_module(&smartmodule.name);
+                }
+                SpecChange::Add(_) => {}
+            }
+        }
+
         Ok(())
     }
+
+    ///
+    /// Handle Quota update sent by SC
+    ///
+    #[instrument(skip(self, req_msg), name = "update_quota_request")]
+    fn handle_update_quota_request(&self, req_msg: RequestMessage<UpdateQuotaRequest>) {
+        let (_, request) = req_msg.get_header_request();
+
+        debug!(message = ?request, "starting Quota update");
+
+        let actions = if !request.all.is_empty() {
+            debug!(
+                epoch = request.epoch,
+                item_count = request.all.len(),
+                "received quota sync all"
+            );
+            self.ctx.quota_localstore().sync_all(request.all)
+        } else {
+            debug!(
+                epoch = request.epoch,
+                item_count = request.changes.len(),
+                "received quota changes"
+            );
+            self.ctx.quota_localstore().apply_changes(request.changes)
+        };
+
+        debug!(actions = actions.count(), "finished Quota update");
+
+        // usage of changed or deleted quotas is counted again from the new limits
+        for action in actions.into_iter() {
+            match action {
+                SpecChange::Mod(quota, _) | SpecChange::Delete(quota) => {
+                    self.ctx.quota_throttles().remove_quota(&quota.name);
+                }
+                SpecChange::Add(_) => {}
+            }
+        }
+    }
+
+    ///
+    /// Handle authorization policy sent by SC
+    ///
+    #[instrument(skip(self, req_msg), name = "update_policy_request")]
+    fn handle_update_policy_request(&self, req_msg: RequestMessage<UpdatePolicyRequest>) {
+        let (_, request) = req_msg.get_header_request();
+
+        let policy = match request
+            .policy
+            .as_deref()
+            .map(serde_json::from_str)
+            .transpose()
+        {
+            Ok(policy) => policy,
+            Err(err) => {
+                error!(%err, "invalid auth policy, keeping previous policy");
+                return;
+            }
+        };
+        info!(has_policy = policy.is_some(), "received auth policy");
+        self.ctx.authorization().update_policy(policy);
+    }
 }
@@ -0,0 +1,195 @@
+//!
+//! # Authorization of public API clients
+//!
+//! Clients are identified by X509 identity sent by authenticating proxy.
+//! Policy of clients is distributed by SC.
+//!
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use tracing::{debug, error};
+
+use fluvio_auth::{AuthContext, Authorization, TypeAction, InstanceAction, AuthError};
+use fluvio_auth::basic::{Action, BasicRbacPolicy};
+use fluvio_auth::x509::X509Identity;
+use fluvio_controlplane_metadata::extended::ObjectType;
+use fluvio_socket::FluvioSocket;
+
+#[derive(Debug, Clone, Default)]
+enum PolicyState {
+    /// policy not received from SC yet, clients are denied
+    #[default]
+    Pending,
+    /// SC doesn't authorize clients
+    AllowAll,
+    Basic(Arc<BasicRbacPolicy>),
+}
+
+/// Authorization of clients with policy received from SC
+#[derive(Debug, Clone, Default)]
+pub struct SpuAuthorization {
+    policy: Arc<RwLock<PolicyState>>,
+}
+
+impl SpuAuthorization {
+    /// replace policy, all clients are allowed if there is no policy
+    pub fn update_policy(&self, policy: Option<BasicRbacPolicy>) {
+        let state = match policy {
+            Some(policy) => PolicyState::Basic(Arc::new(policy)),
+            None => PolicyState::AllowAll,
+        };
+        debug!(?state, "updating auth policy");
+        *self.policy.write().unwrap_or_else(|err| err.into_inner()) = state;
+    }
+
+    async fn evaluate(
+        &self,
+        action: Action,
+        object_type: ObjectType,
+        instance: Option<&str>,
+        identity: &X509Identity,
+    ) -> Result<bool, AuthError> {
+        let state = self
+            .policy
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        match state {
+            PolicyState::Pending => Ok(false),
+            PolicyState::AllowAll => Ok(true),
+            PolicyState::Basic(policy) => {
+                policy
+                    .evaluate(action, object_type, instance, identity)
+                    .await
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Authorization for SpuAuthorization {
+    type Context = SpuAuthContext;
+
+    async fn create_auth_context(
+        &self,
+        socket: &mut FluvioSocket,
+    ) -> Result<Self::Context, AuthError> {
+        let identity = X509Identity::create_from_connection(socket)
+            .await
+            .map_err(|err| {
+                error!(%err, "failed to create x509 identity");
+                err
+            })?;
+        Ok(SpuAuthContext {
+            identity,
+            authorization: self.clone(),
+        })
+    }
+}
+
+/// Auth context of client connection
+#[derive(Debug, Clone)]
+pub struct SpuAuthContext {
+    identity: X509Identity,
+    authorization: SpuAuthorization,
+}
+
+impl SpuAuthContext {
+    pub fn principal(&self) -> &str {
+        &self.identity.principal
+    }
+}
+
+#[async_trait]
+impl AuthContext for SpuAuthContext {
+    async fn allow_type_action(
+        &self,
+        ty: ObjectType,
+        action: TypeAction,
+    ) -> Result<bool, AuthError> {
+        self.authorization
+            .evaluate(action.into(), ty, None, &self.identity)
+            .await
+    }
+
+    async fn allow_instance_action(
+        &self,
+        ty: ObjectType,
+        action: InstanceAction,
+        key: &str,
+    ) -> Result<bool, AuthError> {
+        self.authorization
+            .evaluate(action.into(), ty, Some(key), &self.identity)
+            .await
+    }
+}
+
+/// check if client is allowed `action` on `topic`.
+/// All clients are allowed if SPU doesn't know their identity
+pub async fn allow_topic_action(
+    auth: Option<&SpuAuthContext>,
+    action: InstanceAction,
+    topic: &str,
+) -> bool {
+    let Some(auth) = auth else {
+        return true;
+    };
+    auth.allow_instance_action(ObjectType::Topic, action, topic)
+        .await
+        .unwrap_or_else(|err| {
+            error!(%err, topic, "error evaluating auth policy");
+            false
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use fluvio_auth::{AuthContext, InstanceAction};
+    use fluvio_auth::basic::{Action, BasicRbacPolicy};
+    use fluvio_auth::x509::X509Identity;
+    use fluvio_controlplane_metadata::extended::ObjectType;
+
+    use super::{SpuAuthorization, SpuAuthContext};
+
+    fn context(authorization: &SpuAuthorization) -> SpuAuthContext {
+        SpuAuthContext {
+            identity: X509Identity::new("alice".to_owned(), vec!["Consumer".to_owned()]),
+            authorization: authorization.clone(),
+        }
+    }
+
+    #[fluvio_future::test]
+    async fn test_topic_actions_follow_policy() {
+        let authorization = SpuAuthorization::default();
+        let ctx = context(&authorization);
+
+        // nothing is allowed until SC sends policy
+        assert!(!ctx
+            .allow_instance_action(ObjectType::Topic, InstanceAction::Read, "topic1")
+            .await
+            .expect("eval"));
+
+        authorization.update_policy(None);
+        assert!(ctx
+            .allow_instance_action(ObjectType::Topic, InstanceAction::Write, "topic1")
+            .await
+            .expect("eval"));
+
+        let mut consumer = HashMap::new();
+        consumer.insert(ObjectType::Topic, vec![Action::Read.into()]);
+        let mut policy = HashMap::new();
+        policy.insert("Consumer".to_owned(), consumer);
+        authorization.update_policy(Some(BasicRbacPolicy::from(policy)));
+
+        assert!(ctx
+            .allow_instance_action(ObjectType::Topic, InstanceAction::Read, "topic1")
+            .await
+            .expect("eval"));
+        assert!(!ctx
+            .allow_instance_action(ObjectType::Topic, InstanceAction::Write, "topic1")
+            .await
+            .expect("eval"));
+    }
+}
@@ -4,6 +4,7 @@
 //! Global Context maintains states need to be shared across in the SPU
 
 use std::sync::Arc;
+use std::time::Duration;
 use std::fmt::Debug;
 
 use tracing::{debug, error, instrument};
@@ -20,12 +21,15 @@ use crate::replication::leader::{
 use crate::control_plane::{StatusMessageSink, SharedStatusUpdate};
 use crate::core::metrics::SpuMetrics;
 use crate::smartengine::SmartEngine;
+use crate::smartengine::join::JoinTables;
 
 use super::leader_client::LeaderConnections;
 use super::smartmodule::SmartModuleLocalStore;
 use super::spus::SharedSpuLocalStore;
 use super::SharedReplicaLocalStore;
 use super::smartmodule::SharedSmartModuleLocalStore;
+use super::quota::{QuotaLocalStore, SharedQuotaLocalStore, QuotaThrottles, QuotaRate, QuotaClient};
+use super::auth::SpuAuthorization;
 use super::spus::SpuLocalStore;
 use super::replica::ReplicaStore;
 use super::SharedSpuConfig;
@@ -38,11 +42,15 @@ pub struct GlobalContext<S> {
     spu_localstore: SharedSpuLocalStore,
     replica_localstore: SharedReplicaLocalStore,
     smartmodule_localstore: SharedSmartModuleLocalStore,
+    quota_localstore: SharedQuotaLocalStore,
+    quota_throttles: Arc<QuotaThrottles>,
+    authorization: SpuAuthorization,
     leaders_state: SharedReplicaLeadersState<S>,
     followers_state: SharedFollowersState<S>,
     spu_followers: SharedSpuUpdates,
     status_update: SharedStatusUpdate,
     sm_engine: SmartEngine,
+    join_tables: JoinTables,
     leaders: Arc<LeaderConnections>,
     metrics: Arc<SpuMetrics>,
 }
@@ -63,18 +71,29 @@ where
         let spus = SpuLocalStore::new_shared();
         let replicas = ReplicaStore::new_shared();
         let metrics = Arc::new(SpuMetrics::new());
+        let sm_engine =
+            SmartEngine::new().with_module_cache_dir(spu_config.smartmodule_cache_dir());
+        let leaders = LeaderConnections::shared(
+            spus.clone(),
+            replicas.clone(),
+            spu_config.client_tls.clone(),
+        );
 
         GlobalContext {
             spu_localstore: spus.clone(),
             replica_localstore: replicas.clone(),
             smartmodule_localstore: SmartModuleLocalStore::new_shared(),
+            quota_localstore: QuotaLocalStore::new_shared(),
+            quota_throttles: Arc::new(QuotaThrottles::default()),
+            authorization: SpuAuthorization::default(),
             config: Arc::new(spu_config),
             leaders_state: ReplicaLeadersState::new_shared(),
             followers_state: FollowersState::new_shared(),
             spu_followers: FollowerNotifier::shared(),
             status_update: StatusMessageSink::shared(),
-            sm_engine: SmartEngine::new(),
-            leaders: LeaderConnections::shared(spus, replicas),
+            sm_engine,
+            join_tables: JoinTables::default(),
+            leaders,
             metrics,
         }
     }
@@ -100,6 +119,38 @@ where
         &self.smartmodule_localstore
     }
 
+    pub fn quota_localstore(&self) -> &QuotaLocalStore {
+        &self.quota_localstore
+    }
+
+    /// usage of quotas by clients
+    pub fn quota_throttles(&self) -> &QuotaThrottles {
+        &self.quota_throttles
+    }
+
+    /// record `amount` of `rate` used by client on `topic`,
+    /// returns how long client must be throttled
+    pub fn record_quota_usage(
+        &self,
+        client: &QuotaClient,
+        topic: &str,
+        rate: QuotaRate,
+        amount: u64,
+    ) -> Duration {
+        let quotas = self
+            .quota_localstore
+            .find_matching(client.principal(), topic);
+        if quotas.is_empty() {
+            return Duration::ZERO;
+        }
+        self.quota_throttles.record(&quotas, client, rate, amount)
+    }
+
+    /// authorization of public API clients
+    pub fn authorization(&self) -> &SpuAuthorization {
+        &self.authorization
+    }
+
     pub fn leaders_state(&self) -> &ReplicaLeadersState<S> {
         &self.leaders_state
     }
@@ -124,6 +175,10 @@ where
         &self.spu_followers
     }
 
+    pub fn follower_notifier_owned(&self) -> SharedSpuUpdates {
+        self.spu_followers.clone()
+    }
+
     #[allow(unused)]
     pub fn status_update(&self) -> &StatusMessageSink {
         &self.status_update
@@ -145,7 +200,11 @@ where
         self.sm_engine.clone()
     }
 
-    #[allow(unused)]
+    /// tables of partitions joined by SmartModules
+    pub(crate) fn join_tables(&self) -> &JoinTables {
+        &self.join_tables
+    }
+
     pub fn leaders(&self) -> Arc<LeaderConnections> {
         self.leaders.clone()
     }
@@ -315,7 +374,13 @@ mod file_replica {
                                     // we are follower
                                     // if we were leader before, we demote out self
                                     if old_replica.leader == local_id {
-                                        self.demote_replica(new_replica).await
+                                        if new_replica.replicas.contains(&local_id) {
+                                            self.demote_replica(new_replica).await
+                                        } else {
+                                            outputs.push(ReplicaChange::Remove(
+                                                self.remove_leader_replica(new_replica).await,
+                                            ));
+                                        }
                                     } else {
                                         // we stay as follower but we switch to new leader
                                         debug!(
@@ -327,9 +392,20 @@ mod file_replica {
                                     }
                                 }
                             } else if new_replica.leader == local_id {
-                                if self.leaders_state().get(&new_replica.id).await.is_some() {
-                                } else {
-                                    error!("leader controller was not found: {}", new_replica.id);
+                                self.update_leader_replica(new_replica).await;
+                            } else if !new_replica.replicas.contains(&local_id) {
+                                // replica was reassigned to other spus
+                                if old_replica.replicas.contains(&local_id) {
+                                    self.remove_follower_replica(new_replica).await
+                                }
+                            } else if !old_replica.replicas.contains(&local_id) {
+                                // replica was reassigned to this spu
+                                if let Err(err) = self
+                                    .followers_state_owned()
+                                    .add_replica(self, new_replica)
+                                    .await
+                                {
+                                    outputs.push(ReplicaChange::StorageError(err));
                                 }
                             } else {
                                 self.followers_state().update_replica(new_replica).await;
@@ -342,6 +418,24 @@ mod file_replica {
             outputs
         }
 
+        /// update followers of leader replica, replicas may be changed by reassignment
+        #[instrument(
+            skip(self,replica),
+            fields(
+                replica = %replica.id,
+            )
+        )]
+        async fn update_leader_replica(&self, replica: Replica) {
+            let replica_id = replica.id.clone();
+            if self.leaders_state().update_replica(replica).await {
+                if let Some(leader) = self.leaders_state().get(&replica_id).await {
+                    leader.update_status().await;
+                }
+            } else {
+                error!("leader controller was not found: {}", replica_id);
+            }
+        }
+
         /// reemove leader replica
         #[instrument(
             skip(self,replica),
@@ -350,6 +444,7 @@ mod file_replica {
             )
         )]
         async fn remove_leader_replica(&self, replica: Replica) -> ReplicaRemovedRequest {
+            self.join_tables().set_leader(&replica.id, None);
             // try to send message to leader controller if still exists
             if let Some(previous_state) = self.leaders_state().remove(&replica.id).await {
                 if let Err(err) = previous_state.remove().await {
@@ -399,6 +494,7 @@ mod file_replica {
             )
         )]
         pub async fn demote_replica(&self, replica: Replica) {
+            self.join_tables().set_leader(&replica.id, None);
             if let Some(leader_replica_state) = self.leaders_state().remove(&replica.id).await {
                 drop(leader_replica_state);
                 if let Err(err) = self
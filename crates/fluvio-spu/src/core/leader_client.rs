@@ -3,11 +3,15 @@ use std::{collections::HashMap, fmt::Debug, sync::Arc};
 use async_lock::Mutex;
 use async_trait::async_trait;
 
+use fluvio::config::TlsPolicy;
 use fluvio::metrics::ClientMetrics;
 use fluvio::{FluvioError, PartitionConsumer};
 use fluvio::spu::{SpuDirectory, SpuSocket};
 use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_future::net::DomainConnector;
+use fluvio_protocol::link::ErrorCode;
 use fluvio_socket::{MultiplexerSocket, ClientConfig, VersionedSerialSocket};
+use fluvio_spu_schema::server::fetch_offset::FetchOffsetsRequest;
 use fluvio_types::{SpuId, PartitionId};
 use tracing::{debug, instrument};
 
@@ -21,20 +25,34 @@ pub struct LeaderConnections {
     spus: SharedSpuLocalStore,
     replicas: SharedReplicaLocalStore,
     leaders: Arc<Mutex<HashMap<SpuId, SpuSocket>>>,
+    // leaders of replicas not hosted by this SPU
+    discovered: Arc<Mutex<HashMap<ReplicaKey, SpuId>>>,
     metrics: Arc<ClientMetrics>,
+    // TLS and identity of this SPU as client of other SPUs
+    client_tls: TlsPolicy,
 }
 
 impl LeaderConnections {
-    pub fn new(spus: SharedSpuLocalStore, replicas: SharedReplicaLocalStore) -> Self {
+    pub fn new(
+        spus: SharedSpuLocalStore,
+        replicas: SharedReplicaLocalStore,
+        client_tls: TlsPolicy,
+    ) -> Self {
         LeaderConnections {
             spus,
             replicas,
             leaders: Default::default(),
+            discovered: Default::default(),
             metrics: Arc::new(ClientMetrics::new()),
+            client_tls,
         }
     }
-    pub fn shared(spus: SharedSpuLocalStore, replicas: SharedReplicaLocalStore) -> Arc<Self> {
-        Arc::new(LeaderConnections::new(spus, replicas))
+    pub fn shared(
+        spus: SharedSpuLocalStore,
+        replicas: SharedReplicaLocalStore,
+        client_tls: TlsPolicy,
+    ) -> Arc<Self> {
+        Arc::new(LeaderConnections::new(spus, replicas, client_tls))
     }
 
     /// create a connection to leader, it can't find it, return
@@ -42,7 +60,8 @@ impl LeaderConnections {
     async fn connect_to_leader(&self, leader: SpuId) -> Result<SpuSocket, FluvioError> {
         if let Some(spu) = self.spus.spec(&leader) {
             debug!("connecting to spu : {:#?}", spu);
-            let client_config = ClientConfig::with_addr(spu.public_endpoint.addr());
+            let connector = DomainConnector::try_from(self.client_tls.clone())?;
+            let client_config = ClientConfig::new(spu.public_endpoint.addr(), connector, false);
             let versioned_socket = client_config.connect().await?;
             let (socket, config, versions) = versioned_socket.split();
             Ok(SpuSocket::new(
@@ -55,6 +74,76 @@ impl LeaderConnections {
         }
     }
 
+    /// find leader of replica, leaders of replicas not hosted by this SPU
+    /// are looked up by asking each SPU for offsets of the replica
+    #[instrument(skip(self))]
+    async fn find_leader(&self, replica: &ReplicaKey) -> Result<SpuId, FluvioError> {
+        if let Some(replica_spec) = self.replicas.spec(replica) {
+            return Ok(replica_spec.leader);
+        }
+        if let Some(leader) = self.discovered.lock().await.get(replica) {
+            return Ok(*leader);
+        }
+
+        for spu in self.spus.all_values() {
+            let socket = match self.serial_socket_to(spu.id).await {
+                Ok(socket) => socket,
+                Err(err) => {
+                    debug!(spu = spu.id, %err, "spu is not reachable");
+                    continue;
+                }
+            };
+            let request = FetchOffsetsRequest::new(replica.topic.clone(), replica.partition);
+            match socket.send_receive(request).await {
+                Ok(response) => {
+                    if response
+                        .find_partition(replica)
+                        .map(|partition| partition.error_code == ErrorCode::None)
+                        .unwrap_or(false)
+                    {
+                        debug!(leader = spu.id, "found leader");
+                        self.discovered.lock().await.insert(replica.clone(), spu.id);
+                        return Ok(spu.id);
+                    }
+                }
+                Err(err) => debug!(spu = spu.id, %err, "error fetching offsets"),
+            }
+        }
+
+        Err(FluvioError::PartitionNotFound(
+            replica.topic.clone(),
+            replica.partition,
+        ))
+    }
+
+    /// forget leader found for replica, so it is looked up again after leader changes
+    pub async fn forget_leader(&self, replica: &ReplicaKey) {
+        self.discovered.lock().await.remove(replica);
+    }
+
+    /// serial socket to leader, reusing existing connection if it is not stale
+    async fn serial_socket_to(
+        &self,
+        leader_id: SpuId,
+    ) -> Result<VersionedSerialSocket, FluvioError> {
+        // check if already have existing connection to same SPU
+        let mut client_lock = self.leaders.lock().await;
+
+        if let Some(spu_socket) = client_lock.get_mut(&leader_id) {
+            if !spu_socket.is_stale() {
+                return Ok(spu_socket.create_serial_socket().await);
+            } else {
+                client_lock.remove(&leader_id);
+            }
+        }
+
+        let mut spu_socket = self.connect_to_leader(leader_id).await?;
+        let serial_socket = spu_socket.create_serial_socket().await;
+        client_lock.insert(leader_id, spu_socket);
+
+        Ok(serial_socket)
+    }
+
     /// create consumer connection to a leader
     #[instrument(skip(self))]
     pub async fn partition_consumer<S>(
@@ -75,28 +164,8 @@ impl SpuDirectory for LeaderConnections {
         &self,
         replica: &ReplicaKey,
     ) -> Result<VersionedSerialSocket, fluvio::FluvioError> {
-        if let Some(replica_spec) = self.replicas.spec(replica) {
-            let leader_id = replica_spec.leader;
-
-            // check if already have existing connection to same SPU
-            let mut client_lock = self.leaders.lock().await;
-
-            if let Some(spu_socket) = client_lock.get_mut(&leader_id) {
-                if !spu_socket.is_stale() {
-                    return Ok(spu_socket.create_serial_socket().await);
-                } else {
-                    client_lock.remove(&leader_id);
-                }
-            }
-
-            let mut spu_socket = self.connect_to_leader(leader_id).await?;
-            let serial_socket = spu_socket.create_serial_socket().await;
-            client_lock.insert(leader_id, spu_socket);
-
-            Ok(serial_socket)
-        } else {
-            Err(FluvioError::TopicNotFound(replica.to_string()))
-        }
+        let leader_id = self.find_leader(replica).await?;
+        self.serial_socket_to(leader_id).await
     }
 
     async fn create_stream_with_version<R: fluvio_protocol::api::Request>(
@@ -108,25 +177,21 @@ impl SpuDirectory for LeaderConnections {
     where
         R: Sync + Send,
     {
-        if let Some(replica_spec) = self.replicas.spec(replica) {
-            let leader_id = replica_spec.leader;
-            let mut client_lock = self.leaders.lock().await;
-
-            if let Some(spu_socket) = client_lock.get_mut(&leader_id) {
-                return spu_socket
-                    .create_stream_with_version(request, version)
-                    .await;
-            }
+        let leader_id = self.find_leader(replica).await?;
+        let mut client_lock = self.leaders.lock().await;
 
-            let mut spu_socket = self.connect_to_leader(leader_id).await?;
-            let stream = spu_socket
+        if let Some(spu_socket) = client_lock.get_mut(&leader_id) {
+            return spu_socket
                 .create_stream_with_version(request, version)
-                .await?;
-            client_lock.insert(leader_id, spu_socket);
-
-            Ok(stream)
-        } else {
-            Err(FluvioError::TopicNotFound(replica.to_string()))
+                .await;
         }
+
+        let mut spu_socket = self.connect_to_leader(leader_id).await?;
+        let stream = spu_socket
+            .create_stream_with_version(request, version)
+            .await?;
+        client_lock.insert(leader_id, spu_socket);
+
+        Ok(stream)
     }
 }
@@ -5,6 +5,8 @@ mod leader_client;
 pub mod spus;
 pub mod replica;
 pub mod smartmodule;
+pub mod quota;
+pub mod auth;
 pub mod metrics;
 
 pub use self::global_context::{GlobalContext, ReplicaChange};
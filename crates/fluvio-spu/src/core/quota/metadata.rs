@@ -0,0 +1,31 @@
+use fluvio_controlplane::spu_api::update_quota::Quota;
+
+use crate::core::Spec;
+use crate::core::LocalStore;
+
+impl Spec for Quota {
+    const LABEL: &'static str = "Quota";
+
+    type Key = String;
+
+    fn key(&self) -> &Self::Key {
+        &self.name
+    }
+
+    fn key_owned(&self) -> Self::Key {
+        self.name.clone()
+    }
+}
+
+pub type QuotaLocalStore = LocalStore<Quota>;
+
+impl LocalStore<Quota> {
+    /// quotas applying to client with `principal` accessing `topic`
+    pub fn find_matching(&self, principal: Option<&str>, topic: &str) -> Vec<Quota> {
+        self.read()
+            .values()
+            .filter(|quota| quota.spec.is_match(principal, topic))
+            .cloned()
+            .collect()
+    }
+}
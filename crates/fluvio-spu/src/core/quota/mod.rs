@@ -0,0 +1,9 @@
+mod metadata;
+mod throttle;
+
+pub use self::metadata::QuotaLocalStore;
+pub use self::throttle::{QuotaThrottles, QuotaRate, QuotaClient};
+
+use std::sync::Arc;
+
+pub type SharedQuotaLocalStore = Arc<QuotaLocalStore>;
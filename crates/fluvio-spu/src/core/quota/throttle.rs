@@ -0,0 +1,314 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use fluvio_controlplane::spu_api::update_quota::Quota;
+use fluvio_controlplane_metadata::quota::QuotaSpec;
+
+/// clients are never throttled longer than this, remaining debt is throttled by next requests
+const MAX_THROTTLE: Duration = Duration::from_secs(10);
+/// how often buckets of idle clients are removed
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// client whose usage is limited by quotas
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum QuotaClient {
+    /// client with principal known to SPU
+    Principal(String),
+    /// client without principal, identified by its host
+    Anonymous(String),
+}
+
+impl QuotaClient {
+    /// anonymous client connected from `peer` address
+    pub fn anonymous(peer: &str) -> Self {
+        let host = peer
+            .parse::<std::net::SocketAddr>()
+            .map(|addr| addr.ip().to_string())
+            .unwrap_or_else(|_| peer.to_owned());
+        Self::Anonymous(host)
+    }
+
+    /// principal matched by quotas
+    pub fn principal(&self) -> Option<&str> {
+        match self {
+            Self::Principal(principal) => Some(principal),
+            Self::Anonymous(_) => None,
+        }
+    }
+}
+
+/// rate limited by quota
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QuotaRate {
+    ProducerBytes,
+    ConsumerBytes,
+    Requests,
+}
+
+impl QuotaRate {
+    fn limit(&self, spec: &QuotaSpec) -> Option<u64> {
+        match self {
+            Self::ProducerBytes => spec.producer_byte_rate,
+            Self::ConsumerBytes => spec.consumer_byte_rate,
+            Self::Requests => spec.request_rate,
+        }
+    }
+}
+
+/// Token bucket refilled at rate of the quota, holding at most one second of it.
+/// Usage above available tokens is debt which client must wait out.
+/// Quota with zero rate is never refilled, so any usage is throttled for max time.
+#[derive(Debug)]
+struct RateBucket {
+    available: f64,
+    limit: f64,
+    updated: Instant,
+}
+
+impl RateBucket {
+    fn new(limit: u64, now: Instant) -> Self {
+        Self {
+            available: limit as f64,
+            limit: limit as f64,
+            updated: now,
+        }
+    }
+
+    fn record(&mut self, limit: u64, amount: u64, now: Instant) -> Duration {
+        self.limit = limit as f64;
+        self.available = self.available_at(now);
+        self.updated = now;
+        self.available -= amount as f64;
+        if self.available >= 0.0 {
+            Duration::ZERO
+        } else {
+            // debt of zero rate is infinite
+            Duration::try_from_secs_f64(-self.available / self.limit)
+                .map_or(MAX_THROTTLE, |throttle| throttle.min(MAX_THROTTLE))
+        }
+    }
+
+    fn available_at(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        (self.available + elapsed * self.limit).min(self.limit)
+    }
+
+    /// bucket refilled since last usage is same as new one
+    fn is_full(&self, now: Instant) -> bool {
+        self.available_at(now) >= self.limit
+    }
+}
+
+/// quota name, client and rate
+type BucketKey = (String, QuotaClient, QuotaRate);
+
+#[derive(Debug, Default)]
+struct Buckets {
+    buckets: HashMap<BucketKey, RateBucket>,
+    evicted: Option<Instant>,
+}
+
+impl Buckets {
+    /// remove buckets of clients which are idle long enough to be refilled
+    fn evict_idle(&mut self, now: Instant) {
+        let evicted = *self.evicted.get_or_insert(now);
+        if now.saturating_duration_since(evicted) < EVICTION_INTERVAL {
+            return;
+        }
+        self.buckets.retain(|_, bucket| !bucket.is_full(now));
+        self.evicted = Some(now);
+    }
+}
+
+/// Usage of quotas by clients.
+/// Each client has its own bucket in every quota matching it.
+#[derive(Debug, Default)]
+pub struct QuotaThrottles {
+    buckets: Mutex<Buckets>,
+}
+
+impl QuotaThrottles {
+    /// record `amount` of `rate` used by client in `quotas`,
+    /// returns how long client must be throttled to stay within all of them
+    pub fn record(
+        &self,
+        quotas: &[Quota],
+        client: &QuotaClient,
+        rate: QuotaRate,
+        amount: u64,
+    ) -> Duration {
+        self.record_at(quotas, client, rate, amount, Instant::now())
+    }
+
+    fn record_at(
+        &self,
+        quotas: &[Quota],
+        client: &QuotaClient,
+        rate: QuotaRate,
+        amount: u64,
+        now: Instant,
+    ) -> Duration {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|err| err.into_inner());
+        buckets.evict_idle(now);
+        let mut throttle = Duration::ZERO;
+        for quota in quotas {
+            let Some(limit) = rate.limit(&quota.spec) else {
+                continue;
+            };
+            let key = (quota.name.clone(), client.clone(), rate);
+            let bucket = buckets
+                .buckets
+                .entry(key)
+                .or_insert_with(|| RateBucket::new(limit, now));
+            throttle = throttle.max(bucket.record(limit, amount, now));
+        }
+        throttle
+    }
+
+    /// forget usage of changed or deleted quota
+    pub fn remove_quota(&self, name: &str) {
+        self.buckets
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .buckets
+            .retain(|(quota, _, _), _| quota != name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use fluvio_controlplane::spu_api::update_quota::Quota;
+    use fluvio_controlplane_metadata::quota::QuotaSpec;
+
+    use super::{QuotaClient, QuotaThrottles, QuotaRate};
+
+    fn quota(name: &str, producer_byte_rate: u64) -> Quota {
+        Quota {
+            name: name.to_owned(),
+            spec: QuotaSpec {
+                producer_byte_rate: Some(producer_byte_rate),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn principal(name: &str) -> QuotaClient {
+        QuotaClient::Principal(name.to_owned())
+    }
+
+    #[test]
+    fn test_throttle_over_quota() {
+        let throttles = QuotaThrottles::default();
+        let quotas = vec![quota("q1", 1000)];
+        let anonymous = QuotaClient::anonymous("127.0.0.1:5000");
+        let now = Instant::now();
+
+        // burst of one second is allowed
+        let throttle =
+            throttles.record_at(&quotas, &anonymous, QuotaRate::ProducerBytes, 1000, now);
+        assert_eq!(throttle, Duration::ZERO);
+
+        let throttle = throttles.record_at(&quotas, &anonymous, QuotaRate::ProducerBytes, 500, now);
+        assert_eq!(throttle, Duration::from_millis(500));
+
+        // debt is repaid after throttle time
+        let later = now + Duration::from_millis(500);
+        let throttle = throttles.record_at(&quotas, &anonymous, QuotaRate::ProducerBytes, 0, later);
+        assert_eq!(throttle, Duration::ZERO);
+
+        // other rates are not limited by quota
+        let throttle =
+            throttles.record_at(&quotas, &anonymous, QuotaRate::ConsumerBytes, 5000, later);
+        assert_eq!(throttle, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_throttle_per_principal_and_quota() {
+        let throttles = QuotaThrottles::default();
+        let quotas = vec![quota("q1", 1000), quota("q2", 100)];
+        let alice = principal("alice");
+        let bob = principal("bob");
+        let now = Instant::now();
+
+        // strictest quota wins
+        let throttle = throttles.record_at(&quotas, &alice, QuotaRate::ProducerBytes, 200, now);
+        assert_eq!(throttle, Duration::from_secs(1));
+
+        // other clients have own budget
+        let throttle = throttles.record_at(&quotas, &bob, QuotaRate::ProducerBytes, 100, now);
+        assert_eq!(throttle, Duration::ZERO);
+
+        // throttle is capped
+        let throttle = throttles.record_at(&quotas, &bob, QuotaRate::ProducerBytes, 100_000, now);
+        assert_eq!(throttle, super::MAX_THROTTLE);
+
+        // removed quota starts with new budget
+        throttles.remove_quota("q1");
+        let throttle = throttles.record_at(&quotas[..1], &bob, QuotaRate::ProducerBytes, 0, now);
+        assert_eq!(throttle, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_throttle_zero_quota() {
+        let throttles = QuotaThrottles::default();
+        let quotas = vec![quota("q1", 0)];
+        let alice = principal("alice");
+        let now = Instant::now();
+
+        let throttle = throttles.record_at(&quotas, &alice, QuotaRate::ProducerBytes, 0, now);
+        assert_eq!(throttle, Duration::ZERO);
+
+        let throttle = throttles.record_at(&quotas, &alice, QuotaRate::ProducerBytes, 1, now);
+        assert_eq!(throttle, super::MAX_THROTTLE);
+
+        let later = now + super::MAX_THROTTLE;
+        let throttle = throttles.record_at(&quotas, &alice, QuotaRate::ProducerBytes, 1, later);
+        assert_eq!(throttle, super::MAX_THROTTLE);
+    }
+
+    #[test]
+    fn test_throttle_anonymous_per_host() {
+        let throttles = QuotaThrottles::default();
+        let quotas = vec![quota("q1", 1000)];
+        let now = Instant::now();
+
+        let client1 = QuotaClient::anonymous("10.0.0.1:5000");
+        let throttle = throttles.record_at(&quotas, &client1, QuotaRate::ProducerBytes, 1500, now);
+        assert_eq!(throttle, Duration::from_millis(500));
+
+        // other connection of same host shares budget
+        let client1 = QuotaClient::anonymous("10.0.0.1:5001");
+        let throttle = throttles.record_at(&quotas, &client1, QuotaRate::ProducerBytes, 500, now);
+        assert_eq!(throttle, Duration::from_secs(1));
+
+        // other hosts have own budget
+        let client2 = QuotaClient::anonymous("10.0.0.2:5000");
+        let throttle = throttles.record_at(&quotas, &client2, QuotaRate::ProducerBytes, 500, now);
+        assert_eq!(throttle, Duration::ZERO);
+    }
+
+    #[test]
+    fn test_evict_idle_buckets() {
+        let throttles = QuotaThrottles::default();
+        let quotas = vec![quota("q1", 1000)];
+        let alice = principal("alice");
+        let bob = principal("bob");
+        let now = Instant::now();
+
+        throttles.record_at(&quotas, &alice, QuotaRate::ProducerBytes, 100, now);
+        // bob is in debt longer than eviction interval
+        throttles.record_at(&quotas, &bob, QuotaRate::ProducerBytes, 100_000_000, now);
+        assert_eq!(throttles.buckets.lock().unwrap().buckets.len(), 2);
+
+        let later = now + super::EVICTION_INTERVAL;
+        throttles.record_at(&[], &alice, QuotaRate::ProducerBytes, 0, later);
+        let buckets = throttles.buckets.lock().unwrap();
+        assert_eq!(buckets.buckets.len(), 1);
+        assert!(buckets
+            .buckets
+            .contains_key(&("q1".to_owned(), bob, QuotaRate::ProducerBytes)));
+    }
+}
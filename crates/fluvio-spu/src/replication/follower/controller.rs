@@ -89,7 +89,7 @@ mod inner {
     use fluvio_protocol::record::ReplicaKey;
     use fluvio_protocol::api::RequestMessage;
     use fluvio_types::SpuId;
-    use fluvio_storage::FileReplica;
+    use fluvio_storage::{FileReplica, ReplicaStorage};
     use fluvio_controlplane_metadata::spu::SpuSpec;
 
     use crate::{replication::leader::UpdateOffsetRequest, core::SharedSpuConfig};
@@ -281,17 +281,46 @@ mod inner {
                     base_offset = p.records.base_offset(),
                     "update from leader");
                     if let Some(replica) = self.states.get(&replica_key).await {
-                        match replica.update_from_leader(&mut p.records, p.hw).await {
-                            Ok(changes) => {
-                                if changes {
-                                    debug!("changes occur, need to send back offset");
-                                    offsets.replicas.push(replica.as_offset_request());
-                                } else {
-                                    debug!("no changes");
+                        // leader was elected while this replica was ahead of it,
+                        // records beyond leader's leo are discarded and synced again
+                        if p.leo >= 0 && replica.leo() > p.leo {
+                            match self.truncate_replica(&replica, p.leo).await {
+                                Ok(()) => offsets.replicas.push(replica.as_offset_request()),
+                                Err(err) => {
+                                    error!("problem truncating {}, error: {:#?}", replica_key, err)
                                 }
                             }
-                            Err(err) => {
-                                error!("problem updating {}, error: {:#?}", replica_key, err)
+                        } else {
+                            match replica.update_from_leader(&mut p.records, p.hw).await {
+                                Ok(changes) => {
+                                    if changes {
+                                        debug!("changes occur, need to send back offset");
+                                        offsets.replicas.push(replica.as_offset_request());
+                                    } else {
+                                        debug!("no changes");
+                                    }
+                                }
+                                Err(err) => {
+                                    error!("problem updating {}, error: {:#?}", replica_key, err)
+                                }
+                            }
+                        }
+                        // consumer offsets are replicated so they survive leader change
+                        if let Some(consumer_offsets) = p.consumer_offsets.take() {
+                            let consumer_offsets = consumer_offsets
+                                .into_iter()
+                                .map(|offset| (offset.consumer_group, offset.offset))
+                                .collect();
+                            if let Err(err) = replica
+                                .write()
+                                .await
+                                .sync_consumer_offsets(consumer_offsets)
+                                .await
+                            {
+                                error!(
+                                    "problem syncing consumer offsets {}, error: {:#?}",
+                                    replica_key, err
+                                )
                             }
                         }
                     } else {
@@ -310,6 +339,21 @@ mod inner {
             }
         }
 
+        /// discard records of follower replica beyond leader's leo, so they are synced again from leader
+        async fn truncate_replica(
+            &self,
+            replica: &FollowerReplicaState<FileReplica>,
+            leader_leo: i64,
+        ) -> anyhow::Result<()> {
+            warn!(
+                replica = %replica.id(),
+                follower_leo = replica.leo(),
+                leader_leo,
+                "follower is ahead of leader, discarding records"
+            );
+            replica.truncate(leader_leo).await
+        }
+
         /// connect to leader, if can't connect try until we succeed
         /// or if we received termination message
         async fn create_socket_to_leader(
@@ -50,14 +50,14 @@ where
 }
 
 // Request trait
-// Note that DEFAULT_API_VERSION is 7 which is required in order to map all fields for file encoding
+// Note that DEFAULT_API_VERSION is 8 which is required in order to map all fields for file encoding
 // TODO: come up with unify encoding
 impl<R> Request for SyncRequest<R>
 where
     R: Encoder + Decoder + Debug,
 {
     const API_KEY: u16 = FollowerPeerApiEnum::SyncRecords as u16;
-    const DEFAULT_API_VERSION: i16 = 7;
+    const DEFAULT_API_VERSION: i16 = 8;
     type Response = SyncResponse;
 }
 
@@ -111,6 +111,15 @@ where
     pub hw: i64,
     pub leo: i64,
     pub records: R,
+    /// snapshot of consumer offsets committed on leader, only sent when they changed
+    #[fluvio(min_version = 8)]
+    pub consumer_offsets: Option<Vec<PeerConsumerOffset>>,
+}
+
+#[derive(Encoder, Decoder, Default, Debug, Clone, PartialEq, Eq)]
+pub struct PeerConsumerOffset {
+    pub consumer_group: String,
+    pub offset: i64,
 }
 
 impl<R> fmt::Display for PeerFetchablePartitionResponse<R>
@@ -153,6 +162,9 @@ impl FileWrite for PeerFilePartitionResponse {
         self.hw.encode(src, version)?;
         self.leo.encode(src, version)?;
         self.records.file_encode(src, data, version)?;
+        if version >= 8 {
+            self.consumer_offsets.encode(src, version)?;
+        }
         Ok(())
     }
 }
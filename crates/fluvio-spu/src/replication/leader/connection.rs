@@ -49,10 +49,38 @@ impl FollowerHandler {
             ctx: ctx.clone(),
             max_bytes: ctx.config().peer_max_bytes,
             follower_id,
-            spu_update,
+            spu_update: spu_update.clone(),
         };
 
+        spu_update.connected();
+        Self::send_consumer_offsets(&ctx, follower_id).await;
         connection.dispatch(sink, stream).await;
+        spu_update.disconnected();
+
+        if spu_update.is_offline() {
+            Self::update_leaders_without_follower(&ctx, follower_id).await;
+        }
+    }
+
+    /// follower may have missed consumer offsets committed while it was disconnected
+    async fn send_consumer_offsets(ctx: &DefaultSharedGlobalContext, follower_id: SpuId) {
+        let leaders: Vec<_> = ctx.leaders_state().read().await.values().cloned().collect();
+        for leader in leaders {
+            leader.send_consumer_offsets(follower_id).await;
+        }
+    }
+
+    /// offline follower is no longer in sync, leaders it follows may commit records without it
+    #[instrument(skip(ctx))]
+    async fn update_leaders_without_follower(ctx: &DefaultSharedGlobalContext, follower_id: SpuId) {
+        let leaders: Vec<_> = ctx.leaders_state().read().await.values().cloned().collect();
+        for leader in leaders {
+            if leader.live_replicas().await.contains(&follower_id) {
+                leader
+                    .update_hw_from_in_sync_followers(ctx.follower_notifier())
+                    .await;
+            }
+        }
     }
 
     #[instrument(name = "LeaderConnection", skip(stream))]
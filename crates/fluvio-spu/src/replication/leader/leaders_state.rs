@@ -65,6 +65,17 @@ impl<S> ReplicaLeadersState<S> {
 }
 
 impl ReplicaLeadersState<FileReplica> {
+    /// update replicas of existing leader, returns false if there is no such leader
+    pub async fn update_replica(&self, replica: Replica) -> bool {
+        let leader = self.read().await.get(&replica.id).cloned();
+        if let Some(leader) = leader {
+            leader.update_replica(replica).await;
+            true
+        } else {
+            false
+        }
+    }
+
     #[instrument(
         skip(self, ctx,replica,status_update),
         fields(replica = %replica.id)
@@ -80,7 +91,10 @@ impl ReplicaLeadersState<FileReplica> {
         let leader_replica =
             LeaderReplicaState::create(replica, ctx.config(), status_update).await?;
         let leader_replica = leader_replica.init(ctx).await?;
-        self.insert_leader(replica_id, leader_replica.clone()).await;
+        self.insert_leader(replica_id.clone(), leader_replica.clone())
+            .await;
+        ctx.join_tables()
+            .set_leader(&replica_id, Some(leader_replica.clone()));
         Ok(leader_replica)
     }
 
@@ -119,7 +133,9 @@ impl ReplicaLeadersState<FileReplica> {
         let replica_storage = follower.inner_owned();
         let leader = LeaderReplicaState::new(replica, config, status_update, replica_storage);
         let leader = leader.init(ctx).await?;
-        self.insert_leader(replica_id, leader.clone()).await;
+        self.insert_leader(replica_id.clone(), leader.clone()).await;
+        ctx.join_tables()
+            .set_leader(&replica_id, Some(leader.clone()));
         Ok(leader)
     }
 }
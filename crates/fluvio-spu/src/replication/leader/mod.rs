@@ -6,6 +6,8 @@ mod peer_api;
 mod update_offsets;
 mod actions;
 mod spu;
+mod producer_state;
+mod transaction_state;
 
 pub use self::leaders_state::{ReplicaLeadersState, SharedReplicaLeadersState};
 pub use self::replica_state::{SharedFileLeaderState, SharedLeaderState, LeaderReplicaState};
@@ -16,3 +18,5 @@ pub use self::update_offsets::UpdateOffsetRequest;
 pub use self::update_offsets::ReplicaOffsetRequest;
 pub use self::actions::FollowerOffsetUpdate;
 pub use self::spu::*;
+pub use self::producer_state::ProducerStates;
+pub use self::transaction_state::ProducedTransaction;
@@ -0,0 +1,556 @@
+//!
+//! # Idempotent Producer State
+//!
+//! Leader tracks epoch and sequence numbers of batches written by idempotent producers,
+//! so batches resent by retries are skipped and batches sent out of order are rejected.
+//! State is kept in memory of the leader and snapshotted in replica storage.
+//! After leader is changed, it's rebuilt from the snapshot and batches written to the log after it.
+//!
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_lock::Mutex;
+use tracing::debug;
+
+use fluvio_protocol::{Encoder, Decoder};
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::{Batch, Offset, RawRecords, RecordSet};
+
+/// number of latest batches per producer remembered to answer duplicates with their offset
+const MAX_TRACKED_BATCHES: usize = 16;
+
+/// producers without batches for this long are forgotten
+pub(crate) const PRODUCER_EXPIRATION: Duration = Duration::from_secs(3600);
+
+pub type SharedProducerStates = Arc<Mutex<ProducerStates>>;
+
+/// Producer sequence numbers of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProducerBatch {
+    producer_id: i64,
+    epoch: i16,
+    first_sequence: i32,
+    last_sequence: i32,
+}
+
+impl ProducerBatch {
+    /// None if batch is not sent by idempotent producer
+    fn from_batch<R>(batch: &Batch<R>) -> Option<Self> {
+        let header = batch.get_header();
+        if header.producer_id < 0 || header.first_sequence < 0 {
+            return None;
+        }
+        Some(Self {
+            producer_id: header.producer_id,
+            epoch: header.producer_epoch,
+            first_sequence: header.first_sequence,
+            last_sequence: header.first_sequence + batch.records_len().max(1) as i32 - 1,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum SequenceCheck {
+    Append,
+    /// batch is already written, offset is None if batch is too old to be tracked
+    Duplicate(Option<Offset>),
+    Rejected(ErrorCode),
+}
+
+#[derive(Debug, Default, Clone, Encoder, Decoder)]
+struct WrittenBatch {
+    first_sequence: i32,
+    last_sequence: i32,
+    base_offset: Offset,
+}
+
+#[derive(Debug)]
+struct ProducerState {
+    epoch: i16,
+    last_sequence: i32,
+    batches: VecDeque<WrittenBatch>,
+    last_update: Instant,
+}
+
+/// State of producer persisted in snapshot
+#[derive(Debug, Default, Encoder, Decoder)]
+pub struct SnapshotProducer {
+    producer_id: i64,
+    epoch: i16,
+    last_sequence: i32,
+    batches: Vec<WrittenBatch>,
+    /// milliseconds since last batch of producer when snapshot was taken
+    idle_ms: u64,
+}
+
+/// Batch accepted by sequence check with its offset relative to base offset of the record set
+#[derive(Debug)]
+struct StagedBatch {
+    batch: ProducerBatch,
+    relative_offset: Offset,
+}
+
+/// Batches of record set accepted by sequence check, recorded in state once record set is written
+#[derive(Debug, Default)]
+pub struct StagedBatches {
+    batches: Vec<StagedBatch>,
+    /// offset of the first duplicated batch removed from the record set,
+    /// error if the batch is too old to be tracked
+    pub duplicate: Option<Result<Offset, ErrorCode>>,
+}
+
+impl StagedBatches {
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    /// batches are replaced by a single batch, e.g. output of smartmodules,
+    /// so all of them are written at base offset of the record set
+    pub fn merge_offsets(&mut self) {
+        for staged in self.batches.iter_mut() {
+            staged.relative_offset = 0;
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ProducerStates {
+    producers: HashMap<i64, ProducerState>,
+}
+
+impl ProducerStates {
+    pub fn shared() -> SharedProducerStates {
+        Arc::new(Mutex::new(Self::default()))
+    }
+
+    /// Check sequences of batches from idempotent producers.
+    /// Duplicated batches are removed from the record set.
+    pub fn check_record_set(
+        &self,
+        records: &mut RecordSet<RawRecords>,
+    ) -> Result<StagedBatches, ErrorCode> {
+        let mut staged = StagedBatches::default();
+        let mut batches = Vec::with_capacity(records.batches.len());
+        let mut relative_offset = 0;
+        for batch in records.batches.drain(..) {
+            let Some(producer_batch) = ProducerBatch::from_batch(&batch) else {
+                relative_offset += batch.records_len() as Offset;
+                batches.push(batch);
+                continue;
+            };
+            match self.check(&producer_batch, &staged.batches) {
+                SequenceCheck::Append => {
+                    staged.batches.push(StagedBatch {
+                        batch: producer_batch,
+                        relative_offset,
+                    });
+                    relative_offset += batch.records_len() as Offset;
+                    batches.push(batch);
+                }
+                SequenceCheck::Duplicate(offset) => {
+                    debug!(?producer_batch, ?offset, "duplicate batch");
+                    staged
+                        .duplicate
+                        .get_or_insert(offset.ok_or(ErrorCode::DuplicateSequenceNumber));
+                }
+                SequenceCheck::Rejected(error_code) => {
+                    debug!(?producer_batch, %error_code, "batch rejected");
+                    return Err(error_code);
+                }
+            }
+        }
+        records.batches = batches;
+        Ok(staged)
+    }
+
+    /// true if record set has batches of idempotent producers
+    pub fn has_producer_batches(records: &RecordSet<RawRecords>) -> bool {
+        records
+            .batches
+            .iter()
+            .any(|batch| ProducerBatch::from_batch(batch).is_some())
+    }
+
+    /// Check staged batches again before write when state was unlocked after `check_record_set`,
+    /// other record sets of the producer may have been written meanwhile.
+    /// None if batches can be written, otherwise offset of already written batches or error
+    pub fn recheck(&self, staged: &StagedBatches) -> Option<Result<Offset, ErrorCode>> {
+        for (index, staged_batch) in staged.batches.iter().enumerate() {
+            match self.check(&staged_batch.batch, &staged.batches[..index]) {
+                SequenceCheck::Append => {}
+                SequenceCheck::Duplicate(offset) => {
+                    return Some(offset.ok_or(ErrorCode::DuplicateSequenceNumber))
+                }
+                SequenceCheck::Rejected(error_code) => return Some(Err(error_code)),
+            }
+        }
+        None
+    }
+
+    /// record batches of record set which was written at `base_offset`
+    pub fn update(&mut self, staged: StagedBatches, base_offset: Offset) {
+        let now = Instant::now();
+        for StagedBatch {
+            batch,
+            relative_offset,
+        } in staged.batches
+        {
+            self.record(batch, base_offset + relative_offset, now);
+        }
+        self.producers
+            .retain(|_, state| now.duration_since(state.last_update) < PRODUCER_EXPIRATION);
+    }
+
+    /// producers to persist in snapshot
+    pub fn snapshot(&self) -> Vec<SnapshotProducer> {
+        let now = Instant::now();
+        self.producers
+            .iter()
+            .map(|(producer_id, state)| SnapshotProducer {
+                producer_id: *producer_id,
+                epoch: state.epoch,
+                last_sequence: state.last_sequence,
+                batches: state.batches.iter().cloned().collect(),
+                idle_ms: u64::try_from(now.duration_since(state.last_update).as_millis())
+                    .unwrap_or(u64::MAX),
+            })
+            .collect()
+    }
+
+    /// replace state with producers of snapshot taken `age` ago, expired producers are skipped
+    pub fn load_snapshot(&mut self, producers: Vec<SnapshotProducer>, age: Duration) {
+        let now = Instant::now();
+        self.producers = producers
+            .into_iter()
+            .filter_map(|producer| {
+                let idle = age.saturating_add(Duration::from_millis(producer.idle_ms));
+                if idle >= PRODUCER_EXPIRATION {
+                    return None;
+                }
+                let state = ProducerState {
+                    epoch: producer.epoch,
+                    last_sequence: producer.last_sequence,
+                    batches: producer.batches.into(),
+                    last_update: now.checked_sub(idle)?,
+                };
+                Some((producer.producer_id, state))
+            })
+            .collect();
+    }
+
+    /// record batch read from the log, used to rebuild state after leader is changed
+    pub fn restore<R>(&mut self, batch: &Batch<R>) {
+        if let Some(producer_batch) = ProducerBatch::from_batch(batch) {
+            self.record(producer_batch, batch.get_base_offset(), Instant::now());
+        }
+    }
+
+    fn record(&mut self, batch: ProducerBatch, base_offset: Offset, now: Instant) {
+        let state = self
+            .producers
+            .entry(batch.producer_id)
+            .or_insert_with(|| ProducerState {
+                epoch: batch.epoch,
+                last_sequence: batch.last_sequence,
+                batches: VecDeque::new(),
+                last_update: now,
+            });
+        if state.epoch != batch.epoch {
+            state.epoch = batch.epoch;
+            state.batches.clear();
+        }
+        state.last_sequence = batch.last_sequence;
+        state.last_update = now;
+        state.batches.push_back(WrittenBatch {
+            first_sequence: batch.first_sequence,
+            last_sequence: batch.last_sequence,
+            base_offset,
+        });
+        if state.batches.len() > MAX_TRACKED_BATCHES {
+            state.batches.pop_front();
+        }
+    }
+
+    /// check batch against written batches and batches staged before it in the same record set
+    fn check(&self, batch: &ProducerBatch, staged: &[StagedBatch]) -> SequenceCheck {
+        let state = self.producers.get(&batch.producer_id);
+        let current = staged
+            .iter()
+            .rev()
+            .map(|staged| &staged.batch)
+            .find(|staged| staged.producer_id == batch.producer_id)
+            .map(|staged| (staged.epoch, staged.last_sequence))
+            .or_else(|| state.map(|state| (state.epoch, state.last_sequence)));
+
+        // unknown producer, accept any sequence
+        let Some((epoch, last_sequence)) = current else {
+            return SequenceCheck::Append;
+        };
+
+        match batch.epoch.cmp(&epoch) {
+            Ordering::Less => SequenceCheck::Rejected(ErrorCode::InvalidProducerEpoch),
+            Ordering::Greater if batch.first_sequence == 0 => SequenceCheck::Append,
+            Ordering::Greater => SequenceCheck::Rejected(ErrorCode::OutOfOrderSequenceNumber),
+            Ordering::Equal if last_sequence.checked_add(1) == Some(batch.first_sequence) => {
+                SequenceCheck::Append
+            }
+            Ordering::Equal if batch.first_sequence <= last_sequence => {
+                let offset = state
+                    .filter(|state| state.epoch == batch.epoch)
+                    .and_then(|state| {
+                        state.batches.iter().find(|written| {
+                            written.first_sequence == batch.first_sequence
+                                && written.last_sequence == batch.last_sequence
+                        })
+                    })
+                    .map(|written| written.base_offset);
+                SequenceCheck::Duplicate(offset)
+            }
+            Ordering::Equal => SequenceCheck::Rejected(ErrorCode::OutOfOrderSequenceNumber),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use fluvio_protocol::record::Record;
+
+    use super::*;
+
+    fn producer_batch(
+        producer_id: i64,
+        epoch: i16,
+        first_sequence: i32,
+        records: usize,
+    ) -> Batch<RawRecords> {
+        let mut batch = Batch::default();
+        for _ in 0..records {
+            batch.add_record(Record::new("value"));
+        }
+        let header = batch.get_mut_header();
+        header.producer_id = producer_id;
+        header.producer_epoch = epoch;
+        header.first_sequence = first_sequence;
+        batch.try_into().expect("raw batch")
+    }
+
+    fn record_set(batches: Vec<Batch<RawRecords>>) -> RecordSet<RawRecords> {
+        RecordSet { batches }
+    }
+
+    #[test]
+    fn test_sequences_in_order() {
+        let mut producers = ProducerStates::default();
+
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 2), producer_batch(1, 0, 2, 3)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert_eq!(records.batches.len(), 2);
+        assert_eq!(staged.duplicate, None);
+        producers.update(staged, 10);
+
+        let mut records = record_set(vec![producer_batch(1, 0, 5, 1)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert_eq!(records.batches.len(), 1);
+        producers.update(staged, 15);
+    }
+
+    #[test]
+    fn test_duplicate_batch_skipped() {
+        let mut producers = ProducerStates::default();
+
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 2)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        producers.update(staged, 10);
+
+        // retry of the same batch
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 2)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert!(records.batches.is_empty());
+        assert_eq!(staged.duplicate, Some(Ok(10)));
+
+        // batches from other producers are not affected
+        let mut records = record_set(vec![
+            producer_batch(2, 0, 0, 2),
+            producer_batch(-1, -1, -1, 1),
+        ]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert_eq!(records.batches.len(), 2);
+        assert_eq!(staged.duplicate, None);
+    }
+
+    #[test]
+    fn test_duplicate_batches_offsets() {
+        let mut producers = ProducerStates::default();
+
+        let mut records = record_set(vec![
+            producer_batch(-1, -1, -1, 1),
+            producer_batch(1, 0, 0, 2),
+            producer_batch(1, 0, 2, 3),
+        ]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        producers.update(staged, 10);
+
+        // each batch is answered with its own offset
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 2)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert_eq!(staged.duplicate, Some(Ok(11)));
+        let mut records = record_set(vec![producer_batch(1, 0, 2, 3)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert_eq!(staged.duplicate, Some(Ok(13)));
+
+        // batches replaced by smartmodule output are written at the same offset
+        let mut records = record_set(vec![producer_batch(1, 0, 5, 1), producer_batch(1, 0, 6, 1)]);
+        let mut staged = producers.check_record_set(&mut records).expect("check");
+        staged.merge_offsets();
+        producers.update(staged, 20);
+        let mut records = record_set(vec![producer_batch(1, 0, 6, 1)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert_eq!(staged.duplicate, Some(Ok(20)));
+    }
+
+    #[test]
+    fn test_recheck_staged_batches() {
+        let mut producers = ProducerStates::default();
+
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 2), producer_batch(1, 0, 2, 1)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert_eq!(producers.recheck(&staged), None);
+
+        // same batches written by other request while state was unlocked
+        let mut other = record_set(vec![producer_batch(1, 0, 0, 2), producer_batch(1, 0, 2, 1)]);
+        let other_staged = producers.check_record_set(&mut other).expect("check");
+        producers.update(other_staged, 10);
+        assert_eq!(producers.recheck(&staged), Some(Ok(10)));
+
+        // sequence moved past staged batches
+        let mut records = record_set(vec![producer_batch(1, 0, 3, 1)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        let mut other = record_set(vec![producer_batch(1, 0, 3, 2)]);
+        let other_staged = producers.check_record_set(&mut other).expect("check");
+        producers.update(other_staged, 13);
+        assert_eq!(
+            producers.recheck(&staged),
+            Some(Err(ErrorCode::DuplicateSequenceNumber))
+        );
+    }
+
+    #[test]
+    fn test_restore_from_log() {
+        let mut producers = ProducerStates::default();
+
+        let mut batch = producer_batch(1, 0, 0, 2);
+        batch.set_base_offset(20);
+        producers.restore(&batch);
+        producers.restore(&producer_batch(-1, -1, -1, 1));
+
+        // retry sent to the previous leader is detected as duplicate
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 2)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert!(records.batches.is_empty());
+        assert_eq!(staged.duplicate, Some(Ok(20)));
+
+        let mut records = record_set(vec![producer_batch(1, 0, 3, 1)]);
+        assert_eq!(
+            producers.check_record_set(&mut records).unwrap_err(),
+            ErrorCode::OutOfOrderSequenceNumber
+        );
+    }
+
+    #[test]
+    fn test_restore_from_snapshot() {
+        let mut producers = ProducerStates::default();
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 2)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        producers.update(staged, 10);
+        let mut records = record_set(vec![producer_batch(2, 0, 0, 1)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        producers.update(staged, 12);
+
+        let mut snapshot = Vec::new();
+        producers
+            .snapshot()
+            .encode(&mut snapshot, 0)
+            .expect("encode");
+        let decoded: Vec<SnapshotProducer> =
+            Decoder::decode_from(&mut snapshot.as_slice(), 0).expect("decode");
+
+        let mut restored = ProducerStates::default();
+        restored.load_snapshot(decoded, Duration::from_secs(1));
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 2)]);
+        let staged = restored.check_record_set(&mut records).expect("check");
+        assert_eq!(staged.duplicate, Some(Ok(10)));
+        let mut records = record_set(vec![producer_batch(2, 0, 1, 1)]);
+        restored.check_record_set(&mut records).expect("check");
+        assert_eq!(records.batches.len(), 1);
+
+        // producers expired since snapshot are forgotten
+        let mut expired = ProducerStates::default();
+        expired.load_snapshot(producers.snapshot(), PRODUCER_EXPIRATION);
+        assert!(expired.producers.is_empty());
+    }
+
+    #[test]
+    fn test_untracked_duplicate() {
+        let mut producers = ProducerStates::default();
+
+        for sequence in 0..=MAX_TRACKED_BATCHES as i32 {
+            let mut records = record_set(vec![producer_batch(1, 0, sequence, 1)]);
+            let staged = producers.check_record_set(&mut records).expect("check");
+            producers.update(staged, sequence as Offset);
+        }
+
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 1)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert!(records.batches.is_empty());
+        assert_eq!(
+            staged.duplicate,
+            Some(Err(ErrorCode::DuplicateSequenceNumber))
+        );
+    }
+
+    #[test]
+    fn test_out_of_order_rejected() {
+        let mut producers = ProducerStates::default();
+
+        let mut records = record_set(vec![producer_batch(1, 0, 0, 2)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        producers.update(staged, 10);
+
+        let mut records = record_set(vec![producer_batch(1, 0, 5, 1)]);
+        assert_eq!(
+            producers.check_record_set(&mut records).unwrap_err(),
+            ErrorCode::OutOfOrderSequenceNumber
+        );
+    }
+
+    #[test]
+    fn test_producer_epoch() {
+        let mut producers = ProducerStates::default();
+
+        let mut records = record_set(vec![producer_batch(1, 1, 0, 2)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        producers.update(staged, 10);
+
+        // older epoch is fenced
+        let mut records = record_set(vec![producer_batch(1, 0, 2, 1)]);
+        assert_eq!(
+            producers.check_record_set(&mut records).unwrap_err(),
+            ErrorCode::InvalidProducerEpoch
+        );
+
+        // new epoch must start from sequence 0
+        let mut records = record_set(vec![producer_batch(1, 2, 2, 1)]);
+        assert_eq!(
+            producers.check_record_set(&mut records).unwrap_err(),
+            ErrorCode::OutOfOrderSequenceNumber
+        );
+        let mut records = record_set(vec![producer_batch(1, 2, 0, 1)]);
+        let staged = producers.check_record_set(&mut records).expect("check");
+        assert_eq!(records.batches.len(), 1);
+        producers.update(staged, 12);
+    }
+}
@@ -2,62 +2,131 @@ use std::{
     cmp::{min, Reverse},
     collections::{BTreeMap, HashSet, BinaryHeap},
     ops::{Deref, DerefMut},
-    sync::Arc,
+    sync::{Arc, RwLock as StdRwLock, RwLockReadGuard as StdRwLockReadGuard},
+    sync::atomic::{AtomicI64, Ordering as AtomicOrdering},
+    time::{Duration, Instant},
 };
 use std::iter::FromIterator;
+use std::io::Cursor;
 use std::fmt;
 
 use fluvio_controlplane::{replica::Replica, sc_api::update_lrs::LrsRequest};
-use tracing::{debug, error, warn};
+use tracing::{debug, error, trace, warn};
 use tracing::instrument;
 use async_rwlock::RwLock;
 use anyhow::{Result, Context};
-
-use fluvio_protocol::record::{RecordSet, Offset, ReplicaKey, RawRecords, Batch};
+use chrono::Utc;
+use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
+use tokio::select;
+
+use fluvio_protocol::{Encoder, Decoder};
+use fluvio_protocol::api::RequestKind;
+use fluvio_protocol::record::{RecordSet, Offset, ReplicaKey, RawRecords, Batch, ControlRecordType};
 use fluvio_controlplane_metadata::partition::{ReplicaStatus, PartitionStatus};
-use fluvio_storage::{FileReplica, ReplicaStorage, OffsetInfo, ReplicaStorageConfig};
+use fluvio_storage::{
+    FileReplica, ReplicaStorage, OffsetInfo, ReplicaStorageConfig, ReplicaSlice, StorageError,
+    ProducerSnapshot,
+};
+use fluvio_storage::iterators::{FileBatch, FileBatchIterator};
 use fluvio_types::SpuId;
+use fluvio_types::defaults::SPU_MIN_IN_SYNC_REPLICAS;
+use fluvio_protocol::link::ErrorCode;
 use fluvio_spu_schema::{Isolation, COMMON_VERSION};
+use fluvio_spu_schema::fetch::AbortedTransaction;
 
 use crate::{
     config::ReplicationConfig,
     control_plane::SharedStatusUpdate,
     smartengine::{
         context::{SharedSmartModuleContext, SmartModuleContext},
-        dedup_to_invocation,
+        dedup_to_invocation, transform_to_invocation,
         batch::process_record_set,
     },
     core::GlobalContext,
 };
-use crate::replication::follower::sync::{PeerFileTopicResponse, PeerFilePartitionResponse};
+use crate::replication::follower::sync::{
+    PeerFileTopicResponse, PeerFilePartitionResponse, PeerConsumerOffset,
+};
 use crate::storage::SharableReplicaStorage;
 
 use super::FollowerNotifier;
+use super::producer_state::{
+    ProducerStates, SharedProducerStates, SnapshotProducer, PRODUCER_EXPIRATION,
+};
+use super::transaction_state::{
+    ProducedTransaction, TransactionStates, SharedTransactionStates, TransactionsSnapshot,
+};
+
+/// producer and transaction states are snapshotted once this many records are written after the latest snapshot
+const PRODUCER_SNAPSHOT_INTERVAL: Offset = 100_000;
 
 pub type SharedLeaderState<S> = LeaderReplicaState<S>;
 pub type SharedFileLeaderState = LeaderReplicaState<FileReplica>;
 
+/// Producer and transaction states persisted in producer snapshot of replica storage
+#[derive(Debug, Default, Encoder, Decoder)]
+struct LeaderStatesSnapshot {
+    /// wall clock time of snapshot in milliseconds
+    timestamp: i64,
+    producers: Vec<SnapshotProducer>,
+    transactions: TransactionsSnapshot,
+}
+
+/// replica assignment and in-sync replica count derived from it
 #[derive(Debug)]
-pub struct LeaderReplicaState<S> {
+struct ReplicaAssignment {
     replica: Replica,
     in_sync_replica: u16,
+}
+
+impl ReplicaAssignment {
+    fn new(replica: Replica) -> Self {
+        Self {
+            in_sync_replica: in_sync_replica_count(&replica),
+            replica,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LeaderReplicaState<S> {
+    /// replica id and leader don't change while this SPU is leader
+    id: ReplicaKey,
+    leader: SpuId,
+    /// shared by all clones of state, so replica updates are seen by running tasks
+    assignment: Arc<StdRwLock<ReplicaAssignment>>,
     storage: SharableReplicaStorage<S>,
     config: ReplicationConfig,
     followers: Arc<RwLock<BTreeMap<SpuId, OffsetInfo>>>,
+    /// followers which haven't received latest consumer offsets
+    consumer_offsets_pending: Arc<RwLock<HashSet<SpuId>>>,
     status_update: SharedStatusUpdate,
     sm_ctx: Option<SharedSmartModuleContext>,
+    producers: SharedProducerStates,
+    transactions: SharedTransactionStates,
+    // followers which have not reported offsets are in sync during grace period after start
+    started: Instant,
+    /// offset of the latest snapshot of producer and transaction states
+    snapshot_offset: Arc<AtomicI64>,
 }
 
 impl<S> Clone for LeaderReplicaState<S> {
     fn clone(&self) -> Self {
         Self {
-            replica: self.replica.clone(),
+            id: self.id.clone(),
+            leader: self.leader,
+            assignment: self.assignment.clone(),
             storage: self.storage.clone(),
             config: self.config.clone(),
             followers: self.followers.clone(),
-            in_sync_replica: self.in_sync_replica,
+            consumer_offsets_pending: self.consumer_offsets_pending.clone(),
             status_update: self.status_update.clone(),
             sm_ctx: self.sm_ctx.clone(),
+            producers: self.producers.clone(),
+            transactions: self.transactions.clone(),
+            started: self.started,
+            snapshot_offset: self.snapshot_offset.clone(),
         }
     }
 }
@@ -85,6 +154,42 @@ impl<S> DerefMut for LeaderReplicaState<S> {
     }
 }
 
+/// replicas added by reassignment are not counted until they are in sync
+fn in_sync_replica_count(replica: &Replica) -> u16 {
+    replica
+        .replicas
+        .iter()
+        .filter(|id| !replica.adding_replicas.contains(id))
+        .count()
+        .max(1) as u16
+}
+
+/// followers in sync with leader. follower is out of sync when it is offline
+/// or when it lags behind leader's hw, for example after reconnecting.
+/// follower which has not reported its offsets, for example because it never connected,
+/// is out of sync once grace period is expired.
+/// replicas added by reassignment are not in sync until reassignment is completed
+fn in_sync_followers(
+    replica: &Replica,
+    leader_hw: Offset,
+    followers: &BTreeMap<SpuId, OffsetInfo>,
+    offline: &HashSet<SpuId>,
+    grace_expired: bool,
+) -> BTreeMap<SpuId, OffsetInfo> {
+    followers
+        .iter()
+        .filter(|(id, _)| !replica.adding_replicas.contains(id) && !offline.contains(id))
+        .filter(|(_, offset)| {
+            if offset.is_valid() {
+                offset.leo >= leader_hw
+            } else {
+                !grace_expired
+            }
+        })
+        .map(|(id, offset)| (*id, offset.clone()))
+        .collect()
+}
+
 /// convert follower ids into BtreeMap of this
 fn ids_to_map(leader_id: SpuId, follower_ids: HashSet<SpuId>) -> BTreeMap<SpuId, OffsetInfo> {
     let mut followers = BTreeMap::new();
@@ -96,7 +201,7 @@ fn ids_to_map(leader_id: SpuId, follower_ids: HashSet<SpuId>) -> BTreeMap<SpuId,
 
 impl<S> LeaderReplicaState<S>
 where
-    S: ReplicaStorage,
+    S: ReplicaStorage + Send + Sync + 'static,
 {
     /// create new state from existing storage
     /// calculate default in_sync_replica from followers
@@ -107,26 +212,33 @@ where
         inner: SharableReplicaStorage<S>,
     ) -> Uninit<Self> {
         debug!(?replica, "replica storage");
-        let in_sync_replica = replica.replicas.len() as u16;
+        let assignment = ReplicaAssignment::new(replica);
+        let replica = &assignment.replica;
         let follower_ids = HashSet::from_iter(replica.replicas.clone());
         let followers = ids_to_map(replica.leader, follower_ids);
         debug!(?followers, "leader followers");
 
         debug!(
-            in_sync_replica,
+            in_sync_replica = assignment.in_sync_replica,
             replica = %replica.id,
             follower = ?replica.replicas,
             "creating leader"
         );
 
         Uninit(Self {
-            replica,
+            id: replica.id.clone(),
+            leader: replica.leader,
+            assignment: Arc::new(StdRwLock::new(assignment)),
             storage: inner,
             config,
             followers: Arc::new(RwLock::new(followers)),
-            in_sync_replica,
+            consumer_offsets_pending: Arc::new(RwLock::new(HashSet::new())),
             status_update,
             sm_ctx: None,
+            producers: ProducerStates::shared(),
+            transactions: TransactionStates::shared(),
+            started: Instant::now(),
+            snapshot_offset: Arc::new(AtomicI64::new(0)),
         })
     }
 
@@ -150,18 +262,61 @@ where
 
     /// replica id
     pub fn id(&self) -> &ReplicaKey {
-        &self.replica.id
+        &self.id
     }
 
     /// leader SPU. This should be same as our local SPU
     pub fn leader(&self) -> SpuId {
-        self.replica.leader
+        self.leader
+    }
+
+    /// current replica assignment, guard must not be held across await
+    fn assignment(&self) -> StdRwLockReadGuard<'_, ReplicaAssignment> {
+        self.assignment
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// replicas of the partition including leader
+    fn replicas(&self) -> Vec<SpuId> {
+        self.assignment().replica.replicas.clone()
+    }
+
+    /// number of replicas, excluding ones being added by reassignment
+    fn in_sync_replica(&self) -> u16 {
+        self.assignment().in_sync_replica
+    }
+
+    /// update replicas of the partition which has same leader.
+    /// added followers start with empty offsets, removed followers are dropped
+    pub async fn update_replica(&self, replica: Replica) {
+        let mut followers = self.followers.write().await;
+        let mut pending = self.consumer_offsets_pending.write().await;
+        followers.retain(|id, _| replica.replicas.contains(id));
+        pending.retain(|id| replica.replicas.contains(id));
+        for id in replica.replicas.iter().filter(|id| **id != replica.leader) {
+            if !followers.contains_key(id) {
+                followers.insert(*id, OffsetInfo::default());
+                pending.insert(*id);
+            }
+        }
+        debug!(?followers, "updated leader followers");
+        drop(pending);
+        drop(followers);
+
+        *self
+            .assignment
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = ReplicaAssignment::new(replica);
     }
 
     /// override in sync replica
     #[allow(unused)]
-    fn set_in_sync_replica(&mut self, replica_count: u16) {
-        self.in_sync_replica = replica_count;
+    fn set_in_sync_replica(&self, replica_count: u16) {
+        self.assignment
+            .write()
+            .unwrap_or_else(|err| err.into_inner())
+            .in_sync_replica = replica_count;
     }
 
     /// update leader's state from follower's offset states
@@ -184,13 +339,15 @@ where
             return false;
         }
 
+        let offline = notifier.offline_followers(&self.replicas()).await;
+
         // get follower info
         let mut followers = self.followers.write().await;
         let update = if let Some(current_follow_info) = followers.get_mut(&follower_id) {
             if current_follow_info.update(&follower_pos) {
                 // if our leo and hw is same there is no need to recompute hw
                 if !leader_pos.is_committed() {
-                    if let Some(hw) = compute_hw(&leader_pos, self.in_sync_replica, &followers) {
+                    if let Some(hw) = self.compute_hw(&leader_pos, &followers, &offline) {
                         debug!(hw, "updating hw");
                         if let Err(err) = self.update_hw(hw).await {
                             error!("error updating hw: {}", err);
@@ -221,6 +378,136 @@ where
         update
     }
 
+    /// hw computed from followers. without min in-sync replicas configured, records are committed
+    /// when all replicas have them, otherwise when all in-sync replicas have them
+    fn compute_hw(
+        &self,
+        leader_pos: &OffsetInfo,
+        followers: &BTreeMap<SpuId, OffsetInfo>,
+        offline: &HashSet<SpuId>,
+    ) -> Option<Offset> {
+        if self.min_in_sync_replicas_configured() {
+            let in_sync = self.in_sync_followers(leader_pos.hw, followers, offline);
+            compute_in_sync_hw(leader_pos, &in_sync, self.min_in_sync_replicas())
+        } else {
+            compute_hw(leader_pos, self.in_sync_replica(), followers)
+        }
+    }
+
+    fn in_sync_followers(
+        &self,
+        leader_hw: Offset,
+        followers: &BTreeMap<SpuId, OffsetInfo>,
+        offline: &HashSet<SpuId>,
+    ) -> BTreeMap<SpuId, OffsetInfo> {
+        let grace_expired = self.started.elapsed() >= self.config.follower_sync_grace_period;
+        in_sync_followers(
+            &self.assignment().replica,
+            leader_hw,
+            followers,
+            offline,
+            grace_expired,
+        )
+    }
+
+    /// recompute hw when in-sync replicas shrink, for example when follower goes offline.
+    /// in-sync replicas are only tracked when min in-sync replicas is configured
+    #[instrument(skip(self, notifier))]
+    pub async fn update_hw_from_in_sync_followers(&self, notifier: &FollowerNotifier) {
+        let leader_pos = self.as_offset();
+        if leader_pos.is_committed() || !self.min_in_sync_replicas_configured() {
+            return;
+        }
+        let offline = notifier.offline_followers(&self.replicas()).await;
+        let followers = self.followers.read().await;
+        let hw = self.compute_hw(&leader_pos, &followers, &offline);
+        drop(followers);
+        if let Some(hw) = hw {
+            debug!(hw, "updating hw");
+            if let Err(err) = self.update_hw(hw).await {
+                error!("error updating hw: {}", err);
+                return;
+            }
+            self.notify_followers(notifier).await;
+            self.update_status().await;
+        }
+    }
+
+    /// min number of in-sync replicas, including leader, required to accept writes.
+    /// topic setting overrides SPU default
+    pub fn min_in_sync_replicas(&self) -> u16 {
+        self.assignment()
+            .replica
+            .min_in_sync_replicas
+            .unwrap_or(self.config.min_in_sync_replicas)
+            .max(1)
+    }
+
+    /// min in-sync replicas set by topic or SPU, otherwise all replicas must have records to commit them
+    fn min_in_sync_replicas_configured(&self) -> bool {
+        self.assignment().replica.min_in_sync_replicas.is_some()
+            || self.config.min_in_sync_replicas > SPU_MIN_IN_SYNC_REPLICAS
+    }
+
+    /// number of in-sync replicas including leader
+    pub async fn in_sync_replica_count(&self, notifier: &FollowerNotifier) -> u16 {
+        let offline = notifier.offline_followers(&self.replicas()).await;
+        let followers = self.followers.read().await;
+        self.in_sync_followers(self.hw(), &followers, &offline)
+            .len() as u16
+            + 1
+    }
+
+    /// writes are rejected if there are fewer in-sync replicas than required,
+    /// so losing followers doesn't downgrade durability of written records
+    pub async fn check_min_in_sync_replicas(
+        &self,
+        notifier: &FollowerNotifier,
+    ) -> Result<u16, ErrorCode> {
+        let in_sync = self.in_sync_replica_count(notifier).await;
+        let min = self.min_in_sync_replicas();
+        if in_sync < min {
+            warn!(replica = %self.id(), in_sync, min, "not enough in-sync replicas");
+            Err(ErrorCode::NotEnoughInSyncReplicas { in_sync, min })
+        } else {
+            Ok(in_sync)
+        }
+    }
+
+    /// wait until records up to `leo` are committed, returns error code of produce acknowledgement.
+    /// records are not acknowledged while there are fewer in-sync replicas than required,
+    /// even if they were committed before followers dropped
+    pub async fn wait_for_commit(
+        &self,
+        leo: Offset,
+        timeout: Duration,
+        notifier: &FollowerNotifier,
+    ) -> ErrorCode {
+        let mut listener = self.offset_listener(&Isolation::ReadCommitted);
+        if self.hw() < leo {
+            let wait_future = async { while listener.listen().await < leo {} };
+            select! {
+                _ = wait_future => {
+                    trace!(replica = %self.id(), "waiting for acks completed");
+                },
+                _ = sleep(timeout) => {
+                    debug!(replica = %self.id(), "response timeout exceeded");
+                    return match self.check_min_in_sync_replicas(notifier).await {
+                        Err(error_code) => error_code,
+                        Ok(_) => ErrorCode::RequestTimedOut {
+                            kind: RequestKind::Produce,
+                            timeout_ms: timeout.as_millis() as u64,
+                        },
+                    };
+                },
+            }
+        }
+        match self.check_min_in_sync_replicas(notifier).await {
+            Err(error_code) => error_code,
+            Ok(_) => ErrorCode::None,
+        }
+    }
+
     /// compute follower that needs to be updated
     /// based on leader's state
     pub async fn follower_updates(
@@ -232,7 +519,14 @@ where
 
         let reader = self.followers.read().await;
         if let Some(follower_info) = reader.get(follower_id) {
-            if follower_info.is_valid() && !follower_info.is_same(&leader_offset) {
+            let offsets_pending = self
+                .consumer_offsets_pending
+                .read()
+                .await
+                .contains(follower_id);
+            if follower_info.is_valid()
+                && (offsets_pending || !follower_info.is_same(&leader_offset))
+            {
                 let mut topic_response = PeerFileTopicResponse {
                     name: self.id().topic.to_owned(),
                     ..Default::default()
@@ -279,6 +573,25 @@ where
                 partition_response.leo = leader_offset.leo;
                 partition_response.hw = leader_offset.hw;
 
+                if offsets_pending {
+                    // clear before taking snapshot so commits made in between are sent next time
+                    self.consumer_offsets_pending
+                        .write()
+                        .await
+                        .remove(follower_id);
+                    let offsets = self.read().await.consumer_offsets();
+                    debug!(groups = offsets.len(), replica = %self.id(), "sending consumer offsets");
+                    partition_response.consumer_offsets = Some(
+                        offsets
+                            .into_iter()
+                            .map(|(consumer_group, offset)| PeerConsumerOffset {
+                                consumer_group,
+                                offset,
+                            })
+                            .collect(),
+                    );
+                }
+
                 topic_response.partitions.push(partition_response);
                 Some(topic_response)
             } else {
@@ -317,6 +630,249 @@ where
         self.status_update.send(lrs).await
     }
 
+    /// sequence state of idempotent producers
+    pub fn producers(&self) -> &SharedProducerStates {
+        &self.producers
+    }
+
+    /// open and aborted transactions of transactional producers
+    pub fn transactions(&self) -> &SharedTransactionStates {
+        &self.transactions
+    }
+
+    /// write control batch ending transaction of the producer, return leo after the batch
+    pub async fn write_txn_marker(
+        &self,
+        producer_id: i64,
+        producer_epoch: i16,
+        control: ControlRecordType,
+        notifier: &FollowerNotifier,
+    ) -> Result<Offset> {
+        let batch: Batch<RawRecords> =
+            Batch::control(producer_id, producer_epoch, control).try_into()?;
+        let mut records = RecordSet {
+            batches: vec![batch],
+        };
+        // committed reads don't see the marker before transaction state is updated
+        let mut transactions = self.transactions.write().await;
+        let (base_offset, leo, _) = self.write_record_set(&mut records, notifier).await?;
+        transactions.record(
+            ProducedTransaction::Control {
+                producer_id,
+                control,
+            },
+            base_offset,
+        );
+        transactions.remove_aborted_before(self.read().await.get_log_start_offset());
+        Ok(leo)
+    }
+
+    /// Rebuild producer and transaction states from the latest snapshot and batches written after it,
+    /// or from the whole log without snapshot. Producers are restored from batches written
+    /// before they expire. Open and aborted transactions are known to the new leader
+    /// and retries in flight during leader change are detected as duplicates.
+    async fn restore_states(&self) -> Result<()> {
+        let min_timestamp =
+            Utc::now().timestamp_millis() - i64::try_from(PRODUCER_EXPIRATION.as_millis())?;
+        let (start_offset, producers_offset, snapshot) = {
+            let storage = self.read().await;
+            (
+                storage.get_log_start_offset(),
+                storage.find_offset_by_timestamp(min_timestamp).await?,
+                storage.get_producer_snapshot(),
+            )
+        };
+        let mut producers = self.producers.lock().await;
+        let mut transactions = self.transactions.write().await;
+        let mut offset = start_offset;
+        if let Some(snapshot) = snapshot.filter(|snapshot| snapshot.offset <= self.leo()) {
+            match LeaderStatesSnapshot::decode_from(&mut snapshot.state.as_slice(), 0) {
+                Ok(states) => {
+                    let age = Utc::now().timestamp_millis() - states.timestamp;
+                    producers.load_snapshot(
+                        states.producers,
+                        Duration::from_millis(u64::try_from(age).unwrap_or_default()),
+                    );
+                    transactions.load_snapshot(states.transactions);
+                    offset = offset.max(snapshot.offset);
+                    self.snapshot_offset
+                        .store(snapshot.offset, AtomicOrdering::SeqCst);
+                }
+                Err(err) => {
+                    warn!(%err, "invalid producer snapshot, rebuilding states from the log");
+                }
+            }
+        }
+        self.for_each_batch(offset, |batch| {
+            transactions.restore(batch);
+            if batch.base_offset >= producers_offset {
+                producers.restore(batch);
+            }
+        })
+        .await?;
+        transactions.remove_aborted_before(start_offset);
+        debug!(
+            offset,
+            producers_offset, "restored producer and transaction states"
+        );
+        Ok(())
+    }
+
+    /// Persist producer and transaction states as of log end offset,
+    /// so next leader replays only batches written after it.
+    /// States are locked while encoded, so they include every batch below the offset.
+    async fn snapshot_states(&self) -> Result<()> {
+        let snapshot = {
+            let producers = self.producers.lock().await;
+            let transactions = self.transactions.read().await;
+            let states = LeaderStatesSnapshot {
+                timestamp: Utc::now().timestamp_millis(),
+                producers: producers.snapshot(),
+                transactions: transactions.snapshot(),
+            };
+            let mut state = Vec::new();
+            states.encode(&mut state, 0)?;
+            ProducerSnapshot {
+                offset: self.leo(),
+                state,
+            }
+        };
+        let offset = snapshot.offset;
+        self.write()
+            .await
+            .commit_producer_snapshot(snapshot)
+            .await?;
+        debug!(offset, "snapshotted producer and transaction states");
+        Ok(())
+    }
+
+    /// snapshot states in background once enough records are written since the latest snapshot
+    fn snapshot_states_if_due(&self) {
+        let leo = self.leo();
+        let snapshot_offset = self.snapshot_offset.load(AtomicOrdering::SeqCst);
+        if leo - snapshot_offset < PRODUCER_SNAPSHOT_INTERVAL
+            || self
+                .snapshot_offset
+                .compare_exchange(
+                    snapshot_offset,
+                    leo,
+                    AtomicOrdering::SeqCst,
+                    AtomicOrdering::SeqCst,
+                )
+                .is_err()
+        {
+            return;
+        }
+        let leader = self.clone();
+        spawn(async move {
+            if let Err(err) = leader.snapshot_states().await {
+                error!(%err, "failed to snapshot producer and transaction states");
+            }
+        });
+    }
+
+    /// Call `f` with each batch from `offset` to the end of the log.
+    /// Only records of control batches are decoded, other batches have header only.
+    async fn for_each_batch(&self, mut offset: Offset, mut f: impl FnMut(&Batch)) -> Result<()> {
+        let leo = self.leo();
+        while offset < leo {
+            let slice = self
+                .read_records(offset, u32::MAX, Isolation::ReadUncommitted)
+                .await?;
+            let Some(file_slice) = slice.file_slice else {
+                break;
+            };
+            let mut next_offset = offset;
+            for file_batch in FileBatchIterator::from_raw_slice(file_slice) {
+                let FileBatch { mut batch, records } = file_batch?;
+                if batch.is_control() {
+                    batch.mut_records().decode(&mut Cursor::new(records), 0)?;
+                }
+                next_offset = next_offset.max(batch.get_last_offset() + 1);
+                f(&batch);
+            }
+            if next_offset <= offset {
+                break;
+            }
+            offset = next_offset;
+        }
+        Ok(())
+    }
+
+    /// commit offset of consumer group, followers are updated so offset survives leader change
+    #[instrument(skip(self, notifier))]
+    pub async fn commit_consumer_offset(
+        &self,
+        consumer_group: &str,
+        offset: Offset,
+        notifier: &FollowerNotifier,
+    ) -> Result<(), StorageError> {
+        self.write()
+            .await
+            .commit_consumer_offset(consumer_group, offset)
+            .await?;
+        self.consumer_offsets_changed(notifier).await;
+        Ok(())
+    }
+
+    /// remove offset of consumer group on leader and followers.
+    /// return true if there was an offset
+    #[instrument(skip(self, notifier))]
+    pub async fn reset_consumer_offset(
+        &self,
+        consumer_group: &str,
+        notifier: &FollowerNotifier,
+    ) -> Result<bool, StorageError> {
+        let removed = self
+            .write()
+            .await
+            .remove_consumer_offset(consumer_group)
+            .await?;
+        if removed {
+            self.consumer_offsets_changed(notifier).await;
+        }
+        Ok(removed)
+    }
+
+    /// follower needs all consumer offsets, for example after it reconnects
+    pub async fn send_consumer_offsets(&self, follower_id: SpuId) {
+        if self.followers.read().await.contains_key(&follower_id) {
+            self.consumer_offsets_pending
+                .write()
+                .await
+                .insert(follower_id);
+        }
+    }
+
+    async fn consumer_offsets_changed(&self, notifier: &FollowerNotifier) {
+        let followers = self.followers.read().await;
+        self.consumer_offsets_pending
+            .write()
+            .await
+            .extend(followers.keys().copied());
+        drop(followers);
+        self.notify_followers(notifier).await;
+    }
+
+    /// read committed records below last stable offset, so records of open transactions are not read.
+    /// hw of returned slice is the last stable offset, aborted transactions in the slice are returned
+    pub async fn read_stable_records(
+        &self,
+        offset: Offset,
+        max_len: u32,
+    ) -> Result<(ReplicaSlice, Vec<AbortedTransaction>), ErrorCode> {
+        let transactions = self.transactions.read().await;
+        let last_stable_offset = transactions.last_stable_offset(self.hw());
+        let mut slice = self
+            .read()
+            .await
+            .read_partition_slice_until(offset, last_stable_offset, max_len)
+            .await?;
+        slice.end.hw = slice.end.hw.min(last_stable_offset);
+        let aborted = transactions.aborted_in(offset, slice.end.hw);
+        Ok((slice, aborted))
+    }
+
     /// write records to storage
     /// then update our follower's leo
     #[instrument(skip(self, records, notifiers))]
@@ -325,46 +881,93 @@ where
         records: &mut RecordSet<RawRecords>,
         notifiers: &FollowerNotifier,
     ) -> Result<(Offset, Offset, usize)> {
-        self.transform(records).await?;
+        let in_sync = self.check_min_in_sync_replicas(notifiers).await?;
+        // control batches written by transaction coordinator are not transformed
+        let transaction = ProducedTransaction::from_record_set(records);
+        // smartmodule context is locked until records are written, so key-value states
+        // are committed only after records which changed them are written
+        let mut sm_ctx = match &self.sm_ctx {
+            Some(sm_ctx) if !transaction.is_some_and(|transaction| transaction.is_control()) => {
+                Some(sm_ctx.write().await)
+            }
+            _ => None,
+        };
+        if let Some(sm_ctx) = sm_ctx.as_deref_mut() {
+            sm_ctx.update_join_tables().await?;
+            if let Err(err) = Self::transform(sm_ctx, transaction, records) {
+                sm_ctx.load_key_values(self).await;
+                return Err(err);
+            }
+        }
         if records.total_records() == 0 {
+            if let Some(sm_ctx) = sm_ctx {
+                sm_ctx.commit_key_values(self).await?;
+            }
             return Ok((self.hw(), self.leo(), 0));
         }
 
-        let offsets = self
-            .storage
-            .write_record_set(records, self.in_sync_replica == 1)
-            .await?;
+        // records are committed right away if leader is the only in-sync replica
+        let commit = if self.min_in_sync_replicas_configured() {
+            in_sync == 1
+        } else {
+            self.in_sync_replica() == 1
+        };
+        let offsets = match self.storage.write_record_set(records, commit).await {
+            Ok(offsets) => offsets,
+            Err(err) => {
+                // key-value changes made by records which are not written are discarded
+                if let Some(sm_ctx) = sm_ctx {
+                    sm_ctx.load_key_values(self).await;
+                }
+                return Err(err);
+            }
+        };
+        // records are written, failure to persist key-value states is not reported to producer
+        if let Some(sm_ctx) = sm_ctx {
+            if let Err(err) = sm_ctx.commit_key_values(self).await {
+                warn!(%err, "smartmodule key-value states not persisted after write");
+            }
+        }
 
         self.notify_followers(notifiers).await;
         self.update_status().await;
+        self.snapshot_states_if_due();
 
         Ok(offsets)
     }
 
-    async fn transform(&self, records: &mut RecordSet<RawRecords>) -> Result<()> {
-        if let Some(ref sm_ctx) = self.sm_ctx {
-            let (sm_result, sm_error) =
-                process_record_set(sm_ctx.write().await.chain_mut(), records)?;
-            if let Some(error) = sm_error {
-                return Err(error.into());
-            }
-            records.batches.clear();
-            if !sm_result.records().is_empty() {
-                let transformed_batch = Batch::<RawRecords>::try_from(sm_result)?;
-                records.batches.push(transformed_batch);
+    fn transform(
+        sm_ctx: &mut SmartModuleContext,
+        transaction: Option<ProducedTransaction>,
+        records: &mut RecordSet<RawRecords>,
+    ) -> Result<()> {
+        let (sm_result, sm_error) = process_record_set(sm_ctx.chain_mut(), records)?;
+        if let Some(error) = sm_error {
+            return Err(ErrorCode::SmartModuleRuntimeError(error).into());
+        }
+        records.batches.clear();
+        if !sm_result.records().is_empty() {
+            let mut transformed_batch = Batch::<RawRecords>::try_from(sm_result)?;
+            if let Some(transaction) = transaction {
+                transaction.apply(&mut transformed_batch);
             }
-        };
+            records.batches.push(transformed_batch);
+        }
         Ok(())
     }
 
     async fn notify_followers(&self, notifier: &FollowerNotifier) {
         let leader_offset = self.as_offset();
         let followers = self.followers.read().await;
+        let offsets_pending = self.consumer_offsets_pending.read().await;
         debug!(?leader_offset);
-        for follower in &self.replica.replicas {
+        for follower in &self.replicas() {
             if let Some(follower_info) = followers.get(follower) {
                 debug!(follower, ?follower_info);
-                if follower_info.is_valid() && !follower_info.is_same(&leader_offset) {
+                if follower_info.is_valid()
+                    && (offsets_pending.contains(follower)
+                        || !follower_info.is_same(&leader_offset))
+                {
                     debug!(follower, "notify");
                     notifier.notify_follower(follower, self.id().clone()).await;
                 } else {
@@ -374,7 +977,6 @@ where
         }
     }
 
-    #[allow(dead_code)]
     pub async fn live_replicas(&self) -> Vec<SpuId> {
         self.followers.read().await.keys().cloned().collect()
     }
@@ -388,21 +990,61 @@ where
 
 pub struct Uninit<S>(S);
 
-impl<S: ReplicaStorage> Uninit<LeaderReplicaState<S>> {
+impl<S> Uninit<LeaderReplicaState<S>>
+where
+    S: ReplicaStorage + Send + Sync + 'static,
+{
     pub async fn init(self, ctx: &GlobalContext<FileReplica>) -> Result<LeaderReplicaState<S>> {
         let mut state = self.0;
-        if let Some(dedup) = &state.replica.deduplication {
-            debug!(?state.replica.deduplication, "init leader smartmodule context");
-            let dedup_filter = dedup_to_invocation(dedup);
-            let mut sm_ctx = SmartModuleContext::try_from(vec![dedup_filter], COMMON_VERSION, ctx)
-                .await?
-                .ok_or_else(|| anyhow::anyhow!("SmartModule context is required here"))?;
+        let replica = state.assignment().replica.clone();
+        // records written by any producer pass thru transforms of topic, then deduplication
+        let mut invocations: Vec<_> = replica
+            .transforms
+            .iter()
+            .map(transform_to_invocation)
+            .collect();
+        invocations.extend(replica.deduplication.as_ref().map(dedup_to_invocation));
+        if let Some(mut sm_ctx) =
+            SmartModuleContext::try_from(invocations, COMMON_VERSION, ctx).await?
+        {
+            debug!(
+                transforms = replica.transforms.len(),
+                ?replica.deduplication,
+                "init leader smartmodule context"
+            );
+            sm_ctx
+                .init_join_tables(replica.id.partition, ctx, None)
+                .await?;
+            sm_ctx.load_key_values(&state).await;
             sm_ctx
                 .look_back(&state)
                 .await
                 .context("leader smartmodule context lookback failed")?;
             state.sm_ctx = Some(Arc::new(RwLock::new(sm_ctx)));
         };
+        state
+            .restore_states()
+            .await
+            .context("restoring producer and transaction states failed")?;
+        state.snapshot_states_if_due();
+        // records may be committed without followers which haven't reported offsets during grace period
+        if state.min_in_sync_replicas_configured() {
+            let leader = state.clone();
+            let notifier = ctx.follower_notifier_owned();
+            spawn(async move {
+                sleep(leader.config.follower_sync_grace_period).await;
+                leader.update_hw_from_in_sync_followers(&notifier).await;
+            });
+        }
+        // followers may have missed offsets committed while this replica was not a leader
+        if !state.read().await.consumer_offsets().is_empty() {
+            let followers = state.followers.read().await;
+            state
+                .consumer_offsets_pending
+                .write()
+                .await
+                .extend(followers.keys().copied());
+        }
         Ok(state)
     }
 }
@@ -433,6 +1075,28 @@ impl<S: ReplicaStorage> Uninit<LeaderReplicaState<S>> {
 ///         follower: leo(3,4)  =>   hw = 3  that is smallest leo that satisfy
 ///         follower: leo(4,4)  =>   hw = 4
 ///         follower: leo(6,7,9) =>  hw = 7,
+/// compute leader's hw from followers in sync, all of them must have replicated records.
+/// hw is not advanced while there are fewer in-sync replicas, including leader, than `min_replicas`,
+/// so records are never committed with fewer copies than required.
+/// if leader is only in-sync replica, all records are committed
+fn compute_in_sync_hw(
+    leader: &OffsetInfo,
+    in_sync: &BTreeMap<SpuId, OffsetInfo>,
+    min_replicas: u16,
+) -> Option<Offset> {
+    if (in_sync.len() as u16 + 1) < min_replicas {
+        None
+    } else if in_sync.is_empty() {
+        (leader.leo > leader.hw).then_some(leader.leo)
+    } else {
+        // follower can be ahead of leader elected from out of sync replica
+        // until it discards its records
+        compute_hw(leader, in_sync.len() as u16 + 1, in_sync)
+            .map(|hw| hw.min(leader.leo))
+            .filter(|hw| *hw > leader.hw)
+    }
+}
+
 fn compute_hw(
     leader: &OffsetInfo,
     min_replica: u16,
@@ -716,6 +1380,37 @@ mod test_hw_updates {
             Some(8)
         );
     }
+
+    #[test]
+    fn test_in_sync_hw_follower_ahead_of_leader() {
+        // follower has records which new leader does not have
+        let in_sync = offsets_maps(vec![(5001, OffsetInfo { leo: 20, hw: 10 })]);
+        assert_eq!(
+            compute_in_sync_hw(&OffsetInfo { leo: 15, hw: 10 }, &in_sync, 2),
+            Some(15)
+        );
+        assert_eq!(
+            compute_in_sync_hw(&OffsetInfo { leo: 15, hw: 15 }, &in_sync, 2),
+            None
+        );
+    }
+
+    #[test]
+    fn test_in_sync_hw_below_min_in_sync_replicas() {
+        let in_sync = offsets_maps(vec![(5001, OffsetInfo { leo: 15, hw: 10 })]);
+        assert_eq!(
+            compute_in_sync_hw(&OffsetInfo { leo: 20, hw: 10 }, &in_sync, 3),
+            None
+        );
+        assert_eq!(
+            compute_in_sync_hw(&OffsetInfo { leo: 20, hw: 10 }, &BTreeMap::new(), 2),
+            None
+        );
+        assert_eq!(
+            compute_in_sync_hw(&OffsetInfo { leo: 20, hw: 10 }, &BTreeMap::new(), 1),
+            Some(20)
+        );
+    }
 }
 
 #[cfg(test)]
@@ -724,7 +1419,10 @@ mod test_leader {
     use async_trait::async_trait;
 
     use fluvio_controlplane_metadata::partition::ReplicaKey;
-    use fluvio_storage::{ReplicaStorage, ReplicaStorageConfig, OffsetInfo, ReplicaSlice};
+    use fluvio_storage::{
+        ReplicaStorage, ReplicaStorageConfig, OffsetInfo, ReplicaSlice,
+        SharedSmartModuleStateStore, SharedSmartModuleKeyValueStore, SharedDeadLetterOffsetStore,
+    };
     use fluvio_protocol::record::Offset;
     use fluvio_protocol::link::ErrorCode;
     use fluvio_protocol::record::BatchRecords;
@@ -786,6 +1484,18 @@ mod test_leader {
             })
         }
 
+        async fn read_partition_slice_until(
+            &self,
+            offset: Offset,
+            _max_offset: Offset,
+            _max_len: u32,
+        ) -> Result<ReplicaSlice, ErrorCode> {
+            Ok(ReplicaSlice {
+                end: OffsetInfo { leo: offset, hw: 0 },
+                ..Default::default()
+            })
+        }
+
         // do dummy implementations of write
         async fn write_recordset<R: BatchRecords>(
             &mut self,
@@ -813,6 +1523,10 @@ mod test_leader {
             Ok(true)
         }
 
+        async fn truncate(&mut self, _offset: Offset) -> Result<()> {
+            todo!()
+        }
+
         type ReplicaConfig = MockConfig;
 
         fn get_log_start_offset(&self) -> Offset {
@@ -822,6 +1536,66 @@ mod test_leader {
         async fn remove(&self) -> Result<(), fluvio_storage::StorageError> {
             todo!()
         }
+
+        fn get_consumer_offset(&self, _consumer_group: &str) -> Option<Offset> {
+            todo!()
+        }
+
+        async fn find_offset_by_timestamp(
+            &self,
+            _timestamp: fluvio_types::Timestamp,
+        ) -> Result<Offset, ErrorCode> {
+            todo!()
+        }
+
+        async fn commit_consumer_offset(
+            &mut self,
+            _consumer_group: &str,
+            _offset: Offset,
+        ) -> Result<(), fluvio_storage::StorageError> {
+            todo!()
+        }
+
+        async fn remove_consumer_offset(
+            &mut self,
+            _consumer_group: &str,
+        ) -> Result<bool, fluvio_storage::StorageError> {
+            todo!()
+        }
+
+        fn consumer_offsets(&self) -> BTreeMap<String, Offset> {
+            todo!()
+        }
+
+        async fn sync_consumer_offsets(
+            &mut self,
+            _offsets: BTreeMap<String, Offset>,
+        ) -> Result<(), fluvio_storage::StorageError> {
+            todo!()
+        }
+
+        fn smartmodule_states(&self) -> SharedSmartModuleStateStore {
+            todo!()
+        }
+
+        fn smartmodule_key_values(&self) -> SharedSmartModuleKeyValueStore {
+            todo!()
+        }
+
+        fn dead_letter_offsets(&self) -> SharedDeadLetterOffsetStore {
+            todo!()
+        }
+
+        fn get_producer_snapshot(&self) -> Option<ProducerSnapshot> {
+            todo!()
+        }
+
+        async fn commit_producer_snapshot(
+            &mut self,
+            _snapshot: ProducerSnapshot,
+        ) -> Result<(), fluvio_storage::StorageError> {
+            todo!()
+        }
     }
 
     #[fluvio_future::test]
@@ -842,7 +1616,44 @@ mod test_leader {
         .expect("state")
         .0;
 
-        assert_eq!(state.in_sync_replica, 1);
+        assert_eq!(state.in_sync_replica(), 1);
+    }
+
+    #[fluvio_future::test]
+    async fn test_leader_update_replica() {
+        let leader_config = SpuConfig {
+            id: 5000,
+            ..Default::default()
+        };
+
+        let replica: ReplicaKey = ("test", 1).into();
+        let state: LeaderReplicaState<MockStorage> = LeaderReplicaState::create(
+            Replica::new(replica.clone(), 5000, vec![5000, 5001]),
+            &leader_config,
+            StatusMessageSink::shared(),
+        )
+        .await
+        .expect("state")
+        .0;
+        assert_eq!(state.in_sync_replica(), 2);
+        // clones of state, such as ones held by spawned tasks, see updates
+        let cloned = state.clone();
+
+        // 5002 is added by reassignment, it's not counted until it is in sync
+        let mut reassigned = Replica::new(replica.clone(), 5000, vec![5000, 5001, 5002]);
+        reassigned.adding_replicas = vec![5002];
+        state.update_replica(reassigned).await;
+        assert_eq!(cloned.in_sync_replica(), 2);
+        assert_eq!(cloned.replicas(), vec![5000, 5001, 5002]);
+        assert_eq!(state.live_replicas().await, vec![5001, 5002]);
+
+        // 5001 is removed
+        state
+            .update_replica(Replica::new(replica, 5000, vec![5000, 5002]))
+            .await;
+        assert_eq!(cloned.in_sync_replica(), 2);
+        assert_eq!(cloned.replicas(), vec![5000, 5002]);
+        assert_eq!(state.live_replicas().await, vec![5002]);
     }
 
     #[fluvio_future::test]
@@ -1096,4 +1907,285 @@ mod test_leader {
         assert!(f1.drain_replicas().await.is_empty());
         assert!(f2.drain_replicas().await.is_empty());
     }
+
+    #[fluvio_future::test]
+    async fn test_min_in_sync_replicas() {
+        use crate::config::ReplicationConfig;
+        use crate::core::GlobalContext;
+        use fluvio_controlplane_metadata::spu::SpuSpec;
+
+        let leader_config = SpuConfig {
+            id: 5000,
+            replication: ReplicationConfig {
+                min_in_sync_replicas: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let specs = vec![
+            SpuSpec::new_private_addr(5000, 9000, "localhost".to_owned()),
+            SpuSpec::new_private_addr(5001, 9001, "localhost".to_owned()),
+            SpuSpec::new_private_addr(5002, 9002, "localhost".to_owned()),
+        ];
+        let gctx: Arc<GlobalContext<MockStorage>> =
+            GlobalContext::new_shared_context(leader_config);
+        gctx.spu_localstore().sync_all(specs);
+        gctx.sync_follower_update().await;
+        let notifier = gctx.follower_notifier();
+        let f1 = notifier.get(&5001).await.expect("5001");
+        let f2 = notifier.get(&5002).await.expect("5002");
+
+        let replica: ReplicaKey = ("test", 1).into();
+        let leader: LeaderReplicaState<MockStorage> = LeaderReplicaState::create(
+            Replica::new(replica.clone(), 5000, vec![5000, 5001, 5002]),
+            gctx.config(),
+            StatusMessageSink::shared(),
+        )
+        .await
+        .expect("state")
+        .0;
+        assert_eq!(leader.min_in_sync_replicas(), 2);
+
+        // followers which have not connected yet are in sync
+        assert_eq!(leader.in_sync_replica_count(notifier).await, 3);
+        leader
+            .write_record_set(&mut create_raw_recordset(10), notifier)
+            .await
+            .expect("write");
+        assert_eq!(leader.leo(), 10);
+
+        leader
+            .update_states_from_followers(5001, OffsetInfo { leo: 10, hw: 0 }, notifier)
+            .await;
+        leader
+            .update_states_from_followers(5002, OffsetInfo { leo: 5, hw: 0 }, notifier)
+            .await;
+        assert_eq!(leader.hw(), 5);
+
+        // 5002 goes offline, records are committed without it
+        f2.connected();
+        f2.disconnected();
+        assert_eq!(leader.in_sync_replica_count(notifier).await, 2);
+        leader.update_hw_from_in_sync_followers(notifier).await;
+        assert_eq!(leader.hw(), 10);
+        leader
+            .write_record_set(&mut create_raw_recordset(10), notifier)
+            .await
+            .expect("write");
+
+        // 5001 goes offline, writes are rejected
+        f1.connected();
+        f1.disconnected();
+        assert_eq!(leader.in_sync_replica_count(notifier).await, 1);
+        let err = leader
+            .write_record_set(&mut create_raw_recordset(10), notifier)
+            .await
+            .expect_err("not enough in-sync replicas");
+        assert_eq!(
+            err.downcast_ref::<ErrorCode>(),
+            Some(&ErrorCode::NotEnoughInSyncReplicas { in_sync: 1, min: 2 })
+        );
+
+        // 5002 is back but it is not in sync until it catches up with hw
+        f2.connected();
+        assert_eq!(leader.in_sync_replica_count(notifier).await, 1);
+        leader
+            .update_states_from_followers(5002, OffsetInfo { leo: 10, hw: 10 }, notifier)
+            .await;
+        assert_eq!(leader.in_sync_replica_count(notifier).await, 2);
+        leader
+            .write_record_set(&mut create_raw_recordset(10), notifier)
+            .await
+            .expect("write");
+
+        // topic overrides SPU default
+        let mut topic_replica = Replica::new(replica, 5000, vec![5000, 5001, 5002]);
+        topic_replica.min_in_sync_replicas = Some(3);
+        let leader: LeaderReplicaState<MockStorage> =
+            LeaderReplicaState::create(topic_replica, gctx.config(), StatusMessageSink::shared())
+                .await
+                .expect("state")
+                .0;
+        assert_eq!(leader.min_in_sync_replicas(), 3);
+    }
+
+    #[fluvio_future::test]
+    async fn test_min_in_sync_replicas_grace_period() {
+        use std::time::Duration;
+
+        use crate::config::ReplicationConfig;
+        use crate::core::GlobalContext;
+        use fluvio_controlplane_metadata::spu::SpuSpec;
+
+        let leader_config = SpuConfig {
+            id: 5000,
+            replication: ReplicationConfig {
+                min_in_sync_replicas: 2,
+                follower_sync_grace_period: Duration::ZERO,
+            },
+            ..Default::default()
+        };
+        let specs = vec![
+            SpuSpec::new_private_addr(5000, 9000, "localhost".to_owned()),
+            SpuSpec::new_private_addr(5001, 9001, "localhost".to_owned()),
+            SpuSpec::new_private_addr(5002, 9002, "localhost".to_owned()),
+        ];
+        let gctx: Arc<GlobalContext<MockStorage>> =
+            GlobalContext::new_shared_context(leader_config);
+        gctx.spu_localstore().sync_all(specs);
+        gctx.sync_follower_update().await;
+        let notifier = gctx.follower_notifier();
+
+        let leader: LeaderReplicaState<MockStorage> = LeaderReplicaState::create(
+            Replica::new(("test", 1), 5000, vec![5000, 5001, 5002]),
+            gctx.config(),
+            StatusMessageSink::shared(),
+        )
+        .await
+        .expect("state")
+        .0;
+
+        // followers which never reported offsets are out of sync after grace period
+        assert_eq!(leader.in_sync_replica_count(notifier).await, 1);
+        leader
+            .write_record_set(&mut create_raw_recordset(10), notifier)
+            .await
+            .expect_err("not enough in-sync replicas");
+
+        leader
+            .update_states_from_followers(5001, OffsetInfo { leo: 0, hw: 0 }, notifier)
+            .await;
+        assert_eq!(leader.in_sync_replica_count(notifier).await, 2);
+        leader
+            .write_record_set(&mut create_raw_recordset(10), notifier)
+            .await
+            .expect("write");
+        leader
+            .update_states_from_followers(5001, OffsetInfo { leo: 10, hw: 0 }, notifier)
+            .await;
+        assert_eq!(leader.hw(), 10);
+    }
+
+    #[fluvio_future::test]
+    async fn test_hw_without_min_in_sync_replicas() {
+        use crate::core::GlobalContext;
+        use fluvio_controlplane_metadata::spu::SpuSpec;
+
+        let leader_config = SpuConfig {
+            id: 5000,
+            ..Default::default()
+        };
+        let specs = vec![
+            SpuSpec::new_private_addr(5000, 9000, "localhost".to_owned()),
+            SpuSpec::new_private_addr(5001, 9001, "localhost".to_owned()),
+            SpuSpec::new_private_addr(5002, 9002, "localhost".to_owned()),
+        ];
+        let gctx: Arc<GlobalContext<MockStorage>> =
+            GlobalContext::new_shared_context(leader_config);
+        gctx.spu_localstore().sync_all(specs);
+        gctx.sync_follower_update().await;
+        let notifier = gctx.follower_notifier();
+        let f2 = notifier.get(&5002).await.expect("5002");
+
+        let leader: LeaderReplicaState<MockStorage> = LeaderReplicaState::create(
+            Replica::new(("test", 1), 5000, vec![5000, 5001, 5002]),
+            gctx.config(),
+            StatusMessageSink::shared(),
+        )
+        .await
+        .expect("state")
+        .0;
+        leader
+            .write_record_set(&mut create_raw_recordset(10), notifier)
+            .await
+            .expect("write");
+        leader
+            .update_states_from_followers(5001, OffsetInfo { leo: 10, hw: 0 }, notifier)
+            .await;
+
+        // records are committed only when all replicas have them, even if follower is offline
+        f2.connected();
+        f2.disconnected();
+        leader.update_hw_from_in_sync_followers(notifier).await;
+        assert_eq!(leader.hw(), 0);
+
+        f2.connected();
+        leader
+            .update_states_from_followers(5002, OffsetInfo { leo: 10, hw: 0 }, notifier)
+            .await;
+        assert_eq!(leader.hw(), 10);
+    }
+
+    #[fluvio_future::test]
+    async fn test_ack_fails_when_in_sync_replicas_drop() {
+        use std::time::Duration;
+
+        use crate::config::ReplicationConfig;
+        use crate::core::GlobalContext;
+        use fluvio_controlplane_metadata::spu::SpuSpec;
+
+        let leader_config = SpuConfig {
+            id: 5000,
+            replication: ReplicationConfig {
+                min_in_sync_replicas: 2,
+                follower_sync_grace_period: Duration::ZERO,
+            },
+            ..Default::default()
+        };
+        let specs = vec![
+            SpuSpec::new_private_addr(5000, 9000, "localhost".to_owned()),
+            SpuSpec::new_private_addr(5001, 9001, "localhost".to_owned()),
+        ];
+        let gctx: Arc<GlobalContext<MockStorage>> =
+            GlobalContext::new_shared_context(leader_config);
+        gctx.spu_localstore().sync_all(specs);
+        gctx.sync_follower_update().await;
+        let notifier = gctx.follower_notifier();
+        let f1 = notifier.get(&5001).await.expect("5001");
+
+        let leader: LeaderReplicaState<MockStorage> = LeaderReplicaState::create(
+            Replica::new(("test", 1), 5000, vec![5000, 5001]),
+            gctx.config(),
+            StatusMessageSink::shared(),
+        )
+        .await
+        .expect("state")
+        .0;
+        f1.connected();
+        leader
+            .update_states_from_followers(5001, OffsetInfo { leo: 0, hw: 0 }, notifier)
+            .await;
+        assert_eq!(leader.in_sync_replica_count(notifier).await, 2);
+
+        let (_, leo, _) = leader
+            .write_record_set(&mut create_raw_recordset(10), notifier)
+            .await
+            .expect("write");
+        assert_eq!(leo, 10);
+
+        // follower drops before replicating, leader alone doesn't commit records
+        f1.disconnected();
+        leader.update_hw_from_in_sync_followers(notifier).await;
+        assert_eq!(leader.hw(), 0);
+        assert_eq!(
+            leader
+                .wait_for_commit(leo, Duration::from_millis(10), notifier)
+                .await,
+            ErrorCode::NotEnoughInSyncReplicas { in_sync: 1, min: 2 }
+        );
+
+        // records committed before follower dropped are not acknowledged either
+        f1.connected();
+        leader
+            .update_states_from_followers(5001, OffsetInfo { leo: 10, hw: 0 }, notifier)
+            .await;
+        assert_eq!(leader.hw(), 10);
+        f1.disconnected();
+        assert_eq!(
+            leader
+                .wait_for_commit(leo, Duration::from_millis(10), notifier)
+                .await,
+            ErrorCode::NotEnoughInSyncReplicas { in_sync: 1, min: 2 }
+        );
+    }
 }
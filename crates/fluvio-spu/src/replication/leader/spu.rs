@@ -1,7 +1,7 @@
 use std::{
     collections::{HashSet, HashMap},
     ops::{Deref},
-    sync::Arc,
+    sync::{Arc, Mutex},
 };
 
 use tracing::{warn, debug};
@@ -57,12 +57,23 @@ impl FollowerNotifier {
                 let pending = FollowerSpuPendingUpdates {
                     event: Arc::new(OffsetPublisher::new(0)),
                     replicas: Arc::new(RwLock::new(HashSet::new())),
+                    connections: Mutex::new(None),
                 };
                 writer.insert(spu, Arc::new(pending));
             }
         }
     }
 
+    /// followers that lost connection to leader
+    pub async fn offline_followers(&self, followers: &[SpuId]) -> HashSet<SpuId> {
+        let reader = self.read().await;
+        followers
+            .iter()
+            .filter(|spu| reader.get(spu).is_some_and(|pending| pending.is_offline()))
+            .copied()
+            .collect()
+    }
+
     /// notify followers that it's state need to be updated
     pub async fn notify_follower(&self, spu: &SpuId, replica: ReplicaKey) {
         let reader = self.read().await;
@@ -83,6 +94,9 @@ impl FollowerNotifier {
 pub struct FollowerSpuPendingUpdates {
     event: Arc<OffsetPublisher>,
     replicas: Arc<RwLock<HashSet<ReplicaKey>>>,
+    // open connections of follower, none if follower has not connected yet.
+    // follower is assumed online until its connection to leader is lost
+    connections: Mutex<Option<usize>>,
 }
 
 impl FollowerSpuPendingUpdates {
@@ -103,6 +117,33 @@ impl FollowerSpuPendingUpdates {
         write.drain().collect()
     }
 
+    /// follower opened connection to this leader
+    pub fn connected(&self) {
+        let mut connections = self
+            .connections
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        *connections = Some(connections.unwrap_or_default() + 1);
+    }
+
+    /// follower connection to this leader is closed
+    pub fn disconnected(&self) {
+        let mut connections = self
+            .connections
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        *connections = Some(connections.unwrap_or_default().saturating_sub(1));
+    }
+
+    /// follower that lost all its connections to leader, it is not in sync
+    pub fn is_offline(&self) -> bool {
+        let connections = self
+            .connections
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        *connections == Some(0)
+    }
+
     #[allow(unused)]
     pub async fn has_replica(&self, replica: &ReplicaKey) -> bool {
         let read = self.replicas.read().await;
@@ -0,0 +1,321 @@
+//!
+//! # Transaction State
+//!
+//! Leader tracks open transactions of transactional producers, so `ReadCommitted` consumers
+//! only read records below the last stable offset, which is the first offset of the oldest
+//! open transaction. Offset ranges of aborted transactions are kept to filter aborted records
+//! until the log is truncated below them by retention.
+//! State is snapshotted in replica storage and rebuilt from the snapshot and the log after it
+//! when replica becomes leader.
+//!
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use async_rwlock::RwLock;
+use tracing::debug;
+
+use fluvio_protocol::{Encoder, Decoder};
+use fluvio_protocol::record::{Batch, ControlRecordType, Offset, RawRecords, RecordSet};
+use fluvio_spu_schema::fetch::AbortedTransaction;
+
+pub type SharedTransactionStates = Arc<RwLock<TransactionStates>>;
+
+/// Transaction of produced record set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProducedTransaction {
+    /// records written in transaction of the producer
+    Records {
+        producer_id: i64,
+        producer_epoch: i16,
+    },
+    /// control batch ending transaction of the producer
+    Control {
+        producer_id: i64,
+        control: ControlRecordType,
+    },
+}
+
+impl ProducedTransaction {
+    /// None if record set is not written by transactional producer
+    pub fn from_record_set(records: &RecordSet<RawRecords>) -> Option<Self> {
+        let batch = records.batches.first()?;
+        let header = batch.get_header();
+        if batch.is_control() {
+            let control = Batch::try_from(batch.clone()).ok()?.control_type()?;
+            return Some(Self::Control {
+                producer_id: header.producer_id,
+                control,
+            });
+        }
+        header.is_transactional().then_some(Self::Records {
+            producer_id: header.producer_id,
+            producer_epoch: header.producer_epoch,
+        })
+    }
+
+    /// keep batch created by smartmodules in the transaction
+    pub fn apply(&self, batch: &mut Batch<RawRecords>) {
+        if let Self::Records {
+            producer_id,
+            producer_epoch,
+        } = *self
+        {
+            let header = batch.get_mut_header();
+            header.producer_id = producer_id;
+            header.producer_epoch = producer_epoch;
+            header.set_transactional();
+        }
+    }
+
+    pub fn is_control(&self) -> bool {
+        matches!(self, Self::Control { .. })
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encoder, Decoder)]
+struct AbortedRange {
+    producer_id: i64,
+    first_offset: Offset,
+    /// offset of the abort control batch
+    last_offset: Offset,
+}
+
+#[derive(Debug, Default, Encoder, Decoder)]
+struct OngoingTransaction {
+    producer_id: i64,
+    first_offset: Offset,
+}
+
+/// Open and aborted transactions persisted in snapshot
+#[derive(Debug, Default, Encoder, Decoder)]
+pub struct TransactionsSnapshot {
+    ongoing: Vec<OngoingTransaction>,
+    aborted: Vec<AbortedRange>,
+}
+
+#[derive(Debug, Default)]
+pub struct TransactionStates {
+    /// first offset of open transaction by producer id
+    ongoing: HashMap<i64, Offset>,
+    /// aborted transactions ordered by offset of abort control batch
+    aborted: VecDeque<AbortedRange>,
+}
+
+impl TransactionStates {
+    pub fn shared() -> SharedTransactionStates {
+        Arc::new(RwLock::new(Self::default()))
+    }
+
+    /// transactional batch of producer was written at `offset`
+    fn begin(&mut self, producer_id: i64, offset: Offset) {
+        self.ongoing.entry(producer_id).or_insert(offset);
+    }
+
+    /// control batch of producer was written at `control_offset`
+    fn end(&mut self, producer_id: i64, control_offset: Offset, control: ControlRecordType) {
+        let Some(first_offset) = self.ongoing.remove(&producer_id) else {
+            debug!(producer_id, "no open transaction to end");
+            return;
+        };
+        debug!(
+            producer_id,
+            first_offset,
+            control_offset,
+            ?control,
+            "transaction ended"
+        );
+        if control == ControlRecordType::Abort {
+            self.aborted.push_back(AbortedRange {
+                producer_id,
+                first_offset,
+                last_offset: control_offset,
+            });
+        }
+    }
+
+    /// record set of the transaction was written at `base_offset`
+    pub fn record(&mut self, transaction: ProducedTransaction, base_offset: Offset) {
+        match transaction {
+            ProducedTransaction::Records { producer_id, .. } => {
+                self.begin(producer_id, base_offset)
+            }
+            ProducedTransaction::Control {
+                producer_id,
+                control,
+            } => self.end(producer_id, base_offset, control),
+        }
+    }
+
+    /// transactions to persist in snapshot
+    pub fn snapshot(&self) -> TransactionsSnapshot {
+        TransactionsSnapshot {
+            ongoing: self
+                .ongoing
+                .iter()
+                .map(|(producer_id, first_offset)| OngoingTransaction {
+                    producer_id: *producer_id,
+                    first_offset: *first_offset,
+                })
+                .collect(),
+            aborted: self.aborted.iter().copied().collect(),
+        }
+    }
+
+    /// replace state with transactions of snapshot
+    pub fn load_snapshot(&mut self, snapshot: TransactionsSnapshot) {
+        self.ongoing = snapshot
+            .ongoing
+            .into_iter()
+            .map(|transaction| (transaction.producer_id, transaction.first_offset))
+            .collect();
+        self.aborted = snapshot.aborted.into();
+    }
+
+    /// rebuild state from batch read from the log, records of control batch must be decoded
+    pub fn restore(&mut self, batch: &Batch) {
+        let header = batch.get_header();
+        if batch.is_control() {
+            if let Some(control) = batch.control_type() {
+                self.end(header.producer_id, batch.base_offset, control);
+            }
+        } else if header.is_transactional() {
+            self.begin(header.producer_id, batch.base_offset);
+        }
+    }
+
+    /// forget aborted transactions ended below `log_start_offset`, their records are removed
+    pub fn remove_aborted_before(&mut self, log_start_offset: Offset) {
+        while self
+            .aborted
+            .front()
+            .is_some_and(|range| range.last_offset < log_start_offset)
+        {
+            self.aborted.pop_front();
+        }
+    }
+
+    /// offset below which all transactions are ended, never greater than `hw`
+    pub fn last_stable_offset(&self, hw: Offset) -> Offset {
+        self.ongoing
+            .values()
+            .copied()
+            .min()
+            .map_or(hw, |first_offset| first_offset.min(hw))
+    }
+
+    /// aborted transactions with records between `start` and `end`
+    pub fn aborted_in(&self, start: Offset, end: Offset) -> Vec<AbortedTransaction> {
+        self.aborted
+            .iter()
+            .filter(|range| range.first_offset < end && range.last_offset >= start)
+            .map(|range| AbortedTransaction {
+                producer_id: range.producer_id,
+                first_offset: range.first_offset,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use fluvio_protocol::record::Record;
+
+    use super::*;
+
+    #[test]
+    fn test_last_stable_offset() {
+        let mut txns = TransactionStates::default();
+        assert_eq!(txns.last_stable_offset(10), 10);
+
+        txns.begin(1, 5);
+        txns.begin(2, 7);
+        // later batches of the same transaction don't move first offset
+        txns.begin(1, 8);
+        assert_eq!(txns.last_stable_offset(10), 5);
+        assert_eq!(txns.last_stable_offset(3), 3);
+
+        txns.end(1, 9, ControlRecordType::Commit);
+        assert_eq!(txns.last_stable_offset(10), 7);
+        txns.end(2, 10, ControlRecordType::Commit);
+        assert_eq!(txns.last_stable_offset(11), 11);
+        assert!(txns.aborted_in(0, 11).is_empty());
+    }
+
+    #[test]
+    fn test_aborted_transactions() {
+        let mut txns = TransactionStates::default();
+        txns.begin(1, 5);
+        txns.begin(2, 6);
+        txns.end(1, 8, ControlRecordType::Abort);
+        txns.end(2, 9, ControlRecordType::Commit);
+
+        let aborted = txns.aborted_in(0, 10);
+        assert_eq!(aborted.len(), 1);
+        assert_eq!(aborted[0].producer_id, 1);
+        assert_eq!(aborted[0].first_offset, 5);
+        assert!(txns.aborted_in(9, 10).is_empty());
+        assert!(txns.aborted_in(0, 5).is_empty());
+    }
+
+    #[test]
+    fn test_restore_from_log() {
+        let mut records = Batch::default();
+        records.add_record(Record::new("value"));
+        records.get_mut_header().producer_id = 1;
+        records.get_mut_header().set_transactional();
+        records.base_offset = 5;
+
+        let mut plain = Batch::default();
+        plain.add_record(Record::new("value"));
+        plain.base_offset = 6;
+
+        let mut abort = Batch::control(1, 0, ControlRecordType::Abort);
+        abort.base_offset = 7;
+
+        let mut txns = TransactionStates::default();
+        txns.restore(&records);
+        txns.restore(&plain);
+        assert_eq!(txns.last_stable_offset(7), 5);
+        txns.restore(&abort);
+        assert_eq!(txns.last_stable_offset(8), 8);
+        let aborted = txns.aborted_in(0, 8);
+        assert_eq!(aborted.len(), 1);
+        assert_eq!(aborted[0].first_offset, 5);
+    }
+
+    #[test]
+    fn test_restore_from_snapshot() {
+        let mut txns = TransactionStates::default();
+        txns.begin(1, 5);
+        txns.end(1, 8, ControlRecordType::Abort);
+        txns.begin(2, 9);
+
+        let mut snapshot = Vec::new();
+        txns.snapshot().encode(&mut snapshot, 0).expect("encode");
+        let decoded: TransactionsSnapshot =
+            Decoder::decode_from(&mut snapshot.as_slice(), 0).expect("decode");
+
+        let mut restored = TransactionStates::default();
+        restored.load_snapshot(decoded);
+        assert_eq!(restored.last_stable_offset(12), 9);
+        assert_eq!(restored.aborted_in(0, 12), txns.aborted_in(0, 12));
+    }
+
+    #[test]
+    fn test_remove_aborted_before_log_start() {
+        let mut txns = TransactionStates::default();
+        txns.begin(1, 5);
+        txns.end(1, 8, ControlRecordType::Abort);
+        txns.begin(2, 9);
+        txns.end(2, 12, ControlRecordType::Abort);
+
+        txns.remove_aborted_before(8);
+        assert_eq!(txns.aborted_in(0, 13).len(), 2);
+        txns.remove_aborted_before(9);
+        let aborted = txns.aborted_in(0, 13);
+        assert_eq!(aborted.len(), 1);
+        assert_eq!(aborted[0].producer_id, 2);
+    }
+}
@@ -6,6 +6,9 @@ use tracing::trace;
 use fluvio_protocol::bytes::Buf;
 use fluvio_protocol::{Encoder, Decoder};
 use fluvio_protocol::api::{RequestMessage, ApiMessage, RequestHeader};
+use fluvio_controlplane::txn_api::write_markers::WriteTxnMarkersRequest;
+use fluvio_controlplane::txn_api::commit_offsets::CommitTxnOffsetsRequest;
+use fluvio_controlplane::offset_api::{FetchGroupOffsetsRequest, ResetGroupOffsetsRequest};
 
 use super::fetch_stream_request::FetchStreamRequest;
 
@@ -14,6 +17,12 @@ use super::fetch_stream_request::FetchStreamRequest;
 #[fluvio(encode_discriminant)]
 pub enum SPUPeerApiEnum {
     FetchStream = 0,
+    // sent by SC, see fluvio_controlplane::txn_api::TxnApiKey
+    WriteTxnMarkers = 1,
+    CommitTxnOffsets = 2,
+    // sent by SC, see fluvio_controlplane::offset_api::OffsetApiKey
+    FetchGroupOffsets = 3,
+    ResetGroupOffsets = 4,
 }
 
 impl Default for SPUPeerApiEnum {
@@ -26,6 +35,14 @@ impl Default for SPUPeerApiEnum {
 pub enum SpuPeerRequest {
     #[fluvio(tag = 0)]
     FetchStream(RequestMessage<FetchStreamRequest>),
+    #[fluvio(tag = 1)]
+    WriteTxnMarkers(RequestMessage<WriteTxnMarkersRequest>),
+    #[fluvio(tag = 2)]
+    CommitTxnOffsets(RequestMessage<CommitTxnOffsetsRequest>),
+    #[fluvio(tag = 3)]
+    FetchGroupOffsets(RequestMessage<FetchGroupOffsetsRequest>),
+    #[fluvio(tag = 4)]
+    ResetGroupOffsets(RequestMessage<ResetGroupOffsetsRequest>),
 }
 
 impl Default for SpuPeerRequest {
@@ -50,6 +67,18 @@ impl ApiMessage for SpuPeerRequest {
                 header,
                 FetchStreamRequest::decode_from(src, version)?,
             ))),
+            SPUPeerApiEnum::WriteTxnMarkers => Ok(SpuPeerRequest::WriteTxnMarkers(
+                RequestMessage::new(header, WriteTxnMarkersRequest::decode_from(src, version)?),
+            )),
+            SPUPeerApiEnum::CommitTxnOffsets => Ok(SpuPeerRequest::CommitTxnOffsets(
+                RequestMessage::new(header, CommitTxnOffsetsRequest::decode_from(src, version)?),
+            )),
+            SPUPeerApiEnum::FetchGroupOffsets => Ok(SpuPeerRequest::FetchGroupOffsets(
+                RequestMessage::new(header, FetchGroupOffsetsRequest::decode_from(src, version)?),
+            )),
+            SPUPeerApiEnum::ResetGroupOffsets => Ok(SpuPeerRequest::ResetGroupOffsets(
+                RequestMessage::new(header, ResetGroupOffsetsRequest::decode_from(src, version)?),
+            )),
         }
     }
 }
@@ -1,6 +1,8 @@
 mod api;
 mod service_impl;
 mod fetch_stream_request;
+mod txn_handler;
+mod offset_handler;
 
 use tracing::info;
 
@@ -0,0 +1,68 @@
+use tracing::{debug, error, instrument};
+
+use fluvio_controlplane::offset_api::{
+    FetchGroupOffsetsRequest, FetchGroupOffsetsResponse, GroupOffsetResult,
+    ResetGroupOffsetsRequest, ResetGroupOffsetsResponse,
+};
+use fluvio_protocol::link::ErrorCode;
+
+use crate::core::DefaultSharedGlobalContext;
+
+/// offsets committed by consumer group, SC has already authorized the request
+#[instrument(skip(ctx))]
+pub(crate) async fn handle_fetch_group_offsets(
+    request: FetchGroupOffsetsRequest,
+    ctx: &DefaultSharedGlobalContext,
+) -> FetchGroupOffsetsResponse {
+    let mut partitions = Vec::with_capacity(request.replicas.len());
+    for replica in request.replicas {
+        let result = match ctx.leaders_state().get(&replica).await {
+            Some(leader_state) => GroupOffsetResult {
+                offset: leader_state
+                    .read()
+                    .await
+                    .get_consumer_offset(&request.consumer_group),
+                replica,
+                error_code: ErrorCode::None,
+            },
+            None => GroupOffsetResult {
+                replica,
+                error_code: ErrorCode::NotLeaderForPartition,
+                offset: None,
+            },
+        };
+        debug!(?result, "fetched group offset");
+        partitions.push(result);
+    }
+    FetchGroupOffsetsResponse { partitions }
+}
+
+/// remove offsets committed by consumer group on leaders and their followers
+#[instrument(skip(ctx))]
+pub(crate) async fn handle_reset_group_offsets(
+    request: ResetGroupOffsetsRequest,
+    ctx: &DefaultSharedGlobalContext,
+) -> ResetGroupOffsetsResponse {
+    let mut partitions = Vec::with_capacity(request.replicas.len());
+    for replica in request.replicas {
+        let error_code = match ctx.leaders_state().get(&replica).await {
+            Some(leader_state) => match leader_state
+                .reset_consumer_offset(&request.consumer_group, ctx.follower_notifier())
+                .await
+            {
+                Ok(_) => ErrorCode::None,
+                Err(err) => {
+                    error!(%err, "error resetting consumer offset");
+                    ErrorCode::StorageError
+                }
+            },
+            None => ErrorCode::NotLeaderForPartition,
+        };
+        partitions.push(GroupOffsetResult {
+            replica,
+            error_code,
+            offset: None,
+        });
+    }
+    ResetGroupOffsetsResponse { partitions }
+}
@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use futures_util::stream::StreamExt;
 use tracing::{debug, warn, instrument};
 use anyhow::Result;
 
-use fluvio_service::{wait_for_request, FluvioService, ConnectInfo};
+use fluvio_protocol::api::ResponseMessage;
+use fluvio_service::{FluvioService, ConnectInfo};
 use fluvio_socket::FluvioSocket;
 
 use crate::core::DefaultSharedGlobalContext;
@@ -12,6 +14,8 @@ use crate::replication::leader::FollowerHandler;
 use super::SpuPeerRequest;
 use super::SPUPeerApiEnum;
 use super::FetchStreamResponse;
+use super::txn_handler::{handle_commit_txn_offsets, handle_write_txn_markers};
+use super::offset_handler::{handle_fetch_group_offsets, handle_reset_group_offsets};
 
 #[derive(Debug)]
 pub struct InternalService {}
@@ -37,33 +41,55 @@ impl FluvioService for InternalService {
         let (mut sink, mut stream) = socket.split();
         let mut api_stream = stream.api_stream::<SpuPeerRequest, SPUPeerApiEnum>();
 
-        // register follower
-        let (follower_id, spu_update) = wait_for_request!(
-            api_stream,
-
-            SpuPeerRequest::FetchStream(req_msg) => {
-
-                let request = &req_msg.request;
-                let follower_id = request.spu_id;
-                debug!(
-                    follower_id,
-                    "received fetch stream"
-                );
-                // check if follower_id is valid
-                if let Some(spu_update) = ctx.follower_notifier().get(&follower_id).await {
-                    let response = FetchStreamResponse::new(follower_id);
-                    let res_msg = req_msg.new_response(response);
-                    sink
-                        .send_response(&res_msg, req_msg.header.api_version())
-                        .await?;
-                    (follower_id,spu_update)
-                } else {
-                    warn!(follower_id, "unknown spu, dropping connection");
-                    return Ok(())
+        // register follower, transaction and offset requests of SC are served until connection is closed
+        let (follower_id, spu_update) = loop {
+            let Some(msg) = api_stream.next().await else {
+                debug!("peer connection terminated");
+                return Ok(());
+            };
+            match msg? {
+                SpuPeerRequest::FetchStream(req_msg) => {
+                    let request = &req_msg.request;
+                    let follower_id = request.spu_id;
+                    debug!(follower_id, "received fetch stream");
+                    // check if follower_id is valid
+                    if let Some(spu_update) = ctx.follower_notifier().get(&follower_id).await {
+                        let response = FetchStreamResponse::new(follower_id);
+                        let res_msg = req_msg.new_response(response);
+                        sink.send_response(&res_msg, req_msg.header.api_version())
+                            .await?;
+                        break (follower_id, spu_update);
+                    } else {
+                        warn!(follower_id, "unknown spu, dropping connection");
+                        return Ok(());
+                    }
+                }
+                SpuPeerRequest::WriteTxnMarkers(req_msg) => {
+                    let (header, request) = req_msg.get_header_request();
+                    let response = handle_write_txn_markers(request, &ctx).await;
+                    let res_msg = ResponseMessage::from_header(&header, response);
+                    sink.send_response(&res_msg, header.api_version()).await?;
+                }
+                SpuPeerRequest::CommitTxnOffsets(req_msg) => {
+                    let (header, request) = req_msg.get_header_request();
+                    let response = handle_commit_txn_offsets(request, &ctx).await;
+                    let res_msg = ResponseMessage::from_header(&header, response);
+                    sink.send_response(&res_msg, header.api_version()).await?;
+                }
+                SpuPeerRequest::FetchGroupOffsets(req_msg) => {
+                    let (header, request) = req_msg.get_header_request();
+                    let response = handle_fetch_group_offsets(request, &ctx).await;
+                    let res_msg = ResponseMessage::from_header(&header, response);
+                    sink.send_response(&res_msg, header.api_version()).await?;
+                }
+                SpuPeerRequest::ResetGroupOffsets(req_msg) => {
+                    let (header, request) = req_msg.get_header_request();
+                    let response = handle_reset_group_offsets(request, &ctx).await;
+                    let res_msg = ResponseMessage::from_header(&header, response);
+                    sink.send_response(&res_msg, header.api_version()).await?;
                 }
-
             }
-        );
+        };
 
         drop(api_stream);
 
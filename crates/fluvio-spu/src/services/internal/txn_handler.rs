@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use tokio::select;
+use tracing::{debug, error, instrument};
+
+use fluvio_controlplane::txn_api::commit_offsets::{CommitTxnOffsetsRequest, CommitTxnOffsetsResponse};
+use fluvio_controlplane::txn_api::write_markers::{
+    TxnPartitionResult, WriteTxnMarkersRequest, WriteTxnMarkersResponse,
+};
+use fluvio_future::timer::sleep;
+use fluvio_protocol::api::RequestKind;
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::{ControlRecordType, Offset};
+use fluvio_spu_schema::Isolation;
+
+use crate::core::DefaultSharedGlobalContext;
+use crate::replication::leader::SharedFileLeaderState;
+
+/// write control batches of ending transaction and wait until they are replicated
+#[instrument(skip(ctx))]
+pub(crate) async fn handle_write_txn_markers(
+    request: WriteTxnMarkersRequest,
+    ctx: &DefaultSharedGlobalContext,
+) -> WriteTxnMarkersResponse {
+    let control = if request.commit {
+        ControlRecordType::Commit
+    } else {
+        ControlRecordType::Abort
+    };
+    let mut partitions = Vec::with_capacity(request.partitions.len());
+    for replica in request.partitions {
+        let error_code = match ctx.leaders_state().get(&replica).await {
+            Some(leader_state) => {
+                match leader_state
+                    .write_txn_marker(
+                        request.producer_id,
+                        request.producer_epoch,
+                        control,
+                        ctx.follower_notifier(),
+                    )
+                    .await
+                {
+                    Ok(leo) => wait_for_hw(&leader_state, leo, request.timeout).await,
+                    Err(err) => {
+                        error!(%replica, "error writing transaction marker: {err:#?}");
+                        match err.downcast_ref::<ErrorCode>() {
+                            Some(error_code) => error_code.clone(),
+                            None => ErrorCode::StorageError,
+                        }
+                    }
+                }
+            }
+            None => ErrorCode::NotLeaderForPartition,
+        };
+        partitions.push(TxnPartitionResult {
+            replica,
+            error_code,
+        });
+    }
+    WriteTxnMarkersResponse { partitions }
+}
+
+/// commit consumer offsets of committed transaction
+#[instrument(skip(ctx))]
+pub(crate) async fn handle_commit_txn_offsets(
+    request: CommitTxnOffsetsRequest,
+    ctx: &DefaultSharedGlobalContext,
+) -> CommitTxnOffsetsResponse {
+    let mut partitions = Vec::with_capacity(request.offsets.len());
+    for txn_offset in request.offsets {
+        let error_code = match ctx.leaders_state().get(&txn_offset.replica).await {
+            Some(leader_state) => match leader_state
+                .commit_consumer_offset(
+                    &txn_offset.consumer_group,
+                    txn_offset.offset,
+                    ctx.follower_notifier(),
+                )
+                .await
+            {
+                Ok(_) => ErrorCode::None,
+                Err(err) => {
+                    error!(%err, "error committing consumer offset");
+                    ErrorCode::StorageError
+                }
+            },
+            None => ErrorCode::NotLeaderForPartition,
+        };
+        partitions.push(TxnPartitionResult {
+            replica: txn_offset.replica,
+            error_code,
+        });
+    }
+    CommitTxnOffsetsResponse { partitions }
+}
+
+async fn wait_for_hw(
+    leader_state: &SharedFileLeaderState,
+    leo: Offset,
+    timeout: Duration,
+) -> ErrorCode {
+    let mut listener = leader_state.offset_listener(&Isolation::ReadCommitted);
+    let wait_future = async {
+        while leader_state.hw() < leo {
+            listener.listen().await;
+        }
+    };
+    select! {
+        _ = wait_future => ErrorCode::None,
+        _ = sleep(timeout) => {
+            debug!(replica = %leader_state.id(), "transaction marker is not replicated in time");
+            ErrorCode::RequestTimedOut {
+                kind: RequestKind::Produce,
+                timeout_ms: timeout.as_millis() as u64,
+            }
+        },
+    }
+}
@@ -9,6 +9,9 @@ use fluvio_spu_schema::server::SpuServerApiKey;
 use fluvio_spu_schema::server::fetch_offset::FetchOffsetsRequest;
 use fluvio_spu_schema::server::stream_fetch::DefaultStreamFetchRequest;
 use fluvio_spu_schema::server::update_offset::UpdateOffsetsRequest;
+use fluvio_spu_schema::server::consumer_offset::{
+    CommitConsumerOffsetRequest, FetchConsumerOffsetRequest, ResetConsumerOffsetRequest,
+};
 use fluvio_spu_schema::{ApiVersionsRequest, ApiVersionsResponse};
 
 #[instrument(skip(request))]
@@ -47,6 +50,21 @@ pub async fn handle_api_version_request(
         0,
         UpdateOffsetsRequest::DEFAULT_API_VERSION,
     ));
+    response.api_keys.push(make_version_key(
+        SpuServerApiKey::CommitConsumerOffset,
+        0,
+        CommitConsumerOffsetRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        SpuServerApiKey::FetchConsumerOffset,
+        0,
+        FetchConsumerOffsetRequest::DEFAULT_API_VERSION,
+    ));
+    response.api_keys.push(make_version_key(
+        SpuServerApiKey::ResetConsumerOffset,
+        0,
+        ResetConsumerOffsetRequest::DEFAULT_API_VERSION,
+    ));
 
     trace!("Returning ApiVersionsResponse: {:#?}", &response);
     Ok(request.new_response(response))
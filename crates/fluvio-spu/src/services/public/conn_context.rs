@@ -1,14 +1,34 @@
+use std::time::{Duration, Instant};
+
+use fluvio_auth::InstanceAction;
+
+use crate::core::auth::{self, SpuAuthContext};
+use crate::core::quota::QuotaClient;
 use crate::services::public::StreamPublishers;
 
 #[derive(Debug)]
 pub(crate) struct ConnectionContext {
     stream_publishers: StreamPublishers,
+    /// auth context of client, only known when SPU is behind authenticating proxy
+    auth: Option<SpuAuthContext>,
+    /// client whose usage is limited by quotas
+    quota_client: QuotaClient,
+    /// client exceeded quota, no more requests are read until then
+    throttled_until: Option<Instant>,
 }
 
 impl ConnectionContext {
-    pub(crate) fn new() -> Self {
+    /// `peer` is address of client connection
+    pub(crate) fn new(auth: Option<SpuAuthContext>, peer: &str) -> Self {
+        let quota_client = match &auth {
+            Some(auth) => QuotaClient::Principal(auth.principal().to_owned()),
+            None => QuotaClient::anonymous(peer),
+        };
         Self {
             stream_publishers: StreamPublishers::new(),
+            auth,
+            quota_client,
+            throttled_until: None,
         }
     }
 
@@ -19,4 +39,46 @@ impl ConnectionContext {
     pub(crate) fn stream_publishers_mut(&mut self) -> &mut StreamPublishers {
         &mut self.stream_publishers
     }
+
+    /// X509 principal of client
+    pub(crate) fn principal(&self) -> Option<&str> {
+        self.auth.as_ref().map(SpuAuthContext::principal)
+    }
+
+    /// client whose usage is limited by quotas
+    pub(crate) fn quota_client(&self) -> &QuotaClient {
+        &self.quota_client
+    }
+
+    /// auth context of client, if SPU knows its identity
+    pub(crate) fn auth(&self) -> Option<&SpuAuthContext> {
+        self.auth.as_ref()
+    }
+
+    /// check if client is allowed `action` on `topic`,
+    /// client is allowed if SPU doesn't know its identity
+    pub(crate) async fn allow_topic_action(&self, action: InstanceAction, topic: &str) -> bool {
+        auth::allow_topic_action(self.auth.as_ref(), action, topic).await
+    }
+
+    /// mute connection for `duration`
+    pub(crate) fn throttle(&mut self, duration: Duration) {
+        if duration.is_zero() {
+            return;
+        }
+        let until = Instant::now() + duration;
+        if self.throttled_until.map_or(true, |current| current < until) {
+            self.throttled_until = Some(until);
+        }
+    }
+
+    /// wait until connection is no longer throttled
+    pub(crate) async fn wait_throttle(&mut self) {
+        if let Some(until) = self.throttled_until.take() {
+            let remaining = until.saturating_duration_since(Instant::now());
+            if !remaining.is_zero() {
+                fluvio_future::timer::sleep(remaining).await;
+            }
+        }
+    }
 }
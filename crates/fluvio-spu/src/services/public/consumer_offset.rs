@@ -0,0 +1,152 @@
+use std::io::Error as IoError;
+
+use tracing::{debug, error, instrument};
+
+use fluvio_protocol::api::{RequestMessage, ResponseMessage};
+use fluvio_protocol::link::ErrorCode;
+use fluvio_spu_schema::server::consumer_offset::{
+    CommitConsumerOffsetRequest, CommitConsumerOffsetResponse, FetchConsumerOffsetRequest,
+    FetchConsumerOffsetResponse, ResetConsumerOffsetRequest, ResetConsumerOffsetResponse,
+};
+use fluvio_storage::ReplicaStorage;
+use fluvio_auth::InstanceAction;
+
+use crate::core::DefaultSharedGlobalContext;
+use crate::services::public::conn_context::ConnectionContext;
+
+#[instrument(skip(req_msg, ctx, conn_ctx))]
+pub(crate) async fn handle_commit_consumer_offset_request(
+    req_msg: RequestMessage<CommitConsumerOffsetRequest>,
+    ctx: DefaultSharedGlobalContext,
+    conn_ctx: &ConnectionContext,
+) -> Result<ResponseMessage<CommitConsumerOffsetResponse>, IoError> {
+    let request = req_msg.request();
+    debug!(
+        consumer_group = %request.consumer_group,
+        replica = %request.replica,
+        offset = request.offset,
+        "commit consumer offset"
+    );
+
+    let error_code = if let Err(err) = validate_consumer_group(&request.consumer_group) {
+        err
+    } else if !conn_ctx
+        .allow_topic_action(InstanceAction::Write, &request.replica.topic)
+        .await
+    {
+        debug!("client is not allowed to commit offsets of topic");
+        ErrorCode::PermissionDenied
+    } else if let Some(leader) = ctx.leaders_state().get(&request.replica).await {
+        let (start_offset, hw) = leader.start_offset_info().await;
+        if request.offset < start_offset || request.offset > hw {
+            debug!(start_offset, hw, "committed offset is out of range");
+            ErrorCode::OffsetOutOfRange
+        } else {
+            match leader
+                .commit_consumer_offset(
+                    &request.consumer_group,
+                    request.offset,
+                    ctx.follower_notifier(),
+                )
+                .await
+            {
+                Ok(_) => ErrorCode::None,
+                Err(err) => {
+                    error!(%err, "error committing consumer offset");
+                    ErrorCode::StorageError
+                }
+            }
+        }
+    } else {
+        ErrorCode::PartitionNotLeader
+    };
+
+    Ok(req_msg.new_response(CommitConsumerOffsetResponse { error_code }))
+}
+
+#[instrument(skip(req_msg, ctx, conn_ctx))]
+pub(crate) async fn handle_fetch_consumer_offset_request(
+    req_msg: RequestMessage<FetchConsumerOffsetRequest>,
+    ctx: DefaultSharedGlobalContext,
+    conn_ctx: &ConnectionContext,
+) -> Result<ResponseMessage<FetchConsumerOffsetResponse>, IoError> {
+    let request = req_msg.request();
+
+    let response = if let Err(error_code) = validate_consumer_group(&request.consumer_group) {
+        FetchConsumerOffsetResponse {
+            error_code,
+            offset: None,
+        }
+    } else if !conn_ctx
+        .allow_topic_action(InstanceAction::Read, &request.replica.topic)
+        .await
+    {
+        debug!(replica = %request.replica, "client is not allowed to fetch offsets of topic");
+        FetchConsumerOffsetResponse {
+            error_code: ErrorCode::PermissionDenied,
+            offset: None,
+        }
+    } else if let Some(leader) = ctx.leaders_state().get(&request.replica).await {
+        let offset = leader
+            .read()
+            .await
+            .get_consumer_offset(&request.consumer_group);
+        debug!(consumer_group = %request.consumer_group, replica = %request.replica, ?offset, "fetch consumer offset");
+        FetchConsumerOffsetResponse {
+            error_code: ErrorCode::None,
+            offset,
+        }
+    } else {
+        FetchConsumerOffsetResponse {
+            error_code: ErrorCode::PartitionNotLeader,
+            offset: None,
+        }
+    };
+
+    Ok(req_msg.new_response(response))
+}
+
+#[instrument(skip(req_msg, ctx, conn_ctx))]
+pub(crate) async fn handle_reset_consumer_offset_request(
+    req_msg: RequestMessage<ResetConsumerOffsetRequest>,
+    ctx: DefaultSharedGlobalContext,
+    conn_ctx: &ConnectionContext,
+) -> Result<ResponseMessage<ResetConsumerOffsetResponse>, IoError> {
+    let request = req_msg.request();
+    debug!(consumer_group = %request.consumer_group, replica = %request.replica, "reset consumer offset");
+
+    let error_code = if let Err(err) = validate_consumer_group(&request.consumer_group) {
+        err
+    } else if !conn_ctx
+        .allow_topic_action(InstanceAction::Write, &request.replica.topic)
+        .await
+    {
+        debug!("client is not allowed to reset offsets of topic");
+        ErrorCode::PermissionDenied
+    } else if let Some(leader) = ctx.leaders_state().get(&request.replica).await {
+        match leader
+            .reset_consumer_offset(&request.consumer_group, ctx.follower_notifier())
+            .await
+        {
+            Ok(_) => ErrorCode::None,
+            Err(err) => {
+                error!(%err, "error resetting consumer offset");
+                ErrorCode::StorageError
+            }
+        }
+    } else {
+        ErrorCode::PartitionNotLeader
+    };
+
+    Ok(req_msg.new_response(ResetConsumerOffsetResponse { error_code }))
+}
+
+fn validate_consumer_group(consumer_group: &str) -> Result<(), ErrorCode> {
+    if consumer_group.trim().is_empty() {
+        Err(ErrorCode::ConsumerGroupInvalidName(
+            consumer_group.to_owned(),
+        ))
+    } else {
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use tracing::{debug, trace, instrument};
 use anyhow::Result;
 
@@ -5,45 +7,86 @@ use fluvio_spu_schema::file::FileRecordSet;
 use fluvio_socket::ExclusiveFlvSink;
 use fluvio_socket::SocketError;
 use fluvio_protocol::{link::ErrorCode, api::RequestMessage};
+use fluvio_protocol::record::{RawRecords, RecordSet};
 use fluvio_spu_schema::fetch::{
-    FileFetchResponse, FileFetchRequest, FilePartitionResponse, FileTopicResponse,
+    FetchRequest, FileFetchResponse, FileFetchRequest, FilePartitionResponse, FileTopicResponse,
     FetchablePartitionResponse, FetchPartition, FetchableTopic, FetchableTopicResponse,
 };
 use fluvio_controlplane_metadata::partition::ReplicaKey;
+use fluvio_auth::InstanceAction;
 
 use crate::core::DefaultSharedGlobalContext;
+use crate::core::quota::{QuotaRate, QuotaClient};
+use crate::services::public::conn_context::ConnectionContext;
+use crate::services::public::record_headers::{
+    fetch_response_has_record_headers, fetch_response_without_headers, supports_record_headers,
+};
 use crate::traffic::TrafficType;
 
 /// perform log fetch request using zero copy write
 #[instrument(
-    skip(request, ctx, sink),
+    skip(request, ctx, conn_ctx, sink),
     fields(
         max_bytes = request.request.max_bytes,
     ),
 )]
-pub async fn handle_fetch_request(
+pub(crate) async fn handle_fetch_request(
     request: RequestMessage<FileFetchRequest>,
     ctx: DefaultSharedGlobalContext,
+    conn_ctx: &mut ConnectionContext,
     sink: ExclusiveFlvSink,
 ) -> Result<()> {
     let (header, fetch_request) = request.get_header_request();
     trace!("Handling FileFetchRequest: {:#?}", fetch_request);
     let mut fetch_response = FileFetchResponse::default();
 
+    let mut throttle = Duration::ZERO;
     for topic_request in &fetch_request.topics {
+        if !conn_ctx
+            .allow_topic_action(InstanceAction::Read, &topic_request.name)
+            .await
+        {
+            debug!(topic = %topic_request.name, "client is not allowed to fetch from topic");
+            fetch_response
+                .topics
+                .push(denied_topic_response(topic_request));
+            continue;
+        }
         let topic_response =
             handle_fetch_topic(&ctx, &fetch_request, topic_request, header.is_connector()).await?;
+        throttle = throttle.max(record_fetch_usage(
+            &ctx,
+            conn_ctx.quota_client(),
+            &topic_response,
+        ));
         fetch_response.topics.push(topic_response);
     }
 
-    let response =
-        RequestMessage::<FileFetchRequest>::response_with_header(&header, fetch_response);
-    trace!("Sending FileFetchResponse: {:#?}", response);
+    if !throttle.is_zero() {
+        debug!(?throttle, "client exceeded quota");
+        fetch_response.throttle_time_ms = throttle.as_millis() as i32;
+        conn_ctx.throttle(throttle);
+    }
 
     let mut inner = sink.lock().await;
-    inner
-        .encode_file_slices(&response, header.api_version())
-        .await?;
+    if supports_record_headers(header.api_version())
+        || !fetch_response_has_record_headers(&fetch_response)
+    {
+        let response =
+            RequestMessage::<FileFetchRequest>::response_with_header(&header, fetch_response);
+        trace!("Sending FileFetchResponse: {:#?}", response);
+        inner
+            .encode_file_slices(&response, header.api_version())
+            .await?;
+    } else {
+        debug!("client doesn't support record headers, sending records without headers");
+        let response = RequestMessage::<FetchRequest<RecordSet<RawRecords>>>::response_with_header(
+            &header,
+            fetch_response_without_headers(fetch_response),
+        );
+        trace!("Sending FetchResponse: {:#?}", response);
+        inner.send_response(&response, header.api_version()).await?;
+    }
 
     drop(inner);
 
@@ -51,6 +94,45 @@ pub async fn handle_fetch_request(
     Ok(())
 }
 
+/// all partitions of topic rejected because client is not authorized
+fn denied_topic_response(topic_request: &FetchableTopic) -> FileTopicResponse {
+    FileTopicResponse {
+        name: topic_request.name.clone(),
+        partitions: topic_request
+            .fetch_partitions
+            .iter()
+            .map(|partition| FilePartitionResponse {
+                partition_index: partition.partition_index,
+                error_code: ErrorCode::PermissionDenied,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// record request and bytes fetched from topic in quotas of client
+fn record_fetch_usage(
+    ctx: &DefaultSharedGlobalContext,
+    client: &QuotaClient,
+    topic_response: &FetchableTopicResponse<FileRecordSet>,
+) -> Duration {
+    let bytes: u64 = topic_response
+        .partitions
+        .iter()
+        .map(|partition| partition.records.len() as u64)
+        .sum();
+    let requests_throttle =
+        ctx.record_quota_usage(client, &topic_response.name, QuotaRate::Requests, 1);
+    let bytes_throttle = ctx.record_quota_usage(
+        client,
+        &topic_response.name,
+        QuotaRate::ConsumerBytes,
+        bytes,
+    );
+    requests_throttle.max(bytes_throttle)
+}
+
 #[instrument(
     skip(ctx, fetch_request, topic_request),
     fields(topic = %topic_request.name),
@@ -3,7 +3,9 @@ mod produce_handler;
 mod fetch_handler;
 mod offset_request;
 mod offset_update;
+mod consumer_offset;
 mod stream_fetch;
+mod record_headers;
 
 #[cfg(test)]
 mod tests;
@@ -15,6 +17,7 @@ use tracing::{info, debug, trace, instrument};
 use futures_util::StreamExt;
 use anyhow::Result;
 
+use fluvio_auth::Authorization;
 use fluvio_socket::FluvioSocket;
 use fluvio_service::{FluvioApiServer, FluvioService, ConnectInfo, call_service};
 use fluvio_spu_schema::server::SpuServerRequest;
@@ -27,6 +30,10 @@ use self::produce_handler::handle_produce_request;
 use self::fetch_handler::handle_fetch_request;
 use self::offset_request::handle_offset_request;
 use self::offset_update::handle_offset_update;
+use self::consumer_offset::{
+    handle_commit_consumer_offset_request, handle_fetch_consumer_offset_request,
+    handle_reset_consumer_offset_request,
+};
 use self::stream_fetch::{StreamFetchHandler, publishers::StreamPublishers};
 use self::conn_context::ConnectionContext;
 
@@ -63,18 +70,31 @@ impl FluvioService for PublicService {
     async fn respond(
         self: Arc<Self>,
         context: DefaultSharedGlobalContext,
-        socket: FluvioSocket,
-        _connection: ConnectInfo,
+        mut socket: FluvioSocket,
+        connection: ConnectInfo,
     ) -> Result<()> {
+        // authenticating proxy sends client identity before any request
+        let auth = if context.config().x509_auth_scopes.is_some() {
+            Some(
+                context
+                    .authorization()
+                    .create_auth_context(&mut socket)
+                    .await?,
+            )
+        } else {
+            None
+        };
+
         let (sink, mut stream) = socket.split();
 
         let mut shared_sink = sink.as_shared();
         let api_stream = stream.api_stream::<SpuServerRequest, SpuServerApiKey>();
         let shutdown = StickyEvent::shared();
         let mut event_stream = api_stream.take_until(shutdown.listen_pinned());
-        let mut conn_ctx = ConnectionContext::new();
+        let mut conn_ctx = ConnectionContext::new(auth, connection.peer());
 
         loop {
+            conn_ctx.wait_throttle().await;
             let event = event_stream.next().await;
             match event {
                 Some(Ok(req_message)) => {
@@ -94,17 +114,22 @@ impl FluvioService for PublicService {
                         ),
                         SpuServerRequest::ProduceRequest(request) => call_service!(
                             request,
-                            handle_produce_request(request, context.clone()),
+                            handle_produce_request(request, context.clone(), &mut conn_ctx),
                             shared_sink,
                             "ProduceRequest"
                         ),
                         SpuServerRequest::FileFetchRequest(request) => {
-                            handle_fetch_request(request, context.clone(), shared_sink.clone())
-                                .await?
+                            handle_fetch_request(
+                                request,
+                                context.clone(),
+                                &mut conn_ctx,
+                                shared_sink.clone(),
+                            )
+                            .await?
                         }
                         SpuServerRequest::FetchOffsetsRequest(request) => call_service!(
                             request,
-                            handle_offset_request(request, context.clone()),
+                            handle_offset_request(request, context.clone(), &conn_ctx),
                             shared_sink,
                             "FetchOffsetsRequest"
                         ),
@@ -124,6 +149,36 @@ impl FluvioService for PublicService {
                             shared_sink,
                             "UpdateOffsetsRequest"
                         ),
+                        SpuServerRequest::CommitConsumerOffsetRequest(request) => call_service!(
+                            request,
+                            handle_commit_consumer_offset_request(
+                                request,
+                                context.clone(),
+                                &conn_ctx
+                            ),
+                            shared_sink,
+                            "CommitConsumerOffsetRequest"
+                        ),
+                        SpuServerRequest::FetchConsumerOffsetRequest(request) => call_service!(
+                            request,
+                            handle_fetch_consumer_offset_request(
+                                request,
+                                context.clone(),
+                                &conn_ctx
+                            ),
+                            shared_sink,
+                            "FetchConsumerOffsetRequest"
+                        ),
+                        SpuServerRequest::ResetConsumerOffsetRequest(request) => call_service!(
+                            request,
+                            handle_reset_consumer_offset_request(
+                                request,
+                                context.clone(),
+                                &conn_ctx
+                            ),
+                            shared_sink,
+                            "ResetConsumerOffsetRequest"
+                        ),
                     }
                 }
                 Some(Err(e)) => {
@@ -1,6 +1,6 @@
 use std::io::Error as IoError;
 
-use tracing::{trace, instrument};
+use tracing::{debug, trace, error, instrument};
 
 use fluvio_protocol::api::{RequestMessage, ResponseMessage};
 use fluvio_spu_schema::server::fetch_offset::FetchOffsetsRequest;
@@ -9,13 +9,17 @@ use fluvio_spu_schema::server::fetch_offset::FetchOffsetsResponse;
 use fluvio_spu_schema::server::fetch_offset::FetchOffsetPartitionResponse;
 use fluvio_controlplane_metadata::partition::ReplicaKey;
 use fluvio_protocol::link::ErrorCode;
+use fluvio_storage::ReplicaStorage;
+use fluvio_auth::InstanceAction;
 
 use crate::core::DefaultSharedGlobalContext;
+use crate::services::public::conn_context::ConnectionContext;
 
-#[instrument(skip(req_msg, ctx))]
-pub async fn handle_offset_request(
+#[instrument(skip(req_msg, ctx, conn_ctx))]
+pub(crate) async fn handle_offset_request(
     req_msg: RequestMessage<FetchOffsetsRequest>,
     ctx: DefaultSharedGlobalContext,
+    conn_ctx: &ConnectionContext,
 ) -> Result<ResponseMessage<FetchOffsetsResponse>, IoError> {
     let request = req_msg.request();
     trace!("handling flv fetch request: {:#?}", request);
@@ -29,6 +33,12 @@ pub async fn handle_offset_request(
             name: topic.clone(),
             ..Default::default()
         };
+        let allowed = conn_ctx
+            .allow_topic_action(InstanceAction::Read, topic)
+            .await;
+        if !allowed {
+            debug!(%topic, "client is not allowed to fetch offsets of topic");
+        }
 
         for partition_req in &topic_request.partitions {
             let partition = &partition_req.partition_index;
@@ -37,12 +47,31 @@ pub async fn handle_offset_request(
                 ..Default::default()
             };
             let rep_id = ReplicaKey::new(topic.clone(), *partition);
-            if let Some(ref replica) = ctx.leaders_state().get(&rep_id).await {
+            if !allowed {
+                partition_response.error_code = ErrorCode::PermissionDenied;
+            } else if let Some(ref replica) = ctx.leaders_state().get(&rep_id).await {
                 trace!("offset fetch request for replica found: {}", rep_id);
                 let (start_offset, hw) = replica.start_offset_info().await;
                 partition_response.error_code = ErrorCode::None;
                 partition_response.start_offset = start_offset;
                 partition_response.last_stable_offset = hw;
+                if let Some(timestamp) = partition_req.timestamp {
+                    match replica
+                        .read()
+                        .await
+                        .find_offset_by_timestamp(timestamp)
+                        .await
+                    {
+                        Ok(offset) => {
+                            trace!(timestamp, offset, "resolved offset by timestamp");
+                            partition_response.timestamp_offset = Some(offset);
+                        }
+                        Err(err) => {
+                            error!(%err, timestamp, "error resolving offset by timestamp");
+                            partition_response.error_code = err;
+                        }
+                    }
+                }
             } else {
                 trace!("offset fetch request is not found: {}", rep_id);
                 partition_response.error_code = ErrorCode::PartitionNotLeader;
@@ -1,11 +1,10 @@
 use std::time::Duration;
 
-use tokio::select;
-use tracing::{debug, trace, error};
+use tracing::{debug, trace, error, warn};
 use tracing::instrument;
 use anyhow::{anyhow, Result};
 
-use fluvio_protocol::api::{RequestKind, RequestHeader};
+use fluvio_protocol::api::RequestHeader;
 use fluvio_spu_schema::Isolation;
 use fluvio_protocol::record::{BatchRecords, Offset, Batch, RawRecords};
 use fluvio::Compression;
@@ -20,11 +19,13 @@ use fluvio_protocol::{api::RequestMessage, link::ErrorCode};
 use fluvio_protocol::api::ResponseMessage;
 use fluvio_protocol::record::RecordSet;
 use fluvio_controlplane_metadata::partition::ReplicaKey;
-
-use fluvio_future::timer::sleep;
+use fluvio_auth::InstanceAction;
 
 use crate::core::DefaultSharedGlobalContext;
-use crate::replication::leader::SharedFileLeaderState;
+use crate::core::auth::SpuAuthContext;
+use crate::core::quota::{QuotaRate, QuotaClient};
+use crate::services::public::conn_context::ConnectionContext;
+use crate::replication::leader::{ProducedTransaction, ProducerStates, SharedFileLeaderState};
 use crate::smartengine::batch::process_batch;
 use crate::smartengine::context::SmartModuleContext;
 use crate::smartengine::EngineError;
@@ -47,15 +48,16 @@ struct PartitionWriteResult {
 }
 
 #[instrument(
-    skip(request,ctx),
+    skip(request,ctx,conn_ctx),
     fields(
         id = request.header.correlation_id(),
         client = %request.header.client_id()
     )
 )]
-pub async fn handle_produce_request(
+pub(crate) async fn handle_produce_request(
     request: RequestMessage<DefaultProduceRequest>,
     ctx: DefaultSharedGlobalContext,
+    conn_ctx: &mut ConnectionContext,
 ) -> Result<ResponseMessage<ProduceResponse>> {
     let (header, produce_request) = request.get_header_request();
     trace!("Handling ProduceRequest: {:#?}", produce_request);
@@ -63,9 +65,24 @@ pub async fn handle_produce_request(
     let smartmodules = produce_request.smartmodules;
 
     let mut topic_results = Vec::with_capacity(produce_request.topics.len());
+    let mut throttle = Duration::ZERO;
     for topic_request in produce_request.topics.into_iter() {
+        if !conn_ctx
+            .allow_topic_action(InstanceAction::Write, &topic_request.name)
+            .await
+        {
+            debug!(topic = %topic_request.name, "client is not allowed to produce to topic");
+            topic_results.push(TopicWriteResult::denied(&topic_request));
+            continue;
+        }
+        throttle = throttle.max(record_produce_usage(
+            &ctx,
+            conn_ctx.quota_client(),
+            &topic_request,
+        ));
         let topic_result =
-            handle_produce_topic(&ctx, topic_request, &smartmodules, &header).await?;
+            handle_produce_topic(&ctx, topic_request, &smartmodules, &header, conn_ctx.auth())
+                .await?;
         topic_results.push(topic_result);
     }
     wait_for_acks(
@@ -75,7 +92,12 @@ pub async fn handle_produce_request(
         &ctx,
     )
     .await;
-    let response = into_response(topic_results);
+    let mut response = into_response(topic_results);
+    if !throttle.is_zero() {
+        debug!(?throttle, "client exceeded quota");
+        response.throttle_time_ms = throttle.as_millis() as i32;
+        conn_ctx.throttle(throttle);
+    }
     trace!("Returning ProduceResponse: {:#?}", &response);
     Ok(RequestMessage::<DefaultProduceRequest>::response_with_header(&header, response))
 }
@@ -89,6 +111,7 @@ async fn handle_produce_topic(
     topic_request: DefaultTopicRequest,
     smartmodules: &[SmartModuleInvocation],
     header: &RequestHeader,
+    auth: Option<&SpuAuthContext>,
 ) -> Result<TopicWriteResult> {
     let topic = &topic_request.name;
 
@@ -101,6 +124,20 @@ async fn handle_produce_topic(
 
     for mut partition_request in topic_request.partitions.into_iter() {
         let replica_id = ReplicaKey::new(topic.clone(), partition_request.partition_index);
+        // control batches are written by SC thru private endpoint only
+        if partition_request
+            .records
+            .batches
+            .iter()
+            .any(|batch| batch.is_control())
+        {
+            debug!(%replica_id, "control batch from client rejected");
+            topic_result.partitions.push(PartitionWriteResult::error(
+                replica_id,
+                ErrorCode::PermissionDenied,
+            ));
+            continue;
+        }
         let leader_state = match ctx.leaders_state().get(&replica_id).await {
             Some(leader_state) => leader_state,
             None => {
@@ -114,38 +151,132 @@ async fn handle_produce_topic(
             }
         };
 
-        if let Err(err) = apply_smartmodules(
+        // sequences are checked before smartmodules, their output has no producer ids.
+        // Lock is taken for batches of idempotent producers only and held until write,
+        // so concurrent batches of the producer are checked in order.
+        let producer_states = leader_state.producers().clone();
+        let mut producers = if ProducerStates::has_producer_batches(&partition_request.records) {
+            Some(producer_states.lock().await)
+        } else {
+            None
+        };
+        let mut staged = match producers
+            .as_ref()
+            .map(|producers| producers.check_record_set(&mut partition_request.records))
+            .transpose()
+        {
+            Ok(staged) => staged.unwrap_or_default(),
+            Err(error_code) => {
+                debug!(%replica_id, %error_code, "producer sequence rejected");
+                topic_result
+                    .partitions
+                    .push(PartitionWriteResult::error(replica_id, error_code));
+                continue;
+            }
+        };
+        if partition_request.records.batches.is_empty() {
+            if let Some(duplicate) = staged.duplicate.take() {
+                debug!(%replica_id, ?duplicate, "duplicate batches skipped");
+                topic_result
+                    .partitions
+                    .push(PartitionWriteResult::duplicate(
+                        replica_id,
+                        duplicate,
+                        leader_state.leo(),
+                    ));
+                continue;
+            }
+        }
+
+        // transaction is taken before smartmodules, their output batch is not transactional
+        let transaction = ProducedTransaction::from_record_set(&partition_request.records);
+        let chain = smartmodules.to_vec();
+        if !chain.is_empty() {
+            // producer state is not locked while smartmodules run,
+            // batches are checked again before write
+            drop(producers.take());
+        }
+        let sm_ctx = match apply_smartmodules(
             &mut partition_request,
-            smartmodules,
+            chain,
+            transaction,
             header.api_version(),
             &leader_state,
             ctx,
+            auth,
         )
         .await
         {
-            error!(
-                ?replica_id,
-                api_version = header.api_version(),
-                "smartmodule engine failed: {err:#?}"
-            );
-            topic_result
-                .partitions
-                .push(PartitionWriteResult::error(replica_id, err));
-            continue;
+            Ok(sm_ctx) => {
+                if sm_ctx.is_some() {
+                    staged.merge_offsets();
+                }
+                sm_ctx
+            }
+            Err(err) => {
+                error!(
+                    ?replica_id,
+                    api_version = header.api_version(),
+                    "smartmodule engine failed: {err:#?}"
+                );
+                topic_result
+                    .partitions
+                    .push(PartitionWriteResult::error(replica_id, err));
+                continue;
+            }
         };
+        if producers.is_none() && !staged.is_empty() {
+            let locked = producers.insert(producer_states.lock().await);
+            if let Some(duplicate) = locked.recheck(&staged) {
+                debug!(%replica_id, ?duplicate, "batches written or rejected meanwhile");
+                topic_result
+                    .partitions
+                    .push(PartitionWriteResult::duplicate(
+                        replica_id,
+                        duplicate,
+                        leader_state.leo(),
+                    ));
+                continue;
+            }
+        }
 
+        // transaction state is locked until written records are recorded in it,
+        // so committed reads don't see records of the transaction before that
+        let transaction_states = leader_state.transactions().clone();
+        let mut transactions = match transaction {
+            Some(_) => Some(transaction_states.write().await),
+            None => None,
+        };
         let partition_response = if partition_request.records.total_records() == 0 {
-            PartitionWriteResult::filtered(replica_id)
+            PartitionWriteResult::filtered(replica_id, leader_state.leo())
         } else {
             handle_produce_partition(
                 ctx,
                 replica_id,
-                leader_state,
+                &leader_state,
                 partition_request,
                 header.is_connector(),
             )
             .await
         };
+        if partition_response.error_code.is_ok() {
+            // records are written, failure to persist key-value states is not reported to producer
+            if let Some(sm_ctx) = &sm_ctx {
+                if let Err(err) = sm_ctx.commit_key_values(&leader_state).await {
+                    warn!(%err, "smartmodule key-value states not persisted after write");
+                }
+            }
+            if let Some(producers) = producers.as_mut() {
+                producers.update(staged, partition_response.base_offset);
+            }
+            if let (Some(transaction), Some(transactions)) = (transaction, transactions.as_mut()) {
+                if partition_response.leo > partition_response.base_offset {
+                    transactions.record(transaction, partition_response.base_offset);
+                }
+            }
+        }
+        drop(transactions);
+        drop(producers);
 
         topic_result.partitions.push(partition_response);
     }
@@ -159,7 +290,7 @@ async fn handle_produce_topic(
 async fn handle_produce_partition(
     ctx: &DefaultSharedGlobalContext,
     replica_id: ReplicaKey,
-    leader_state: SharedFileLeaderState,
+    leader_state: &SharedFileLeaderState,
     partition_request: PartitionProduceData<RecordSet<RawRecords>>,
     is_connector: bool,
 ) -> PartitionWriteResult {
@@ -198,6 +329,10 @@ async fn handle_produce_partition(
                 error!(%replica_id, "Replica SmartEngine error: {:#?}", engine_err);
                 return PartitionWriteResult::error(replica_id, map_engine_error(engine_err));
             };
+            if let Some(error_code) = err.downcast_ref::<ErrorCode>() {
+                error!(%replica_id, %error_code, "Replica rejected records");
+                return PartitionWriteResult::error(replica_id, error_code.clone());
+            }
             match err.downcast_ref::<StorageError>() {
                 Some(StorageError::BatchTooBig(_)) => {
                     error!(%replica_id, "Batch is too big: {:#?}", err);
@@ -212,19 +347,29 @@ async fn handle_produce_partition(
     }
 }
 
+/// returns context of smartmodules if records are replaced by their output,
+/// key-value states changed by smartmodules are committed by caller once records are written
 async fn apply_smartmodules(
     partition_request: &mut PartitionProduceData<RecordSet<RawRecords>>,
-    smartmodules: &[SmartModuleInvocation],
+    smartmodules: Vec<SmartModuleInvocation>,
+    transaction: Option<ProducedTransaction>,
     api_version: i16,
     leader_state: &SharedFileLeaderState,
     ctx: &DefaultSharedGlobalContext,
-) -> Result<(), ErrorCode> {
-    let Some(mut sm_ctx) =
-        SmartModuleContext::try_from(smartmodules.to_vec(), api_version, ctx).await?
+    auth: Option<&SpuAuthContext>,
+) -> Result<Option<SmartModuleContext>, ErrorCode> {
+    let Some(mut sm_ctx) = SmartModuleContext::try_from(smartmodules, api_version, ctx).await?
     else {
-        return Ok(());
+        return Ok(None);
     };
 
+    sm_ctx
+        .init_join_tables(partition_request.partition_index, ctx, auth)
+        .await?;
+    sm_ctx
+        .init_dead_letter_topics(partition_request.partition_index, ctx, auth)
+        .await?;
+    sm_ctx.load_key_values(leader_state).await;
     sm_ctx.look_back(leader_state).await?;
 
     let records = &partition_request.records;
@@ -246,20 +391,28 @@ async fn apply_smartmodules(
             }
         }
         Err(general_error) => {
+            if let Some(engine_err) = general_error.downcast_ref::<EngineError>() {
+                return Err(map_engine_error(engine_err));
+            }
             return Err(ErrorCode::Other(format!(
                 "smartmodule chain failed: {general_error}"
             )));
         }
     };
 
-    let smartmoduled_records = Batch::<RawRecords>::try_from(sm_result)
+    sm_ctx.write_dead_letters().await?;
+
+    let mut smartmoduled_records = Batch::<RawRecords>::try_from(sm_result)
         .map_err(|e| ErrorCode::Other(format!("Compression Error: {:?}", e)))?;
+    if let Some(transaction) = transaction {
+        transaction.apply(&mut smartmoduled_records);
+    }
 
     partition_request.records = RecordSet {
         batches: vec![smartmoduled_records],
     };
 
-    Ok(())
+    Ok(Some(sm_ctx))
 }
 
 fn validate_records<R: BatchRecords>(
@@ -288,7 +441,9 @@ fn validate_records<R: BatchRecords>(
 }
 /// For isolation = ReadCommitted wait until the replica's `hw` includes written records offsets or
 /// until `timeout` passes. In case of timeout, the partition response returns `RequestTimedOut`
-/// error code. The timeout is not shared between partitions.
+/// error code. `NotEnoughInSyncReplicas` is returned whenever in-sync replicas are below minimum
+/// after the wait, so records are not acknowledged with fewer copies than required.
+/// The timeout is not shared between partitions.
 ///
 /// For isolation = ReadUncommitted - it's no op.
 async fn wait_for_acks(
@@ -313,34 +468,10 @@ async fn wait_for_acks(
                         continue;
                     }
                 };
-                let leo = partition.leo;
-                if leader_state.hw().ge(&leo) {
-                    trace!(?partition.replica_id, %leo, "batch already committed, skip waiting");
-                    continue;
-                }
-
-                let mut listener = leader_state.offset_listener(&isolation);
-                let wait_future = async {
-                    loop {
-                        let hw = listener.listen().await;
-                        if hw.ge(&leo) {
-                            break;
-                        }
-                    }
-                };
-                let timer = sleep(timeout);
-                select! {
-                    _ = wait_future => {
-                        trace!(?partition.replica_id, "waiting for acks completed");
-                    },
-                    _ = timer => {
-                        debug!(?partition.replica_id, "response timeout exceeded");
-                        partition.error_code = ErrorCode::RequestTimedOut {
-                            kind: RequestKind::Produce,
-                            timeout_ms: timeout.as_millis() as u64
-                        };
-                    },
-                }
+                partition.error_code = leader_state
+                    .wait_for_commit(partition.leo, timeout, ctx.follower_notifier())
+                    .await;
+                trace!(?partition.replica_id, %partition.error_code, "waiting for acks completed");
             }
         }
         Isolation::ReadUncommitted => {}
@@ -360,6 +491,26 @@ impl From<TopicWriteResult> for TopicProduceResponse {
     }
 }
 
+impl TopicWriteResult {
+    /// all partitions of topic rejected because client is not authorized
+    fn denied(topic_request: &DefaultTopicRequest) -> Self {
+        let partitions = topic_request
+            .partitions
+            .iter()
+            .map(|partition| {
+                PartitionWriteResult::error(
+                    ReplicaKey::new(topic_request.name.clone(), partition.partition_index),
+                    ErrorCode::PermissionDenied,
+                )
+            })
+            .collect();
+        Self {
+            topic: topic_request.name.clone(),
+            partitions,
+        }
+    }
+}
+
 impl PartitionWriteResult {
     fn error(replica_id: ReplicaKey, error_code: ErrorCode) -> Self {
         Self {
@@ -378,10 +529,20 @@ impl PartitionWriteResult {
         }
     }
 
-    fn filtered(replica_id: ReplicaKey) -> Self {
-        Self {
-            replica_id,
-            ..Default::default()
+    /// all records are filtered out by smartmodules, nothing is written at `leo`
+    fn filtered(replica_id: ReplicaKey, leo: Offset) -> Self {
+        Self::ok(replica_id, leo, leo)
+    }
+
+    /// batches are already written at offset, or too old to know it
+    fn duplicate(
+        replica_id: ReplicaKey,
+        duplicate: Result<Offset, ErrorCode>,
+        leo: Offset,
+    ) -> Self {
+        match duplicate {
+            Ok(base_offset) => Self::ok(replica_id, base_offset, leo),
+            Err(error_code) => Self::error(replica_id, error_code),
         }
     }
 }
@@ -397,6 +558,25 @@ impl From<PartitionWriteResult> for PartitionProduceResponse {
     }
 }
 
+/// record request and bytes produced to topic in quotas of client
+fn record_produce_usage(
+    ctx: &DefaultSharedGlobalContext,
+    client: &QuotaClient,
+    topic_request: &DefaultTopicRequest,
+) -> Duration {
+    let bytes: u64 = topic_request
+        .partitions
+        .iter()
+        .flat_map(|partition| partition.records.batches.iter())
+        .map(|batch| batch.batch_len() as u64)
+        .sum();
+    let requests_throttle =
+        ctx.record_quota_usage(client, &topic_request.name, QuotaRate::Requests, 1);
+    let bytes_throttle =
+        ctx.record_quota_usage(client, &topic_request.name, QuotaRate::ProducerBytes, bytes);
+    requests_throttle.max(bytes_throttle)
+}
+
 fn into_response(topic_results: Vec<TopicWriteResult>) -> ProduceResponse {
     let responses = topic_results
         .into_iter()
@@ -0,0 +1,115 @@
+//!
+//! # Records for clients without header support
+//!
+//! Clients older than [`RECORD_HEADERS_API_VERSION`] can't decode records with headers,
+//! so records are read from the log and sent back without headers instead of zero copy.
+//! Slices whose batches are not flagged with record headers, including control batches,
+//! are still sent as zero copy.
+//!
+
+use std::io::Cursor;
+
+use tracing::error;
+
+use fluvio_protocol::{Decoder, Version};
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::{Batch, RawRecords, Record, RecordSet};
+use fluvio_spu_schema::RECORD_HEADERS_API_VERSION;
+use fluvio_spu_schema::fetch::{
+    FetchResponse, FetchableTopicResponse, FetchablePartitionResponse, FileFetchResponse,
+    FilePartitionResponse,
+};
+use fluvio_spu_schema::file::FileRecordSet;
+use fluvio_storage::iterators::{FileBatchHeaderIterator, FileBatchIterator};
+
+pub(crate) type MemoryFetchResponse = FetchResponse<RecordSet<RawRecords>>;
+type MemoryPartitionResponse = FetchablePartitionResponse<RecordSet<RawRecords>>;
+
+pub(crate) fn supports_record_headers(version: Version) -> bool {
+    version >= RECORD_HEADERS_API_VERSION
+}
+
+/// true if any batch of file slice has records with headers,
+/// slice which can't be read is handled as having headers so it is not sent as zero copy
+pub(crate) fn has_record_headers(records: &FileRecordSet) -> bool {
+    FileBatchHeaderIterator::from_raw_slice(records.raw_slice()).any(|batch| match batch {
+        Ok(batch) => batch.header.has_record_headers(),
+        Err(err) => {
+            error!(%err, "error reading batch header");
+            true
+        }
+    })
+}
+
+/// true if any partition of response has records with headers
+pub(crate) fn fetch_response_has_record_headers(response: &FileFetchResponse) -> bool {
+    response
+        .topics
+        .iter()
+        .flat_map(|topic| topic.partitions.iter())
+        .any(|partition| has_record_headers(&partition.records))
+}
+
+/// read batches of file slice into memory, removing headers of records
+pub(crate) fn records_without_headers(
+    records: &FileRecordSet,
+) -> Result<RecordSet<RawRecords>, ErrorCode> {
+    let mut record_set = RecordSet::default();
+    for file_batch in FileBatchIterator::from_raw_slice(records.raw_slice()) {
+        let file_batch = file_batch.map_err(|err| {
+            error!(%err, "error reading batch");
+            ErrorCode::StorageError
+        })?;
+        let mut batch = file_batch.batch;
+        let records: Vec<Record> = Decoder::decode_from(&mut Cursor::new(file_batch.records), 0)
+            .map_err(|err| {
+                error!(%err, "error decoding records");
+                ErrorCode::StorageError
+            })?;
+        *batch.mut_records() = records;
+        batch.clear_record_headers();
+        let batch = Batch::<RawRecords>::try_from(batch).map_err(|err| {
+            error!(%err, "error compressing records");
+            ErrorCode::CompressionError
+        })?;
+        record_set.batches.push(batch);
+    }
+    Ok(record_set)
+}
+
+fn partition_without_headers(partition: FilePartitionResponse) -> MemoryPartitionResponse {
+    let (error_code, records) = match records_without_headers(&partition.records) {
+        Ok(records) => (partition.error_code, records),
+        Err(error_code) => (error_code, RecordSet::default()),
+    };
+    MemoryPartitionResponse {
+        partition_index: partition.partition_index,
+        error_code,
+        high_watermark: partition.high_watermark,
+        next_filter_offset: partition.next_filter_offset,
+        log_start_offset: partition.log_start_offset,
+        aborted: partition.aborted,
+        records,
+    }
+}
+
+pub(crate) fn fetch_response_without_headers(response: FileFetchResponse) -> MemoryFetchResponse {
+    MemoryFetchResponse {
+        throttle_time_ms: response.throttle_time_ms,
+        error_code: response.error_code,
+        session_id: response.session_id,
+        topics: response
+            .topics
+            .into_iter()
+            .map(|topic| FetchableTopicResponse {
+                name: topic.name,
+                partitions: topic
+                    .partitions
+                    .into_iter()
+                    .map(partition_without_headers)
+                    .collect(),
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
@@ -1,40 +1,53 @@
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use tracing::{debug, error, instrument, trace, warn};
 use tokio::select;
 
+use fluvio_auth::InstanceAction;
 use fluvio_compression::CompressionError;
 use fluvio_controlplane_metadata::partition::ReplicaKey;
 use fluvio_types::event::{StickyEvent, offsets::OffsetPublisher};
 use fluvio_future::task::spawn;
+use fluvio_future::timer::sleep;
 use fluvio_protocol::{
     api::{RequestMessage, RequestHeader},
     record::{RecordSet, Offset, RawRecords},
 };
 use fluvio_protocol::link::{ErrorCode, smartmodule::SmartModuleTransformRuntimeError};
 use fluvio_protocol::record::Batch;
+use fluvio_smartmodule::dataplane::smartmodule::SmartModuleChainTrace;
 use fluvio_socket::{ExclusiveFlvSink, SocketError};
 use fluvio_storage::iterators::FileBatchIterator;
+use fluvio_storage::{ReplicaStorage, SharedSmartModuleStateStore, SmartModuleState};
 use fluvio_spu_schema::{
     server::stream_fetch::{
         DefaultStreamFetchRequest, FileStreamFetchRequest, StreamFetchRequest, StreamFetchResponse,
     },
-    fetch::{FilePartitionResponse, FetchablePartitionResponse},
+    fetch::{AbortedBatchFilter, FilePartitionResponse, FetchablePartitionResponse},
     Isolation,
     file::FileRecordSet,
 };
 use fluvio_types::event::offsets::OffsetChangeListener;
 
 use crate::core::{DefaultSharedGlobalContext, metrics::IncreaseValue};
+use crate::core::quota::{QuotaRate, QuotaClient};
+use crate::core::auth::SpuAuthContext;
 use crate::replication::leader::SharedFileLeaderState;
 use crate::services::public::conn_context::ConnectionContext;
+use crate::services::public::record_headers::{
+    has_record_headers, records_without_headers, supports_record_headers,
+};
 use crate::services::public::stream_fetch::publishers::INIT_OFFSET;
-use crate::smartengine::context::SmartModuleContext;
+use crate::smartengine::context::{SmartModuleContext, restore_accumulators};
 use crate::smartengine::batch::process_batch;
+use crate::smartengine::{EngineError, map_engine_error};
 use crate::core::metrics::SpuMetrics;
 use crate::traffic::TrafficType;
 
+/// minimum time between checkpoints of SmartModule state of a stream
+const SMARTMODULE_STATE_CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Fetch records as stream
 pub struct StreamFetchHandler {
     replica: ReplicaKey,
@@ -48,6 +61,19 @@ pub struct StreamFetchHandler {
     leader_state: SharedFileLeaderState,
     stream_id: u32,
     metrics: Arc<SpuMetrics>,
+    smartmodule_state: Option<SmartModuleStateCheckpoint>,
+    ctx: DefaultSharedGlobalContext,
+    quota_client: QuotaClient,
+}
+
+/// Checkpoints of SmartModule state of a stream, persisted at most once per interval
+struct SmartModuleStateCheckpoint {
+    state_id: String,
+    store: SharedSmartModuleStateStore,
+    /// latest state not yet persisted
+    pending: Option<SmartModuleState>,
+    last_offset: Offset,
+    last_time: Instant,
 }
 
 impl StreamFetchHandler {
@@ -62,16 +88,29 @@ impl StreamFetchHandler {
         let (header, msg) = request.get_header_request();
         let replica = ReplicaKey::new(msg.topic.clone(), msg.partition);
 
+        if !conn_ctx
+            .allow_topic_action(InstanceAction::Read, &replica.topic)
+            .await
+        {
+            debug!(topic = %replica.topic, "client is not allowed to consume from topic");
+            send_back_error(&sink, &replica, &header, 0, ErrorCode::PermissionDenied).await?;
+            return Ok(());
+        }
+
         if let Some(leader_state) = ctx.leaders_state().get(&replica).await {
             let (stream_id, offset_publisher) = conn_ctx
                 .stream_publishers_mut()
                 .create_new_publisher()
                 .await;
             let consumer_offset_listener = offset_publisher.change_listener();
+            let quota_client = conn_ctx.quota_client().clone();
+            let auth = conn_ctx.auth().cloned();
 
             spawn(async move {
                 if let Err(err) = StreamFetchHandler::fetch(
                     ctx,
+                    quota_client,
+                    auth,
                     sink,
                     end_event.clone(),
                     leader_state,
@@ -97,6 +136,8 @@ impl StreamFetchHandler {
                     error_code: ErrorCode::NotLeaderForPartition,
                     ..Default::default()
                 },
+                smartmodule_trace: None,
+                throttle_time_ms: 0,
             };
 
             let response_msg =
@@ -115,7 +156,7 @@ impl StreamFetchHandler {
 
     #[allow(clippy::too_many_arguments)]
     #[instrument(
-        skip(ctx,replica,end_event,leader_state,header,msg,consumer_offset_listener),
+        skip(ctx,quota_client,auth,replica,end_event,leader_state,header,msg,consumer_offset_listener),
         fields(
             replica = %replica,
             sink = sink.id()
@@ -123,6 +164,8 @@ impl StreamFetchHandler {
     ]
     pub async fn fetch(
         ctx: DefaultSharedGlobalContext,
+        quota_client: QuotaClient,
+        auth: Option<SpuAuthContext>,
         sink: ExclusiveFlvSink,
         end_event: Arc<StickyEvent>,
         leader_state: SharedFileLeaderState,
@@ -135,14 +178,65 @@ impl StreamFetchHandler {
         debug!("request: {:#?}", msg);
         let version = header.api_version();
 
-        let sm_ctx = match SmartModuleContext::try_from(msg.smartmodules, version, &ctx).await {
-            Ok(Some(mut ctx)) => {
-                if let Err(error_code) = ctx.look_back(&leader_state).await {
+        let mut smartmodules = msg.smartmodules;
+        let mut starting_offset = msg.fetch_offset;
+        let mut smartmodule_state = None;
+        if let Some(state_id) = msg.smartmodule_state_id {
+            let store = leader_state.read().await.smartmodule_states();
+            let state = store.lock().await.get(&state_id).cloned();
+            let mut last_offset = INIT_OFFSET;
+            if let Some(state) = state {
+                let (start_offset, hw) = leader_state.start_offset_info().await;
+                starting_offset = state.offset.clamp(start_offset, hw);
+                debug!(
+                    %state_id,
+                    starting_offset, "resuming from checkpointed smartmodule state"
+                );
+                restore_accumulators(&mut smartmodules, state.accumulators);
+                last_offset = starting_offset;
+            }
+            smartmodule_state = Some(SmartModuleStateCheckpoint {
+                state_id,
+                store,
+                pending: None,
+                last_offset,
+                last_time: Instant::now(),
+            });
+        }
+
+        let sm_ctx = match SmartModuleContext::try_from(smartmodules, version, &ctx).await {
+            Ok(Some(mut sm_ctx)) => {
+                if let Err(error_code) = sm_ctx
+                    .init_join_tables(replica.partition, &ctx, auth.as_ref())
+                    .await
+                {
+                    warn!("smartmodule join tables init failed: {:?}", error_code);
+                    send_back_error(&sink, &replica, &header, stream_id, error_code).await?;
+                    return Ok(());
+                }
+                if let Err(error_code) = sm_ctx
+                    .init_dead_letter_topics(replica.partition, &ctx, auth.as_ref())
+                    .await
+                {
+                    warn!(
+                        "smartmodule dead-letter topics init failed: {:?}",
+                        error_code
+                    );
+                    send_back_error(&sink, &replica, &header, stream_id, error_code).await?;
+                    return Ok(());
+                }
+                sm_ctx.load_key_values(&leader_state).await;
+                if let Err(error_code) = sm_ctx.look_back(&leader_state).await {
                     warn!("smartmodule look_back failed: {:?}", error_code);
                     send_back_error(&sink, &replica, &header, stream_id, error_code).await?;
                     return Ok(());
                 }
-                Some(ctx)
+                sm_ctx.chain_mut().set_debug(msg.smartmodule_debug);
+                // trace is sent together with records, it may take at most half of the response
+                sm_ctx
+                    .chain_mut()
+                    .set_trace_max_bytes(usize::try_from(msg.max_bytes).unwrap_or_default() / 2);
+                Some(sm_ctx)
             }
             Ok(None) => None,
             Err(error_code) => {
@@ -160,7 +254,6 @@ impl StreamFetchHandler {
             max_bytes
         };
 
-        let starting_offset = msg.fetch_offset;
         let isolation = msg.isolation;
 
         debug!(
@@ -184,6 +277,9 @@ impl StreamFetchHandler {
             leader_state,
             max_fetch_bytes,
             metrics: ctx.metrics(),
+            smartmodule_state,
+            ctx,
+            quota_client,
         };
 
         if let Err(err) = handler.process(starting_offset, sm_ctx).await {
@@ -327,7 +423,7 @@ impl StreamFetchHandler {
 
         debug!("done with stream fetch loop exiting");
 
-        Ok(())
+        self.flush_smartmodule_state().await
     }
 
     /// send back records back to consumer
@@ -352,11 +448,23 @@ impl StreamFetchHandler {
         // Read records from the leader starting from `offset`
         // Returns with the HW/LEO of the latest records available in the leader
         // This describes the range of records that can be read in this request
-        let read_end_offset = match self
-            .leader_state
-            .read_records(starting_offset, self.max_fetch_bytes, self.isolation)
-            .await
-        {
+        let read_result = match self.isolation {
+            // records of open transactions are not read, aborted transactions are sent to consumer
+            Isolation::ReadCommitted => self
+                .leader_state
+                .read_stable_records(starting_offset, self.max_fetch_bytes)
+                .await
+                .map(|(slice, aborted)| {
+                    file_partition_response.aborted = Some(aborted);
+                    slice
+                }),
+            Isolation::ReadUncommitted => {
+                self.leader_state
+                    .read_records(starting_offset, self.max_fetch_bytes, self.isolation)
+                    .await
+            }
+        };
+        let read_end_offset = match read_result {
             Ok(slice) => {
                 file_partition_response.high_watermark = slice.end.hw;
                 file_partition_response.log_start_offset = slice.start;
@@ -390,14 +498,30 @@ impl StreamFetchHandler {
             return Ok((starting_offset, false));
         }
 
+        // quotas are charged for bytes read from log, even if SmartModules drop records
+        let throttle = self.record_quota_usage(file_partition_response.records.len() as u64);
+
         let (offset, wait, metrics_update) = match sm_ctx {
             Some(sm_ctx) => {
                 // If a SmartModule is provided, we need to read records from file to memory
                 // In-memory records are then processed by SmartModule and returned to consumer
 
                 let records = &file_partition_response.records;
-                let mut file_batch_iterator =
-                    FileBatchIterator::from_raw_slice(records.raw_slice());
+                // control batches and aborted transactions are not passed to SmartModule
+                let mut aborted_filter = AbortedBatchFilter::new(
+                    file_partition_response.aborted.clone().unwrap_or_default(),
+                );
+                let mut file_batch_iterator = FileBatchIterator::from_raw_slice(
+                    records.raw_slice(),
+                )
+                .filter(|file_batch| {
+                    !matches!(file_batch, Ok(file_batch) if aborted_filter.skip(&file_batch.batch))
+                });
+
+                sm_ctx
+                    .update_join_tables()
+                    .await
+                    .map_err(StreamFetchError::Fetch)?;
 
                 let (batch, smartmodule_error) = process_batch(
                     sm_ctx.chain_mut(),
@@ -406,9 +530,24 @@ impl StreamFetchHandler {
                     self.metrics.chain_metrics(),
                 )
                 .map_err(|err| {
-                    StreamFetchError::Fetch(ErrorCode::Other(format!("SmartModule err {err}")))
+                    let error_code = match err.downcast_ref::<EngineError>() {
+                        Some(engine_err) => map_engine_error(engine_err),
+                        None => ErrorCode::Other(format!("SmartModule err {err}")),
+                    };
+                    StreamFetchError::Fetch(error_code)
                 })?;
+                let state_id = self
+                    .smartmodule_state
+                    .as_ref()
+                    .map(|checkpoint| checkpoint.state_id.as_str());
+                sm_ctx
+                    .write_consumed_dead_letters(state_id, &self.leader_state)
+                    .await
+                    .map_err(StreamFetchError::Fetch)?;
+                let smartmodule_trace =
+                    Some(sm_ctx.chain_mut().take_trace()).filter(|trace| !trace.is_empty());
                 let metrics_update = IncreaseValue::from(&batch);
+                let completed = smartmodule_error.is_none();
 
                 let (offset, wait) = self
                     .send_processed_response(
@@ -416,10 +555,68 @@ impl StreamFetchHandler {
                         next_offset,
                         batch,
                         smartmodule_error,
+                        smartmodule_trace,
+                        throttle,
                     )
                     .await?;
+                if completed {
+                    self.checkpoint_smartmodule_state(sm_ctx, offset).await?;
+                    sm_ctx
+                        .commit_key_values(&self.leader_state)
+                        .await
+                        .map_err(StreamFetchError::Fetch)?;
+                }
                 (offset, wait, metrics_update)
             }
+            None if !supports_record_headers(self.header.api_version())
+                && has_record_headers(&file_partition_response.records) =>
+            {
+                // Client can't decode record headers, records are read from file to memory
+                // and sent back without headers
+                debug!("No SmartModule, sending back log without record headers");
+                let metrics_update = IncreaseValue::from(&file_partition_response);
+
+                let partition_response = FetchablePartitionResponse {
+                    partition_index: file_partition_response.partition_index,
+                    error_code: file_partition_response.error_code,
+                    high_watermark: file_partition_response.high_watermark,
+                    next_filter_offset: file_partition_response.next_filter_offset,
+                    log_start_offset: file_partition_response.log_start_offset,
+                    records: records_without_headers(&file_partition_response.records)?,
+                    aborted: file_partition_response.aborted,
+                };
+
+                let response = StreamFetchResponse {
+                    topic: self.replica.topic.clone(),
+                    stream_id: self.stream_id,
+                    partition: partition_response,
+                    smartmodule_trace: None,
+                    throttle_time_ms: throttle.as_millis() as i32,
+                };
+
+                let response_msg =
+                    RequestMessage::<DefaultStreamFetchRequest>::response_with_header(
+                        &self.header,
+                        response,
+                    );
+
+                trace!("sending back fetch response msg: {:#?}", response_msg);
+
+                let mut inner_sink = self.sink.lock().await;
+                inner_sink
+                    .send_response(&response_msg, self.header.api_version())
+                    .await?;
+
+                drop(inner_sink);
+
+                debug!(read_time_ms = %now.elapsed().as_millis(),"finish sending back records");
+
+                (
+                    read_end_offset.isolation(&self.isolation),
+                    true,
+                    metrics_update,
+                )
+            }
             None => {
                 // If no SmartModule is provided, respond using raw file records
                 debug!("No SmartModule, sending back entire log");
@@ -429,6 +626,8 @@ impl StreamFetchHandler {
                     topic: self.replica.topic.clone(),
                     stream_id: self.stream_id,
                     partition: file_partition_response,
+                    smartmodule_trace: None,
+                    throttle_time_ms: throttle.as_millis() as i32,
                 };
 
                 let response_msg = RequestMessage::<FileStreamFetchRequest>::response_with_header(
@@ -457,16 +656,91 @@ impl StreamFetchHandler {
         self.metrics
             .outbound()
             .increase_by_value(self.header.is_connector(), metrics_update);
+
+        if !throttle.is_zero() {
+            debug!(?throttle, "consumer exceeded quota, pausing stream");
+            select! {
+                _ = sleep(throttle) => {},
+                _ = self.end_event.listen() => {},
+            }
+        }
         Ok((offset, wait))
     }
 
-    #[instrument(skip(self, file_partition_response, batch, smartmodule_error))]
+    /// record bytes read for consumer in quotas of client,
+    /// returns how long stream must be paused
+    fn record_quota_usage(&self, bytes: u64) -> Duration {
+        self.ctx.record_quota_usage(
+            &self.quota_client,
+            &self.replica.topic,
+            QuotaRate::ConsumerBytes,
+            bytes,
+        )
+    }
+
+    /// checkpoint accumulators of aggregate SmartModules which processed records up to `offset`,
+    /// state is persisted only if offset advanced and checkpoint interval elapsed
+    async fn checkpoint_smartmodule_state(
+        &mut self,
+        sm_ctx: &SmartModuleContext,
+        offset: Offset,
+    ) -> Result<(), StreamFetchError> {
+        let Some(checkpoint) = &mut self.smartmodule_state else {
+            return Ok(());
+        };
+        if offset <= checkpoint.last_offset {
+            return Ok(());
+        }
+        checkpoint.pending = Some(SmartModuleState {
+            offset,
+            accumulators: sm_ctx.accumulators(),
+        });
+        if checkpoint.last_time.elapsed() < SMARTMODULE_STATE_CHECKPOINT_INTERVAL {
+            return Ok(());
+        }
+        self.flush_smartmodule_state().await
+    }
+
+    /// persist latest checkpoint of SmartModule state, if any
+    async fn flush_smartmodule_state(&mut self) -> Result<(), StreamFetchError> {
+        let Some(checkpoint) = &mut self.smartmodule_state else {
+            return Ok(());
+        };
+        let Some(state) = checkpoint.pending.take() else {
+            return Ok(());
+        };
+        let offset = state.offset;
+        checkpoint
+            .store
+            .lock()
+            .await
+            .commit(&checkpoint.state_id, state)
+            .await
+            .map_err(|err| {
+                error!(%err, "error committing smartmodule state");
+                StreamFetchError::Fetch(ErrorCode::StorageError)
+            })?;
+        checkpoint.last_offset = offset;
+        checkpoint.last_time = Instant::now();
+        Ok(())
+    }
+
+    #[instrument(skip(
+        self,
+        file_partition_response,
+        batch,
+        smartmodule_error,
+        smartmodule_trace,
+        throttle
+    ))]
     async fn send_processed_response(
         &self,
         file_partition_response: FilePartitionResponse,
         next_offset: Offset,
-        batch: Batch,
+        mut batch: Batch,
         smartmodule_error: Option<SmartModuleTransformRuntimeError>,
+        smartmodule_trace: Option<SmartModuleChainTrace>,
+        throttle: Duration,
     ) -> Result<(Offset, bool), StreamFetchError> {
         type DefaultPartitionResponse = FetchablePartitionResponse<RecordSet<RawRecords>>;
 
@@ -479,7 +753,8 @@ impl StreamFetchHandler {
         let has_error = !matches!(error_code, ErrorCode::None);
         let has_records = !batch.records().is_empty();
 
-        if !has_records && !has_error {
+        // traces are sent even if SmartModules dropped all records
+        if !has_records && !has_error && smartmodule_trace.is_none() {
             debug!(next_offset, "No records to send back, skipping");
             return Ok((next_offset, false));
         }
@@ -499,6 +774,10 @@ impl StreamFetchHandler {
 
         //trace!("batch: {:#?}",batch);
 
+        if !supports_record_headers(self.header.api_version()) {
+            batch.clear_record_headers();
+        }
+
         let records = RecordSet::default().add(batch);
         let partition_response = DefaultPartitionResponse {
             partition_index: self.replica.partition,
@@ -515,6 +794,8 @@ impl StreamFetchHandler {
             topic: self.replica.topic.clone(),
             stream_id: self.stream_id,
             partition: partition_response,
+            smartmodule_trace,
+            throttle_time_ms: throttle.as_millis() as i32,
         };
 
         let response_msg = RequestMessage::<DefaultStreamFetchRequest>::response_with_header(
@@ -551,6 +832,8 @@ async fn send_back_error(
         topic: replica.topic.clone(),
         stream_id,
         partition: partition_response,
+        smartmodule_trace: None,
+        throttle_time_ms: 0,
     };
 
     let response_msg =
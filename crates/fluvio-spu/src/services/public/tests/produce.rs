@@ -532,7 +532,7 @@ async fn test_produce_basic_with_smartmodule_with_lookback() {
     let mut smartmodule = SmartModuleInvocation {
         wasm: SmartModuleInvocationWasm::Predefined(FLUVIO_WASM_FILTER_WITH_LOOKBACK.to_owned()),
         kind: SmartModuleKind::Filter,
-        params: Default::default(),
+        ..Default::default()
     };
     smartmodule.params.set_lookback(Some(Lookback::last(1)));
     let mut smartmodules = vec![smartmodule];
@@ -1024,6 +1024,121 @@ async fn test_produce_with_deduplication() {
     debug!("terminated controller");
 }
 
+const FLUVIO_WASM_FILTER_ODD: &str = "fluvio_smartmodule_filter_odd";
+const FLUVIO_WASM_MAP_DOUBLE: &str = "fluvio_smartmodule_map_double";
+
+#[fluvio_future::test(ignore)]
+async fn test_produce_with_topic_transforms() {
+    let test_path = temp_dir().join("test_produce_with_topic_transforms");
+    ensure_clean_dir(&test_path);
+    let port = portpicker::pick_unused_port().expect("No free ports left");
+
+    let addr = format!("127.0.0.1:{port}");
+    let mut spu_config = SpuConfig::default();
+    spu_config.log.base_dir = test_path;
+    let ctx = GlobalContext::new_shared_context(spu_config);
+    load_wasm_module(&ctx, FLUVIO_WASM_FILTER_ODD);
+    load_wasm_module(&ctx, FLUVIO_WASM_MAP_DOUBLE);
+
+    let server_end_event = create_public_server(addr.to_owned(), ctx.clone()).run();
+
+    // wait for stream controller async to start
+    sleep(Duration::from_millis(100)).await;
+
+    let client_socket =
+        MultiplexerSocket::new(FluvioSocket::connect(&addr).await.expect("connect"));
+
+    let topic = "test_produce_with_topic_transforms";
+    let mut test = Replica::new((topic, 0), 5001, vec![5001]);
+    test.transforms = vec![Transform {
+        uses: FLUVIO_WASM_FILTER_ODD.to_owned(),
+        with: Default::default(),
+    }];
+    let test_id = test.id.clone();
+    ctx.replica_localstore().sync_all(vec![test.clone()]);
+
+    let replica = LeaderReplicaState::create(test, ctx.config(), ctx.status_update_owned())
+        .await
+        .expect("replica")
+        .init(&ctx)
+        .await
+        .expect("init succeeded");
+
+    ctx.leaders_state().insert(test_id, replica.clone()).await;
+
+    {
+        // topic transforms are applied without smartmodules in request
+        let records = vec_to_raw_batch(&["1", "2", "3", "4"]);
+
+        let mut produce_request: DefaultProduceRequest = Default::default();
+
+        let partition_produce = DefaultPartitionRequest {
+            partition_index: 0,
+            records,
+        };
+        let topic_produce_request = TopicProduceData {
+            name: topic.to_owned(),
+            partitions: vec![partition_produce],
+            ..Default::default()
+        };
+
+        produce_request.topics.push(topic_produce_request);
+
+        let produce_response = client_socket
+            .send_and_receive(RequestMessage::new_request(produce_request))
+            .await
+            .expect("send offset");
+
+        assert_eq!(produce_response.responses.len(), 1);
+        assert_eq!(produce_response.responses[0].partitions.len(), 1);
+        assert_eq!(
+            produce_response.responses[0].partitions[0].error_code,
+            ErrorCode::None
+        );
+        assert_eq!(read_records(&replica).await, vec!["2", "4"]);
+    }
+
+    {
+        // topic transforms are applied to output of smartmodules in request
+        let records = vec_to_raw_batch(&["1", "3"]);
+
+        let mut produce_request: DefaultProduceRequest = Default::default();
+        produce_request.smartmodules = vec![SmartModuleInvocation {
+            wasm: SmartModuleInvocationWasm::Predefined(FLUVIO_WASM_MAP_DOUBLE.to_owned()),
+            kind: SmartModuleKind::Map,
+            ..Default::default()
+        }];
+
+        let partition_produce = DefaultPartitionRequest {
+            partition_index: 0,
+            records,
+        };
+        let topic_produce_request = TopicProduceData {
+            name: topic.to_owned(),
+            partitions: vec![partition_produce],
+            ..Default::default()
+        };
+
+        produce_request.topics.push(topic_produce_request);
+
+        let produce_response = client_socket
+            .send_and_receive(RequestMessage::new_request(produce_request))
+            .await
+            .expect("send offset");
+
+        assert_eq!(produce_response.responses.len(), 1);
+        assert_eq!(produce_response.responses[0].partitions.len(), 1);
+        assert_eq!(
+            produce_response.responses[0].partitions[0].error_code,
+            ErrorCode::None
+        );
+        assert_eq!(read_records(&replica).await, vec!["2", "4", "2", "6"]);
+    }
+
+    server_end_event.notify();
+    debug!("terminated controller");
+}
+
 #[fluvio_future::test(ignore)]
 async fn test_produce_smart_engine_memory_overfow() {
     let test_path = temp_dir().join("test_produce_smart_engine_memory_overfow");
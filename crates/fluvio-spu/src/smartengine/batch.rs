@@ -99,12 +99,14 @@ pub(crate) fn process_batch<R: SmartModuleInputBatch>(
             }
 
             let record_bytes = records.write_size(0);
+            // trace of debug mode is sent in the same response
+            let trace_bytes = sm_chain_instance.trace_size();
 
             // if smartmodule bytes exceed max bytes then we skip this batch
-            if total_bytes + record_bytes > max_bytes {
+            if total_bytes + record_bytes + trace_bytes > max_bytes {
                 debug!(
                     total_bytes = total_bytes + record_bytes,
-                    max_bytes, "Total SmartModuleInstance bytes reached"
+                    trace_bytes, max_bytes, "Total SmartModuleInstance bytes reached"
                 );
                 return Ok((smartmodule_batch, maybe_error));
             }
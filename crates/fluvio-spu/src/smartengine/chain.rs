@@ -1,37 +1,56 @@
+#[cfg(feature = "smartengine")]
+use std::time::Duration;
+
 #[cfg(feature = "smartengine")]
 use tracing::{debug, error};
 use fluvio_protocol::link::ErrorCode;
 use fluvio_spu_schema::server::smartmodule::SmartModuleInvocation;
 
 #[cfg(feature = "smartengine")]
-use fluvio_smartengine::{EngineError, SmartModuleConfig, SmartModuleInitialData};
+use fluvio_smartengine::{
+    EngineError, SmartModuleConfig, SmartModuleInitialData, SmartModuleJoinTable,
+    SmartModuleWindowConfig,
+};
 
 #[cfg(feature = "smartengine")]
 use fluvio_spu_schema::server::smartmodule::{SmartModuleContextData, SmartModuleKind};
 
+#[cfg(feature = "smartengine")]
+use crate::smartengine::map_engine_error;
 use crate::smartengine::SmartModuleChainBuilder;
 use crate::smartengine::SmartEngine;
 use crate::smartengine::SmartModuleChainInstance;
+use crate::smartengine::SmartModuleKeyValueState;
+use crate::smartengine::join::JoinTableState;
 
 #[cfg(not(feature = "smartengine"))]
 pub(crate) fn build_chain(
     mut _chain_builder: SmartModuleChainBuilder,
     _invocations: Vec<SmartModuleInvocation>,
+    _key_value_states: Vec<SmartModuleKeyValueState>,
+    _module_names: Vec<Option<String>>,
     _version: i16,
     _engine: SmartEngine,
-) -> Result<SmartModuleChainInstance, ErrorCode> {
+) -> Result<(SmartModuleChainInstance, Vec<JoinTableState>), ErrorCode> {
     let smci = SmartModuleChainInstance {};
-    Ok(smci)
+    Ok((smci, vec![]))
 }
 
 #[cfg(feature = "smartengine")]
 pub(crate) fn build_chain(
     mut chain_builder: SmartModuleChainBuilder,
     invocations: Vec<SmartModuleInvocation>,
+    key_value_states: Vec<SmartModuleKeyValueState>,
+    module_names: Vec<Option<String>>,
     version: i16,
     engine: SmartEngine,
-) -> Result<SmartModuleChainInstance, ErrorCode> {
-    for invocation in invocations {
+) -> Result<(SmartModuleChainInstance, Vec<JoinTableState>), ErrorCode> {
+    let mut join_tables = vec![];
+    for ((invocation, key_value_state), module_name) in invocations
+        .into_iter()
+        .zip(key_value_states)
+        .zip(module_names)
+    {
         let raw = invocation
             .wasm
             .into_raw()
@@ -49,18 +68,47 @@ pub(crate) fn build_chain(
             SmartModuleKind::Generic(SmartModuleContextData::Aggregate { ref accumulator }) => {
                 SmartModuleInitialData::with_aggregate(accumulator.clone())
             }
+            SmartModuleKind::Join(ref topic)
+            | SmartModuleKind::Generic(SmartModuleContextData::Join(ref topic)) => {
+                let table = SmartModuleJoinTable::default();
+                join_tables.push(JoinTableState::new(topic.clone(), table.clone()));
+                SmartModuleInitialData::with_join(table)
+            }
+            SmartModuleKind::Generic(SmartModuleContextData::Window {
+                size_ms,
+                slide_ms,
+                allowed_lateness_ms,
+            }) => SmartModuleInitialData::with_window(
+                SmartModuleWindowConfig::hopping(
+                    Duration::from_millis(size_ms),
+                    Duration::from_millis(slide_ms),
+                )
+                .with_allowed_lateness(Duration::from_millis(allowed_lateness_ms)),
+            ),
             _ => SmartModuleInitialData::default(),
         };
 
         let lookback = invocation.params.lookback().map(Into::into);
 
         debug!("param: {:#?}", invocation.params);
+        let mut config_builder = SmartModuleConfig::builder();
+        if let Some(module_name) = module_name {
+            config_builder.name(module_name);
+        }
+        if let Some(fuel_limit) = invocation.budget.fuel_limit {
+            config_builder.fuel_limit(fuel_limit);
+        }
+        if let Some(call_timeout) = invocation.budget.call_timeout {
+            config_builder.call_timeout(call_timeout);
+        }
         chain_builder.add_smart_module(
-            SmartModuleConfig::builder()
+            config_builder
                 .params(invocation.params)
                 .version(version)
                 .lookback(lookback)
                 .initial_data(initial_data)
+                .key_value_state(key_value_state)
+                .error_policy(invocation.error_policy)
                 .build()
                 .map_err(|err| ErrorCode::SmartModuleInvalid {
                     error: err.to_string(),
@@ -73,16 +121,13 @@ pub(crate) fn build_chain(
     let chain = chain_builder.initialize(&engine).map_err(|err| {
         error!("Error Initializing SmartModule chain: {err:#?}");
         match err.downcast_ref() {
-            Some(EngineError::StoreMemoryExceeded {
-                current: _,
-                requested,
-                max,
-            }) => ErrorCode::SmartModuleMemoryLimitExceeded {
-                requested: *requested as u64,
-                max: *max as u64,
-            },
+            Some(
+                engine_err @ (EngineError::StoreMemoryExceeded { .. }
+                | EngineError::FuelExhausted { .. }
+                | EngineError::CallTimeout { .. }),
+            ) => map_engine_error(engine_err),
             _ => ErrorCode::SmartModuleChainInitError(err.to_string()),
         }
     })?;
-    Ok(chain)
+    Ok((chain, join_tables))
 }
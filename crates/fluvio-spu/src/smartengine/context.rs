@@ -4,21 +4,34 @@ use std::time::Duration;
 use async_rwlock::RwLock;
 use chrono::Utc;
 use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::link::smartmodule::SmartModuleTransformRuntimeError;
 use fluvio_smartmodule::Record;
-use fluvio_spu_schema::server::smartmodule::{SmartModuleInvocation, SmartModuleInvocationWasm};
-use fluvio_storage::ReplicaStorage;
+use fluvio_smartmodule::dataplane::smartmodule::SmartModuleErrorPolicy;
+use fluvio_spu_schema::server::smartmodule::{
+    SmartModuleBudget, SmartModuleContextData, SmartModuleInvocation, SmartModuleInvocationWasm,
+    SmartModuleKind,
+};
+use fluvio_storage::{FileReplica, ReplicaStorage, SmartModuleAccumulator};
 use fluvio_storage::iterators::{FileBatch, FileBatchIterator, FileRecordIterator, RecordItem};
-use fluvio_types::Timestamp;
+use fluvio_types::{PartitionId, Timestamp};
+use sha2::{Digest, Sha256};
 use tracing::{debug, trace, error};
 
+use crate::config::SmartEngineConfig;
 use crate::core::GlobalContext;
+use crate::core::auth::SpuAuthContext;
 use crate::core::metrics::SpuMetrics;
 use crate::replication::leader::LeaderReplicaState;
 
 use crate::smartengine::chain;
+use crate::smartengine::dead_letter::DeadLetterTopic;
+use crate::smartengine::join::JoinTableState;
+use crate::smartengine::key_value::KeyValueState;
+use crate::smartengine::{EngineError, map_engine_error};
 use crate::smartengine::Lookback;
 use crate::smartengine::SmartModuleChainBuilder;
 use crate::smartengine::SmartModuleChainInstance;
+use crate::smartengine::SmartModuleKeyValueState;
 use crate::smartengine::Version;
 
 #[derive(Debug)]
@@ -26,6 +39,13 @@ pub struct SmartModuleContext {
     chain: SmartModuleChainInstance,
     version: Version,
     spu_metrics: Arc<SpuMetrics>,
+    // ids of aggregate SmartModules in the chain order
+    aggregates: Vec<String>,
+    join_tables: Vec<JoinTableState>,
+    key_value_states: Vec<KeyValueState>,
+    dead_letter_topics: Vec<DeadLetterTopic>,
+    // hash of SmartModules of the chain, their kinds and params
+    chain_id: String,
 }
 
 pub type SharedSmartModuleContext = Arc<RwLock<SmartModuleContext>>;
@@ -43,6 +63,18 @@ impl SmartModuleContext {
         &mut self.chain
     }
 
+    /// current accumulators of aggregate SmartModules
+    pub fn accumulators(&self) -> Vec<SmartModuleAccumulator> {
+        self.aggregates
+            .iter()
+            .zip(self.chain.accumulators())
+            .map(|(smartmodule, accumulator)| SmartModuleAccumulator {
+                smartmodule: smartmodule.clone(),
+                accumulator,
+            })
+            .collect()
+    }
+
     pub async fn look_back<R: ReplicaStorage>(
         &mut self,
         replica: &LeaderReplicaState<R>,
@@ -55,10 +87,120 @@ impl SmartModuleContext {
             .await
             .map_err(|err| {
                 error!("look_back chain error: {err:#}");
-                ErrorCode::SmartModuleLookBackError(err.root_cause().to_string())
+                match err.downcast_ref() {
+                    Some(
+                        engine_err @ (EngineError::FuelExhausted { .. }
+                        | EngineError::CallTimeout { .. }),
+                    ) => map_engine_error(engine_err),
+                    _ => ErrorCode::SmartModuleLookBackError(err.root_cause().to_string()),
+                }
             })
     }
 
+    /// attach tables of joined topics to their partitions and load them,
+    /// `auth` is context of client running the chain
+    pub async fn init_join_tables(
+        &mut self,
+        partition: PartitionId,
+        ctx: &GlobalContext<FileReplica>,
+        auth: Option<&SpuAuthContext>,
+    ) -> Result<(), ErrorCode> {
+        for join_table in self.join_tables.iter_mut() {
+            join_table.attach(partition, ctx, auth).await?;
+        }
+        self.update_join_tables().await
+    }
+
+    /// apply records written to joined topics since last update
+    pub async fn update_join_tables(&mut self) -> Result<(), ErrorCode> {
+        for join_table in self.join_tables.iter_mut() {
+            join_table.update(self.version).await?;
+        }
+        Ok(())
+    }
+
+    /// attach dead-letter topics to their partitions,
+    /// `auth` is context of client running the chain
+    pub async fn init_dead_letter_topics(
+        &mut self,
+        partition: PartitionId,
+        ctx: &GlobalContext<FileReplica>,
+        auth: Option<&SpuAuthContext>,
+    ) -> Result<(), ErrorCode> {
+        for dead_letter_topic in self.dead_letter_topics.iter_mut() {
+            dead_letter_topic.attach(partition, ctx, auth).await?;
+        }
+        Ok(())
+    }
+
+    /// write records failed by SmartModules since last call to their dead-letter topics
+    pub async fn write_dead_letters(&mut self) -> Result<(), ErrorCode> {
+        for (dead_letter_topic, errors) in self.take_dead_letters() {
+            dead_letter_topic.write(errors).await?;
+        }
+        Ok(())
+    }
+
+    /// write records of the consumed replica failed by SmartModules since last call
+    /// to their dead-letter topics, skipping records already written when consumed before
+    /// by the stream with the same SmartModule state id, or by the same chain if there is none
+    pub async fn write_consumed_dead_letters<R: ReplicaStorage>(
+        &mut self,
+        state_id: Option<&str>,
+        replica: &LeaderReplicaState<R>,
+    ) -> Result<(), ErrorCode> {
+        let chain_id = state_id.unwrap_or(&self.chain_id).to_owned();
+        for (dead_letter_topic, errors) in self.take_dead_letters() {
+            dead_letter_topic
+                .write_once(&chain_id, errors, replica)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// failed records grouped by their dead-letter topic
+    fn take_dead_letters(
+        &mut self,
+    ) -> Vec<(&DeadLetterTopic, Vec<SmartModuleTransformRuntimeError>)> {
+        let mut dead_letters = self.chain.take_dead_letters();
+        let mut by_topic = vec![];
+        for dead_letter_topic in self.dead_letter_topics.iter() {
+            if dead_letters.is_empty() {
+                break;
+            }
+            let (errors, rest): (Vec<_>, Vec<_>) = dead_letters
+                .into_iter()
+                .partition(|dead_letter| dead_letter.topic == dead_letter_topic.topic());
+            dead_letters = rest;
+            by_topic.push((
+                dead_letter_topic,
+                errors
+                    .into_iter()
+                    .map(|dead_letter| dead_letter.error)
+                    .collect(),
+            ));
+        }
+        by_topic
+    }
+
+    /// load key-value states of SmartModules persisted in the replica
+    pub async fn load_key_values<R: ReplicaStorage>(&self, replica: &LeaderReplicaState<R>) {
+        for key_value_state in self.key_value_states.iter() {
+            key_value_state.load(replica).await;
+        }
+    }
+
+    /// persist key-value states changed by SmartModules
+    pub async fn commit_key_values<R: ReplicaStorage>(
+        &self,
+        replica: &LeaderReplicaState<R>,
+    ) -> Result<(), ErrorCode> {
+        for key_value_state in self.key_value_states.iter() {
+            key_value_state.commit(replica).await?;
+        }
+        Ok(())
+    }
+
     /// given SmartModule invocation and context, generate execution context
     async fn build_smartmodule_context<R: ReplicaStorage>(
         invocations: Vec<SmartModuleInvocation>,
@@ -70,15 +212,53 @@ impl SmartModuleContext {
         }
 
         let mut fetched_invocations = Vec::with_capacity(invocations.len());
-        for invocation in invocations {
+        let mut chain_key_value_states = Vec::with_capacity(invocations.len());
+        let mut module_names = Vec::with_capacity(invocations.len());
+        let mut key_value_states = vec![];
+        let chain_id = chain_id(&invocations);
+        let aggregates = invocations
+            .iter()
+            .filter(|invocation| is_aggregate(invocation))
+            .map(|invocation| smartmodule_id(&invocation.wasm))
+            .collect();
+        let mut dead_letter_topics: Vec<DeadLetterTopic> = vec![];
+        for mut invocation in invocations {
+            invocation.budget = cap_budget(invocation.budget, &ctx.config().smart_engine);
+            if let SmartModuleErrorPolicy::DeadLetter(topic) = &invocation.error_policy {
+                if !dead_letter_topics
+                    .iter()
+                    .any(|dead_letter_topic| dead_letter_topic.topic() == topic)
+                {
+                    dead_letter_topics.push(DeadLetterTopic::new(topic.clone()));
+                }
+            }
+            // only state of SmartModule referenced by name can be persisted
+            let key_value_state = SmartModuleKeyValueState::default();
+            key_value_state.set_max_size(ctx.config().smart_engine.key_value_max_bytes);
+            if let SmartModuleInvocationWasm::Predefined(name) = &invocation.wasm {
+                key_value_states.push(KeyValueState::new(name.clone(), key_value_state.clone()));
+                // compiled SmartModule is cached only for SmartModules referenced by name
+                module_names.push(Some(name.clone()));
+            } else {
+                module_names.push(None);
+            }
+            chain_key_value_states.push(key_value_state);
             fetched_invocations.push(resolve_invocation(invocation, ctx)?)
         }
         let mut chain_builder = SmartModuleChainBuilder::default();
         chain_builder.set_store_memory_limit(ctx.config().smart_engine.store_max_memory);
+        if let Some(fuel_limit) = ctx.config().smart_engine.fuel_limit {
+            chain_builder.set_fuel_limit(fuel_limit);
+        }
+        if let Some(call_timeout) = ctx.config().smart_engine.call_timeout {
+            chain_builder.set_call_timeout(call_timeout);
+        }
 
-        let chain = chain::build_chain(
+        let (chain, join_tables) = chain::build_chain(
             chain_builder,
             fetched_invocations,
+            chain_key_value_states,
+            module_names,
             version,
             ctx.smartengine_owned(),
         )?;
@@ -87,10 +267,79 @@ impl SmartModuleContext {
             chain,
             version,
             spu_metrics: ctx.metrics(),
+            aggregates,
+            join_tables,
+            key_value_states,
+            dead_letter_topics,
+            chain_id,
         }))
     }
 }
 
+/// replace initial accumulators of aggregate invocations with checkpointed ones.
+/// Accumulator is restored only if it was checkpointed by the same SmartModule,
+/// SmartModules used more than once in the chain are matched in the chain order.
+pub fn restore_accumulators(
+    invocations: &mut [SmartModuleInvocation],
+    mut accumulators: Vec<SmartModuleAccumulator>,
+) {
+    for invocation in invocations.iter_mut() {
+        let smartmodule = smartmodule_id(&invocation.wasm);
+        let (SmartModuleKind::Aggregate { accumulator }
+        | SmartModuleKind::Generic(SmartModuleContextData::Aggregate { accumulator })) =
+            &mut invocation.kind
+        else {
+            continue;
+        };
+        if let Some(index) = accumulators
+            .iter()
+            .position(|restored| restored.smartmodule == smartmodule)
+        {
+            *accumulator = accumulators.remove(index).accumulator;
+        }
+    }
+}
+
+fn is_aggregate(invocation: &SmartModuleInvocation) -> bool {
+    matches!(
+        invocation.kind,
+        SmartModuleKind::Aggregate { .. }
+            | SmartModuleKind::Generic(SmartModuleContextData::Aggregate { .. })
+    )
+}
+
+/// limits requested by the invocation can't exceed limits of SPU
+fn cap_budget(budget: SmartModuleBudget, config: &SmartEngineConfig) -> SmartModuleBudget {
+    SmartModuleBudget {
+        fuel_limit: budget
+            .fuel_limit
+            .map(|fuel| config.fuel_limit.map_or(fuel, |max| fuel.min(max))),
+        call_timeout: budget
+            .call_timeout
+            .map(|timeout| config.call_timeout.map_or(timeout, |max| timeout.min(max))),
+    }
+}
+
+/// hash of SmartModules of the chain, their kinds and params.
+/// Accumulators are not part of it, so it doesn't change when they are restored
+fn chain_id(invocations: &[SmartModuleInvocation]) -> String {
+    let mut hasher = Sha256::new();
+    for invocation in invocations {
+        hasher.update(smartmodule_id(&invocation.wasm));
+        hasher.update(invocation.kind.to_string());
+        hasher.update(format!("{:?}", invocation.params));
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// name of SmartModule, or hash of its binary if it is not referenced by name
+fn smartmodule_id(wasm: &SmartModuleInvocationWasm) -> String {
+    match wasm {
+        SmartModuleInvocationWasm::Predefined(name) => name.clone(),
+        SmartModuleInvocationWasm::AdHoc(bytes) => hex::encode(Sha256::digest(bytes)),
+    }
+}
+
 fn resolve_invocation<R: ReplicaStorage>(
     invocation: SmartModuleInvocation,
     ctx: &GlobalContext<R>,
@@ -238,3 +487,92 @@ async fn read_batches_by_age<R: ReplicaStorage>(
     );
     Ok(result)
 }
+
+#[cfg(test)]
+mod test {
+
+    use std::time::Duration;
+
+    use fluvio_spu_schema::server::smartmodule::{
+        SmartModuleBudget, SmartModuleInvocation, SmartModuleInvocationWasm, SmartModuleKind,
+    };
+    use fluvio_storage::SmartModuleAccumulator;
+
+    use crate::config::SmartEngineConfig;
+
+    use super::{cap_budget, restore_accumulators};
+
+    fn aggregate(name: &str) -> SmartModuleInvocation {
+        SmartModuleInvocation {
+            wasm: SmartModuleInvocationWasm::Predefined(name.to_owned()),
+            kind: SmartModuleKind::Aggregate {
+                accumulator: vec![],
+            },
+            ..Default::default()
+        }
+    }
+
+    fn accumulator(invocation: &SmartModuleInvocation) -> &[u8] {
+        match &invocation.kind {
+            SmartModuleKind::Aggregate { accumulator } => accumulator,
+            _ => panic!("not aggregate"),
+        }
+    }
+
+    #[test]
+    fn test_restore_accumulators_by_smartmodule() {
+        let mut invocations = vec![aggregate("count"), aggregate("sum"), aggregate("sum")];
+        let checkpointed = vec![
+            SmartModuleAccumulator {
+                smartmodule: "sum".to_owned(),
+                accumulator: b"10".to_vec(),
+            },
+            SmartModuleAccumulator {
+                smartmodule: "max".to_owned(),
+                accumulator: b"7".to_vec(),
+            },
+            SmartModuleAccumulator {
+                smartmodule: "sum".to_owned(),
+                accumulator: b"20".to_vec(),
+            },
+        ];
+
+        restore_accumulators(&mut invocations, checkpointed);
+
+        assert!(accumulator(&invocations[0]).is_empty());
+        assert_eq!(accumulator(&invocations[1]), b"10");
+        assert_eq!(accumulator(&invocations[2]), b"20");
+    }
+
+    #[test]
+    fn test_cap_budget_by_spu_limits() {
+        let requested = SmartModuleBudget {
+            fuel_limit: Some(1_000),
+            call_timeout: Some(Duration::from_secs(10)),
+        };
+
+        // unbounded SPU allows any budget
+        let config = SmartEngineConfig::default();
+        assert_eq!(cap_budget(requested, &config), requested);
+
+        let config = SmartEngineConfig {
+            fuel_limit: Some(100),
+            call_timeout: Some(Duration::from_secs(1)),
+            ..Default::default()
+        };
+        assert_eq!(
+            cap_budget(requested, &config),
+            SmartModuleBudget {
+                fuel_limit: Some(100),
+                call_timeout: Some(Duration::from_secs(1)),
+            }
+        );
+
+        // lower budget is kept, missing one falls back to SPU limits of the chain
+        let requested = SmartModuleBudget {
+            fuel_limit: Some(10),
+            call_timeout: None,
+        };
+        assert_eq!(cap_budget(requested, &config), requested);
+    }
+}
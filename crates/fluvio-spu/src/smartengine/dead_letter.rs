@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use fluvio::spu::SpuDirectory;
+use fluvio_auth::InstanceAction;
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::link::smartmodule::SmartModuleTransformRuntimeError;
+use fluvio_protocol::record::{Batch, Header, MemoryRecords, RawRecords, Record, RecordSet, ReplicaKey};
+use fluvio_spu_schema::produce::{DefaultPartitionRequest, DefaultProduceRequest, DefaultTopicRequest};
+use fluvio_storage::{FileReplica, ReplicaStorage};
+use fluvio_types::PartitionId;
+use tracing::{debug, error, instrument};
+
+use crate::core::GlobalContext;
+use crate::core::auth::{allow_topic_action, SpuAuthContext};
+use crate::core::leader_client::LeaderConnections;
+use crate::replication::leader::{LeaderReplicaState, SharedFileLeaderState, SharedSpuUpdates};
+
+/// header with error hint of SmartModule
+pub(crate) const DEAD_LETTER_ERROR_HEADER: &str = "smartmodule-error";
+/// header with offset of failed record in processed partition
+pub(crate) const DEAD_LETTER_OFFSET_HEADER: &str = "smartmodule-offset";
+/// header with kind of SmartModule
+pub(crate) const DEAD_LETTER_KIND_HEADER: &str = "smartmodule-kind";
+
+/// Dead-letter topic of SmartModule invocation.
+/// Failed records are written to partition of the topic with the same number as processed partition.
+#[derive(Debug)]
+pub(crate) struct DeadLetterTopic {
+    topic: String,
+    target: Option<DeadLetterTarget>,
+}
+
+/// Leader of dead-letter partition
+#[derive(Debug)]
+enum DeadLetterTarget {
+    /// partition led by this SPU
+    Local(SharedFileLeaderState, SharedSpuUpdates),
+    /// partition led by other SPU, records are produced to it
+    Remote(Arc<LeaderConnections>, ReplicaKey),
+}
+
+impl DeadLetterTarget {
+    async fn write(&self, mut records: RecordSet<RawRecords>) -> Result<(), ErrorCode> {
+        match self {
+            Self::Local(leader, notifier) => {
+                leader
+                    .write_record_set(&mut records, notifier)
+                    .await
+                    .map_err(|err| ErrorCode::SmartModuleDeadLetterError(err.to_string()))?;
+                Ok(())
+            }
+            Self::Remote(leaders, replica) => {
+                let result = produce_to_leader(leaders, replica, records).await;
+                if result.is_err() {
+                    leaders.forget_leader(replica).await;
+                }
+                result
+            }
+        }
+    }
+}
+
+/// produce records to leader of replica on other SPU
+async fn produce_to_leader(
+    leaders: &LeaderConnections,
+    replica: &ReplicaKey,
+    records: RecordSet<RawRecords>,
+) -> Result<(), ErrorCode> {
+    let mut request = DefaultProduceRequest::default();
+    request.topics.push(DefaultTopicRequest {
+        name: replica.topic.clone(),
+        partitions: vec![DefaultPartitionRequest {
+            partition_index: replica.partition,
+            records,
+        }],
+        ..Default::default()
+    });
+
+    let socket = leaders
+        .create_serial_socket(replica)
+        .await
+        .map_err(|err| ErrorCode::SmartModuleDeadLetterError(err.to_string()))?;
+    let response = socket
+        .send_receive(request)
+        .await
+        .map_err(|err| ErrorCode::SmartModuleDeadLetterError(err.to_string()))?;
+    let error_code = response
+        .find_partition_response(&replica.topic, replica.partition)
+        .map(|partition| partition.error_code.clone())
+        .ok_or_else(|| {
+            ErrorCode::SmartModuleDeadLetterError(format!(
+                "no response for dead-letter partition {replica}"
+            ))
+        })?;
+    if error_code.is_ok() {
+        Ok(())
+    } else {
+        Err(ErrorCode::SmartModuleDeadLetterError(format!(
+            "producing to dead-letter partition {replica} failed: {error_code}"
+        )))
+    }
+}
+
+impl DeadLetterTopic {
+    pub(crate) fn new(topic: String) -> Self {
+        Self {
+            topic,
+            target: None,
+        }
+    }
+
+    pub(crate) fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// find leader of dead-letter partition, partitions led by other SPUs are produced to.
+    /// Client of the chain must be allowed to write to dead-letter topic
+    pub(crate) async fn attach(
+        &mut self,
+        partition: PartitionId,
+        ctx: &GlobalContext<FileReplica>,
+        auth: Option<&SpuAuthContext>,
+    ) -> Result<(), ErrorCode> {
+        if !allow_topic_action(auth, InstanceAction::Write, &self.topic).await {
+            debug!(topic = %self.topic, "client is not allowed to write to dead-letter topic");
+            return Err(ErrorCode::PermissionDenied);
+        }
+        let replica = ReplicaKey::new(self.topic.clone(), partition);
+        let target = match ctx.leaders_state().get(&replica).await {
+            Some(leader) => DeadLetterTarget::Local(leader, ctx.follower_notifier_owned()),
+            None => DeadLetterTarget::Remote(ctx.leaders(), replica),
+        };
+        self.target = Some(target);
+        Ok(())
+    }
+
+    /// write failed records together with their errors
+    #[instrument(skip(self, errors), fields(topic = %self.topic))]
+    pub(crate) async fn write(
+        &self,
+        errors: Vec<SmartModuleTransformRuntimeError>,
+    ) -> Result<(), ErrorCode> {
+        let Some(target) = &self.target else {
+            return Err(ErrorCode::SmartModuleDeadLetterError(format!(
+                "dead-letter topic {} is not attached",
+                self.topic
+            )));
+        };
+        if errors.is_empty() {
+            return Ok(());
+        }
+
+        debug!(records = errors.len(), "writing dead letters");
+        let mut batch = Batch::<MemoryRecords>::default();
+        for error in errors {
+            let mut record = Record {
+                key: error.record_key,
+                value: error.record_value,
+                ..Default::default()
+            };
+            record.add_header(Header::new(DEAD_LETTER_ERROR_HEADER, error.hint));
+            record.add_header(Header::new(
+                DEAD_LETTER_OFFSET_HEADER,
+                error.offset.to_string(),
+            ));
+            record.add_header(Header::new(DEAD_LETTER_KIND_HEADER, error.kind.to_string()));
+            batch.add_record(record);
+        }
+
+        let batch = Batch::<RawRecords>::try_from(batch).map_err(|err| {
+            ErrorCode::SmartModuleDeadLetterError(format!("encoding dead letters failed: {err}"))
+        })?;
+        let records = RecordSet {
+            batches: vec![batch],
+        };
+        target.write(records).await.map_err(|err| {
+            error!(%err, "error writing dead letters");
+            err
+        })
+    }
+
+    /// write failed records of consumed replica which were not written by the chain before.
+    /// Offset following the last written record is persisted in the consumed replica per chain,
+    /// the offsets are locked while written so concurrent streams of the chain don't write twice.
+    #[instrument(skip(self, errors, replica), fields(topic = %self.topic))]
+    pub(crate) async fn write_once<R: ReplicaStorage>(
+        &self,
+        chain_id: &str,
+        errors: Vec<SmartModuleTransformRuntimeError>,
+        replica: &LeaderReplicaState<R>,
+    ) -> Result<(), ErrorCode> {
+        let offsets = replica.read().await.dead_letter_offsets();
+        let mut offsets = offsets.lock().await;
+        let written = offsets.get(chain_id, &self.topic).unwrap_or_default();
+        let errors: Vec<_> = errors
+            .into_iter()
+            .filter(|error| error.offset >= written)
+            .collect();
+        let Some(next_offset) = errors.iter().map(|error| error.offset + 1).max() else {
+            return Ok(());
+        };
+        self.write(errors).await?;
+        offsets
+            .commit(chain_id, &self.topic, next_offset)
+            .await
+            .map_err(|err| {
+                error!(%err, "error committing dead-letter offset");
+                ErrorCode::StorageError
+            })
+    }
+}
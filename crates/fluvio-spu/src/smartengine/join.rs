@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Weak};
+
+use anyhow::{anyhow, Result};
+use async_lock::Mutex;
+use futures_util::StreamExt;
+use tokio::select;
+use fluvio::{ConsumerConfig, Isolation, Offset as ConsumerOffset};
+use fluvio_auth::InstanceAction;
+use fluvio_future::task::spawn;
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::{Offset, ReplicaKey};
+use fluvio_spu_schema::fetch::AbortedBatchFilter;
+use fluvio_storage::FileReplica;
+use fluvio_storage::iterators::{FileBatchIterator, FileRecordIterator};
+use fluvio_types::PartitionId;
+use fluvio_types::event::StickyEvent;
+use tracing::{debug, error, instrument};
+
+use crate::core::GlobalContext;
+use crate::core::auth::{allow_topic_action, SpuAuthContext};
+use crate::core::leader_client::LeaderConnections;
+use crate::replication::leader::SharedFileLeaderState;
+use crate::smartengine::{SmartModuleJoinTable, Version};
+
+/// Table of a joined partition, shared by all chains joining it on this SPU
+#[derive(Debug)]
+pub(crate) struct SharedJoinTable {
+    replica: ReplicaKey,
+    table: SmartModuleJoinTable,
+    // next offset of joined partition to apply, lock serializes updates
+    next_offset: Mutex<Offset>,
+    // leader of joined partition if it is led by this SPU, kept current as leadership changes
+    leader: std::sync::Mutex<Option<SharedFileLeaderState>>,
+    // connections to leaders on other SPUs
+    leaders: Arc<LeaderConnections>,
+    // set while records are streamed from leader on other SPU
+    streaming: AtomicBool,
+    // stops streaming when table is no longer used
+    dropped: Arc<StickyEvent>,
+}
+
+impl Drop for SharedJoinTable {
+    fn drop(&mut self) {
+        self.dropped.notify();
+    }
+}
+
+impl SharedJoinTable {
+    /// store latest value of the key, `None` value of tombstone removes the key.
+    /// fails if table would exceed max size
+    fn apply(&self, key: Option<&[u8]>, value: Option<&[u8]>) -> Result<(), ErrorCode> {
+        let Some(key) = key else {
+            return Ok(());
+        };
+        let Some(value) = value else {
+            self.table.remove(key);
+            return Ok(());
+        };
+        if self.table.upsert(key, value) {
+            Ok(())
+        } else {
+            Err(ErrorCode::SmartModuleJoinError(format!(
+                "table of joined partition {} exceeds max size",
+                self.replica
+            )))
+        }
+    }
+
+    /// apply records of joined partition led by this SPU written since last update
+    async fn update_from_replica(
+        &self,
+        replica: &SharedFileLeaderState,
+        version: Version,
+    ) -> Result<(), ErrorCode> {
+        let mut next_offset = self.next_offset.lock().await;
+        let (start_offset, _) = replica.start_offset_info().await;
+        let mut offset = (*next_offset).max(start_offset);
+        loop {
+            // records of open transactions are not read, aborted ones are skipped
+            let (slice, aborted) = replica
+                .read_stable_records(offset, u32::MAX)
+                .await
+                .map_err(|err| ErrorCode::SmartModuleJoinError(err.to_string()))?;
+            let Some(file_slice) = slice.file_slice else {
+                break;
+            };
+
+            let mut aborted_filter = AbortedBatchFilter::new(aborted);
+            let mut batch_end = offset;
+            for file_batch in FileBatchIterator::from_raw_slice(file_slice) {
+                let file_batch =
+                    file_batch.map_err(|err| ErrorCode::SmartModuleJoinError(err.to_string()))?;
+                batch_end = batch_end.max(file_batch.batch.get_last_offset() + 1);
+                if aborted_filter.skip(&file_batch.batch) {
+                    continue;
+                }
+                for item in FileRecordIterator::new(std::iter::once(Ok(file_batch)), version) {
+                    let item =
+                        item.map_err(|err| ErrorCode::SmartModuleJoinError(err.to_string()))?;
+                    if item.offset < offset {
+                        continue;
+                    }
+                    self.apply(
+                        item.record.key().map(|key| key.as_ref()),
+                        (!item.record.is_tombstone()).then_some(item.record.value().as_ref()),
+                    )?;
+                }
+            }
+
+            if batch_end <= offset {
+                break;
+            }
+            offset = batch_end;
+        }
+
+        debug!(
+            from = *next_offset,
+            to = offset,
+            keys = self.table.len(),
+            "join table updated"
+        );
+        *next_offset = offset;
+        Ok(())
+    }
+
+    /// apply records of joined partition written since last update,
+    /// read from storage if this SPU leads the partition, otherwise streamed from its leader
+    async fn update(self: &Arc<Self>, version: Version) -> Result<(), ErrorCode> {
+        let leader = self.leader.lock().unwrap().clone();
+        match leader {
+            Some(leader) => self.update_from_replica(&leader, version).await,
+            // records are applied as they are streamed from leader,
+            // streaming is restarted if it stopped, e.g. after leader changed
+            None => self.stream_from_leader().await,
+        }
+    }
+
+    /// load committed records of joined partition from leader on other SPU,
+    /// then keep streaming new ones in background
+    async fn stream_from_leader(self: &Arc<Self>) -> Result<(), ErrorCode> {
+        // concurrent chains wait for table to be loaded
+        let mut next_offset = self.next_offset.lock().await;
+        if self.streaming.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let leaders = self.leaders.clone();
+        if let Err(err) = self.load_from_leader(&leaders, &mut next_offset).await {
+            leaders.forget_leader(&self.replica).await;
+            return Err(ErrorCode::SmartModuleJoinError(format!(
+                "error loading joined partition {}: {err}",
+                self.replica
+            )));
+        }
+        debug!(
+            replica = %self.replica,
+            offset = *next_offset,
+            keys = self.table.len(),
+            "join table loaded from leader"
+        );
+
+        self.streaming.store(true, Ordering::SeqCst);
+        let table = Arc::downgrade(self);
+        let replica = self.replica.clone();
+        let dropped = self.dropped.clone();
+        let offset = *next_offset;
+        spawn(async move {
+            let streamed = select! {
+                result = keep_streaming(&table, &replica, &leaders, offset) => result,
+                _ = dropped.listen() => Ok(()),
+            };
+            if let Err(err) = streamed {
+                error!(%replica, %err, "error streaming joined partition");
+                leaders.forget_leader(&replica).await;
+            }
+            if let Some(table) = table.upgrade() {
+                table.streaming.store(false, Ordering::SeqCst);
+            }
+        });
+        Ok(())
+    }
+
+    /// apply committed records of joined partition up to its current end
+    async fn load_from_leader(
+        &self,
+        leaders: &Arc<LeaderConnections>,
+        next_offset: &mut Offset,
+    ) -> Result<()> {
+        let consumer = leaders
+            .clone()
+            .partition_consumer(self.replica.topic.clone(), self.replica.partition)
+            .await;
+        let mut stream = Box::pin(
+            consumer
+                .stream_with_config(
+                    ConsumerOffset::absolute(*next_offset)?,
+                    committed_config(false)?,
+                )
+                .await?,
+        );
+        while let Some(record) = stream.next().await {
+            let record = record?;
+            self.apply(
+                record.key(),
+                (!record.is_tombstone()).then_some(record.value()),
+            )?;
+            *next_offset = record.offset() + 1;
+        }
+        Ok(())
+    }
+}
+
+/// apply committed records of joined partition as they are written, until table is dropped
+async fn keep_streaming(
+    table: &Weak<SharedJoinTable>,
+    replica: &ReplicaKey,
+    leaders: &Arc<LeaderConnections>,
+    offset: Offset,
+) -> Result<()> {
+    let consumer = leaders
+        .clone()
+        .partition_consumer(replica.topic.clone(), replica.partition)
+        .await;
+    let mut stream = Box::pin(
+        consumer
+            .stream_with_config(ConsumerOffset::absolute(offset)?, committed_config(true)?)
+            .await?,
+    );
+    while let Some(record) = stream.next().await {
+        let record = record?;
+        let Some(table) = table.upgrade() else {
+            return Ok(());
+        };
+        // partition became led by this SPU, records are read from its storage
+        if table.leader.lock().unwrap().is_some() {
+            return Ok(());
+        }
+        let mut next_offset = table.next_offset.lock().await;
+        if record.offset() < *next_offset {
+            continue;
+        }
+        table.apply(
+            record.key(),
+            (!record.is_tombstone()).then_some(record.value()),
+        )?;
+        *next_offset = record.offset() + 1;
+    }
+    Err(anyhow!("stream from leader ended"))
+}
+
+fn committed_config(continuous: bool) -> Result<ConsumerConfig> {
+    ConsumerConfig::builder()
+        .isolation(Isolation::ReadCommitted)
+        .disable_continuous(!continuous)
+        .build()
+}
+
+/// Tables of joined partitions, kept as long as any chain uses them
+#[derive(Debug, Default)]
+pub(crate) struct JoinTables(std::sync::Mutex<HashMap<ReplicaKey, Weak<SharedJoinTable>>>);
+
+impl JoinTables {
+    /// table of joined partition, created empty if no chain uses it.
+    /// `leader` is current leader of partition if it is led by this SPU
+    pub(crate) fn get_or_create(
+        &self,
+        replica: ReplicaKey,
+        max_size: usize,
+        leader: Option<SharedFileLeaderState>,
+        leaders: Arc<LeaderConnections>,
+    ) -> Arc<SharedJoinTable> {
+        let mut tables = self.0.lock().unwrap();
+        tables.retain(|_, table| table.strong_count() > 0);
+        if let Some(table) = tables.get(&replica).and_then(Weak::upgrade) {
+            *table.leader.lock().unwrap() = leader;
+            return table;
+        }
+
+        let table = SmartModuleJoinTable::default();
+        table.set_max_size(max_size);
+        let shared = Arc::new(SharedJoinTable {
+            replica: replica.clone(),
+            table,
+            next_offset: Mutex::new(0),
+            leader: std::sync::Mutex::new(leader),
+            leaders,
+            streaming: AtomicBool::new(false),
+            dropped: StickyEvent::shared(),
+        });
+        tables.insert(replica, Arc::downgrade(&shared));
+        shared
+    }
+
+    /// track leader of joined partition on this SPU, `None` once other SPU leads it
+    pub(crate) fn set_leader(&self, replica: &ReplicaKey, leader: Option<SharedFileLeaderState>) {
+        let tables = self.0.lock().unwrap();
+        if let Some(table) = tables.get(replica).and_then(Weak::upgrade) {
+            debug!(%replica, local = leader.is_some(), "joined partition leader changed");
+            *table.leader.lock().unwrap() = leader;
+        }
+    }
+}
+
+/// Materialized table of a topic joined by SmartModule.
+/// Table is built from partition of the joined topic with the same number as consumed partition.
+/// Partition led by this SPU is read from its storage, otherwise it is streamed from its leader.
+#[derive(Debug)]
+pub(crate) struct JoinTableState {
+    topic: String,
+    table: SmartModuleJoinTable,
+    shared: Option<Arc<SharedJoinTable>>,
+}
+
+impl JoinTableState {
+    #[cfg_attr(not(feature = "smartengine"), allow(dead_code))]
+    pub(crate) fn new(topic: String, table: SmartModuleJoinTable) -> Self {
+        Self {
+            topic,
+            table,
+            shared: None,
+        }
+    }
+
+    /// share table of joined partition with other chains joining it and load it.
+    /// Client of the chain must be allowed to read joined topic
+    pub(crate) async fn attach(
+        &mut self,
+        partition: PartitionId,
+        ctx: &GlobalContext<FileReplica>,
+        auth: Option<&SpuAuthContext>,
+    ) -> Result<(), ErrorCode> {
+        if !allow_topic_action(auth, InstanceAction::Read, &self.topic).await {
+            debug!(topic = %self.topic, "client is not allowed to read joined topic");
+            return Err(ErrorCode::PermissionDenied);
+        }
+        let replica = ReplicaKey::new(self.topic.clone(), partition);
+        let leader = ctx.leaders_state().get(&replica).await;
+        let shared = ctx.join_tables().get_or_create(
+            replica,
+            ctx.config().smart_engine.join_table_max_bytes,
+            leader,
+            ctx.leaders(),
+        );
+        self.table.share(&shared.table);
+        self.shared = Some(shared);
+        Ok(())
+    }
+
+    /// apply records of joined partition written since last update
+    #[instrument(skip(self), fields(topic = %self.topic))]
+    pub(crate) async fn update(&mut self, version: Version) -> Result<(), ErrorCode> {
+        let Some(shared) = &self.shared else {
+            return Ok(());
+        };
+        shared.update(version).await
+    }
+}
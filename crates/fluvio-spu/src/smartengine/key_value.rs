@@ -0,0 +1,57 @@
+use fluvio_protocol::link::ErrorCode;
+use fluvio_storage::ReplicaStorage;
+use tracing::{debug, error, instrument};
+
+use crate::replication::leader::LeaderReplicaState;
+use crate::smartengine::SmartModuleKeyValueState;
+
+/// Key-value state of SmartModule referenced by name.
+/// State is persisted in storage of the processed replica, so it is kept per SmartModule and partition.
+/// Consumers running the same SmartModule on the same partition share persisted state,
+/// last committed value of each key wins.
+#[derive(Debug)]
+pub(crate) struct KeyValueState {
+    smartmodule: String,
+    state: SmartModuleKeyValueState,
+}
+
+impl KeyValueState {
+    pub(crate) fn new(smartmodule: String, state: SmartModuleKeyValueState) -> Self {
+        Self { smartmodule, state }
+    }
+
+    /// replace state with one persisted in the replica, discarding changes not committed
+    #[instrument(skip(self, replica), fields(smartmodule = %self.smartmodule))]
+    pub(crate) async fn load<R: ReplicaStorage>(&self, replica: &LeaderReplicaState<R>) {
+        let store = replica.read().await.smartmodule_key_values();
+        let key_values = store
+            .lock()
+            .await
+            .get(&self.smartmodule)
+            .unwrap_or_default();
+        debug!(keys = key_values.len(), "key-value state loaded");
+        self.state.load(key_values);
+    }
+
+    /// persist keys SmartModule changed since last commit.
+    /// Replica is not locked while keys are written.
+    #[instrument(skip(self, replica), fields(smartmodule = %self.smartmodule))]
+    pub(crate) async fn commit<R: ReplicaStorage>(
+        &self,
+        replica: &LeaderReplicaState<R>,
+    ) -> Result<(), ErrorCode> {
+        let changes = self.state.take_changes();
+        if changes.is_empty() {
+            return Ok(());
+        }
+        let store = replica.read().await.smartmodule_key_values();
+        let mut store = store.lock().await;
+        store
+            .commit(&self.smartmodule, changes)
+            .await
+            .map_err(|err| {
+                error!(%err, "error committing smartmodule key-value state");
+                ErrorCode::StorageError
+            })
+    }
+}
@@ -3,24 +3,29 @@ use std::collections::BTreeMap;
 use fluvio::{
     SmartModuleInvocation, SmartModuleInvocationWasm, SmartModuleKind, SmartModuleExtraParams,
 };
-use fluvio_controlplane_metadata::topic::Deduplication;
+use fluvio_controlplane_metadata::topic::{Deduplication, Transform};
 use fluvio_protocol::link::ErrorCode;
 
 pub(crate) mod batch;
 pub(crate) mod file_batch;
 pub(crate) mod produce_batch;
 pub(crate) mod context;
+pub(crate) mod dead_letter;
+pub(crate) mod join;
+pub(crate) mod key_value;
 mod chain;
 
 #[cfg(feature = "smartengine")]
 pub(crate) use fluvio_smartengine::{
     EngineError, Lookback, SmartModuleChainBuilder, metrics::SmartModuleChainMetrics, SmartEngine,
-    SmartModuleChainInstance, Version,
+    SmartModuleChainInstance, SmartModuleDeadLetter, SmartModuleJoinTable,
+    SmartModuleKeyValueState, Version,
 };
 
 // Stub structures to support a null smartengine config
 #[cfg(not(feature = "smartengine"))]
 mod null_smartengine {
+    use std::collections::BTreeMap;
     use std::future::Future;
     use std::sync::atomic::{AtomicU64, Ordering};
     use std::time::Duration;
@@ -28,8 +33,11 @@ mod null_smartengine {
     use anyhow::Result;
     use serde::{Deserialize, Serialize};
 
+    use fluvio_smartmodule::dataplane::smartmodule::SmartModuleChainTrace;
+    use fluvio_smartmodule::dataplane::smartmodule::SmartModuleDebugConfig;
     use fluvio_smartmodule::dataplane::smartmodule::SmartModuleInput;
     use fluvio_smartmodule::dataplane::smartmodule::SmartModuleOutput;
+    use fluvio_smartmodule::dataplane::smartmodule::SmartModuleTransformRuntimeError;
     use fluvio_smartmodule::Record;
 
     // refactor to use more widely as a "flow" metric?
@@ -80,6 +88,12 @@ mod null_smartengine {
         pub fn new() -> Self {
             SmartEngine {}
         }
+
+        pub fn with_module_cache_dir(self, _dir: impl Into<std::path::PathBuf>) -> Self {
+            self
+        }
+
+        pub fn invalidate_module(&self, _name: &str) {}
     }
 
     #[derive(Default)]
@@ -87,6 +101,10 @@ mod null_smartengine {
 
     impl SmartModuleChainBuilder {
         pub fn set_store_memory_limit(&mut self, _max_memory_bytes: usize) {}
+
+        pub fn set_fuel_limit(&mut self, _fuel: u64) {}
+
+        pub fn set_call_timeout(&mut self, _timeout: Duration) {}
     }
 
     #[derive(Debug)]
@@ -105,6 +123,26 @@ mod null_smartengine {
             Ok(())
         }
 
+        pub fn accumulators(&self) -> Vec<Vec<u8>> {
+            vec![]
+        }
+
+        pub fn take_dead_letters(&mut self) -> Vec<SmartModuleDeadLetter> {
+            vec![]
+        }
+
+        pub fn set_debug(&mut self, _debug: Option<SmartModuleDebugConfig>) {}
+
+        pub fn set_trace_max_bytes(&mut self, _max_bytes: usize) {}
+
+        pub fn trace_size(&self) -> usize {
+            0
+        }
+
+        pub fn take_trace(&mut self) -> SmartModuleChainTrace {
+            SmartModuleChainTrace::default()
+        }
+
         pub fn process(
             &mut self,
             input: SmartModuleInput,
@@ -122,6 +160,50 @@ mod null_smartengine {
 
     pub type Version = i16;
 
+    #[derive(Debug)]
+    pub struct SmartModuleDeadLetter {
+        pub topic: String,
+        pub error: SmartModuleTransformRuntimeError,
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct SmartModuleJoinTable;
+
+    #[allow(dead_code)]
+    impl SmartModuleJoinTable {
+        pub fn set_max_size(&self, _max_size: usize) {}
+
+        pub fn share(&self, _other: &SmartModuleJoinTable) {}
+
+        pub fn upsert(&self, _key: &[u8], _value: &[u8]) -> bool {
+            true
+        }
+
+        pub fn remove(&self, _key: &[u8]) {}
+
+        pub fn len(&self) -> usize {
+            0
+        }
+
+        pub fn is_empty(&self) -> bool {
+            true
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct SmartModuleKeyValueState;
+
+    #[allow(dead_code)]
+    impl SmartModuleKeyValueState {
+        pub fn set_max_size(&self, _max_size: usize) {}
+
+        pub fn load(&self, _entries: BTreeMap<Vec<u8>, Vec<u8>>) {}
+
+        pub fn take_changes(&self) -> Vec<(Vec<u8>, Option<Vec<u8>>)> {
+            vec![]
+        }
+    }
+
     // copied from SmartEngine crate, refactor to remove this and config smartengine crate to export w/o specific engine later
     #[allow(dead_code)]
     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -143,6 +225,10 @@ mod null_smartengine {
             requested: usize,
             max: usize,
         },
+        #[error("SmartModule call exhausted fuel limit {limit}")]
+        FuelExhausted { limit: u64 },
+        #[error("SmartModule call exceeded timeout {timeout:?}")]
+        CallTimeout { timeout: Duration },
     }
 }
 
@@ -168,6 +254,19 @@ pub(crate) fn dedup_to_invocation(dedup: &Deduplication) -> SmartModuleInvocatio
         wasm: SmartModuleInvocationWasm::Predefined(dedup.filter.transform.uses.clone()),
         kind: SmartModuleKind::Filter,
         params: SmartModuleExtraParams::new(params, Some(lookback)),
+        error_policy: Default::default(),
+        budget: Default::default(),
+    }
+}
+
+/// SmartModule of topic transforms, its kind is detected from the module exports
+pub(crate) fn transform_to_invocation(transform: &Transform) -> SmartModuleInvocation {
+    SmartModuleInvocation {
+        wasm: SmartModuleInvocationWasm::Predefined(transform.uses.clone()),
+        kind: SmartModuleKind::Generic(Default::default()),
+        params: transform.with.clone().into(),
+        error_policy: Default::default(),
+        budget: Default::default(),
     }
 }
 
@@ -183,6 +282,12 @@ pub(crate) fn map_engine_error(err: &EngineError) -> ErrorCode {
             requested: *requested as u64,
             max: *max as u64,
         },
+        EngineError::FuelExhausted { limit } => {
+            ErrorCode::SmartModuleFuelExhausted { limit: *limit }
+        }
+        EngineError::CallTimeout { timeout } => ErrorCode::SmartModuleTimeout {
+            timeout_ms: timeout.as_millis() as u64,
+        },
     }
 }
 
@@ -190,7 +295,7 @@ pub(crate) fn map_engine_error(err: &EngineError) -> ErrorCode {
 mod tests {
     use std::time::Duration;
 
-    use fluvio_controlplane_metadata::topic::{Bounds, Filter, Transform};
+    use fluvio_controlplane_metadata::topic::{Bounds, Filter};
 
     use super::*;
 
@@ -225,4 +330,25 @@ mod tests {
             Some(&"param_value".to_string())
         );
     }
+
+    #[test]
+    fn test_transform_to_inv() {
+        //given
+        let transform = Transform {
+            uses: "sanitize@0.1.0".to_string(),
+            with: BTreeMap::from([("field".to_string(), "password".to_string())]),
+        };
+
+        //when
+        let inv = transform_to_invocation(&transform);
+
+        //then
+        assert!(matches!(
+            inv.wasm,
+            SmartModuleInvocationWasm::Predefined(str) if str.eq("sanitize@0.1.0")
+        ));
+        assert!(matches!(inv.kind, SmartModuleKind::Generic(_)));
+        assert_eq!(inv.params.get("field"), Some(&"password".to_string()));
+        assert!(inv.params.lookback().is_none());
+    }
 }
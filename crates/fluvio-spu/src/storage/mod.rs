@@ -163,6 +163,15 @@ where
         Ok((base_offset, leo, bytes_written))
     }
 
+    /// discard records at or after offset, so they can be written again
+    pub async fn truncate(&self, offset: Offset) -> Result<()> {
+        let mut writer = self.write().await;
+        writer.truncate(offset).await?;
+        self.leo.update(writer.get_leo());
+        self.hw.update(writer.get_hw());
+        Ok(())
+    }
+
     /// perform permanent remove
     pub async fn remove(&self) -> Result<(), StorageError> {
         self.leo.update(REMOVAL_START);
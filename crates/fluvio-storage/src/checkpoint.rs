@@ -1,9 +1,12 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
 use std::fmt::Display;
 use std::io::Cursor;
 use std::io::Error as IoError;
 use std::io::ErrorKind;
 use std::io::SeekFrom;
-use std::path::PathBuf;
+use std::ops::RangeBounds;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use bytes::Buf;
@@ -14,10 +17,15 @@ use futures_lite::io::AsyncSeekExt;
 use tracing::debug;
 use tracing::info;
 use tracing::trace;
+use tracing::warn;
 
 use fluvio_future::fs::File;
 use fluvio_future::fs::metadata;
+use fluvio_future::fs::read;
+use fluvio_future::fs::remove_file;
+use fluvio_future::fs::rename;
 use fluvio_future::fs::util;
+use fluvio_protocol::{Encoder, Decoder};
 
 use crate::config::SharedReplicaConfig;
 
@@ -156,6 +164,275 @@ where
     }
 }
 
+/// compact journal into checkpoint once it grows beyond both this size and the checkpoint
+const JOURNAL_COMPACT_MIN_SIZE: u64 = 64 * 1024;
+
+/// Values keyed by name checkpointed to files in the replica directory.
+///
+/// Values are kept in memory. Every change is appended to a journal next to the checkpoint
+/// and synced, so only changed entries are written.
+/// Once the journal outgrows the checkpoint, all values are synced to a temporary file
+/// which then replaces the checkpoint, the directory is synced and the journal is discarded,
+/// so a crash keeps either the previous or the new checkpoint.
+/// On load, the journal is replayed on top of the checkpoint; an entry partially written
+/// by a crash at the end of the journal is dropped.
+#[derive(Debug)]
+pub struct CheckPointStore<V, K = String> {
+    path: PathBuf,
+    journal_path: PathBuf,
+    values: BTreeMap<K, V>,
+    journal: Option<File>,
+    journal_len: u64,
+    checkpoint_len: u64,
+}
+
+impl<V, K> CheckPointStore<V, K>
+where
+    V: Encoder + Decoder,
+    K: Encoder + Decoder + Ord,
+{
+    /// load existing values from checkpoint `name` in base directory or create empty store
+    pub async fn create(base_dir: &Path, name: &str) -> Result<Self, IoError> {
+        let path = base_dir.join(name);
+        let journal_path = path.with_extension("journal");
+
+        let mut values = BTreeMap::new();
+        let checkpoint_len = match metadata(&path).await {
+            Ok(_) => {
+                let contents = read(&path).await?;
+                if apply_entries(&contents, &mut values) != contents.len() {
+                    return Err(IoError::new(
+                        ErrorKind::InvalidData,
+                        format!("invalid checkpoint {}", path.display()),
+                    ));
+                }
+                contents.len() as u64
+            }
+            Err(_) => {
+                debug!(path = %path.display(), "no checkpoint found");
+                0
+            }
+        };
+
+        let journal_len = match metadata(&journal_path).await {
+            Ok(_) => {
+                let contents = read(&journal_path).await?;
+                let valid = apply_entries(&contents, &mut values);
+                if valid != contents.len() {
+                    warn!(
+                        path = %journal_path.display(),
+                        dropped = contents.len() - valid,
+                        "incomplete checkpoint journal entry dropped"
+                    );
+                }
+                contents.len() as u64
+            }
+            Err(_) => 0,
+        };
+        info!(values = values.len(), path = %path.display(), "checkpoint loaded");
+
+        let mut store = Self {
+            path,
+            journal_path,
+            values,
+            journal: None,
+            journal_len,
+            checkpoint_len,
+        };
+        if journal_len > 0 {
+            store.flush().await?;
+        }
+        Ok(store)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.values.get(key)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.values.iter()
+    }
+
+    /// values with keys in the range
+    pub fn range<R>(&self, range: R) -> impl Iterator<Item = (&K, &V)>
+    where
+        R: RangeBounds<K>,
+    {
+        self.values.range(range)
+    }
+
+    /// store value under the key and persist it
+    pub async fn insert<Q>(&mut self, key: &Q, value: V) -> Result<(), IoError>
+    where
+        K: Borrow<Q>,
+        Q: ToOwned<Owned = K> + ?Sized,
+    {
+        self.update(vec![(key.to_owned(), Some(value))]).await
+    }
+
+    /// remove value of the key, return true if there was a value
+    pub async fn remove<Q>(&mut self, key: &Q) -> Result<bool, IoError>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ToOwned<Owned = K> + ?Sized,
+    {
+        if self.values.contains_key(key) {
+            self.update(vec![(key.to_owned(), None)]).await?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// store or remove (`None`) values of the keys and persist them with single sync
+    pub async fn update(&mut self, changes: Vec<(K, Option<V>)>) -> Result<(), IoError> {
+        if changes.is_empty() {
+            return Ok(());
+        }
+
+        let mut contents = Vec::new();
+        for (key, value) in &changes {
+            encode_entry(key, value.as_ref(), &mut contents)?;
+        }
+
+        let journal = match &mut self.journal {
+            Some(journal) => journal,
+            None => self
+                .journal
+                .insert(util::open_read_append(&self.journal_path).await?),
+        };
+        journal.write_all(&contents).await?;
+        journal.sync_data().await?;
+        self.journal_len += contents.len() as u64;
+        trace!(path = %self.journal_path.display(), changes = changes.len(), "checkpoint journal appended");
+
+        for (key, value) in changes {
+            match value {
+                Some(value) => {
+                    self.values.insert(key, value);
+                }
+                None => {
+                    self.values.remove(&key);
+                }
+            }
+        }
+
+        if self.journal_len >= self.checkpoint_len.max(JOURNAL_COMPACT_MIN_SIZE) {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// replace all values, return true if they were changed
+    pub async fn replace(&mut self, values: BTreeMap<K, V>) -> Result<bool, IoError>
+    where
+        V: PartialEq,
+    {
+        if self.values == values {
+            return Ok(false);
+        }
+        self.values = values;
+        self.flush().await?;
+        Ok(true)
+    }
+
+    /// write all values to checkpoint and discard journal
+    async fn flush(&mut self) -> Result<(), IoError> {
+        let mut contents = Vec::new();
+        for (key, value) in &self.values {
+            encode_entry(key, Some(value), &mut contents)?;
+        }
+
+        let tmp_path = self.path.with_extension("tmp");
+        let mut file = File::create(&tmp_path).await?;
+        file.write_all(&contents).await?;
+        file.sync_all().await?;
+        drop(file);
+
+        rename(&tmp_path, &self.path).await?;
+        self.sync_dir().await?;
+        self.checkpoint_len = contents.len() as u64;
+
+        // journal entries are included in checkpoint, replaying them again is harmless
+        self.journal = None;
+        if self.journal_len > 0 {
+            remove_file(&self.journal_path).await?;
+            self.sync_dir().await?;
+            self.journal_len = 0;
+        }
+        trace!(path = %self.path.display(), "checkpoint flushed");
+        Ok(())
+    }
+
+    async fn sync_dir(&self) -> Result<(), IoError> {
+        if let Some(dir) = self.path.parent() {
+            File::open(dir).await?.sync_all().await?;
+        }
+        Ok(())
+    }
+}
+
+/// append length prefixed entry of key and value, `None` value removes the key
+fn encode_entry<K: Encoder, V: Encoder>(
+    key: &K,
+    value: Option<&V>,
+    dest: &mut Vec<u8>,
+) -> Result<(), IoError> {
+    let mut entry = Vec::new();
+    key.encode(&mut entry, 0)?;
+    value.is_some().encode(&mut entry, 0)?;
+    if let Some(value) = value {
+        value.encode(&mut entry, 0)?;
+    }
+    let len = u32::try_from(entry.len()).map_err(|_| {
+        IoError::new(
+            ErrorKind::InvalidInput,
+            format!("checkpoint entry of {} bytes is too large", entry.len()),
+        )
+    })?;
+    dest.put_u32(len);
+    dest.extend_from_slice(&entry);
+    Ok(())
+}
+
+/// apply entries from contents to values until the end or first incomplete entry.
+/// return number of bytes applied
+fn apply_entries<K, V>(contents: &[u8], values: &mut BTreeMap<K, V>) -> usize
+where
+    K: Decoder + Ord,
+    V: Decoder,
+{
+    let mut remaining = contents;
+    while remaining.len() >= 4 {
+        let len = (&remaining[..4]).get_u32() as usize;
+        let Some(mut entry) = remaining.get(4..4 + len) else {
+            break;
+        };
+        let mut key = K::default();
+        let mut value: Option<V> = None;
+        if key.decode(&mut entry, 0).is_err()
+            || value.decode(&mut entry, 0).is_err()
+            || !entry.is_empty()
+        {
+            break;
+        }
+        match value {
+            Some(value) => {
+                values.insert(key, value);
+            }
+            None => {
+                values.remove(&key);
+            }
+        }
+        remaining = &remaining[4 + len..];
+    }
+    contents.len() - remaining.len()
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -163,8 +440,12 @@ mod tests {
 
     use flv_util::fixture::ensure_clean_file;
 
+    use std::collections::BTreeMap;
+
+    use flv_util::fixture::ensure_clean_dir;
+
     use crate::config::ReplicaConfig;
-    use super::CheckPoint;
+    use super::{CheckPoint, CheckPointStore};
 
     #[fluvio_future::test]
     async fn checkpoint_test() {
@@ -196,4 +477,78 @@ mod tests {
             .await
             .expect("write aft er reading should work");
     }
+
+    #[fluvio_future::test]
+    async fn checkpoint_store_test() {
+        let test_dir = temp_dir().join("checkpoint_store_test");
+        ensure_clean_dir(&test_dir);
+
+        let mut store: CheckPointStore<i64> = CheckPointStore::create(&test_dir, "test.chk")
+            .await
+            .expect("create");
+        assert_eq!(store.get("a"), None);
+
+        store.insert("a", 10).await.expect("insert");
+        store.insert("b", 5).await.expect("insert");
+        store.insert("a", 20).await.expect("insert");
+        assert!(store.remove("b").await.expect("remove"));
+        assert!(!store.remove("b").await.expect("remove"));
+        drop(store);
+
+        let mut store: CheckPointStore<i64> = CheckPointStore::create(&test_dir, "test.chk")
+            .await
+            .expect("reload");
+        assert_eq!(store.get("a"), Some(&20));
+        assert_eq!(store.get("b"), None);
+        assert!(!test_dir.join("test.tmp").exists());
+
+        let values = BTreeMap::from([("c".to_owned(), 3)]);
+        assert!(store.replace(values.clone()).await.expect("replace"));
+        assert!(!store.replace(values).await.expect("replace"));
+        assert_eq!(store.iter().count(), 1);
+    }
+
+    #[fluvio_future::test]
+    async fn checkpoint_store_journal_test() {
+        let test_dir = temp_dir().join("checkpoint_store_journal_test");
+        ensure_clean_dir(&test_dir);
+        let journal_path = test_dir.join("test.journal");
+
+        let mut store: CheckPointStore<i64> = CheckPointStore::create(&test_dir, "test.chk")
+            .await
+            .expect("create");
+        store.insert("a", 10).await.expect("insert");
+        store
+            .update(vec![("b".to_owned(), Some(5)), ("a".to_owned(), None)])
+            .await
+            .expect("update");
+        drop(store);
+
+        // only changes are journaled, checkpoint is not written yet
+        assert!(!test_dir.join("test.chk").exists());
+        let mut journal = std::fs::read(&journal_path).expect("read journal");
+
+        // entry partially written by crash is dropped
+        journal.extend_from_slice(&[0, 0, 0, 20, 1]);
+        std::fs::write(&journal_path, journal).expect("write journal");
+
+        let store: CheckPointStore<i64> = CheckPointStore::create(&test_dir, "test.chk")
+            .await
+            .expect("reload");
+        assert_eq!(store.get("a"), None);
+        assert_eq!(store.get("b"), Some(&5));
+
+        // journal is compacted into checkpoint on load
+        assert!(test_dir.join("test.chk").exists());
+        assert!(!journal_path.exists());
+        drop(store);
+
+        let store: CheckPointStore<i64> = CheckPointStore::create(&test_dir, "test.chk")
+            .await
+            .expect("reload");
+        assert_eq!(
+            store.iter().collect::<Vec<_>>(),
+            vec![(&"b".to_owned(), &5)]
+        );
+    }
 }
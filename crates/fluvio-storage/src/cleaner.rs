@@ -1,21 +1,25 @@
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use std::ops::Div;
 use std::ops::Rem;
 
-use tracing::{debug, info, instrument};
+use tracing::{debug, error, info, instrument};
 
 use fluvio_future::task::spawn;
 use fluvio_future::timer::sleep;
+use fluvio_protocol::record::Offset;
 use fluvio_types::event::StickyEvent;
 
+use crate::compaction::Compactor;
 use crate::config::{SharedReplicaConfig, StorageConfig};
 use crate::replica::ReplicaSize;
 use crate::segments::SharedSegments;
 
 /// Replica cleaner. This is a background task that periodically checks for expired segments and
 /// removes them. It also enforces max partition size by removing first segments if replica size is
-/// exceeded. In the future, this may be done by a central cleaner pool instead of per a replica.
+/// exceeded. For compacted replicas, closed segments are compacted instead of expired and size is not
+/// enforced, since removing segments would lose latest records of keys.
+/// In the future, this may be done by a central cleaner pool instead of per a replica.
 #[derive(Debug)]
 pub(crate) struct Cleaner {
     config: Arc<StorageConfig>,
@@ -23,6 +27,7 @@ pub(crate) struct Cleaner {
     segments: Arc<SharedSegments>,
     replica_size: Arc<ReplicaSize>,
     end_event: Arc<StickyEvent>,
+    last_compaction: Mutex<Option<(Offset, Instant)>>,
 }
 
 impl Cleaner {
@@ -39,6 +44,7 @@ impl Cleaner {
             segments,
             replica_size,
             end_event,
+            last_compaction: Mutex::new(None),
         });
 
         let cleaner_ref = cleaner.clone();
@@ -73,7 +79,11 @@ impl Cleaner {
                 },
                 _ = sleep(sleep_period) => {
                     self.enforce_size().await;
-                    self.enforce_ttl().await;
+                    if self.replica_config.compact {
+                        self.enforce_compaction().await;
+                    } else {
+                        self.enforce_ttl().await;
+                    }
                 }
             }
         }
@@ -83,6 +93,10 @@ impl Cleaner {
 
     #[instrument(skip(self))]
     async fn enforce_size(&self) {
+        if self.replica_config.compact {
+            debug!("compacted replica, max partition size is not enforced");
+            return;
+        }
         let replica_size = self.replica_size.get();
         let max_partition_size = self.replica_config.max_partition_size.get();
         let excess = replica_size.saturating_sub(max_partition_size);
@@ -130,6 +144,36 @@ impl Cleaner {
             self.replica_size.store_prev(read.occupied_memory());
         }
     }
+
+    /// compact closed segments when new segment is closed or tombstones may have expired since last compaction
+    #[instrument(skip(self))]
+    async fn enforce_compaction(&self) {
+        let delete_retention =
+            Duration::from_secs(self.replica_config.delete_retention_seconds.get() as u64);
+        let max_offset = self.segments.read().await.max_offset();
+        let due = match *self.last_compaction.lock().expect("lock") {
+            Some((offset, time)) => offset < max_offset || time.elapsed() >= delete_retention,
+            None => true,
+        };
+        if !due {
+            return;
+        }
+
+        let compactor = Compactor::new(
+            self.segments.clone(),
+            self.replica_config.clone(),
+            delete_retention,
+        );
+        match compactor.compact().await {
+            Ok(result) => {
+                debug!(?result, "compaction done");
+                *self.last_compaction.lock().expect("lock") = Some((max_offset, Instant::now()));
+                let read = self.segments.read().await;
+                self.replica_size.store_prev(read.occupied_memory());
+            }
+            Err(err) => error!(%err, "compaction failed"),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -137,7 +181,7 @@ mod tests {
 
     use std::env::temp_dir;
     use std::ops::AddAssign;
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
     use std::time::Duration;
 
     use anyhow::Result;
@@ -145,8 +189,9 @@ mod tests {
     use fluvio_future::timer::sleep;
     use flv_util::fixture::ensure_new_dir;
     use fluvio_protocol::fixture::create_batch;
-    use fluvio_protocol::record::Offset;
+    use fluvio_protocol::record::{Batch, Offset, RawRecords, Record};
 
+    use crate::batch::FileBatchStream;
     use crate::config::SharedReplicaConfig;
     use crate::segment::MutableSegment;
     use crate::segment::ReadSegment;
@@ -200,6 +245,25 @@ mod tests {
         assert_eq!(read.occupied_memory(), replica_size.get());
     }
 
+    #[fluvio_future::test]
+    async fn test_enforce_size_keeps_compacted_segments() {
+        //given
+        let mut config = default_option();
+        config.max_partition_size = 150;
+        config.segment_max_bytes = 80;
+        config.compact = true;
+        let segments = shared_segments("cleaner-enforce-size-compact", 2, config.clone()).await;
+        let replica_size = Arc::new(ReplicaSize::default());
+        replica_size.store_prev(151);
+        let cleaner = test_cleaner(config, segments.clone(), replica_size.clone());
+
+        //when
+        cleaner.enforce_size().await;
+
+        //then
+        assert_eq!(segments.read().await.find_first(10), vec![100, 600]);
+    }
+
     #[fluvio_future::test]
     async fn test_enforce_ttl() {
         //given
@@ -221,6 +285,121 @@ mod tests {
         assert_eq!(read.occupied_memory(), replica_size.get());
     }
 
+    #[fluvio_future::test]
+    async fn test_enforce_compaction() {
+        //given
+        let rep_dir = temp_dir().join("cleaner-enforce-compaction");
+        ensure_new_dir(&rep_dir).expect("new");
+        let mut config = default_option();
+        config.base_dir = rep_dir;
+        config.segment_max_bytes = 1000;
+        config.compact = true;
+        config.delete_retention_seconds = 3600;
+        let option = config.clone().shared();
+
+        let segments = SharedSegments::from(SegmentList::new());
+        segments
+            .add_segment(
+                create_keyed_segment(
+                    option.clone(),
+                    0,
+                    vec![
+                        vec![("a", Some("1")), ("b", Some("1"))],
+                        vec![("a", Some("2")), ("b", None)],
+                    ],
+                )
+                .await
+                .expect("create"),
+            )
+            .await;
+        segments
+            .add_segment(
+                create_keyed_segment(option, 4, vec![vec![("a", Some("3")), ("c", Some(""))]])
+                    .await
+                    .expect("create"),
+            )
+            .await;
+        let replica_size = Arc::new(ReplicaSize::default());
+        let cleaner = test_cleaner(config.clone(), segments.clone(), replica_size.clone());
+
+        //when
+        cleaner.enforce_compaction().await;
+
+        //then
+        assert_eq!(
+            read_records(&segments).await,
+            vec![
+                (3, "b".to_owned(), "".to_owned()),
+                (4, "a".to_owned(), "3".to_owned()),
+                (5, "c".to_owned(), "".to_owned())
+            ]
+        );
+        assert_eq!(segments.read().await.occupied_memory(), replica_size.get());
+        // reading from removed offset starts from next kept record
+        let slice = segments
+            .find_slice(1, None)
+            .await
+            .expect("slice")
+            .expect("some slice");
+        assert_eq!(slice.position(), 0);
+
+        //when tombstone is older than delete retention
+        config.delete_retention_seconds = 0;
+        let cleaner = test_cleaner(config, segments.clone(), replica_size.clone());
+        cleaner.enforce_compaction().await;
+
+        //then record with empty value is not tombstone
+        assert_eq!(segments.read().await.find_first(10), vec![4]);
+        assert_eq!(
+            read_records(&segments).await,
+            vec![
+                (4, "a".to_owned(), "3".to_owned()),
+                (5, "c".to_owned(), "".to_owned())
+            ]
+        );
+    }
+
+    async fn create_keyed_segment(
+        option: Arc<SharedReplicaConfig>,
+        start: Offset,
+        batches: Vec<Vec<(&str, Option<&str>)>>,
+    ) -> Result<ReadSegment> {
+        let mut mut_segment = MutableSegment::create(start, option).await?;
+        for records in batches {
+            let mut batch = Batch::from(
+                records
+                    .into_iter()
+                    .map(|(key, value)| match value {
+                        Some(value) => Record::new_key_value(key, value),
+                        None => Record::tombstone(key),
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            mut_segment.append_batch(&mut batch).await?;
+        }
+        mut_segment.convert_to_segment().await
+    }
+
+    async fn read_records(segments: &SharedSegments) -> Vec<(Offset, String, String)> {
+        let mut records = vec![];
+        for (_, path) in segments.read().await.log_paths() {
+            let mut stream = FileBatchStream::<RawRecords>::open(path)
+                .await
+                .expect("open");
+            while let Some(batch_pos) = stream.try_next().await.expect("next") {
+                let batch = batch_pos.inner();
+                for record in batch.memory_records().expect("records") {
+                    records.push((
+                        batch.get_base_offset() + record.offset_delta(),
+                        String::from_utf8_lossy(record.key().expect("key").as_ref()).to_string(),
+                        String::from_utf8_lossy(record.value().as_ref()).to_string(),
+                    ));
+                }
+            }
+        }
+        records
+    }
+
     async fn shared_segments(
         path: &str,
         count: usize,
@@ -287,6 +466,7 @@ mod tests {
             segments,
             replica_size,
             end_event: StickyEvent::shared(),
+            last_compaction: Mutex::new(None),
         }
     }
 }
@@ -0,0 +1,449 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use tracing::{debug, info, instrument};
+
+use fluvio_future::fs::{create_dir_all, metadata, remove_dir_all, remove_file, rename, File};
+use fluvio_protocol::record::{Batch, ControlRecordType, Offset, RawRecords, Record, Size};
+use fluvio_types::Timestamp;
+
+use crate::batch::FileBatchStream;
+use crate::config::{ReplicaConfig, SharedReplicaConfig};
+use crate::index::EXTENSION as INDEX_EXTENSION;
+use crate::records::MESSAGE_LOG_EXTENSION;
+use crate::segment::MutableSegment;
+use crate::segments::SharedSegments;
+use crate::time_index::EXTENSION as TIME_INDEX_EXTENSION;
+use crate::util::{generate_file_name, log_path_get_offset};
+
+/// directory inside replica where compacted segments are written before replacing originals
+const COMPACT_DIR: &str = "compact";
+
+/// marker of compacted segment whose files are complete and must replace original segment
+const SWAP_EXTENSION: &str = "swap";
+
+const SEGMENT_EXTENSIONS: [&str; 3] =
+    [MESSAGE_LOG_EXTENSION, INDEX_EXTENSION, TIME_INDEX_EXTENSION];
+
+/// Result of compaction of closed segments
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct CompactionResult {
+    pub rewritten: usize,
+    pub removed: usize,
+}
+
+/// Compacts closed segments so only latest record for each key is kept.
+///
+/// Records without key and control batches are always kept. Tombstone record, marked by tombstone header
+/// since record value can't be null, removes previous records of the key and is kept until its batch
+/// is older than `delete_retention`, so consumers have time to see the deletion. Records of aborted
+/// or open transactions never replace earlier records of their keys. Offsets of kept records are
+/// not changed, compacted segments contain gaps instead.
+pub(crate) struct Compactor {
+    segments: Arc<SharedSegments>,
+    option: Arc<SharedReplicaConfig>,
+    delete_retention: Duration,
+}
+
+impl Compactor {
+    pub(crate) fn new(
+        segments: Arc<SharedSegments>,
+        option: Arc<SharedReplicaConfig>,
+        delete_retention: Duration,
+    ) -> Self {
+        Self {
+            segments,
+            option,
+            delete_retention,
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub(crate) async fn compact(&self) -> Result<CompactionResult> {
+        let logs = self.segments.read().await.log_paths();
+        let latest = latest_offsets(&logs).await?;
+        debug!(segments = logs.len(), keys = latest.len(), "compacting");
+
+        // finish replacing segments if it was interrupted in previous compaction
+        recover_compaction(&self.option.base_dir).await?;
+        let compact_dir = self.option.base_dir.join(COMPACT_DIR);
+        create_dir_all(&compact_dir).await?;
+        let compact_option = ReplicaConfig {
+            base_dir: compact_dir.clone(),
+            index_max_bytes: self.option.index_max_bytes.get(),
+            index_max_interval_bytes: self.option.index_max_interval_bytes.get(),
+            segment_max_bytes: Size::MAX,
+            ..Default::default()
+        }
+        .shared();
+
+        let mut result = CompactionResult::default();
+        for (base_offset, path) in logs {
+            if !self.rewrite_segment(&path, &latest, None).await?.0 {
+                continue;
+            }
+            let mut compacted = MutableSegment::create(base_offset, compact_option.clone()).await?;
+            let (_, written) = self
+                .rewrite_segment(&path, &latest, Some(&mut compacted))
+                .await?;
+            compacted.roll_over().await?;
+            compacted.flush().await?;
+            drop(compacted);
+
+            if written == 0 {
+                info!(base_offset, "all records compacted, removing segment");
+                self.segments.remove_segments(&[base_offset]).await;
+                result.removed += 1;
+            } else {
+                prepare_swap(&compact_dir, base_offset).await?;
+                self.segments
+                    .replace_segment(base_offset, &compact_dir, self.option.clone())
+                    .await?;
+                result.rewritten += 1;
+            }
+        }
+
+        remove_dir_all(&compact_dir).await?;
+        Ok(result)
+    }
+
+    /// write batches of log at `path` to `compacted` without records which are removed by compaction.
+    /// If `compacted` is None, only check if there is anything to remove.
+    /// returns if any record was removed and number of batches written
+    async fn rewrite_segment(
+        &self,
+        path: &Path,
+        latest: &HashMap<Vec<u8>, Offset>,
+        mut compacted: Option<&mut MutableSegment>,
+    ) -> Result<(bool, usize)> {
+        let now = to_millis(SystemTime::now());
+        let delete_retention = self.delete_retention.as_millis() as Timestamp;
+        // batches without timestamp are treated as old as segment
+        let modified = to_millis(metadata(path).await?.modified()?);
+
+        let mut changed = false;
+        let mut written = 0;
+        let mut stream = FileBatchStream::<RawRecords>::open(path).await?;
+        while let Some(batch_pos) = stream.try_next().await? {
+            let raw_batch = batch_pos.inner();
+            let base_offset = raw_batch.get_base_offset();
+            // control batches end transactions, they are never compacted
+            if raw_batch.is_control() {
+                if let Some(compacted) = compacted.as_deref_mut() {
+                    compacted.append_batch_at_offset(&raw_batch).await?;
+                    written += 1;
+                }
+                continue;
+            }
+            let timestamp = match raw_batch.get_header().max_time_stamp {
+                timestamp if timestamp < 0 => modified,
+                timestamp => timestamp,
+            };
+            let tombstone_expired = now - timestamp >= delete_retention;
+
+            let mut records = raw_batch.memory_records()?;
+            let count = records.len();
+            records.retain(|record| {
+                is_retained(
+                    record,
+                    base_offset + record.offset_delta(),
+                    latest,
+                    tombstone_expired,
+                )
+            });
+
+            if records.len() != count {
+                changed = true;
+            }
+            let Some(compacted) = compacted.as_deref_mut() else {
+                if changed {
+                    break;
+                }
+                continue;
+            };
+            if records.len() == count {
+                compacted.append_batch_at_offset(&raw_batch).await?;
+                written += 1;
+                continue;
+            }
+            if records.is_empty() {
+                debug!(base_offset, "all records in batch removed");
+                continue;
+            }
+            let mut batch: Batch = Batch::default();
+            batch.base_offset = base_offset;
+            batch.header = raw_batch.header.clone();
+            batch.schema_id = raw_batch.schema_id.clone();
+            *batch.mut_records() = records;
+            let batch: Batch<RawRecords> = batch.try_into()?;
+            compacted.append_batch_at_offset(&batch).await?;
+            written += 1;
+        }
+        Ok((changed, written))
+    }
+}
+
+/// Completes replacing of segments interrupted by crash or error, so replica is loaded with either
+/// original or compacted files of each segment. Compacted segments without swap marker are discarded.
+pub(crate) async fn recover_compaction(base_dir: &Path) -> Result<()> {
+    let compact_dir = base_dir.join(COMPACT_DIR);
+    if metadata(&compact_dir).await.is_err() {
+        return Ok(());
+    }
+    for entry in compact_dir.read_dir()? {
+        let path = entry?.path();
+        if path.extension().and_then(|extension| extension.to_str()) != Some(SWAP_EXTENSION) {
+            continue;
+        }
+        let base_offset = log_path_get_offset(&path)?;
+        info!(base_offset, "completing replace of compacted segment");
+        swap_segment_files(&compact_dir, base_dir, base_offset).await?;
+    }
+    remove_dir_all(&compact_dir).await?;
+    Ok(())
+}
+
+/// Makes files of compacted segment durable and writes swap marker.
+/// Every segment file is created, so file missing in `dir` after marker is written was already moved.
+async fn prepare_swap(dir: &Path, base_offset: Offset) -> Result<()> {
+    for extension in SEGMENT_EXTENSIONS {
+        let path = generate_file_name(dir, base_offset, extension);
+        let file = if metadata(&path).await.is_ok() {
+            File::open(&path).await?
+        } else {
+            File::create(&path).await?
+        };
+        file.sync_all().await?;
+    }
+    let marker = File::create(generate_file_name(dir, base_offset, SWAP_EXTENSION)).await?;
+    marker.sync_all().await?;
+    sync_dir(dir)?;
+    Ok(())
+}
+
+/// move files of compacted segment from `dir` over original ones and remove swap marker
+pub(crate) async fn swap_segment_files(
+    dir: &Path,
+    base_dir: &Path,
+    base_offset: Offset,
+) -> Result<()> {
+    for extension in SEGMENT_EXTENSIONS {
+        let from = generate_file_name(dir, base_offset, extension);
+        if metadata(&from).await.is_ok() {
+            rename(&from, generate_file_name(base_dir, base_offset, extension)).await?;
+        }
+    }
+    sync_dir(base_dir)?;
+    remove_file(generate_file_name(dir, base_offset, SWAP_EXTENSION)).await?;
+    Ok(())
+}
+
+fn sync_dir(dir: &Path) -> Result<()> {
+    std::fs::File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+/// find offset of latest record for each key.
+/// Records of transactions are counted only once their commit is found,
+/// so records of aborted or still open transactions never remove committed ones.
+async fn latest_offsets(logs: &[(Offset, PathBuf)]) -> Result<HashMap<Vec<u8>, Offset>> {
+    let mut latest = HashMap::new();
+    // keys written by open transaction of each producer
+    let mut transactions: HashMap<i64, Vec<(Vec<u8>, Offset)>> = HashMap::new();
+    for (_, path) in logs {
+        let mut stream = FileBatchStream::<RawRecords>::open(path).await?;
+        while let Some(batch_pos) = stream.try_next().await? {
+            let batch = batch_pos.inner();
+            let producer_id = batch.get_header().producer_id;
+            if batch.is_control() {
+                let keys = transactions.remove(&producer_id).unwrap_or_default();
+                let batch: Batch = batch.try_into()?;
+                if batch.control_type() == Some(ControlRecordType::Commit) {
+                    for (key, offset) in keys {
+                        insert_latest(&mut latest, key, offset);
+                    }
+                }
+                continue;
+            }
+            let base_offset = batch.get_base_offset();
+            let transactional = batch.get_header().is_transactional();
+            for record in batch.memory_records()? {
+                let Some(key) = record.key() else {
+                    continue;
+                };
+                let key = key.as_ref().to_vec();
+                let offset = base_offset + record.offset_delta();
+                if transactional {
+                    transactions
+                        .entry(producer_id)
+                        .or_default()
+                        .push((key, offset));
+                } else {
+                    insert_latest(&mut latest, key, offset);
+                }
+            }
+        }
+    }
+    Ok(latest)
+}
+
+fn insert_latest(latest: &mut HashMap<Vec<u8>, Offset>, key: Vec<u8>, offset: Offset) {
+    let latest_offset = latest.entry(key).or_insert(offset);
+    *latest_offset = (*latest_offset).max(offset);
+}
+
+fn is_retained(
+    record: &Record,
+    offset: Offset,
+    latest: &HashMap<Vec<u8>, Offset>,
+    tombstone_expired: bool,
+) -> bool {
+    let Some(key) = record.key() else {
+        return true;
+    };
+    if latest
+        .get(key.as_ref())
+        .is_some_and(|latest_offset| *latest_offset > offset)
+    {
+        return false;
+    }
+    !(record.is_tombstone() && tombstone_expired)
+}
+
+fn to_millis(time: SystemTime) -> Timestamp {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as Timestamp)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+    use std::sync::Arc;
+
+    use flv_util::fixture::ensure_new_dir;
+    use fluvio_protocol::record::{Batch, ControlRecordType, Offset, Record};
+
+    use crate::config::{ReplicaConfig, SharedReplicaConfig};
+
+    use super::*;
+
+    #[fluvio_future::test]
+    async fn test_recover_interrupted_swap() {
+        //given
+        let base_dir = temp_dir().join("compaction-recover-swap");
+        ensure_new_dir(&base_dir).expect("new");
+        let compact_dir = base_dir.join(COMPACT_DIR);
+        create_dir_all(&compact_dir).await.expect("create");
+        let option = test_option(&base_dir);
+        let compact_option = test_option(&compact_dir);
+
+        write_segment(option.clone(), 0, &["1", "2", "3"]).await;
+        let compacted_len = write_segment(compact_option.clone(), 0, &["3"]).await;
+        prepare_swap(&compact_dir, 0).await.expect("prepare");
+        // interrupted after index was moved
+        rename(
+            generate_file_name(&compact_dir, 0, INDEX_EXTENSION),
+            generate_file_name(&base_dir, 0, INDEX_EXTENSION),
+        )
+        .await
+        .expect("rename");
+
+        // compacted segment without swap marker was not complete
+        let original_len = write_segment(option, 20, &["1", "2"]).await;
+        write_segment(compact_option, 20, &["2"]).await;
+
+        //when
+        recover_compaction(&base_dir).await.expect("recover");
+
+        //then
+        assert_eq!(log_len(&base_dir, 0).await, compacted_len);
+        assert_eq!(log_len(&base_dir, 20).await, original_len);
+        assert!(metadata(&compact_dir).await.is_err());
+    }
+
+    #[fluvio_future::test]
+    async fn test_latest_offsets_skip_aborted_transactions() {
+        //given
+        let base_dir = temp_dir().join("compaction-latest-offsets-aborted");
+        ensure_new_dir(&base_dir).expect("new");
+        let option = test_option(&base_dir);
+        let mut segment = MutableSegment::create(0, option).await.expect("create");
+
+        let transactional = |producer_id: i64, value: &str| {
+            let mut batch = Batch::from(vec![Record::new_key_value("key", value)]);
+            batch.get_mut_header().producer_id = producer_id;
+            batch.get_mut_header().set_transactional();
+            batch
+        };
+        let batches = vec![
+            // offset 0
+            Batch::from(vec![Record::new_key_value("key", "committed")]),
+            // offset 1, aborted
+            transactional(1, "aborted"),
+            Batch::control(1, 0, ControlRecordType::Abort),
+            // offset 3, committed
+            transactional(2, "committed"),
+            Batch::control(2, 0, ControlRecordType::Commit),
+            // offset 5, never ended
+            transactional(3, "open"),
+        ];
+        for mut batch in batches {
+            segment.append_batch(&mut batch).await.expect("append");
+        }
+        segment.roll_over().await.expect("roll over");
+        segment.flush().await.expect("flush");
+        let path = generate_file_name(&base_dir, 0, MESSAGE_LOG_EXTENSION);
+
+        //when
+        let latest = latest_offsets(&[(0, path)]).await.expect("latest");
+
+        //then
+        assert_eq!(latest.len(), 1);
+        assert_eq!(latest.get(b"key".as_slice()), Some(&3));
+    }
+
+    fn test_option(base_dir: &Path) -> Arc<SharedReplicaConfig> {
+        ReplicaConfig {
+            base_dir: base_dir.to_owned(),
+            ..Default::default()
+        }
+        .shared()
+    }
+
+    async fn write_segment(
+        option: Arc<SharedReplicaConfig>,
+        base_offset: Offset,
+        values: &[&str],
+    ) -> u64 {
+        let base_dir = option.base_dir.clone();
+        let mut segment = MutableSegment::create(base_offset, option)
+            .await
+            .expect("create");
+        let mut batch = Batch::from(
+            values
+                .iter()
+                .map(|value| Record::new_key_value("key", *value))
+                .collect::<Vec<_>>(),
+        );
+        segment.append_batch(&mut batch).await.expect("append");
+        segment.roll_over().await.expect("roll over");
+        segment.flush().await.expect("flush");
+        log_len(&base_dir, base_offset).await
+    }
+
+    async fn log_len(base_dir: &Path, base_offset: Offset) -> u64 {
+        metadata(generate_file_name(
+            base_dir,
+            base_offset,
+            MESSAGE_LOG_EXTENSION,
+        ))
+        .await
+        .expect("metadata")
+        .len()
+    }
+}
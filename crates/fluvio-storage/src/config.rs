@@ -11,7 +11,8 @@ use serde::Deserialize;
 use fluvio_controlplane_metadata::topic::CleanupPolicy;
 use fluvio_types::defaults::{
     SPU_LOG_INDEX_MAX_BYTES, SPU_LOG_BASE_DIR, STORAGE_FLUSH_WRITE_COUNT, STORAGE_FLUSH_IDLE_MSEC,
-    STORAGE_MAX_BATCH_SIZE, STORAGE_RETENTION_SECONDS, SPU_PARTITION_MAX_BYTES,
+    STORAGE_MAX_BATCH_SIZE, STORAGE_RETENTION_SECONDS, STORAGE_DELETE_RETENTION_SECONDS,
+    SPU_PARTITION_MAX_BYTES,
 };
 use fluvio_types::defaults::SPU_LOG_INDEX_MAX_INTERVAL_BYTES;
 use fluvio_types::defaults::SPU_LOG_SEGMENT_MAX_BYTES;
@@ -50,6 +51,12 @@ pub struct ReplicaConfig {
     #[builder(default = "default_retention_seconds()")]
     #[serde(default = "default_retention_seconds")]
     pub retention_seconds: Size,
+    #[builder(default)]
+    #[serde(default)]
+    pub compact: bool, // if true, keep only latest record per key instead of expiring segments
+    #[builder(default = "default_delete_retention_seconds()")]
+    #[serde(default = "default_delete_retention_seconds")]
+    pub delete_retention_seconds: Size,
     #[builder(default = "default_max_partition_size()")]
     #[serde(default = "default_max_partition_size")]
     pub max_partition_size: Size64,
@@ -68,6 +75,10 @@ impl ReplicaStorageConfig for ReplicaConfig {
                 CleanupPolicy::Segment(segment) => {
                     self.retention_seconds = segment.retention_secs();
                 }
+                CleanupPolicy::Compact(compact) => {
+                    self.compact = true;
+                    self.delete_retention_seconds = compact.retention_secs();
+                }
             }
         }
 
@@ -124,6 +135,10 @@ const fn default_retention_seconds() -> Size {
     STORAGE_RETENTION_SECONDS
 }
 
+const fn default_delete_retention_seconds() -> Size {
+    STORAGE_DELETE_RETENTION_SECONDS
+}
+
 const fn default_max_partition_size() -> Size64 {
     SPU_PARTITION_MAX_BYTES
 }
@@ -150,6 +165,8 @@ impl Default for ReplicaConfig {
             flush_idle_msec: default_flush_idle_msec(),
             max_batch_size: default_max_batch_size(),
             retention_seconds: default_retention_seconds(),
+            compact: false,
+            delete_retention_seconds: default_delete_retention_seconds(),
             max_partition_size: default_max_partition_size(),
             update_hw: true,
         }
@@ -227,6 +244,8 @@ pub struct SharedReplicaConfig {
     pub max_batch_size: SharedConfigU32Value,
     pub update_hw: bool, // if true, enable hw update
     pub retention_seconds: SharedConfigU32Value,
+    pub compact: bool,
+    pub delete_retention_seconds: SharedConfigU32Value,
     pub max_partition_size: SharedConfigU64Value,
 }
 
@@ -242,6 +261,8 @@ impl From<ReplicaConfig> for SharedReplicaConfig {
             max_batch_size: SharedConfigU32Value::new(config.max_batch_size),
             update_hw: config.update_hw,
             retention_seconds: SharedConfigU32Value::new(config.retention_seconds),
+            compact: config.compact,
+            delete_retention_seconds: SharedConfigU32Value::new(config.delete_retention_seconds),
             max_partition_size: SharedConfigU64Value::new(config.max_partition_size),
         }
     }
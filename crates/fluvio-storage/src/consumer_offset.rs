@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+use std::io::Error as IoError;
+use std::path::Path;
+
+use tracing::debug;
+
+use fluvio_protocol::record::Offset;
+
+use crate::checkpoint::CheckPointStore;
+
+const CONSUMER_OFFSETS_FILE_NAME: &str = "consumer_offsets.chk";
+
+/// Offsets committed by consumer groups for a replica, checkpointed in the replica directory.
+#[derive(Debug)]
+pub struct ConsumerOffsetStore {
+    offsets: CheckPointStore<Offset>,
+}
+
+impl ConsumerOffsetStore {
+    /// load existing offsets from replica directory or create empty store
+    pub async fn create(base_dir: &Path) -> Result<Self, IoError> {
+        let offsets = CheckPointStore::create(base_dir, CONSUMER_OFFSETS_FILE_NAME).await?;
+        Ok(Self { offsets })
+    }
+
+    /// committed offset for consumer group
+    pub fn get(&self, consumer_group: &str) -> Option<Offset> {
+        self.offsets.get(consumer_group).copied()
+    }
+
+    /// all consumer groups with committed offsets
+    pub fn groups(&self) -> impl Iterator<Item = (&String, &Offset)> {
+        self.offsets.iter()
+    }
+
+    /// replace all offsets, used by follower to apply offsets replicated from leader.
+    /// return true if offsets were changed
+    pub async fn replace(&mut self, offsets: BTreeMap<String, Offset>) -> Result<bool, IoError> {
+        debug!(groups = offsets.len(), "replace consumer offsets");
+        self.offsets.replace(offsets).await
+    }
+
+    /// store offset for consumer group and persist it
+    pub async fn commit(&mut self, consumer_group: &str, offset: Offset) -> Result<(), IoError> {
+        debug!(consumer_group, offset, "commit consumer offset");
+        self.offsets.insert(consumer_group, offset).await
+    }
+
+    /// remove committed offset for consumer group.
+    /// return true if there was an offset
+    pub async fn remove(&mut self, consumer_group: &str) -> Result<bool, IoError> {
+        debug!(consumer_group, "remove consumer offset");
+        self.offsets.remove(consumer_group).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::collections::BTreeMap;
+    use std::env::temp_dir;
+
+    use flv_util::fixture::ensure_clean_dir;
+    use fluvio_protocol::record::Offset;
+
+    use super::ConsumerOffsetStore;
+
+    #[fluvio_future::test]
+    async fn test_consumer_offset_store() {
+        let test_dir = temp_dir().join("consumer_offset_store_test");
+        ensure_clean_dir(&test_dir);
+
+        let mut store = ConsumerOffsetStore::create(&test_dir)
+            .await
+            .expect("create");
+        assert_eq!(store.get("group1"), None);
+
+        store.commit("group1", 10).await.expect("commit");
+        store.commit("group2", 5).await.expect("commit");
+        store.commit("group1", 20).await.expect("commit");
+        drop(store);
+
+        let mut store = ConsumerOffsetStore::create(&test_dir)
+            .await
+            .expect("reload");
+        assert_eq!(store.get("group1"), Some(20));
+        assert_eq!(store.get("group2"), Some(5));
+
+        assert!(store.remove("group2").await.expect("remove"));
+        assert!(!store.remove("group2").await.expect("remove"));
+        drop(store);
+
+        let store = ConsumerOffsetStore::create(&test_dir)
+            .await
+            .expect("reload");
+        assert_eq!(store.get("group2"), None);
+        assert_eq!(store.groups().count(), 1);
+    }
+
+    #[fluvio_future::test]
+    async fn test_consumer_offset_store_replace() {
+        let test_dir = temp_dir().join("consumer_offset_store_replace_test");
+        ensure_clean_dir(&test_dir);
+
+        let mut store = ConsumerOffsetStore::create(&test_dir)
+            .await
+            .expect("create");
+        store.commit("group1", 10).await.expect("commit");
+
+        let offsets: BTreeMap<String, Offset> = [("group2".to_owned(), 3)].into_iter().collect();
+        assert!(store.replace(offsets.clone()).await.expect("replace"));
+        assert!(!store.replace(offsets).await.expect("replace"));
+        drop(store);
+
+        let store = ConsumerOffsetStore::create(&test_dir)
+            .await
+            .expect("reload");
+        assert_eq!(store.get("group1"), None);
+        assert_eq!(store.get("group2"), Some(3));
+    }
+}
@@ -0,0 +1,89 @@
+use std::io::Error as IoError;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_lock::Mutex;
+use tracing::debug;
+
+use fluvio_protocol::record::Offset;
+
+use crate::checkpoint::CheckPointStore;
+
+const DEAD_LETTER_OFFSETS_FILE_NAME: &str = "dead_letter_offsets.chk";
+
+pub type SharedDeadLetterOffsetStore = Arc<Mutex<DeadLetterOffsetStore>>;
+
+/// Next offsets of a replica whose failed records are not yet written to dead-letter topics,
+/// keyed by SmartModule chain and dead-letter topic, checkpointed in the replica directory.
+#[derive(Debug)]
+pub struct DeadLetterOffsetStore {
+    offsets: CheckPointStore<Offset>,
+}
+
+impl DeadLetterOffsetStore {
+    /// load existing offsets from replica directory or create empty store
+    pub async fn create(base_dir: &Path) -> Result<Self, IoError> {
+        let offsets = CheckPointStore::create(base_dir, DEAD_LETTER_OFFSETS_FILE_NAME).await?;
+        Ok(Self { offsets })
+    }
+
+    pub fn shared(self) -> SharedDeadLetterOffsetStore {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// next offset not yet written to dead-letter topic by the chain
+    pub fn get(&self, chain_id: &str, topic: &str) -> Option<Offset> {
+        self.offsets.get(&offset_key(chain_id, topic)).copied()
+    }
+
+    /// store next offset not yet written to dead-letter topic by the chain and persist it
+    pub async fn commit(
+        &mut self,
+        chain_id: &str,
+        topic: &str,
+        offset: Offset,
+    ) -> Result<(), IoError> {
+        debug!(chain_id, topic, offset, "commit dead-letter offset");
+        self.offsets
+            .insert(&offset_key(chain_id, topic), offset)
+            .await
+    }
+}
+
+/// topic names can't contain '/', so the key is unambiguous
+fn offset_key(chain_id: &str, topic: &str) -> String {
+    format!("{chain_id}/{topic}")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+
+    use flv_util::fixture::ensure_clean_dir;
+
+    use super::DeadLetterOffsetStore;
+
+    #[fluvio_future::test]
+    async fn test_dead_letter_offset_store() {
+        let test_dir = temp_dir().join("dead_letter_offset_store_test");
+        ensure_clean_dir(&test_dir);
+
+        let mut store = DeadLetterOffsetStore::create(&test_dir)
+            .await
+            .expect("create");
+        assert_eq!(store.get("chain1", "errors"), None);
+
+        store.commit("chain1", "errors", 10).await.expect("commit");
+        store.commit("chain2", "errors", 5).await.expect("commit");
+        store.commit("chain1", "errors", 20).await.expect("commit");
+        drop(store);
+
+        let store = DeadLetterOffsetStore::create(&test_dir)
+            .await
+            .expect("reload");
+        assert_eq!(store.get("chain1", "errors"), Some(20));
+        assert_eq!(store.get("chain2", "errors"), Some(5));
+        assert_eq!(store.get("chain2", "other"), None);
+    }
+}
@@ -60,44 +60,11 @@ impl Iterator for FileBatchIterator {
             return None;
         }
 
-        let offset = self.offset;
-
-        // ugly hack for armv7 pread offset = i32
-        // needed for gnu but not zig musl
-        #[cfg(all(target_pointer_width = "32", target_env = "gnu"))]
-        let offset: i32 = offset.try_into().unwrap();
-
-        let mut header = vec![0u8; BATCH_FILE_HEADER_SIZE];
-        let bytes_read = match pread(
-            unsafe { BorrowedFd::borrow_raw(self.fd) },
-            &mut header,
-            offset,
-        )
-        .map_err(|err| IoError::new(ErrorKind::Other, format!("pread error {err}")))
-        {
-            Ok(bytes) => bytes,
+        let batch = match read_batch_header(self.fd, self.offset) {
+            Ok(batch) => batch,
             Err(err) => return Some(Err(err)),
         };
 
-        if bytes_read < header.len() {
-            return Some(Err(IoError::new(
-                ErrorKind::UnexpectedEof,
-                format!(
-                    "not eough for batch header {} out of {}",
-                    bytes_read,
-                    header.len()
-                ),
-            )));
-        }
-
-        let mut batch: Batch = Batch::default();
-        if let Err(err) = batch.decode_from_file_buf(&mut Cursor::new(header), 0) {
-            return Some(Err(IoError::new(
-                ErrorKind::Other,
-                format!("decodinge batch header error {err}"),
-            )));
-        }
-
         let remainder = batch.batch_len as usize - BATCH_HEADER_SIZE;
 
         let mut raw_records = vec![0u8; remainder];
@@ -160,6 +127,80 @@ impl Iterator for FileBatchIterator {
     }
 }
 
+/// read and decode header of batch at `offset` of file
+fn read_batch_header(fd: RawFd, offset: Offset) -> Result<Batch, IoError> {
+    // ugly hack for armv7 pread offset = i32
+    // needed for gnu but not zig musl
+    #[cfg(all(target_pointer_width = "32", target_env = "gnu"))]
+    let offset: i32 = offset.try_into().unwrap();
+
+    let mut header = vec![0u8; BATCH_FILE_HEADER_SIZE];
+    let bytes_read = pread(unsafe { BorrowedFd::borrow_raw(fd) }, &mut header, offset)
+        .map_err(|err| IoError::new(ErrorKind::Other, format!("pread error {err}")))?;
+
+    if bytes_read < header.len() {
+        return Err(IoError::new(
+            ErrorKind::UnexpectedEof,
+            format!(
+                "not eough for batch header {} out of {}",
+                bytes_read,
+                header.len()
+            ),
+        ));
+    }
+
+    let mut batch: Batch = Batch::default();
+    batch
+        .decode_from_file_buf(&mut Cursor::new(header), 0)
+        .map_err(|err| {
+            IoError::new(
+                ErrorKind::Other,
+                format!("decodinge batch header error {err}"),
+            )
+        })?;
+    Ok(batch)
+}
+
+/// Iterator that returns headers of batches from file without reading their records
+pub struct FileBatchHeaderIterator {
+    fd: RawFd,
+    offset: Offset,
+    end: i64,
+}
+
+impl FileBatchHeaderIterator {
+    pub fn from_raw_slice(slice: AsyncFileSlice) -> Self {
+        use std::os::unix::io::AsRawFd;
+        let offset = slice.position() as i64;
+        Self {
+            fd: slice.as_raw_fd(),
+            offset,
+            end: offset + slice.len() as i64,
+        }
+    }
+}
+
+impl Iterator for FileBatchHeaderIterator {
+    type Item = Result<Batch, IoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.end {
+            return None;
+        }
+        match read_batch_header(self.fd, self.offset) {
+            Ok(batch) => {
+                self.offset += BATCH_FILE_HEADER_SIZE as i64 + batch.batch_len as i64
+                    - BATCH_HEADER_SIZE as i64;
+                Some(Ok(batch))
+            }
+            Err(err) => {
+                self.offset = self.end;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
 /// Iterator that converts an iterator over file batches to an iterator over record items.
 /// RecordItem is a record with resolved offset and timestamp.
 pub struct FileRecordIterator<T: Iterator<Item = Result<FileBatch, IoError>>> {
@@ -311,6 +352,62 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_file_batch_header_iterator() -> anyhow::Result<()> {
+        //given
+        let base_dir = temp_dir().join("test_file_batch_header_iterator");
+        let mut replica = run_block_on(FileReplica::create_or_load_with_storage(
+            format!(
+                "test_file_batch_header_iterator_{}",
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)?
+                    .as_millis()
+            ),
+            Default::default(),
+            Default::default(),
+            ReplicaConfigBuilder::default().base_dir(base_dir).build(),
+            Arc::new(StorageConfigBuilder::default().build()?),
+        ))?;
+
+        let mut batch1 = Batch::default();
+        batch1.add_record(Record::new("1"));
+        batch1.add_record(Record::new("2"));
+
+        let mut batch2 = Batch::default();
+        batch2.base_offset = 2;
+        let mut record = Record::new("3");
+        record.add_header(("trace-id", "abc"));
+        batch2.add_record(record);
+        batch2.header.set_record_headers();
+
+        let mut records = RecordSet {
+            batches: vec![batch1, batch2],
+        };
+        run_block_on(replica.write_recordset(&mut records, false))?;
+
+        //when
+        let slice = run_block_on(replica.read_partition_slice(
+            0,
+            u32::MAX,
+            fluvio_spu_schema::Isolation::ReadUncommitted,
+        ))?;
+        let file_slice = slice
+            .file_slice
+            .ok_or_else(|| anyhow::anyhow!("expected file slice"))?;
+
+        let batches = FileBatchHeaderIterator::from_raw_slice(file_slice)
+            .collect::<Result<Vec<Batch>, std::io::Error>>()?;
+
+        //then
+        assert_eq!(batches.len(), 2);
+        assert_eq!(batches[0].base_offset, 0);
+        assert!(!batches[0].header.has_record_headers());
+        assert_eq!(batches[1].base_offset, 2);
+        assert!(batches[1].header.has_record_headers());
+
+        Ok(())
+    }
+
     #[test]
     fn test_file_record_iterator_error_propagated() -> anyhow::Result<()> {
         //given
@@ -1,11 +1,17 @@
 pub mod batch;
 pub mod batch_header;
 mod checkpoint;
+mod consumer_offset;
+mod smartmodule_state;
+mod smartmodule_key_value;
+mod dead_letter_offset;
+mod producer_snapshot;
 mod error;
 pub mod records;
 mod index;
 mod mut_records;
 mod mut_index;
+mod time_index;
 mod segments;
 mod replica;
 pub mod segment;
@@ -19,16 +25,29 @@ pub mod iterators;
 #[cfg(feature = "fixture")]
 pub mod fixture;
 mod cleaner;
+mod compaction;
 
 pub use crate::error::StorageError;
 pub use crate::records::FileRecordsSlice;
 pub use crate::index::LogIndex;
 pub use crate::index::OffsetPosition;
 pub use crate::replica::FileReplica;
+pub use crate::consumer_offset::ConsumerOffsetStore;
+pub use crate::smartmodule_state::{
+    SharedSmartModuleStateStore, SmartModuleAccumulator, SmartModuleState, SmartModuleStateStore,
+};
+pub use crate::smartmodule_key_value::{
+    SharedSmartModuleKeyValueStore, SmartModuleKeyValueChanges, SmartModuleKeyValueStore,
+    SmartModuleKeyValues,
+};
+pub use crate::dead_letter_offset::{DeadLetterOffsetStore, SharedDeadLetterOffsetStore};
+pub use crate::producer_snapshot::{ProducerSnapshot, ProducerSnapshotStore};
 
 pub use inner::*;
 mod inner {
 
+    use std::collections::BTreeMap;
+
     use async_trait::async_trait;
     use anyhow::Result;
 
@@ -39,6 +58,7 @@ mod inner {
     use fluvio_protocol::record::RecordSet;
     use fluvio_future::file_slice::AsyncFileSlice;
     use fluvio_controlplane::replica::Replica;
+    use fluvio_types::Timestamp;
 
     #[derive(Debug, Clone, Eq, PartialEq)]
     pub struct OffsetInfo {
@@ -111,6 +131,10 @@ mod inner {
     }
 
     use crate::StorageError;
+    use crate::{
+        ProducerSnapshot, SharedDeadLetterOffsetStore, SharedSmartModuleStateStore,
+        SharedSmartModuleKeyValueStore,
+    };
 
     /// Contain information about slice of Replica
     #[derive(Debug, Default)]
@@ -154,8 +178,22 @@ mod inner {
             isolation: Isolation,
         ) -> Result<ReplicaSlice, ErrorCode>;
 
+        /// read committed partition slice which ends before `max_offset`
+        /// return hw and leo
+        async fn read_partition_slice_until(
+            &self,
+            offset: Offset,
+            max_offset: Offset,
+            max_len: u32,
+        ) -> Result<ReplicaSlice, ErrorCode>;
+
         fn get_partition_size(&self) -> Size64;
 
+        /// first offset of batch with timestamp at or after `timestamp`,
+        /// log end offset if all records are older
+        async fn find_offset_by_timestamp(&self, timestamp: Timestamp)
+            -> Result<Offset, ErrorCode>;
+
         /// write record set
         async fn write_recordset<R: BatchRecords>(
             &mut self,
@@ -165,6 +203,55 @@ mod inner {
 
         async fn update_high_watermark(&mut self, offset: Offset) -> Result<bool, StorageError>;
 
+        /// discard records at or after offset, batch containing the offset is discarded too.
+        /// high watermark is lowered to new log end offset if it is beyond it
+        async fn truncate(&mut self, offset: Offset) -> Result<()>;
+
+        /// offset committed by consumer group
+        fn get_consumer_offset(&self, consumer_group: &str) -> Option<Offset>;
+
+        /// persist offset committed by consumer group
+        async fn commit_consumer_offset(
+            &mut self,
+            consumer_group: &str,
+            offset: Offset,
+        ) -> Result<(), StorageError>;
+
+        /// remove offset committed by consumer group
+        /// return true if there was an offset
+        async fn remove_consumer_offset(
+            &mut self,
+            consumer_group: &str,
+        ) -> Result<bool, StorageError>;
+
+        /// offsets committed by all consumer groups
+        fn consumer_offsets(&self) -> BTreeMap<String, Offset>;
+
+        /// replace offsets of all consumer groups with offsets replicated from leader
+        async fn sync_consumer_offsets(
+            &mut self,
+            offsets: BTreeMap<String, Offset>,
+        ) -> Result<(), StorageError>;
+
+        /// checkpoints of SmartModule states, shared so they are persisted without locking the replica
+        fn smartmodule_states(&self) -> SharedSmartModuleStateStore;
+
+        /// key-value states of SmartModules, shared so they are persisted without locking the replica
+        fn smartmodule_key_values(&self) -> SharedSmartModuleKeyValueStore;
+
+        /// offsets of the replica not yet written to dead-letter topics,
+        /// shared so failed records are written and committed without locking the replica
+        fn dead_letter_offsets(&self) -> SharedDeadLetterOffsetStore;
+
+        /// latest snapshot of producer and transaction states
+        fn get_producer_snapshot(&self) -> Option<ProducerSnapshot>;
+
+        /// persist snapshot of producer and transaction states
+        async fn commit_producer_snapshot(
+            &mut self,
+            snapshot: ProducerSnapshot,
+        ) -> Result<(), StorageError>;
+
         /// permanently remove
         async fn remove(&self) -> Result<(), StorageError>;
     }
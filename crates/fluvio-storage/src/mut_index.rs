@@ -228,6 +228,25 @@ impl MutLogIndex {
         Ok(())
     }
 
+    /// remove entries at or after file position, used when log is truncated
+    pub(crate) async fn truncate(&mut self, position: Size) -> Result<(), IoError> {
+        let slot = (0..self.first_empty_slot)
+            .find(|slot| u32::from_be(self[*slot as usize].position()) >= position)
+            .unwrap_or(self.first_empty_slot);
+        debug!(
+            position,
+            removed = self.first_empty_slot - slot,
+            "truncating index"
+        );
+        for empty_slot in slot..self.first_empty_slot {
+            self[empty_slot as usize] = (0, 0);
+        }
+        self.first_empty_slot = slot;
+        self.accumulated_batch_len = 0;
+        self.last_offset_delta = 0;
+        self.mmap.flush_ft().await
+    }
+
     /// entries capacity in the index
     fn entries(&self) -> Size {
         (self.capacity() / INDEX_ENTRY_SIZE) as u32
@@ -0,0 +1,94 @@
+use std::io::Error as IoError;
+use std::path::Path;
+
+use tracing::debug;
+
+use fluvio_protocol::{Encoder, Decoder};
+use fluvio_protocol::record::Offset;
+
+use crate::checkpoint::CheckPointStore;
+
+const PRODUCER_SNAPSHOT_FILE_NAME: &str = "producer_snapshot.chk";
+const SNAPSHOT_KEY: &str = "leader";
+
+/// Producer and transaction states of leader, encoded by SPU, as of an offset of the log
+#[derive(Debug, Default, Clone, Encoder, Decoder, PartialEq, Eq)]
+pub struct ProducerSnapshot {
+    /// states include all batches below this offset
+    pub offset: Offset,
+    pub state: Vec<u8>,
+}
+
+/// Latest producer snapshot of a replica checkpointed in the replica directory,
+/// so new leader rebuilds states only from batches written after it.
+#[derive(Debug)]
+pub struct ProducerSnapshotStore {
+    snapshots: CheckPointStore<ProducerSnapshot>,
+}
+
+impl ProducerSnapshotStore {
+    /// load existing snapshot from replica directory or create empty store
+    pub async fn create(base_dir: &Path) -> Result<Self, IoError> {
+        let snapshots = CheckPointStore::create(base_dir, PRODUCER_SNAPSHOT_FILE_NAME).await?;
+        Ok(Self { snapshots })
+    }
+
+    /// latest snapshot
+    pub fn get(&self) -> Option<&ProducerSnapshot> {
+        self.snapshots.get(SNAPSHOT_KEY)
+    }
+
+    /// replace snapshot and persist it
+    pub async fn commit(&mut self, snapshot: ProducerSnapshot) -> Result<(), IoError> {
+        debug!(offset = snapshot.offset, "commit producer snapshot");
+        self.snapshots.insert(SNAPSHOT_KEY, snapshot).await
+    }
+
+    /// discard snapshot which includes batches beyond `leo`, used after log is truncated.
+    /// return true if snapshot was discarded
+    pub async fn discard_after(&mut self, leo: Offset) -> Result<bool, IoError> {
+        if self.get().is_some_and(|snapshot| snapshot.offset > leo) {
+            debug!(leo, "discard producer snapshot");
+            self.snapshots.remove(SNAPSHOT_KEY).await
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+
+    use flv_util::fixture::ensure_clean_dir;
+
+    use super::{ProducerSnapshot, ProducerSnapshotStore};
+
+    #[fluvio_future::test]
+    async fn test_producer_snapshot_store() {
+        let test_dir = temp_dir().join("producer_snapshot_store_test");
+        ensure_clean_dir(&test_dir);
+
+        let mut store = ProducerSnapshotStore::create(&test_dir)
+            .await
+            .expect("create");
+        assert_eq!(store.get(), None);
+
+        let snapshot = ProducerSnapshot {
+            offset: 20,
+            state: b"state".to_vec(),
+        };
+        store.commit(snapshot.clone()).await.expect("commit");
+        drop(store);
+
+        let mut store = ProducerSnapshotStore::create(&test_dir)
+            .await
+            .expect("reload");
+        assert_eq!(store.get(), Some(&snapshot));
+
+        assert!(!store.discard_after(20).await.expect("discard"));
+        assert!(store.discard_after(19).await.expect("discard"));
+        assert_eq!(store.get(), None);
+    }
+}
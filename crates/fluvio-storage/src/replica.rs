@@ -1,6 +1,7 @@
 use std::cmp::min;
 use std::mem;
 use std::sync::Arc;
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 
 use tracing::{debug, trace, warn, instrument, info};
@@ -15,14 +16,21 @@ use fluvio_spu_schema::Isolation;
 use fluvio_protocol::record::{Offset, ReplicaKey, Size, Size64};
 use fluvio_protocol::record::{Batch, BatchRecords};
 use fluvio_protocol::record::RecordSet;
+use fluvio_types::Timestamp;
 
 use crate::{OffsetInfo, checkpoint::CheckPoint};
+use crate::consumer_offset::ConsumerOffsetStore;
+use crate::smartmodule_state::{SharedSmartModuleStateStore, SmartModuleStateStore};
+use crate::smartmodule_key_value::{SharedSmartModuleKeyValueStore, SmartModuleKeyValueStore};
+use crate::dead_letter_offset::{DeadLetterOffsetStore, SharedDeadLetterOffsetStore};
+use crate::producer_snapshot::{ProducerSnapshot, ProducerSnapshotStore};
 use crate::segments::SharedSegments;
 use crate::segment::MutableSegment;
 use crate::config::{ReplicaConfig, SharedReplicaConfig, StorageConfig};
 use crate::ReplicaSlice;
 use crate::{StorageError, ReplicaStorage};
 use crate::cleaner::Cleaner;
+use crate::compaction::recover_compaction;
 
 /// Replica is public abstraction for commit log which are distributed.
 /// Internally it is stored as list of segments.  Each segment contains finite sets of record batches.
@@ -37,6 +45,11 @@ pub struct FileReplica {
     active_segment: MutableSegment,
     prev_segments: Arc<SharedSegments>,
     commit_checkpoint: CheckPoint<Offset>,
+    consumer_offsets: ConsumerOffsetStore,
+    smartmodule_states: SharedSmartModuleStateStore,
+    smartmodule_key_values: SharedSmartModuleKeyValueStore,
+    dead_letter_offsets: SharedDeadLetterOffsetStore,
+    producer_snapshot: ProducerSnapshotStore,
     cleaner: Arc<Cleaner>,
     size: Arc<ReplicaSize>,
 }
@@ -110,6 +123,17 @@ impl ReplicaStorage for FileReplica {
         }
     }
 
+    #[instrument(skip(self, offset, max_offset, max_len))]
+    async fn read_partition_slice_until(
+        &self,
+        offset: Offset,
+        max_offset: Offset,
+        max_len: u32,
+    ) -> Result<ReplicaSlice, ErrorCode> {
+        self.read_records(offset, Some(max_offset.min(self.get_hw())), max_len)
+            .await
+    }
+
     /// return the size in bytes (includes index size and log size)
     #[instrument(skip(self))]
     fn get_partition_size(&self) -> Size64 {
@@ -123,6 +147,23 @@ impl ReplicaStorage for FileReplica {
         total_prev_segments_len + active_len
     }
 
+    #[instrument(skip(self))]
+    async fn find_offset_by_timestamp(&self, timestamp: Timestamp) -> Result<Offset, ErrorCode> {
+        if let Some(offset) = self
+            .prev_segments
+            .find_offset_by_timestamp(timestamp)
+            .await?
+        {
+            return Ok(offset);
+        }
+        let offset = self
+            .active_segment
+            .find_offset_by_timestamp(timestamp)
+            .await
+            .map_err(|err| ErrorCode::Other(format!("timestamp lookup error: {err:#?}")))?;
+        Ok(offset.unwrap_or_else(|| self.get_leo()))
+    }
+
     /// write records to this replica
     /// if update_highwatermark is set, set high watermark is end
     //  this is used when LRS = 1
@@ -175,6 +216,105 @@ impl ReplicaStorage for FileReplica {
         }
     }
 
+    #[instrument(skip(self))]
+    async fn truncate(&mut self, offset: Offset) -> Result<()> {
+        if offset >= self.get_leo() {
+            return Ok(());
+        }
+
+        if offset >= self.active_segment.get_base_offset() {
+            self.active_segment.truncate(offset).await?;
+        } else {
+            // segment containing offset becomes active segment again
+            let segment = match self.prev_segments.split_off(offset).await? {
+                Some(segment) => {
+                    let base_offset = segment.get_base_offset();
+                    drop(segment);
+                    let mut segment =
+                        MutableSegment::open_for_write(base_offset, self.option.clone()).await?;
+                    segment.validate_and_repair().await?;
+                    segment.truncate(offset).await?;
+                    segment
+                }
+                None => MutableSegment::create(offset, self.option.clone()).await?,
+            };
+            let old_segment = mem::replace(&mut self.active_segment, segment);
+            old_segment.as_segment().await?.remove().await?;
+            self.size
+                .store_prev(self.prev_segments.read().await.occupied_memory());
+        }
+        self.size
+            .store_active(self.active_segment.occupied_memory());
+
+        let leo = self.get_leo();
+        info!(offset, leo, "replica truncated");
+        if self.get_hw() > leo {
+            self.commit_checkpoint.write(leo).await?;
+        }
+        self.producer_snapshot.discard_after(leo).await?;
+        Ok(())
+    }
+
+    fn get_consumer_offset(&self, consumer_group: &str) -> Option<Offset> {
+        self.consumer_offsets.get(consumer_group)
+    }
+
+    #[instrument(skip(self))]
+    async fn commit_consumer_offset(
+        &mut self,
+        consumer_group: &str,
+        offset: Offset,
+    ) -> Result<(), StorageError> {
+        self.consumer_offsets.commit(consumer_group, offset).await?;
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    async fn remove_consumer_offset(&mut self, consumer_group: &str) -> Result<bool, StorageError> {
+        Ok(self.consumer_offsets.remove(consumer_group).await?)
+    }
+
+    fn consumer_offsets(&self) -> BTreeMap<String, Offset> {
+        self.consumer_offsets
+            .groups()
+            .map(|(group, offset)| (group.clone(), *offset))
+            .collect()
+    }
+
+    #[instrument(skip(self, offsets))]
+    async fn sync_consumer_offsets(
+        &mut self,
+        offsets: BTreeMap<String, Offset>,
+    ) -> Result<(), StorageError> {
+        self.consumer_offsets.replace(offsets).await?;
+        Ok(())
+    }
+
+    fn smartmodule_states(&self) -> SharedSmartModuleStateStore {
+        self.smartmodule_states.clone()
+    }
+
+    fn smartmodule_key_values(&self) -> SharedSmartModuleKeyValueStore {
+        self.smartmodule_key_values.clone()
+    }
+
+    fn dead_letter_offsets(&self) -> SharedDeadLetterOffsetStore {
+        self.dead_letter_offsets.clone()
+    }
+
+    fn get_producer_snapshot(&self) -> Option<ProducerSnapshot> {
+        self.producer_snapshot.get().cloned()
+    }
+
+    #[instrument(skip(self, snapshot))]
+    async fn commit_producer_snapshot(
+        &mut self,
+        snapshot: ProducerSnapshot,
+    ) -> Result<(), StorageError> {
+        self.producer_snapshot.commit(snapshot).await?;
+        Ok(())
+    }
+
     #[instrument(skip(self))]
     async fn remove(&self) -> Result<(), StorageError> {
         remove_dir_all(&self.option.base_dir)
@@ -227,6 +367,7 @@ impl FileReplica {
 
         let shared_config: Arc<SharedReplicaConfig> = Arc::new(rep_option.into());
 
+        recover_compaction(&shared_config.base_dir).await?;
         let (segments, last_offset_res) = SharedSegments::from_dir(shared_config.clone()).await?;
 
         let active_segment = if let Some(last_offset) = last_offset_res {
@@ -260,6 +401,18 @@ impl FileReplica {
             commit_checkpoint.write(leo).await?;
         }
 
+        let consumer_offsets = ConsumerOffsetStore::create(&shared_config.base_dir).await?;
+        let smartmodule_states = SmartModuleStateStore::create(&shared_config.base_dir)
+            .await?
+            .shared();
+        let smartmodule_key_values = SmartModuleKeyValueStore::create(&shared_config.base_dir)
+            .await?
+            .shared();
+        let dead_letter_offsets = DeadLetterOffsetStore::create(&shared_config.base_dir)
+            .await?
+            .shared();
+        let producer_snapshot = ProducerSnapshotStore::create(&shared_config.base_dir).await?;
+
         let size = Arc::new(ReplicaSize::default());
         let cleaner = Cleaner::start_new(
             storage_config,
@@ -275,6 +428,11 @@ impl FileReplica {
             active_segment,
             prev_segments: segments,
             commit_checkpoint,
+            consumer_offsets,
+            smartmodule_states,
+            smartmodule_key_values,
+            dead_letter_offsets,
+            producer_snapshot,
             cleaner,
             size,
         })
@@ -356,9 +514,22 @@ impl FileReplica {
             }
         } else {
             debug!(start_offset, active_base_offset, "not in active sgments");
-            self.prev_segments
+            match self
+                .prev_segments
                 .find_slice(start_offset, max_offset)
                 .await?
+            {
+                Some(slice) => slice,
+                // rest of the closed segments was removed by compaction, continue from active segment
+                None => match self
+                    .active_segment
+                    .records_slice(active_base_offset, max_offset)
+                    .await?
+                {
+                    Some(slice) => slice,
+                    None => return Ok(slice),
+                },
+            }
         };
 
         let limited_slice = AsyncFileSlice::new(
@@ -939,6 +1110,72 @@ mod tests {
         assert_eq!(segment.get_end_offset(), 4);
     }
 
+    #[fluvio_future::test]
+    async fn test_replica_truncate() {
+        let mut option = base_option("test_truncate");
+        // enough for 2 batch (2 records per batch)
+        option.segment_max_bytes = 160;
+        option.index_max_interval_bytes = 50;
+
+        let producer = BatchProducer::builder()
+            .records(2u16)
+            .record_generator(Arc::new(|_, _| Record::new("1")))
+            .build()
+            .expect("batch");
+
+        let mut replica = create_replica("test", 0, option.clone()).await;
+        for _ in 0..5 {
+            replica
+                .write_batch(&mut producer.generate_batch())
+                .await
+                .expect("write");
+        }
+        replica.update_high_watermark(10).await.expect("hw");
+        assert_eq!(replica.get_leo(), 10);
+        assert_eq!(replica.prev_segments.read().await.len(), 2);
+
+        // batch containing offset in active segment is discarded
+        replica.truncate(9).await.expect("truncate");
+        assert_eq!(replica.get_leo(), 8);
+        assert_eq!(replica.get_hw(), 8);
+        assert_eq!(replica.prev_segments.read().await.len(), 2);
+
+        // segment containing offset becomes active segment
+        replica.truncate(5).await.expect("truncate");
+        assert_eq!(replica.get_leo(), 4);
+        assert_eq!(replica.get_hw(), 4);
+        assert_eq!(replica.get_log_start_offset(), 0);
+        assert_eq!(replica.prev_segments.read().await.len(), 1);
+        assert_eq!(replica.active_segment.get_base_offset(), 4);
+
+        replica
+            .write_batch(&mut producer.generate_batch())
+            .await
+            .expect("write");
+        assert_eq!(replica.get_leo(), 6);
+        let slice = replica
+            .read_partition_slice(4, 1000, Isolation::ReadUncommitted)
+            .await
+            .expect("read");
+        assert!(slice.file_slice.is_some());
+        drop(replica);
+
+        // truncated replica is reloaded
+        let mut replica = create_replica("test", 0, option).await;
+        assert_eq!(replica.get_leo(), 6);
+        assert_eq!(replica.get_hw(), 4);
+        assert_eq!(replica.prev_segments.read().await.len(), 1);
+
+        // offset beyond leo is ignored
+        replica.truncate(10).await.expect("truncate");
+        assert_eq!(replica.get_leo(), 6);
+
+        replica.truncate(2).await.expect("truncate");
+        assert_eq!(replica.get_leo(), 2);
+        assert_eq!(replica.get_hw(), 2);
+        assert_eq!(replica.prev_segments.read().await.len(), 0);
+    }
+
     /// test replica with purging segments
     #[fluvio_future::test]
     async fn test_replica_segment_purge() {
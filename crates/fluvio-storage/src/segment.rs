@@ -12,6 +12,7 @@ use fluvio_future::file_slice::AsyncFileSlice;
 use fluvio_protocol::record::{Batch, BatchRecords};
 use fluvio_protocol::record::{Offset, Size, Size64};
 use fluvio_protocol::link::ErrorCode;
+use fluvio_types::Timestamp;
 
 use crate::batch_header::{BatchHeaderStream, FileEmptyRecords};
 use crate::mut_index::MutLogIndex;
@@ -25,25 +26,27 @@ use crate::StorageError;
 use crate::batch::{FileBatchStream};
 use crate::index::OffsetPosition;
 use crate::validator::LogValidationError;
+use crate::time_index::{TimeIndex, MutTimeIndex};
 
-pub type MutableSegment = Segment<MutLogIndex, MutFileRecords>;
-pub type ReadSegment = Segment<LogIndex, FileRecordsSlice>;
+pub type MutableSegment = Segment<MutLogIndex, MutFileRecords, MutTimeIndex>;
+pub type ReadSegment = Segment<LogIndex, FileRecordsSlice, TimeIndex>;
 
 pub(crate) struct BatchPosition {
     batch: Batch<FileEmptyRecords>,
     pos: Size,
 }
 
-/// Segment contains both message log and index
-pub struct Segment<I, L> {
+/// Segment contains message log, offset index and time index
+pub struct Segment<I, L, T> {
     option: Arc<SharedReplicaConfig>,
     msg_log: L,
     index: I,
+    time_index: T,
     base_offset: Offset,
     end_offset: Offset,
 }
 
-impl<I, L> fmt::Debug for Segment<I, L> {
+impl<I, L, T> fmt::Debug for Segment<I, L, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
@@ -54,7 +57,7 @@ impl<I, L> fmt::Debug for Segment<I, L> {
     }
 }
 
-impl<I, L> Segment<I, L> {
+impl<I, L, T> Segment<I, L, T> {
     /// end offset, this always starts as baseoffset which indicates empty records
     pub fn get_end_offset(&self) -> Offset {
         self.end_offset
@@ -71,11 +74,12 @@ impl<I, L> Segment<I, L> {
     }
 }
 
-impl<I, L> Segment<I, L>
+impl<I, L, T> Segment<I, L, T>
 where
     I: Index,
     I: Deref<Target = [(Size, Size)]>,
     L: FileRecords,
+    T: AsRef<TimeIndex>,
 {
     #[allow(dead_code)]
     pub fn get_index(&self) -> &I {
@@ -226,12 +230,41 @@ where
         Ok(None)
     }
 
+    /// find first batch with timestamp at or after `timestamp` and return its base offset.
+    /// Time index is used to skip batches which are known to be older.
+    #[instrument(skip(self))]
+    pub(crate) async fn find_offset_by_timestamp(
+        &self,
+        timestamp: Timestamp,
+    ) -> Result<Option<Offset>> {
+        let position = self.time_index.as_ref().find_position(timestamp);
+        debug!(file_position = position, "found time index position");
+
+        let mut header_stream = self.open_batch_header_stream(position).await?;
+        while let Some(batch_pos) = header_stream.try_next().await? {
+            let batch = batch_pos.inner();
+            if batch.get_base_offset() >= self.end_offset {
+                break;
+            }
+            if batch.get_header().max_time_stamp >= timestamp {
+                debug!(
+                    base_offset = batch.get_base_offset(),
+                    max_time_stamp = batch.get_header().max_time_stamp,
+                    "found batch with timestamp"
+                );
+                return Ok(Some(batch.get_base_offset()));
+            }
+        }
+
+        Ok(None)
+    }
+
     pub(crate) fn occupied_memory(&self) -> Size64 {
         self.index.len() + self.msg_log.len()
     }
 }
 
-impl Segment<LogIndex, FileRecordsSlice> {
+impl Segment<LogIndex, FileRecordsSlice, TimeIndex> {
     /// open read only segments if base and end offset are known
     pub async fn open_for_read(
         base_offset: Offset,
@@ -243,10 +276,12 @@ impl Segment<LogIndex, FileRecordsSlice> {
         let base_offset = msg_log.get_base_offset();
         debug!(base_offset, end_offset, "offset from msg log");
         let index = LogIndex::open_from_offset(base_offset, option.clone()).await?;
+        let time_index = TimeIndex::open(&option.base_dir, base_offset).await?;
 
         Ok(Segment {
             msg_log,
             index,
+            time_index,
             option,
             base_offset,
             end_offset,
@@ -262,6 +297,7 @@ impl Segment<LogIndex, FileRecordsSlice> {
         let msg_log = FileRecordsSlice::open(base_offset, option.clone()).await?;
         let index = LogIndex::open_from_offset(base_offset, option.clone()).await?;
         let base_offset = msg_log.get_base_offset();
+        let time_index = TimeIndex::open(&option.base_dir, base_offset).await?;
         match msg_log.validate(&index).await {
             Ok(val) => {
                 // check if validation is successful
@@ -274,6 +310,7 @@ impl Segment<LogIndex, FileRecordsSlice> {
                 Ok(Segment {
                     msg_log,
                     index,
+                    time_index,
                     option,
                     base_offset,
                     end_offset: val.leo(),
@@ -295,12 +332,17 @@ impl Segment<LogIndex, FileRecordsSlice> {
         let index_file_path = self.index.clean();
         info!(index_path = %index_file_path.display(),"removing index file");
         remove_file(&index_file_path).await?;
+        let time_index_path = self.time_index.get_path();
+        if fluvio_future::fs::metadata(time_index_path).await.is_ok() {
+            info!(time_index_path = %time_index_path.display(), "removing time index file");
+            remove_file(time_index_path).await?;
+        }
         Ok(())
     }
 }
 
 /// Implementation for Active segment
-impl Segment<MutLogIndex, MutFileRecords> {
+impl Segment<MutLogIndex, MutFileRecords, MutTimeIndex> {
     // create segment on base directory
 
     pub async fn create(
@@ -311,11 +353,18 @@ impl Segment<MutLogIndex, MutFileRecords> {
         let msg_log = MutFileRecords::create(base_offset, option.clone()).await?;
 
         let index = MutLogIndex::create(base_offset, option.clone()).await?;
+        let time_index = MutTimeIndex::open(
+            &option.base_dir,
+            base_offset,
+            option.index_max_interval_bytes.get_consistent(),
+        )
+        .await?;
 
         Ok(MutableSegment {
             option: option.to_owned(),
             msg_log,
             index,
+            time_index,
             base_offset,
             end_offset: base_offset,
         })
@@ -333,12 +382,19 @@ impl Segment<MutLogIndex, MutFileRecords> {
         let msg_log = MutFileRecords::create(base_offset, option.clone()).await?;
         let base_offset = msg_log.get_base_offset();
         let index = MutLogIndex::open(base_offset, option.clone()).await?;
+        let time_index = MutTimeIndex::open(
+            &option.base_dir,
+            base_offset,
+            option.index_max_interval_bytes.get_consistent(),
+        )
+        .await?;
 
         let base_offset = msg_log.get_base_offset();
         Ok(MutableSegment {
             option,
             msg_log,
             index,
+            time_index,
             base_offset,
             end_offset: base_offset,
         })
@@ -376,9 +432,30 @@ impl Segment<MutLogIndex, MutFileRecords> {
             }
         }
         self.end_offset = leo;
+        self.repair_time_index().await?;
         Ok(self.end_offset)
     }
 
+    /// drop time index entries beyond valid log and
+    /// recover max timestamp from batches written after last entry
+    async fn repair_time_index(&mut self) -> Result<()> {
+        let log_len = self.msg_log.get_pos();
+        self.time_index.truncate(log_len).await?;
+
+        let mut header_stream = self
+            .open_batch_header_stream(self.time_index.last_position())
+            .await?;
+        while let Some(batch_pos) = header_stream.try_next().await? {
+            if batch_pos.get_pos() >= log_len {
+                break;
+            }
+            let batch = batch_pos.inner();
+            self.time_index
+                .observe_batch(batch.batch_len as Size, batch.get_header().max_time_stamp);
+        }
+        Ok(())
+    }
+
     // shrink index
     #[cfg(test)]
     async fn shrink_index(&mut self) -> Result<(), IoError> {
@@ -407,7 +484,7 @@ impl Segment<MutLogIndex, MutFileRecords> {
     /// This will perform following steps:
     /// 1. Set batch's base offset to current end offset
     /// 2. Append batch to msg log
-    /// 3. Write batch location to index
+    /// 3. Write batch location to index and time index
     #[instrument(skip(batch))]
     pub async fn append_batch<R: BatchRecords>(&mut self, batch: &mut Batch<R>) -> Result<bool> {
         // adjust base offset and offset delta
@@ -417,11 +494,32 @@ impl Segment<MutLogIndex, MutFileRecords> {
         }
 
         batch.set_base_offset(self.end_offset);
+        self.write_batch(batch).await
+    }
+
+    /// Append batch keeping its base offset, offsets between current end offset and batch
+    /// are left as gap. This is used to rewrite segment when it is compacted.
+    pub(crate) async fn append_batch_at_offset<R: BatchRecords>(
+        &mut self,
+        batch: &Batch<R>,
+    ) -> Result<bool> {
+        if batch.records_len() == 0 {
+            return Err(StorageError::EmptyBatch.into());
+        }
+        if batch.get_base_offset() < self.end_offset {
+            return Err(LogValidationError::InvalidBaseOffsetMinimum {
+                invalid_batch_offset: batch.get_base_offset(),
+            }
+            .into());
+        }
+        self.write_batch(batch).await
+    }
 
+    async fn write_batch<R: BatchRecords>(&mut self, batch: &Batch<R>) -> Result<bool> {
         let next_end_offset = batch.get_last_offset();
 
         // relative offset of the batch to segment
-        let relative_offset_in_segment = (self.end_offset - self.base_offset) as i32;
+        let relative_offset_in_segment = (batch.get_base_offset() - self.base_offset) as i32;
         let start_file_pos = self.msg_log.get_pos();
         debug!(
             base_offset = batch.get_base_offset(),
@@ -445,6 +543,14 @@ impl Segment<MutLogIndex, MutFileRecords> {
                     batch_len as u32,
                 )
                 .await?;
+            self.time_index
+                .write_batch(
+                    relative_offset_in_segment as u32,
+                    start_file_pos,
+                    batch_len as u32,
+                    batch.get_header().max_time_stamp,
+                )
+                .await?;
             self.end_offset = next_end_offset + 1;
             debug!(end_offset = self.end_offset, "updated leo");
             Ok(true)
@@ -453,6 +559,26 @@ impl Segment<MutLogIndex, MutFileRecords> {
         }
     }
 
+    /// discard batches with records at or after offset, batch containing the offset is discarded too.
+    /// return new end offset
+    #[instrument(skip(self))]
+    pub(crate) async fn truncate(&mut self, offset: Offset) -> Result<Offset> {
+        let Some(batch_pos) = self.find_offset_position(offset).await? else {
+            return Ok(self.end_offset);
+        };
+        info!(
+            offset,
+            position = batch_pos.pos,
+            base_offset = self.base_offset,
+            "truncating segment"
+        );
+        self.msg_log.set_len(batch_pos.pos).await?;
+        self.index.truncate(batch_pos.pos).await?;
+        self.repair_time_index().await?;
+        self.end_offset = batch_pos.batch.get_base_offset().min(offset);
+        Ok(self.end_offset)
+    }
+
     #[allow(unused)]
     pub async fn flush(&mut self) -> Result<(), StorageError> {
         self.msg_log.flush().await.map_err(|err| err.into())
@@ -680,4 +806,48 @@ mod tests {
             )
             .expect("failed to get records");
     }
+
+    #[fluvio_future::test]
+    async fn test_segment_find_offset_by_timestamp() {
+        let test_dir = temp_dir().join("seg-find-timestamp");
+        ensure_new_dir(&test_dir).expect("new");
+
+        let option = default_option(test_dir.clone(), 0).shared();
+
+        let mut seg_sink = MutableSegment::create(40, option).await.expect("create");
+        for timestamp in [1000, 3000, 2000, 4000] {
+            let mut batch = create_batch();
+            batch.get_mut_header().first_timestamp = timestamp;
+            batch.get_mut_header().max_time_stamp = timestamp;
+            seg_sink.append_batch(&mut batch).await.expect("write");
+        }
+        assert_eq!(seg_sink.get_end_offset(), 48);
+
+        assert_eq!(
+            seg_sink.find_offset_by_timestamp(500).await.expect("find"),
+            Some(40)
+        );
+        assert_eq!(
+            seg_sink.find_offset_by_timestamp(2500).await.expect("find"),
+            Some(42)
+        );
+        assert_eq!(
+            seg_sink.find_offset_by_timestamp(3500).await.expect("find"),
+            Some(46)
+        );
+        assert_eq!(
+            seg_sink.find_offset_by_timestamp(5000).await.expect("find"),
+            None
+        );
+
+        let segment = seg_sink.convert_to_segment().await.expect("convert");
+        assert_eq!(
+            segment.find_offset_by_timestamp(2500).await.expect("find"),
+            Some(42)
+        );
+        assert_eq!(
+            segment.find_offset_by_timestamp(3500).await.expect("find"),
+            Some(46)
+        );
+    }
 }
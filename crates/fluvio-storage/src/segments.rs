@@ -1,22 +1,26 @@
 use std::collections::BTreeMap;
-use std::ops::Bound::Included;
+use std::ops::Bound::{Included, Excluded, Unbounded};
 use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::sync::atomic::AtomicI64;
 use std::time::Duration;
 
 use async_lock::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tracing::{debug, trace, error, instrument, info};
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 
 use fluvio_protocol::link::ErrorCode;
 use fluvio_protocol::record::Size64;
 use fluvio_protocol::record::Offset;
 use fluvio_future::file_slice::AsyncFileSlice;
+use fluvio_types::Timestamp;
 
 use crate::config::SharedReplicaConfig;
 use crate::segment::ReadSegment;
 use crate::util::log_path_get_offset;
+use crate::records::FileRecords;
+use crate::compaction::swap_segment_files;
 
 const MEM_ORDER: std::sync::atomic::Ordering = std::sync::atomic::Ordering::SeqCst;
 
@@ -118,22 +122,33 @@ impl SharedSegments {
         }
     }
 
-    /// find slice in the segments
-    /// if not found, return OutOfRange error
+    /// find slice in the segments.
+    /// If records at start offset were removed by compaction, slice starts from next record.
+    /// Returns None if there are no records at or after start offset in the segments.
     pub async fn find_slice(
         &self,
         start_offset: Offset,
         max_offset: Option<Offset>,
-    ) -> Result<AsyncFileSlice, ErrorCode> {
+    ) -> Result<Option<AsyncFileSlice>, ErrorCode> {
         let reader = self.read().await;
-        if let Some((_offset, segment)) = reader.find_segment(start_offset) {
+        if let Some((offset, segment)) = reader.find_segment(start_offset) {
             if let Some(slice) = segment.records_slice(start_offset, max_offset).await? {
-                Ok(slice)
-            } else {
-                Err(ErrorCode::Other(format!(
-                    "slice not found in start_offset: {start_offset}, segment: {segment:#?} "
-                )))
+                return Ok(Some(slice));
             }
+            debug!(start_offset, "no records in segment, trying next segments");
+            for segment in reader
+                .segments
+                .range((Excluded(*offset), Unbounded))
+                .map(|(_, s)| s)
+            {
+                if let Some(slice) = segment
+                    .records_slice(segment.get_base_offset(), max_offset)
+                    .await?
+                {
+                    return Ok(Some(slice));
+                }
+            }
+            Ok(None)
         } else {
             Err(ErrorCode::Other(format!(
                 "Segment not found for start_offset: {start_offset}"
@@ -141,6 +156,83 @@ impl SharedSegments {
         }
     }
 
+    /// find first offset with timestamp at or after `timestamp` in the segments
+    pub async fn find_offset_by_timestamp(
+        &self,
+        timestamp: Timestamp,
+    ) -> Result<Option<Offset>, ErrorCode> {
+        let reader = self.read().await;
+        for segment in reader.segments.values() {
+            if let Some(offset) = segment
+                .find_offset_by_timestamp(timestamp)
+                .await
+                .map_err(|err| ErrorCode::Other(format!("timestamp lookup error: {err:#?}")))?
+            {
+                return Ok(Some(offset));
+            }
+        }
+        Ok(None)
+    }
+
+    /// replace segment with rewritten one from `dir`, used by compaction.
+    /// Files of new segment are moved over old ones while holding write lock so readers never miss the segment.
+    /// Swap marker of new segment must be written before, so interrupted replace is completed when replica is loaded.
+    #[instrument(skip(self, option))]
+    pub(crate) async fn replace_segment(
+        &self,
+        base_offset: Offset,
+        dir: &Path,
+        option: Arc<SharedReplicaConfig>,
+    ) -> Result<()> {
+        let mut write = self.write().await;
+        let Some((old_segment, _)) = write.remove_segment(&base_offset) else {
+            return Err(anyhow!("segment: {base_offset} not found"));
+        };
+        let end_offset = old_segment.get_end_offset();
+        drop(old_segment);
+        swap_segment_files(dir, &option.base_dir, base_offset).await?;
+        let segment = ReadSegment::open_for_read(base_offset, end_offset, option).await?;
+        let min_offset = write.add_segment(segment);
+        self.min_offset.store(min_offset, MEM_ORDER);
+        info!(base_offset, end_offset, "segment replaced");
+        Ok(())
+    }
+
+    /// remove segments with records at or after offset, used when log is truncated.
+    /// Segment which contains the offset is taken out of the list, its files are kept.
+    #[instrument(skip(self))]
+    pub(crate) async fn split_off(&self, offset: Offset) -> Result<Option<ReadSegment>> {
+        let mut write = self.write().await;
+        let after: Vec<Offset> = write
+            .segments
+            .range(offset..)
+            .map(|(base, _)| *base)
+            .collect();
+        let mut removed = vec![];
+        for base_offset in after {
+            if let Some((segment, _)) = write.remove_segment(&base_offset) {
+                removed.push(segment);
+            }
+        }
+        let containing = write
+            .segments
+            .range(..offset)
+            .next_back()
+            .filter(|(_, segment)| segment.get_end_offset() > offset)
+            .map(|(base_offset, _)| *base_offset);
+        let containing = containing
+            .and_then(|base_offset| write.remove_segment(&base_offset))
+            .map(|(segment, _)| segment);
+        self.min_offset.store(write.min_offset, MEM_ORDER);
+        drop(write);
+
+        for segment in removed {
+            info!(base_offset = segment.get_base_offset(), "removing segment");
+            segment.remove().await?;
+        }
+        Ok(containing)
+    }
+
     #[instrument(skip(self))]
     async fn remove_segment(&self, base_offset: &Offset) {
         let mut write = self.write().await;
@@ -264,6 +356,21 @@ impl SegmentList {
     pub(crate) fn find_first(&self, count: usize) -> Vec<Offset> {
         self.segments.keys().take(count).copied().collect()
     }
+
+    /// base offset and log file path of each segment
+    pub(crate) fn log_paths(&self) -> Vec<(Offset, PathBuf)> {
+        self.segments
+            .iter()
+            .map(|(base_offset, segment)| {
+                (*base_offset, segment.get_msg_log().get_path().to_owned())
+            })
+            .collect()
+    }
+
+    /// end offset of last segment
+    pub(crate) fn max_offset(&self) -> Offset {
+        self.max_offset
+    }
 }
 
 #[cfg(test)]
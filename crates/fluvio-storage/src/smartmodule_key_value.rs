@@ -0,0 +1,149 @@
+use std::collections::BTreeMap;
+use std::io::Error as IoError;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_lock::Mutex;
+use tracing::debug;
+
+use fluvio_protocol::{Encoder, Decoder};
+
+use crate::checkpoint::CheckPointStore;
+
+const SMARTMODULE_KEY_VALUE_FILE_NAME: &str = "smartmodule_key_value.chk";
+
+/// Key-value state of a SmartModule
+pub type SmartModuleKeyValues = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Keys changed by a SmartModule with their values, `None` if key was deleted
+pub type SmartModuleKeyValueChanges = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+pub type SharedSmartModuleKeyValueStore = Arc<Mutex<SmartModuleKeyValueStore>>;
+
+/// Entry key of SmartModule state
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Encoder, Decoder)]
+struct KeyValueKey {
+    smartmodule: String,
+    key: Vec<u8>,
+}
+
+/// Key-value states of SmartModules processing a replica, keyed by SmartModule name
+/// and checkpointed in the replica directory. Each entry is persisted separately,
+/// so only keys changed by SmartModule are written.
+#[derive(Debug)]
+pub struct SmartModuleKeyValueStore {
+    entries: CheckPointStore<Vec<u8>, KeyValueKey>,
+}
+
+impl SmartModuleKeyValueStore {
+    /// load existing states from replica directory or create empty store
+    pub async fn create(base_dir: &Path) -> Result<Self, IoError> {
+        let entries = CheckPointStore::create(base_dir, SMARTMODULE_KEY_VALUE_FILE_NAME).await?;
+        Ok(Self { entries })
+    }
+
+    pub fn shared(self) -> SharedSmartModuleKeyValueStore {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// last persisted state of SmartModule
+    pub fn get(&self, smartmodule: &str) -> Option<SmartModuleKeyValues> {
+        let start = KeyValueKey {
+            smartmodule: smartmodule.to_owned(),
+            key: vec![],
+        };
+        let key_values: SmartModuleKeyValues = self
+            .entries
+            .range(start..)
+            .take_while(|(entry, _)| entry.smartmodule == smartmodule)
+            .map(|(entry, value)| (entry.key.clone(), value.clone()))
+            .collect();
+        (!key_values.is_empty()).then_some(key_values)
+    }
+
+    /// persist keys changed by SmartModule
+    pub async fn commit(
+        &mut self,
+        smartmodule: &str,
+        changes: SmartModuleKeyValueChanges,
+    ) -> Result<(), IoError> {
+        debug!(
+            smartmodule,
+            keys = changes.len(),
+            "commit smartmodule key-values"
+        );
+        let changes = changes
+            .into_iter()
+            .map(|(key, value)| {
+                let entry = KeyValueKey {
+                    smartmodule: smartmodule.to_owned(),
+                    key,
+                };
+                (entry, value)
+            })
+            .collect();
+        self.entries.update(changes).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+
+    use flv_util::fixture::ensure_clean_dir;
+
+    use super::SmartModuleKeyValueStore;
+
+    #[fluvio_future::test]
+    async fn test_smartmodule_key_value_store() {
+        let test_dir = temp_dir().join("smartmodule_key_value_store_test");
+        ensure_clean_dir(&test_dir);
+
+        let mut store = SmartModuleKeyValueStore::create(&test_dir)
+            .await
+            .expect("create");
+        assert_eq!(store.get("dedup"), None);
+
+        store
+            .commit("dedup", vec![(b"a".to_vec(), Some(b"1".to_vec()))])
+            .await
+            .expect("commit");
+        store
+            .commit(
+                "dedup",
+                vec![
+                    (b"b".to_vec(), Some(b"2".to_vec())),
+                    (b"c".to_vec(), Some(b"3".to_vec())),
+                ],
+            )
+            .await
+            .expect("commit");
+        store
+            .commit("dedup", vec![(b"c".to_vec(), None)])
+            .await
+            .expect("commit");
+        store
+            .commit("other", vec![(b"a".to_vec(), Some(b"4".to_vec()))])
+            .await
+            .expect("commit");
+        drop(store);
+
+        let store = SmartModuleKeyValueStore::create(&test_dir)
+            .await
+            .expect("reload");
+        assert_eq!(
+            store
+                .get("dedup")
+                .expect("dedup state")
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec![
+                (b"a".to_vec(), b"1".to_vec()),
+                (b"b".to_vec(), b"2".to_vec())
+            ]
+        );
+        assert_eq!(store.get("other").map(|state| state.len()), Some(1));
+        assert_eq!(store.get("missing"), None);
+    }
+}
@@ -0,0 +1,126 @@
+use std::io::Error as IoError;
+use std::path::Path;
+use std::sync::Arc;
+
+use async_lock::Mutex;
+use tracing::debug;
+
+use fluvio_protocol::{Encoder, Decoder};
+use fluvio_protocol::record::Offset;
+
+use crate::checkpoint::CheckPointStore;
+
+const SMARTMODULE_STATE_FILE_NAME: &str = "smartmodule_state.chk";
+
+pub type SharedSmartModuleStateStore = Arc<Mutex<SmartModuleStateStore>>;
+
+/// Checkpoint of stream processed by aggregate SmartModules
+#[derive(Debug, Default, Clone, Encoder, Decoder, PartialEq, Eq)]
+pub struct SmartModuleState {
+    /// next offset to be processed
+    pub offset: Offset,
+    /// accumulators of aggregate SmartModules in the chain order
+    pub accumulators: Vec<SmartModuleAccumulator>,
+}
+
+/// Accumulator of aggregate SmartModule
+#[derive(Debug, Default, Clone, Encoder, Decoder, PartialEq, Eq)]
+pub struct SmartModuleAccumulator {
+    /// name of SmartModule, or hash of its binary if it is not referenced by name
+    pub smartmodule: String,
+    pub accumulator: Vec<u8>,
+}
+
+/// SmartModule states of a replica keyed by state id chosen by the consumer,
+/// checkpointed in the replica directory.
+#[derive(Debug)]
+pub struct SmartModuleStateStore {
+    states: CheckPointStore<SmartModuleState>,
+}
+
+impl SmartModuleStateStore {
+    /// load existing states from replica directory or create empty store
+    pub async fn create(base_dir: &Path) -> Result<Self, IoError> {
+        let states = CheckPointStore::create(base_dir, SMARTMODULE_STATE_FILE_NAME).await?;
+        Ok(Self { states })
+    }
+
+    pub fn shared(self) -> SharedSmartModuleStateStore {
+        Arc::new(Mutex::new(self))
+    }
+
+    /// last checkpoint of state
+    pub fn get(&self, state_id: &str) -> Option<&SmartModuleState> {
+        self.states.get(state_id)
+    }
+
+    /// store checkpoint of state and persist it
+    pub async fn commit(&mut self, state_id: &str, state: SmartModuleState) -> Result<(), IoError> {
+        debug!(state_id, offset = state.offset, "commit smartmodule state");
+        self.states.insert(state_id, state).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+
+    use flv_util::fixture::ensure_clean_dir;
+
+    use super::{SmartModuleAccumulator, SmartModuleState, SmartModuleStateStore};
+
+    #[fluvio_future::test]
+    async fn test_smartmodule_state_store() {
+        let test_dir = temp_dir().join("smartmodule_state_store_test");
+        ensure_clean_dir(&test_dir);
+
+        let mut store = SmartModuleStateStore::create(&test_dir)
+            .await
+            .expect("create");
+        assert_eq!(store.get("sum"), None);
+
+        store
+            .commit(
+                "sum",
+                SmartModuleState {
+                    offset: 10,
+                    accumulators: vec![SmartModuleAccumulator {
+                        smartmodule: "sum".to_owned(),
+                        accumulator: b"45".to_vec(),
+                    }],
+                },
+            )
+            .await
+            .expect("commit");
+        store
+            .commit(
+                "sum",
+                SmartModuleState {
+                    offset: 20,
+                    accumulators: vec![SmartModuleAccumulator {
+                        smartmodule: "sum".to_owned(),
+                        accumulator: b"190".to_vec(),
+                    }],
+                },
+            )
+            .await
+            .expect("commit");
+        drop(store);
+
+        let store = SmartModuleStateStore::create(&test_dir)
+            .await
+            .expect("reload");
+        assert_eq!(
+            store.get("sum"),
+            Some(&SmartModuleState {
+                offset: 20,
+                accumulators: vec![SmartModuleAccumulator {
+                    smartmodule: "sum".to_owned(),
+                    accumulator: b"190".to_vec(),
+                }],
+            })
+        );
+        assert_eq!(store.get("other"), None);
+    }
+}
@@ -0,0 +1,272 @@
+use std::io::Error as IoError;
+use std::path::{Path, PathBuf};
+
+use tracing::{debug, trace, warn};
+use futures_lite::io::AsyncWriteExt;
+
+use fluvio_future::fs::{File, metadata, read};
+use fluvio_future::fs::util as file_util;
+use fluvio_protocol::{Encoder, Decoder};
+use fluvio_protocol::record::{Offset, Size};
+use fluvio_types::Timestamp;
+
+use crate::util::generate_file_name;
+
+pub const EXTENSION: &str = "timeindex";
+
+/// size of encoded entry
+const TIME_INDEX_ENTRY_SIZE: usize = 16;
+
+/// Entry of time index.
+/// `timestamp` is the max timestamp of all batches in the segment before the batch
+/// at `offset_delta`, so every batch before file `position` is older than or equal to it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encoder, Decoder)]
+pub struct TimeIndexEntry {
+    pub timestamp: Timestamp,
+    pub offset_delta: Size,
+    pub position: Size,
+}
+
+/// Sparse index of batch timestamps in a segment.
+///
+/// Entries are kept in memory and loaded from `.timeindex` file next to the segment log.
+/// Since timestamp of an entry is running max, entries are sorted by timestamp even if batches are not.
+/// Segments without time index file are scanned from the beginning.
+#[derive(Debug)]
+pub struct TimeIndex {
+    path: PathBuf,
+    entries: Vec<TimeIndexEntry>,
+}
+
+impl TimeIndex {
+    pub async fn open(base_dir: &Path, base_offset: Offset) -> Result<Self, IoError> {
+        let path = generate_file_name(base_dir, base_offset, EXTENSION);
+        let entries = if metadata(&path).await.is_ok() {
+            decode_entries(&read(&path).await?)
+        } else {
+            debug!(path = %path.display(), "no time index found");
+            vec![]
+        };
+        trace!(entries = entries.len(), path = %path.display(), "time index loaded");
+        Ok(Self { path, entries })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// file position from where batches with timestamp at or after `timestamp` should be scanned
+    pub fn find_position(&self, timestamp: Timestamp) -> Size {
+        let index = self
+            .entries
+            .partition_point(|entry| entry.timestamp < timestamp);
+        if index == 0 {
+            0
+        } else {
+            self.entries[index - 1].position
+        }
+    }
+
+    #[cfg(test)]
+    pub fn entries(&self) -> &[TimeIndexEntry] {
+        &self.entries
+    }
+}
+
+impl AsRef<TimeIndex> for TimeIndex {
+    fn as_ref(&self) -> &TimeIndex {
+        self
+    }
+}
+
+/// Time index for active segment.
+/// File is created when first entry is written.
+pub struct MutTimeIndex {
+    index: TimeIndex,
+    file: Option<File>,
+    max_timestamp: Timestamp,
+    accumulated_batch_len: Size,
+    max_index_interval: Size,
+}
+
+impl std::fmt::Debug for MutTimeIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "TimeIndex({})", self.index.path.display())
+    }
+}
+
+impl MutTimeIndex {
+    /// open existing time index, or start with no entries
+    pub async fn open(
+        base_dir: &Path,
+        base_offset: Offset,
+        max_index_interval: Size,
+    ) -> Result<Self, IoError> {
+        let index = TimeIndex::open(base_dir, base_offset).await?;
+        let max_timestamp = index
+            .entries
+            .last()
+            .map(|entry| entry.timestamp)
+            .unwrap_or(Timestamp::MIN);
+        Ok(Self {
+            index,
+            file: None,
+            max_timestamp,
+            accumulated_batch_len: 0,
+            max_index_interval,
+        })
+    }
+
+    async fn file(&mut self) -> Result<&mut File, IoError> {
+        match self.file {
+            Some(ref mut file) => Ok(file),
+            None => Ok(self
+                .file
+                .insert(file_util::open_read_append(&self.index.path).await?)),
+        }
+    }
+
+    /// position of last entry, batches after this are not covered by index
+    pub fn last_position(&self) -> Size {
+        self.index
+            .entries
+            .last()
+            .map(|entry| entry.position)
+            .unwrap_or_default()
+    }
+
+    /// account batch which was not written through this index, used when recovering state
+    pub fn observe_batch(&mut self, batch_size: Size, max_timestamp: Timestamp) {
+        self.accumulated_batch_len += batch_size;
+        self.max_timestamp = self.max_timestamp.max(max_timestamp);
+    }
+
+    /// record batch written to the segment.
+    /// entry is written only if accumulated batches size since last entry is greater than max interval
+    pub async fn write_batch(
+        &mut self,
+        offset_delta: Size,
+        file_position: Size,
+        batch_size: Size,
+        max_timestamp: Timestamp,
+    ) -> Result<(), IoError> {
+        if file_position > 0 && self.accumulated_batch_len >= self.max_index_interval {
+            let entry = TimeIndexEntry {
+                timestamp: self.max_timestamp,
+                offset_delta,
+                position: file_position,
+            };
+            trace!(?entry, "add time index entry");
+            let mut buf = Vec::with_capacity(TIME_INDEX_ENTRY_SIZE);
+            entry.encode(&mut buf, 0)?;
+            let file = self.file().await?;
+            file.write_all(&buf).await?;
+            file.flush().await?;
+            self.index.entries.push(entry);
+            self.accumulated_batch_len = 0;
+        }
+        self.observe_batch(batch_size, max_timestamp);
+        Ok(())
+    }
+
+    /// remove entries at or after file position, used when log is truncated
+    pub async fn truncate(&mut self, position: Size) -> Result<(), IoError> {
+        let len = self
+            .index
+            .entries
+            .partition_point(|entry| entry.position < position);
+        if len < self.index.entries.len() {
+            warn!(
+                position,
+                removed = self.index.entries.len() - len,
+                "truncating time index"
+            );
+            self.index.entries.truncate(len);
+            let file = self.file().await?;
+            file.set_len((len * TIME_INDEX_ENTRY_SIZE) as u64).await?;
+            self.max_timestamp = self
+                .index
+                .entries
+                .last()
+                .map(|entry| entry.timestamp)
+                .unwrap_or(Timestamp::MIN);
+        }
+        Ok(())
+    }
+}
+
+impl AsRef<TimeIndex> for MutTimeIndex {
+    fn as_ref(&self) -> &TimeIndex {
+        &self.index
+    }
+}
+
+/// decode entries, partially written entry at the end is ignored
+fn decode_entries(bytes: &[u8]) -> Vec<TimeIndexEntry> {
+    bytes
+        .chunks_exact(TIME_INDEX_ENTRY_SIZE)
+        .filter_map(|mut chunk| TimeIndexEntry::decode_from(&mut chunk, 0).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::env::temp_dir;
+
+    use flv_util::fixture::ensure_new_dir;
+
+    use super::*;
+
+    #[fluvio_future::test]
+    async fn test_time_index_write_and_find() {
+        let test_dir = temp_dir().join("time-index-write");
+        ensure_new_dir(&test_dir).expect("new");
+
+        let mut index = MutTimeIndex::open(&test_dir, 100, 0).await.expect("open");
+        // out of order timestamp is covered by running max
+        index.write_batch(0, 0, 50, 1000).await.expect("write");
+        index.write_batch(2, 50, 50, 3000).await.expect("write");
+        index.write_batch(4, 100, 50, 2000).await.expect("write");
+        index.write_batch(6, 150, 50, 4000).await.expect("write");
+
+        assert_eq!(
+            index.index.entries(),
+            &[
+                TimeIndexEntry {
+                    timestamp: 1000,
+                    offset_delta: 2,
+                    position: 50
+                },
+                TimeIndexEntry {
+                    timestamp: 3000,
+                    offset_delta: 4,
+                    position: 100
+                },
+                TimeIndexEntry {
+                    timestamp: 3000,
+                    offset_delta: 6,
+                    position: 150
+                },
+            ]
+        );
+
+        assert_eq!(index.as_ref().find_position(500), 0);
+        assert_eq!(index.as_ref().find_position(1000), 0);
+        assert_eq!(index.as_ref().find_position(2500), 50);
+        assert_eq!(index.as_ref().find_position(3500), 150);
+        drop(index);
+
+        let index = TimeIndex::open(&test_dir, 100).await.expect("open");
+        assert_eq!(index.entries().len(), 3);
+        assert_eq!(index.find_position(3500), 150);
+
+        let mut index = MutTimeIndex::open(&test_dir, 100, 0).await.expect("open");
+        index.truncate(100).await.expect("truncate");
+        assert_eq!(index.as_ref().find_position(3500), 50);
+        drop(index);
+
+        let index = TimeIndex::open(&test_dir, 100).await.expect("open");
+        assert_eq!(index.entries().len(), 1);
+    }
+}
@@ -13,9 +13,18 @@ use fluvio_future::net::DomainConnector;
 use fluvio_sc_schema::objects::{
     DeleteRequest, ObjectApiCreateRequest, ObjectApiDeleteRequest, ObjectApiListRequest,
     ObjectApiWatchRequest, Metadata, ListFilter, WatchRequest, WatchResponse, CreateRequest,
-    CommonCreateRequest,
+    CommonCreateRequest, ObjectApiUpdateRequest, UpdateRequest,
 };
-use fluvio_sc_schema::{AdminSpec, DeletableAdminSpec, CreatableAdminSpec, TryEncodableFrom};
+use fluvio_sc_schema::{
+    AdminSpec, DeletableAdminSpec, CreatableAdminSpec, UpdatableAdminSpec, TryEncodableFrom,
+};
+use fluvio_sc_schema::partition::{
+    ElectPreferredLeaderRequest, ElectionResult, ReassignPartitionsRequest, ReassignmentResult,
+};
+use fluvio_sc_schema::consumer_group::{
+    ConsumerGroupOffset, FetchConsumerGroupOffsetsRequest, ResetConsumerGroupOffsetsRequest,
+};
+use fluvio_protocol::record::ReplicaKey;
 use fluvio_socket::{ClientConfig, VersionedSerialSocket, SerialFrame, MultiplexerSocket};
 
 use crate::FluvioConfig;
@@ -200,6 +209,25 @@ impl FluvioAdmin {
         Ok(())
     }
 
+    /// Apply update action to existing object
+    #[instrument(skip(self, key, action))]
+    pub async fn update<S>(
+        &self,
+        key: impl Into<S::UpdateKey>,
+        action: impl Into<S::UpdateAction>,
+    ) -> Result<()>
+    where
+        S: UpdatableAdminSpec + Sync + Send,
+    {
+        let update_request: UpdateRequest<S> = UpdateRequest::new(key.into(), action.into());
+        debug!("sending update request: {:#?}", update_request);
+
+        self.send_receive_admin::<ObjectApiUpdateRequest, _>(update_request)
+            .await?
+            .as_result()?;
+        Ok(())
+    }
+
     /// return all instance of this spec
     #[instrument(skip(self))]
     pub async fn all<S>(&self) -> Result<Vec<Metadata<S>>>
@@ -245,6 +273,96 @@ impl FluvioAdmin {
             .map(|out: ListResponse<S>| out.inner())
     }
 
+    /// Reassign replicas of partitions.
+    /// Returns result for each assignment, including assignments generated for balanced topics.
+    #[instrument(skip(self, request))]
+    pub async fn reassign_partitions(
+        &self,
+        request: ReassignPartitionsRequest,
+    ) -> Result<Vec<ReassignmentResult>> {
+        if self
+            .socket
+            .lookup_version::<ReassignPartitionsRequest>()
+            .is_none()
+        {
+            return Err(anyhow!("SC does not support partition reassignment"));
+        }
+        debug!(?request, "sending reassign partitions request");
+        let response = self.socket.send_receive(request).await?;
+        Ok(response.results)
+    }
+
+    /// Move leaders of partitions to their preferred replica.
+    /// All partitions are elected if `partitions` is empty.
+    #[instrument(skip(self, partitions))]
+    pub async fn elect_preferred_leaders(
+        &self,
+        partitions: Vec<ReplicaKey>,
+    ) -> Result<Vec<ElectionResult>> {
+        if self
+            .socket
+            .lookup_version::<ElectPreferredLeaderRequest>()
+            .is_none()
+        {
+            return Err(anyhow!("SC does not support preferred leader election"));
+        }
+        let response = self
+            .socket
+            .send_receive(ElectPreferredLeaderRequest { partitions })
+            .await?;
+        Ok(response.results)
+    }
+
+    /// Offsets committed by consumer group for all partitions of the topic
+    #[instrument(skip(self))]
+    pub async fn consumer_group_offsets(
+        &self,
+        group: &str,
+        topic: &str,
+    ) -> Result<Vec<ConsumerGroupOffset>> {
+        if self
+            .socket
+            .lookup_version::<FetchConsumerGroupOffsetsRequest>()
+            .is_none()
+        {
+            return Err(anyhow!("SC does not support consumer group offsets"));
+        }
+        let response = self
+            .socket
+            .send_receive(FetchConsumerGroupOffsetsRequest::new(group, topic))
+            .await?;
+        if response.error_code.is_error() {
+            return Err(response.error_code.into());
+        }
+        Ok(response.partitions)
+    }
+
+    /// Remove offsets committed by consumer group for all partitions of the topic.
+    /// Fails if the group has live members.
+    /// Returns result for each partition.
+    #[instrument(skip(self))]
+    pub async fn reset_consumer_group_offsets(
+        &self,
+        group: &str,
+        topic: &str,
+    ) -> Result<Vec<ConsumerGroupOffset>> {
+        if self
+            .socket
+            .lookup_version::<ResetConsumerGroupOffsetsRequest>()
+            .is_none()
+        {
+            return Err(anyhow!("SC does not support consumer group offsets"));
+        }
+        let response = self
+            .socket
+            .send_receive(ResetConsumerGroupOffsetsRequest::new(group, topic))
+            .await?;
+        if response.error_code.is_error() {
+            return Err(response.error_code.into());
+        }
+        Ok(response.partitions)
+    }
+
     /// Watch stream of changes for metadata
     /// There is caching, this is just pass through
     #[instrument(skip(self))]
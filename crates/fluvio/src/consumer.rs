@@ -1,6 +1,8 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use anyhow::Result;
+use async_channel::Sender;
 use tracing::{debug, error, trace, instrument, info, warn};
 use futures_util::stream::{Stream, select_all};
 use once_cell::sync::Lazy;
@@ -8,13 +10,20 @@ use futures_util::future::{Either, err, join_all};
 use futures_util::stream::{StreamExt, once, iter};
 use futures_util::FutureExt;
 
+use fluvio_future::timer::sleep;
 use fluvio_types::PartitionId;
 use fluvio_types::defaults::{FLUVIO_CLIENT_MAX_FETCH_BYTES, FLUVIO_MAX_SIZE_TOPIC_NAME};
 use fluvio_types::event::offsets::OffsetPublisher;
 use fluvio_spu_schema::server::stream_fetch::{
     DefaultStreamFetchRequest, DefaultStreamFetchResponse, CHAIN_SMARTMODULE_API,
+    SMARTMODULE_DEBUG_API, SMARTMODULE_STATE_API,
+};
+use fluvio_spu_schema::server::consumer_offset::{
+    CommitConsumerOffsetRequest, FetchConsumerOffsetRequest, ResetConsumerOffsetRequest,
 };
 use fluvio_spu_schema::Isolation;
+use fluvio_spu_schema::fetch::AbortedBatchFilter;
+use fluvio_socket::VersionedSerialSocket;
 use fluvio_protocol::record::ReplicaKey;
 use fluvio_protocol::link::ErrorCode;
 use fluvio_protocol::record::Batch;
@@ -30,7 +39,10 @@ pub use fluvio_spu_schema::server::smartmodule::SmartModuleInvocation;
 pub use fluvio_spu_schema::server::smartmodule::SmartModuleInvocationWasm;
 pub use fluvio_spu_schema::server::smartmodule::SmartModuleKind;
 pub use fluvio_spu_schema::server::smartmodule::SmartModuleContextData;
+pub use fluvio_spu_schema::server::smartmodule::SmartModuleBudget;
 pub use fluvio_smartmodule::dataplane::smartmodule::SmartModuleExtraParams;
+pub use fluvio_smartmodule::dataplane::smartmodule::SmartModuleErrorPolicy;
+pub use fluvio_smartmodule::dataplane::smartmodule::{SmartModuleChainTrace, SmartModuleDebugConfig};
 
 /// An interface for consuming events from a particular partition
 ///
@@ -79,6 +91,75 @@ where
         self.metrics.clone()
     }
 
+    /// Commits `offset` as the next offset to be consumed by `consumer_group` in this partition
+    ///
+    /// The committed offset is stored by the SPU leading the partition. A consumer
+    /// configured with the same [`ConsumerConfig::consumer_group`] resumes from it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fluvio::{PartitionConsumer, Offset};
+    /// # mod futures {
+    /// #     pub use futures_util::stream::StreamExt;
+    /// # }
+    /// # async fn example(consumer: &PartitionConsumer) -> anyhow::Result<()> {
+    /// use futures::StreamExt;
+    /// let mut stream = consumer.stream(Offset::beginning()).await?;
+    /// if let Some(Ok(record)) = stream.next().await {
+    ///     consumer.commit_offset("my-group", record.offset() + 1).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(skip(self))]
+    pub async fn commit_offset(
+        &self,
+        consumer_group: &str,
+        offset: fluvio_protocol::record::Offset,
+    ) -> Result<()> {
+        let replica = ReplicaKey::new(&self.topic, self.partition);
+        let mut serial_socket = self.pool.create_serial_socket(&replica).await?;
+        let response = serial_socket
+            .send_receive(CommitConsumerOffsetRequest::new(
+                consumer_group,
+                replica,
+                offset,
+            ))
+            .await?;
+        if response.error_code.is_error() {
+            return Err(response.error_code.into());
+        }
+        Ok(())
+    }
+
+    /// Returns the offset committed by `consumer_group` in this partition, if any
+    #[instrument(skip(self))]
+    pub async fn committed_offset(
+        &self,
+        consumer_group: &str,
+    ) -> Result<Option<fluvio_protocol::record::Offset>> {
+        let replica = ReplicaKey::new(&self.topic, self.partition);
+        let mut serial_socket = self.pool.create_serial_socket(&replica).await?;
+        fetch_committed_offset(&mut serial_socket, &replica, consumer_group).await
+    }
+
+    /// Removes the offset committed by `consumer_group` in this partition
+    ///
+    /// Consumers of the group will start from their own starting [`Offset`] afterwards.
+    #[instrument(skip(self))]
+    pub async fn reset_committed_offset(&self, consumer_group: &str) -> Result<()> {
+        let replica = ReplicaKey::new(&self.topic, self.partition);
+        let mut serial_socket = self.pool.create_serial_socket(&replica).await?;
+        let response = serial_socket
+            .send_receive(ResetConsumerOffsetRequest::new(consumer_group, replica))
+            .await?;
+        if response.error_code.is_error() {
+            return Err(response.error_code.into());
+        }
+        Ok(())
+    }
+
     /// Continuously streams events from a particular offset in the consumer's partition
     ///
     /// Streaming is one of the two ways to consume events in Fluvio.
@@ -242,41 +323,56 @@ where
         impl Stream<Item = Result<Batch, ErrorCode>>,
         fluvio_protocol::record::Offset,
     )> {
+        let trace_sender = config
+            .smartmodule_debug
+            .as_ref()
+            .map(|(_, sender)| sender.clone());
         let (stream, start_offset) = self.request_stream(offset, config).await?;
         let metrics = self.metrics.clone();
         let flattened =
             stream.flat_map(move |batch_result: Result<DefaultStreamFetchResponse, _>| {
-                let response = match batch_result {
+                let mut response = match batch_result {
                     Ok(response) => response,
                     Err(e) => return Either::Right(once(err(e))),
                 };
 
+                if let (Some(sender), Some(trace)) =
+                    (&trace_sender, response.smartmodule_trace.take())
+                {
+                    if let Err(err) = sender.try_send(trace) {
+                        debug!(%err, "smartmodule trace dropped");
+                    }
+                }
+
                 // If we ever get an error_code AND batches of records, we want to first send
                 // the records down the consumer stream, THEN an Err with the error inside.
                 // This way the consumer always gets to read all records that were properly
                 // processed before hitting an error, so that the error does not obscure those records.
 
+                // control batches and records of aborted transactions are not visible to consumer
+                let mut aborted_filter =
+                    AbortedBatchFilter::new(response.partition.aborted.take().unwrap_or_default());
                 let inner_metrics = metrics.clone();
-                let batches =
-                    response
-                        .partition
-                        .records
-                        .batches
-                        .into_iter()
-                        .map(move |raw_batch| {
-                            inner_metrics
-                                .consumer()
-                                .add_records(raw_batch.records_len() as u64);
-                            inner_metrics
-                                .consumer()
-                                .add_bytes(raw_batch.batch_len() as u64);
-
-                            let batch: Result<Batch, _> = raw_batch.try_into();
-                            match batch {
-                                Ok(batch) => Ok(batch),
-                                Err(err) => Err(ErrorCode::Other(err.to_string())),
-                            }
-                        });
+                let batches = response
+                    .partition
+                    .records
+                    .batches
+                    .into_iter()
+                    .filter(move |raw_batch| !aborted_filter.skip(raw_batch))
+                    .map(move |raw_batch| {
+                        inner_metrics
+                            .consumer()
+                            .add_records(raw_batch.records_len() as u64);
+                        inner_metrics
+                            .consumer()
+                            .add_bytes(raw_batch.batch_len() as u64);
+
+                        let batch: Result<Batch, _> = raw_batch.try_into();
+                        match batch {
+                            Ok(batch) => Ok(batch),
+                            Err(err) => Err(ErrorCode::Other(err.to_string())),
+                        }
+                    });
                 let error = {
                     let code = response.partition.error_code;
                     match code {
@@ -310,9 +406,21 @@ where
 
         let replica = ReplicaKey::new(&self.topic, self.partition);
         let mut serial_socket = self.pool.create_serial_socket(&replica).await?;
-        let offsets = fetch_offsets(&mut serial_socket, &replica).await?;
+        let offsets = fetch_offsets(&mut serial_socket, &replica, offset.timestamp()).await?;
 
-        let start_absolute_offset = offset.resolve(&offsets).await?;
+        let committed_offset = match config.consumer_group {
+            Some(ref consumer_group) => {
+                fetch_committed_offset(&mut serial_socket, &replica, consumer_group).await?
+            }
+            None => None,
+        };
+        let start_absolute_offset = match committed_offset {
+            Some(committed) => {
+                debug!(committed, "resuming from committed offset");
+                committed.clamp(offsets.start_offset, offsets.last_stable_offset)
+            }
+            None => offset.resolve(&offsets).await?,
+        };
         let end_absolute_offset = offsets.last_stable_offset;
         let record_count = end_absolute_offset - start_absolute_offset;
 
@@ -325,6 +433,8 @@ where
             .isolation(config.isolation)
             .max_bytes(config.max_bytes)
             .smartmodules(config.smartmodule)
+            .smartmodule_state_id(config.smartmodule_state_id)
+            .smartmodule_debug(config.smartmodule_debug.map(|(debug, _)| debug))
             .build()?;
 
         let stream_fetch_version = serial_socket
@@ -335,6 +445,16 @@ where
         if stream_fetch_version < CHAIN_SMARTMODULE_API {
             warn!("SPU does not support SmartModule chaining. SmartModules will not be applied to the stream");
         }
+        if stream_request.smartmodule_state_id.is_some()
+            && stream_fetch_version < SMARTMODULE_STATE_API
+        {
+            warn!("SPU does not support SmartModule state. Aggregates will start from initial accumulator");
+        }
+        if stream_request.smartmodule_debug.is_some()
+            && stream_fetch_version < SMARTMODULE_DEBUG_API
+        {
+            warn!("SPU does not support SmartModule debug mode. Traces will not be received");
+        }
 
         let mut stream = self
             .pool
@@ -356,6 +476,9 @@ where
 
                 let publisher = OffsetPublisher::shared(0);
                 let mut listener = publisher.change_listener();
+                let throttle = Arc::new(StreamThrottle::default());
+                throttle.throttle(response.throttle_time_ms);
+                let update_throttle = throttle.clone();
 
                 // update stream with received offsets
                 spawn(async move {
@@ -379,6 +502,8 @@ where
                                     session_id: stream_id,
                                 }],
                             };
+                            // SPU doesn't stream more records until offsets are updated
+                            update_throttle.wait().await;
                             debug!(?request, "Sending offset update request:");
                             let response = serial_socket.send_receive(request).await;
                             if let Err(err) = response {
@@ -399,6 +524,7 @@ where
                 let response_publisher = publisher.clone();
                 let update_stream = StreamExt::map(stream, move |item| {
                     item.map(|response| {
+                        throttle.throttle(response.throttle_time_ms);
                         if let Some(last_offset) = response.partition.next_offset_for_fetch() {
                             debug!(last_offset, stream_id, "received last offset from spu");
                             response_publisher.update(last_offset);
@@ -430,6 +556,53 @@ where
     }
 }
 
+/// Consumer throttled by SPU for exceeding quota, offsets are not updated until then
+#[derive(Debug, Default)]
+struct StreamThrottle(Mutex<Option<Instant>>);
+
+impl StreamThrottle {
+    /// back off for throttle time set by SPU
+    fn throttle(&self, throttle_time_ms: i32) {
+        if throttle_time_ms <= 0 {
+            return;
+        }
+        debug!(throttle_time_ms, "consumer throttled by quota");
+        let until = Instant::now() + Duration::from_millis(throttle_time_ms as u64);
+        let mut throttled_until = self.0.lock().expect("throttle lock poisoned");
+        if throttled_until.map_or(true, |current| current < until) {
+            *throttled_until = Some(until);
+        }
+    }
+
+    async fn wait(&self) {
+        let throttled_until = self.0.lock().expect("throttle lock poisoned").take();
+        if let Some(until) = throttled_until {
+            let remaining = until.saturating_duration_since(Instant::now());
+            if !remaining.is_zero() {
+                sleep(remaining).await;
+            }
+        }
+    }
+}
+
+async fn fetch_committed_offset(
+    serial_socket: &mut VersionedSerialSocket,
+    replica: &ReplicaKey,
+    consumer_group: &str,
+) -> Result<Option<fluvio_protocol::record::Offset>> {
+    let response = serial_socket
+        .send_receive(FetchConsumerOffsetRequest::new(
+            consumer_group,
+            replica.clone(),
+        ))
+        .await?;
+    if response.error_code.is_error() {
+        return Err(response.error_code.into());
+    }
+    debug!(consumer_group, offset = ?response.offset, "fetched committed offset");
+    Ok(response.offset)
+}
+
 /// Wrap an inner record stream and only stream until a given number of records have been fetched.
 ///
 /// This is used for "disable continuous" mode. In this mode, we first make a FetchOffsetPartitionResponse
@@ -569,6 +742,17 @@ pub struct ConsumerConfig {
     pub isolation: Isolation,
     #[builder(default)]
     pub(crate) smartmodule: Vec<SmartModuleInvocation>,
+    /// If set, the stream resumes from the offset committed by this consumer group.
+    /// The requested starting offset is used only when the group has not committed yet.
+    #[builder(default, setter(into, strip_option))]
+    pub consumer_group: Option<String>,
+    /// If set, SPU checkpoints accumulators of aggregate SmartModules under this id.
+    /// Stream with the same id resumes from the checkpointed accumulators and offset.
+    #[builder(default, setter(into, strip_option))]
+    pub smartmodule_state_id: Option<String>,
+    /// If set, SPU runs SmartModule chain in debug mode and its traces are sent to the channel
+    #[builder(default, setter(custom))]
+    pub(crate) smartmodule_debug: Option<(SmartModuleDebugConfig, Sender<SmartModuleChainTrace>)>,
 }
 
 impl ConsumerConfig {
@@ -578,6 +762,17 @@ impl ConsumerConfig {
 }
 
 impl ConsumerConfigBuilder {
+    /// Run SmartModule chain in debug mode, traces are sent to `traces` as they arrive.
+    /// Traces are dropped if the channel is full.
+    pub fn smartmodule_debug(
+        &mut self,
+        debug: SmartModuleDebugConfig,
+        traces: Sender<SmartModuleChainTrace>,
+    ) -> &mut Self {
+        self.smartmodule_debug = Some(Some((debug, traces)));
+        self
+    }
+
     pub fn build(&self) -> Result<ConsumerConfig> {
         let config = self.build_impl().map_err(|e| {
             FluvioError::ConsumerConfig(format!("Missing required config option: {e}"))
@@ -0,0 +1,402 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use async_channel::{Receiver, Sender};
+use futures_util::stream::{BoxStream, SelectAll, Stream, StreamExt};
+use tracing::{debug, info, instrument, warn};
+
+use fluvio_future::timer::sleep;
+use fluvio_protocol::link::ErrorCode;
+use fluvio_sc_schema::consumer_group::{
+    AssignmentStrategy, ConsumerGroupHeartbeatRequest, LeaveConsumerGroupRequest,
+    DEFAULT_SESSION_TIMEOUT_MS,
+};
+use fluvio_socket::VersionedSerialSocket;
+use fluvio_types::PartitionId;
+
+use crate::consumer::{ConsumerConfig, PartitionConsumer, Record};
+use crate::metrics::ClientMetrics;
+use crate::offset::Offset;
+use crate::spu::SpuPool;
+
+type RecordStream = BoxStream<'static, Result<Record, ErrorCode>>;
+
+/// how often streams of assigned partitions which failed to start are retried
+const STREAM_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Consumes a topic as a member of a consumer group
+///
+/// Members of the same group send heartbeats to the cluster, which assigns
+/// partitions of the topic across live members. Partitions are reassigned
+/// when a member joins, leaves, or stops sending heartbeats, so each partition
+/// is consumed by a single member of the group at a time.
+///
+/// Partitions start from the offset committed by the group with [`GroupConsumer::commit`],
+/// the starting [`Offset`] is used only for partitions without committed offset.
+pub struct GroupConsumer {
+    topic: String,
+    group: String,
+    strategy: AssignmentStrategy,
+    session_timeout: Duration,
+    sc_socket: Arc<VersionedSerialSocket>,
+    pool: Arc<SpuPool>,
+    metrics: Arc<ClientMetrics>,
+}
+
+impl GroupConsumer {
+    pub(crate) fn new(
+        topic: String,
+        group: String,
+        sc_socket: VersionedSerialSocket,
+        pool: Arc<SpuPool>,
+        metrics: Arc<ClientMetrics>,
+    ) -> Self {
+        Self {
+            topic,
+            group,
+            strategy: AssignmentStrategy::default(),
+            session_timeout: Duration::from_millis(DEFAULT_SESSION_TIMEOUT_MS as u64),
+            sc_socket: Arc::new(sc_socket),
+            pool,
+            metrics,
+        }
+    }
+
+    /// Sets how partitions are distributed across members of the group.
+    /// The strategy of the first member is used until the group becomes empty.
+    pub fn with_strategy(mut self, strategy: AssignmentStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Sets the time after which this member is removed from the group if it stops sending heartbeats
+    pub fn with_session_timeout(mut self, session_timeout: Duration) -> Self {
+        self.session_timeout = session_timeout;
+        self
+    }
+
+    /// Returns the name of the topic this consumer is reading from
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Returns the name of the consumer group
+    pub fn group(&self) -> &str {
+        &self.group
+    }
+
+    /// Continuously streams records from the partitions assigned to this member
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fluvio::{GroupConsumer, Offset};
+    /// # mod futures {
+    /// #     pub use futures_util::stream::StreamExt;
+    /// # }
+    /// # async fn example(consumer: &GroupConsumer) -> anyhow::Result<()> {
+    /// use futures::StreamExt;
+    /// let mut stream = consumer.stream(Offset::beginning()).await?;
+    /// while let Some(Ok(record)) = stream.next().await {
+    ///     println!("Got record: {}", record.get_value().as_utf8_lossy_string());
+    ///     consumer.commit(&record).await?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn stream(
+        &self,
+        offset: Offset,
+    ) -> Result<impl Stream<Item = Result<Record, ErrorCode>>> {
+        let config = ConsumerConfig::builder().build()?;
+        self.stream_with_config(offset, config).await
+    }
+
+    /// Continuously streams records from the partitions assigned to this member,
+    /// using a custom [`ConsumerConfig`] for each partition.
+    ///
+    /// The stream leaves the group when it is dropped.
+    #[instrument(skip(self, offset, config))]
+    pub async fn stream_with_config(
+        &self,
+        offset: Offset,
+        mut config: ConsumerConfig,
+    ) -> Result<impl Stream<Item = Result<Record, ErrorCode>>> {
+        config.consumer_group = Some(self.group.clone());
+
+        let (sender, receiver) = async_channel::bounded(1);
+        let (events_sender, events) = async_channel::unbounded();
+        let applied_generation = Arc::new(AtomicU32::new(0));
+        let membership = GroupMembership {
+            topic: self.topic.clone(),
+            group: self.group.clone(),
+            strategy: self.strategy,
+            session_timeout: self.session_timeout,
+            sc_socket: self.sc_socket.clone(),
+            member_id: String::new(),
+            generation: 0,
+            partitions: vec![],
+            applied_generation: applied_generation.clone(),
+        };
+        let streams = GroupStreams {
+            topic: self.topic.clone(),
+            pool: self.pool.clone(),
+            metrics: self.metrics.clone(),
+            offset,
+            config,
+            partitions: vec![],
+            failed: vec![],
+            applied_generation,
+        };
+        // heartbeats run on their own task, so a slow reader of the stream doesn't expire membership
+        fluvio_future::task::spawn(membership.run(events_sender, sender.clone()));
+        fluvio_future::task::spawn(streams.run(events, sender));
+
+        Ok(receiver)
+    }
+
+    /// Commits the offset after `record` for the group, so the record is not
+    /// consumed again by members of the group.
+    pub async fn commit(&self, record: &Record) -> Result<()> {
+        self.partition_consumer(record.partition)
+            .commit_offset(&self.group, record.offset + 1)
+            .await
+    }
+
+    fn partition_consumer(&self, partition: PartitionId) -> PartitionConsumer {
+        PartitionConsumer::new(
+            self.topic.clone(),
+            partition,
+            self.pool.clone(),
+            self.metrics.clone(),
+        )
+    }
+}
+
+/// Change of membership sent from heartbeat task to streaming task
+enum MembershipEvent {
+    Assigned {
+        generation: u32,
+        partitions: Vec<PartitionId>,
+    },
+    Failed(ErrorCode),
+}
+
+/// Background task keeping membership of the group alive
+struct GroupMembership {
+    topic: String,
+    group: String,
+    strategy: AssignmentStrategy,
+    session_timeout: Duration,
+    sc_socket: Arc<VersionedSerialSocket>,
+    member_id: String,
+    generation: u32,
+    partitions: Vec<PartitionId>,
+    /// generation whose partitions are being streamed
+    applied_generation: Arc<AtomicU32>,
+}
+
+impl GroupMembership {
+    #[instrument(skip(self, events, records), fields(topic = %self.topic, group = %self.group))]
+    async fn run(
+        mut self,
+        events: Sender<MembershipEvent>,
+        records: Sender<Result<Record, ErrorCode>>,
+    ) {
+        // heartbeat several times within session timeout, so a single lost heartbeat is tolerated
+        let heartbeat_interval = self.session_timeout / 3;
+
+        loop {
+            if records.is_closed() || events.is_closed() {
+                debug!("group consumer stream dropped");
+                break;
+            }
+            let event = match self.heartbeat().await {
+                Ok(true) => Some(MembershipEvent::Assigned {
+                    generation: self.generation,
+                    partitions: self.partitions.clone(),
+                }),
+                Ok(false) => None,
+                Err(err) => {
+                    warn!(%err, "consumer group heartbeat failed");
+                    err.downcast_ref::<ErrorCode>()
+                        .map(|error_code| MembershipEvent::Failed(error_code.clone()))
+                }
+            };
+            if let Some(event) = event {
+                if events.send(event).await.is_err() {
+                    break;
+                }
+            }
+            sleep(heartbeat_interval).await;
+        }
+
+        self.leave().await;
+    }
+
+    /// send heartbeat, return true if generation or assigned partitions have changed
+    async fn heartbeat(&mut self) -> Result<bool> {
+        let response = loop {
+            let mut request = ConsumerGroupHeartbeatRequest::new(&self.group, &self.topic);
+            request.member_id = self.member_id.clone();
+            request.generation = self.applied_generation.load(Ordering::SeqCst);
+            request.strategy = self.strategy;
+            request.session_timeout_ms = self.session_timeout.as_millis() as u32;
+
+            let response = self.sc_socket.send_receive(request).await?;
+            match response.error_code {
+                ErrorCode::None => break response,
+                ErrorCode::ConsumerGroupUnknownMember(_) if !self.member_id.is_empty() => {
+                    // session expired, join again as a new member
+                    info!(member_id = %self.member_id, "member expired, rejoining consumer group");
+                    self.member_id.clear();
+                }
+                error_code => return Err(error_code.into()),
+            }
+        };
+
+        self.member_id = response.member_id;
+
+        // partitions revoked from other members are assigned within the same generation
+        let mut partitions = response.partitions;
+        partitions.sort_unstable();
+        if response.generation == self.generation && partitions == self.partitions {
+            return Ok(false);
+        }
+        info!(
+            member_id = %self.member_id,
+            generation = response.generation,
+            ?partitions,
+            "consumer group partitions assigned"
+        );
+        self.generation = response.generation;
+        self.partitions = partitions;
+        Ok(true)
+    }
+
+    async fn leave(&self) {
+        if self.member_id.is_empty() {
+            return;
+        }
+        let request = LeaveConsumerGroupRequest::new(&self.group, &self.member_id);
+        match self.sc_socket.send_receive(request).await {
+            Ok(response) if response.error_code.is_error() => {
+                warn!(error_code = %response.error_code, "failed to leave consumer group")
+            }
+            Ok(_) => info!(member_id = %self.member_id, "left consumer group"),
+            Err(err) => warn!(%err, "failed to leave consumer group"),
+        }
+    }
+}
+
+/// Background task streaming partitions assigned to the member
+struct GroupStreams {
+    topic: String,
+    pool: Arc<SpuPool>,
+    metrics: Arc<ClientMetrics>,
+    offset: Offset,
+    config: ConsumerConfig,
+    partitions: Vec<PartitionId>,
+    /// assigned partitions whose streams failed to start
+    failed: Vec<PartitionId>,
+    applied_generation: Arc<AtomicU32>,
+}
+
+impl GroupStreams {
+    #[instrument(skip(self, events, sender), fields(topic = %self.topic))]
+    async fn run(
+        mut self,
+        events: Receiver<MembershipEvent>,
+        sender: Sender<Result<Record, ErrorCode>>,
+    ) {
+        use tokio::select;
+
+        let mut streams: SelectAll<RecordStream> = SelectAll::new();
+        let mut retry_at = Instant::now();
+
+        loop {
+            let retry_in = retry_at.saturating_duration_since(Instant::now());
+            select! {
+                event = events.recv() => match event {
+                    Ok(MembershipEvent::Assigned { generation, partitions }) => {
+                        if partitions != self.partitions {
+                            self.partitions = partitions;
+                            self.failed.clear();
+                            streams = SelectAll::new();
+                            let errors = self
+                                .start_streams(self.partitions.clone(), &mut streams)
+                                .await;
+                            retry_at = Instant::now() + STREAM_RETRY_INTERVAL;
+                            // consumer is told about partitions which are not streamed yet
+                            for error_code in errors {
+                                if sender.send(Err(error_code)).await.is_err() {
+                                    return;
+                                }
+                            }
+                        }
+                        // revoked partitions are no longer streamed
+                        self.applied_generation.store(generation, Ordering::SeqCst);
+                    }
+                    Ok(MembershipEvent::Failed(error_code)) => {
+                        if sender.send(Err(error_code)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => {
+                        debug!("consumer group membership ended");
+                        break;
+                    }
+                },
+                item = streams.next(), if !streams.is_empty() => {
+                    if let Some(item) = item {
+                        if sender.send(item).await.is_err() {
+                            debug!("group consumer stream dropped");
+                            break;
+                        }
+                    }
+                }
+                _ = sleep(retry_in), if !self.failed.is_empty() => {
+                    let failed = std::mem::take(&mut self.failed);
+                    self.start_streams(failed, &mut streams).await;
+                    retry_at = Instant::now() + STREAM_RETRY_INTERVAL;
+                }
+            }
+        }
+    }
+
+    /// start streams of assigned partitions, from offsets committed by the group.
+    /// Partitions failed to start are retried later, their errors are returned.
+    async fn start_streams(
+        &mut self,
+        partitions: Vec<PartitionId>,
+        streams: &mut SelectAll<RecordStream>,
+    ) -> Vec<ErrorCode> {
+        let mut errors = vec![];
+        for partition in partitions {
+            let consumer = PartitionConsumer::new(
+                self.topic.clone(),
+                partition,
+                self.pool.clone(),
+                self.metrics.clone(),
+            );
+            match consumer
+                .stream_with_config(self.offset.clone(), self.config.clone())
+                .await
+            {
+                Ok(stream) => streams.push(stream.boxed()),
+                Err(err) => {
+                    warn!(%err, partition, "failed to stream partition, will retry");
+                    errors.push(
+                        err.downcast_ref::<ErrorCode>()
+                            .cloned()
+                            .unwrap_or_else(|| ErrorCode::Other(err.to_string())),
+                    );
+                    self.failed.push(partition);
+                }
+            }
+        }
+        errors
+    }
+}
@@ -1,11 +1,12 @@
 use std::convert::TryFrom;
 use std::sync::Arc;
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use tokio::sync::OnceCell;
 use anyhow::{anyhow, Result};
 
 use fluvio_sc_schema::objects::ObjectApiWatchRequest;
+use fluvio_sc_schema::producer::InitProducerIdRequest;
 use fluvio_types::PartitionId;
 use fluvio_socket::{
     ClientConfig, Versions, VersionedSerialSocket, SharedMultiplexerSocket, MultiplexerSocket,
@@ -21,8 +22,9 @@ use crate::FluvioError;
 use crate::FluvioConfig;
 use crate::consumer::MultiplePartitionConsumer;
 use crate::consumer::PartitionSelectionStrategy;
+use crate::consumer_group::GroupConsumer;
 use crate::metrics::ClientMetrics;
-use crate::producer::TopicProducerConfig;
+use crate::producer::{ProducerError, ProducerId, ProducerTransaction, TopicProducerConfig};
 use crate::spu::SpuPool;
 use crate::sync::MetadataStores;
 
@@ -183,7 +185,68 @@ impl Fluvio {
             return Err(FluvioError::TopicNotFound(topic).into());
         }
 
-        TopicProducer::new(topic, spu_pool, config, self.metric.clone()).await
+        let producer_id = self.init_producer_id(&config).await?;
+        let transaction = match (&config.transactional_id, producer_id) {
+            (Some(transactional_id), Some(producer_id)) => Some(ProducerTransaction::new(
+                transactional_id.clone(),
+                producer_id,
+                self.create_serial_client().await,
+            )),
+            _ => None,
+        };
+        TopicProducer::new(
+            topic,
+            spu_pool,
+            config,
+            producer_id,
+            transaction,
+            self.metric.clone(),
+        )
+        .await
+    }
+
+    /// obtain producer id from SC if producer is idempotent
+    async fn init_producer_id(&self, config: &TopicProducerConfig) -> Result<Option<ProducerId>> {
+        let transactional = config.transactional_id.is_some();
+        if !config.is_idempotent() {
+            if transactional {
+                return Err(FluvioError::Producer(ProducerError::InvalidConfiguration(
+                    "transactional producer requires at-least-once delivery".to_owned(),
+                ))
+                .into());
+            }
+            return Ok(None);
+        }
+        let socket = self.create_serial_client().await;
+        if socket.lookup_version::<InitProducerIdRequest>().is_none() {
+            if transactional {
+                return Err(FluvioError::Producer(ProducerError::InvalidConfiguration(
+                    "SC does not support transactional producer".to_owned(),
+                ))
+                .into());
+            }
+            warn!("SC does not support idempotent producer, retried batches may be duplicated");
+            return Ok(None);
+        }
+        let response = socket
+            .send_receive(InitProducerIdRequest {
+                transactional_id: config.transactional_id.clone(),
+            })
+            .await?;
+        if response.error_code.is_error() {
+            return Err(response.error_code.into());
+        }
+        debug!(
+            producer_id = response.producer_id,
+            producer_epoch = response.producer_epoch,
+            transactional,
+            "idempotent producer"
+        );
+        Ok(Some(ProducerId {
+            id: response.producer_id,
+            epoch: response.producer_epoch,
+            transactional,
+        }))
     }
 
     /// Creates a new `PartitionConsumer` for the given topic and partition
@@ -238,6 +301,38 @@ impl Fluvio {
         ))
     }
 
+    /// Creates a new `GroupConsumer` which consumes `topic` as a member of consumer `group`
+    ///
+    /// Partitions of the topic are assigned across live members of the group,
+    /// and reassigned when members join or leave.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use fluvio::{Fluvio, Offset};
+    /// # async fn do_consume_as_group(fluvio: &Fluvio) -> anyhow::Result<()> {
+    /// let consumer = fluvio.group_consumer("my-topic", "my-group").await?;
+    /// let stream = consumer.stream(Offset::beginning()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn group_consumer(
+        &self,
+        topic: impl Into<String>,
+        group: impl Into<String>,
+    ) -> Result<GroupConsumer> {
+        let topic = topic.into();
+        let group = group.into();
+        debug!(topic = &*topic, group = &*group, "Creating group consumer");
+        Ok(GroupConsumer::new(
+            topic,
+            group,
+            self.create_serial_client().await,
+            self.spu_pool().await?,
+            self.metric.clone(),
+        ))
+    }
+
     /// Provides an interface for managing a Fluvio cluster
     ///
     /// # Example
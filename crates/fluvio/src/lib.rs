@@ -11,6 +11,7 @@ mod fluvio;
 mod offset;
 mod producer;
 mod sync;
+mod consumer_group;
 
 pub mod config;
 pub mod consumer;
@@ -32,8 +33,11 @@ pub use fluvio_spu_schema::Isolation;
 pub use consumer::{
     PartitionConsumer, ConsumerConfig, MultiplePartitionConsumer, PartitionSelectionStrategy,
     SmartModuleInvocation, SmartModuleInvocationWasm, SmartModuleKind, SmartModuleContextData,
-    SmartModuleExtraParams,
+    SmartModuleErrorPolicy, SmartModuleExtraParams, SmartModuleDebugConfig, SmartModuleChainTrace,
+    SmartModuleBudget,
 };
+pub use consumer_group::GroupConsumer;
+pub use fluvio_sc_schema::consumer_group::AssignmentStrategy;
 pub use offset::Offset;
 
 pub use crate::admin::FluvioAdmin;
@@ -188,6 +192,10 @@ pub mod metadata {
         pub use fluvio_sc_schema::tableformat::*;
     }
 
+    pub mod quota {
+        pub use fluvio_sc_schema::quota::*;
+    }
+
     pub mod core {
         pub use fluvio_sc_schema::core::*;
     }
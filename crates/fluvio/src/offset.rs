@@ -1,10 +1,13 @@
 use std::io::Error as IoError;
 use std::io::ErrorKind;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use tracing::{debug, trace};
 use fluvio_protocol::record::ReplicaKey;
 use fluvio_spu_schema::server::fetch_offset::FetchOffsetsRequest;
 use fluvio_spu_schema::server::fetch_offset::FetchOffsetPartitionResponse;
+use fluvio_spu_schema::server::fetch_offset::FETCH_OFFSET_TIMESTAMP_API_VERSION;
+use fluvio_types::Timestamp;
 
 use crate::FluvioError;
 use fluvio_socket::VersionedSerialSocket;
@@ -14,6 +17,7 @@ pub(crate) enum OffsetInner {
     Absolute(i64),
     FromBeginning(i64),
     FromEnd(i64),
+    FromTimestamp(Timestamp),
 }
 
 impl OffsetInner {
@@ -28,6 +32,12 @@ impl OffsetInner {
                 let resolved = offsets.last_stable_offset - offset;
                 resolved.clamp(offsets.start_offset, offsets.last_stable_offset)
             }
+            Self::FromTimestamp(_) => {
+                let resolved = offsets
+                    .timestamp_offset
+                    .unwrap_or(offsets.last_stable_offset);
+                resolved.clamp(offsets.start_offset, offsets.last_stable_offset)
+            }
         }
     }
 }
@@ -271,6 +281,39 @@ impl Offset {
         }
     }
 
+    /// Creates an offset pointing to the first event produced at or after `time`
+    ///
+    /// The offset is resolved by the SPU using the timestamps of stored batches,
+    /// so events produced slightly before `time` may be included if they were
+    /// batched together with newer ones. If all events are older than `time`,
+    /// the offset points to the end of the log.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fluvio::Offset;
+    /// use std::time::{Duration, SystemTime};
+    /// // Creates an offset pointing to events produced in the last 2 hours
+    /// let offset: Offset = Offset::from_timestamp(SystemTime::now() - Duration::from_secs(2 * 60 * 60));
+    /// ```
+    pub fn from_timestamp(time: SystemTime) -> Offset {
+        let timestamp = time
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as Timestamp)
+            .unwrap_or_default();
+        Self {
+            inner: OffsetInner::FromTimestamp(timestamp),
+        }
+    }
+
+    /// timestamp in milliseconds, if offset must be resolved by timestamp
+    pub(crate) fn timestamp(&self) -> Option<Timestamp> {
+        match self.inner {
+            OffsetInner::FromTimestamp(timestamp) => Some(timestamp),
+            _ => None,
+        }
+    }
+
     /// Converts this offset into an absolute offset
     ///
     /// If this offset is relative from the beginning (i.e. it was created
@@ -303,15 +346,28 @@ impl Offset {
 pub(crate) async fn fetch_offsets(
     client: &mut VersionedSerialSocket,
     replica: &ReplicaKey,
+    timestamp: Option<Timestamp>,
 ) -> Result<FetchOffsetPartitionResponse, FluvioError> {
     debug!("fetching offset for replica: {}", replica);
 
-    let response = client
-        .send_receive(FetchOffsetsRequest::new(
-            replica.topic.to_owned(),
-            replica.partition,
-        ))
-        .await?;
+    let request = match timestamp {
+        Some(timestamp) => {
+            let version = client.versions().lookup_version::<FetchOffsetsRequest>();
+            if version.unwrap_or_default() < FETCH_OFFSET_TIMESTAMP_API_VERSION {
+                return Err(FluvioError::Other(
+                    "SPU does not support resolving offsets by timestamp".to_owned(),
+                ));
+            }
+            FetchOffsetsRequest::new_with_timestamp(
+                replica.topic.to_owned(),
+                replica.partition,
+                timestamp,
+            )
+        }
+        None => FetchOffsetsRequest::new(replica.topic.to_owned(), replica.partition),
+    };
+
+    let response = client.send_receive(request).await?;
 
     trace!(
         "receive fetch response replica: {}, {:#?}",
@@ -343,6 +399,7 @@ mod tests {
             partition_index: 0,
             start_offset: 0,
             last_stable_offset: 10,
+            timestamp_offset: None,
         };
 
         let offset_inner = OffsetInner::FromBeginning(3);
@@ -357,6 +414,7 @@ mod tests {
             partition_index: 0,
             start_offset: 5,
             last_stable_offset: 10,
+            timestamp_offset: None,
         };
 
         let offset_inner = OffsetInner::FromBeginning(3);
@@ -371,6 +429,7 @@ mod tests {
             partition_index: 0,
             start_offset: 0,
             last_stable_offset: 10,
+            timestamp_offset: None,
         };
 
         let offset_inner = OffsetInner::FromBeginning(15);
@@ -385,6 +444,7 @@ mod tests {
             partition_index: 0,
             start_offset: 5,
             last_stable_offset: 10,
+            timestamp_offset: None,
         };
 
         let offset_inner = OffsetInner::FromBeginning(15);
@@ -399,6 +459,7 @@ mod tests {
             partition_index: 0,
             start_offset: 0,
             last_stable_offset: 10,
+            timestamp_offset: None,
         };
 
         let offset_inner = OffsetInner::FromEnd(3);
@@ -413,6 +474,7 @@ mod tests {
             partition_index: 0,
             start_offset: 6,
             last_stable_offset: 10,
+            timestamp_offset: None,
         };
 
         let offset_inner = OffsetInner::FromEnd(6);
@@ -427,10 +489,39 @@ mod tests {
             partition_index: 0,
             start_offset: 0,
             last_stable_offset: 10,
+            timestamp_offset: None,
         };
 
         let offset_inner = OffsetInner::FromEnd(100);
         let absolute = offset_inner.resolve(&offsets);
         assert_eq!(absolute, 0);
     }
+
+    #[test]
+    fn test_offset_timestamp() {
+        let offsets = FetchOffsetPartitionResponse {
+            error_code: Default::default(),
+            partition_index: 0,
+            start_offset: 5,
+            last_stable_offset: 10,
+            timestamp_offset: Some(7),
+        };
+
+        let offset_inner = OffsetInner::FromTimestamp(1_500_000_000);
+        assert_eq!(offset_inner.resolve(&offsets), 7);
+    }
+
+    #[test]
+    fn test_offset_timestamp_not_resolved() {
+        let offsets = FetchOffsetPartitionResponse {
+            error_code: Default::default(),
+            partition_index: 0,
+            start_offset: 5,
+            last_stable_offset: 10,
+            timestamp_offset: None,
+        };
+
+        let offset_inner = OffsetInner::FromTimestamp(1_500_000_000);
+        assert_eq!(offset_inner.resolve(&offsets), 10);
+    }
 }
@@ -5,7 +5,7 @@ use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
 
-use async_lock::Mutex;
+use async_lock::{Mutex, RwLock};
 use async_channel::Sender;
 use tracing::trace;
 
@@ -55,7 +55,7 @@ impl BatchesDeque {
 pub(crate) struct RecordAccumulator {
     batch_size: usize,
     queue_size: usize,
-    batches: Arc<Vec<BatchHandler>>,
+    batches: RwLock<Vec<BatchHandler>>,
     compression: Compression,
 }
 
@@ -71,7 +71,7 @@ impl RecordAccumulator {
             batches.push((BatchEvents::shared(), BatchesDeque::shared()));
         }
         Self {
-            batches: Arc::new(batches),
+            batches: RwLock::new(batches),
             batch_size,
             compression,
             queue_size,
@@ -86,7 +86,10 @@ impl RecordAccumulator {
     ) -> Result<PushRecord, ProducerError> {
         let (batch_events, batches_lock) = self
             .batches
+            .read()
+            .await
             .get(partition_id as usize)
+            .cloned()
             .ok_or(ProducerError::PartitionNotFound(partition_id))?;
 
         let mut batches = batches_lock.batches.lock().await;
@@ -140,8 +143,24 @@ impl RecordAccumulator {
         }
     }
 
-    pub(crate) fn batches(&self) -> Arc<Vec<BatchHandler>> {
-        self.batches.clone()
+    pub(crate) async fn batches(&self) -> Vec<BatchHandler> {
+        self.batches.read().await.clone()
+    }
+
+    /// Add batches for partitions added to the topic.
+    /// Returns batches of new partitions only.
+    pub(crate) async fn add_partitions(
+        &self,
+        partition_n: PartitionCount,
+    ) -> Vec<(PartitionId, BatchHandler)> {
+        let mut batches = self.batches.write().await;
+        let mut new_batches = vec![];
+        for partition_id in batches.len() as PartitionId..partition_n {
+            let handler = (BatchEvents::shared(), BatchesDeque::shared());
+            batches.push(handler.clone());
+            new_batches.push((partition_id, handler));
+        }
+        new_batches
     }
 }
 
@@ -368,6 +387,7 @@ mod test {
 
         let batches = accumulator
             .batches()
+            .await
             .get(0)
             .expect("failed to get batch info")
             .0
@@ -410,6 +430,29 @@ mod test {
         );
     }
 
+    #[fluvio_future::test]
+    async fn test_record_accumulator_add_partitions() {
+        let accumulator = RecordAccumulator::new(1000, 10, 1, Compression::None);
+
+        assert!(accumulator
+            .push_record(Record::from(("key", "value")), 2)
+            .await
+            .is_err());
+
+        let added = accumulator.add_partitions(3).await;
+        assert_eq!(
+            added.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(accumulator.batches().await.len(), 3);
+        assert!(accumulator.add_partitions(3).await.is_empty());
+
+        accumulator
+            .push_record(Record::from(("key", "value")), 2)
+            .await
+            .expect("failed push");
+    }
+
     #[fluvio_future::test]
     async fn test_produce_partition_response_future_ready() {
         //given
@@ -109,6 +109,21 @@ pub struct TopicProducerConfig {
     #[builder(default = "default_delivery()")]
     pub(crate) delivery_semantic: DeliverySemantic,
 
+    /// Stamp batches with producer id and sequence numbers, so the SPU leader skips batches
+    /// duplicated by retries and rejects batches sent out of order.
+    /// Disabled by default, always enabled for transactional producer.
+    /// Applies only to [`DeliverySemantic::AtLeastOnce`].
+    #[builder(default)]
+    pub(crate) idempotence: bool,
+
+    /// Identifies transactional producer across restarts. Records of transactional producer are
+    /// written in transactions, see [`TopicProducer::begin_transaction`](crate::TopicProducer::begin_transaction).
+    /// New producer with the same id fences previous instances and aborts their open transaction.
+    /// Enables idempotence, requires at-least-once delivery.
+    /// Must be a valid resource name, like topic names.
+    #[builder(setter(into, strip_option), default)]
+    pub(crate) transactional_id: Option<String>,
+
     #[builder(default)]
     pub(crate) smartmodules: Vec<SmartModuleInvocation>,
 }
@@ -127,11 +142,21 @@ impl Default for TopicProducerConfig {
             #[cfg(feature = "stats")]
             stats_collect: default_stats_collect(),
             delivery_semantic: default_delivery(),
+            idempotence: false,
+            transactional_id: None,
             smartmodules: vec![],
         }
     }
 }
 
+impl TopicProducerConfig {
+    /// producer needs producer id and sequence numbers
+    pub(crate) fn is_idempotent(&self) -> bool {
+        (self.idempotence || self.transactional_id.is_some())
+            && matches!(self.delivery_semantic, DeliverySemantic::AtLeastOnce(_))
+    }
+}
+
 /// Defines guarantees that Producer must follow delivering records to SPU.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Hash)]
 pub enum DeliverySemantic {
@@ -345,4 +370,29 @@ mod tests {
         //then
         assert_eq!(iter.collect::<Vec<Duration>>(), [])
     }
+
+    #[test]
+    fn test_idempotence_requires_at_least_once() {
+        //given
+        let default_config = TopicProducerConfig::default();
+        let enabled = TopicProducerConfigBuilder::default()
+            .idempotence(true)
+            .build()
+            .expect("config");
+        let transactional = TopicProducerConfigBuilder::default()
+            .transactional_id("tx")
+            .build()
+            .expect("config");
+        let at_most_once = TopicProducerConfigBuilder::default()
+            .idempotence(true)
+            .delivery_semantic(DeliverySemantic::AtMostOnce)
+            .build()
+            .expect("config");
+
+        //then
+        assert!(!default_config.is_idempotent());
+        assert!(enabled.is_idempotent());
+        assert!(transactional.is_idempotent());
+        assert!(!at_most_once.is_idempotent());
+    }
 }
@@ -27,4 +27,6 @@ pub enum ProducerError {
     ProduceRequestRetryTimeout(#[from] TimeoutError),
     #[error("the batch enqueue timeout limit reached")]
     BatchQueueWaitTimeout,
+    #[error("transaction failed: {0}")]
+    Transaction(String),
 }
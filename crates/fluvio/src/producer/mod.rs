@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use tracing::instrument;
+use tracing::{debug, instrument};
 use async_lock::RwLock;
 use anyhow::Result;
 
@@ -8,7 +8,7 @@ use fluvio_protocol::record::ReplicaKey;
 use fluvio_protocol::record::Record;
 use fluvio_compression::Compression;
 use fluvio_sc_schema::topic::CompressionAlgorithm;
-use fluvio_types::PartitionId;
+use fluvio_types::{PartitionCount, PartitionId};
 use fluvio_types::event::StickyEvent;
 
 mod accumulator;
@@ -19,10 +19,12 @@ mod record;
 mod partitioning;
 mod partition_producer;
 mod memory_batch;
+mod sequence;
+mod transaction;
 
 pub mod event;
 
-pub use fluvio_protocol::record::{RecordKey, RecordData};
+pub use fluvio_protocol::record::{RecordKey, RecordData, Header};
 
 use crate::FluvioError;
 use crate::metrics::ClientMetrics;
@@ -41,13 +43,26 @@ pub use self::error::ProducerError;
 use self::event::EventHandler;
 pub use self::output::ProduceOutput;
 use self::partition_producer::PartitionProducer;
+pub(crate) use self::sequence::ProducerId;
+pub(crate) use self::transaction::ProducerTransaction;
 pub use self::record::{FutureRecordMetadata, RecordMetadata};
 
 /// Pool of producers for a given topic. There is a producer per partition
 struct ProducerPool {
-    flush_events: Vec<(Arc<EventHandler>, Arc<EventHandler>)>,
-    end_events: Vec<Arc<StickyEvent>>,
-    errors: Vec<Arc<RwLock<Option<ProducerError>>>>,
+    config: Arc<TopicProducerConfig>,
+    topic: String,
+    spu_pool: Arc<SpuPool>,
+    client_metric: Arc<ClientMetrics>,
+    producer_id: Option<ProducerId>,
+    producers: RwLock<Vec<PartitionProducerHandle>>,
+}
+
+/// Events and last error of partition producer
+#[derive(Clone)]
+struct PartitionProducerHandle {
+    flush_event: (Arc<EventHandler>, Arc<EventHandler>),
+    end_event: Arc<StickyEvent>,
+    error: Arc<RwLock<Option<ProducerError>>>,
 }
 
 impl ProducerPool {
@@ -55,46 +70,34 @@ impl ProducerPool {
         config: Arc<TopicProducerConfig>,
         topic: String,
         spu_pool: Arc<SpuPool>,
-        batches: Arc<Vec<BatchHandler>>,
+        batches: Vec<BatchHandler>,
         client_metric: Arc<ClientMetrics>,
+        producer_id: Option<ProducerId>,
     ) -> Self {
-        let mut end_events = vec![];
-        let mut flush_events = vec![];
-        let mut errors = vec![];
-        for (partition_id, (batch_events, batch_list)) in batches.iter().enumerate() {
-            let end_event = StickyEvent::shared();
-            let flush_event = (EventHandler::shared(), EventHandler::shared());
-            let replica = ReplicaKey::new(topic.clone(), partition_id as PartitionId);
-            let error = Arc::new(RwLock::new(None));
-
-            PartitionProducer::start(
-                config.clone(),
-                replica,
-                spu_pool.clone(),
-                batch_list.clone(),
-                batch_events.clone(),
-                error.clone(),
-                end_event.clone(),
-                flush_event.clone(),
-                client_metric.clone(),
-            );
-            errors.push(error);
-            end_events.push(end_event);
-            flush_events.push(flush_event);
-        }
-        Self {
-            end_events,
-            flush_events,
-            errors,
-        }
+        let mut pool = Self {
+            config,
+            topic,
+            spu_pool,
+            client_metric,
+            producer_id,
+            producers: RwLock::new(vec![]),
+        };
+        let producers = batches
+            .into_iter()
+            .enumerate()
+            .map(|(partition_id, batch)| pool.start_producer(partition_id as PartitionId, batch))
+            .collect();
+        *pool.producers.get_mut() = producers;
+        pool
     }
 
     fn shared(
         config: Arc<TopicProducerConfig>,
         topic: String,
         spu_pool: Arc<SpuPool>,
-        batches: Arc<Vec<BatchHandler>>,
+        batches: Vec<BatchHandler>,
         client_metric: Arc<ClientMetrics>,
+        producer_id: Option<ProducerId>,
     ) -> Arc<Self> {
         Arc::new(ProducerPool::new(
             config,
@@ -102,18 +105,64 @@ impl ProducerPool {
             spu_pool,
             batches,
             client_metric,
+            producer_id,
         ))
     }
 
+    fn start_producer(
+        &self,
+        partition_id: PartitionId,
+        (batch_events, batch_list): BatchHandler,
+    ) -> PartitionProducerHandle {
+        let end_event = StickyEvent::shared();
+        let flush_event = (EventHandler::shared(), EventHandler::shared());
+        let replica = ReplicaKey::new(self.topic.clone(), partition_id);
+        let error = Arc::new(RwLock::new(None));
+
+        PartitionProducer::start(
+            self.config.clone(),
+            replica,
+            self.spu_pool.clone(),
+            batch_list,
+            batch_events,
+            error.clone(),
+            end_event.clone(),
+            flush_event.clone(),
+            self.client_metric.clone(),
+            self.producer_id,
+        );
+        PartitionProducerHandle {
+            flush_event,
+            end_event,
+            error,
+        }
+    }
+
+    /// Start producers for partitions added to the topic after producer was created
+    async fn add_partitions(
+        &self,
+        record_accumulator: &RecordAccumulator,
+        partition_count: PartitionCount,
+    ) {
+        if self.producers.read().await.len() >= partition_count as usize {
+            return;
+        }
+        let mut producers = self.producers.write().await;
+        for (partition_id, batch) in record_accumulator.add_partitions(partition_count).await {
+            debug!(topic = %self.topic, partition_id, "starting producer for new partition");
+            producers.push(self.start_producer(partition_id, batch));
+        }
+    }
+
     async fn flush_all_batches(&self) -> Result<()> {
-        for ((manual_flush_notifier, batch_flushed_event), error) in
-            self.flush_events.iter().zip(self.errors.iter())
-        {
+        let producers = self.producers.read().await.clone();
+        for producer in producers {
+            let (manual_flush_notifier, batch_flushed_event) = &producer.flush_event;
             let listener = batch_flushed_event.listen();
             manual_flush_notifier.notify().await;
             listener.await;
             {
-                let error_handle = error.read().await;
+                let error_handle = producer.error.read().await;
                 if let Some(error) = &*error_handle {
                     return Err(error.clone().into());
                 }
@@ -124,20 +173,27 @@ impl ProducerPool {
     }
 
     async fn last_error(&self, partition_id: PartitionId) -> Option<ProducerError> {
-        let error = self.errors[partition_id as usize].read().await;
+        let error = self
+            .producers
+            .read()
+            .await
+            .get(partition_id as usize)?
+            .error
+            .clone();
+        let error = error.read().await;
         error.clone()
     }
 
     async fn clear_errors(&self) {
-        for error in self.errors.iter() {
-            let mut error_handle = error.write().await;
+        for producer in self.producers.read().await.iter() {
+            let mut error_handle = producer.error.write().await;
             *error_handle = None;
         }
     }
 
-    fn end(&self) {
-        for event in &self.end_events {
-            event.notify();
+    fn end(&mut self) {
+        for producer in self.producers.get_mut().iter() {
+            producer.end_event.notify();
         }
     }
 }
@@ -168,6 +224,7 @@ struct InnerTopicProducer {
     spu_pool: Arc<SpuPool>,
     record_accumulator: RecordAccumulator,
     producer_pool: Arc<ProducerPool>,
+    transaction: Option<ProducerTransaction>,
 }
 
 impl InnerTopicProducer {
@@ -188,6 +245,11 @@ impl InnerTopicProducer {
         let partition_count = topic_spec.partitions();
         let partition_config = PartitionerConfig { partition_count };
 
+        // partitions may have been added to the topic
+        self.producer_pool
+            .add_partitions(&self.record_accumulator, partition_count)
+            .await;
+
         let key = record.key.as_ref().map(|k| k.as_ref());
         let value = record.value.as_ref();
         let partition = self
@@ -199,6 +261,12 @@ impl InnerTopicProducer {
             return Err(error.into());
         }
 
+        if let Some(transaction) = &self.transaction {
+            transaction
+                .add_partition(ReplicaKey::new(self.topic.clone(), partition))
+                .await?;
+        }
+
         let push_record = self
             .record_accumulator
             .push_record(record, partition)
@@ -210,6 +278,14 @@ impl InnerTopicProducer {
     async fn clear_errors(&self) {
         self.producer_pool.clear_errors().await;
     }
+
+    fn transaction(&self) -> Result<&ProducerTransaction, ProducerError> {
+        self.transaction.as_ref().ok_or_else(|| {
+            ProducerError::InvalidConfiguration(
+                "transactions require transactional_id in producer config".to_owned(),
+            )
+        })
+    }
 }
 
 cfg_if::cfg_if! {
@@ -313,6 +389,8 @@ impl TopicProducer {
         topic: String,
         spu_pool: Arc<SpuPool>,
         config: TopicProducerConfig,
+        producer_id: Option<ProducerId>,
+        transaction: Option<ProducerTransaction>,
         metrics: Arc<ClientMetrics>,
     ) -> Result<Self> {
         let config = Arc::new(config);
@@ -370,8 +448,9 @@ impl TopicProducer {
             config.clone(),
             topic.clone(),
             spu_pool.clone(),
-            record_accumulator.batches(),
+            record_accumulator.batches().await,
             metrics.clone(),
+            producer_id,
         );
 
         Ok(Self {
@@ -381,6 +460,7 @@ impl TopicProducer {
                 spu_pool,
                 producer_pool,
                 record_accumulator,
+                transaction,
             }),
             #[cfg(feature = "smartengine")]
             sm_chain: Default::default(),
@@ -403,6 +483,67 @@ impl TopicProducer {
         self.inner.flush().await
     }
 
+    /// Starts a transaction of transactional producer.
+    ///
+    /// Records sent in the transaction are visible to
+    /// [`Isolation::ReadCommitted`](crate::Isolation::ReadCommitted) consumers only after
+    /// the transaction is committed. Records of aborted transaction are never visible to them.
+    /// Transactional producer can send records only inside a transaction.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn example(producer: &TopicProducer) -> anyhow::Result<()> {
+    /// producer.begin_transaction().await?;
+    /// producer.send("Key", "Value").await?;
+    /// producer.commit_transaction().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn begin_transaction(&self) -> Result<()> {
+        self.inner.transaction()?.begin().await?;
+        Ok(())
+    }
+
+    /// Adds offsets of consumer group to the transaction.
+    /// Offsets are committed only if the transaction is committed,
+    /// so records are consumed and produced atomically.
+    pub async fn send_offsets_to_transaction(
+        &self,
+        consumer_group: impl Into<String>,
+        offsets: impl IntoIterator<Item = (ReplicaKey, i64)>,
+    ) -> Result<()> {
+        self.inner
+            .transaction()?
+            .add_offsets(consumer_group.into(), offsets.into_iter().collect())
+            .await?;
+        Ok(())
+    }
+
+    /// Sends all the queued records and commits the transaction.
+    /// If any record failed to be sent, the transaction must be aborted.
+    pub async fn commit_transaction(&self) -> Result<()> {
+        let transaction = self.inner.transaction()?;
+        self.inner.flush().await?;
+        transaction.end(true).await?;
+        Ok(())
+    }
+
+    /// Aborts the transaction, its records are not visible to
+    /// [`Isolation::ReadCommitted`](crate::Isolation::ReadCommitted) consumers.
+    /// Records which failed to be sent may leave sequence numbers out of order,
+    /// then producer has to be created again to continue.
+    pub async fn abort_transaction(&self) -> Result<()> {
+        let transaction = self.inner.transaction()?;
+        if let Err(err) = self.inner.flush().await {
+            debug!(%err, "failed to flush records of aborted transaction");
+        }
+        self.inner.clear_errors().await;
+        transaction.end(false).await?;
+        Ok(())
+    }
+
     /// Sends a key/value record to this producer's Topic.
     ///
     /// The partition that the record will be sent to is derived from the Key.
@@ -434,7 +575,63 @@ impl TopicProducer {
         let record_key = key.into();
         let record_value = value.into();
         let record = Record::from((record_key, record_value));
+        self.send_record(record).await
+    }
+
+    /// Sends a key/value record with headers to this producer's Topic.
+    ///
+    /// Headers carry metadata such as trace ids or content types apart from the value.
+    /// Records with headers are rejected by SPUs which don't support them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn example(producer: &TopicProducer) -> anyhow::Result<()> {
+    /// producer
+    ///     .send_with_headers("Key", "Value", [("trace-id", "abc")])
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(
+        skip(self, key, value, headers),
+        fields(topic = %self.inner.topic),
+    )]
+    pub async fn send_with_headers(
+        &self,
+        key: impl Into<RecordKey>,
+        value: impl Into<RecordData>,
+        headers: impl IntoIterator<Item = impl Into<Header>>,
+    ) -> Result<ProduceOutput> {
+        let mut record = Record::from((key.into(), value.into()));
+        record.headers = headers.into_iter().map(Into::into).collect();
+        self.send_record(record).await
+    }
+
+    /// Sends a tombstone for the key to this producer's Topic.
+    ///
+    /// Compacted topics remove previous records of the key and eventually the tombstone itself.
+    /// Tombstones are marked with a header, so they are rejected by SPUs which don't support headers.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use fluvio::{TopicProducer, FluvioError};
+    /// # async fn example(producer: &TopicProducer) -> anyhow::Result<()> {
+    /// producer.send_tombstone("Key").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[instrument(
+        skip(self, key),
+        fields(topic = %self.inner.topic),
+    )]
+    pub async fn send_tombstone(&self, key: impl Into<RecordKey>) -> Result<ProduceOutput> {
+        self.send_record(Record::tombstone(key)).await
+    }
 
+    async fn send_record(&self, record: Record) -> Result<ProduceOutput> {
         cfg_if::cfg_if! {
             if #[cfg(feature = "smartengine")] {
                 let mut entries = vec![record];
@@ -1,11 +1,14 @@
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use async_lock::{RwLock};
-use tracing::{debug, info, instrument, error, trace};
+use tracing::{debug, info, instrument, error, trace, warn};
 
+use fluvio_protocol::link::ErrorCode;
 use fluvio_protocol::record::ReplicaKey;
 use fluvio_protocol::record::{RawRecords, Batch};
 use fluvio_spu_schema::produce::{DefaultPartitionRequest, DefaultTopicRequest, DefaultProduceRequest};
+use fluvio_spu_schema::RECORD_HEADERS_API_VERSION;
 use fluvio_future::timer::sleep;
 use fluvio_types::SpuId;
 use fluvio_types::event::StickyEvent;
@@ -21,6 +24,7 @@ use crate::TopicProducerConfig;
 use super::ProducerError;
 use super::accumulator::{BatchEvents, BatchesDeque};
 use super::event::EventHandler;
+use super::sequence::{PartitionSequence, ProducerId};
 
 /// Struct that is responsible for sending produce requests to the SPU in a given partition.
 pub(crate) struct PartitionProducer {
@@ -31,9 +35,14 @@ pub(crate) struct PartitionProducer {
     batch_events: Arc<BatchEvents>,
     last_error: Arc<RwLock<Option<ProducerError>>>,
     metrics: Arc<ClientMetrics>,
+    /// sequence numbers if producer is idempotent
+    sequence: Option<Mutex<PartitionSequence>>,
+    /// SPU throttled producer for exceeding quota, no requests are sent until then
+    throttled_until: Mutex<Option<Instant>>,
 }
 
 impl PartitionProducer {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         config: Arc<TopicProducerConfig>,
         replica: ReplicaKey,
@@ -42,6 +51,7 @@ impl PartitionProducer {
         batch_events: Arc<BatchEvents>,
         last_error: Arc<RwLock<Option<ProducerError>>>,
         metrics: Arc<ClientMetrics>,
+        producer_id: Option<ProducerId>,
     ) -> Self {
         Self {
             config,
@@ -51,9 +61,12 @@ impl PartitionProducer {
             batch_events,
             last_error,
             metrics,
+            sequence: producer_id.map(|id| Mutex::new(PartitionSequence::new(id))),
+            throttled_until: Mutex::new(None),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn shared(
         config: Arc<TopicProducerConfig>,
         replica: ReplicaKey,
@@ -62,6 +75,7 @@ impl PartitionProducer {
         batch_events: Arc<BatchEvents>,
         error: Arc<RwLock<Option<ProducerError>>>,
         metrics: Arc<ClientMetrics>,
+        producer_id: Option<ProducerId>,
     ) -> Arc<Self> {
         Arc::new(PartitionProducer::new(
             config,
@@ -71,6 +85,7 @@ impl PartitionProducer {
             batch_events,
             error,
             metrics,
+            producer_id,
         ))
     }
 
@@ -85,6 +100,7 @@ impl PartitionProducer {
         end_event: Arc<StickyEvent>,
         flush_event: (Arc<EventHandler>, Arc<EventHandler>),
         metrics: Arc<ClientMetrics>,
+        producer_id: Option<ProducerId>,
     ) {
         let producer = PartitionProducer::shared(
             config,
@@ -94,6 +110,7 @@ impl PartitionProducer {
             batch_events,
             error,
             metrics,
+            producer_id,
         );
         fluvio_future::task::spawn(async move {
             producer.run(end_event, flush_event).await;
@@ -215,6 +232,10 @@ impl PartitionProducer {
 
         let mut batch_notifiers = vec![];
 
+        let headers_supported = spu_socket
+            .lookup_version::<DefaultProduceRequest>()
+            .map_or(false, |version| version >= RECORD_HEADERS_API_VERSION);
+
         for p_batch in batches_ready {
             let mut partition_request = DefaultPartitionRequest {
                 partition_index: self.replica.partition,
@@ -223,7 +244,41 @@ impl PartitionProducer {
             let notify = p_batch.notify.clone();
             let batch = p_batch.batch();
 
-            let raw_batch: Batch<RawRecords> = batch.try_into()?;
+            if !headers_supported
+                && batch
+                    .records()
+                    .iter()
+                    .any(|record| !record.headers().is_empty())
+            {
+                warn!(replica = %self.replica, "SPU does not support record headers, batch rejected");
+                let error =
+                    ErrorCode::Other("record headers are not supported by the SPU".to_owned());
+                if let Err(_e) = notify
+                    .send(ProducePartitionResponseFuture::ready(-1, error))
+                    .await
+                {
+                    trace!("Failed to notify produce result because receiver was dropped");
+                }
+                continue;
+            }
+
+            let mut raw_batch: Batch<RawRecords> = batch.try_into()?;
+            if let Some(sequence) = &self.sequence {
+                let stamped = sequence
+                    .lock()
+                    .expect("sequence lock poisoned")
+                    .stamp(&mut raw_batch);
+                if let Err(error) = stamped {
+                    warn!(replica = %self.replica, %error, "producer fenced, batch rejected");
+                    if let Err(_e) = notify
+                        .send(ProducePartitionResponseFuture::ready(-1, error))
+                        .await
+                    {
+                        trace!("Failed to notify produce result because receiver was dropped");
+                    }
+                    continue;
+                }
+            }
 
             let producer_metrics = self.metrics.producer_client();
             producer_metrics.add_records(raw_batch.records_len() as u64);
@@ -234,12 +289,25 @@ impl PartitionProducer {
             topic_request.partitions.push(partition_request);
         }
 
+        if batch_notifiers.is_empty() {
+            return Ok(());
+        }
+
+        self.wait_throttle().await;
+
+        request.transactional_id = self.config.transactional_id.clone();
         request.isolation = self.config.isolation;
         request.timeout = self.config.timeout;
         request.smartmodules = self.config.smartmodules.clone();
         request.topics.push(topic_request);
 
-        let (response, _) = self.send_to_socket(spu_socket, request).await?;
+        let (response, _) = match self.send_to_socket(spu_socket, request).await {
+            Ok(response) => response,
+            Err(err) => {
+                self.bump_epoch();
+                return Err(err);
+            }
+        };
 
         for (batch_notifier, partition_response_fut) in
             batch_notifiers.into_iter().zip(response.into_iter())
@@ -252,6 +320,42 @@ impl PartitionProducer {
         Ok(())
     }
 
+    /// start new sequence epoch after batches may have been lost
+    fn bump_epoch(&self) {
+        if let Some(sequence) = &self.sequence {
+            let mut sequence = sequence.lock().expect("sequence lock poisoned");
+            sequence.bump_epoch();
+            debug!(replica = %self.replica, ?sequence, "producer epoch bumped");
+        }
+    }
+
+    /// back off for throttle time set by SPU
+    fn throttle(&self, throttle_time_ms: i32) {
+        if throttle_time_ms <= 0 {
+            return;
+        }
+        debug!(replica = %self.replica, throttle_time_ms, "producer throttled by quota");
+        let until = Instant::now() + Duration::from_millis(throttle_time_ms as u64);
+        let mut throttled_until = self.throttled_until.lock().expect("throttle lock poisoned");
+        if throttled_until.map_or(true, |current| current < until) {
+            *throttled_until = Some(until);
+        }
+    }
+
+    async fn wait_throttle(&self) {
+        let throttled_until = self
+            .throttled_until
+            .lock()
+            .expect("throttle lock poisoned")
+            .take();
+        if let Some(until) = throttled_until {
+            let remaining = until.saturating_duration_since(Instant::now());
+            if !remaining.is_zero() {
+                sleep(remaining).await;
+            }
+        }
+    }
+
     async fn send_to_socket(
         &self,
         socket: VersionedSerialSocket,
@@ -276,10 +380,15 @@ impl PartitionProducer {
                     .timeout(policy.timeout)
                     .await
                     .map_err(|timeout_err| FluvioError::Producer(timeout_err.into()))??;
+                self.throttle(produce_response.throttle_time_ms);
 
                 let mut futures = Vec::with_capacity(partition_count);
+                let mut rejected = false;
                 for topic in produce_response.responses.into_iter() {
                     for partition in topic.partitions {
+                        // duplicate batch is already written, its sequence is accepted
+                        rejected |= partition.error_code.is_error()
+                            && partition.error_code != ErrorCode::DuplicateSequenceNumber;
                         futures.push(ProducePartitionResponseFuture::ready(
                             partition.base_offset,
                             partition.error_code,
@@ -287,6 +396,10 @@ impl PartitionProducer {
                         last_offset = Some(partition.base_offset);
                     }
                 }
+                if rejected {
+                    // sequences of rejected batches were not accepted by the leader
+                    self.bump_epoch();
+                }
                 futures
             }
         };
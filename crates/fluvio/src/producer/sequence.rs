@@ -0,0 +1,173 @@
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::Batch;
+
+/// Producer id and epoch assigned by SC to idempotent producer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ProducerId {
+    pub(crate) id: i64,
+    pub(crate) epoch: i16,
+    /// epoch of transactional producer is assigned by SC only
+    pub(crate) transactional: bool,
+}
+
+/// Sequence numbers of batches sent by idempotent producer to a single partition.
+///
+/// SPU leader accepts batch only if its first sequence continues the last accepted batch.
+/// If batches are lost, producer starts a new epoch from sequence 0.
+/// Once all epochs are used, producer is fenced and can't send batches anymore.
+#[derive(Debug)]
+pub(crate) struct PartitionSequence {
+    producer_id: i64,
+    epoch: i16,
+    next_sequence: i32,
+    transactional: bool,
+    fenced: bool,
+}
+
+impl PartitionSequence {
+    pub(crate) fn new(producer_id: ProducerId) -> Self {
+        Self {
+            producer_id: producer_id.id,
+            epoch: producer_id.epoch,
+            next_sequence: 0,
+            transactional: producer_id.transactional,
+            fenced: false,
+        }
+    }
+
+    /// set producer id, epoch and first sequence of the batch
+    pub(crate) fn stamp<R>(&mut self, batch: &mut Batch<R>) -> Result<(), ErrorCode> {
+        let count = batch.records_len() as i32;
+        if self.next_sequence.checked_add(count).is_none() {
+            self.bump_epoch();
+        }
+        if self.fenced {
+            return Err(ErrorCode::ProducerEpochExhausted);
+        }
+        let header = batch.get_mut_header();
+        header.producer_id = self.producer_id;
+        header.producer_epoch = self.epoch;
+        header.first_sequence = self.next_sequence;
+        if self.transactional {
+            header.set_transactional();
+        }
+        self.next_sequence += count;
+        Ok(())
+    }
+
+    /// start new epoch, used when it's unknown if sent batches were written.
+    /// Transactional producer keeps its epoch, new epoch would not be fenced by SC,
+    /// failed transaction has to be aborted instead.
+    pub(crate) fn bump_epoch(&mut self) {
+        if self.transactional {
+            return;
+        }
+        match self.epoch.checked_add(1) {
+            Some(epoch) => {
+                self.epoch = epoch;
+                self.next_sequence = 0;
+            }
+            None => self.fenced = true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fluvio_protocol::record::{Batch, Record};
+
+    use super::*;
+
+    fn batch(records: usize) -> Batch {
+        let mut batch = Batch::default();
+        for _ in 0..records {
+            batch.add_record(Record::new("value"));
+        }
+        batch
+    }
+
+    #[test]
+    fn test_stamp_sequences() {
+        //given
+        let mut sequence = PartitionSequence::new(ProducerId {
+            id: 10,
+            epoch: 2,
+            transactional: false,
+        });
+        let mut first = batch(3);
+        let mut second = batch(2);
+
+        //when
+        sequence.stamp(&mut first).expect("stamp");
+        sequence.stamp(&mut second).expect("stamp");
+
+        //then
+        assert_eq!(first.get_header().producer_id, 10);
+        assert_eq!(first.get_header().producer_epoch, 2);
+        assert_eq!(first.get_header().first_sequence, 0);
+        assert_eq!(second.get_header().first_sequence, 3);
+    }
+
+    #[test]
+    fn test_bump_epoch_resets_sequence() {
+        //given
+        let mut sequence = PartitionSequence::new(ProducerId {
+            id: 10,
+            epoch: 0,
+            transactional: false,
+        });
+        let mut first = batch(3);
+        let mut second = batch(1);
+        sequence.stamp(&mut first).expect("stamp");
+
+        //when
+        sequence.bump_epoch();
+        sequence.stamp(&mut second).expect("stamp");
+
+        //then
+        assert_eq!(second.get_header().producer_epoch, 1);
+        assert_eq!(second.get_header().first_sequence, 0);
+    }
+
+    #[test]
+    fn test_transactional_keeps_epoch() {
+        //given
+        let mut sequence = PartitionSequence::new(ProducerId {
+            id: 10,
+            epoch: 3,
+            transactional: true,
+        });
+        let mut first = batch(2);
+        let mut second = batch(1);
+
+        //when
+        sequence.stamp(&mut first).expect("stamp");
+        sequence.bump_epoch();
+        sequence.stamp(&mut second).expect("stamp");
+
+        //then
+        assert!(first.get_header().is_transactional());
+        assert_eq!(second.get_header().producer_epoch, 3);
+        assert_eq!(second.get_header().first_sequence, 2);
+    }
+
+    #[test]
+    fn test_exhausted_epoch_fences_producer() {
+        //given
+        let mut sequence = PartitionSequence::new(ProducerId {
+            id: 10,
+            epoch: i16::MAX,
+            transactional: false,
+        });
+        let mut first = batch(1);
+
+        //when
+        sequence.bump_epoch();
+
+        //then
+        assert_eq!(
+            sequence.stamp(&mut first),
+            Err(ErrorCode::ProducerEpochExhausted)
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use std::collections::HashSet;
+
+use async_lock::Mutex;
+use tracing::debug;
+
+use fluvio_protocol::api::Request;
+use fluvio_protocol::link::ErrorCode;
+use fluvio_protocol::record::{Offset, ReplicaKey};
+use fluvio_sc_schema::transaction::{
+    AddOffsetsToTxnRequest, AddPartitionsToTxnRequest, EndTxnRequest, TxnConsumerOffset,
+};
+use fluvio_socket::VersionedSerialSocket;
+
+use super::ProducerError;
+use super::sequence::ProducerId;
+
+#[derive(Debug, Default)]
+struct TransactionState {
+    ongoing: bool,
+    /// partitions registered in the transaction coordinator
+    partitions: HashSet<ReplicaKey>,
+}
+
+/// Transaction of transactional producer.
+///
+/// SC is the transaction coordinator, partitions are registered in it before the first
+/// record of the transaction is sent to them. When transaction ends, SC writes control
+/// batches to all registered partitions.
+pub(crate) struct ProducerTransaction {
+    transactional_id: String,
+    producer_id: ProducerId,
+    socket: VersionedSerialSocket,
+    state: Mutex<TransactionState>,
+}
+
+impl ProducerTransaction {
+    pub(crate) fn new(
+        transactional_id: String,
+        producer_id: ProducerId,
+        socket: VersionedSerialSocket,
+    ) -> Self {
+        Self {
+            transactional_id,
+            producer_id,
+            socket,
+            state: Mutex::new(TransactionState::default()),
+        }
+    }
+
+    pub(crate) async fn begin(&self) -> Result<(), ProducerError> {
+        let mut state = self.state.lock().await;
+        if state.ongoing {
+            return Err(ProducerError::Transaction(
+                "transaction is already in progress".to_owned(),
+            ));
+        }
+        state.ongoing = true;
+        Ok(())
+    }
+
+    /// register partition in the transaction before records are sent to it
+    pub(crate) async fn add_partition(&self, replica: ReplicaKey) -> Result<(), ProducerError> {
+        let mut state = self.state.lock().await;
+        if !state.ongoing {
+            return Err(ProducerError::Transaction(
+                "transactional producer requires a transaction, call begin_transaction first"
+                    .to_owned(),
+            ));
+        }
+        if state.partitions.contains(&replica) {
+            return Ok(());
+        }
+        debug!(%replica, "adding partition to transaction");
+        let request = AddPartitionsToTxnRequest {
+            transactional_id: self.transactional_id.clone(),
+            producer_id: self.producer_id.id,
+            producer_epoch: self.producer_id.epoch,
+            partitions: vec![replica.clone()],
+        };
+        let response = self.send(request).await?;
+        check(response.error_code)?;
+        state.partitions.insert(replica);
+        Ok(())
+    }
+
+    /// consumer offsets are committed only if the transaction is committed
+    pub(crate) async fn add_offsets(
+        &self,
+        consumer_group: String,
+        offsets: Vec<(ReplicaKey, Offset)>,
+    ) -> Result<(), ProducerError> {
+        let state = self.state.lock().await;
+        if !state.ongoing {
+            return Err(ProducerError::Transaction(
+                "no transaction in progress".to_owned(),
+            ));
+        }
+        let request = AddOffsetsToTxnRequest {
+            transactional_id: self.transactional_id.clone(),
+            producer_id: self.producer_id.id,
+            producer_epoch: self.producer_id.epoch,
+            consumer_group,
+            offsets: offsets
+                .into_iter()
+                .map(|(replica, offset)| TxnConsumerOffset { replica, offset })
+                .collect(),
+        };
+        let response = self.send(request).await?;
+        check(response.error_code)
+    }
+
+    /// commit or abort the transaction, records must be flushed before
+    pub(crate) async fn end(&self, commit: bool) -> Result<(), ProducerError> {
+        let mut state = self.state.lock().await;
+        if !state.ongoing {
+            return Err(ProducerError::Transaction(
+                "no transaction in progress".to_owned(),
+            ));
+        }
+        debug!(
+            commit,
+            partitions = state.partitions.len(),
+            "ending transaction"
+        );
+        let request = EndTxnRequest {
+            transactional_id: self.transactional_id.clone(),
+            producer_id: self.producer_id.id,
+            producer_epoch: self.producer_id.epoch,
+            commit,
+        };
+        let response = self.send(request).await?;
+        check(response.error_code)?;
+        *state = TransactionState::default();
+        Ok(())
+    }
+
+    async fn send<R: Request + Send + Sync>(
+        &self,
+        request: R,
+    ) -> Result<R::Response, ProducerError> {
+        self.socket
+            .send_receive(request)
+            .await
+            .map_err(|err| ProducerError::Transaction(err.to_string()))
+    }
+}
+
+fn check(error_code: ErrorCode) -> Result<(), ProducerError> {
+    if error_code.is_error() {
+        Err(ProducerError::Transaction(error_code.to_string()))
+    } else {
+        Ok(())
+    }
+}
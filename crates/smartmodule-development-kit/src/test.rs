@@ -20,7 +20,9 @@ use fluvio_smartengine::transformation::TransformationConfig;
 use fluvio_smartengine::{
     SmartEngine, SmartModuleChainBuilder, SmartModuleConfig, SmartModuleChainInstance, Lookback,
 };
-use fluvio_smartmodule::dataplane::smartmodule::SmartModuleInput;
+use fluvio_smartmodule::dataplane::smartmodule::{
+    SmartModuleChainTrace, SmartModuleDebugConfig, SmartModuleInput,
+};
 use fluvio_protocol::record::Record;
 use fluvio_cli_common::user_input::{UserInputRecords, UserInputType};
 
@@ -92,6 +94,11 @@ pub struct TestCmd {
     /// Sets the lookback parameter to the last N records.
     #[arg(long, short)]
     lookback_last: Option<u64>,
+
+    /// Print input and output of each SmartModule in the chain, and messages logged by
+    /// SmartModules, to stderr
+    #[arg(long)]
+    trace: bool,
 }
 
 fn parse_key_val(s: &str) -> Result<(String, String)> {
@@ -175,7 +182,13 @@ impl TestCmd {
 
         sm_input.set_base_timestamp(Utc::now().timestamp_millis());
 
+        if self.trace {
+            chain.set_debug(Some(SmartModuleDebugConfig::default()));
+        }
         let output = chain.process(sm_input, &metrics)?;
+        if self.trace {
+            print_trace(chain.take_trace());
+        }
 
         if self.verbose {
             println!("{:?} records outputed", output.successes.len());
@@ -202,6 +215,31 @@ impl TestCmd {
     }
 }
 
+fn print_trace(trace: SmartModuleChainTrace) {
+    for step in trace.records {
+        let output = match step.error {
+            Some(error) => format!("error: {error}"),
+            None if step.output.is_empty() => "dropped".to_string(),
+            None => step
+                .output
+                .iter()
+                .map(|record| record.value.as_utf8_lossy_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        };
+        eprintln!(
+            "[trace] offset {} step {} ({}): {} -> {output}",
+            step.offset,
+            step.step,
+            step.smartmodule,
+            step.input.value.as_utf8_lossy_string(),
+        );
+    }
+    for log in trace.logs {
+        eprintln!("[log] step {}: {}", log.step, log.message);
+    }
+}
+
 async fn look_back(chain: &mut SmartModuleChainInstance, records: Vec<String>) -> Result<()> {
     let records: Vec<Record> = records
         .into_iter()
@@ -0,0 +1,33 @@
+//! Map SmartModule built as WebAssembly component, uppercases values and keeps keys and headers.
+
+wit_bindgen::generate!({
+    path: "../../../crates/fluvio-smartengine/wit",
+    world: "smartmodule",
+    exports: {
+        "fluvio:smartmodule/transform": ComponentMap,
+    },
+});
+
+use exports::fluvio::smartmodule::transform::{Guest, SmartmoduleRecord, TransformOutput};
+
+struct ComponentMap;
+
+impl Guest for ComponentMap {
+    fn init(_params: Vec<(String, String)>) -> Result<(), String> {
+        Ok(())
+    }
+
+    fn process(records: Vec<SmartmoduleRecord>) -> TransformOutput {
+        let records = records
+            .into_iter()
+            .map(|mut record| {
+                record.value.make_ascii_uppercase();
+                record
+            })
+            .collect();
+        TransformOutput {
+            records,
+            error: None,
+        }
+    }
+}
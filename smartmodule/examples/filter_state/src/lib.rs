@@ -0,0 +1,14 @@
+use fluvio_smartmodule::{smartmodule, state, Result, SmartModuleRecord};
+
+/// Passes only the first record of each key, seen keys are kept in SmartModule state
+#[smartmodule(filter)]
+pub fn filter(record: &SmartModuleRecord) -> Result<bool> {
+    let Some(key) = record.key() else {
+        return Ok(true);
+    };
+    if state::get(key).is_some() {
+        return Ok(false);
+    }
+    state::put(key, record.offset().to_string())?;
+    Ok(true)
+}
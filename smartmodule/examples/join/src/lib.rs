@@ -0,0 +1,18 @@
+use fluvio_smartmodule::{smartmodule, SmartModuleRecord, RecordData, Result};
+
+/// Appends latest value of the joined topic for the same key, records without match are kept as is
+#[smartmodule(join)]
+pub fn join(
+    record: &SmartModuleRecord,
+    right: Option<&RecordData>,
+) -> Result<(Option<RecordData>, RecordData)> {
+    let key = record.key.clone();
+    let Some(right) = right else {
+        return Ok((key, record.value.clone()));
+    };
+
+    let mut value = Vec::from(record.value.as_ref());
+    value.push(b':');
+    value.extend_from_slice(right.as_ref());
+    Ok((key, value.into()))
+}
@@ -0,0 +1,13 @@
+use fluvio_smartmodule::{smartmodule, Result, SmartModuleRecord, RecordData};
+
+/// Sums integer values of each key within a window
+#[smartmodule(window)]
+pub fn window(accumulator: RecordData, current: &SmartModuleRecord) -> Result<RecordData> {
+    let accumulator_string = std::str::from_utf8(accumulator.as_ref())?;
+    let current_string = std::str::from_utf8(current.value.as_ref())?;
+
+    let accumulator_int = accumulator_string.trim().parse::<i64>().unwrap_or(0);
+    let current_int = current_string.trim().parse::<i64>()?;
+
+    Ok((accumulator_int + current_int).to_string().into())
+}